@@ -0,0 +1,113 @@
+//! Full-column MSMs and FFTs at k = 18 to 22 on every backend the build can run on: the CPU,
+//! and with `--features gpu` a CUDA device. `commit_instances` runs on the default backend,
+//! the device when there is one. Run with `make bench-gpu`.
+
+use std::ops::RangeInclusive;
+
+use criterion::{criterion_group, criterion_main, BatchSize, BenchmarkId, Criterion};
+use halo2_playground::{
+    accel::{self, Backend, Bases},
+    commit_instances,
+    entropy::SeededEntropy,
+    storage::cached_params,
+};
+use halo2_proofs::{
+    arithmetic::{Field, FieldExt},
+    circuit::{Layouter, SimpleFloorPlanner},
+    halo2curves::{
+        bn256::{Bn256, Fr},
+        group::Curve,
+    },
+    plonk::{keygen_vk, Circuit, Column, ConstraintSystem, Error, Instance},
+    poly::{
+        kzg::{commitment::KZGCommitmentScheme, multiopen::VerifierSHPLONK},
+        EvaluationDomain,
+    },
+};
+
+const SEED: u64 = 0x5962_be5d_763d_318d;
+const KS: RangeInclusive<u32> = 18..=22;
+
+/// Only declares an instance column: `commit_instances` never looks past the cs shape.
+#[derive(Clone, Default)]
+struct InstanceCircuit;
+
+impl<F: FieldExt> Circuit<F> for InstanceCircuit {
+    type Config = Column<Instance>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        meta.instance_column()
+    }
+
+    fn synthesize(&self, _: Self::Config, _: impl Layouter<F>) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+fn bench_accel(c: &mut Criterion) {
+    let mut rng = SeededEntropy::new(SEED);
+    let mut group = c.benchmark_group("accel");
+    group.sample_size(10);
+    for k in KS {
+        let params = cached_params(k).expect("cached_params");
+        let bases = Bases::new(&params).expect("Lagrange bases");
+        let domain = EvaluationDomain::<Fr>::new(1, k);
+        let values: Vec<Fr> = (0..1 << k).map(|_| Fr::random(&mut rng)).collect();
+        let commitment = Backend::Cpu.commit(&bases, &values);
+        let coeffs = Backend::Cpu.lagrange_to_coeff(&domain, values.clone());
+
+        for backend in accel::backends() {
+            // every backend computes the same points and values
+            assert_eq!(backend.commit(&bases, &values), commitment);
+            let lagrange = backend.coeff_to_lagrange(&domain, coeffs.to_vec());
+            assert_eq!(&lagrange[..], &values[..]);
+            assert_eq!(
+                &backend.lagrange_to_coeff(&domain, values.clone())[..],
+                &coeffs[..]
+            );
+
+            let id = |name: &str| BenchmarkId::new(format!("{}/{}", name, backend.name()), k);
+            group.bench_function(id("msm"), |b| b.iter(|| backend.commit(&bases, &values)));
+            group.bench_function(id("lagrange_to_coeff"), |b| {
+                b.iter_batched(
+                    || values.clone(),
+                    |values| backend.lagrange_to_coeff(&domain, values),
+                    BatchSize::LargeInput,
+                )
+            });
+            group.bench_function(id("coeff_to_lagrange"), |b| {
+                b.iter_batched(
+                    || coeffs.to_vec(),
+                    |coeffs| backend.coeff_to_lagrange(&domain, coeffs),
+                    BatchSize::LargeInput,
+                )
+            });
+        }
+
+        let vk = keygen_vk(&params, &InstanceCircuit).expect("keygen_vk");
+        let column = &values[..(1 << k) - (vk.cs.blinding_factors() + 1)];
+        let commit = || {
+            commit_instances::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<'_, Bn256>>(
+                &params,
+                &vk,
+                &[&[column]],
+            )
+            .expect("commit_instances")
+        };
+        assert_eq!(
+            commit()[0][0],
+            Backend::Cpu.commit(&bases, column).to_affine()
+        );
+        let id = format!("commit_instances/{}", accel::backend().name());
+        group.bench_function(BenchmarkId::new(id, k), |b| b.iter(commit));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_accel);
+criterion_main!(benches);
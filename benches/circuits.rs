@@ -0,0 +1,102 @@
+//! Keygen, proving and verification times of the multiplication and Poseidon circuits at a few
+//! `k` from the smallest that fits, so dependency upgrades that slow them down show up.
+//! Run with `make bench`.
+
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use halo2_gadgets::poseidon::primitives::Spec;
+use halo2_playground::{
+    audit::fit_k,
+    circuits::{mul::MulCircuit, poseidon::PoseidonHashCircuit},
+    entropy::SeededEntropy,
+    poseidon::MySpec,
+    prover::{run_proof, ProofOptions},
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk, Circuit},
+};
+
+const SEED: u64 = 0x5962_be5d_763d_318d;
+/// How many sizes above the smallest fitting `k` to measure.
+const EXTRA_K: u32 = 2;
+
+fn bench_circuit<C: Circuit<Fr> + Clone>(
+    c: &mut Criterion,
+    name: &str,
+    circuit: &C,
+    instances: &[&[Fr]],
+) {
+    let mut group = c.benchmark_group(name);
+    group.sample_size(10);
+    let min_k = fit_k(circuit).expect("fit_k");
+    for k in min_k..=min_k + EXTRA_K {
+        let params = cached_params(k).expect("cached_params");
+        group.bench_with_input(BenchmarkId::new("keygen", k), &k, |b, _| {
+            b.iter(|| {
+                let vk = keygen_vk(&params, circuit).expect("keygen_vk");
+                keygen_pk(&params, vk, circuit).expect("keygen_pk")
+            })
+        });
+
+        let vk = keygen_vk(&params, circuit).expect("keygen_vk");
+        let pk = keygen_pk(&params, vk, circuit).expect("keygen_pk");
+        let prove = || {
+            run_proof(
+                &params,
+                &pk,
+                circuit.clone(),
+                instances,
+                SeededEntropy::new(SEED),
+                ProofOptions::default(),
+            )
+            .expect("create_proof")
+        };
+        group.bench_with_input(BenchmarkId::new("prove", k), &k, |b, _| b.iter(prove));
+
+        let proof = prove();
+        group.bench_with_input(BenchmarkId::new("verify", k), &k, |b, _| {
+            b.iter(|| {
+                check_proof(
+                    &params,
+                    pk.get_vk(),
+                    instances,
+                    &proof,
+                    ProofOptions::default(),
+                )
+                .into_result()
+                .expect("verify_proof")
+            })
+        });
+    }
+    group.finish();
+}
+
+fn bench_poseidon<S, const WIDTH: usize, const RATE: usize>(c: &mut Criterion)
+where
+    S: Spec<Fr, WIDTH, RATE> + Copy + Clone,
+{
+    let message = [(); RATE].map(|_| Fr::from(7));
+    let circuit = PoseidonHashCircuit::<Fr, S, WIDTH, RATE, RATE>::new(message);
+    let hash = PoseidonHashCircuit::<Fr, S, WIDTH, RATE, RATE>::hash(message);
+    bench_circuit(
+        c,
+        &format!("poseidon_width_{}", WIDTH),
+        &circuit,
+        &[&[hash]],
+    );
+}
+
+fn mul(c: &mut Criterion) {
+    let circuit = MulCircuit::new(Fr::from(3), Fr::from(5));
+    bench_circuit(c, "mul", &circuit, &[&MulCircuit::instance(Fr::from(15))]);
+}
+
+fn poseidon(c: &mut Criterion) {
+    bench_poseidon::<MySpec<3, 2>, 3, 2>(c);
+    bench_poseidon::<MySpec<5, 4>, 5, 4>(c);
+}
+
+criterion_group!(benches, mul, poseidon);
+criterion_main!(benches);
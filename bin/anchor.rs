@@ -0,0 +1,50 @@
+use halo2_playground::{
+    anchor::{anchor_instances, check_anchor, check_anchor_cached, AnchorHash},
+    audit::fit_k,
+    cache::InstanceCommitmentCache,
+    circuits::mul::MulCircuit,
+    storage::cached_params,
+};
+use halo2_proofs::{halo2curves::bn256::Fr, plonk::keygen_vk};
+
+fn main() {
+    let circuit = MulCircuit::<Fr>::default();
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let instance = MulCircuit::instance(Fr::from(15));
+    let other = MulCircuit::instance(Fr::from(16));
+    let proofs: &[&[&[Fr]]] = &[&[&instance], &[&other]];
+
+    for hash in [AnchorHash::Keccak256, AnchorHash::Poseidon] {
+        let anchor = anchor_instances(&params, &vk, proofs, hash).expect("anchor");
+        println!("{:?} anchor: {}", hash, anchor);
+        assert_eq!(
+            anchor_instances(&params, &vk, proofs, hash).expect("anchor"),
+            anchor
+        );
+        assert!(check_anchor(&params, &vk, proofs, &anchor).expect("check_anchor"));
+
+        // any change to the values, their order or the number of proofs changes the anchor
+        let swapped: &[&[&[Fr]]] = &[&[&other], &[&instance]];
+        let single: &[&[&[Fr]]] = &[&[&instance]];
+        for changed in [swapped, single] {
+            assert!(!check_anchor(&params, &vk, changed, &anchor).expect("check_anchor"));
+        }
+    }
+
+    // the second check of the same instances reuses their commitments
+    let mut cache = InstanceCommitmentCache::new();
+    let anchor = anchor_instances(&params, &vk, proofs, AnchorHash::Keccak256).expect("anchor");
+    for _ in 0..2 {
+        assert!(check_anchor_cached(&mut cache, &params, &vk, proofs, &anchor).expect("check"));
+    }
+    let single: &[&[&[Fr]]] = &[&[&instance]];
+    assert!(!check_anchor_cached(&mut cache, &params, &vk, single, &anchor).expect("check"));
+    assert_eq!((cache.hits(), cache.misses(), cache.len()), (1, 2, 2));
+
+    let keccak = anchor_instances(&params, &vk, proofs, AnchorHash::Keccak256).expect("anchor");
+    let poseidon = anchor_instances(&params, &vk, proofs, AnchorHash::Poseidon).expect("anchor");
+    assert_ne!(keccak.to_bytes(), poseidon.to_bytes());
+
+    println!("instance anchor checks passed");
+}
@@ -0,0 +1,77 @@
+use halo2_playground::{
+    audit::fit_k,
+    chips::arith::{ArithChip, ArithConfig, ArithContext},
+    report::report_if_requested,
+};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+#[derive(Clone, Debug)]
+struct Config {
+    arith: ArithConfig,
+    instance: Column<Instance>,
+}
+
+/// Proves knowledge of `a` and `b` with `(a + b) * (a - b) + 7` in instance row 0.
+#[derive(Clone, Default)]
+struct DifferenceOfSquares<F: FieldExt> {
+    a: Value<F>,
+    b: Value<F>,
+}
+
+impl<F: FieldExt> Circuit<F> for DifferenceOfSquares<F> {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        Config {
+            arith: ArithChip::configure(meta),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let ctx = ArithContext::new();
+        let (a, b) = (ctx.input(self.a), ctx.input(self.b));
+        let out = (a + b) * (a - b) + ctx.constant(F::from(7));
+
+        let cells =
+            ArithChip::construct(config.arith).assign(layouter.namespace(|| "arith"), &ctx)?;
+        layouter.constrain_instance(cells[out.index()].cell(), config.instance, 0)
+    }
+}
+
+fn main() {
+    report_if_requested("arith", &DifferenceOfSquares::<Fr>::default());
+    for (a, b) in [(3u64, 5u64), (0, 0), (11, 2)] {
+        let (a, b) = (Fr::from(a), Fr::from(b));
+        let circuit = DifferenceOfSquares {
+            a: Value::known(a),
+            b: Value::known(b),
+        };
+        let k = fit_k(&circuit).expect("fit_k");
+        let expected = a.square() - b.square() + Fr::from(7);
+        let prover = MockProver::run(k, &circuit, vec![vec![expected]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+
+        let prover = MockProver::run(k, &circuit, vec![vec![expected + Fr::one()]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+    println!("arith: assigned-value arithmetic checks passed");
+}
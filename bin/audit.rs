@@ -0,0 +1,66 @@
+use halo2_playground::{
+    audit::{audit_k_headroom, audit_poseidon, audit_range_bits, Finding, Severity},
+    continuation::{run_steps, ChainChunkCircuit},
+    poseidon::MySpec,
+};
+use halo2_proofs::halo2curves::bn256::Fr;
+
+// parameter choices of the circuits in this repository
+const CHAIN_K: u32 = 8;
+const CHAIN_STEPS: usize = 64;
+const WORD_BITS: [usize; 2] = [32, 64];
+const PACK_MAX_BYTES: usize = 31;
+
+fn main() {
+    let mut findings: Vec<Finding> = vec![];
+    findings.extend(audit_poseidon::<MySpec<3, 2>, 3, 2>());
+    findings.extend(audit_poseidon::<MySpec<5, 4>, 5, 4>());
+    for bits in WORD_BITS {
+        findings.extend(audit_range_bits::<Fr>(
+            &format!("rotate u{}", bits),
+            bits,
+            8,
+        ));
+    }
+    findings.extend(audit_range_bits::<Fr>("pack", 8 * PACK_MAX_BYTES, 8));
+
+    let initial = Fr::from(1);
+    findings.extend(audit_k_headroom(
+        "continuation chunk",
+        CHAIN_K,
+        &ChainChunkCircuit::<Fr, CHAIN_STEPS>::new(),
+        &[vec![initial, run_steps(initial, CHAIN_STEPS)]],
+    ));
+
+    // a wrong claimed output is told apart from a circuit that doesn't fit
+    let unsatisfied = audit_k_headroom(
+        "continuation chunk",
+        CHAIN_K,
+        &ChainChunkCircuit::<Fr, CHAIN_STEPS>::new(),
+        &[vec![initial, run_steps(initial, CHAIN_STEPS) + Fr::one()]],
+    );
+    assert!(matches!(
+        unsatisfied.as_slice(),
+        [Finding {
+            severity: Severity::Error,
+            rule: "satisfied",
+            ..
+        }]
+    ));
+
+    for finding in findings.iter() {
+        println!("{}", finding);
+    }
+    let errors = findings
+        .iter()
+        .filter(|finding| finding.severity == Severity::Error)
+        .count();
+    println!(
+        "{} warning(s), {} error(s)",
+        findings.len() - errors,
+        errors
+    );
+    if errors > 0 {
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,89 @@
+use halo2_playground::{
+    barycentric::{evaluate_lagrange, instance_evaluations},
+    challenges::proof_challenges,
+    commit_instances, commit_instances_with_blinds,
+    continuation::{prove_chain, ChainChunkCircuit},
+    entropy::SeededEntropy,
+    GOD_PRIVATE_KEY,
+};
+use halo2_proofs::{
+    arithmetic::{compute_inner_product, eval_polynomial, Field, FieldExt},
+    halo2curves::{
+        bn256::{Bn256, Fr, G1Affine},
+        group::{prime::PrimeCurveAffine, Curve},
+    },
+    plonk::{keygen_pk, keygen_vk},
+    poly::{
+        commitment::ParamsProver,
+        kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::VerifierSHPLONK,
+        },
+    },
+};
+
+const K: u32 = 8;
+const STEPS: usize = 64;
+
+fn main() {
+    let s = Fr::from_u128(GOD_PRIVATE_KEY);
+    let general_params = ParamsKZG::<Bn256>::unsafe_setup_with_s(K, s);
+    let circuit = ChainChunkCircuit::<Fr, STEPS>::new();
+    let vk = keygen_vk(&general_params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&general_params, vk.clone(), &circuit).expect("keygen_pk");
+    let mut rng = SeededEntropy::new(0x5962_be5d_763d_318d);
+    let chunk = prove_chain::<STEPS, _>(&general_params, &pk, Fr::one(), 1, &mut rng)
+        .expect("prove_chain")
+        .remove(0);
+    let column = [chunk.state_in, chunk.state_out];
+    let (challenges, _) =
+        proof_challenges(&vk, &[&[&column]], &chunk.proof).expect("proof_challenges");
+    let x = challenges.x;
+
+    // against the coefficient form
+    let mut values = column.to_vec();
+    values.resize(1 << K, Fr::zero());
+    let coeffs = vk
+        .domain
+        .lagrange_to_coeff(vk.domain.lagrange_from_vec(values));
+    for point in [x, Fr::random(&mut rng), Fr::from(7)] {
+        assert_eq!(
+            evaluate_lagrange(&vk, &column, point),
+            eval_polynomial(&coeffs, point),
+            "coefficient form"
+        );
+    }
+    // on the domain the polynomial takes the instance values
+    let omega = vk.domain.get_omega();
+    assert_eq!(evaluate_lagrange(&vk, &column, omega), chunk.state_out);
+    assert_eq!(evaluate_lagrange(&vk, &column, omega.square()), Fr::zero());
+
+    // against the Lagrange basis the verifier evaluates
+    let xn = x.pow_vartime(&[1 << K]);
+    let l_i_s = vk.domain.l_i_range(x, xn, 0..column.len() as i32);
+    let evals = instance_evaluations(&vk, &[&column], x).expect("instance_evaluations");
+    assert_eq!(evals, vec![compute_inner_product(&column, &l_i_s)]);
+
+    // the toxic waste is known here, so the instance commitment opens at s
+    let commitments = commit_instances::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<Bn256>>(
+        general_params.verifier_params(),
+        &vk,
+        &[&[&column]],
+    )
+    .expect("commit_instances");
+    let opened = (G1Affine::generator() * evaluate_lagrange(&vk, &column, s)).to_affine();
+    assert_eq!(commitments[0][0], opened, "commitment");
+    // the KZG params of this fork drop the blind, so KZG instance commitments never hide
+    let blinded =
+        commit_instances_with_blinds::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<Bn256>>(
+            general_params.verifier_params(),
+            &vk,
+            &[&[&column]],
+            &[vec![Fr::from(7)]],
+        )
+        .expect("commit_instances_with_blinds");
+    assert_eq!(blinded, commitments);
+
+    assert!(instance_evaluations(&vk, &[], x).is_err());
+    println!("instance evaluation checks passed");
+}
@@ -0,0 +1,125 @@
+use halo2_playground::{
+    audit::fit_k,
+    chips::branch::{BranchChip, BranchConfig, Condition},
+    report::report_if_requested,
+};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+#[derive(Clone, Debug)]
+struct Config {
+    branch: BranchConfig,
+    advice: Column<Advice>,
+    instance: Column<Instance>,
+}
+
+/// Takes `a` and `b` from instance rows 0 and 1 and exposes `cond ? (a, b) : (b, a)` in rows
+/// 2 and 3. A public condition is read from row 4, a private one is witnessed.
+#[derive(Clone)]
+enum SwapCircuit {
+    Public,
+    Private(Value<bool>),
+}
+
+impl<F: FieldExt> Circuit<F> for SwapCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        match self {
+            SwapCircuit::Public => SwapCircuit::Public,
+            SwapCircuit::Private(_) => SwapCircuit::Private(Value::unknown()),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+        Config {
+            branch: BranchChip::configure(meta),
+            advice,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = BranchChip::construct(config.branch);
+        let inputs = layouter.assign_region(
+            || "load inputs",
+            |mut region| {
+                let rows = match self {
+                    SwapCircuit::Public => vec![0, 1, 4],
+                    SwapCircuit::Private(_) => vec![0, 1],
+                };
+                rows.into_iter()
+                    .enumerate()
+                    .map(|(offset, row)| {
+                        region.assign_advice_from_instance(
+                            || "input",
+                            config.instance,
+                            row,
+                            config.advice,
+                            offset,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            },
+        )?;
+        let (a, b) = (&inputs[0], &inputs[1]);
+        let cond = match self {
+            SwapCircuit::Public => Condition::Copy(&inputs[2]),
+            SwapCircuit::Private(value) => Condition::Witness(*value),
+        };
+        let (_, outs) = chip.select_all(layouter.namespace(|| "swap"), cond, &[(a, b), (b, a)])?;
+        for (i, out) in outs.iter().enumerate() {
+            layouter.constrain_instance(out.cell(), config.instance, 2 + i)?;
+        }
+        Ok(())
+    }
+}
+
+fn run(circuit: &SwapCircuit, instance: Vec<Fr>) -> bool {
+    let k = fit_k::<Fr, _>(circuit).expect("fit_k");
+    MockProver::run(k, circuit, vec![instance])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+fn main() {
+    report_if_requested("branch", &SwapCircuit::Public);
+    let (a, b) = (Fr::from(3), Fr::from(11));
+    for cond in [true, false] {
+        let (x, y) = if cond { (a, b) } else { (b, a) };
+        let c = Fr::from(cond as u64);
+
+        // complete: both branches prove, publicly and privately
+        assert!(run(&SwapCircuit::Public, vec![a, b, x, y, c]), "{}", cond);
+        let private = SwapCircuit::Private(Value::known(cond));
+        assert!(run(&private, vec![a, b, x, y]), "{}", cond);
+
+        // sound: the other branch's outputs are rejected
+        assert!(!run(&SwapCircuit::Public, vec![a, b, y, x, c]), "{}", cond);
+        assert!(!run(&private, vec![a, b, y, x]), "{}", cond);
+        assert!(!run(&private, vec![a, b, x, x]), "{}", cond);
+    }
+
+    // cond = 2 satisfies the selection constraint with out = 2a - b, only booleanity stops it
+    let two = Fr::from(2);
+    let (x, y) = (two * (a - b) + b, two * (b - a) + a);
+    assert!(!run(&SwapCircuit::Public, vec![a, b, x, y, two]));
+
+    println!("branch: both branches sound and complete");
+}
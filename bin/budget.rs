@@ -0,0 +1,77 @@
+use std::time::Duration;
+
+use halo2_playground::{
+    audit::fit_k,
+    budget::{prove_within, BudgetError, Phase},
+    circuits::poseidon::PoseidonHashCircuit,
+    entropy::SeededEntropy,
+    poseidon::MySpec,
+    prover::{run_proof, ProofOptions},
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+type Poseidon = PoseidonHashCircuit<Fr, MySpec<3, 2>, 3, 2, 2>;
+
+const SEED: u64 = 0x5962_be5d_763d_318d;
+
+fn main() {
+    let message = [Fr::from(1), Fr::from(2)];
+    let circuit = Poseidon::new(message);
+    let instances: &[&[Fr]] = &[&[Poseidon::hash(message)]];
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let prove = |budget| {
+        prove_within(
+            &params,
+            &pk,
+            circuit,
+            instances,
+            SeededEntropy::new(SEED),
+            ProofOptions::default(),
+            budget,
+        )
+    };
+
+    // a spent budget stops before synthesis, with nothing to extrapolate from
+    match prove(Duration::ZERO) {
+        Err(BudgetError::Exceeded(progress)) => {
+            assert_eq!(progress.phase, Phase::Synthesis);
+            assert_eq!(progress.estimated_remaining, None);
+            println!("{}", progress);
+        }
+        other => panic!(
+            "expected the budget to run out, got {:?}",
+            other.map(|p| p.len())
+        ),
+    }
+
+    // within budget the proof is exactly run_proof's
+    let proof = prove(Duration::MAX).expect("prove_within");
+    let expected = run_proof(
+        &params,
+        &pk,
+        circuit,
+        instances,
+        SeededEntropy::new(SEED),
+        ProofOptions::default(),
+    )
+    .expect("run_proof");
+    assert_eq!(proof, expected);
+    check_proof(
+        &params,
+        pk.get_vk(),
+        instances,
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+
+    println!("time-boxed proving checks passed");
+}
@@ -0,0 +1,72 @@
+use halo2_playground::{
+    audit::fit_k,
+    builder::{BuilderCircuit, Prototype},
+    chips::arith::{ArithContext, AssignedValue},
+    entropy::SeededEntropy,
+    prover::{run_proof, ProofOptions},
+    report::report_if_requested,
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::Value,
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+/// Knowledge of `x` with `x^3 + x + 5` public, the whole circuit being `build`.
+#[derive(Clone, Default)]
+struct Cubic<F: FieldExt> {
+    x: Value<F>,
+}
+
+impl<F: FieldExt> Prototype<F> for Cubic<F> {
+    fn build<'a>(&self, ctx: &'a ArithContext<F>) -> Vec<AssignedValue<'a, F>> {
+        let x = ctx.input(self.x);
+        vec![x * x * x + x + ctx.constant(F::from(5))]
+    }
+}
+
+fn main() {
+    let circuit = BuilderCircuit(Cubic {
+        x: Value::known(Fr::from(3)),
+    });
+    let k = fit_k::<Fr, _>(&circuit).expect("fit_k");
+    report_if_requested("builder", &circuit);
+    let instance = circuit.instance().expect("witness");
+    assert_eq!(instance, vec![Fr::from(35)]);
+    assert!(BuilderCircuit(Cubic::<Fr>::default())
+        .instance::<Fr>()
+        .is_none());
+
+    let prover = MockProver::run(k, &circuit, vec![instance.clone()]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+    let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(36)]]).unwrap();
+    assert!(prover.verify().is_err());
+
+    // the prototype is an ordinary circuit to the prover and verifier
+    let params = cached_params(k).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &[&instance],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[&instance],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+    println!("builder: prototype proved and verified");
+}
@@ -0,0 +1,74 @@
+use halo2_playground::{
+    ceremony::{
+        reconstruct_secret, secret_commitment, setup_from_shares, split_secret, CeremonyError,
+    },
+    entropy::SeededEntropy,
+    GOD_PRIVATE_KEY,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    halo2curves::bn256::{Bn256, Fr},
+    poly::{commitment::Params, kzg::commitment::ParamsKZG},
+};
+
+const K: u32 = 4;
+const THRESHOLD: usize = 3;
+const PARTICIPANTS: usize = 5;
+
+fn params_bytes(params: &ParamsKZG<Bn256>) -> Vec<u8> {
+    let mut bytes = vec![];
+    params.write(&mut bytes).unwrap();
+    bytes
+}
+
+fn main() {
+    let s = Fr::from_u128(GOD_PRIVATE_KEY);
+    let commitment = secret_commitment(s);
+    let shares = split_secret(
+        s,
+        THRESHOLD,
+        PARTICIPANTS,
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+    )
+    .expect("split_secret");
+    assert_eq!(shares.len(), PARTICIPANTS);
+    let expected = params_bytes(&ParamsKZG::<Bn256>::unsafe_setup_with_s(K, s));
+
+    // any quorum works
+    for quorum in [
+        &shares[..3],
+        &shares[2..],
+        &[shares[4], shares[0], shares[2]][..],
+    ] {
+        assert_eq!(reconstruct_secret(quorum).unwrap(), s);
+        let params = setup_from_shares(K, quorum, THRESHOLD, &commitment).expect("quorum");
+        assert_eq!(params_bytes(&params), expected);
+    }
+
+    // fewer shares don't give s back
+    assert_ne!(reconstruct_secret(&shares[..2]).unwrap(), s);
+    assert_eq!(
+        setup_from_shares(K, &shares[..2], THRESHOLD, &commitment).unwrap_err(),
+        CeremonyError::NotEnoughShares {
+            shares: 2,
+            threshold: THRESHOLD
+        }
+    );
+    // a tampered share is caught by the commitment
+    let mut tampered = shares[..3].to_vec();
+    tampered[1].value += Fr::one();
+    assert_eq!(
+        setup_from_shares(K, &tampered, THRESHOLD, &commitment).unwrap_err(),
+        CeremonyError::WrongSecret
+    );
+    assert_eq!(
+        reconstruct_secret(&[shares[0], shares[0]]).unwrap_err(),
+        CeremonyError::DuplicateShare(1)
+    );
+    assert!(split_secret(s, 6, PARTICIPANTS, SeededEntropy::new(0)).is_err());
+
+    println!(
+        "ceremony: {} of {} shares rebuild the params",
+        THRESHOLD, PARTICIPANTS
+    );
+}
@@ -0,0 +1,62 @@
+use halo2_playground::{
+    challenges::proof_challenges,
+    continuation::{prove_chain, ChainChunkCircuit},
+    entropy::SeededEntropy,
+    storage::cached_params,
+};
+use halo2_proofs::{
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+const K: u32 = 8;
+const STEPS: usize = 64;
+
+fn main() {
+    let general_params = cached_params(K).expect("cached_params");
+    let circuit = ChainChunkCircuit::<Fr, STEPS>::new();
+    let vk = keygen_vk(&general_params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&general_params, vk.clone(), &circuit).expect("keygen_pk");
+    let rng = SeededEntropy::new(0x5962_be5d_763d_318d);
+    let chunk = prove_chain::<STEPS, _>(&general_params, &pk, Fr::one(), 1, rng)
+        .expect("prove_chain")
+        .remove(0);
+    let instances: &[&[&[Fr]]] = &[&[&[chunk.state_in, chunk.state_out]]];
+
+    let (challenges, consumed) =
+        proof_challenges(&vk, instances, &chunk.proof).expect("proof_challenges");
+    assert!(consumed < chunk.proof.len());
+    println!(
+        "challenges read from the first {} of {} proof bytes",
+        consumed,
+        chunk.proof.len()
+    );
+
+    // the prefix is enough, and the bytes after it don't matter
+    let (prefix_challenges, _) =
+        proof_challenges(&vk, instances, &chunk.proof[..consumed]).expect("prefix");
+    assert_eq!(prefix_challenges, challenges);
+    let mut proof = chunk.proof.clone();
+    proof[consumed] ^= 1;
+    assert_eq!(
+        proof_challenges(&vk, instances, &proof).unwrap().0,
+        challenges
+    );
+
+    // the last commitment before x only changes x, or fails to decode
+    let mut proof = chunk.proof.clone();
+    proof[consumed - 32] ^= 1;
+    if let Ok((tampered, _)) = proof_challenges(&vk, instances, &proof) {
+        assert_eq!(tampered.y, challenges.y);
+        assert_ne!(tampered.x, challenges.x);
+    }
+
+    // different instances give different challenges from theta on
+    let other: &[&[&[Fr]]] = &[&[&[chunk.state_in, chunk.state_in]]];
+    let (tampered, _) = proof_challenges(&vk, other, &chunk.proof).expect("other instances");
+    assert_ne!(tampered.theta, challenges.theta);
+    assert_ne!(tampered.x, challenges.x);
+
+    assert!(proof_challenges(&vk, instances, &chunk.proof[..consumed - 1]).is_err());
+    println!("challenge derivation checks passed");
+}
@@ -0,0 +1,105 @@
+use std::time::{Duration, Instant};
+
+use halo2_playground::{
+    cache::InstanceCommitmentCache, commit_instances, commit_instances_prover, multicore,
+    storage::cached_params,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    halo2curves::bn256::{Bn256, Fr},
+    plonk::{keygen_vk, Circuit, Column, ConstraintSystem, Error, Instance},
+    poly::{
+        commitment::ParamsProver,
+        kzg::{commitment::KZGCommitmentScheme, multiopen::VerifierSHPLONK},
+    },
+};
+
+const K: u32 = 12;
+const COLUMNS: usize = 16;
+const RUNS: usize = 3;
+
+/// Only declares instance columns: `commit_instances` never looks past the cs shape.
+#[derive(Clone, Default)]
+struct WideInstanceCircuit;
+
+impl<F: FieldExt> Circuit<F> for WideInstanceCircuit {
+    type Config = [Column<Instance>; COLUMNS];
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        [(); COLUMNS].map(|_| meta.instance_column())
+    }
+
+    fn synthesize(&self, _: Self::Config, _: impl Layouter<F>) -> Result<(), Error> {
+        Ok(())
+    }
+}
+
+/// Times `commit_instances` over `COLUMNS` full columns; run with and without `--features rayon`
+/// (`make bench-commit`) to compare.
+fn main() {
+    let params = cached_params(K).expect("cached_params");
+    let vk = keygen_vk(&params, &WideInstanceCircuit).expect("keygen_vk");
+    let rows = (1 << K) - (vk.cs.blinding_factors() + 1);
+    let columns: Vec<Vec<Fr>> = (0..COLUMNS)
+        .map(|c| (0..rows).map(|r| Fr::from((c * rows + r) as u64)).collect())
+        .collect();
+    let columns: Vec<&[Fr]> = columns.iter().map(Vec::as_slice).collect();
+
+    let mut best = Duration::MAX;
+    let mut commitments = vec![];
+    for _ in 0..RUNS {
+        let start = Instant::now();
+        commitments = commit_instances::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<Bn256>>(
+            params.verifier_params(),
+            &vk,
+            &[&columns],
+        )
+        .expect("commit_instances");
+        best = best.min(start.elapsed());
+    }
+    assert_eq!(commitments[0].len(), COLUMNS);
+    // the prover's params commit to the same points
+    assert_eq!(
+        commit_instances_prover::<KZGCommitmentScheme<Bn256>>(&params, &vk, &[&columns])
+            .expect("commit_instances_prover"),
+        commitments
+    );
+    println!(
+        "commit_instances: {} columns of {} rows at k = {} in {:.2?} on {} thread(s)",
+        COLUMNS,
+        rows,
+        K,
+        best,
+        multicore::threads()
+    );
+
+    // repeats are answered without the MSM, whichever side's params ask
+    let mut cache = InstanceCommitmentCache::with_capacity(1);
+    let start = Instant::now();
+    cache
+        .get_or_commit(params.verifier_params(), &vk, &[&columns])
+        .expect("get_or_commit");
+    let miss = start.elapsed();
+    let start = Instant::now();
+    let cached = cache
+        .get_or_commit(&params, &vk, &[&columns])
+        .expect("get_or_commit");
+    let hit = start.elapsed();
+    assert_eq!(cached, commitments);
+    // other values take the only slot, evicting the least recently used
+    let empty: Vec<&[Fr]> = vec![&[]; COLUMNS];
+    cache
+        .get_or_commit(&params, &vk, &[&empty])
+        .expect("get_or_commit");
+    assert_eq!(
+        (cache.hits(), cache.misses(), cache.evictions(), cache.len()),
+        (1, 2, 1, 1)
+    );
+    println!("cached: miss {:.2?}, hit {:.2?}", miss, hit);
+}
@@ -0,0 +1,139 @@
+use halo2_playground::{
+    audit::fit_k,
+    chips::{
+        constant_pool::{ConstantPool, PoolStats},
+        standard_plonk::{StandardPlonkChip, StandardPlonkConfig},
+    },
+    dev::Assignments,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+use std::cell::Cell;
+
+const TERMS: u64 = 48;
+/// The few constants the terms cycle through.
+const CONSTANTS: [u64; 3] = [2, 3, 5];
+
+#[derive(Clone, Debug)]
+struct Config {
+    plonk: StandardPlonkConfig,
+    instance: Column<Instance>,
+}
+
+/// `Σ c_i x` over `TERMS` terms whose constants repeat, in instance row 0. With `pooled` the
+/// constants come from one pool; without, each term assigns its own.
+#[derive(Clone, Default)]
+struct RepeatedConstants {
+    x: Value<Fr>,
+    pooled: bool,
+    /// The pool's statistics from the last synthesis.
+    stats: Cell<Option<PoolStats>>,
+}
+
+impl Circuit<Fr> for RepeatedConstants {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            pooled: self.pooled,
+            ..Self::default()
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let instance = meta.instance_column();
+        let constants = meta.fixed_column();
+        meta.enable_equality(instance);
+        meta.enable_constant(constants);
+        Config {
+            plonk: StandardPlonkChip::configure(meta),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        // the pool shares the chip's first column, like the constants it replaces
+        let pool = ConstantPool::new(config.plonk.a);
+        let chip = StandardPlonkChip::construct(config.plonk.clone());
+        let x = chip.assign(layouter.namespace(|| "x"), self.x)?;
+        let mut sum = None;
+        for i in 0..TERMS {
+            let value = Fr::from(CONSTANTS[i as usize % CONSTANTS.len()]);
+            let constant = if self.pooled {
+                pool.constant(layouter.namespace(|| "constant"), value)?
+            } else {
+                layouter.assign_region(
+                    || "constant",
+                    |mut region| {
+                        region.assign_advice_from_constant(|| "constant", config.plonk.a, 0, value)
+                    },
+                )?
+            };
+            let term = chip.mul(layouter.namespace(|| "c * x"), &constant, &x)?;
+            sum = Some(match sum {
+                None => term,
+                Some(sum) => chip.add(layouter.namespace(|| "sum"), &sum, &term)?,
+            });
+        }
+        self.stats.set(Some(pool.stats()));
+        layouter.constrain_instance(sum.expect("terms").cell(), config.instance, 0)
+    }
+}
+
+fn main() {
+    let x = Fr::from(7);
+    let total: u64 = (0..TERMS)
+        .map(|i| CONSTANTS[i as usize % CONSTANTS.len()])
+        .sum();
+    let instance = vec![vec![x * Fr::from(total)]];
+    let circuit = |pooled| RepeatedConstants {
+        x: Value::known(x),
+        pooled,
+        stats: Cell::new(None),
+    };
+
+    let mut rows = vec![];
+    for pooled in [false, true] {
+        let circuit = circuit(pooled);
+        let k = fit_k::<Fr, _>(&circuit).expect("fit_k");
+        let prover = MockProver::run(k, &circuit, instance.clone()).unwrap();
+        assert_eq!(prover.verify(), Ok(()));
+        let assignments = Assignments::synthesize(k, &circuit, instance.clone()).unwrap();
+        rows.push(assignments.used_rows());
+    }
+    let pooled = circuit(true);
+    Assignments::synthesize(
+        fit_k::<Fr, _>(&pooled).expect("fit_k"),
+        &pooled,
+        instance.clone(),
+    )
+    .unwrap();
+    let stats = pooled.stats.get().expect("synthesized");
+    assert_eq!(
+        stats,
+        PoolStats {
+            requested: TERMS as usize,
+            unique: CONSTANTS.len()
+        }
+    );
+    assert_eq!(rows[0] - rows[1], stats.saved_rows());
+    assert!(MockProver::run(
+        fit_k::<Fr, _>(&pooled).expect("fit_k"),
+        &pooled,
+        vec![vec![Fr::one()]]
+    )
+    .unwrap()
+    .verify()
+    .is_err());
+    println!("{}: {} rows instead of {}", stats, rows[1], rows[0]);
+}
@@ -0,0 +1,67 @@
+use halo2_playground::{
+    cancel::{CancelToken, Cancellable},
+    continuation::{
+        prove_chain, prove_chain_cancellable, run_steps, verify_chain, ChainChunkCircuit,
+    },
+    entropy::SeededEntropy,
+    fmt::to_short,
+    storage::cached_params,
+};
+use halo2_proofs::{
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk, Error},
+    poly::commitment::ParamsProver,
+};
+
+const K: u32 = 8;
+const STEPS: usize = 64;
+const CHUNKS: usize = 4;
+
+fn main() {
+    let initial = Fr::from(1);
+    let circuit = ChainChunkCircuit::<Fr, STEPS>::new();
+
+    let prover =
+        MockProver::run(K, &circuit, vec![vec![initial, run_steps(initial, STEPS)]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    let general_params = cached_params(K).expect("cached_params");
+    let verifier_params = general_params.verifier_params();
+
+    let vk = keygen_vk(&general_params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&general_params, vk, &circuit).expect("keygen_pk");
+
+    let rng = SeededEntropy::new(0x5962_be5d_763d_318d);
+    let proofs =
+        prove_chain::<STEPS, _>(&general_params, &pk, initial, CHUNKS, rng).expect("prove_chain");
+    let final_state = proofs.last().unwrap().state_out;
+    assert_eq!(final_state, run_steps(initial, STEPS * CHUNKS));
+    println!("chunks: {}, steps per chunk: {}", proofs.len(), STEPS);
+    println!("proof length per chunk : {}", proofs[0].proof.len());
+    println!("final state : {}", to_short(&final_state));
+
+    let verify = |proofs: &[_]| {
+        verify_chain(verifier_params, pk.get_vk(), initial, final_state, proofs)
+            .expect("verify_chain")
+    };
+    assert!(verify(&proofs));
+
+    // dropping a chunk breaks the chain even though every remaining proof is valid
+    let mut broken = proofs.clone();
+    broken.remove(1);
+    assert!(!verify(&broken));
+    // and so does cutting it short, or leaving nothing to check
+    assert!(!verify(&proofs[..CHUNKS - 1]));
+    assert!(!verify(&[]));
+
+    // a cancelled run stops instead of proving
+    let token = CancelToken::new();
+    token.cancel();
+    let rng = SeededEntropy::new(0);
+    let cancelled =
+        prove_chain_cancellable::<STEPS, _>(&general_params, &pk, initial, CHUNKS, rng, &token);
+    assert!(matches!(cancelled, Err(Error::Synthesis)));
+    let circuit = Cancellable::new(circuit, token);
+    assert!(MockProver::run(K, &circuit, vec![vec![initial, run_steps(initial, STEPS)]]).is_err());
+}
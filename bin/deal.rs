@@ -0,0 +1,296 @@
+use std::convert::TryInto;
+
+use halo2_gadgets::poseidon::{primitives::ConstantLength, Hash, Pow5Chip, Pow5Config};
+use halo2_playground::{
+    audit::fit_k,
+    chips::shuffle::{ShuffleChip, ShuffleConfig},
+    entropy::SeededEntropy,
+    merkle::hash_pair,
+    poseidon::MySpec,
+    prover::{run_proof, ProofOptions},
+    report::report_if_requested,
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk, Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+use rand::RngCore;
+
+const WIDTH: usize = 3;
+const RATE: usize = 2;
+const DECK: usize = 52;
+/// Cards dealt to each player.
+const HAND: usize = 2;
+
+fn name(card: u64) -> String {
+    let rank = [
+        "2", "3", "4", "5", "6", "7", "8", "9", "T", "J", "Q", "K", "A",
+    ][card as usize % 13];
+    let suit = ["c", "d", "h", "s"][card as usize / 13];
+    format!("{}{}", rank, suit)
+}
+
+/// A card at a position of the shuffled deck and the salt hiding it.
+#[derive(Clone, Copy, Debug)]
+struct Opening {
+    card: u64,
+    salt: Fr,
+}
+
+impl Opening {
+    fn commitment(&self) -> Fr {
+        hash_pair(Fr::from(self.card), self.salt)
+    }
+}
+
+/// The dealer's shuffle, deterministic in the seed so a dispute can replay it.
+fn shuffle(seed: u64) -> Vec<Opening> {
+    let mut rng = SeededEntropy::new(seed);
+    let mut cards = (0..DECK as u64).collect::<Vec<_>>();
+    for i in (1..DECK).rev() {
+        cards.swap(i, (rng.next_u64() % (i as u64 + 1)) as usize);
+    }
+    cards
+        .into_iter()
+        .map(|card| Opening {
+            card,
+            salt: Fr::random(&mut rng),
+        })
+        .collect()
+}
+
+#[derive(Clone, Debug)]
+struct Config {
+    shuffle: ShuffleConfig,
+    load: Column<Advice>,
+    instance: Column<Instance>,
+    poseidon_config: Pow5Config<Fr, WIDTH, RATE>,
+}
+
+/// Proves that the public commitments in instance rows `0..DECK`, one per position of the
+/// shuffled deck, are to a permutation of the whole deck: every card exactly once. Opening a
+/// commitment then deals the card at its position, so the dealer can neither stack the deck
+/// with duplicates nor change a card once it is committed.
+#[derive(Clone, Debug)]
+struct DeckCircuit {
+    deck: Value<Vec<Opening>>,
+}
+
+impl Default for DeckCircuit {
+    fn default() -> Self {
+        Self {
+            deck: Value::unknown(),
+        }
+    }
+}
+
+impl DeckCircuit {
+    fn new(deck: Vec<Opening>) -> Self {
+        assert_eq!(deck.len(), DECK, "a deck of {} cards", DECK);
+        Self {
+            deck: Value::known(deck),
+        }
+    }
+
+    fn instance(deck: &[Opening]) -> Vec<Fr> {
+        deck.iter().map(Opening::commitment).collect()
+    }
+}
+
+impl Circuit<Fr> for DeckCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let state = (0..WIDTH).map(|_| meta.advice_column()).collect::<Vec<_>>();
+        let partial_sbox = meta.advice_column();
+        let rc_a = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        let rc_b = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        meta.enable_constant(rc_b[0]);
+        let load = meta.advice_column();
+        meta.enable_equality(load);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        Config {
+            shuffle: ShuffleChip::configure(meta),
+            load,
+            instance,
+            poseidon_config: Pow5Chip::configure::<MySpec<WIDTH, RATE>>(
+                meta,
+                state.try_into().unwrap(),
+                partial_sbox,
+                rc_a.try_into().unwrap(),
+                rc_b.try_into().unwrap(),
+            ),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let (ordered, cards, salts) = layouter.assign_region(
+            || "load deck",
+            |mut region| {
+                let ordered = (0..DECK)
+                    .map(|card| {
+                        region.assign_advice_from_constant(
+                            || "ordered",
+                            config.load,
+                            card,
+                            Fr::from(card as u64),
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let (mut cards, mut salts) = (vec![], vec![]);
+                for i in 0..DECK {
+                    let opening = self.deck.as_ref().map(|deck| deck[i]);
+                    cards.push(region.assign_advice(
+                        || "card",
+                        config.load,
+                        DECK + 2 * i,
+                        || opening.map(|opening| Fr::from(opening.card)),
+                    )?);
+                    salts.push(region.assign_advice(
+                        || "salt",
+                        config.load,
+                        DECK + 2 * i + 1,
+                        || opening.map(|opening| opening.salt),
+                    )?);
+                }
+                Ok((ordered, cards, salts))
+            },
+        )?;
+        ShuffleChip::construct(config.shuffle.clone()).assign(
+            layouter.namespace(|| "shuffle"),
+            &ordered,
+            &cards,
+        )?;
+
+        for (i, (card, salt)) in cards.into_iter().zip(salts).enumerate() {
+            let hasher = Hash::<_, _, MySpec<WIDTH, RATE>, ConstantLength<2>, WIDTH, RATE>::init(
+                Pow5Chip::construct(config.poseidon_config.clone()),
+                layouter.namespace(|| format!("init position {}", i)),
+            )?;
+            let commitment = hasher.hash(
+                layouter.namespace(|| format!("position {}", i)),
+                [card, salt],
+            )?;
+            layouter.constrain_instance(commitment.cell(), config.instance, i)?;
+        }
+        Ok(())
+    }
+}
+
+fn run(circuit: &DeckCircuit, instance: Vec<Fr>) -> bool {
+    let k = fit_k::<Fr, _>(circuit).expect("fit_k");
+    MockProver::run(k, circuit, vec![instance])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+/// What a player accepts from the dealer: an opening of the commitment at `position`.
+fn receive(commitments: &[Fr], position: usize, opening: &Opening) -> Result<u64, String> {
+    if opening.card >= DECK as u64 || opening.commitment() != commitments[position] {
+        return Err(format!(
+            "position {} does not open to {}",
+            position, opening.card
+        ));
+    }
+    Ok(opening.card)
+}
+
+fn main() {
+    report_if_requested("deal", &DeckCircuit::default());
+    let deck = shuffle(0x5eed);
+    let commitments = DeckCircuit::instance(&deck);
+    let circuit = DeckCircuit::new(deck.clone());
+    assert!(run(&circuit, commitments.clone()));
+
+    // a stacked deck with a second ace of spades in place of the deuce of clubs
+    let mut stacked = deck.clone();
+    let deuce = stacked
+        .iter()
+        .position(|opening| opening.card == 0)
+        .unwrap();
+    stacked[deuce].card = 51;
+    assert!(!run(
+        &DeckCircuit::new(stacked.clone()),
+        DeckCircuit::instance(&stacked)
+    ));
+    // or an honest deck proven against someone else's commitments
+    assert!(!run(&circuit, DeckCircuit::instance(&shuffle(0x5eee))));
+
+    // the dealer proves the deck before dealing anything
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &DeckCircuit::default()).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &DeckCircuit::default()).expect("keygen_pk");
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &[&commitments],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[&commitments],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+
+    // alternate deals from the top: alice gets positions 0 and 2, bob 1 and 3, each opened to
+    // its player alone
+    let players = ["alice", "bob"];
+    let mut hands = vec![vec![]; players.len()];
+    for position in 0..HAND * players.len() {
+        let player = position % players.len();
+        let card = receive(&commitments, position, &deck[position]).expect("an honest deal");
+        hands[player].push(card);
+    }
+    for (player, hand) in players.iter().zip(hands.iter()) {
+        println!(
+            "{:>5}: {}",
+            player,
+            hand.iter()
+                .map(|card| name(*card))
+                .collect::<Vec<_>>()
+                .join(" ")
+        );
+    }
+    let dealt = hands.concat();
+    let mut distinct = dealt.clone();
+    distinct.sort_unstable();
+    distinct.dedup();
+    assert_eq!(distinct.len(), dealt.len(), "no card is dealt twice");
+
+    // a dealer swapping a card after committing has no opening for it
+    let swapped = Opening {
+        card: (deck[0].card + 1) % DECK as u64,
+        ..deck[0]
+    };
+    assert!(receive(&commitments, 0, &swapped).is_err());
+    // nor can they hand out a card from elsewhere in the deck
+    assert!(receive(&commitments, 0, &deck[HAND * players.len()]).is_err());
+    println!(
+        "{}-card deck committed and proven, {}-byte proof",
+        DECK,
+        proof.len()
+    );
+}
@@ -0,0 +1,141 @@
+use halo2_playground::{
+    audit::fit_k,
+    chips::{
+        decompose::{DecomposeChip, DecomposeConfig, Decomposition},
+        tables::Tables,
+    },
+    entropy::SeededEntropy,
+    report::report_if_requested,
+};
+use halo2_proofs::{
+    arithmetic::{Field, FieldExt},
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+#[derive(Clone, Debug)]
+struct Config {
+    tables: Tables,
+    decompose: DecomposeConfig,
+    advice: Column<Advice>,
+    instance: Column<Instance>,
+}
+
+/// Decomposes the value in instance row 0 and exposes its limbs in rows 1, 2, ...
+#[derive(Clone)]
+struct DecomposeCircuit {
+    mode: Decomposition,
+}
+
+impl Circuit<Fr> for DecomposeCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+        let mut tables = Tables::new();
+        let decompose = DecomposeChip::configure(meta, &mut tables);
+        Config {
+            tables,
+            decompose,
+            advice,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        config.tables.load(&mut layouter)?;
+        let chip = DecomposeChip::construct(config.decompose);
+        let value = layouter.assign_region(
+            || "load value",
+            |mut region| {
+                region.assign_advice_from_instance(|| "value", config.instance, 0, config.advice, 0)
+            },
+        )?;
+        let limbs = chip.decompose(layouter.namespace(|| "decompose"), &value, self.mode)?;
+        for (i, limb) in limbs.iter().enumerate() {
+            layouter.constrain_instance(limb.cell(), config.instance, 1 + i)?;
+        }
+        Ok(())
+    }
+}
+
+fn run(mode: Decomposition, value: Fr, limbs: &[u64]) -> bool {
+    let circuit = DecomposeCircuit { mode };
+    let k = fit_k::<Fr, _>(&circuit).expect("fit_k");
+    let instance = std::iter::once(value)
+        .chain(limbs.iter().map(|&limb| Fr::from(limb)))
+        .collect();
+    MockProver::run(k, &circuit, vec![instance])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+fn main() {
+    report_if_requested(
+        "decompose",
+        &DecomposeCircuit {
+            mode: Decomposition::Bytes,
+        },
+    );
+    let mut rng = SeededEntropy::new(0x5962_be5d_763d_318d);
+    let mut values: Vec<Fr> = (0..4).map(|_| Fr::random(&mut rng)).collect();
+    // around zero, the top of 2^253 and the modulus
+    values.extend([
+        Fr::zero(),
+        Fr::one(),
+        Fr::from_u128(1 << 127) * Fr::from_u128(1 << 126),
+        -Fr::from(2),
+        -Fr::one(),
+    ]);
+
+    assert_eq!(Decomposition::Bits.limbs::<Fr>(), 254);
+    assert_eq!(Decomposition::Bytes.limbs::<Fr>(), 32);
+    for mode in [Decomposition::Bytes, Decomposition::Bits] {
+        for value in values.iter() {
+            let limbs = mode.split(value);
+            let recomposed = limbs.iter().rev().fold(Fr::zero(), |acc, &limb| {
+                acc * Fr::from(1 << mode.limb_bits()) + Fr::from(limb)
+            });
+            assert_eq!(recomposed, *value);
+            assert!(run(mode, *value, &limbs), "{:?} of {:?}", mode, value);
+
+            // any other limbs for the value are rejected
+            let mut wrong = limbs.clone();
+            wrong[0] ^= 1;
+            assert!(!run(mode, *value, &wrong));
+        }
+    }
+
+    // 5 and 5 + p both fit in 254 bits, but only the canonical limbs are accepted
+    let p_minus_one = Decomposition::Bits.split(&-Fr::one());
+    let mut five_plus_p = p_minus_one;
+    let mut carry = 6;
+    for bit in five_plus_p.iter_mut() {
+        let sum = *bit + (carry & 1);
+        *bit = sum & 1;
+        carry = (carry >> 1) + (sum >> 1);
+    }
+    assert_eq!(carry, 0);
+    assert!(run(
+        Decomposition::Bits,
+        Fr::from(5),
+        &Decomposition::Bits.split(&Fr::from(5))
+    ));
+    assert!(!run(Decomposition::Bits, Fr::from(5), &five_plus_p));
+    println!("decomposed {} values into bits and bytes", values.len());
+}
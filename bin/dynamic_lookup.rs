@@ -0,0 +1,144 @@
+use halo2_playground::{
+    audit::fit_k,
+    chips::dynamic_lookup::{DynamicLookupChip, DynamicLookupConfig},
+    report::report_if_requested,
+};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
+    poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+struct Config {
+    lookup: DynamicLookupConfig<2>,
+    q_square: Selector,
+    advice: Column<Advice>,
+    instance: Column<Instance>,
+}
+
+/// A private table of squares `(x, x^2)` over witnessed `x`s, built in advice columns and
+/// constrained by its own gate. Each pair `(x, y)` in instance rows `2i` and `2i + 1` is looked
+/// up in it, which proves `y = x^2` for an `x` the table holds.
+#[derive(Clone)]
+struct SquaresCircuit<F: FieldExt> {
+    keys: Vec<Value<F>>,
+    reads: usize,
+}
+
+impl<F: FieldExt> SquaresCircuit<F> {
+    fn new(keys: &[F], reads: usize) -> Self {
+        Self {
+            keys: keys.iter().map(|x| Value::known(*x)).collect(),
+            reads,
+        }
+    }
+}
+
+impl<F: FieldExt> Circuit<F> for SquaresCircuit<F> {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            keys: vec![Value::unknown(); self.keys.len()],
+            reads: self.reads,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+        let lookup = DynamicLookupChip::<F, 2>::configure(meta);
+        // what makes the table a table of squares; without it any rows would do
+        let q_square = meta.selector();
+        meta.create_gate("square", |meta| {
+            let q_square = meta.query_selector(q_square);
+            let x = meta.query_advice(lookup.table[0], Rotation::cur());
+            let y = meta.query_advice(lookup.table[1], Rotation::cur());
+            vec![q_square * (y - x.clone() * x)]
+        });
+        Config {
+            lookup,
+            q_square,
+            advice,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = DynamicLookupChip::<F, 2>::construct(config.lookup);
+        let rows: Vec<[Value<F>; 2]> = self.keys.iter().map(|x| [*x, *x * *x]).collect();
+        chip.assign_table(layouter.namespace(|| "squares"), &rows, |region, offset| {
+            config.q_square.enable(region, offset)
+        })?;
+
+        let reads = layouter.assign_region(
+            || "load reads",
+            |mut region| {
+                (0..2 * self.reads)
+                    .map(|row| {
+                        region.assign_advice_from_instance(
+                            || "read",
+                            config.instance,
+                            row,
+                            config.advice,
+                            row,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            },
+        )?;
+        for (i, read) in reads.chunks(2).enumerate() {
+            chip.lookup(
+                layouter.namespace(|| format!("read {}", i)),
+                [&read[0], &read[1]],
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn run(circuit: &SquaresCircuit<Fr>, reads: &[(u64, u64)]) -> bool {
+    let k = fit_k::<Fr, _>(circuit).expect("fit_k");
+    let instance = reads
+        .iter()
+        .flat_map(|(x, y)| [Fr::from(*x), Fr::from(*y)])
+        .collect();
+    MockProver::run(k, circuit, vec![instance])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+fn main() {
+    let keys: Vec<Fr> = [3, 7, 10, 12].into_iter().map(Fr::from).collect();
+    let circuit = SquaresCircuit::new(&keys, 3);
+    report_if_requested("dynamic_lookup", &circuit);
+
+    // satisfied: every read is a row of the table, in any order and with repeats
+    assert!(run(&circuit, &[(7, 49), (3, 9), (7, 49)]));
+    assert!(run(&circuit, &[(12, 144), (10, 100), (3, 9)]));
+    // violated: a wrong square, a right square of a key the table lacks, and a row whose
+    // columns come from different table rows
+    assert!(!run(&circuit, &[(7, 50), (3, 9), (10, 100)]));
+    assert!(!run(&circuit, &[(5, 25), (3, 9), (10, 100)]));
+    assert!(!run(&circuit, &[(3, 49), (3, 9), (10, 100)]));
+    // rows with the lookup disabled are all zeros, and match without a zero table row
+    assert!(run(&SquaresCircuit::new(&keys, 0), &[]));
+
+    // the table is a witness: the prover picks which squares it holds
+    let other = SquaresCircuit::new(&[Fr::from(5), Fr::from(6)], 1);
+    assert!(run(&other, &[(5, 25)]));
+    assert!(!run(&other, &[(7, 49)]));
+}
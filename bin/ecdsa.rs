@@ -0,0 +1,103 @@
+use std::time::Instant;
+
+use halo2_playground::{
+    audit::fit_k,
+    chips::nonnative::LIMBS,
+    circuits::ecdsa::{self, public_key, sign, verify, EcdsaCircuit, Point, Signature},
+    entropy::SeededEntropy,
+    prover::{run_proof, ProofOptions},
+    report::{report_if_requested, CostReport},
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+use num_bigint::BigUint;
+use sha3::{Digest, Keccak256};
+
+fn main() {
+    report_if_requested("ecdsa", &EcdsaCircuit::default());
+
+    let n = ecdsa::group_order();
+    let secret = BigUint::from_bytes_be(&Keccak256::digest(b"secret key")) % &n;
+    let nonce = BigUint::from_bytes_be(&Keccak256::digest(b"nonce")) % &n;
+    let z = BigUint::from_bytes_be(&Keccak256::digest(b"message"));
+    let public = public_key(&secret);
+    assert!(public.is_on_curve());
+    assert!(Point::auxiliary().is_on_curve());
+    let signature = sign(&secret, &z, &nonce);
+    assert!(verify(&public, &z, &signature));
+    let forged = Signature {
+        s: (&signature.s + 1u32) % &n,
+        ..signature.clone()
+    };
+    assert!(!verify(&public, &z, &forged));
+
+    let circuit = EcdsaCircuit::new(signature);
+    let instance = EcdsaCircuit::instance::<Fr>(&z, &public);
+    let k = fit_k::<Fr, _>(&circuit).expect("fit_k");
+    let prover = MockProver::run(k, &circuit, vec![instance.clone()]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+    let prover = MockProver::run(k, &EcdsaCircuit::new(forged), vec![instance.clone()]).unwrap();
+    assert!(prover.verify().is_err());
+    // the signature of one message doesn't pass for another
+    let mut other = instance.clone();
+    other[0] += Fr::one();
+    let prover = MockProver::run(k, &circuit, vec![other.clone()]).unwrap();
+    assert!(prover.verify().is_err());
+    println!(
+        "ecdsa over secp256k1: k = {}, public inputs z, Q.x, Q.y in {} limbs each",
+        k, LIMBS
+    );
+
+    let params = cached_params(k).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let report = CostReport::from_vk(&vk);
+    println!("verifier cost:\n{}", report);
+    if !std::env::args().skip(1).any(|arg| arg == "--prove") {
+        println!("run with --prove for a real proof and its verification time");
+        return;
+    }
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let start = Instant::now();
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &[&instance],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    let prove = start.elapsed();
+    let start = Instant::now();
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[&instance],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+    let verify = start.elapsed();
+    assert!(check_proof(
+        &params,
+        pk.get_vk(),
+        &[&other],
+        &proof,
+        ProofOptions::default()
+    )
+    .into_result()
+    .is_err());
+    println!(
+        "proof of {} bytes, proved in {:.2?}, verified in {:.2?}",
+        proof.len(),
+        prove,
+        verify
+    );
+}
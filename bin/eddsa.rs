@@ -0,0 +1,69 @@
+use ed25519_dalek::{Keypair, PublicKey, SecretKey, Signer};
+use halo2_playground::{
+    audit::fit_k,
+    circuits::{
+        ecdsa::EcdsaCircuit,
+        eddsa::{self, challenge, verify, EddsaCircuit, Point},
+    },
+    report::{report_if_requested, CostReport},
+};
+use halo2_proofs::{arithmetic::Field, dev::MockProver, halo2curves::bn256::Fr};
+use num_bigint::BigUint;
+use sha2::{Digest, Sha512};
+
+fn main() {
+    report_if_requested("eddsa", &EddsaCircuit::default());
+
+    let seed = Sha512::digest(b"secret key");
+    let secret = SecretKey::from_bytes(&seed[..32]).expect("a 32-byte secret");
+    let public = PublicKey::from(&secret);
+    let keypair = Keypair { secret, public };
+    let message = b"message";
+    let signature = keypair.sign(message).to_bytes();
+    let public = keypair.public.to_bytes();
+    assert!(verify(&public, message, &signature));
+
+    let mut r = [0u8; 32];
+    r.copy_from_slice(&signature[..32]);
+    let a = Point::decompress(&public).expect("a canonical public key");
+    let big_r = Point::decompress(&r).expect("a canonical R");
+    assert_eq!(a.compress(), public);
+    let k = challenge(&r, &public, message);
+    let mut forged = signature;
+    let s = (BigUint::from_bytes_le(&signature[32..]) + 1u32) % eddsa::group_order();
+    let mut bytes = s.to_bytes_le();
+    bytes.resize(32, 0);
+    forged[32..].copy_from_slice(&bytes);
+    assert!(!verify(&public, message, &forged));
+
+    let circuit = EddsaCircuit::new(&signature);
+    let instance = EddsaCircuit::instance::<Fr>(&a, &big_r, &k);
+    let k = fit_k::<Fr, _>(&circuit).expect("fit_k");
+    let prover = MockProver::run(k, &circuit, vec![instance.clone()]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+    let prover = MockProver::run(k, &EddsaCircuit::new(&forged), vec![instance.clone()]).unwrap();
+    assert!(prover.verify().is_err());
+    // the signature doesn't pass under another challenge
+    let mut other = instance;
+    other[12] += Fr::one();
+    let prover = MockProver::run(k, &circuit, vec![other]).unwrap();
+    assert!(prover.verify().is_err());
+
+    let edwards = CostReport::measure(&circuit).expect("measure eddsa");
+    let weierstrass = CostReport::measure(&EcdsaCircuit::default()).expect("measure ecdsa");
+    for (name, report) in [
+        ("ed25519 (edwards)", &edwards),
+        ("secp256k1 (weierstrass)", &weierstrass),
+    ] {
+        println!(
+            "{:<24} k = {:>2}, {:>7} rows, ~{} proof bytes",
+            name,
+            report.k,
+            report
+                .rows_used
+                .map(|rows| rows.to_string())
+                .unwrap_or_default(),
+            report.proof_bytes
+        );
+    }
+}
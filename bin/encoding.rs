@@ -0,0 +1,221 @@
+use halo2_playground::{
+    encoding::{
+        decode_base64, decode_hex, decode_instances, encode_base64, encode_hex, encode_instances,
+        field_from_le_bytes, parse_columns, parse_field, EncodingError, Reduction,
+    },
+    evm::{from_word, from_words, to_word, to_words},
+    fmt::{to_decimal, to_hex, to_hex_padded, to_short},
+    schema::{Encoding, WitnessError, WitnessLayout},
+};
+use halo2_proofs::{
+    arithmetic::{Field, FieldExt},
+    halo2curves::bn256::Fr,
+};
+
+const MODULUS_DEC: &str =
+    "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+
+fn main() {
+    let parse = |s: &str| parse_field::<Fr>(s, Reduction::Reject);
+    let reduce = |s: &str| parse_field::<Fr>(s, Reduction::Reduce);
+
+    assert_eq!(parse("0"), Ok(Fr::zero()));
+    assert_eq!(parse("0x2a"), Ok(Fr::from(42)));
+    assert_eq!(parse(" 1_000 "), Ok(Fr::from(1000)));
+    assert_eq!(parse(Fr::MODULUS), parse(MODULUS_DEC));
+
+    // the modulus boundary
+    let p_minus_1 = MODULUS_DEC.replace("617", "616");
+    assert_eq!(parse(&p_minus_1), Ok(-Fr::one()));
+    assert!(matches!(
+        parse(MODULUS_DEC),
+        Err(EncodingError::NonCanonical(_))
+    ));
+    assert!(matches!(
+        parse(Fr::MODULUS),
+        Err(EncodingError::NonCanonical(_))
+    ));
+    assert_eq!(reduce(MODULUS_DEC), Ok(Fr::zero()));
+    assert_eq!(reduce(&MODULUS_DEC.replace("617", "618")), Ok(Fr::one()));
+
+    // 2^256 - 1 fits the repr but not the field
+    let max_repr = format!("0x{}", "f".repeat(64));
+    assert!(matches!(
+        parse(&max_repr),
+        Err(EncodingError::NonCanonical(_))
+    ));
+    assert_eq!(
+        reduce(&max_repr),
+        Ok(Fr::from(2).pow_vartime(&[256]) - Fr::one())
+    );
+    assert_eq!(
+        field_from_le_bytes::<Fr>(&[0xff; 32], Reduction::Reduce),
+        reduce(&max_repr)
+    );
+
+    assert_eq!(parse(""), Err(EncodingError::Empty));
+    assert_eq!(parse("0x"), Err(EncodingError::Empty));
+    assert_eq!(parse("12a"), Err(EncodingError::InvalidDigit('a')));
+    assert_eq!(
+        reduce(&format!("0x1{}", "0".repeat(128))),
+        Err(EncodingError::TooLarge)
+    );
+
+    let columns = parse_columns::<Fr>("1, 2 3\n# comment\n\n0x10\n", Reduction::Reject).unwrap();
+    assert_eq!(
+        columns,
+        vec![
+            vec![Fr::from(1), Fr::from(2), Fr::from(3)],
+            vec![Fr::from(16)]
+        ]
+    );
+    assert!(parse_columns::<Fr>(MODULUS_DEC, Reduction::Reject).is_err());
+
+    // formatting round-trips through parsing
+    for value in [
+        Fr::zero(),
+        Fr::from(7),
+        -Fr::one(),
+        Fr::from_u128(u128::MAX),
+    ] {
+        assert_eq!(parse(&to_decimal(&value)), Ok(value));
+        assert_eq!(parse(&to_hex(&value)), Ok(value));
+        assert_eq!(parse(&to_hex_padded(&value)), Ok(value));
+    }
+    assert_eq!(to_decimal(&-Fr::one()), p_minus_1);
+    assert_eq!(to_hex(&Fr::from(255)), "0xff");
+    assert_eq!(to_short(&Fr::from(42)), "42");
+    assert_eq!(to_short(&-Fr::from(3)), "-3");
+    assert_eq!(to_short(&Fr::from_u128(u128::MAX)), "0xffff…ffff");
+
+    // instances through JSON, at both ends of the field
+    let columns = vec![
+        vec![Fr::zero(), Fr::one(), -Fr::one()],
+        vec![],
+        vec![Fr::from(9)],
+    ];
+    let json = encode_instances(&columns);
+    assert!(json.starts_with(&format!("[[\"0x{}\",", "0".repeat(64))));
+    assert_eq!(
+        decode_instances::<Fr>(&json, Reduction::Reject),
+        Ok(columns)
+    );
+    assert_eq!(
+        decode_instances::<Fr>(" [ [1, \"0x2\"] ,[] ]\n", Reduction::Reject),
+        Ok(vec![vec![Fr::one(), Fr::from(2)], vec![]])
+    );
+    assert_eq!(decode_instances::<Fr>("[]", Reduction::Reject), Ok(vec![]));
+    assert!(matches!(
+        decode_instances::<Fr>(&format!("[[\"{}\"]]", MODULUS_DEC), Reduction::Reject),
+        Err(EncodingError::NonCanonical(_))
+    ));
+    for broken in ["", "[[1]", "[1]", "[[1,]]", "[[1]] x", "[[\"1]]"] {
+        assert!(matches!(
+            decode_instances::<Fr>(broken, Reduction::Reject),
+            Err(EncodingError::Json(_))
+        ));
+    }
+
+    // proofs through hex and base64, at every padding length
+    for len in 0..8 {
+        let bytes: Vec<u8> = (0..len).map(|i| (i * 97 + 200) as u8).collect();
+        assert_eq!(decode_hex(&encode_hex(&bytes)), Ok(bytes.clone()));
+        assert_eq!(decode_base64(&encode_base64(&bytes)), Ok(bytes));
+    }
+    assert_eq!(encode_hex(&[0, 0xab]), "00ab");
+    assert_eq!(decode_hex("0x00AB\n"), Ok(vec![0, 0xab]));
+    assert_eq!(encode_base64(b"halo2"), "aGFsbzI=");
+    assert_eq!(decode_base64("aGFsbzI=\n"), Ok(b"halo2".to_vec()));
+    assert_eq!(
+        decode_hex("abc"),
+        Err(EncodingError::Length {
+            encoding: "hex",
+            len: 3
+        })
+    );
+    assert_eq!(decode_hex("0g"), Err(EncodingError::InvalidDigit('g')));
+    assert!(matches!(
+        decode_base64("aGFsbzI"),
+        Err(EncodingError::Length { .. })
+    ));
+    assert_eq!(
+        decode_base64("a=FsbzI="),
+        Err(EncodingError::InvalidDigit('='))
+    );
+
+    // witness files, positional and JSON
+    let layout = WitnessLayout::new("example")
+        .field("x", Encoding::Field, "any value")
+        .field("flag", Encoding::Integer { bits: 1 }, "a bit")
+        .field("byte", Encoding::Integer { bits: 8 }, "a byte");
+    let expected = vec![Fr::from(3), Fr::one(), Fr::from(255)];
+    assert_eq!(layout.read::<Fr>("3, 1 0xff\n"), Ok(expected.clone()));
+    assert_eq!(
+        layout.read::<Fr>(r#"{"byte": "0xff", "x": 3, "flag": "1"}"#),
+        Ok(expected)
+    );
+    assert_eq!(
+        layout.read::<Fr>("3 1"),
+        Err(WitnessError::Count {
+            expected: 3,
+            found: 2
+        })
+    );
+    assert!(matches!(
+        layout.read::<Fr>("3 2 0"),
+        Err(WitnessError::Field { name, expected: Encoding::Integer { bits: 1 }, .. }) if name == "flag"
+    ));
+    assert!(matches!(
+        layout.read::<Fr>("3 1 256"),
+        Err(WitnessError::Field { name, .. }) if name == "byte"
+    ));
+    assert!(matches!(
+        layout.read::<Fr>(&format!("{} 1 0", MODULUS_DEC)),
+        Err(WitnessError::Field { name, .. }) if name == "x"
+    ));
+    assert_eq!(
+        layout.read::<Fr>(r#"{"x": "3", "flag": "1"}"#),
+        Err(WitnessError::Missing("byte".to_string()))
+    );
+    assert_eq!(
+        layout.read::<Fr>(r#"{"x": "3", "flag": "1", "byte": "0", "y": "0"}"#),
+        Err(WitnessError::Unknown("y".to_string()))
+    );
+    assert!(matches!(
+        layout.read::<Fr>(r#"{"x": "3", "flag": }"#),
+        Err(WitnessError::Json(_))
+    ));
+    assert!(matches!(
+        layout.read::<Fr>(r#"{"x": ["3"], "flag": "1", "byte": "0"}"#),
+        Err(WitnessError::Json(_))
+    ));
+
+    // EVM words are big-endian, the reverse of the repr
+    let mut one = [0u8; 32];
+    one[31] = 1;
+    assert_eq!(to_word(&Fr::one()), one);
+    assert_eq!(from_word(&one), Ok(Fr::one()));
+    let mut modulus = [0u8; 32];
+    modulus.copy_from_slice(&to_word(&-Fr::one()));
+    modulus[31] += 1;
+    assert!(matches!(
+        from_word(&modulus),
+        Err(EncodingError::NonCanonical(_))
+    ));
+    let values = [
+        Fr::zero(),
+        Fr::from(42),
+        -Fr::one(),
+        Fr::from_u128(u128::MAX),
+    ];
+    let packed = to_words(&values);
+    assert_eq!(packed.len(), 32 * values.len());
+    assert_eq!(&packed[32..64], &to_word(&Fr::from(42)));
+    assert_eq!(from_words(&packed), Ok(values.to_vec()));
+    assert_eq!(
+        from_words(&packed[..packed.len() - 1]),
+        Err(EncodingError::PartialWord(31))
+    );
+
+    println!("field encoding checks passed");
+}
@@ -0,0 +1,108 @@
+use halo2_playground::{
+    audit::fit_k,
+    entropy::SeededEntropy,
+    expr::{Expr, ExprCircuit, ExprError, Relation},
+    prover::{run_proof, ProofOptions},
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+const RELATION: &str = "(a + b) * c - d^3 == out";
+
+fn run(circuit: &ExprCircuit<Fr>, instance: Vec<Fr>) -> bool {
+    let k = fit_k::<Fr, _>(circuit).expect("fit_k");
+    MockProver::run(k, circuit, vec![instance])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+fn parse_error(text: &str) -> usize {
+    match Relation::<Fr>::parse(text) {
+        Err(ExprError::Parse { position, .. }) => position,
+        other => panic!("{:?} parsed: {:?}", text, other),
+    }
+}
+
+fn main() {
+    let relation = Relation::<Fr>::parse(RELATION).expect("parse");
+    assert_eq!(relation.variables, ["a", "b", "c", "d", "out"]);
+    assert_eq!(relation.public_names(), ["out"]);
+    // precedence: `-` binds looser than `^`, and both operators of a kind associate left
+    let x = Relation::<Fr>::parse("-x^2 - 1 - 2 == y").unwrap();
+    assert_eq!(x.lhs.eval(&[Fr::from(3), Fr::zero()]), -Fr::from(12));
+    assert!(matches!(x.rhs, Expr::Var(1)));
+    assert_eq!(parse_error("a + == b"), 4);
+    assert_eq!(parse_error("a + b"), 5);
+    assert_eq!(parse_error("(a == b"), 3);
+    assert_eq!(parse_error("a^b == c"), 2);
+    assert_eq!(parse_error("a = b"), 2);
+
+    // out = (2 + 3) * 4 - 1 = 19, derived
+    let (a, b, c, d) = (Fr::from(2), Fr::from(3), Fr::from(4), Fr::from(1));
+    let values = relation
+        .complete(&[("a", a), ("b", b), ("c", c), ("d", d)])
+        .expect("complete");
+    let instance = relation.instance(&values);
+    assert_eq!(instance, vec![Fr::from(19)]);
+    assert_eq!(
+        relation.complete(&[("a", a), ("b", b), ("c", c), ("d", d), ("out", Fr::one())]),
+        Err(ExprError::Unsatisfied)
+    );
+    assert_eq!(
+        relation.complete(&[("a", a), ("b", b)]),
+        Err(ExprError::Missing(vec![
+            "c".into(),
+            "d".into(),
+            "out".into()
+        ]))
+    );
+    assert_eq!(
+        relation.complete(&[("e", a)]),
+        Err(ExprError::UnknownVariable("e".into()))
+    );
+
+    let circuit = ExprCircuit::new(relation.clone(), values.clone());
+    assert!(run(&circuit, instance.clone()));
+    assert!(!run(&circuit, vec![Fr::from(20)]));
+    // a private value that doesn't satisfy the relation, with the right public output
+    let mut wrong = values.clone();
+    wrong[3] = Fr::from(2);
+    assert!(!run(
+        &ExprCircuit::new(relation.clone(), wrong),
+        instance.clone()
+    ));
+
+    // no public variables: knowing a square root of 49
+    let root = Relation::<Fr>::parse("x^2 - 49 == 0").unwrap();
+    let values = root.complete(&[("x", -Fr::from(7))]).unwrap();
+    assert!(run(&ExprCircuit::new(root, values), vec![]));
+
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &[&instance],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[&instance],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+    println!("{}: out = 19, {}-byte proof", RELATION, proof.len());
+}
@@ -0,0 +1,94 @@
+use std::ptr;
+
+use halo2_playground::{
+    audit::fit_k,
+    circuits::mul::MulCircuit,
+    entropy::SeededEntropy,
+    ffi::{
+        hp_verify_kzg_proof, HP_CIRCUIT_MUL, HP_CIRCUIT_POSEIDON, HP_INVALID_ARGUMENT, HP_REJECTED,
+        HP_VERIFIED,
+    },
+    onchain::encode_columns,
+    prover::{run_proof, ProofOptions},
+    storage::cached_params,
+};
+use halo2_proofs::{
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+    poly::commitment::Params,
+};
+
+/// `hp_verify_kzg_proof` over `[params, vk, instances, proof]`, as a C host would call it.
+fn call(circuit: u32, inputs: [&[u8]; 4]) -> i32 {
+    let [params, vk, instances, proof] = inputs;
+    // SAFETY: every pointer comes from a live slice of the length passed with it
+    unsafe {
+        hp_verify_kzg_proof(
+            circuit,
+            params.as_ptr(),
+            params.len(),
+            vk.as_ptr(),
+            vk.len(),
+            instances.as_ptr(),
+            instances.len(),
+            proof.as_ptr(),
+            proof.len(),
+        )
+    }
+}
+
+fn main() {
+    let (a, b) = (Fr::from(3), Fr::from(5));
+    let circuit = MulCircuit::new(a, b);
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let instance = MulCircuit::instance(a * b);
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &[&instance],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("run_proof");
+    let mut vk_bytes = vec![];
+    pk.get_vk().write(&mut vk_bytes).expect("write vk");
+    let mut params_bytes = vec![];
+    params.write(&mut params_bytes).expect("write params");
+    let instances = encode_columns(&[instance]);
+
+    let inputs: [&[u8]; 4] = [&params_bytes, &vk_bytes, &instances, &proof];
+    assert_eq!(call(HP_CIRCUIT_MUL, inputs), HP_VERIFIED);
+    // the key doesn't rebuild as the Poseidon circuit's
+    assert_eq!(call(HP_CIRCUIT_POSEIDON, inputs), HP_REJECTED);
+    assert_eq!(call(7, inputs), HP_INVALID_ARGUMENT);
+    let wrong = encode_columns(&[MulCircuit::instance(a * b + Fr::one())]);
+    assert_eq!(
+        call(HP_CIRCUIT_MUL, [&params_bytes, &vk_bytes, &wrong, &proof]),
+        HP_REJECTED
+    );
+    // null is only a valid pointer for an empty input
+    // SAFETY: the null pointers are rejected before anything is read
+    let null = unsafe {
+        hp_verify_kzg_proof(
+            HP_CIRCUIT_MUL,
+            ptr::null(),
+            params_bytes.len(),
+            vk_bytes.as_ptr(),
+            vk_bytes.len(),
+            instances.as_ptr(),
+            instances.len(),
+            proof.as_ptr(),
+            proof.len(),
+        )
+    };
+    assert_eq!(null, HP_INVALID_ARGUMENT);
+    assert_eq!(
+        call(HP_CIRCUIT_MUL, [&[], &vk_bytes, &instances, &proof]),
+        HP_REJECTED
+    );
+
+    println!("hp_verify_kzg_proof accepts the proof and rejects the rest");
+}
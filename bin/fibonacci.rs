@@ -0,0 +1,82 @@
+use halo2_playground::{
+    audit::fit_k,
+    circuits::fibonacci::FibonacciCircuit,
+    entropy::SeededEntropy,
+    prover::{run_proof, ProofOptions},
+    report::{report_if_requested, CostReport},
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+const N: usize = 64;
+type Fibonacci = FibonacciCircuit<Fr, N>;
+
+fn run(instance: Vec<Fr>) -> bool {
+    let circuit = Fibonacci::new();
+    let k = fit_k::<Fr, _>(&circuit).expect("fit_k");
+    MockProver::run(k, &circuit, vec![instance])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+fn main() {
+    report_if_requested("fibonacci", &Fibonacci::new());
+    let (f0, f1) = (Fr::zero(), Fr::one());
+    let sequence = Fibonacci::sequence(f0, f1);
+    assert_eq!(sequence[10], Fr::from(55));
+    // f(64) still fits in a u64, so it can be checked against integers
+    let (mut a, mut b) = (0u64, 1u64);
+    for _ in 0..N {
+        (a, b) = (b, a + b);
+    }
+    assert_eq!(sequence[N], Fr::from(a));
+
+    assert!(run(Fibonacci::instance(f0, f1)));
+    assert!(run(Fibonacci::instance(Fr::from(2), Fr::from(1))));
+    assert!(!run(vec![f0, f1, sequence[N] + Fr::one()]));
+    // f(63) is one row short of the public row
+    assert!(!run(vec![f0, f1, sequence[N - 1]]));
+
+    let circuit = Fibonacci::new();
+    let instance = Fibonacci::instance(f0, f1);
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit.clone(),
+        &[&instance],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[&instance],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+    let report = CostReport::measure(&circuit).expect("measure");
+    println!(
+        "f({}) = {} in one region, k = {}, {} rows, {}-byte proof",
+        N,
+        a,
+        report.k,
+        report
+            .rows_used
+            .map(|rows| rows.to_string())
+            .unwrap_or_default(),
+        proof.len()
+    );
+}
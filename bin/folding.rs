@@ -0,0 +1,69 @@
+use std::time::Instant;
+
+use halo2_playground::{
+    continuation::{prove_chain, run_steps, verify_chain, ChainChunkCircuit},
+    entropy::SeededEntropy,
+    folding::{FoldingAccumulator, FoldingParams},
+    storage::cached_params,
+};
+use halo2_proofs::{
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+    poly::commitment::ParamsProver,
+};
+
+const K: u32 = 8;
+const STEPS: usize = 64;
+const CHUNKS: usize = 4;
+
+fn rng() -> SeededEntropy {
+    SeededEntropy::new(0x5962_be5d_763d_318d)
+}
+
+fn main() {
+    let initial = Fr::from(1);
+    let expected = run_steps(initial, STEPS * CHUNKS);
+
+    // folding
+    let params = FoldingParams::new(rng());
+    let start = Instant::now();
+    let mut accumulator = FoldingAccumulator::new(&params, initial);
+    while accumulator.steps < STEPS * CHUNKS {
+        accumulator.fold_step(&params);
+    }
+    let fold_time = start.elapsed();
+    let start = Instant::now();
+    assert!(accumulator.decide(&params), "decide");
+    let decide_time = start.elapsed();
+    assert_eq!(accumulator.state_out, expected);
+    println!(
+        "folding: {} steps, fold {:?}, decide {:?}",
+        accumulator.steps, fold_time, decide_time
+    );
+
+    // chunk proofs
+    let general_params = cached_params(K).expect("cached_params");
+    let circuit = ChainChunkCircuit::<Fr, STEPS>::new();
+    let vk = keygen_vk(&general_params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&general_params, vk, &circuit).expect("keygen_pk");
+    let start = Instant::now();
+    let proofs =
+        prove_chain::<STEPS, _>(&general_params, &pk, initial, CHUNKS, rng()).expect("prove_chain");
+    let prove_time = start.elapsed();
+    let start = Instant::now();
+    let ok = verify_chain(
+        general_params.verifier_params(),
+        pk.get_vk(),
+        initial,
+        expected,
+        &proofs,
+    )
+    .expect("verify_chain");
+    assert!(ok, "verify_chain");
+    let verify_time = start.elapsed();
+    assert_eq!(proofs.last().unwrap().state_out, expected);
+    println!(
+        "chunks: {} x {} steps, prove {:?}, verify {:?}",
+        CHUNKS, STEPS, prove_time, verify_time
+    );
+}
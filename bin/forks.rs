@@ -0,0 +1,31 @@
+//! Proves the same multiplication under each halo2 fork `compat` is built against and prints
+//! their costs side by side. Upstream zcash `halo2_proofs` is only in with `halo2-zcash`
+//! (`make forks`).
+
+use halo2_playground::{compat, entropy::SeededEntropy, error::PlaygroundError, scheme::SchemeRun};
+
+const K: u32 = 5;
+const SEED: u64 = 0x5962_be5d_763d_318d;
+
+type Run = fn(u32, u64, u64, u64, SeededEntropy) -> Result<SchemeRun, PlaygroundError>;
+
+fn main() {
+    #[allow(unused_mut)]
+    let mut forks: Vec<(&str, Run)> = vec![(compat::pse::NAME, compat::pse::run)];
+    #[cfg(feature = "halo2-zcash")]
+    forks.push((compat::zcash::NAME, compat::zcash::run));
+
+    println!(
+        "{:<6} {:<10} {:>6} {:>10} {:>10} {:>10}",
+        "fork", "scheme", "proof", "keygen", "prove", "verify"
+    );
+    for (fork, run) in forks {
+        let result = run(K, 3, 5, 15, SeededEntropy::new(SEED)).expect("run");
+        // each fork rejects a wrong product
+        assert!(run(K, 3, 5, 16, SeededEntropy::new(SEED)).is_err());
+        println!(
+            "{:<6} {:<10} {:>6} {:>10.2?} {:>10.2?} {:>10.2?}",
+            fork, result.scheme, result.proof_bytes, result.keygen, result.prove, result.verify
+        );
+    }
+}
@@ -0,0 +1,120 @@
+use halo2_playground::{
+    audit::fit_k,
+    dev::{constraint_system_dot, dump_constraint_system, mock_check, Assignments},
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+struct Config {
+    a: Column<Advice>,
+    b: Column<Advice>,
+    c: Column<Advice>,
+    q: Selector,
+}
+
+/// `a * b + 2 = c` on one row, with `c` as given, right or not.
+#[derive(Clone, Default)]
+struct MulAddCircuit {
+    a: Value<Fr>,
+    b: Value<Fr>,
+    c: Value<Fr>,
+}
+
+impl Circuit<Fr> for MulAddCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let [a, b, c] = [(); 3].map(|_| meta.advice_column());
+        let q = meta.selector();
+        meta.create_gate("mul add", |meta| {
+            let q = meta.query_selector(q);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let c = meta.query_advice(c, Rotation::cur());
+            vec![(
+                "a * b + 2 = c",
+                q * (a * b + Expression::Constant(Fr::from(2)) - c),
+            )]
+        });
+        Config { a, b, c, q }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "mul add",
+            |mut region| {
+                config.q.enable(&mut region, 0)?;
+                region.assign_advice(|| "a", config.a, 0, || self.a)?;
+                region.assign_advice(|| "b", config.b, 0, || self.b)?;
+                region.assign_advice(|| "c", config.c, 0, || self.c)?;
+                Ok(())
+            },
+        )
+    }
+}
+
+fn main() {
+    let circuit = |c: u64| MulAddCircuit {
+        a: Value::known(Fr::from(3)),
+        b: Value::known(Fr::from(5)),
+        c: Value::known(Fr::from(c)),
+    };
+    let right = circuit(17);
+    let k = fit_k::<Fr, _>(&right).expect("fit_k");
+    assert!(MockProver::run(k, &right, vec![]).unwrap().verify().is_ok());
+    let assignments = Assignments::synthesize(k, &right, vec![]).expect("synthesize");
+    assert!(assignments.all_failing().is_empty());
+
+    let wrong = circuit(16);
+    assert!(MockProver::run(k, &wrong, vec![])
+        .unwrap()
+        .verify()
+        .is_err());
+    assert!(mock_check(k, &right, vec![]).is_ok());
+    let report = mock_check(k, &wrong, vec![]).unwrap_err().to_string();
+    assert!(report.starts_with("1 failure:\n  1. "), "{}", report);
+    assert!(report.contains("mul add"), "{}", report);
+    let assignments = Assignments::synthesize(k, &wrong, vec![]).expect("synthesize");
+    let failing = assignments.all_failing();
+    assert_eq!(failing.len(), 1);
+    let failure = &failing[0];
+    assert_eq!((failure.gate.as_str(), failure.row), ("mul add", 0));
+    assert_eq!(failure.value, Fr::one());
+    // q * a * b, q * 2 and q * -c
+    let values: Vec<Fr> = failure.terms.iter().map(|term| term.value).collect();
+    assert_eq!(values, vec![Fr::from(15), Fr::from(2), -Fr::from(16)]);
+    // the selector is off on every other row
+    assert!(assignments.failing(1).is_empty());
+    println!("{}", failure);
+
+    // what configure built, without synthesizing anything
+    let dump = dump_constraint_system::<Fr, MulAddCircuit>();
+    assert!(dump.contains("degree 3, 3 advice"), "{}", dump);
+    assert!(
+        dump.contains("gate \"mul add\"\n  0 \"a * b + 2 = c\" (degree 3): "),
+        "{}",
+        dump
+    );
+    for column in ["a0: +0", "a1: +0", "a2: +0"] {
+        assert!(dump.contains(column), "{}", dump);
+    }
+    let dot = constraint_system_dot::<Fr, MulAddCircuit>();
+    assert!(dot.contains("gate0 -> \"a2\";"), "{}", dot);
+    print!("{}", dump);
+}
@@ -0,0 +1,90 @@
+use std::{fs, path::Path};
+
+use halo2_playground::{
+    circuits::{mul::MulCircuit, poseidon::PoseidonHashCircuit},
+    entropy::SeededEntropy,
+    evm::{encode_calldata, estimate_gas, from_words, proof_points, solidity_vk},
+    poseidon::MySpec,
+    prover::{run_proof, MultiOpen, ProofOptions, TranscriptHash},
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk, Circuit},
+};
+
+const K: u32 = 7;
+const OUT_DIR: &str = "target/evm";
+
+fn generate<C: Circuit<Fr> + Clone>(name: &str, circuit: C, instances: &[&[Fr]]) {
+    let params = cached_params(K).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let dir = Path::new(OUT_DIR);
+    fs::create_dir_all(dir).unwrap();
+
+    let solidity = solidity_vk(&format!("{}VerifyingKey", name), pk.get_vk(), &params);
+    fs::write(dir.join(format!("{}VerifyingKey.sol", name)), solidity).unwrap();
+
+    for multiopen in [MultiOpen::Shplonk, MultiOpen::Gwc] {
+        let options = ProofOptions {
+            multiopen,
+            transcript: TranscriptHash::Keccak256,
+        };
+        let proof = run_proof(
+            &params,
+            &pk,
+            circuit.clone(),
+            instances,
+            SeededEntropy::new(0x5962_be5d_763d_318d),
+            options,
+        )
+        .expect("create_proof");
+        check_proof(&params, pk.get_vk(), instances, &proof, options)
+            .into_result()
+            .expect("verify_proof");
+        let points = proof_points(pk.get_vk(), multiopen);
+        assert!(points * 64 <= proof.len() && (proof.len() - points * 64) % 32 == 0);
+
+        let calldata = encode_calldata(instances, &proof);
+        let (words, rest) = calldata.split_at(calldata.len() - proof.len());
+        assert_eq!(from_words(words).unwrap(), instances.concat());
+        assert_eq!(rest, &proof[..]);
+        let suffix = format!("{:?}", multiopen).to_lowercase();
+        fs::write(dir.join(format!("{}-{}.calldata", name, suffix)), &calldata).unwrap();
+        let gas = estimate_gas(pk.get_vk(), multiopen, &calldata);
+        println!(
+            "{} ({:?}): calldata {} bytes, gas >= {} (calldata {}, ecMul {}, ecAdd {}, pairing {})",
+            name,
+            multiopen,
+            calldata.len(),
+            gas.total(),
+            gas.calldata,
+            gas.ec_mul,
+            gas.ec_add,
+            gas.pairing
+        );
+    }
+}
+
+fn main() {
+    let (a, b) = (Fr::from(3), Fr::from(5));
+    generate(
+        "Multiplication",
+        MulCircuit::new(a, b),
+        &[&MulCircuit::instance(a * b)],
+    );
+
+    let message = [Fr::from(1), Fr::from(2)];
+    let hash = PoseidonHashCircuit::<Fr, MySpec<3, 2>, 3, 2, 2>::hash(message);
+    generate(
+        "Hash",
+        PoseidonHashCircuit::<Fr, MySpec<3, 2>, 3, 2, 2>::new(message),
+        &[&[hash]],
+    );
+    println!(
+        "wrote the verifying key libraries and calldata to {}",
+        OUT_DIR
+    );
+}
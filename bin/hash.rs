@@ -1,203 +1,115 @@
-use ff::Field;
-use halo2_playground::GOD_PRIVATE_KEY;
+use halo2_playground::{
+    audit::fit_k,
+    circuits::poseidon::PoseidonHashCircuit,
+    dev::mock_if_requested,
+    entropy::{entropy_from_args, EntropySource, SeededEntropy},
+    error::PlaygroundError,
+    fmt::to_hex,
+    poseidon::{assert_secure, MySpec},
+    prover::{run_proof, MultiOpen, ProofOptions, TranscriptHash},
+    report::{report_if_requested, CostReport},
+    storage::cached_params,
+    sweep::{measure, SweepCosts},
+    verifier::check_proof,
+};
 use halo2_proofs::{
-    circuit::{Layouter, SimpleFloorPlanner, Value},
     halo2curves::{
-        bn256::{Bn256, Fr, G1Affine},
-        group::ff,
+        bn256::{Bn256, Fr},
         FieldExt,
     },
-    plonk::{
-        create_proof, keygen_pk, keygen_vk, verify_proof, Advice, Circuit, Column,
-        ConstraintSystem, Error, Instance,
-    },
+    plonk::{keygen_pk, keygen_vk},
     poly::{
         commitment::{Params, ParamsProver},
-        kzg::{
-            commitment::{KZGCommitmentScheme, ParamsKZG, ParamsVerifierKZG},
-            multiopen::{ProverSHPLONK, VerifierSHPLONK},
-            strategy::SingleStrategy,
-        },
+        kzg::commitment::ParamsVerifierKZG,
     },
-    transcript::{
-        Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer,
-    },
-};
-
-use halo2_gadgets::poseidon::{
-    primitives::{self as poseidon, ConstantLength, Spec},
-    Hash, Pow5Chip, Pow5Config,
 };
-use rand_xorshift::XorShiftRng;
-use std::convert::TryInto;
-use std::marker::PhantomData;
-
-use rand::{RngCore, SeedableRng};
-
-#[derive(Clone, Copy)]
-struct HashCircuit<S, const WIDTH: usize, const RATE: usize, const L: usize>
-where
-    S: Spec<Fr, WIDTH, RATE> + Clone + Copy,
-{
-    message: Value<[Fr; L]>,
-    _spec: PhantomData<S>,
-}
-
-#[derive(Debug, Clone)]
-struct MyConfig<const WIDTH: usize, const RATE: usize, const L: usize> {
-    input: [Column<Advice>; L],
-    expected: Column<Instance>,
-    poseidon_config: Pow5Config<Fr, WIDTH, RATE>,
-}
-
-impl<S, const WIDTH: usize, const RATE: usize, const L: usize> Circuit<Fr>
-    for HashCircuit<S, WIDTH, RATE, L>
-where
-    S: Spec<Fr, WIDTH, RATE> + Copy + Clone,
-{
-    type Config = MyConfig<WIDTH, RATE, L>;
-    type FloorPlanner = SimpleFloorPlanner;
-
-    fn without_witnesses(&self) -> Self {
-        Self {
-            message: Value::unknown(),
-            _spec: PhantomData,
-        }
-    }
-
-    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
-        let state = (0..WIDTH).map(|_| meta.advice_column()).collect::<Vec<_>>();
-        let expected = meta.instance_column();
-        meta.enable_equality(expected);
-        let partial_sbox = meta.advice_column();
-
-        let rc_a = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
-        let rc_b = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
-
-        meta.enable_constant(rc_b[0]);
-
-        Self::Config {
-            input: state[..RATE].try_into().unwrap(),
-            expected,
-            poseidon_config: Pow5Chip::configure::<S>(
-                meta,
-                state.try_into().unwrap(),
-                partial_sbox,
-                rc_a.try_into().unwrap(),
-                rc_b.try_into().unwrap(),
-            ),
-        }
-    }
 
-    fn synthesize(
-        &self,
-        config: Self::Config,
-        mut layouter: impl Layouter<Fr>,
-    ) -> Result<(), Error> {
-        let chip = Pow5Chip::construct(config.poseidon_config.clone());
+use halo2_gadgets::poseidon::primitives::Spec;
+use std::{convert::TryInto, time::Instant};
 
-        let message = layouter.assign_region(
-            || "load message",
-            |mut region| {
-                let message_word = |i: usize| {
-                    let value = self.message.map(|message_vals| message_vals[i]);
-                    region.assign_advice(
-                        || format!("load message_{}", i),
-                        config.input[i],
-                        0,
-                        || value,
-                    )
-                };
-
-                let message: Result<Vec<_>, Error> = (0..L).map(message_word).collect();
-                Ok(message?.try_into().unwrap())
-            },
-        )?;
-
-        let hasher = Hash::<_, _, S, ConstantLength<L>, WIDTH, RATE>::init(
-            chip,
-            layouter.namespace(|| "init"),
-        )?;
-        let output = hasher.hash(layouter.namespace(|| "hash"), message)?;
-
-        layouter.constrain_instance(output.cell(), config.expected, 0)
-    }
-}
-
-#[derive(Debug, Clone, Copy)]
-struct MySpec<const WIDTH: usize, const RATE: usize>;
-
-impl<const WIDTH: usize, const RATE: usize> Spec<Fr, WIDTH, RATE> for MySpec<WIDTH, RATE> {
-    fn full_rounds() -> usize {
-        8
-    }
-
-    fn partial_rounds() -> usize {
-        56
-    }
-
-    fn sbox(val: Fr) -> Fr {
-        val.pow_vartime(&[5])
-    }
-
-    fn secure_mds() -> usize {
-        0
-    }
-}
-
-const K: u32 = 7;
+use rand::RngCore;
 
 fn run_poseidon<S, const WIDTH: usize, const RATE: usize, const L: usize>()
 where
     S: Spec<Fr, WIDTH, RATE> + Copy + Clone,
 {
     println!("WIDTH = {}, RATE = {}, L = {}", WIDTH, RATE, L);
+    assert_secure::<S, WIDTH, RATE>();
 
-    let mut rng = XorShiftRng::from_seed([
-        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
-        0xe5,
-    ]);
+    let mut rng = SeededEntropy::new(0x5962_be5d_763d_318d);
     let message: [Fr; L] = (0..L)
         .map(|_| Fr::from_u128(rng.next_u32() as u8 as u128))
         .collect::<Vec<_>>()
         .try_into()
         .unwrap();
-    let output = poseidon::Hash::<_, S, ConstantLength<L>, WIDTH, RATE>::init().hash(message);
-
-    let circuit = HashCircuit::<S, WIDTH, RATE, L> {
-        message: Value::known(message),
-        _spec: PhantomData,
-    };
-
-    let s = Fr::from_u128(GOD_PRIVATE_KEY);
-    let general_params = ParamsKZG::<Bn256>::unsafe_setup_with_s(K, s);
+    let output = PoseidonHashCircuit::<Fr, S, WIDTH, RATE, L>::hash(message);
+    let circuit = PoseidonHashCircuit::<Fr, S, WIDTH, RATE, L>::new(message);
+
+    let k = fit_k(&circuit).expect("fit_k");
+    report_if_requested("poseidon", &circuit);
+    println!("k = {}", k);
+    #[cfg(feature = "dev-graph")]
+    {
+        let path = format!("poseidon-{}-{}-{}.svg", WIDTH, RATE, L);
+        let options = halo2_playground::dev::LayoutOptions {
+            title: Some(format!(
+                "Poseidon WIDTH = {}, RATE = {}, L = {}",
+                WIDTH, RATE, L
+            )),
+            ..Default::default()
+        };
+        halo2_playground::dev::render_layout(&circuit, k, std::path::Path::new(&path), &options)
+            .expect("render_layout");
+        println!("layout in {}", path);
+    }
+    mock_if_requested("poseidon", k, &circuit, vec![vec![output]]).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(err.exit_code());
+    });
+    let general_params = cached_params(k).expect("cached_params");
     let verifier_params: ParamsVerifierKZG<Bn256> = general_params.verifier_params().clone();
 
     let vk = keygen_vk(&general_params, &circuit).expect("keygen_vk");
     let pk = keygen_pk(&general_params, vk, &circuit).expect("keygen_pk");
-    let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
-    let rng = XorShiftRng::from_seed([
-        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
-        0xe5,
-    ]);
-
-    create_proof::<
-        KZGCommitmentScheme<Bn256>,
-        ProverSHPLONK<'_, Bn256>,
-        Challenge255<G1Affine>,
-        XorShiftRng,
-        Blake2bWrite<Vec<u8>, G1Affine, Challenge255<G1Affine>>,
-        HashCircuit<S, WIDTH, RATE, L>,
-    >(
-        &general_params,
-        &pk,
-        &[circuit],
-        &[&[&[output]]],
-        rng,
-        &mut transcript,
-    )
-    .expect("proof generation should not fail");
-    let proof = transcript.finalize();
+    let rng = entropy_from_args(0x5962_be5d_763d_318d).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(2);
+    });
+    println!("proving randomness: {}", rng.describe());
+    for multiopen in MultiOpen::ALL {
+        let options = ProofOptions {
+            multiopen,
+            ..ProofOptions::default()
+        };
+        let start = Instant::now();
+        let proof = run_proof(
+            &general_params,
+            &pk,
+            circuit,
+            &[&[output]],
+            rng.clone(),
+            options,
+        )
+        .expect("proof generation should not fail");
+        let prove_time = start.elapsed();
+        let start = Instant::now();
+        check_proof(&verifier_params, pk.get_vk(), &[&[output]], &proof, options)
+            .into_result()
+            .expect("verify_proof");
+        println!(
+            "{:?} proof length : {}, prove {:.2?}, verify {:.2?}",
+            multiopen,
+            proof.len(),
+            prove_time,
+            start.elapsed()
+        );
+    }
+    let keccak = ProofOptions {
+        transcript: TranscriptHash::Keccak256,
+        ..ProofOptions::default()
+    };
+    let evm_proof = run_proof(&general_params, &pk, circuit, &[&[output]], rng, keccak)
+        .expect("proof generation should not fail");
 
     //
     let mut verifier_params_buf = vec![];
@@ -206,28 +118,104 @@ where
         .expect("write");
     let mut vk_buf = vec![];
     pk.get_vk().write(&mut vk_buf).expect("write");
-    println!("proof length : {}", proof.len());
+    println!("hash : {}", to_hex(&output));
+    println!("Keccak256 proof length : {}", evm_proof.len());
     println!("verifier parameters length : {}", verifier_params_buf.len());
     println!("vk length: {}", vk_buf.len());
 
-    let strategy = SingleStrategy::new(&general_params);
-    let mut transcript = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(&proof[..]);
-    verify_proof::<
-        KZGCommitmentScheme<Bn256>,
-        VerifierSHPLONK<'_, Bn256>,
-        Challenge255<G1Affine>,
-        Blake2bRead<&[u8], G1Affine, Challenge255<G1Affine>>,
-        SingleStrategy<'_, Bn256>,
-    >(
+    check_proof(
         &verifier_params,
         pk.get_vk(),
-        strategy,
-        &[&[&[output]]],
-        &mut transcript,
+        &[&[output]],
+        &evm_proof,
+        keccak,
     )
+    .into_result()
     .expect("verify_proof");
 }
 
+struct SweepRow {
+    width: usize,
+    rate: usize,
+    length: usize,
+    report: CostReport,
+    costs: SweepCosts,
+}
+
+/// The costs of hashing `L` elements with [`MySpec`] at `WIDTH` and `RATE`, at the smallest
+/// `k` that fits.
+fn sweep_poseidon<const WIDTH: usize, const RATE: usize, const L: usize>(
+) -> Result<SweepRow, PlaygroundError> {
+    let message: [Fr; L] = (0..L)
+        .map(|i| Fr::from(i as u64 + 1))
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap();
+    let circuit = PoseidonHashCircuit::<Fr, MySpec<WIDTH, RATE>, WIDTH, RATE, L>::new(message);
+    let output = PoseidonHashCircuit::<Fr, MySpec<WIDTH, RATE>, WIDTH, RATE, L>::hash(message);
+    let report = CostReport::measure(&circuit)?;
+    let params = cached_params(report.k)?;
+    let costs = measure(
+        &params,
+        &circuit,
+        &[&[output]],
+        &[],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+    )?;
+    Ok(SweepRow {
+        width: WIDTH,
+        rate: RATE,
+        length: L,
+        report,
+        costs,
+    })
+}
+
+/// Compares widths 3, 9 and 12, each at the largest rate, over a few message lengths.
+fn sweep() {
+    let runs: [fn() -> Result<SweepRow, PlaygroundError>; 9] = [
+        sweep_poseidon::<3, 2, 2>,
+        sweep_poseidon::<3, 2, 8>,
+        sweep_poseidon::<3, 2, 32>,
+        sweep_poseidon::<9, 8, 2>,
+        sweep_poseidon::<9, 8, 8>,
+        sweep_poseidon::<9, 8, 32>,
+        sweep_poseidon::<12, 11, 2>,
+        sweep_poseidon::<12, 11, 8>,
+        sweep_poseidon::<12, 11, 32>,
+    ];
+    println!(
+        "{:>5} {:>4} {:>3} {:>3} {:>8} {:>6} {:>6} {:>11} {:>11}",
+        "width", "rate", "L", "k", "rows", "advice", "proof", "prove", "verify"
+    );
+    for run in runs {
+        let row = run().unwrap_or_else(|err| {
+            eprintln!("error: {}", err);
+            std::process::exit(err.exit_code());
+        });
+        let rows = row
+            .report
+            .rows_used
+            .map_or_else(|| "-".to_string(), |rows| rows.to_string());
+        println!(
+            "{:>5} {:>4} {:>3} {:>3} {:>8} {:>6} {:>6} {:>11.2?} {:>11.2?}",
+            row.width,
+            row.rate,
+            row.length,
+            row.report.k,
+            rows,
+            row.report.advice_columns,
+            row.costs.proof_bytes,
+            row.costs.prove,
+            row.costs.verify
+        );
+    }
+}
+
 fn main() {
+    if std::env::args().skip(1).any(|arg| arg == "--sweep") {
+        sweep();
+        return;
+    }
     run_poseidon::<MySpec<3, 2>, 3, 2, 2>();
 }
@@ -0,0 +1,137 @@
+use std::io::Cursor;
+
+use halo2_playground::{
+    barycentric::instance_evaluations,
+    circuits::mul::MulCircuit,
+    commit_instances,
+    instance_stream::{
+        commit_instances_streamed, instance_evaluations_streamed, InstanceReader, LagrangeBases,
+        StreamError,
+    },
+    storage::cached_params,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    halo2curves::{
+        bn256::{Bn256, Fr},
+        group::ff::PrimeField,
+    },
+    plonk::keygen_vk,
+    poly::{
+        commitment::Params,
+        kzg::{commitment::KZGCommitmentScheme, multiopen::VerifierSHPLONK},
+    },
+};
+
+const K: u32 = 8;
+
+fn words(values: &[Fr]) -> Vec<u8> {
+    values.iter().flat_map(|v| v.to_repr()).collect()
+}
+
+fn main() {
+    let params = cached_params(K).expect("cached_params");
+    let mut params_bytes = vec![];
+    params.write(&mut params_bytes).expect("write params");
+    let vk = keygen_vk(&params, &MulCircuit::<Fr>::default()).expect("keygen_vk");
+    let max_rows = (1 << K) - (vk.cs.blinding_factors() + 1);
+
+    let values: Vec<Fr> = [Fr::zero(), Fr::one(), -Fr::one()]
+        .into_iter()
+        .chain((3..200u64).map(|i| Fr::from(i * i + 7)))
+        .collect();
+    let bytes = words(&values);
+    let reference = commit_instances::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<'_, Bn256>>(
+        &params,
+        &vk,
+        &[&[&values]],
+    )
+    .expect("commit_instances")[0][0];
+    let mut bases = LagrangeBases::new(Cursor::new(&params_bytes)).expect("params header");
+    for chunk in [1, 7, 64, max_rows] {
+        let streamed = commit_instances_streamed(
+            &mut bases,
+            &vk,
+            vec![InstanceReader::new(&bytes[..])],
+            chunk,
+        )
+        .expect("commit_instances_streamed");
+        assert_eq!(streamed, vec![reference], "chunks of {}", chunk);
+    }
+
+    // off the domain, and on it at a row with a value and one without
+    let omega = vk.domain.get_omega();
+    for x in [
+        Fr::from(0x5eed),
+        omega.pow_vartime(&[5]),
+        omega.pow_vartime(&[250]),
+    ] {
+        let streamed = instance_evaluations_streamed(&vk, vec![InstanceReader::new(&bytes[..])], x)
+            .expect("instance_evaluations_streamed");
+        assert_eq!(
+            streamed,
+            instance_evaluations(&vk, &[&values], x).expect("instance_evaluations")
+        );
+    }
+
+    // a column filling every usable row, and one row more
+    let full = vec![Fr::one(); max_rows];
+    assert!(commit_instances_streamed(
+        &mut bases,
+        &vk,
+        vec![InstanceReader::new(&words(&full)[..])],
+        16
+    )
+    .is_ok());
+    let long = words(&vec![Fr::one(); max_rows + 1]);
+    assert!(matches!(
+        commit_instances_streamed(&mut bases, &vk, vec![InstanceReader::new(&long[..])], 16),
+        Err(StreamError::TooLong { column: 0, .. })
+    ));
+    assert!(matches!(
+        instance_evaluations_streamed(&vk, vec![InstanceReader::new(&long[..])], Fr::one()),
+        Err(StreamError::TooLong { column: 0, .. })
+    ));
+
+    // words that aren't whole or canonical, and shapes that don't match the key
+    assert!(matches!(
+        commit_instances_streamed(&mut bases, &vk, vec![InstanceReader::new(&bytes[..40])], 16),
+        Err(StreamError::Encoding { row: 1, .. })
+    ));
+    let mut non_canonical = bytes.clone();
+    non_canonical[32..64].copy_from_slice(&[0xff; 32]);
+    assert!(matches!(
+        commit_instances_streamed(
+            &mut bases,
+            &vk,
+            vec![InstanceReader::new(&non_canonical[..])],
+            16
+        ),
+        Err(StreamError::Encoding { row: 1, .. })
+    ));
+    assert!(matches!(
+        commit_instances_streamed::<_, InstanceReader<&[u8]>>(&mut bases, &vk, vec![], 16),
+        Err(StreamError::Columns {
+            expected: 1,
+            found: 0
+        })
+    ));
+    let mut larger = vec![];
+    cached_params(K + 1)
+        .expect("cached_params")
+        .write(&mut larger)
+        .expect("write params");
+    let mut larger = LagrangeBases::new(Cursor::new(&larger)).expect("params header");
+    assert!(matches!(
+        commit_instances_streamed(&mut larger, &vk, vec![InstanceReader::new(&bytes[..])], 16),
+        Err(StreamError::KeyMismatch {
+            params_k: 9,
+            vk_k: 8
+        })
+    ));
+
+    println!(
+        "streamed commitments and evaluations of {} rows match",
+        values.len()
+    );
+}
@@ -0,0 +1,141 @@
+use std::time::{Duration, Instant};
+
+use halo2_playground::{
+    audit::fit_k,
+    circuits::{
+        keccak::{keccak256, KeccakCircuit},
+        poseidon::PoseidonHashCircuit,
+        sha256::{sha256, Sha256Circuit},
+    },
+    entropy::SeededEntropy,
+    poseidon::MySpec,
+    prover::{run_proof, ProofOptions},
+    report::{report_if_requested, CostReport},
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk, Circuit},
+};
+use sha3::{Digest, Keccak256};
+
+type Poseidon = PoseidonHashCircuit<Fr, MySpec<3, 2>, 3, 2, 2>;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Proves and verifies once, checking that a changed instance is rejected.
+fn prove<C: Circuit<Fr> + Clone>(circuit: &C, instance: &[Fr]) -> Duration {
+    let params = cached_params(fit_k(circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, circuit).expect("keygen_pk");
+    let start = Instant::now();
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit.clone(),
+        &[instance],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    let prove = start.elapsed();
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[instance],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+    let mut tampered = instance.to_vec();
+    tampered[0] += Fr::one();
+    assert!(check_proof(
+        &params,
+        pk.get_vk(),
+        &[&tampered],
+        &proof,
+        ProofOptions::default()
+    )
+    .into_result()
+    .is_err());
+    prove
+}
+
+fn main() {
+    report_if_requested("keccak", &KeccakCircuit::<32>::default());
+
+    assert_eq!(
+        hex(&keccak256(b"")),
+        "c5d2460186f7233c927e7db2dcc703c0e500b653ca82273b7bfad8045d85a470"
+    );
+    // the off-circuit reference against the sha3 crate, across the block boundary
+    for len in 0..300 {
+        let message: Vec<u8> = (0..len).map(|i| (i * 7) as u8).collect();
+        assert_eq!(
+            keccak256(&message)[..],
+            Keccak256::digest(&message)[..],
+            "{} bytes",
+            len
+        );
+    }
+
+    // 136 bytes fill the rate, so the padding takes a second block
+    let preimage = [0xa5; 136];
+    let digest = keccak256(&preimage);
+    let circuit = KeccakCircuit::new(preimage);
+    let instance = KeccakCircuit::<136>::instance::<Fr>(&digest);
+    let k = fit_k::<Fr, _>(&circuit).expect("fit_k");
+    let prover = MockProver::run(k, &circuit, vec![instance.clone()]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+    let mut other = preimage;
+    other[135] ^= 1;
+    let prover = MockProver::run(k, &KeccakCircuit::new(other), vec![instance]).unwrap();
+    assert!(prover.verify().is_err());
+    println!("keccak256 of 136 bytes, 2 blocks: k = {}", k);
+
+    let preimage = [0x5a; 32];
+    let digest = keccak256(&preimage);
+    let keccak = KeccakCircuit::new(preimage);
+    let keccak_instance = KeccakCircuit::<32>::instance(&digest);
+    println!("keccak256 of 32 bytes = {}", hex(&digest));
+    let sha = Sha256Circuit::new(preimage);
+    let sha_instance = Sha256Circuit::<32>::instance(&sha256(&preimage));
+    let message = [Fr::from(1), Fr::from(2)];
+    let poseidon = Poseidon::new(message);
+
+    for (name, report, time) in [
+        (
+            "keccak256",
+            CostReport::measure(&keccak).expect("measure"),
+            prove(&keccak, &keccak_instance),
+        ),
+        (
+            "sha256",
+            CostReport::measure(&sha).expect("measure"),
+            prove(&sha, &sha_instance),
+        ),
+        (
+            "poseidon",
+            CostReport::measure(&poseidon).expect("measure"),
+            prove(&poseidon, &[Poseidon::hash(message)]),
+        ),
+    ] {
+        println!(
+            "  {:<10} k = {:>2}, {:>6} rows, {:>2} advice columns, proved in {:.2?}",
+            name,
+            report.k,
+            report
+                .rows_used
+                .map(|rows| rows.to_string())
+                .unwrap_or_default(),
+            report.advice_columns,
+            time
+        );
+    }
+}
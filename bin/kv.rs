@@ -0,0 +1,146 @@
+use halo2_playground::{
+    audit::fit_k,
+    circuits::kv::KvCircuit,
+    entropy::SeededEntropy,
+    merkle::KvTree,
+    prover::{run_proof, ProofOptions},
+    report::{report_if_requested, CostReport},
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+// 256 keys in each shape
+type Kv = KvCircuit<5, 4, 4>;
+type Binary = KvCircuit<3, 2, 8>;
+type Wide = KvCircuit<17, 16, 2>;
+
+fn run(circuit: &Kv, instance: Vec<Fr>) -> bool {
+    let k = fit_k::<Fr, _>(circuit).expect("fit_k");
+    MockProver::run(k, circuit, vec![instance])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+fn main() {
+    report_if_requested("kv", &Kv::default());
+    let mut tree = KvTree::<5, 4>::new(4);
+    let empty_root = tree.root();
+    for (key, value) in [(3, 30), (17, 170), (200, 2000), (255, 2550)] {
+        tree.insert(key, Fr::from(value));
+    }
+    for key in 0..tree.capacity() {
+        assert_eq!(
+            tree.path(key).root::<5, 4>(tree.get(key)),
+            tree.root(),
+            "key {}",
+            key
+        );
+    }
+    for key in [3, 17, 200, 255] {
+        tree.insert(key, Fr::zero());
+    }
+    assert_eq!(tree.root(), empty_root, "removing every key");
+    for (key, value) in [(3, 30), (17, 170), (200, 2000), (255, 2550)] {
+        tree.insert(key, Fr::from(value));
+    }
+
+    // a read of 17 writes its value back
+    let (key, value) = (17, Fr::from(170));
+    let root = tree.root();
+    let read = Kv::new(tree.path(key));
+    assert!(run(&read, Kv::instance(key, value, value, root, root)));
+    // another value, another key sharing the path's digits but one, or another root
+    assert!(!run(
+        &read,
+        Kv::instance(key, value + Fr::one(), value + Fr::one(), root, root)
+    ));
+    assert!(!run(&read, Kv::instance(key ^ 1, value, value, root, root)));
+    assert!(!run(
+        &read,
+        Kv::instance(key + 64, value, value, root, root)
+    ));
+    assert!(!run(
+        &read,
+        Kv::instance(key, value, value, root + Fr::one(), root)
+    ));
+    // 42 is absent
+    let absent = Kv::new(tree.path(42));
+    assert!(run(
+        &absent,
+        Kv::instance(42, Fr::zero(), Fr::zero(), root, root)
+    ));
+
+    // writing 42 moves the root, with the same siblings
+    let path = tree.path(42);
+    tree.insert(42, Fr::from(420));
+    let new_root = tree.root();
+    assert_eq!(path.root::<5, 4>(Fr::from(420)), new_root);
+    let instance = Kv::instance(42, Fr::zero(), Fr::from(420), root, new_root);
+    assert!(run(&absent, instance.clone()));
+    // a new root that also changes another key
+    let mut other = tree.clone();
+    other.insert(43, Fr::one());
+    assert!(!run(
+        &absent,
+        Kv::instance(42, Fr::zero(), Fr::from(420), root, other.root())
+    ));
+    // once 43, a sibling of 42, changes, the old siblings no longer open the root
+    let read = Kv::new(path.clone());
+    assert!(run(
+        &read,
+        Kv::instance(42, Fr::from(420), Fr::from(420), new_root, new_root)
+    ));
+    assert!(!run(
+        &read,
+        Kv::instance(42, Fr::from(420), Fr::from(420), other.root(), other.root())
+    ));
+
+    let params = cached_params(fit_k(&absent).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &Kv::default()).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &Kv::default()).expect("keygen_pk");
+    let proof = run_proof(
+        &params,
+        &pk,
+        absent,
+        &[&instance],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[&instance],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+
+    println!("an update in a tree of 256 keys:");
+    for (arity, depth, report) in [
+        (2, 8, CostReport::measure(&Binary::default())),
+        (4, 4, CostReport::measure(&Kv::default())),
+        (16, 2, CostReport::measure(&Wide::default())),
+    ] {
+        let report = report.expect("measure");
+        println!(
+            "  arity {:>2}, depth {}: k = {:>2}, {:>5} rows, {:>2} advice columns",
+            arity,
+            depth,
+            report.k,
+            report
+                .rows_used
+                .map(|rows| rows.to_string())
+                .unwrap_or_default(),
+            report.advice_columns,
+        );
+    }
+}
@@ -0,0 +1,16 @@
+use halo2_playground::layout::compare;
+
+fn main() {
+    let comparison = compare(64).expect("compare layouts");
+    let (per_op, single) = (
+        &comparison.per_operation.report,
+        &comparison.single_region.report,
+    );
+    // the copies between regions are what need the equality columns
+    assert!(single.permutation_columns < per_op.permutation_columns);
+    assert!(
+        single.advice_columns + single.fixed_columns < per_op.advice_columns + per_op.fixed_columns
+    );
+    assert!(single.rows_used.unwrap() <= per_op.rows_used.unwrap());
+    println!("{}", comparison);
+}
@@ -0,0 +1,31 @@
+use halo2_playground::{
+    audit::fit_k, circuits::mul::MulCircuit, leakage::LeakageReport, storage::cached_params,
+};
+use halo2_proofs::{
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+fn main() {
+    let circuit = MulCircuit::<Fr>::default();
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+
+    // 3 * 5 and 1 * 15 prove the same product
+    let instance = MulCircuit::instance(Fr::from(15));
+    let witnesses = [
+        MulCircuit::new(Fr::from(3), Fr::from(5)),
+        MulCircuit::new(Fr::from(1), Fr::from(15)),
+    ];
+    let report = LeakageReport::measure(
+        &params,
+        &pk,
+        [&witnesses[0], &witnesses[1]],
+        &[instance.as_slice()],
+        0x5962_be5d_763d_318d,
+    )
+    .expect("measure leakage");
+    println!("mul, 3 * 5 against 1 * 15:\n{}", report);
+    assert!(report.passes(), "the proofs tell the witnesses apart");
+}
@@ -0,0 +1,177 @@
+use halo2_playground::{
+    bundle::{EnvelopeError, Prover, Verifier},
+    circuits::mul::MulCircuit,
+    entropy::SeededEntropy,
+    limits::{admit, estimate_prover_memory, LimitError, MemoryBudget, ResourceLimits},
+    storage::cached_params,
+    verifier::{check_proof, VerificationReport},
+};
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr},
+    plonk::{keygen_pk, keygen_vk},
+    poly::kzg::commitment::ParamsKZG,
+};
+
+const K: u32 = 4;
+
+fn main() {
+    let general_params = cached_params(K).expect("cached_params");
+    let vk = keygen_vk(&general_params, &MulCircuit::<Fr>::default()).expect("keygen_vk");
+    let instance = MulCircuit::instance(Fr::from(15));
+    let needed = estimate_prover_memory(&vk);
+    println!("estimated prover memory at k = {}: {} bytes", K, needed);
+
+    let limits = ResourceLimits::default();
+    let budget = MemoryBudget::new(2 * needed);
+    let first = admit(&limits, &budget, &vk, &[&instance]).expect("first request");
+    let second = admit(&limits, &budget, &vk, &[&instance]).expect("second request");
+    assert!(matches!(
+        admit(&limits, &budget, &vk, &[&instance]),
+        Err(LimitError::Memory { .. })
+    ));
+    drop(first);
+    let third = admit(&limits, &budget, &vk, &[&instance]).expect("third request");
+    drop((second, third));
+    assert_eq!(budget.available(), 2 * needed);
+
+    let small = ResourceLimits {
+        max_k: K - 1,
+        ..limits
+    };
+    assert_eq!(
+        admit(&small, &budget, &vk, &[&instance]).unwrap_err(),
+        LimitError::K { k: K, max: K - 1 }
+    );
+    let small = ResourceLimits {
+        max_instance_len: 1,
+        ..limits
+    };
+    assert!(matches!(
+        admit(&small, &budget, &vk, &[&instance]),
+        Err(LimitError::InstanceLength {
+            column: 0,
+            len: 2,
+            ..
+        })
+    ));
+
+    let path =
+        std::env::temp_dir().join(format!("halo2-playground-witness-{}", std::process::id()));
+    std::fs::write(&path, [0u8; 100]).unwrap();
+    let small = ResourceLimits {
+        max_witness_bytes: 99,
+        ..limits
+    };
+    assert!(limits.check_witness_file(&path).is_ok());
+    assert!(matches!(
+        small.check_witness_file(&path),
+        Err(LimitError::WitnessSize {
+            bytes: 100,
+            max: 99
+        })
+    ));
+    std::fs::remove_file(&path).unwrap();
+
+    // the envelope's declared sizes are checked before anything past the header is read
+    let circuit = MulCircuit::new(Fr::from(3), Fr::from(5));
+    let pk = keygen_pk(&general_params, vk.clone(), &circuit).expect("keygen_pk");
+    let bundle = Prover::new(&general_params, &pk)
+        .prove(
+            circuit,
+            vec![instance.clone()],
+            SeededEntropy::new(0x5962_be5d_763d_318d),
+        )
+        .expect("prove");
+    let bytes = bundle.to_bytes(&vk);
+    let verifier = Verifier::new(&general_params, &vk);
+    assert_eq!(verifier.verify_bytes(&bytes, &limits).unwrap(), bundle);
+    let small = ResourceLimits {
+        max_k: K - 1,
+        ..limits
+    };
+    assert!(matches!(
+        verifier.verify_bytes(&bytes, &small),
+        Err(EnvelopeError::Limit(LimitError::K { .. }))
+    ));
+    let small = ResourceLimits {
+        max_proof_bytes: bundle.proof.len() - 1,
+        ..limits
+    };
+    assert!(matches!(
+        verifier.verify_bytes(&bytes, &small),
+        Err(EnvelopeError::Limit(LimitError::ProofSize { .. }))
+    ));
+    // a header claiming a huge column is refused without allocating it
+    let mut forged = bytes.clone();
+    forged[15..19].copy_from_slice(&u32::MAX.to_le_bytes());
+    assert!(matches!(
+        verifier.verify_bytes(&forged, &limits),
+        Err(EnvelopeError::Limit(LimitError::InstanceLength {
+            column: 0,
+            ..
+        }))
+    ));
+    assert!(matches!(
+        verifier.verify_bytes(&bytes[..bytes.len() - 1], &limits),
+        Err(EnvelopeError::Format(_))
+    ));
+    let mut declared = bytes.clone();
+    declared[7..11].copy_from_slice(&(K + 1).to_le_bytes());
+    assert!(matches!(
+        verifier.verify_bytes(&declared, &limits),
+        Err(EnvelopeError::K { .. })
+    ));
+    let mut wrong = bytes;
+    *wrong.last_mut().unwrap() ^= 1;
+    assert!(matches!(
+        verifier.verify_bytes(&wrong, &limits),
+        Err(EnvelopeError::Verify(_))
+    ));
+    // a bundle made for another key is refused by its digest, past the one column's length
+    // and the proof's
+    let mut other_key = bytes.clone();
+    other_key[23] ^= 1;
+    assert!(matches!(
+        verifier.verify_bytes(&other_key, &limits),
+        Err(EnvelopeError::Verify(
+            VerificationReport::DigestMismatch { .. }
+        ))
+    ));
+
+    // a failed verification says what was wrong
+    let check = |params: &ParamsKZG<Bn256>, instances: &[&[Fr]], proof: &[u8]| {
+        check_proof(params, &vk, instances, proof, bundle.options)
+    };
+    let columns = bundle.columns();
+    assert!(check(&general_params, &columns, &bundle.proof).is_verified());
+    assert!(matches!(
+        check(
+            &general_params,
+            &columns,
+            &bundle.proof[..bundle.proof.len() / 2]
+        ),
+        VerificationReport::MalformedProof(_)
+    ));
+    assert!(matches!(
+        check(&general_params, &[], &bundle.proof),
+        VerificationReport::InstanceShape {
+            expected_columns: 1,
+            ..
+        }
+    ));
+    let larger = cached_params(K + 1).expect("cached_params");
+    assert!(matches!(
+        check(&larger, &columns, &bundle.proof),
+        VerificationReport::KeyMismatch {
+            params_k,
+            vk_k: K
+        } if params_k == K + 1
+    ));
+    let other = MulCircuit::instance(Fr::from(16));
+    assert!(matches!(
+        check(&general_params, &[&other], &bundle.proof),
+        VerificationReport::Rejected(_)
+    ));
+
+    println!("resource limit checks passed");
+}
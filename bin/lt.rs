@@ -0,0 +1,164 @@
+use halo2_playground::{
+    audit::fit_k,
+    chips::{
+        lt::{LtChip, LtConfig},
+        range_check::{RangeCheckChip, RangeCheckConfig},
+        tables::Tables,
+    },
+    entropy::SeededEntropy,
+    prover::{run_proof, ProofOptions},
+    report::report_if_requested,
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk, Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+const N_BYTES: usize = 8;
+
+#[derive(Clone, Debug)]
+struct Config {
+    tables: Tables,
+    lt: LtConfig<N_BYTES>,
+    range: RangeCheckConfig,
+    advice: Column<Advice>,
+    instance: Column<Instance>,
+}
+
+/// Proves that a private 64-bit bid is below the public limit in instance row 0. Both are
+/// range checked first, since the comparison only holds for operands of `N_BYTES` bytes.
+#[derive(Clone, Default)]
+struct BelowLimitCircuit {
+    bid: Value<Fr>,
+}
+
+impl Circuit<Fr> for BelowLimitCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        let constants = meta.fixed_column();
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+        meta.enable_constant(constants);
+        // the comparison and the range checks look up the same byte table
+        let mut tables = Tables::new();
+        let lt = LtChip::<Fr, N_BYTES>::configure(meta, &mut tables);
+        let range = RangeCheckChip::<Fr, 8>::configure(meta, &mut tables);
+        Config {
+            tables,
+            lt,
+            range,
+            advice,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let lt = LtChip::<Fr, N_BYTES>::construct(config.lt);
+        let range = RangeCheckChip::<Fr, 8>::construct(config.range);
+        config.tables.load(&mut layouter)?;
+        let (bid, limit) = layouter.assign_region(
+            || "load operands",
+            |mut region| {
+                let bid = region.assign_advice(|| "bid", config.advice, 0, || self.bid)?;
+                let limit = region.assign_advice_from_instance(
+                    || "limit",
+                    config.instance,
+                    0,
+                    config.advice,
+                    1,
+                )?;
+                Ok((bid, limit))
+            },
+        )?;
+        for (name, cell) in [("bid", &bid), ("limit", &limit)] {
+            range.range_check(layouter.namespace(|| name), cell, 8 * N_BYTES)?;
+        }
+        let below = lt.lt(layouter.namespace(|| "bid < limit"), &bid, &limit)?;
+        layouter.assign_region(
+            || "assert below",
+            |mut region| region.constrain_constant(below.cell(), Fr::one()),
+        )
+    }
+}
+
+fn circuit(bid: Fr) -> BelowLimitCircuit {
+    BelowLimitCircuit {
+        bid: Value::known(bid),
+    }
+}
+
+fn run(bid: Fr, limit: Fr) -> bool {
+    let circuit = circuit(bid);
+    let k = fit_k::<Fr, _>(&circuit).expect("fit_k");
+    MockProver::run(k, &circuit, vec![vec![limit]])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+fn main() {
+    let limit = Fr::from(1000);
+    report_if_requested("lt", &circuit(Fr::from(999)));
+
+    assert!(run(Fr::from(999), limit));
+    assert!(run(Fr::zero(), Fr::one()));
+    assert!(!run(Fr::from(1000), limit));
+    assert!(!run(Fr::from(1001), limit));
+    assert!(!run(Fr::zero(), Fr::zero()));
+    // the extremes of the width
+    let max = Fr::from(u64::MAX);
+    assert!(run(max - Fr::one(), max));
+    assert!(!run(max, max));
+    // a "negative" bid is far below in the field's order of integers, but not 64 bits wide
+    assert!(!run(-Fr::one(), limit));
+
+    let bid = circuit(Fr::from(999));
+    let params = cached_params(fit_k(&bid).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &BelowLimitCircuit::default()).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &BelowLimitCircuit::default()).expect("keygen_pk");
+    let proof = run_proof(
+        &params,
+        &pk,
+        bid,
+        &[&[limit]],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[&[limit]],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+    assert!(check_proof(
+        &params,
+        pk.get_vk(),
+        &[&[Fr::from(999)]],
+        &proof,
+        ProofOptions::default()
+    )
+    .into_result()
+    .is_err());
+    println!("bid below the limit proven, {}-byte proof", proof.len());
+}
@@ -0,0 +1,99 @@
+use halo2_playground::{
+    audit::fit_k,
+    circuits::merkle::MerklePathCircuit,
+    entropy::SeededEntropy,
+    merkle::MerkleTree,
+    prover::{run_proof, ProofOptions},
+    report::report_if_requested,
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+const DEPTH: usize = 4;
+type MerkleCircuit = MerklePathCircuit<DEPTH>;
+
+fn run(circuit: &MerkleCircuit, instance: Vec<Fr>) -> bool {
+    let k = fit_k::<Fr, _>(circuit).expect("fit_k");
+    MockProver::run(k, circuit, vec![instance])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+fn main() {
+    report_if_requested("merkle", &MerkleCircuit::default());
+    let leaves: Vec<Fr> = (0..11).map(|i| Fr::from(100 + i)).collect();
+    let tree = MerkleTree::new(&leaves, DEPTH);
+    let root = tree.root();
+    for index in 0..1 << DEPTH {
+        assert_eq!(
+            tree.path(index).root(tree.leaf(index)),
+            root,
+            "leaf {}",
+            index
+        );
+    }
+
+    for index in [0, 6, 10] {
+        let (leaf, path) = (tree.leaf(index), tree.path(index));
+        let circuit = MerkleCircuit::new(path.clone());
+        assert!(
+            run(&circuit, MerkleCircuit::instance(root, leaf)),
+            "leaf {}",
+            index
+        );
+
+        // another root, another leaf, or the path taken the wrong way at any level
+        assert!(!run(
+            &circuit,
+            MerkleCircuit::instance(root + Fr::one(), leaf)
+        ));
+        assert!(!run(
+            &circuit,
+            MerkleCircuit::instance(root, leaf + Fr::one())
+        ));
+        for level in 0..DEPTH {
+            let mut flipped = path.clone();
+            flipped.directions[level] = !flipped.directions[level];
+            let circuit = MerkleCircuit::new(flipped);
+            assert!(
+                !run(&circuit, MerkleCircuit::instance(root, leaf)),
+                "leaf {} level {}",
+                index,
+                level
+            );
+        }
+    }
+
+    let index = 9;
+    let instance = MerkleCircuit::instance(root, tree.leaf(index));
+    let circuit = MerkleCircuit::new(tree.path(index));
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &MerkleCircuit::default()).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &MerkleCircuit::default()).expect("keygen_pk");
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &[&instance],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[&instance],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+    println!("depth {} inclusion proof length : {}", DEPTH, proof.len());
+}
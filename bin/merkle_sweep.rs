@@ -0,0 +1,90 @@
+//! Rows, proof size and timings of the Merkle inclusion circuit at depths 8, 16, 24 and 32,
+//! each at the smallest `k` that fits, for sizing tree-based applications. The paths come from
+//! sparse trees, so even depth 32 only stores the nodes above the one leaf. Run with
+//! `make merkle-sweep`.
+
+use halo2_playground::{
+    circuits::merkle::MerklePathCircuit,
+    entropy::SeededEntropy,
+    error::PlaygroundError,
+    merkle::SparseMerkleTree,
+    report::CostReport,
+    storage::cached_params,
+    sweep::{measure, SweepCosts},
+};
+use halo2_proofs::halo2curves::bn256::Fr;
+
+const SEED: u64 = 0x5962_be5d_763d_318d;
+
+struct SweepRow {
+    depth: usize,
+    report: CostReport,
+    costs: SweepCosts,
+}
+
+/// The costs of proving one leaf's inclusion in a depth-`DEPTH` tree.
+fn sweep_depth<const DEPTH: usize>() -> Result<SweepRow, PlaygroundError> {
+    let mut tree = SparseMerkleTree::new(DEPTH);
+    // a key whose path alternates directions, with a sibling set beside the leaf
+    let key: u64 = 0x5555_5555 & ((1 << DEPTH) - 1);
+    let leaf = Fr::from(100);
+    tree.insert(key, leaf);
+    tree.insert(key ^ 1, Fr::from(101));
+    let path = tree.path(key);
+    assert_eq!(path.root(leaf), tree.root());
+
+    let circuit = MerklePathCircuit::<DEPTH>::new(path);
+    let instance = MerklePathCircuit::<DEPTH>::instance(tree.root(), leaf);
+    let report = CostReport::measure(&circuit)?;
+    let params = cached_params(report.k)?;
+    let costs = measure(
+        &params,
+        &circuit,
+        &[&instance],
+        &[],
+        SeededEntropy::new(SEED),
+    )?;
+    Ok(SweepRow {
+        depth: DEPTH,
+        report,
+        costs,
+    })
+}
+
+fn main() {
+    let runs: [fn() -> Result<SweepRow, PlaygroundError>; 4] = [
+        sweep_depth::<8>,
+        sweep_depth::<16>,
+        sweep_depth::<24>,
+        sweep_depth::<32>,
+    ];
+    println!(
+        "{:>5} {:>3} {:>8} {:>6} {:>11} {:>11}",
+        "depth", "k", "rows", "proof", "prove", "verify"
+    );
+    let mut previous: Option<SweepRow> = None;
+    for run in runs {
+        let row = run().unwrap_or_else(|err| {
+            eprintln!("error: {}", err);
+            std::process::exit(err.exit_code());
+        });
+        let rows = row
+            .report
+            .rows_used
+            .map_or_else(|| "-".to_string(), |rows| rows.to_string());
+        println!(
+            "{:>5} {:>3} {:>8} {:>6} {:>11.2?} {:>11.2?}",
+            row.depth, row.report.k, rows, row.costs.proof_bytes, row.costs.prove, row.costs.verify
+        );
+        // a deeper path takes more rows, and never a smaller k
+        if let Some(previous) = &previous {
+            assert!(row.report.k >= previous.report.k, "depth {}", row.depth);
+            assert!(
+                row.report.rows_used > previous.report.rows_used,
+                "depth {}",
+                row.depth
+            );
+        }
+        previous = Some(row);
+    }
+}
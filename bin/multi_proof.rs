@@ -0,0 +1,109 @@
+//! Proves M multiplications `a_i * b_i = c_i` in one proof under one key, each with its own
+//! public input, and compares its size and verification time with M separate proofs.
+
+use std::time::{Duration, Instant};
+
+use halo2_playground::{
+    audit::fit_k,
+    circuits::mul::MulCircuit,
+    entropy::SeededEntropy,
+    prover::{run_multi_proof, run_proof, ProofOptions},
+    storage::cached_params,
+    verifier::{check_multi_proof, check_proof},
+};
+use halo2_proofs::{
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+const SEED: u64 = 0x5962_be5d_763d_318d;
+
+fn main() {
+    let template = MulCircuit::<Fr>::default();
+    let k = fit_k(&template).expect("fit_k");
+    let params = cached_params(k).expect("cached_params");
+    let vk = keygen_vk(&params, &template).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &template).expect("keygen_pk");
+    let options = ProofOptions::default();
+
+    println!(
+        "{:>3} {:>12} {:>12} {:>14} {:>14}",
+        "M", "one proof", "M proofs", "verify one", "verify M"
+    );
+    for m in [1, 2, 4, 8] {
+        let factors: Vec<(Fr, Fr)> = (0..m)
+            .map(|i| (Fr::from(i as u64 + 2), Fr::from(i as u64 + 3)))
+            .collect();
+        let circuits: Vec<_> = factors
+            .iter()
+            .map(|&(a, b)| MulCircuit::new(a, b))
+            .collect();
+        let publics: Vec<Vec<Fr>> = factors
+            .iter()
+            .map(|&(a, b)| MulCircuit::instance(a * b))
+            .collect();
+        let columns: Vec<[&[Fr]; 1]> = publics.iter().map(|public| [&public[..]]).collect();
+        let instances: Vec<&[&[Fr]]> = columns.iter().map(|column| &column[..]).collect();
+
+        let proof = run_multi_proof(
+            &params,
+            &pk,
+            &circuits,
+            &instances,
+            SeededEntropy::new(SEED),
+            options,
+        )
+        .expect("run_multi_proof");
+        let start = Instant::now();
+        check_multi_proof(&params, pk.get_vk(), &instances, &proof, options)
+            .into_result()
+            .expect("check_multi_proof");
+        let verify_one = start.elapsed();
+
+        // the instances have to come in the order the circuits were proven
+        if m > 1 {
+            let mut swapped = instances.clone();
+            swapped.swap(0, 1);
+            assert!(
+                !check_multi_proof(&params, pk.get_vk(), &swapped, &proof, options).is_verified()
+            );
+            assert!(
+                !check_multi_proof(&params, pk.get_vk(), &instances[1..], &proof, options)
+                    .is_verified()
+            );
+        }
+
+        let mut separate_bytes = 0;
+        let mut verify_separate = Duration::ZERO;
+        for (circuit, columns) in circuits.into_iter().zip(instances.iter()) {
+            let proof = run_proof(
+                &params,
+                &pk,
+                circuit,
+                columns,
+                SeededEntropy::new(SEED),
+                options,
+            )
+            .expect("run_proof");
+            separate_bytes += proof.len();
+            let start = Instant::now();
+            check_proof(&params, pk.get_vk(), columns, &proof, options)
+                .into_result()
+                .expect("check_proof");
+            verify_separate += start.elapsed();
+        }
+        if m == 1 {
+            assert_eq!(proof.len(), separate_bytes);
+        } else {
+            assert!(proof.len() < separate_bytes);
+        }
+        println!(
+            "{:>3} {:>12} {:>12} {:>14.2?} {:>14.2?}",
+            m,
+            proof.len(),
+            separate_bytes,
+            verify_one,
+            verify_separate
+        );
+    }
+}
@@ -1,179 +1,337 @@
-use halo2_playground::GOD_PRIVATE_KEY;
+#[cfg(feature = "dev-graph")]
+use std::path::Path;
+use std::time::Instant;
+
+use halo2_playground::{
+    audit::fit_k,
+    batch::{Batch, BatchError},
+    chips::standard_plonk::{StandardPlonkChip, StandardPlonkConfig},
+    circuits::mul::MulCircuit,
+    dev::mock_if_requested,
+    entropy::{entropy_from_args, ChosenEntropy, EntropySource, SeededEntropy},
+    error::PlaygroundError,
+    prover::{prove_both_transcripts, run_proof, MultiOpen, ProofOptions, TranscriptHash},
+    report::report_if_requested,
+    schema::{as_columns, InstanceBuilder, InstanceError},
+    scheme::{prove_and_verify as prove_under, Ipa, Kzg, SchemeRun},
+    storage::cached_params,
+    verifier::{check_proof, verify_split},
+};
 
+#[cfg(feature = "dev-graph")]
+use halo2_playground::dev::{render_layout, LayoutOptions};
 use halo2_proofs::{
     arithmetic::FieldExt,
-    circuit::{Layouter, Region, SimpleFloorPlanner, Value},
+    circuit::{Layouter, SimpleFloorPlanner, Value},
     dev::MockProver,
-    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    halo2curves::{
+        bn256::{Bn256, Fr, G1Affine},
+        pasta::{EqAffine, Fp},
+    },
     plonk::{
-        create_proof, keygen_pk, keygen_vk, verify_proof, verify_proof2, Advice, Circuit, Column,
-        ConstraintSystem, Error, Instance, Selector,
+        keygen_pk, keygen_vk, verify_proof2, Circuit, Column, ConstraintSystem, Error, Instance,
     },
     poly::{
         commitment::{Params, ParamsProver},
+        ipa::commitment::ParamsIPA,
         kzg::{
-            commitment::{KZGCommitmentScheme, ParamsKZG, ParamsVerifierKZG},
-            multiopen::{ProverSHPLONK, VerifierSHPLONK},
+            commitment::{KZGCommitmentScheme, ParamsVerifierKZG},
+            multiopen::VerifierSHPLONK,
             strategy::SingleStrategy,
         },
-        Rotation,
-    },
-    transcript::{
-        Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer,
     },
+    transcript::{Blake2bRead, Challenge255, TranscriptReadBuffer},
 };
 
-use rand::SeedableRng;
-use rand_xorshift::XorShiftRng;
-
 #[derive(Clone, Debug)]
-struct Config {
-    pub advice: [Column<Advice>; 2],
-    pub instance: Column<Instance>,
-    pub s_mul: Selector,
+struct QuadraticConfig {
+    plonk: StandardPlonkConfig,
+    instance: Column<Instance>,
 }
 
-#[derive(Default)]
-struct DefaultCircuit<F: FieldExt> {
-    pub a: Value<F>,
-    pub b: Value<F>,
+/// `x^2 - 3x + 2` for a private `x` in instance row 0, composed from the standard PLONK
+/// operations without a gate of its own.
+#[derive(Clone, Default)]
+struct QuadraticCircuit {
+    x: Value<Fr>,
 }
 
-impl<F: FieldExt> Circuit<F> for DefaultCircuit<F> {
-    type Config = Config;
+impl Circuit<Fr> for QuadraticCircuit {
+    type Config = QuadraticConfig;
     type FloorPlanner = SimpleFloorPlanner;
 
     fn without_witnesses(&self) -> Self {
         Self::default()
     }
 
-    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
-        let advice = [meta.advice_column(), meta.advice_column()];
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
         let instance = meta.instance_column();
-        let s_mul = meta.selector();
-
-        meta.enable_equality(advice[0]);
-        meta.enable_equality(advice[1]);
         meta.enable_equality(instance);
-
-        meta.create_gate("mul", |meta| {
-            let lhs = meta.query_advice(advice[0], Rotation::cur());
-            let rhs = meta.query_advice(advice[1], Rotation::cur());
-            let out = meta.query_advice(advice[0], Rotation::next());
-            let s_mul = meta.query_selector(s_mul);
-            vec![s_mul * (lhs * rhs - out)]
-        });
-
-        Config {
-            advice,
+        QuadraticConfig {
+            plonk: StandardPlonkChip::configure(meta),
             instance,
-            s_mul,
         }
     }
+
     fn synthesize(
         &self,
         config: Self::Config,
-        mut layouter: impl Layouter<F>,
+        mut layouter: impl Layouter<Fr>,
     ) -> Result<(), Error> {
-        let a = layouter.assign_region(
-            || "load private a",
-            |mut region| region.assign_advice(|| "private input", config.advice[0], 0, || self.a),
-        )?;
-        let b = layouter.assign_region(
-            || "load private b",
-            |mut region| region.assign_advice(|| "private input", config.advice[1], 0, || self.b),
-        )?;
-        let c = layouter.assign_region(
-            || "a * b",
-            |mut region: Region<'_, F>| {
-                config.s_mul.enable(&mut region, 0)?;
-                a.copy_advice(|| "lhs", &mut region, config.advice[0], 0)?;
-                b.copy_advice(|| "rhs", &mut region, config.advice[1], 0)?;
-                let value = a.value().copied() * b.value();
-                region.assign_advice(|| "lhs * rhs", config.advice[0], 1, || value)
-            },
-        )?;
-        layouter.constrain_instance(c.cell(), config.instance, 1)?;
-        Ok(())
+        let chip = StandardPlonkChip::construct(config.plonk);
+        let x = chip.assign(layouter.namespace(|| "x"), self.x)?;
+        let x_copy = chip.copy(layouter.namespace(|| "copy x"), &x)?;
+        let square = chip.mul(layouter.namespace(|| "x^2"), &x, &x_copy)?;
+        let three = chip.assign_constant(layouter.namespace(|| "3"), Fr::from(3))?;
+        let two = chip.assign_constant(layouter.namespace(|| "2"), Fr::from(2))?;
+        let linear = chip.mul(layouter.namespace(|| "3x"), &three, &x)?;
+        let difference = chip.sub(layouter.namespace(|| "x^2 - 3x"), &square, &linear)?;
+        let out = chip.add(layouter.namespace(|| "+ 2"), &difference, &two)?;
+        layouter.constrain_instance(out.cell(), config.instance, 0)
     }
 }
 
+fn quadratic_holds(x: u64, out: Fr) -> bool {
+    let circuit = QuadraticCircuit {
+        x: Value::known(Fr::from(x)),
+    };
+    MockProver::run(
+        fit_k::<Fr, _>(&circuit).expect("fit_k"),
+        &circuit,
+        vec![vec![out]],
+    )
+    .unwrap()
+    .verify()
+    .is_ok()
+}
+
 #[cfg(feature = "dev-graph")]
 fn render<F: FieldExt>(circuit: &impl Circuit<F>) {
-    use plotters::prelude::*;
-    let root = SVGBackend::new("multiplication.svg", (1024, 768)).into_drawing_area();
-    root.fill(&WHITE).unwrap();
-    let root = root
-        .titled("Example Circuit Layout", ("sans-serif", 20))
-        .unwrap();
-
-    halo2_proofs::dev::CircuitLayout::default()
-        .show_labels(true)
-        .mark_equality_cells(true)
-        .show_equality_constraints(true)
-        .render(4, circuit, &root)
-        .unwrap();
+    let options = LayoutOptions {
+        title: Some("Example Circuit Layout".to_string()),
+        ..LayoutOptions::default()
+    };
+    render_layout(circuit, 4, Path::new("multiplication.svg"), &options).unwrap();
 }
 
 #[cfg(not(feature = "dev-graph"))]
 fn render<F: FieldExt>(_: &impl Circuit<F>) {}
 
-fn prove_and_verify(circuit: DefaultCircuit<Fr>, public_inputs: &[&[Fr]]) {
+/// Setup, proving and verification fail with a `PlaygroundError` rather than panicking, and
+/// `main` exits with its status; the `assert!`s check that proofs under other options don't
+/// verify.
+fn prove_and_verify(
+    circuit: MulCircuit<Fr>,
+    public_inputs: &[&[Fr]],
+    rng: ChosenEntropy,
+) -> Result<(), PlaygroundError> {
     let k = 10;
-    let s = Fr::from_u128(GOD_PRIVATE_KEY);
-    let general_params = ParamsKZG::<Bn256>::unsafe_setup_with_s(k, s);
+    let general_params = cached_params(k)?;
 
     let mut verifier_params: ParamsVerifierKZG<Bn256> = general_params.verifier_params().clone();
 
-    let vk = keygen_vk(&general_params, &circuit).expect("keygen_vk");
-    let pk = keygen_pk(&general_params, vk, &circuit).expect("keygen_pk");
-
-    let rng = XorShiftRng::from_seed([
-        0x59, 0x62, 0xbe, 0x5d, 0x76, 0x3d, 0x31, 0x8d, 0x17, 0xdb, 0x37, 0x32, 0x54, 0x06, 0xbc,
-        0xe5,
-    ]);
-    let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
-    create_proof::<
-        KZGCommitmentScheme<Bn256>,
-        ProverSHPLONK<'_, Bn256>,
-        Challenge255<G1Affine>,
-        XorShiftRng,
-        Blake2bWrite<Vec<u8>, G1Affine, Challenge255<G1Affine>>,
-        DefaultCircuit<Fr>,
-    >(
+    let vk = keygen_vk(&general_params, &circuit).map_err(PlaygroundError::Keygen)?;
+    let pk = keygen_pk(&general_params, vk, &circuit).map_err(PlaygroundError::Keygen)?;
+
+    let start = Instant::now();
+    let proof = run_proof(
         &general_params,
         &pk,
-        &[circuit],
-        &[public_inputs],
-        rng,
-        &mut transcript,
-    )
-    .expect("create_proof");
-    let proof = transcript.finalize();
+        circuit.clone(),
+        public_inputs,
+        rng.clone(),
+        ProofOptions::default(),
+    )?;
+    let prove_time = start.elapsed();
     let mut vk_buf = vec![];
-    pk.get_vk().write(&mut vk_buf).expect("write");
+    pk.get_vk()
+        .write(&mut vk_buf)
+        .map_err(|e| PlaygroundError::Serialization(format!("vk: {}", e)))?;
 
-    println!("proof length : {}", proof.len());
     println!("vk length: {}", vk_buf.len());
 
     // original verifier
+    let start = Instant::now();
+    check_proof(
+        &verifier_params,
+        pk.get_vk(),
+        public_inputs,
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()?;
+    println!(
+        "SHPLONK proof length : {}, prove {:.2?}, verify {:.2?}",
+        proof.len(),
+        prove_time,
+        start.elapsed()
+    );
+    // GWC multiopen instead of SHPLONK
     {
-        let mut verifier_transcript = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(&proof[..]);
-        let strategy = SingleStrategy::new(&verifier_params);
-        verify_proof::<
-            KZGCommitmentScheme<Bn256>,
-            VerifierSHPLONK<'_, Bn256>,
-            Challenge255<G1Affine>,
-            Blake2bRead<&[u8], G1Affine, Challenge255<G1Affine>>,
-            SingleStrategy<'_, Bn256>,
-        >(
+        let options = ProofOptions {
+            multiopen: MultiOpen::Gwc,
+            ..ProofOptions::default()
+        };
+        let start = Instant::now();
+        let proof = run_proof(
+            &general_params,
+            &pk,
+            circuit.clone(),
+            public_inputs,
+            rng.clone(),
+            options,
+        )?;
+        let prove_time = start.elapsed();
+        let start = Instant::now();
+        check_proof(
             &verifier_params,
             pk.get_vk(),
-            strategy,
-            &[public_inputs],
-            &mut verifier_transcript,
+            public_inputs,
+            &proof,
+            options,
         )
-        .expect("verify_proof");
+        .into_result()?;
+        println!(
+            "GWC proof length : {}, prove {:.2?}, verify {:.2?}",
+            proof.len(),
+            prove_time,
+            start.elapsed()
+        );
+        let shplonk = ProofOptions::default();
+        assert!(check_proof(
+            &verifier_params,
+            pk.get_vk(),
+            public_inputs,
+            &proof,
+            shplonk
+        )
+        .into_result()
+        .is_err());
+        // the split verifier follows the options too
+        let instances: &[&[&[Fr]]] = &[public_inputs];
+        assert!(matches!(
+            verify_split(
+                &verifier_params,
+                pk.get_vk(),
+                &[(instances, &proof[..])],
+                options
+            ),
+            Ok(true)
+        ));
+        assert!(!matches!(
+            verify_split(
+                &verifier_params,
+                pk.get_vk(),
+                &[(instances, &proof[..])],
+                shplonk
+            ),
+            Ok(true)
+        ));
+    }
+    // Keccak256 transcript, for EVM verifiers
+    {
+        let options = ProofOptions {
+            transcript: TranscriptHash::Keccak256,
+            ..ProofOptions::default()
+        };
+        let proof = run_proof(
+            &general_params,
+            &pk,
+            circuit.clone(),
+            public_inputs,
+            rng,
+            options,
+        )?;
+        println!("Keccak256 proof length : {}", proof.len());
+        check_proof(
+            &verifier_params,
+            pk.get_vk(),
+            public_inputs,
+            &proof,
+            options,
+        )
+        .into_result()?;
+        assert!(check_proof(
+            &verifier_params,
+            pk.get_vk(),
+            public_inputs,
+            &proof,
+            ProofOptions::default()
+        )
+        .into_result()
+        .is_err());
+        // and so does a batch of them
+        let instances: Vec<Vec<Fr>> = public_inputs.iter().map(|c| c.to_vec()).collect();
+        let mut batch = Batch::new().options(options);
+        batch.push(pk.get_vk(), instances.clone(), proof.clone());
+        batch.push(pk.get_vk(), instances, proof);
+        batch
+            .verify(&verifier_params)
+            .map_err(|e| PlaygroundError::Verify(e.to_string()))?;
+    }
+    // both transcripts from one call, each proof only verifying under its own
+    {
+        let start = Instant::now();
+        let dual = prove_both_transcripts(
+            &general_params,
+            &pk,
+            circuit,
+            public_inputs,
+            MultiOpen::Shplonk,
+            [SeededEntropy::new(1), SeededEntropy::new(2)],
+        )?;
+        println!("proofs under both transcripts in {:.2?}", start.elapsed());
+        let keccak = ProofOptions {
+            transcript: TranscriptHash::Keccak256,
+            ..ProofOptions::default()
+        };
+        for (proof, options, other) in [
+            (&dual.blake2b, ProofOptions::default(), keccak),
+            (&dual.keccak256, keccak, ProofOptions::default()),
+        ] {
+            check_proof(&verifier_params, pk.get_vk(), public_inputs, proof, options)
+                .into_result()?;
+            assert!(
+                check_proof(&verifier_params, pk.get_vk(), public_inputs, proof, other)
+                    .into_result()
+                    .is_err()
+            );
+        }
+    }
+    // split verifier: cheap pre-check, deferred final check
+    {
+        let instances: &[&[&[Fr]]] = &[public_inputs];
+        let ok = verify_split(
+            &verifier_params,
+            pk.get_vk(),
+            &[(instances, &proof[..])],
+            ProofOptions::default(),
+        )
+        .map_err(|e| PlaygroundError::Verify(format!("precheck_proof: {:?}", e)))?;
+        if !ok {
+            return Err(PlaygroundError::Verify("final_check".to_string()));
+        }
+    }
+    // batch verifier: one accumulated check, and the failing proof is named
+    {
+        let instances: Vec<Vec<Fr>> = public_inputs.iter().map(|c| c.to_vec()).collect();
+        let mut batch = Batch::new();
+        assert!(matches!(
+            batch.verify(&verifier_params),
+            Err(BatchError::Empty)
+        ));
+        batch.push(pk.get_vk(), instances.clone(), proof.clone());
+        batch.push(pk.get_vk(), instances.clone(), proof.clone());
+        batch
+            .verify(&verifier_params)
+            .map_err(|e| PlaygroundError::Verify(e.to_string()))?;
+        let mut tampered = instances;
+        tampered[0][1] += Fr::one();
+        batch.push(pk.get_vk(), tampered, proof.clone());
+        assert!(matches!(
+            batch.verify(&verifier_params),
+            Err(BatchError::Invalid { index: 2, .. })
+        ));
     }
     // modified verifier
     {
@@ -183,7 +341,7 @@ fn prove_and_verify(circuit: DefaultCircuit<Fr>, public_inputs: &[&[Fr]]) {
         let mut verifier_params_buf = vec![];
         verifier_params
             .write(&mut verifier_params_buf)
-            .expect("write");
+            .map_err(|e| PlaygroundError::Serialization(format!("verifier params: {}", e)))?;
         println!("verifier parameters length : {}", verifier_params_buf.len());
         let strategy = SingleStrategy::new(&verifier_params);
         verify_proof2::<
@@ -199,27 +357,77 @@ fn prove_and_verify(circuit: DefaultCircuit<Fr>, public_inputs: &[&[Fr]]) {
             &[public_inputs],
             &mut verifier_transcript,
         )
-        .expect("verify_proof2");
+        .map_err(|e| PlaygroundError::Verify(format!("verify_proof2: {:?}", e)))?;
     }
+    Ok(())
 }
 
 fn main() {
-    let dummy = Fr::from(0);
-
-    let k = 4;
-
     let a = Fr::from(3);
     let b = Fr::from(5);
     let c = a * b;
 
-    let circuit = DefaultCircuit {
-        a: Value::known(a),
-        b: Value::known(b),
-    };
-    let public_inputs = vec![dummy, c];
-    let prover = MockProver::run(k, &circuit, vec![public_inputs.clone()]).unwrap();
-    assert_eq!(prover.verify(), Ok(()));
+    let circuit = MulCircuit::new(a, b);
+    let k = fit_k(&circuit).expect("fit_k");
+    report_if_requested("multiplication", &circuit);
+    let rng = entropy_from_args(0x5962_be5d_763d_318d).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(2);
+    });
+    println!("proving randomness: {}", rng.describe());
+    // the product goes wherever the circuit's layout puts it
+    let builder = InstanceBuilder::for_circuit::<MulCircuit<Fr>>().expect("instance layout");
+    assert_eq!(
+        builder.build().unwrap_err(),
+        InstanceError::Missing("product".to_string())
+    );
+    assert!(matches!(
+        builder.clone().set("c", c),
+        Err(InstanceError::Unknown(_))
+    ));
+    let public = builder
+        .set("product", c)
+        .and_then(|builder| builder.build())
+        .expect("instances");
+    assert_eq!(public, vec![MulCircuit::instance(c)]);
+    let public_inputs = as_columns(&public);
+    mock_if_requested("multiplication", k, &circuit, public.clone()).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(err.exit_code());
+    });
     render(&circuit);
 
-    prove_and_verify(circuit, &[&[dummy, c]]);
+    prove_and_verify(circuit, &public_inputs, rng.clone()).unwrap_or_else(|err| {
+        eprintln!("error: {}", err);
+        std::process::exit(err.exit_code());
+    });
+
+    // the same circuit under both commitment schemes
+    let print = |run: SchemeRun| {
+        println!(
+            "{}: {}-byte proof, keygen {:.2?}, prove {:.2?}, verify {:.2?}",
+            run.scheme, run.proof_bytes, run.keygen, run.prove, run.verify
+        )
+    };
+    let params = cached_params(k).expect("cached_params");
+    print(
+        prove_under::<Kzg, _, _>(&params, MulCircuit::new(a, b), &public_inputs, rng.clone())
+            .expect("kzg"),
+    );
+    let (a, b) = (Fp::from(3), Fp::from(5));
+    print(
+        prove_under::<Ipa, _, _>(
+            &ParamsIPA::<EqAffine>::new(k),
+            MulCircuit::new(a, b),
+            &[&MulCircuit::instance(a * b)],
+            rng,
+        )
+        .expect("ipa"),
+    );
+
+    // the same chip composes other arithmetic: 5^2 - 15 + 2 = 12, and 1 and 2 are roots
+    assert!(quadratic_holds(5, Fr::from(12)));
+    assert!(!quadratic_holds(5, Fr::from(13)));
+    assert!(quadratic_holds(1, Fr::zero()));
+    assert!(quadratic_holds(2, Fr::zero()));
 }
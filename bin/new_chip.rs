@@ -0,0 +1,285 @@
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use clap::Parser;
+
+#[derive(Parser)]
+#[clap(about = "Scaffold a new chip: its module, a MockProver test binary and the wiring")]
+struct Cli {
+    /// snake_case name of the chip, e.g. `range_check`
+    name: String,
+    /// Print what would be written without touching the tree
+    #[clap(long)]
+    dry_run: bool,
+}
+
+const CHIP_TEMPLATE: &str = r##"use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+pub struct __Name__Config {
+    pub advice: [Column<Advice>; 2],
+    pub s___name__: Selector,
+}
+
+/// TODO: describe what the chip constrains. The scaffolded gate squares its input.
+pub struct __Name__Chip<F: FieldExt> {
+    config: __Name__Config,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> __Name__Chip<F> {
+    pub fn construct(config: __Name__Config) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> __Name__Config {
+        let advice = [meta.advice_column(), meta.advice_column()];
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        let s___name__ = meta.selector();
+        meta.create_gate("__name__", |meta| {
+            let s = meta.query_selector(s___name__);
+            let input = meta.query_advice(advice[0], Rotation::cur());
+            let output = meta.query_advice(advice[1], Rotation::cur());
+            vec![s * (input.clone() * input - output)]
+        });
+        __Name__Config { advice, s___name__ }
+    }
+
+    /// The value `assign` computes, for tests and witness generation outside the circuit.
+    pub fn reference(input: F) -> F {
+        input.square()
+    }
+
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        input: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "__name__",
+            |mut region| {
+                self.config.s___name__.enable(&mut region, 0)?;
+                let input = input.copy_advice(|| "input", &mut region, self.config.advice[0], 0)?;
+                let output = input.value().map(|input| Self::reference(*input));
+                region.assign_advice(|| "output", self.config.advice[1], 0, || output)
+            },
+        )
+    }
+}
+"##;
+
+const TEST_TEMPLATE: &str = r##"use halo2_playground::{
+    audit::fit_k,
+    chips::__name__::{__Name__Chip, __Name__Config},
+};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+#[derive(Clone, Debug)]
+struct Config {
+    __name__: __Name__Config,
+    advice: Column<Advice>,
+    instance: Column<Instance>,
+}
+
+/// Runs the chip on instance row 0 and exposes its output in instance row 1.
+#[derive(Clone, Default)]
+struct __Name__Circuit;
+
+impl<F: FieldExt> Circuit<F> for __Name__Circuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+        Config {
+            __name__: __Name__Chip::configure(meta),
+            advice,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = __Name__Chip::construct(config.__name__);
+        let input = layouter.assign_region(
+            || "load input",
+            |mut region| {
+                region.assign_advice_from_instance(|| "input", config.instance, 0, config.advice, 0)
+            },
+        )?;
+        let out = chip.assign(layouter.namespace(|| "__name__"), &input)?;
+        layouter.constrain_instance(out.cell(), config.instance, 1)
+    }
+}
+
+fn main() {
+    let k = fit_k::<Fr, _>(&__Name__Circuit).expect("fit_k");
+    let inputs = [0u64, 1, 2, 0xffff_ffff];
+    for input in inputs {
+        let input = Fr::from(input);
+        let expected = __Name__Chip::reference(input);
+
+        let prover = MockProver::run(k, &__Name__Circuit, vec![vec![input, expected]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()), "input {:?}", input);
+
+        let wrong = expected + Fr::one();
+        let prover = MockProver::run(k, &__Name__Circuit, vec![vec![input, wrong]]).unwrap();
+        assert!(prover.verify().is_err(), "input {:?}", input);
+    }
+    println!("__name__: checked {} inputs", inputs.len());
+}
+"##;
+
+fn fail(message: String) -> ! {
+    eprintln!("error: {}", message);
+    process::exit(1);
+}
+
+fn is_snake_case(name: &str) -> bool {
+    let mut chars = name.chars();
+    matches!(chars.next(), Some('a'..='z'))
+        && chars.all(|c| matches!(c, 'a'..='z' | '0'..='9' | '_'))
+        && !name.ends_with('_')
+        && !name.contains("__")
+}
+
+fn camel_case(name: &str) -> String {
+    name.split('_')
+        .map(|word| {
+            let mut chars = word.chars();
+            match chars.next() {
+                Some(first) => first.to_ascii_uppercase().to_string() + chars.as_str(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn render(template: &str, name: &str) -> String {
+    template
+        .replace("__Name__", &camel_case(name))
+        .replace("__name__", name)
+}
+
+/// Inserts `pub mod <name>;` into the sorted mod list of `src/chips/mod.rs`.
+fn register_module(mod_rs: &str, name: &str) -> String {
+    let line = format!("pub mod {};", name);
+    let mut lines: Vec<&str> = mod_rs.lines().collect();
+    let at = lines
+        .iter()
+        .position(|l| l.starts_with("pub mod ") && **l > line.as_str())
+        .unwrap_or_else(|| {
+            lines
+                .iter()
+                .rposition(|l| l.starts_with("pub mod "))
+                .map_or(lines.len(), |i| i + 1)
+        });
+    lines.insert(at, &line);
+    lines.join("\n") + "\n"
+}
+
+fn register_bin(cargo_toml: &str, name: &str) -> String {
+    format!(
+        "{}\n[[bin]]\nname = \"{}\"\npath = \"bin/{}.rs\"\n",
+        cargo_toml, name, name
+    )
+}
+
+/// Adds the test binary to `make ci`, after the last chip test run there.
+fn register_ci(makefile: &str, name: &str) -> String {
+    let mut lines: Vec<String> = makefile.lines().map(str::to_string).collect();
+    let at = lines
+        .iter()
+        .rposition(|l| l.starts_with("\tcargo run --bin ") && !l.contains(" -- "))
+        .map_or(lines.len(), |i| i + 1);
+    lines.insert(at, format!("\tcargo run --bin {}", name));
+    lines.join("\n") + "\n"
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let name = cli.name.as_str();
+    if !is_snake_case(name) {
+        fail(format!("`{}` is not a snake_case identifier", name));
+    }
+    let root = Path::new(env!("CARGO_MANIFEST_DIR"));
+    let chip_path = root.join("src/chips").join(format!("{}.rs", name));
+    let test_path = root.join("bin").join(format!("{}.rs", name));
+    for path in [&chip_path, &test_path] {
+        if path.exists() {
+            fail(format!("{} already exists", path.display()));
+        }
+    }
+    let read = |path: &Path| {
+        fs::read_to_string(path)
+            .unwrap_or_else(|err| fail(format!("reading {}: {}", path.display(), err)))
+    };
+    let (mod_path, cargo_path, make_path) = (
+        root.join("src/chips/mod.rs"),
+        root.join("Cargo.toml"),
+        root.join("Makefile"),
+    );
+    let cargo_toml = read(&cargo_path);
+    if cargo_toml.contains(&format!("name = \"{}\"", name)) {
+        fail(format!("a binary named `{}` already exists", name));
+    }
+
+    let writes: Vec<(PathBuf, String)> = vec![
+        (chip_path, render(CHIP_TEMPLATE, name)),
+        (test_path, render(TEST_TEMPLATE, name)),
+        (mod_path.clone(), register_module(&read(&mod_path), name)),
+        (cargo_path, register_bin(&cargo_toml, name)),
+        (make_path.clone(), register_ci(&read(&make_path), name)),
+    ];
+    for (path, contents) in writes.iter() {
+        let path = path.strip_prefix(root).unwrap_or(path);
+        if cli.dry_run {
+            println!("would write {} ({} bytes)", path.display(), contents.len());
+        } else {
+            println!("writing {}", path.display());
+        }
+    }
+    if cli.dry_run {
+        return;
+    }
+    for (path, contents) in writes {
+        fs::write(&path, contents)
+            .unwrap_or_else(|err| fail(format!("writing {}: {}", path.display(), err)));
+    }
+    println!(
+        "next: replace the scaffolded gate, then `cargo run --bin {}`",
+        name
+    );
+}
@@ -0,0 +1,80 @@
+use halo2_playground::{
+    audit::fit_k,
+    entropy::SeededEntropy,
+    limits::ResourceLimits,
+    onchain::{encode_columns, verify, PoseidonCircuit},
+    prover::{run_proof, ProofOptions},
+    storage::cached_params,
+};
+use halo2_proofs::{
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+    poly::commitment::Params,
+};
+
+fn main() {
+    let message = [Fr::from(3), Fr::from(5)];
+    let circuit = PoseidonCircuit::new(message);
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let instance = vec![PoseidonCircuit::hash(message)];
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &[&instance],
+        SeededEntropy::new(0x0c4a_11ed),
+        ProofOptions::default(),
+    )
+    .expect("run_proof");
+
+    let mut vk_bytes = vec![];
+    pk.get_vk().write(&mut vk_bytes).expect("write vk");
+    let mut params_bytes = vec![];
+    params.write(&mut params_bytes).expect("write params");
+    let instances = encode_columns(&[instance.clone()]);
+    assert!(verify(&vk_bytes, &params_bytes, &instances, &proof));
+
+    // another statement, a flipped proof byte, and inputs that don't parse
+    let other = encode_columns(&[vec![instance[0] + Fr::one()]]);
+    assert!(!verify(&vk_bytes, &params_bytes, &other, &proof));
+    let mut tampered = proof.clone();
+    tampered[40] ^= 1;
+    assert!(!verify(&vk_bytes, &params_bytes, &instances, &tampered));
+    assert!(!verify(
+        &vk_bytes,
+        &params_bytes,
+        &instances[..instances.len() - 1],
+        &proof
+    ));
+    assert!(!verify(&vk_bytes, &params_bytes, &[], &proof));
+    let mut non_canonical = instances.clone();
+    non_canonical[4..].copy_from_slice(&[0xff; 32]);
+    assert!(!verify(&vk_bytes, &params_bytes, &non_canonical, &proof));
+    let mut trailing = vk_bytes.clone();
+    trailing.push(0);
+    assert!(!verify(&trailing, &params_bytes, &instances, &proof));
+    assert!(!verify(&vk_bytes, &params_bytes[..4], &instances, &proof));
+
+    // sizes over the default limits are rejected before they are allocated
+    let limits = ResourceLimits::default();
+    let mut huge_k = params_bytes.clone();
+    huge_k[..4].copy_from_slice(&(limits.max_k + 1).to_le_bytes());
+    assert!(!verify(&vk_bytes, &huge_k, &instances, &proof));
+    let mut long_column = instances.clone();
+    long_column[..4].copy_from_slice(&(limits.max_instance_len as u32 + 1).to_le_bytes());
+    assert!(!verify(&vk_bytes, &params_bytes, &long_column, &proof));
+    let extra_column = encode_columns(&[instance.clone(), instance.clone()]);
+    assert!(!verify(&vk_bytes, &params_bytes, &extra_column, &proof));
+    let mut long_proof = proof.clone();
+    long_proof.resize(limits.max_proof_bytes + 1, 0);
+    assert!(!verify(&vk_bytes, &params_bytes, &instances, &long_proof));
+
+    println!(
+        "byte-slice verifier: {} vk, {} params, {} proof bytes",
+        vk_bytes.len(),
+        params_bytes.len(),
+        proof.len()
+    );
+}
@@ -0,0 +1,110 @@
+use halo2_playground::{
+    chips::pack::{PackChip, PackConfig},
+    reference::{bytes_to_words, field_to_bytes, Endianness},
+};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+const N_BYTES: usize = 16;
+const WORD_BYTES: usize = 4;
+
+#[derive(Clone, Debug)]
+struct Config {
+    pack: PackConfig,
+    advice: Column<Advice>,
+    instance: Column<Instance>,
+}
+
+/// Round-trips the value in instance row 0 through bytes and words, exposing the first word
+/// in instance row 1.
+#[derive(Clone)]
+struct PackCircuit {
+    endianness: Endianness,
+}
+
+impl<F: FieldExt> Circuit<F> for PackCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+        Config {
+            pack: PackChip::configure(meta),
+            advice,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = PackChip::construct(config.pack);
+        chip.load(&mut layouter)?;
+        let value = layouter.assign_region(
+            || "load value",
+            |mut region| {
+                region.assign_advice_from_instance(|| "value", config.instance, 0, config.advice, 0)
+            },
+        )?;
+        let bytes = chip.field_to_bytes(
+            layouter.namespace(|| "bytes"),
+            &value,
+            N_BYTES,
+            self.endianness,
+        )?;
+        let words = chip.bytes_to_words(
+            layouter.namespace(|| "words"),
+            &bytes,
+            WORD_BYTES,
+            self.endianness,
+        )?;
+        let bytes = chip.words_to_bytes(
+            layouter.namespace(|| "unpack words"),
+            &words,
+            WORD_BYTES,
+            self.endianness,
+        )?;
+        let packed = chip.bytes_to_field(layouter.namespace(|| "pack"), &bytes, self.endianness)?;
+        layouter.assign_region(
+            || "round trip",
+            |mut region| region.constrain_equal(packed.cell(), value.cell()),
+        )?;
+        layouter.constrain_instance(words[0].cell(), config.instance, 1)
+    }
+}
+
+fn main() {
+    let k = 9;
+    let value = Fr::from_u128(0x0011_2233_4455_6677_8899_aabb_ccdd_eeff);
+    for endianness in [Endianness::Little, Endianness::Big] {
+        let bytes = field_to_bytes(&value, N_BYTES, endianness);
+        let words = bytes_to_words(&bytes, WORD_BYTES, endianness);
+        let circuit = PackCircuit { endianness };
+
+        let prover = MockProver::run(k, &circuit, vec![vec![value, Fr::from(words[0])]]).unwrap();
+        assert_eq!(prover.verify(), Ok(()), "{:?}", endianness);
+
+        let prover = MockProver::run(k, &circuit, vec![vec![value, Fr::from(words[1])]]).unwrap();
+        assert!(prover.verify().is_err(), "{:?}", endianness);
+
+        // values wider than N_BYTES don't decompose
+        let wide = value * Fr::from_u128(1 << 64);
+        let prover = MockProver::run(k, &circuit, vec![vec![wide, Fr::from(words[0])]]).unwrap();
+        assert!(prover.verify().is_err(), "{:?}", endianness);
+    }
+}
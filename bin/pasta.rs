@@ -0,0 +1,154 @@
+use std::time::{Duration, Instant};
+
+use halo2_playground::{
+    circuits::{mul::MulCircuit, poseidon::PoseidonHashCircuit},
+    commit_instances, commit_instances_hiding, commit_instances_with_blinds,
+    entropy::SeededEntropy,
+    ipa,
+    poseidon::MySpec,
+    prover::{run_proof, ProofOptions},
+    report::report_if_requested,
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    halo2curves::{
+        bn256::Fr,
+        pasta::{EqAffine, Fp},
+    },
+    plonk::{keygen_pk, keygen_vk, Circuit},
+    poly::ipa::{commitment::IPACommitmentScheme, multiopen::VerifierIPA},
+};
+
+type Ipa = IPACommitmentScheme<EqAffine>;
+
+const K: u32 = 7;
+
+struct Measurement {
+    proof_len: usize,
+    prove: Duration,
+    verify: Duration,
+}
+
+fn kzg<C: Circuit<Fr>>(circuit: C, instances: &[&[Fr]]) -> Measurement {
+    let params = cached_params(K).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let start = Instant::now();
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        instances,
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    let prove = start.elapsed();
+    let start = Instant::now();
+    check_proof(
+        &params,
+        pk.get_vk(),
+        instances,
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+    Measurement {
+        proof_len: proof.len(),
+        prove,
+        verify: start.elapsed(),
+    }
+}
+
+fn ipa<C: Circuit<Fp>>(circuit: C, instances: &[&[Fp]]) -> Measurement {
+    let params = ipa::setup(K);
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let start = Instant::now();
+    let proof = ipa::prove(
+        &params,
+        &pk,
+        circuit,
+        instances,
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+    )
+    .expect("create_proof");
+    let prove = start.elapsed();
+    let start = Instant::now();
+    ipa::verify(&params, pk.get_vk(), instances, &proof).expect("verify_proof");
+    let verify = start.elapsed();
+    let mut tampered = instances[0].to_vec();
+    tampered[tampered.len() - 1] += Fp::one();
+    assert!(ipa::verify(&params, pk.get_vk(), &[&tampered], &proof).is_err());
+
+    // IPA commitments are hiding: blinded ones differ and reopen with the returned blinds
+    let plain =
+        commit_instances::<Ipa, VerifierIPA<'_, EqAffine>>(&params, pk.get_vk(), &[instances])
+            .expect("commit_instances");
+    let (hiding, blinds) = commit_instances_hiding::<Ipa, VerifierIPA<'_, EqAffine>, _>(
+        &params,
+        pk.get_vk(),
+        &[instances],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+    )
+    .expect("commit_instances_hiding");
+    assert_ne!(plain, hiding);
+    let reopened = commit_instances_with_blinds::<Ipa, VerifierIPA<'_, EqAffine>>(
+        &params,
+        pk.get_vk(),
+        &[instances],
+        &blinds,
+    )
+    .expect("commit_instances_with_blinds");
+    assert_eq!(hiding, reopened);
+    Measurement {
+        proof_len: proof.len(),
+        prove,
+        verify,
+    }
+}
+
+fn report(name: &str, kzg: Measurement, ipa: Measurement) {
+    println!("{}:", name);
+    for (scheme, m) in [("KZG/BN256", kzg), ("IPA/Vesta", ipa)] {
+        println!(
+            "  {:<10} proof {:>5} bytes, prove {:>10.2?}, verify {:>10.2?}",
+            scheme, m.proof_len, m.prove, m.verify
+        );
+    }
+}
+
+fn mul<F: FieldExt>() -> (MulCircuit<F>, Vec<F>) {
+    let (a, b) = (F::from(3), F::from(5));
+    (MulCircuit::new(a, b), MulCircuit::instance(a * b))
+}
+
+fn main() {
+    report_if_requested("mul", &MulCircuit::<Fr>::default());
+    let (kzg_circuit, kzg_instance) = mul::<Fr>();
+    let (ipa_circuit, ipa_instance) = mul::<Fp>();
+    report(
+        "multiplication",
+        kzg(kzg_circuit, &[&kzg_instance]),
+        ipa(ipa_circuit, &[&ipa_instance]),
+    );
+
+    let kzg_message = [Fr::from(1), Fr::from(2)];
+    let kzg_hash = PoseidonHashCircuit::<Fr, MySpec<3, 2>, 3, 2, 2>::hash(kzg_message);
+    let ipa_message = [Fp::from(1), Fp::from(2)];
+    let ipa_hash = PoseidonHashCircuit::<Fp, MySpec<3, 2>, 3, 2, 2>::hash(ipa_message);
+    report(
+        "poseidon",
+        kzg(
+            PoseidonHashCircuit::<Fr, MySpec<3, 2>, 3, 2, 2>::new(kzg_message),
+            &[&[kzg_hash]],
+        ),
+        ipa(
+            PoseidonHashCircuit::<Fp, MySpec<3, 2>, 3, 2, 2>::new(ipa_message),
+            &[&[ipa_hash]],
+        ),
+    );
+}
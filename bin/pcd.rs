@@ -0,0 +1,68 @@
+use halo2_playground::{
+    circuits::transition::StateTransitionCircuit,
+    entropy::SeededEntropy,
+    pcd::{check_chain, prove_transitions, ChainError},
+    report::report_if_requested,
+    storage::cached_params,
+};
+use halo2_proofs::{
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+const K: u32 = 7;
+const LENGTH: u64 = 4;
+
+fn main() {
+    let initial = Fr::zero();
+    let updates: Vec<Fr> = (1..=LENGTH).map(Fr::from).collect();
+    let head = updates.iter().fold(initial, |root, update| {
+        StateTransitionCircuit::next_root(root, *update)
+    });
+
+    let circuit = StateTransitionCircuit::new(updates[0]);
+    report_if_requested("transition", &circuit);
+    let root_out = StateTransitionCircuit::next_root(initial, updates[0]);
+    let prover = MockProver::run(K, &circuit, vec![vec![initial, root_out]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+    let prover = MockProver::run(K, &circuit, vec![vec![initial + Fr::one(), root_out]]).unwrap();
+    assert!(prover.verify().is_err());
+
+    let params = cached_params(K).expect("cached_params");
+    let vk = keygen_vk(&params, &StateTransitionCircuit::default()).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &StateTransitionCircuit::default()).expect("keygen_pk");
+    let vk = pk.get_vk();
+
+    // coordinator
+    let proofs = prove_transitions(
+        &params,
+        &pk,
+        initial,
+        &updates,
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+    )
+    .expect("prove_transitions");
+    assert_eq!(proofs.len(), LENGTH as usize);
+
+    // checker
+    check_chain(&params, vk, initial, head, &proofs).expect("check_chain");
+    assert_eq!(
+        check_chain(&params, vk, initial, updates[0], &proofs),
+        Err(ChainError::WrongHead)
+    );
+    let mut skipped = proofs.clone();
+    skipped.remove(1);
+    assert_eq!(
+        check_chain(&params, vk, initial, head, &skipped),
+        Err(ChainError::Broken { index: 1 })
+    );
+    let mut swapped = proofs.clone();
+    swapped[2].proof = proofs[1].proof.clone();
+    assert_eq!(
+        check_chain(&params, vk, initial, head, &swapped),
+        Err(ChainError::InvalidProof)
+    );
+
+    println!("pcd: chain of {} proofs checked", proofs.len());
+}
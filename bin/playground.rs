@@ -0,0 +1,846 @@
+#[cfg(feature = "dev-graph")]
+use std::ops::Range;
+use std::{
+    fs, io,
+    net::TcpListener,
+    path::{Path, PathBuf},
+    process,
+    time::Duration,
+};
+
+use clap::{Args, Parser, Subcommand, ValueEnum};
+#[cfg(feature = "dev-graph")]
+use halo2_playground::dev::{render_layout, LayoutOptions};
+use halo2_playground::{
+    audit::fit_k,
+    audit_log::AuditLog,
+    circuits::{self, mul::MulCircuit, poseidon::PoseidonHashCircuit},
+    cli::{self, BatchMap, CliCircuit, KeyFiles, PoseidonCircuit},
+    config::{self, RunConfig},
+    demo, dev,
+    encoding::{parse_field, Reduction},
+    entropy::OsEntropy,
+    error::PlaygroundError,
+    expr::{ExprCircuit, Relation},
+    fmt::to_decimal,
+    poseidon::MySpec,
+    prover::{run_proof, MultiOpen, ProofOptions, TranscriptHash},
+    readiness::{self, Service},
+    report::CostReport,
+    srs, storage,
+    store::{ArtifactStore, StoreLimits},
+    sweep::{csv_rows, measure, SweepCosts, SweepPoint, CSV_HEADER},
+    transcript_registry::{self, TRANSCRIPTS},
+    usage::UsageReport,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr},
+    plonk::{keygen_pk, keygen_vk},
+    poly::kzg::commitment::ParamsKZG,
+};
+
+/// The commit the binary was built from, recorded in artifact bundles; `make` sets it.
+const GIT_REV: Option<&str> = option_env!("GIT_REV");
+
+#[derive(Parser)]
+#[clap(about = "Set up, prove and verify the playground circuits through files")]
+struct Cli {
+    /// Append every keygen, prove and verify to this hash-chained JSONL log
+    #[clap(long, global = true)]
+    audit_log: Option<PathBuf>,
+    /// Print the circuit's cost report first
+    #[clap(long, global = true)]
+    report: bool,
+    /// Where params come from: unsafe[:<secret>], random or an SRS file. `cached_params` reads
+    /// it from the arguments itself; parsing it here rejects a bad one up front
+    #[clap(long, global = true, value_parser = parse_srs)]
+    #[allow(dead_code)]
+    srs: Option<String>,
+    /// Check a witness with `MockProver` before proving it, the default in debug builds;
+    /// `prove` reads these from the arguments itself
+    #[clap(long, global = true)]
+    #[allow(dead_code)]
+    mock: bool,
+    #[clap(long, global = true, conflicts_with = "mock")]
+    #[allow(dead_code)]
+    no_mock: bool,
+    #[clap(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Write the KZG parameters of the `--srs` provider
+    Setup {
+        #[clap(long)]
+        k: u32,
+        #[clap(long)]
+        params: PathBuf,
+    },
+    /// Prove and verify the multiplication and Poseidon circuits with no setup
+    Demo {
+        /// Write the demo's verifying keys here instead, for building with `embedded-demo`
+        #[clap(long)]
+        write_keys: Option<PathBuf>,
+        /// Print a JSON array with the proof, key sizes, instances and timings of each run
+        #[clap(long)]
+        json: bool,
+    },
+    /// Generate the verifying key of a circuit
+    Keygen {
+        #[clap(flatten)]
+        common: Common,
+    },
+    /// Prove a circuit from a witness file, writing the proof and its public instances. The
+    /// witness is the values in order, or a JSON object by name (see `schema --witness`)
+    Prove {
+        #[clap(flatten)]
+        common: Common,
+        #[clap(long)]
+        witness: PathBuf,
+        /// Written as JSON if named `.json`
+        #[clap(long, required_unless_present = "out")]
+        instances: Option<PathBuf>,
+        /// Written as hex or base64 if named `.hex` or `.b64`
+        #[clap(long, required_unless_present = "out")]
+        proof: Option<PathBuf>,
+        /// Write one bundle of the proof, instances, verifying key and SRS digest instead,
+        /// e.g. `bundle.h2p`, for `verify <bundle>`
+        #[clap(long, conflicts_with_all = &["instances", "proof"])]
+        out: Option<PathBuf>,
+        #[clap(long, value_enum, default_value = "shplonk")]
+        multiopen: MultiOpenArg,
+        /// A registered transcript hash: blake2b, keccak256 or poseidon
+        #[clap(long, default_value = "blake2b", value_parser = parse_transcript)]
+        transcript: TranscriptHash,
+        /// Give up proving after this many seconds, reporting how far it got
+        #[clap(long)]
+        time_budget: Option<f64>,
+    },
+    /// Set up, keygen, prove and verify as a TOML file says: the circuit and its Poseidon
+    /// parameters, k, transcript, SRS and output paths (see the `config` module)
+    Run {
+        #[clap(long)]
+        config: PathBuf,
+    },
+    /// Measure a circuit over a grid of parameters, writing one CSV row per point
+    Sweep {
+        #[clap(long, value_enum)]
+        circuit: CircuitName,
+        /// Comma-separated circuit sizes
+        #[clap(long, value_delimiter = ',', default_value = "7,8")]
+        k: Vec<u32>,
+        /// Comma-separated message lengths, for poseidon (1 to 8)
+        #[clap(long, value_delimiter = ',', default_value = "2")]
+        lengths: Vec<usize>,
+        /// Comma-separated batch sizes for batch verification
+        #[clap(long, value_delimiter = ',', default_value = "1")]
+        batch: Vec<usize>,
+        #[clap(long)]
+        out: PathBuf,
+    },
+    /// Prove an arithmetic relation such as `(a+b)*c - d^3 == out` with the values of its
+    /// variables; those on the right of `==` are public, and a lone unset one is derived
+    Expr {
+        relation: String,
+        /// Comma-separated `name=value` pairs
+        #[clap(long = "set", value_delimiter = ',')]
+        values: Vec<String>,
+    },
+    /// List the circuits this build includes and the features behind them
+    Circuits,
+    /// Draw a circuit's layout, to compare how circuits use their rows and columns
+    #[cfg(feature = "dev-graph")]
+    Layout {
+        #[clap(long, value_enum)]
+        circuit: CircuitName,
+        /// An `.svg` or `.png` file
+        #[clap(long)]
+        out: PathBuf,
+        /// Rows; the smallest that fit by default
+        #[clap(long)]
+        k: Option<u32>,
+        /// Draw only these rows, as `start..end`
+        #[clap(long, value_parser = parse_range)]
+        rows: Option<Range<usize>>,
+        /// Draw only these columns, as `start..end`
+        #[clap(long, value_parser = parse_range)]
+        columns: Option<Range<usize>>,
+        #[clap(long)]
+        no_labels: bool,
+    },
+    /// Print a circuit's gates, lookups, permutation and queried rotations as `configure`
+    /// builds them
+    Constraints {
+        #[clap(long, value_enum)]
+        circuit: CircuitName,
+        /// As a Graphviz digraph instead
+        #[clap(long)]
+        dot: bool,
+    },
+    /// Report how keygen combined a circuit's selectors into fixed columns, how full each
+    /// column and region is, and which columns could be merged
+    Usage {
+        #[clap(long, value_enum)]
+        circuit: CircuitName,
+    },
+    /// Describe the public inputs of a circuit, as a Markdown table or JSON
+    Schema {
+        #[clap(long, value_enum)]
+        circuit: CircuitName,
+        #[clap(long)]
+        json: bool,
+        /// Describe the witness file instead; with --json, as a JSON Schema
+        #[clap(long)]
+        witness: bool,
+    },
+    /// Load the params and the proving keys of both circuits once, then answer `GET /ready`,
+    /// `GET /live` and `GET /metrics` over HTTP
+    Serve {
+        #[clap(long)]
+        params: PathBuf,
+        #[clap(long, default_value = "127.0.0.1:8484")]
+        addr: String,
+        /// Seconds a readiness report is reused for
+        #[clap(long, default_value = "10")]
+        max_age: f64,
+        /// Threads answering requests
+        #[clap(long, default_value = "4")]
+        workers: usize,
+        /// Print readiness once and exit, failing when not ready
+        #[clap(long)]
+        check: bool,
+    },
+    /// Evict the least recently used params, keys and proofs from the artifact store that
+    /// caches them, until each category is within its limit; one without a limit is kept
+    Gc {
+        /// The store to trim, by default the one `PLAYGROUND_STORE_DIR` names or the default
+        /// cache
+        #[clap(long)]
+        store: Option<PathBuf>,
+        /// In bytes, or with a K, M or G suffix
+        #[clap(long, value_parser = parse_size)]
+        params_limit: Option<u64>,
+        #[clap(long, value_parser = parse_size)]
+        keys_limit: Option<u64>,
+        #[clap(long, value_parser = parse_size)]
+        proofs_limit: Option<u64>,
+    },
+    /// Verify a proof against its public instances, or a bundle from `prove --out`
+    Verify {
+        /// A bundle, which names its circuit and carries its key, instances and proof
+        #[clap(conflicts_with_all = &["instances", "proof", "batch"])]
+        bundle: Option<PathBuf>,
+        #[clap(long, value_enum, required_unless_present = "bundle")]
+        circuit: Option<CircuitName>,
+        #[clap(long)]
+        params: PathBuf,
+        /// For a bundle, the trusted key its proof has to be made for
+        #[clap(long, required_unless_present = "bundle")]
+        vk: Option<PathBuf>,
+        /// Read as JSON if named `.json`
+        #[clap(long, required_unless_present_any = &["batch", "bundle"])]
+        instances: Option<PathBuf>,
+        /// Read as hex or base64 if named `.hex` or `.b64`
+        #[clap(long, required_unless_present_any = &["batch", "bundle"])]
+        proof: Option<PathBuf>,
+        /// Verify these proofs together, each against the `.instances` file next to it
+        #[clap(long, multiple_values = true, conflicts_with_all = &["instances", "proof"])]
+        batch: Vec<PathBuf>,
+        #[clap(long, value_enum, default_value = "shplonk")]
+        multiopen: MultiOpenArg,
+        /// A registered transcript hash: blake2b, keccak256 or poseidon
+        #[clap(long, default_value = "blake2b", value_parser = parse_transcript)]
+        transcript: TranscriptHash,
+    },
+}
+
+#[derive(Args)]
+struct Common {
+    #[clap(long, value_enum)]
+    circuit: CircuitName,
+    #[clap(long)]
+    params: PathBuf,
+    #[clap(long)]
+    vk: PathBuf,
+}
+
+impl Common {
+    fn files(&self) -> KeyFiles {
+        KeyFiles {
+            params: self.params.clone(),
+            vk: self.vk.clone(),
+        }
+    }
+}
+
+fn key_files(params: &Path, vk: &Option<PathBuf>) -> KeyFiles {
+    KeyFiles {
+        params: params.to_path_buf(),
+        vk: vk.clone().expect("required by clap"),
+    }
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CircuitName {
+    Mul,
+    Poseidon,
+    /// Two instance columns, inputs and outputs
+    #[clap(name = "batch_map")]
+    BatchMap,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum MultiOpenArg {
+    Shplonk,
+    Gwc,
+}
+
+fn parse_srs(arg: &str) -> Result<String, String> {
+    srs::parse_provider(arg)
+        .map(|_| arg.to_string())
+        .map_err(|e| e.to_string())
+}
+
+fn parse_transcript(id: &str) -> Result<TranscriptHash, String> {
+    transcript_registry::by_id(id)
+        .map(|entry| entry.hash)
+        .ok_or_else(|| {
+            let ids: Vec<_> = TRANSCRIPTS.iter().map(|entry| entry.id).collect();
+            format!(
+                "unknown transcript {:?}, expected one of {}",
+                id,
+                ids.join(", ")
+            )
+        })
+}
+
+fn proof_options(multiopen: MultiOpenArg, transcript: TranscriptHash) -> ProofOptions {
+    ProofOptions {
+        multiopen: match multiopen {
+            MultiOpenArg::Shplonk => MultiOpen::Shplonk,
+            MultiOpenArg::Gwc => MultiOpen::Gwc,
+        },
+        transcript,
+    }
+}
+
+#[cfg(feature = "dev-graph")]
+fn parse_range(arg: &str) -> Result<Range<usize>, String> {
+    let (start, end) = arg
+        .split_once("..")
+        .ok_or_else(|| format!("`{}` is not start..end", arg))?;
+    let bound = |text: &str| {
+        text.trim()
+            .parse::<usize>()
+            .map_err(|e| format!("`{}`: {}", text, e))
+    };
+    let range = bound(start)?..bound(end)?;
+    if range.is_empty() {
+        return Err(format!("`{}` is empty", arg));
+    }
+    Ok(range)
+}
+
+/// Bytes, or KiB, MiB or GiB with a `K`, `M` or `G` suffix.
+fn parse_size(arg: &str) -> Result<u64, String> {
+    let (digits, unit) = match arg.trim().char_indices().last() {
+        Some((i, 'K' | 'k')) => (&arg.trim()[..i], 1 << 10),
+        Some((i, 'M' | 'm')) => (&arg.trim()[..i], 1 << 20),
+        Some((i, 'G' | 'g')) => (&arg.trim()[..i], 1 << 30),
+        _ => (arg.trim(), 1),
+    };
+    let count = digits
+        .trim()
+        .parse::<u64>()
+        .map_err(|e| format!("`{}`: {}", arg, e))?;
+    count
+        .checked_mul(unit)
+        .ok_or_else(|| format!("`{}` is too large", arg))
+}
+
+fn gc(store: Option<&Path>, limits: StoreLimits) -> Result<(), PlaygroundError> {
+    let root = store.map_or_else(storage::store_dir, Path::to_path_buf);
+    let report = ArtifactStore::open(&root)
+        .and_then(|store| store.with_limits(limits).gc())
+        .map_err(PlaygroundError::io(&root))?;
+    for path in report.removed.iter() {
+        println!("removed {}", path.display());
+    }
+    println!(
+        "{} artifacts, {} bytes freed",
+        report.removed.len(),
+        report.freed
+    );
+    Ok(())
+}
+
+fn seconds(secs: f64) -> Result<Duration, PlaygroundError> {
+    if secs.is_finite() && secs >= 0.0 {
+        Ok(Duration::from_secs_f64(secs))
+    } else {
+        Err(PlaygroundError::Other(format!(
+            "{} is not a number of seconds",
+            secs
+        )))
+    }
+}
+
+fn poseidon_costs<const L: usize>(
+    params: &ParamsKZG<Bn256>,
+    batches: &[usize],
+) -> Result<SweepCosts, PlaygroundError> {
+    let mut message = [Fr::zero(); L];
+    for (i, m) in message.iter_mut().enumerate() {
+        *m = Fr::from(i as u64 + 1);
+    }
+    let circuit = PoseidonHashCircuit::<Fr, MySpec<3, 2>, 3, 2, L>::new(message);
+    let hash = PoseidonHashCircuit::<Fr, MySpec<3, 2>, 3, 2, L>::hash(message);
+    measure(params, &circuit, &[&[hash]], batches, OsEntropy)
+}
+
+fn sweep(
+    circuit: CircuitName,
+    ks: &[u32],
+    lengths: &[usize],
+    batches: &[usize],
+    out: &Path,
+) -> Result<(), PlaygroundError> {
+    let mut lines = vec![CSV_HEADER.to_string()];
+    for &k in ks.iter() {
+        let params = storage::cached_params(k)?;
+        let points: Vec<(SweepPoint, Result<SweepCosts, PlaygroundError>)> = match circuit {
+            CircuitName::Mul => {
+                let (a, b) = (Fr::from(3), Fr::from(5));
+                let instance = MulCircuit::instance(a * b);
+                let costs = measure(
+                    &params,
+                    &MulCircuit::new(a, b),
+                    &[&instance],
+                    batches,
+                    OsEntropy,
+                );
+                let point = SweepPoint {
+                    circuit: "mul",
+                    k,
+                    length: None,
+                };
+                vec![(point, costs)]
+            }
+            CircuitName::BatchMap => {
+                let instance = BatchMap::instance([1, 2, 3, 4].map(Fr::from));
+                let columns: Vec<&[Fr]> = instance.iter().map(Vec::as_slice).collect();
+                let costs = measure(&params, &BatchMap::new(), &columns, batches, OsEntropy);
+                let point = SweepPoint {
+                    circuit: "batch_map",
+                    k,
+                    length: None,
+                };
+                vec![(point, costs)]
+            }
+            CircuitName::Poseidon => lengths
+                .iter()
+                .map(|&length| {
+                    let costs = match length {
+                        1 => poseidon_costs::<1>(&params, batches),
+                        2 => poseidon_costs::<2>(&params, batches),
+                        3 => poseidon_costs::<3>(&params, batches),
+                        4 => poseidon_costs::<4>(&params, batches),
+                        5 => poseidon_costs::<5>(&params, batches),
+                        6 => poseidon_costs::<6>(&params, batches),
+                        7 => poseidon_costs::<7>(&params, batches),
+                        8 => poseidon_costs::<8>(&params, batches),
+                        _ => Err(PlaygroundError::Other(format!(
+                            "message length {} is not compiled in",
+                            length
+                        ))),
+                    };
+                    let point = SweepPoint {
+                        circuit: "poseidon",
+                        k,
+                        length: Some(length),
+                    };
+                    (point, costs)
+                })
+                .collect(),
+        };
+        for (point, costs) in points.iter() {
+            if let Err(err) = costs {
+                eprintln!("k = {}, length {:?}: {}", point.k, point.length, err);
+            }
+            lines.extend(csv_rows(point, batches, costs));
+        }
+    }
+    fs::write(out, lines.join("\n") + "\n").map_err(PlaygroundError::io(out))
+}
+
+fn run_demo(write_keys: Option<&Path>, json: bool) -> Result<(), PlaygroundError> {
+    if let Some(dir) = write_keys {
+        return demo::write_keys(dir);
+    }
+    if json {
+        let runs: Vec<String> = demo::artifacts()?.iter().map(|run| run.to_json()).collect();
+        println!("[{}]", runs.join(","));
+        return Ok(());
+    }
+    for run in demo::run()? {
+        println!(
+            "{}: k = {}, {} vk, {}-byte proof verified in {:.2?}",
+            run.circuit,
+            run.k,
+            if run.embedded_vk {
+                "embedded"
+            } else {
+                "generated"
+            },
+            run.proof_bytes,
+            run.elapsed
+        );
+    }
+    Ok(())
+}
+
+fn prove_expr(text: &str, values: &[String]) -> Result<(), PlaygroundError> {
+    let relation = Relation::<Fr>::parse(text)
+        .map_err(|e| PlaygroundError::Serialization(format!("relation {}", e)))?;
+    let assignment = values
+        .iter()
+        .map(|pair| {
+            let (name, value) = pair.split_once('=').ok_or_else(|| {
+                PlaygroundError::Serialization(format!("`{}` is not name=value", pair))
+            })?;
+            let value = parse_field(value, Reduction::Reject)
+                .map_err(|e| PlaygroundError::Serialization(format!("{}: {}", name, e)))?;
+            Ok((name.trim(), value))
+        })
+        .collect::<Result<Vec<_>, PlaygroundError>>()?;
+    let values = relation
+        .complete(&assignment)
+        .map_err(|e| PlaygroundError::Unprovable(e.to_string()))?;
+    let instance = relation.instance(&values);
+    let circuit = ExprCircuit::new(relation.clone(), values);
+
+    let k = fit_k::<Fr, _>(&circuit)?;
+    let params = storage::cached_params(k)?;
+    let vk = keygen_vk(&params, &circuit).map_err(PlaygroundError::Keygen)?;
+    let pk = keygen_pk(&params, vk, &circuit).map_err(PlaygroundError::Keygen)?;
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &[&instance],
+        OsEntropy,
+        ProofOptions::default(),
+    )?;
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[&instance],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()?;
+    for (name, value) in relation.public_names().iter().zip(instance.iter()) {
+        println!("{} = {}", name, to_decimal(value));
+    }
+    println!("k = {}, {}-byte proof verified", k, proof.len());
+    Ok(())
+}
+
+fn serve(
+    params: &Path,
+    addr: &str,
+    max_age: Duration,
+    workers: usize,
+    check: bool,
+) -> Result<(), PlaygroundError> {
+    let store = storage::artifact_store().map_err(PlaygroundError::io(&storage::store_dir()))?;
+    let mut service = Service::new(cli::read_params(params)?).with_store(store);
+    service.add_key(&MulCircuit::<Fr>::empty())?;
+    service.add_key(&PoseidonCircuit::empty())?;
+    service.warm_up()?;
+    let readiness = service.readiness();
+    println!("{}", readiness);
+    if check {
+        return if readiness.is_ready() {
+            Ok(())
+        } else {
+            Err(PlaygroundError::Other("not ready".to_string()))
+        };
+    }
+    let socket = |e: io::Error| PlaygroundError::Other(format!("{}: {}", addr, e));
+    let listener = TcpListener::bind(addr).map_err(socket)?;
+    println!(
+        "serving readiness on http://{0}/ready and metrics on http://{0}/metrics",
+        addr
+    );
+    readiness::serve(listener, &service, max_age, workers);
+    Ok(())
+}
+
+/// Errors are [`PlaygroundError`]s, so `main` can exit with their kind's status.
+fn run<C: CliCircuit>(
+    command: &Command,
+    log: &mut Option<AuditLog>,
+) -> Result<(), PlaygroundError> {
+    match command {
+        Command::Setup { k, params } => cli::setup(*k, params),
+        Command::Demo { write_keys, json } => run_demo(write_keys.as_deref(), *json),
+        Command::Serve {
+            params,
+            addr,
+            max_age,
+            workers,
+            check,
+        } => serve(params, addr, seconds(*max_age)?, *workers, *check),
+        Command::Gc {
+            store,
+            params_limit,
+            keys_limit,
+            proofs_limit,
+        } => gc(
+            store.as_deref(),
+            StoreLimits {
+                params: *params_limit,
+                keys: *keys_limit,
+                proofs: *proofs_limit,
+            },
+        ),
+        Command::Expr { relation, values } => prove_expr(relation, values),
+        Command::Run { config: path } => config::run(&RunConfig::read(path)?, log),
+        Command::Sweep {
+            circuit,
+            k,
+            lengths,
+            batch,
+            out,
+        } => sweep(*circuit, k, lengths, batch, out),
+        Command::Circuits => {
+            for entry in circuits::registry() {
+                println!(
+                    "{:<12} {:<16} {}",
+                    entry.name,
+                    entry.feature.unwrap_or("always"),
+                    (entry.instance_layout)().circuit
+                );
+            }
+            Ok(())
+        }
+        Command::Constraints { dot: false, .. } => {
+            print!("{}", dev::dump_constraint_system::<Fr, C>());
+            Ok(())
+        }
+        Command::Constraints { dot: true, .. } => {
+            print!("{}", dev::constraint_system_dot::<Fr, C>());
+            Ok(())
+        }
+        Command::Usage { .. } => {
+            println!("usage:\n{}", UsageReport::measure(&C::empty())?);
+            Ok(())
+        }
+        Command::Schema {
+            json,
+            witness: true,
+            ..
+        } => {
+            let layout = C::witness_layout();
+            if *json {
+                println!("{}", layout.json_schema());
+            } else {
+                print!("{}", layout.to_table());
+            }
+            Ok(())
+        }
+        Command::Schema { json, .. } => {
+            let layout = C::instance_layout();
+            if *json {
+                println!("{}", layout.to_json());
+            } else {
+                print!("{}", layout.to_table());
+            }
+            Ok(())
+        }
+        #[cfg(feature = "dev-graph")]
+        Command::Layout {
+            out,
+            k,
+            rows,
+            columns,
+            no_labels,
+            ..
+        } => {
+            let circuit = C::empty();
+            let k = match k {
+                Some(k) => *k,
+                None => fit_k::<Fr, _>(&circuit)?,
+            };
+            let options = LayoutOptions {
+                title: Some(format!("{}, k = {}", C::NAME, k)),
+                rows: rows.clone(),
+                columns: columns.clone(),
+                region_labels: !no_labels,
+                ..LayoutOptions::for_path(out)?
+            };
+            render_layout(&circuit, k, out, &options)?;
+            println!("{} layout in {}", C::NAME, out.display());
+            Ok(())
+        }
+        Command::Keygen { common } => cli::keygen::<C>(&common.files(), log),
+        Command::Prove {
+            common,
+            witness,
+            out: Some(out),
+            multiopen,
+            transcript,
+            time_budget,
+            ..
+        } => cli::prove_bundle::<C>(
+            &common.files(),
+            witness,
+            out,
+            proof_options(*multiopen, *transcript),
+            time_budget.map(seconds).transpose()?,
+            GIT_REV,
+            log,
+        ),
+        Command::Prove {
+            common,
+            witness,
+            instances,
+            proof,
+            multiopen,
+            transcript,
+            time_budget,
+            ..
+        } => cli::prove::<C>(
+            &common.files(),
+            witness,
+            instances.as_ref().expect("required by clap"),
+            proof.as_ref().expect("required by clap"),
+            proof_options(*multiopen, *transcript),
+            time_budget.map(seconds).transpose()?,
+            log,
+        ),
+        Command::Verify {
+            bundle: Some(bundle),
+            params,
+            vk,
+            ..
+        } => cli::verify_bundle::<C>(params, bundle, vk.as_deref(), log),
+        Command::Verify {
+            params,
+            vk,
+            batch,
+            multiopen,
+            transcript,
+            ..
+        } if !batch.is_empty() => cli::verify_batch::<C>(
+            &key_files(params, vk),
+            batch,
+            proof_options(*multiopen, *transcript),
+            log,
+        ),
+        Command::Verify {
+            params,
+            vk,
+            instances,
+            proof,
+            multiopen,
+            transcript,
+            ..
+        } => cli::verify::<C>(
+            &key_files(params, vk),
+            instances.as_ref().expect("required by clap"),
+            proof.as_ref().expect("required by clap"),
+            proof_options(*multiopen, *transcript),
+            log,
+        ),
+    }
+}
+
+/// The circuit `verify` works with: the one asked for, or the one a bundle names.
+fn verify_circuit(
+    circuit: Option<CircuitName>,
+    bundle: Option<&Path>,
+) -> Result<CircuitName, PlaygroundError> {
+    match (circuit, bundle) {
+        (Some(circuit), _) => Ok(circuit),
+        (None, Some(bundle)) => {
+            let name = cli::bundle_circuit(bundle)?;
+            CircuitName::from_str(&name, false).map_err(|_| {
+                PlaygroundError::serialization(
+                    bundle,
+                    format!("no circuit {:?} in this build", name),
+                )
+            })
+        }
+        (None, None) => unreachable!("required by clap"),
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let circuit = match &cli.command {
+        Command::Setup { .. }
+        | Command::Demo { .. }
+        | Command::Serve { .. }
+        | Command::Gc { .. }
+        | Command::Expr { .. }
+        | Command::Run { .. }
+        | Command::Circuits => CircuitName::Mul,
+        Command::Sweep { circuit, .. }
+        | Command::Schema { circuit, .. }
+        | Command::Constraints { circuit, .. }
+        | Command::Usage { circuit } => *circuit,
+        #[cfg(feature = "dev-graph")]
+        Command::Layout { circuit, .. } => *circuit,
+        Command::Keygen { common } | Command::Prove { common, .. } => common.circuit,
+        Command::Verify {
+            circuit, bundle, ..
+        } => match verify_circuit(*circuit, bundle.as_deref()) {
+            Ok(circuit) => circuit,
+            Err(err) => {
+                eprintln!("error: {}", err);
+                process::exit(err.exit_code());
+            }
+        },
+    };
+    let mut log = match cli.audit_log.as_ref().map(AuditLog::open).transpose() {
+        Ok(log) => log,
+        Err(err) => {
+            let err = PlaygroundError::AuditLog(err);
+            eprintln!("error: {}", err);
+            process::exit(err.exit_code());
+        }
+    };
+    if cli.report {
+        let report = match circuit {
+            CircuitName::Mul => CostReport::measure(&MulCircuit::<Fr>::empty()),
+            CircuitName::Poseidon => CostReport::measure(&PoseidonCircuit::empty()),
+            CircuitName::BatchMap => CostReport::measure(&BatchMap::empty()),
+        };
+        match report {
+            Ok(report) => println!("cost report:\n{}", report),
+            Err(err) => eprintln!("cost report: {}", err),
+        }
+    }
+    let result = match circuit {
+        CircuitName::Mul => run::<MulCircuit<Fr>>(&cli.command, &mut log),
+        CircuitName::Poseidon => run::<PoseidonCircuit>(&cli.command, &mut log),
+        CircuitName::BatchMap => run::<BatchMap>(&cli.command, &mut log),
+    };
+    match result {
+        Ok(()) => {
+            if let Command::Verify { batch, .. } = &cli.command {
+                if batch.is_empty() {
+                    println!("proof verified");
+                }
+            }
+        }
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(err.exit_code());
+        }
+    }
+}
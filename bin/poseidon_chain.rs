@@ -0,0 +1,135 @@
+//! Fills `k = 10` up to `--max-k` (11 by default) with chained Poseidon hashes and times
+//! keygen, proving and verification at each size, with the prover's estimated memory. `make
+//! chain-stress` runs it in release up to `k = 20`. Built with `profiling`, `--profile` splits
+//! each run into phases with the peak memory after each instead (`make profile`).
+
+use std::time::Instant;
+
+use halo2_playground::{
+    circuits::poseidon_chain::PoseidonChainCircuit,
+    dev::Assignments,
+    entropy::SeededEntropy,
+    prover::{run_proof, ProofOptions},
+    report::CostReport,
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+const MIN_K: u32 = 10;
+
+fn max_k() -> u32 {
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.iter().position(|arg| arg == "--max-k") {
+        Some(i) => args
+            .get(i + 1)
+            .and_then(|k| k.parse().ok())
+            .filter(|k| (MIN_K..=28).contains(k))
+            .unwrap_or_else(|| {
+                eprintln!("error: --max-k takes a k from {} to 28", MIN_K);
+                std::process::exit(2);
+            }),
+        None => 11,
+    }
+}
+
+fn used_rows(length: usize) -> usize {
+    Assignments::synthesize(
+        MIN_K,
+        &PoseidonChainCircuit::new(Fr::zero(), length),
+        vec![vec![]],
+    )
+    .expect("synthesize")
+    .used_rows()
+}
+
+fn main() {
+    let seed = Fr::from(7);
+    let circuit = PoseidonChainCircuit::new(seed, 4);
+    let head = PoseidonChainCircuit::head(seed, 4);
+    let prover = MockProver::run(MIN_K, &circuit, vec![vec![head]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+    // another link's output, or the head from another seed
+    for wrong in [
+        PoseidonChainCircuit::head(seed, 3),
+        PoseidonChainCircuit::head(seed + Fr::one(), 4),
+    ] {
+        let prover = MockProver::run(MIN_K, &circuit, vec![vec![wrong]]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    // each link takes the same rows, so the lengths that fill a k follow from two layouts
+    let first = used_rows(1);
+    let per_link = used_rows(2) - first;
+    let blinding = Assignments::synthesize(MIN_K, &PoseidonChainCircuit::default(), vec![vec![]])
+        .expect("synthesize")
+        .constraint_system()
+        .blinding_factors();
+    println!("{} rows per link", per_link);
+
+    println!(
+        "{:>3} {:>7} {:>10} {:>10} {:>10} {:>6} {:>10}",
+        "k", "links", "keygen", "prove", "verify", "proof", "memory"
+    );
+    for k in MIN_K..=max_k() {
+        let usable = (1 << k) - (blinding + 1);
+        let length = 1 + (usable - first) / per_link;
+        let circuit = PoseidonChainCircuit::new(seed, length);
+        let head = [PoseidonChainCircuit::head(seed, length)];
+
+        let params = cached_params(k).expect("cached_params");
+        #[cfg(feature = "profiling")]
+        if std::env::args().any(|arg| arg == "--profile") {
+            let profile = halo2_playground::profiling::profile(
+                &params,
+                circuit,
+                &[&head],
+                SeededEntropy::new(0x5962_be5d_763d_318d),
+                ProofOptions::default(),
+            )
+            .expect("profile");
+            println!("k = {}, {} links:\n{}", k, length, profile);
+            continue;
+        }
+        let start = Instant::now();
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+        let memory = CostReport::from_vk(&vk).prover_memory;
+        let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+        let keygen = start.elapsed();
+        let start = Instant::now();
+        let proof = run_proof(
+            &params,
+            &pk,
+            circuit,
+            &[&head],
+            SeededEntropy::new(0x5962_be5d_763d_318d),
+            ProofOptions::default(),
+        )
+        .expect("run_proof");
+        let prove = start.elapsed();
+        let start = Instant::now();
+        check_proof(
+            &params,
+            pk.get_vk(),
+            &[&head],
+            &proof,
+            ProofOptions::default(),
+        )
+        .into_result()
+        .expect("verify_proof");
+        println!(
+            "{:>3} {:>7} {:>10.2?} {:>10.2?} {:>10.2?} {:>6} {:>7} MiB",
+            k,
+            length,
+            keygen,
+            prove,
+            start.elapsed(),
+            proof.len(),
+            memory >> 20
+        );
+    }
+}
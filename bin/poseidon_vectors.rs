@@ -0,0 +1,75 @@
+use halo2_gadgets::poseidon::primitives::{self as poseidon, ConstantLength, Spec};
+use halo2_playground::{
+    poseidon::{assert_secure, MySpec, SECURE_FULL_ROUNDS},
+    reference::poseidon_permute,
+};
+use halo2_proofs::halo2curves::{
+    bn256::Fr,
+    group::ff::{Field, PrimeField},
+    FieldExt,
+};
+
+// Permutation test vectors of the Poseidon reference implementation (poseidonperm_x5_254_*).
+const X5_254_3_INPUT: [u64; 3] = [0, 1, 2];
+const X5_254_3_OUTPUT: [&str; 3] = [
+    "115cc0f5e7d690413df64c6b9662e9cf2a3617f2743245519e19607a4417189a",
+    "0fca49b798923ab0239de1c9e7a4a9a2210312b6a2f616d18b5a87f9b628ae29",
+    "0e7ae82e40091e63cbd4f16a6d16310b3729d4b6e138fcf54110e2867045a30c",
+];
+const X5_254_5_INPUT: [u64; 5] = [0, 1, 2, 3, 4];
+const X5_254_5_OUTPUT_0: &str = "299c867db6c1fdd79dcefa40e4510b9837e60ebb1ce0663dbaa525df65250465";
+
+fn fr(hex: &str) -> Fr {
+    let mut repr = [0u8; 32];
+    for (i, byte) in repr.iter_mut().rev().enumerate() {
+        *byte = u8::from_str_radix(&hex[2 * i..2 * i + 2], 16).unwrap();
+    }
+    Fr::from_repr(repr).unwrap()
+}
+
+fn check_rounds<S: Spec<Fr, WIDTH, RATE>, const WIDTH: usize, const RATE: usize>(
+    partial_rounds: usize,
+) {
+    assert_secure::<S, WIDTH, RATE>();
+    assert_eq!(S::full_rounds(), SECURE_FULL_ROUNDS);
+    assert_eq!(S::partial_rounds(), partial_rounds);
+    let (round_constants, mds, mds_inv) = S::constants();
+    assert_eq!(
+        round_constants.len(),
+        S::full_rounds() + S::partial_rounds()
+    );
+
+    // mds * mds_inv = I
+    for (i, row) in mds.iter().enumerate() {
+        for j in 0..WIDTH {
+            let entry = row
+                .iter()
+                .zip(mds_inv.iter())
+                .fold(Fr::zero(), |acc, (m, inv_row)| acc + *m * inv_row[j]);
+            let expected = if i == j { Fr::one() } else { Fr::zero() };
+            assert_eq!(entry, expected, "MDS inverse at ({}, {})", i, j);
+        }
+    }
+}
+
+fn main() {
+    check_rounds::<MySpec<3, 2>, 3, 2>(57);
+    check_rounds::<MySpec<5, 4>, 5, 4>(60);
+
+    let mut state = X5_254_3_INPUT.map(Fr::from);
+    poseidon_permute::<_, MySpec<3, 2>, 3, 2>(&mut state);
+    assert_eq!(state, X5_254_3_OUTPUT.map(fr), "t = 3 permutation");
+
+    let mut state = X5_254_5_INPUT.map(Fr::from);
+    poseidon_permute::<_, MySpec<5, 4>, 5, 4>(&mut state);
+    assert_eq!(state[0], fr(X5_254_5_OUTPUT_0), "t = 5 permutation");
+
+    // the written-out permutation agrees with the sponge used by the circuits
+    let message = [Fr::from(3), Fr::from(5)];
+    let mut state = [message[0], message[1], Fr::from_u128(2 << 64)];
+    poseidon_permute::<_, MySpec<3, 2>, 3, 2>(&mut state);
+    let hash = poseidon::Hash::<_, MySpec<3, 2>, ConstantLength<2>, 3, 2>::init().hash(message);
+    assert_eq!(state[0], hash, "sponge");
+
+    println!("Poseidon parameters match the reference vectors");
+}
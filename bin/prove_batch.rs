@@ -0,0 +1,79 @@
+//! Proves a batch of multiplications with 1, 2 and 4 proofs in flight and reports the
+//! throughput of each; every proof must verify against its own instance, in order.
+
+use std::time::Instant;
+
+use halo2_playground::{
+    audit::fit_k,
+    circuits::mul::MulCircuit,
+    entropy::SeededEntropy,
+    prover::{prove_batch, run_proof, ProofOptions},
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+const SEED: u64 = 0x5962_be5d_763d_318d;
+const JOBS: u64 = 8;
+
+fn main() {
+    let template = MulCircuit::<Fr>::default();
+    let k = fit_k(&template).expect("fit_k");
+    let params = cached_params(k).expect("cached_params");
+    let vk = keygen_vk(&params, &template).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &template).expect("keygen_pk");
+    let options = ProofOptions::default();
+    let jobs = || {
+        (0..JOBS)
+            .map(|i| {
+                let (a, b) = (Fr::from(i + 2), Fr::from(i + 3));
+                (MulCircuit::new(a, b), vec![MulCircuit::instance(a * b)])
+            })
+            .collect::<Vec<_>>()
+    };
+
+    for parallelism in [1, 2, 4] {
+        let start = Instant::now();
+        let proofs = prove_batch(
+            &params,
+            &pk,
+            jobs(),
+            |i| SeededEntropy::new(SEED + i as u64),
+            options,
+            parallelism,
+        );
+        let elapsed = start.elapsed();
+        assert_eq!(proofs.len(), JOBS as usize);
+        for (i, ((_, instances), proof)) in jobs().into_iter().zip(proofs.iter()).enumerate() {
+            let proof = proof.as_ref().expect("prove_batch");
+            let columns: Vec<&[Fr]> = instances.iter().map(|column| &column[..]).collect();
+            check_proof(&params, pk.get_vk(), &columns, proof, options)
+                .into_result()
+                .expect("check_proof");
+            // the same job and randomness proven alone gives the same bytes
+            if i == 0 {
+                let (circuit, _) = jobs().swap_remove(0);
+                let alone = run_proof(
+                    &params,
+                    &pk,
+                    circuit,
+                    &columns,
+                    SeededEntropy::new(SEED),
+                    options,
+                )
+                .expect("run_proof");
+                assert_eq!(&alone, proof);
+            }
+        }
+        println!(
+            "{} proofs, {} in flight: {:.2?}, {:.1} proofs/s",
+            JOBS,
+            parallelism,
+            elapsed,
+            JOBS as f64 / elapsed.as_secs_f64()
+        );
+    }
+}
@@ -0,0 +1,67 @@
+use std::fs;
+
+use halo2_playground::{
+    circuits::{self, mul::MulCircuit, vdf::VdfCircuit},
+    provenance::{check_provenance, provenance_path, record_provenance, source_hash, Provenance},
+    storage::{cached_params, write_vk},
+};
+use halo2_proofs::{halo2curves::bn256::Fr, plonk::keygen_vk};
+
+const K: u32 = 8;
+
+fn main() {
+    let dir = std::env::temp_dir().join(format!(
+        "halo2-playground-provenance-{}",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    let params = cached_params(K).expect("cached_params");
+    let vk = keygen_vk(&params, &MulCircuit::<Fr>::default()).expect("keygen_vk");
+    let path = dir.join("mul.vk");
+    let source = source_hash(&[circuits::mul::SOURCE]);
+
+    write_vk(&path, &vk).unwrap();
+    assert_eq!(
+        check_provenance(&path, &source, &vk).unwrap(),
+        Provenance::Unrecorded
+    );
+
+    record_provenance(&path, &source, &vk).unwrap();
+    assert_eq!(
+        check_provenance(&path, &source, &vk).unwrap(),
+        Provenance::Current
+    );
+    // keygen is deterministic, so a second run gives the same fingerprint
+    let again = keygen_vk(&params, &MulCircuit::<Fr>::default()).expect("keygen_vk");
+    assert_eq!(
+        check_provenance(&path, &source, &again).unwrap(),
+        Provenance::Current
+    );
+
+    // the source changed since keygen
+    let edited = source_hash(&[&format!("{}\n// edited", circuits::mul::SOURCE)]);
+    assert!(matches!(
+        check_provenance(&path, &edited, &vk).unwrap(),
+        Provenance::StaleSource { .. }
+    ));
+    // a different key was put where the recorded one was
+    let other = keygen_vk(&params, &VdfCircuit::new(4)).expect("keygen_vk");
+    assert!(matches!(
+        check_provenance(&path, &source, &other).unwrap(),
+        Provenance::KeyChanged { .. }
+    ));
+    assert_ne!(
+        source_hash(&[circuits::mul::SOURCE, circuits::vdf::SOURCE]),
+        source_hash(&[&format!(
+            "{}{}",
+            circuits::mul::SOURCE,
+            circuits::vdf::SOURCE
+        )])
+    );
+
+    fs::write(provenance_path(&path), "garbage").unwrap();
+    assert!(check_provenance(&path, &source, &vk).is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+    println!("provenance: stale keys detected");
+}
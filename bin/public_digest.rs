@@ -0,0 +1,115 @@
+//! Exposes `N` products as one Poseidon digest instead of `N` instance rows, and compares the
+//! EVM calldata of the two statements.
+
+use std::convert::TryInto;
+
+use halo2_playground::{
+    audit::fit_k,
+    circuits::products::{DigestedProducts, ProductsCircuit},
+    entropy::SeededEntropy,
+    evm::{calldata_gas, encode_calldata},
+    prover::{run_proof, MultiOpen, ProofOptions, TranscriptHash},
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+const N: usize = 64;
+const SEED: u64 = 0x5962_be5d_763d_318d;
+
+struct Run {
+    k: u32,
+    instance_bytes: usize,
+    calldata: Vec<u8>,
+}
+
+fn run<const DIGEST: bool>(factors: [(Fr, Fr); N]) -> Run {
+    let products = factors.map(|(a, b)| a * b);
+    let instance = ProductsCircuit::<N, DIGEST>::instance(products);
+    let circuit = ProductsCircuit::<N, DIGEST>::new(factors);
+    let k = fit_k(&circuit).expect("fit_k");
+    let params = cached_params(k).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let options = ProofOptions {
+        multiopen: MultiOpen::Shplonk,
+        transcript: TranscriptHash::Keccak256,
+    };
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &[&instance],
+        SeededEntropy::new(SEED),
+        options,
+    )
+    .expect("run_proof");
+    check_proof(&params, pk.get_vk(), &[&instance], &proof, options)
+        .into_result()
+        .expect("check_proof");
+
+    // a statement over other products doesn't verify
+    let mut other = products;
+    other[N - 1] += Fr::one();
+    let other = ProductsCircuit::<N, DIGEST>::instance(other);
+    assert!(!check_proof(&params, pk.get_vk(), &[&other], &proof, options).is_verified());
+
+    Run {
+        k,
+        instance_bytes: 32 * instance.len(),
+        calldata: encode_calldata(&[&instance], &proof),
+    }
+}
+
+fn main() {
+    let factors: [(Fr, Fr); N] = (0..N as u64)
+        .map(|i| (Fr::from(i + 2), Fr::from(i + 3)))
+        .collect::<Vec<_>>()
+        .try_into()
+        .unwrap();
+    let products = factors.map(|(a, b)| a * b);
+
+    // the digest binds every product, and their order
+    let digest = DigestedProducts::<N>::instance(products);
+    assert_eq!(digest.len(), 1);
+    let mut swapped = products;
+    swapped.swap(0, 1);
+    assert_ne!(DigestedProducts::<N>::instance(swapped), digest);
+    let circuit = DigestedProducts::<N>::new(factors);
+    let k = fit_k(&circuit).expect("fit_k");
+    let mock = |instance: Vec<Fr>| {
+        MockProver::run(k, &circuit, vec![instance])
+            .unwrap()
+            .verify()
+            .is_ok()
+    };
+    assert!(mock(digest.clone()));
+    assert!(!mock(DigestedProducts::<N>::instance(swapped)));
+    assert!(!mock(vec![digest[0] + Fr::one()]));
+
+    let exposed = run::<false>(factors);
+    let digested = run::<true>(factors);
+    assert_eq!(exposed.instance_bytes, 32 * N);
+    assert_eq!(digested.instance_bytes, 32);
+    assert!(digested.calldata.len() < exposed.calldata.len());
+    assert!(calldata_gas(&digested.calldata) < calldata_gas(&exposed.calldata));
+
+    println!(
+        "{:>10} {:>3} {:>10} {:>10} {:>10}",
+        "instances", "k", "instance", "calldata", "gas"
+    );
+    for (name, run) in [("exposed", exposed), ("digest", digested)] {
+        println!(
+            "{:>10} {:>3} {:>10} {:>10} {:>10}",
+            name,
+            run.k,
+            run.instance_bytes,
+            run.calldata.len(),
+            calldata_gas(&run.calldata)
+        );
+    }
+}
@@ -0,0 +1,114 @@
+use halo2_playground::{
+    audit::fit_k,
+    chips::{
+        range_check::{limbs, RangeCheckChip, RangeCheckConfig},
+        tables::Tables,
+    },
+    report::report_if_requested,
+};
+
+use halo2_proofs::{
+    arithmetic::{Field, FieldExt},
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+const LIMB_BITS: usize = 8;
+
+#[derive(Clone, Debug)]
+struct Config {
+    tables: Tables,
+    range: RangeCheckConfig,
+    advice: Column<Advice>,
+    instance: Column<Instance>,
+}
+
+/// Range checks the value in instance row 0 to `bits` bits and exposes its lowest limb in
+/// row 1.
+#[derive(Clone)]
+struct RangeCheckCircuit {
+    bits: usize,
+}
+
+impl<F: FieldExt> Circuit<F> for RangeCheckCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+        let mut tables = Tables::new();
+        let range = RangeCheckChip::<F, LIMB_BITS>::configure(meta, &mut tables);
+        Config {
+            tables,
+            range,
+            advice,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.tables.load(&mut layouter)?;
+        let chip = RangeCheckChip::<F, LIMB_BITS>::construct(config.range);
+        let value = layouter.assign_region(
+            || "load value",
+            |mut region| {
+                region.assign_advice_from_instance(|| "value", config.instance, 0, config.advice, 0)
+            },
+        )?;
+        let limbs = chip.range_check(layouter.namespace(|| "range check"), &value, self.bits)?;
+        layouter.constrain_instance(limbs[0].cell(), config.instance, 1)
+    }
+}
+
+fn run(bits: usize, value: Fr) -> bool {
+    let circuit = RangeCheckCircuit { bits };
+    let k = fit_k::<Fr, _>(&circuit).expect("fit_k");
+    let low = Fr::from(limbs(&value, LIMB_BITS, 1)[0]);
+    MockProver::run(k, &circuit, vec![vec![value, low]])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+fn main() {
+    report_if_requested("range_check", &RangeCheckCircuit { bits: 64 });
+    let value = Fr::from_u128(0x0123_4567_89ab_cdef);
+    assert_eq!(
+        limbs(&value, LIMB_BITS, 9),
+        vec![0xef, 0xcd, 0xab, 0x89, 0x67, 0x45, 0x23, 0x01, 0]
+    );
+    assert_eq!(limbs(&value, 12, 2), vec![0xdef, 0xabc]);
+
+    // whole limbs, and a top limb of 4 bits
+    for bits in [64, 20] {
+        let max = Fr::from_u128((1 << bits) - 1);
+        let over = Fr::from_u128(1 << bits);
+        assert!(run(bits, Fr::zero()), "0 in {} bits", bits);
+        assert!(run(bits, max), "2^{} - 1", bits);
+        assert!(!run(bits, over), "2^{}", bits);
+        // -1 is the largest field element
+        assert!(!run(bits, -Fr::one()), "-1 in {} bits", bits);
+    }
+    assert!(run(64, value));
+    assert!(!run(56, value));
+    assert!(run(57, value));
+
+    // the limb must be the value's, not any table entry
+    let circuit = RangeCheckCircuit { bits: 64 };
+    let k = fit_k::<Fr, _>(&circuit).expect("fit_k");
+    let prover = MockProver::run(k, &circuit, vec![vec![value, Fr::from(0xee)]]).unwrap();
+    assert!(prover.verify().is_err());
+}
@@ -0,0 +1,155 @@
+//! Counts the RISC-V instructions the byte-slice verifier takes for the Poseidon and the
+//! multiplication proofs, the half of on-chain feasibility the printed sizes leave out. Writes
+//! each proof's vk, params, instances and proof under the fixture directory, then runs the
+//! `ckb-vm-bench` guest on them under `qemu-riscv64` with QEMU's `insn` plugin. CKB-VM charges
+//! about one cycle per instruction, more for multiplication and memory, so the counts are a
+//! lower bound on its cycles. `make riscv-bench` builds the guest and sets the paths:
+//!
+//! - `RISCV_GUEST`: the guest binary; without it only the fixtures are written
+//! - `QEMU`: the emulator, `qemu-riscv64` by default
+//! - `QEMU_INSN_PLUGIN`: QEMU's `libinsn.so`
+
+use std::{
+    env, fs,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use halo2_playground::{
+    audit::fit_k,
+    circuits::mul::MulCircuit,
+    entropy::SeededEntropy,
+    onchain::{encode_columns, PoseidonCircuit},
+    prover::{run_proof, ProofOptions},
+    storage::cached_params,
+};
+use halo2_proofs::{
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk, Circuit},
+    poly::commitment::Params,
+};
+
+const SEED: u64 = 0x0c4a_11ed;
+
+/// Proves `circuit` and writes `<name>.{vk,params,instances,proof}` under `dir`, returning
+/// their sizes in that order.
+fn write_fixture<C: Circuit<Fr>>(
+    dir: &Path,
+    name: &str,
+    circuit: C,
+    instance: Vec<Fr>,
+) -> [usize; 4] {
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &[&instance],
+        SeededEntropy::new(SEED),
+        ProofOptions::default(),
+    )
+    .expect("run_proof");
+    let mut vk_bytes = vec![];
+    pk.get_vk().write(&mut vk_bytes).expect("write vk");
+    let mut params_bytes = vec![];
+    params.write(&mut params_bytes).expect("write params");
+    let files = [
+        ("vk", vk_bytes),
+        ("params", params_bytes),
+        ("instances", encode_columns(&[instance])),
+        ("proof", proof),
+    ];
+    files.map(|(ext, bytes)| {
+        let path = dir.join(format!("{}.{}", name, ext));
+        fs::write(&path, &bytes).unwrap_or_else(|e| panic!("{}: {}", path.display(), e));
+        bytes.len()
+    })
+}
+
+/// The instructions the guest retired verifying `name`'s fixture, from the `insn` plugin's
+/// `insns: N` line.
+fn count_instructions(guest: &Path, plugin: &str, name: &str, dir: &Path) -> Result<u64, String> {
+    let qemu = env::var("QEMU").unwrap_or_else(|_| "qemu-riscv64".to_string());
+    let output = Command::new(&qemu)
+        .args(["-plugin", plugin, "-d", "plugin"])
+        .arg(guest)
+        .arg(name)
+        .arg(dir)
+        .output()
+        .map_err(|e| format!("{}: {}", qemu, e))?;
+    if !output.status.success() {
+        return Err(format!(
+            "the guest didn't verify {} ({})",
+            name, output.status
+        ));
+    }
+    let log = String::from_utf8_lossy(&output.stderr);
+    log.lines()
+        .filter_map(|line| line.trim().strip_prefix("insns:"))
+        .last()
+        .and_then(|count| count.trim().parse().ok())
+        .ok_or_else(|| "no instruction count in the plugin output".to_string())
+}
+
+fn main() {
+    let dir = PathBuf::from(
+        env::args()
+            .nth(1)
+            .unwrap_or_else(|| "target/riscv-bench".to_string()),
+    );
+    fs::create_dir_all(&dir).expect("create fixture dir");
+
+    let message = [Fr::from(3), Fr::from(5)];
+    let (a, b) = (Fr::from(3), Fr::from(5));
+    let fixtures = [
+        (
+            "poseidon",
+            write_fixture(
+                &dir,
+                "poseidon",
+                PoseidonCircuit::new(message),
+                vec![PoseidonCircuit::hash(message)],
+            ),
+        ),
+        (
+            "mul",
+            write_fixture(
+                &dir,
+                "mul",
+                MulCircuit::new(a, b),
+                MulCircuit::instance(a * b),
+            ),
+        ),
+    ];
+
+    let guest = env::var_os("RISCV_GUEST").map(PathBuf::from);
+    let plugin = env::var("QEMU_INSN_PLUGIN").ok();
+    println!(
+        "{:<10} {:>8} {:>10} {:>9} {:>7} {:>14}",
+        "circuit", "vk", "params", "instances", "proof", "instructions"
+    );
+    for (name, [vk, params, instances, proof]) in fixtures {
+        let count = match (&guest, &plugin) {
+            (Some(guest), Some(plugin)) => match count_instructions(guest, plugin, name, &dir) {
+                Ok(count) => count.to_string(),
+                Err(err) => {
+                    eprintln!("{}: {}", name, err);
+                    std::process::exit(1);
+                }
+            },
+            _ => "-".to_string(),
+        };
+        println!(
+            "{:<10} {:>8} {:>10} {:>9} {:>7} {:>14}",
+            name, vk, params, instances, proof, count
+        );
+    }
+    if guest.is_none() || plugin.is_none() {
+        println!(
+            "fixtures in {}; set RISCV_GUEST and QEMU_INSN_PLUGIN to count instructions",
+            dir.display()
+        );
+    }
+}
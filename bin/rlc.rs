@@ -0,0 +1,170 @@
+use halo2_playground::{
+    audit::fit_k,
+    challenges::proof_challenges,
+    chips::rlc::{rlc, RlcChip, RlcConfig},
+    entropy::SeededEntropy,
+    prover::{run_proof, ProofOptions},
+    report::report_if_requested,
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk, Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+const N: usize = 6;
+
+#[derive(Clone, Debug)]
+struct Config {
+    rlc: RlcConfig,
+    advice: Column<Advice>,
+    instance: Column<Instance>,
+}
+
+/// Proves that a private list equals the public one in instance rows `0..N` with a single
+/// equality: both are combined under the second-phase challenge.
+#[derive(Clone)]
+struct ListEqualityCircuit {
+    list: Value<[Fr; N]>,
+}
+
+impl Default for ListEqualityCircuit {
+    fn default() -> Self {
+        Self {
+            list: Value::unknown(),
+        }
+    }
+}
+
+impl Circuit<Fr> for ListEqualityCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+        Config {
+            rlc: RlcChip::configure(meta),
+            advice,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let chip = RlcChip::construct(config.rlc);
+        // the prover runs this once per phase; in the first the challenge is unknown, and
+        // only the first-phase columns are committed
+        let (private, public) = layouter.assign_region(
+            || "load lists",
+            |mut region| {
+                let private = (0..N)
+                    .map(|i| {
+                        let value = self.list.map(|list| list[i]);
+                        region.assign_advice(|| "private", config.advice, i, || value)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let public = (0..N)
+                    .map(|i| {
+                        region.assign_advice_from_instance(
+                            || "public",
+                            config.instance,
+                            i,
+                            config.advice,
+                            N + i,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok((private, public))
+            },
+        )?;
+        let private = chip.combine(layouter.namespace(|| "private rlc"), &private)?;
+        let public = chip.combine(layouter.namespace(|| "public rlc"), &public)?;
+        layouter.assign_region(
+            || "equal",
+            |mut region| region.constrain_equal(private.cell(), public.cell()),
+        )
+    }
+}
+
+fn run(circuit: &ListEqualityCircuit, public: &[Fr]) -> bool {
+    let k = fit_k::<Fr, _>(circuit).expect("fit_k");
+    MockProver::run(k, circuit, vec![public.to_vec()])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+fn main() {
+    let list: [Fr; N] = [3, 1, 4, 1, 5, 9].map(Fr::from);
+    let circuit = ListEqualityCircuit {
+        list: Value::known(list),
+    };
+    report_if_requested("rlc", &circuit);
+
+    assert!(run(&circuit, &list));
+    let mut changed = list;
+    changed[4] += Fr::one();
+    assert!(!run(&circuit, &changed));
+    // the combination depends on the order
+    let mut swapped = list;
+    swapped.swap(0, 1);
+    assert!(!run(&circuit, &swapped));
+    assert_eq!(rlc(&[Fr::from(2), Fr::from(3)], Fr::from(10)), Fr::from(23));
+
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &ListEqualityCircuit::default()).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &ListEqualityCircuit::default()).expect("keygen_pk");
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &[&list],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[&list],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+    assert!(check_proof(
+        &params,
+        pk.get_vk(),
+        &[&changed],
+        &proof,
+        ProofOptions::default()
+    )
+    .into_result()
+    .is_err());
+
+    // the challenge the proof used, replayed from its first-phase commitments
+    let (challenges, _) =
+        proof_challenges(pk.get_vk(), &[&[&list]], &proof).expect("proof_challenges");
+    assert_eq!(challenges.user.len(), 1);
+    assert_ne!(challenges.user[0], Fr::zero());
+    println!(
+        "rlc of {} values proven, {}-byte proof, challenge {:?}",
+        N,
+        proof.len(),
+        challenges.user[0]
+    );
+}
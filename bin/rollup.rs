@@ -0,0 +1,556 @@
+use std::convert::TryInto;
+
+use halo2_gadgets::poseidon::{primitives::ConstantLength, Hash, Pow5Chip};
+use halo2_playground::{
+    audit::fit_k,
+    chips::{
+        branch::{BranchChip, BranchConfig, Condition},
+        ecc::EccChip,
+        range_check::RangeCheckConfig,
+        standard_plonk::{StandardPlonkChip, StandardPlonkConfig},
+        uint::{UintChip, Width},
+    },
+    circuits::{
+        poseidon::PoseidonHashCircuit,
+        schnorr::{assert_signature, public_key, sign, verify, Point, SchnorrConfig, Signature},
+    },
+    entropy::SeededEntropy,
+    merkle::{MerklePath, MerkleTree},
+    poseidon::MySpec,
+    prover::{run_proof, ProofOptions},
+    report::{report_if_requested, CostReport},
+    schema::{DescribeInstances, Encoding, InstanceLayout},
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::{
+        bn256::{Fq, Fr},
+        group::ff::PrimeField,
+    },
+    plonk::{keygen_pk, keygen_vk, Circuit, ConstraintSystem, Error},
+};
+
+const WIDTH: usize = 3;
+const RATE: usize = 2;
+/// Four accounts.
+const DEPTH: usize = 2;
+/// Transfers per batch.
+const N: usize = 2;
+
+fn poseidon<const L: usize>(message: [Fr; L]) -> Fr {
+    PoseidonHashCircuit::<Fr, MySpec<WIDTH, RATE>, WIDTH, RATE, L>::hash(message)
+}
+
+/// A balance is a field element so that forced transfers can wrap as they would in a circuit
+/// without range checks; the rollup only ever accepts `u64`s.
+#[derive(Clone, Copy, Debug)]
+struct Account {
+    public: Point,
+    balance: Fr,
+    nonce: u64,
+}
+
+fn as_u64(value: Fr) -> Option<u64> {
+    let repr = value.to_repr();
+    repr[8..]
+        .iter()
+        .all(|byte| *byte == 0)
+        .then(|| u64::from_le_bytes(repr[..8].try_into().unwrap()))
+}
+
+impl Account {
+    fn leaf(&self) -> Fr {
+        poseidon([
+            self.public.x,
+            self.public.y,
+            self.balance,
+            Fr::from(self.nonce),
+        ])
+    }
+}
+
+/// What the sender signs: their next nonce, so a transfer can't be replayed, the recipient's
+/// key and the amount.
+fn message(nonce: u64, to: &Point, amount: u64) -> Fr {
+    poseidon([Fr::from(nonce), to.x, to.y, Fr::from(amount)])
+}
+
+#[derive(Clone, Copy, Debug)]
+struct Transfer {
+    from: usize,
+    to: usize,
+    amount: u64,
+    signature: Signature,
+}
+
+/// Both accounts before a transfer, with the sender's path in the tree before it and the
+/// recipient's in the tree between debiting the sender and crediting them.
+#[derive(Clone, Debug)]
+struct Step {
+    transfer: Transfer,
+    sender: Account,
+    sender_path: MerklePath,
+    recipient: Account,
+    recipient_path: MerklePath,
+}
+
+/// The operator's view of the state: every account, committed to by a Merkle tree of their
+/// leaves.
+#[derive(Clone, Debug)]
+struct Rollup {
+    accounts: Vec<Account>,
+}
+
+impl Rollup {
+    fn tree(&self) -> MerkleTree {
+        let leaves = self.accounts.iter().map(Account::leaf).collect::<Vec<_>>();
+        MerkleTree::new(&leaves, DEPTH)
+    }
+
+    fn root(&self) -> Fr {
+        self.tree().root()
+    }
+
+    /// Applies `transfer` if the sequencer would accept it.
+    fn apply(&mut self, transfer: Transfer) -> Result<Step, &'static str> {
+        let sender = self.accounts[transfer.from];
+        let to = self.accounts[transfer.to].public;
+        if !verify(
+            &sender.public,
+            message(sender.nonce, &to, transfer.amount),
+            &transfer.signature,
+        ) {
+            return Err("bad signature");
+        }
+        let amount = Fr::from(transfer.amount);
+        as_u64(sender.balance - amount).ok_or("insufficient balance")?;
+        as_u64(self.accounts[transfer.to].balance + amount).ok_or("balance overflow")?;
+        Ok(self.apply_unchecked(transfer))
+    }
+
+    /// Applies `transfer` as the circuit would, wrapping where it must not.
+    fn apply_unchecked(&mut self, transfer: Transfer) -> Step {
+        let sender = self.accounts[transfer.from];
+        let sender_path = self.tree().path(transfer.from);
+        let debited = &mut self.accounts[transfer.from];
+        debited.balance -= Fr::from(transfer.amount);
+        debited.nonce += 1;
+        let recipient = self.accounts[transfer.to];
+        let recipient_path = self.tree().path(transfer.to);
+        let credited = &mut self.accounts[transfer.to];
+        credited.balance += Fr::from(transfer.amount);
+        Step {
+            transfer,
+            sender,
+            sender_path,
+            recipient,
+            recipient_path,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Config {
+    schnorr: SchnorrConfig,
+    branch: BranchConfig,
+    plonk: StandardPlonkConfig,
+    uint: RangeCheckConfig,
+}
+
+/// Proves a batch of `N` transfers taking the account tree with root instance row 0 to the
+/// root in row 1. Each transfer is signed by the sender over their nonce, which it bumps, and
+/// debits and credits `u64` balances, range checked so neither can wrap around the field.
+#[derive(Clone, Debug)]
+struct RollupCircuit {
+    steps: Value<Vec<Step>>,
+}
+
+impl Default for RollupCircuit {
+    fn default() -> Self {
+        Self {
+            steps: Value::unknown(),
+        }
+    }
+}
+
+impl RollupCircuit {
+    fn new(steps: Vec<Step>) -> Self {
+        assert_eq!(steps.len(), N, "a batch of {} transfers", N);
+        Self {
+            steps: Value::known(steps),
+        }
+    }
+
+    fn instance(old_root: Fr, new_root: Fr) -> Vec<Fr> {
+        vec![old_root, new_root]
+    }
+}
+
+impl DescribeInstances for RollupCircuit {
+    fn instance_layout() -> InstanceLayout {
+        InstanceLayout::new(format!("rollup ({} transfers)", N))
+            .field(
+                0,
+                "old root",
+                Encoding::Field,
+                "account tree before the batch",
+            )
+            .field(
+                0,
+                "new root",
+                Encoding::Field,
+                "account tree after the batch",
+            )
+    }
+}
+
+fn hash<const L: usize>(
+    config: &Config,
+    mut layouter: impl Layouter<Fr>,
+    message: [AssignedCell<Fr, Fr>; L],
+) -> Result<AssignedCell<Fr, Fr>, Error> {
+    let hasher = Hash::<_, _, MySpec<WIDTH, RATE>, ConstantLength<L>, WIDTH, RATE>::init(
+        Pow5Chip::construct(config.schnorr.poseidon.clone()),
+        layouter.namespace(|| "init"),
+    )?;
+    hasher.hash(layouter.namespace(|| "hash"), message)
+}
+
+/// The roots the old and the new leaf hash up to along the same path.
+fn update(
+    config: &Config,
+    layouter: &mut impl Layouter<Fr>,
+    path: Value<&MerklePath>,
+    old_leaf: AssignedCell<Fr, Fr>,
+    new_leaf: AssignedCell<Fr, Fr>,
+) -> Result<(AssignedCell<Fr, Fr>, AssignedCell<Fr, Fr>), Error> {
+    let branch = BranchChip::construct(config.branch.clone());
+    let siblings = layouter.assign_region(
+        || "load siblings",
+        |mut region| {
+            (0..DEPTH)
+                .map(|level| {
+                    region.assign_advice(
+                        || format!("sibling {}", level),
+                        config.schnorr.load,
+                        level,
+                        || path.map(|path| path.siblings[level]),
+                    )
+                })
+                .collect::<Result<Vec<_>, _>>()
+        },
+    )?;
+    let (mut old, mut new) = (old_leaf, new_leaf);
+    for (level, sibling) in siblings.iter().enumerate() {
+        let (_, pairs) = branch.select_all(
+            layouter.namespace(|| format!("order level {}", level)),
+            Condition::Witness(path.map(|path| path.directions[level])),
+            &[
+                (sibling, &old),
+                (&old, sibling),
+                (sibling, &new),
+                (&new, sibling),
+            ],
+        )?;
+        let [old_left, old_right, new_left, new_right]: [AssignedCell<Fr, Fr>; 4] =
+            pairs.try_into().unwrap();
+        old = hash(
+            config,
+            layouter.namespace(|| format!("old level {}", level)),
+            [old_left, old_right],
+        )?;
+        new = hash(
+            config,
+            layouter.namespace(|| format!("new level {}", level)),
+            [new_left, new_right],
+        )?;
+    }
+    Ok((old, new))
+}
+
+impl Circuit<Fr> for RollupCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        // the balances' range checks share the signatures' byte table
+        let mut schnorr = SchnorrConfig::configure(meta);
+        let uint = UintChip::configure(meta, &mut schnorr.tables);
+        Config {
+            schnorr,
+            branch: BranchChip::configure(meta),
+            plonk: StandardPlonkChip::configure(meta),
+            uint,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let ecc = EccChip::construct(config.schnorr.ecc.clone());
+        let plonk = StandardPlonkChip::construct(config.plonk.clone());
+        let uint = UintChip::construct(config.uint.clone());
+        config.schnorr.tables.load(&mut layouter)?;
+        let one = plonk.assign_constant(layouter.namespace(|| "one"), Fr::one())?;
+
+        let mut root: Option<AssignedCell<Fr, Fr>> = None;
+        for i in 0..N {
+            let mut layouter = layouter.namespace(|| format!("transfer {}", i));
+            let step = self.steps.as_ref().map(|steps| &steps[i]);
+            let coordinates = |account: &Account| (account.public.x, account.public.y);
+            let sender = ecc.witness(
+                layouter.namespace(|| "sender"),
+                step.map(|step| coordinates(&step.sender)),
+            )?;
+            let recipient = ecc.witness(
+                layouter.namespace(|| "recipient"),
+                step.map(|step| coordinates(&step.recipient)),
+            )?;
+            let [balance, nonce, amount, recipient_balance, recipient_nonce]: [AssignedCell<Fr, Fr>;
+                5] = layouter.assign_region(
+                || "load accounts",
+                |mut region| {
+                    let witnesses = [
+                        ("balance", step.map(|step| step.sender.balance)),
+                        ("nonce", step.map(|step| Fr::from(step.sender.nonce))),
+                        ("amount", step.map(|step| Fr::from(step.transfer.amount))),
+                        ("recipient balance", step.map(|step| step.recipient.balance)),
+                        (
+                            "recipient nonce",
+                            step.map(|step| Fr::from(step.recipient.nonce)),
+                        ),
+                    ];
+                    let cells = witnesses
+                        .iter()
+                        .enumerate()
+                        .map(|(row, (name, value))| {
+                            region.assign_advice(|| *name, config.schnorr.load, row, || *value)
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Ok(cells.try_into().unwrap())
+                },
+            )?;
+
+            let message = hash(
+                &config,
+                layouter.namespace(|| "message"),
+                [
+                    nonce.clone(),
+                    recipient.x.clone(),
+                    recipient.y.clone(),
+                    amount.clone(),
+                ],
+            )?;
+            assert_signature(
+                &config.schnorr,
+                &mut layouter,
+                &sender,
+                message,
+                step.map(|step| step.transfer.signature),
+            )?;
+
+            // debit the sender and bump their nonce
+            uint.check(layouter.namespace(|| "amount"), &amount, Width::U64)?;
+            let debited = plonk.sub(layouter.namespace(|| "debit"), &balance, &amount)?;
+            uint.check(layouter.namespace(|| "debited"), &debited, Width::U64)?;
+            let next_nonce = plonk.add(layouter.namespace(|| "next nonce"), &nonce, &one)?;
+            let old_leaf = hash(
+                &config,
+                layouter.namespace(|| "sender leaf"),
+                [sender.x.clone(), sender.y.clone(), balance, nonce],
+            )?;
+            let new_leaf = hash(
+                &config,
+                layouter.namespace(|| "debited leaf"),
+                [sender.x, sender.y, debited, next_nonce],
+            )?;
+            let (before, debited_root) = update(
+                &config,
+                &mut layouter,
+                step.map(|step| &step.sender_path),
+                old_leaf,
+                new_leaf,
+            )?;
+            match &root {
+                None => layouter.constrain_instance(before.cell(), config.schnorr.instance, 0)?,
+                Some(root) => {
+                    plonk.constrain_equal(layouter.namespace(|| "chain"), root, &before)?
+                }
+            }
+
+            // credit the recipient
+            let credited =
+                plonk.add(layouter.namespace(|| "credit"), &recipient_balance, &amount)?;
+            uint.check(layouter.namespace(|| "credited"), &credited, Width::U64)?;
+            let old_leaf = hash(
+                &config,
+                layouter.namespace(|| "recipient leaf"),
+                [
+                    recipient.x.clone(),
+                    recipient.y.clone(),
+                    recipient_balance,
+                    recipient_nonce.clone(),
+                ],
+            )?;
+            let new_leaf = hash(
+                &config,
+                layouter.namespace(|| "credited leaf"),
+                [recipient.x, recipient.y, credited, recipient_nonce],
+            )?;
+            let (between, after) = update(
+                &config,
+                &mut layouter,
+                step.map(|step| &step.recipient_path),
+                old_leaf,
+                new_leaf,
+            )?;
+            plonk.constrain_equal(layouter.namespace(|| "between"), &debited_root, &between)?;
+            root = Some(after);
+        }
+        let root = root.expect("a nonempty batch");
+        layouter.constrain_instance(root.cell(), config.schnorr.instance, 1)
+    }
+}
+
+fn run(circuit: &RollupCircuit, instance: Vec<Fr>) -> bool {
+    let k = fit_k::<Fr, _>(circuit).expect("fit_k");
+    MockProver::run(k, circuit, vec![instance])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+fn main() {
+    report_if_requested("rollup", &RollupCircuit::default());
+    let secrets = [0xa11ce, 0xb0b, 0xca201, 0xd0d].map(Fq::from);
+    let genesis = Rollup {
+        accounts: secrets
+            .iter()
+            .zip([1000, 50, 0, u64::MAX - 10])
+            .map(|(secret, balance)| Account {
+                public: public_key(secret),
+                balance: Fr::from(balance),
+                nonce: 0,
+            })
+            .collect(),
+    };
+    let signed = |state: &Rollup, from: usize, to: usize, amount: u64| {
+        let nonce = state.accounts[from].nonce;
+        let to_key = state.accounts[to].public;
+        Transfer {
+            from,
+            to,
+            amount,
+            signature: sign(
+                &secrets[from],
+                message(nonce, &to_key, amount),
+                &Fq::from(0x0dd_ba11 + 7 * nonce + from as u64),
+            ),
+        }
+    };
+
+    // alice pays bob 300, then bob pays carol 320 out of his new balance
+    let mut state = genesis.clone();
+    let first = signed(&state, 0, 1, 300);
+    let mut steps = vec![state.apply(first).expect("first transfer")];
+    let second = signed(&state, 1, 2, 320);
+    steps.push(state.apply(second).expect("second transfer"));
+    assert_eq!(
+        state
+            .accounts
+            .iter()
+            .map(|account| account.balance)
+            .collect::<Vec<_>>(),
+        [700, 30, 320, u64::MAX - 10].map(Fr::from)
+    );
+    let batch = RollupCircuit::new(steps.clone());
+    let instance = RollupCircuit::instance(genesis.root(), state.root());
+    assert!(run(&batch, instance.clone()));
+    assert!(!run(
+        &batch,
+        RollupCircuit::instance(genesis.root(), genesis.root())
+    ));
+
+    // the operator rejects a replay, an overdraft and a forgery, and so does the circuit
+    assert_eq!(state.clone().apply(first).unwrap_err(), "bad signature");
+    let mut forced = genesis.clone();
+    forced.apply(first).unwrap();
+    let replay = forced.apply_unchecked(first);
+    let circuit = RollupCircuit::new(vec![steps[0].clone(), replay]);
+    assert!(!run(
+        &circuit,
+        RollupCircuit::instance(genesis.root(), forced.root())
+    ));
+    for (from, to, amount, why) in [
+        (1, 0, 1_000, "insufficient balance"),
+        (0, 3, 11, "balance overflow"),
+    ] {
+        let transfer = signed(&state, from, to, amount);
+        assert_eq!(state.clone().apply(transfer).unwrap_err(), why);
+        // forced after the batch's first transfer, wrapping around the field
+        let mut forced = genesis.clone();
+        forced.apply(first).unwrap();
+        let bad = forced.apply_unchecked(signed(&forced, from, to, amount));
+        let circuit = RollupCircuit::new(vec![steps[0].clone(), bad]);
+        assert!(
+            !run(
+                &circuit,
+                RollupCircuit::instance(genesis.root(), forced.root())
+            ),
+            "{}",
+            why
+        );
+    }
+    let mut forged = second;
+    forged.signature.s += Fq::one();
+    let mut forging = genesis.clone();
+    forging.apply(first).unwrap();
+    assert_eq!(forging.clone().apply(forged).unwrap_err(), "bad signature");
+    let bad = forging.apply_unchecked(forged);
+    let circuit = RollupCircuit::new(vec![steps[0].clone(), bad]);
+    assert!(!run(&circuit, instance.clone()));
+
+    let params = cached_params(fit_k(&batch).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &RollupCircuit::default()).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &RollupCircuit::default()).expect("keygen_pk");
+    let proof = run_proof(
+        &params,
+        &pk,
+        batch.clone(),
+        &[&instance],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[&instance],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+    let report = CostReport::measure(&batch).expect("measure");
+    println!(
+        "{} transfers over {} accounts: k = {}, {} rows, {}-byte proof",
+        N,
+        1 << DEPTH,
+        report.k,
+        report
+            .rows_used
+            .map(|rows| rows.to_string())
+            .unwrap_or_default(),
+        proof.len()
+    );
+}
@@ -0,0 +1,90 @@
+use halo2_playground::chips::rotate::{RotateChip, RotateConfig, WordOp};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+#[derive(Clone, Debug)]
+struct Config {
+    rotate: RotateConfig,
+    advice: Column<Advice>,
+    instance: Column<Instance>,
+}
+
+/// Applies `op` to the word in instance row 0 and exposes the result in instance row 1.
+#[derive(Clone)]
+struct WordOpCircuit {
+    bits: usize,
+    op: WordOp,
+}
+
+impl<F: FieldExt> Circuit<F> for WordOpCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+        Config {
+            rotate: RotateChip::configure(meta),
+            advice,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = RotateChip::construct(config.rotate);
+        chip.load(&mut layouter)?;
+        let word = layouter.assign_region(
+            || "load word",
+            |mut region| {
+                region.assign_advice_from_instance(|| "word", config.instance, 0, config.advice, 0)
+            },
+        )?;
+        let out = chip.assign(layouter.namespace(|| "op"), &word, self.bits, self.op)?;
+        layouter.constrain_instance(out.cell(), config.instance, 1)
+    }
+}
+
+fn main() {
+    let k = 9;
+    let pattern = 0x0123_4567_89ab_cdefu64;
+    let mut checked = 0;
+    for bits in [32, 64] {
+        let word = WordOp::ShiftRight(0).apply(pattern, bits);
+        for n in 0..bits {
+            for op in [
+                WordOp::RotateRight(n),
+                WordOp::RotateLeft(n),
+                WordOp::ShiftRight(n),
+                WordOp::ShiftLeft(n),
+            ] {
+                let circuit = WordOpCircuit { bits, op };
+                let expected = op.apply(word, bits);
+                let instances = vec![vec![Fr::from(word), Fr::from(expected)]];
+                let prover = MockProver::run(k, &circuit, instances).unwrap();
+                assert_eq!(prover.verify(), Ok(()), "{:?} on {} bits", op, bits);
+
+                let instances = vec![vec![Fr::from(word), Fr::from(expected ^ 1)]];
+                let prover = MockProver::run(k, &circuit, instances).unwrap();
+                assert!(prover.verify().is_err(), "{:?} on {} bits", op, bits);
+                checked += 1;
+            }
+        }
+    }
+    println!("checked {} word operations", checked);
+}
@@ -0,0 +1,101 @@
+use std::time::Instant;
+
+use halo2_playground::{
+    circuits::rsa::{self, verify, PrivateKey, RsaCircuit, LIMBS},
+    entropy::SeededEntropy,
+    prover::{run_proof, ProofOptions},
+    report::{report_if_requested, CostReport},
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+use sha2::{Digest, Sha256};
+
+fn main() {
+    report_if_requested("rsa", &RsaCircuit::default());
+
+    let key = PrivateKey::generate(&mut SeededEntropy::new(0x7273_6132_3034_38));
+    assert_eq!(key.n.bits() as usize, rsa::MODULUS_BITS);
+    let hash: [u8; 32] = Sha256::digest(b"message").into();
+    let signature = key.sign(&hash);
+    assert!(verify(&key.n, &hash, &signature));
+    let forged = &signature + 1u32;
+    assert!(!verify(&key.n, &hash, &forged));
+    // s + n has the same power modulo n but isn't below it
+    assert!(!verify(&key.n, &hash, &(&signature + &key.n)));
+
+    let circuit = RsaCircuit::new(signature);
+    let instance = RsaCircuit::instance::<Fr>(&key.n, &hash);
+    let report = CostReport::measure(&circuit).expect("measure rsa");
+    let k = report.k;
+    let prover = MockProver::run(k, &circuit, vec![instance.clone()]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+    let prover = MockProver::run(k, &RsaCircuit::new(forged), vec![instance.clone()]).unwrap();
+    assert!(prover.verify().is_err());
+    // the signature of one message doesn't pass for another
+    let mut other = instance.clone();
+    other[LIMBS] += Fr::one();
+    let prover = MockProver::run(k, &circuit, vec![other.clone()]).unwrap();
+    assert!(prover.verify().is_err());
+    println!(
+        "rsa-2048 modexp (e = {}): k = {}, {} of {} usable rows, public inputs n and the hash \
+         in 64-bit limbs",
+        rsa::EXPONENT,
+        k,
+        report
+            .rows_used
+            .map_or_else(|| "?".to_string(), |rows| rows.to_string()),
+        report.usable_rows()
+    );
+    println!("verifier cost:\n{}", report);
+
+    if !std::env::args().skip(1).any(|arg| arg == "--prove") {
+        println!("run with --prove for a real proof and its verification time");
+        return;
+    }
+    let params = cached_params(k).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let start = Instant::now();
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &[&instance],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    let prove = start.elapsed();
+    let start = Instant::now();
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[&instance],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+    let verify = start.elapsed();
+    assert!(check_proof(
+        &params,
+        pk.get_vk(),
+        &[&other],
+        &proof,
+        ProofOptions::default()
+    )
+    .into_result()
+    .is_err());
+    println!(
+        "proof of {} bytes, proved in {:.2?}, verified in {:.2?}",
+        proof.len(),
+        prove,
+        verify
+    );
+}
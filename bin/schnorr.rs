@@ -0,0 +1,107 @@
+use std::time::Instant;
+
+use halo2_playground::{
+    audit::fit_k,
+    circuits::{
+        ecdsa::EcdsaCircuit,
+        schnorr::{public_key, sign, verify, Point, SchnorrCircuit, Signature},
+    },
+    entropy::SeededEntropy,
+    prover::{run_proof, ProofOptions},
+    report::{report_if_requested, CostReport},
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    dev::MockProver,
+    halo2curves::bn256::{Fq, Fr},
+    plonk::{keygen_pk, keygen_vk},
+};
+
+fn main() {
+    report_if_requested("schnorr", &SchnorrCircuit::default());
+
+    let secret = Fq::from(0x5ec2_e7);
+    let nonce = Fq::from(0x0dd_ba11);
+    let message = Fr::from(1000);
+    let public = public_key(&secret);
+    assert!(Point::generator().is_on_curve());
+    assert!(Point::auxiliary().is_on_curve());
+    let signature = sign(&secret, message, &nonce);
+    assert!(verify(&public, message, &signature));
+    let forged = Signature {
+        s: signature.s + Fq::one(),
+        ..signature
+    };
+    assert!(!verify(&public, message, &forged));
+    assert!(!verify(&public, message + Fr::one(), &signature));
+
+    let circuit = SchnorrCircuit::new(signature);
+    let instance = SchnorrCircuit::instance(&public, message);
+    let k = fit_k::<Fr, _>(&circuit).expect("fit_k");
+    let prover = MockProver::run(k, &circuit, vec![instance.clone()]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+    let prover = MockProver::run(k, &SchnorrCircuit::new(forged), vec![instance.clone()]).unwrap();
+    assert!(prover.verify().is_err());
+    let other = SchnorrCircuit::instance(&public, message + Fr::one());
+    let prover = MockProver::run(k, &circuit, vec![other.clone()]).unwrap();
+    assert!(prover.verify().is_err());
+    // a key that isn't on the curve
+    let mut off_curve = instance.clone();
+    off_curve[1] += Fr::one();
+    let prover = MockProver::run(k, &circuit, vec![off_curve]).unwrap();
+    assert!(prover.verify().is_err());
+
+    let native = CostReport::measure(&circuit).expect("measure schnorr");
+    let nonnative = CostReport::measure(&EcdsaCircuit::default()).expect("measure ecdsa");
+    for (name, report) in [
+        ("schnorr over grumpkin", &native),
+        ("ecdsa over secp256k1", &nonnative),
+    ] {
+        println!(
+            "{:<22} k = {:>2}, {:>7} rows, ~{} proof bytes",
+            name,
+            report.k,
+            report
+                .rows_used
+                .map(|rows| rows.to_string())
+                .unwrap_or_default(),
+            report.proof_bytes
+        );
+    }
+
+    let params = cached_params(k).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let start = Instant::now();
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &[&instance],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    let prove = start.elapsed();
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[&instance],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+    assert!(check_proof(
+        &params,
+        pk.get_vk(),
+        &[&other],
+        &proof,
+        ProofOptions::default()
+    )
+    .into_result()
+    .is_err());
+    println!("proof of {} bytes, proved in {:.2?}", proof.len(), prove);
+}
@@ -0,0 +1,123 @@
+use std::time::{Duration, Instant};
+
+use halo2_playground::{
+    audit::fit_k,
+    circuits::{
+        poseidon::PoseidonHashCircuit,
+        sha256::{sha256, Sha256Circuit},
+    },
+    entropy::SeededEntropy,
+    fmt::to_hex,
+    poseidon::MySpec,
+    prover::{run_proof, ProofOptions},
+    report::{report_if_requested, CostReport},
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk, Circuit},
+};
+
+type Poseidon = PoseidonHashCircuit<Fr, MySpec<3, 2>, 3, 2, 2>;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Proves and verifies once, checking that a changed instance is rejected.
+fn prove<C: Circuit<Fr> + Clone>(circuit: &C, instance: &[Fr]) -> (usize, Duration) {
+    let params = cached_params(fit_k(circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, circuit).expect("keygen_pk");
+    let start = Instant::now();
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit.clone(),
+        &[instance],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    let prove = start.elapsed();
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[instance],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+    let mut tampered = instance.to_vec();
+    tampered[0] += Fr::one();
+    assert!(check_proof(
+        &params,
+        pk.get_vk(),
+        &[&tampered],
+        &proof,
+        ProofOptions::default()
+    )
+    .into_result()
+    .is_err());
+    (proof.len(), prove)
+}
+
+fn main() {
+    report_if_requested("sha256", &Sha256Circuit::<32>::default());
+
+    // FIPS 180-2 vectors for the off-circuit reference
+    assert_eq!(
+        hex(&sha256(b"")),
+        "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855"
+    );
+    assert_eq!(
+        hex(&sha256(b"abc")),
+        "ba7816bf8f01cfea414140de5dae2223b00361a396177a9cb410ff61f20015ad"
+    );
+
+    // two blocks, since the padding doesn't fit after 56 bytes
+    let preimage = *b"abcdbcdecdefdefgefghfghighijhijkijkljklmklmnlmnomnopnopq";
+    let digest = sha256(&preimage);
+    assert_eq!(
+        hex(&digest),
+        "248d6a61d20638b8e5c026930c3e6039a33ce45964ff2167f6ecedd419db06c1"
+    );
+    let circuit = Sha256Circuit::new(preimage);
+    let instance = Sha256Circuit::<56>::instance::<Fr>(&digest);
+    let k = fit_k::<Fr, _>(&circuit).expect("fit_k");
+    let prover = MockProver::run(k, &circuit, vec![instance.clone()]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+    let mut other = preimage;
+    other[0] ^= 1;
+    let prover = MockProver::run(k, &Sha256Circuit::new(other), vec![instance]).unwrap();
+    assert!(prover.verify().is_err());
+    println!("sha256 of 56 bytes, 2 blocks: k = {}", k);
+
+    let preimage = [0x5a; 32];
+    let digest = sha256(&preimage);
+    let circuit = Sha256Circuit::new(preimage);
+    let (sha_bytes, sha_prove) = prove(&circuit, &Sha256Circuit::<32>::instance(&digest));
+    let sha_report = CostReport::measure(&circuit).expect("measure");
+    println!("sha256 of 32 bytes = {}", hex(&digest));
+
+    let message = [Fr::from(1), Fr::from(2)];
+    let poseidon = Poseidon::new(message);
+    let hash = Poseidon::hash(message);
+    let (poseidon_bytes, poseidon_prove) = prove(&poseidon, &[hash]);
+    let poseidon_report = CostReport::measure(&poseidon).expect("measure");
+    println!("poseidon of 2 elements = {}", to_hex(&hash));
+
+    for (name, report, bytes, time) in [
+        ("sha256", sha_report, sha_bytes, sha_prove),
+        ("poseidon", poseidon_report, poseidon_bytes, poseidon_prove),
+    ] {
+        println!(
+            "  {:<9} k = {:>2}, {} advice columns, proof {:>5} bytes, proved in {:.2?}",
+            name, report.k, report.advice_columns, bytes, time
+        );
+    }
+}
@@ -0,0 +1,376 @@
+use std::convert::TryInto;
+
+use halo2_gadgets::poseidon::{primitives::ConstantLength, Hash, Pow5Chip, Pow5Config};
+use halo2_playground::{
+    audit::fit_k,
+    chips::{
+        branch::{BranchChip, BranchConfig, Condition},
+        range_check::RangeCheckConfig,
+        tables::Tables,
+        uint::{UintChip, Width},
+    },
+    circuits::poseidon::PoseidonHashCircuit,
+    entropy::SeededEntropy,
+    merkle::{hash_pair, MerklePath, MerkleTree},
+    poseidon::MySpec,
+    prover::{run_proof, ProofOptions},
+    report::report_if_requested,
+    schema::{DescribeInstances, Encoding, InstanceLayout},
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::{Field, FieldExt},
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk, Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+const WIDTH: usize = 3;
+const RATE: usize = 2;
+const DEPTH: usize = 8;
+
+fn poseidon<const L: usize>(message: [Fr; L]) -> Fr {
+    PoseidonHashCircuit::<Fr, MySpec<WIDTH, RATE>, WIDTH, RATE, L>::hash(message)
+}
+
+/// A note of `value` spendable by whoever knows the secret key behind `owner`.
+#[derive(Clone, Copy, Debug)]
+struct Note {
+    value: Fr,
+    owner: Fr,
+    salt: Fr,
+}
+
+impl Note {
+    fn owner(secret_key: Fr) -> Fr {
+        poseidon([secret_key])
+    }
+
+    fn commitment(&self) -> Fr {
+        poseidon([self.value, self.owner, self.salt])
+    }
+
+    fn nullifier(&self, secret_key: Fr) -> Fr {
+        hash_pair(secret_key, self.commitment())
+    }
+}
+
+#[derive(Clone, Debug)]
+struct Config {
+    load: Column<Advice>,
+    instance: Column<Instance>,
+    branch: BranchConfig,
+    tables: Tables,
+    uint: RangeCheckConfig,
+    poseidon_config: Pow5Config<Fr, WIDTH, RATE>,
+}
+
+/// Spends a note committed to in the tree with root instance row 0 and moves its value into a
+/// new note, whose commitment is row 2. The spender proves they own the note by deriving its
+/// owner from their secret key, and publishes its nullifier in row 1 so it cannot be spent
+/// twice. The value stays private, range-checked to a `u64` so it cannot wrap around the
+/// field.
+#[derive(Clone, Debug)]
+struct TransferCircuit {
+    secret_key: Value<Fr>,
+    note: Value<Note>,
+    path: Value<MerklePath>,
+    recipient: Value<Fr>,
+    salt: Value<Fr>,
+}
+
+impl Default for TransferCircuit {
+    fn default() -> Self {
+        Self {
+            secret_key: Value::unknown(),
+            note: Value::unknown(),
+            path: Value::unknown(),
+            recipient: Value::unknown(),
+            salt: Value::unknown(),
+        }
+    }
+}
+
+impl TransferCircuit {
+    fn new(secret_key: Fr, note: Note, path: MerklePath, recipient: Fr, salt: Fr) -> Self {
+        assert_eq!(path.depth(), DEPTH, "a path of depth {}", DEPTH);
+        Self {
+            secret_key: Value::known(secret_key),
+            note: Value::known(note),
+            path: Value::known(path),
+            recipient: Value::known(recipient),
+            salt: Value::known(salt),
+        }
+    }
+
+    fn instance(root: Fr, nullifier: Fr, output: Fr) -> Vec<Fr> {
+        vec![root, nullifier, output]
+    }
+}
+
+impl DescribeInstances for TransferCircuit {
+    fn instance_layout() -> InstanceLayout {
+        InstanceLayout::new("shielded transfer")
+            .field(0, "root", Encoding::Field, "root of the note tree")
+            .field(
+                0,
+                "nullifier",
+                Encoding::Field,
+                "nullifier of the spent note",
+            )
+            .field(0, "output", Encoding::Field, "commitment to the new note")
+    }
+}
+
+fn hash<const L: usize>(
+    config: &Config,
+    mut layouter: impl Layouter<Fr>,
+    message: [AssignedCell<Fr, Fr>; L],
+) -> Result<AssignedCell<Fr, Fr>, Error> {
+    let hasher = Hash::<_, _, MySpec<WIDTH, RATE>, ConstantLength<L>, WIDTH, RATE>::init(
+        Pow5Chip::construct(config.poseidon_config.clone()),
+        layouter.namespace(|| "init"),
+    )?;
+    hasher.hash(layouter.namespace(|| "hash"), message)
+}
+
+impl Circuit<Fr> for TransferCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let state = (0..WIDTH).map(|_| meta.advice_column()).collect::<Vec<_>>();
+        let partial_sbox = meta.advice_column();
+        let rc_a = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        let rc_b = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        meta.enable_constant(rc_b[0]);
+        let load = meta.advice_column();
+        meta.enable_equality(load);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        let mut tables = Tables::new();
+        let uint = UintChip::configure(meta, &mut tables);
+        Config {
+            load,
+            instance,
+            branch: BranchChip::configure(meta),
+            tables,
+            uint,
+            poseidon_config: Pow5Chip::configure::<MySpec<WIDTH, RATE>>(
+                meta,
+                state.try_into().unwrap(),
+                partial_sbox,
+                rc_a.try_into().unwrap(),
+                rc_b.try_into().unwrap(),
+            ),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let branch = BranchChip::construct(config.branch.clone());
+        let uint = UintChip::construct(config.uint.clone());
+        config.tables.load(&mut layouter)?;
+        let [secret_key, value, salt, recipient, new_salt]: [AssignedCell<Fr, Fr>; 5] = layouter
+            .assign_region(
+                || "load witnesses",
+                |mut region| {
+                    let witnesses = [
+                        ("secret key", self.secret_key),
+                        ("value", self.note.map(|note| note.value)),
+                        ("salt", self.note.map(|note| note.salt)),
+                        ("recipient", self.recipient),
+                        ("new salt", self.salt),
+                    ];
+                    let cells = witnesses
+                        .iter()
+                        .enumerate()
+                        .map(|(row, (name, value))| {
+                            region.assign_advice(|| *name, config.load, row, || *value)
+                        })
+                        .collect::<Result<Vec<_>, _>>()?;
+                    Ok(cells.try_into().unwrap())
+                },
+            )?;
+        let siblings = layouter.assign_region(
+            || "load siblings",
+            |mut region| {
+                (0..DEPTH)
+                    .map(|level| {
+                        region.assign_advice(
+                            || format!("sibling {}", level),
+                            config.load,
+                            level,
+                            || self.path.as_ref().map(|path| path.siblings[level]),
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            },
+        )?;
+
+        // the note is ours and in the tree
+        let owner = hash(
+            &config,
+            layouter.namespace(|| "owner"),
+            [secret_key.clone()],
+        )?;
+        let commitment = hash(
+            &config,
+            layouter.namespace(|| "commitment"),
+            [value.clone(), owner, salt],
+        )?;
+        let mut node = commitment.clone();
+        for (level, sibling) in siblings.iter().enumerate() {
+            let (_, pair) = branch.select_all(
+                layouter.namespace(|| format!("order level {}", level)),
+                Condition::Witness(self.path.as_ref().map(|path| path.directions[level])),
+                &[(sibling, &node), (&node, sibling)],
+            )?;
+            node = hash(
+                &config,
+                layouter.namespace(|| format!("level {}", level)),
+                pair.try_into().unwrap(),
+            )?;
+        }
+        layouter.constrain_instance(node.cell(), config.instance, 0)?;
+
+        let nullifier = hash(
+            &config,
+            layouter.namespace(|| "nullifier"),
+            [secret_key, commitment],
+        )?;
+        layouter.constrain_instance(nullifier.cell(), config.instance, 1)?;
+
+        // the same value goes to the recipient
+        uint.check(layouter.namespace(|| "value"), &value, Width::U64)?;
+        let output = hash(
+            &config,
+            layouter.namespace(|| "output"),
+            [value, recipient, new_salt],
+        )?;
+        layouter.constrain_instance(output.cell(), config.instance, 2)
+    }
+}
+
+fn run(circuit: &TransferCircuit, instance: Vec<Fr>) -> bool {
+    let k = fit_k::<Fr, _>(circuit).expect("fit_k");
+    MockProver::run(k, circuit, vec![instance])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+fn main() {
+    report_if_requested("shielded_transfer", &TransferCircuit::default());
+    let (alice, bob) = (Fr::from(0xa11ce), Fr::from(0xb0b));
+    let note = Note {
+        value: Fr::from(1000),
+        owner: Note::owner(alice),
+        salt: Fr::from(7),
+    };
+    // a note wider than a u64, committed to by a careless wallet
+    let wide = Note {
+        value: Fr::from_u128(1 << 64),
+        owner: Note::owner(alice),
+        salt: Fr::from(8),
+    };
+    let leaves = (0..12)
+        .map(|i| Fr::from(1000 + i))
+        .chain([note.commitment(), wide.commitment()])
+        .collect::<Vec<_>>();
+    let index = leaves.len() - 2;
+    let tree = MerkleTree::new(&leaves, DEPTH);
+    let root = tree.root();
+
+    let to_bob = Note {
+        value: note.value,
+        owner: Note::owner(bob),
+        salt: Fr::from(9),
+    };
+    let circuit = TransferCircuit::new(alice, note, tree.path(index), to_bob.owner, to_bob.salt);
+    let instance = TransferCircuit::instance(root, note.nullifier(alice), to_bob.commitment());
+    assert!(run(&circuit, instance.clone()));
+
+    // spending the note again reveals the same nullifier, which the verifier has seen
+    let again = TransferCircuit::new(
+        alice,
+        note,
+        tree.path(index),
+        Note::owner(alice),
+        Fr::from(10),
+    );
+    let output = Note {
+        owner: Note::owner(alice),
+        salt: Fr::from(10),
+        ..note
+    };
+    let replay = TransferCircuit::instance(root, note.nullifier(alice), output.commitment());
+    assert!(run(&again, replay.clone()));
+    assert_eq!(instance[1], replay[1], "one nullifier per note");
+    // bob cannot spend alice's note, even with a nullifier of his choosing
+    let stolen = TransferCircuit::new(bob, note, tree.path(index), to_bob.owner, to_bob.salt);
+    assert!(!run(&stolen, instance.clone()));
+    assert!(!run(
+        &stolen,
+        TransferCircuit::instance(root, note.nullifier(bob), to_bob.commitment())
+    ));
+    // nor can alice pay out more than the note holds, or spend a note outside the tree
+    let inflated = Note {
+        value: note.value + Fr::one(),
+        ..to_bob
+    };
+    assert!(!run(
+        &circuit,
+        TransferCircuit::instance(root, note.nullifier(alice), inflated.commitment())
+    ));
+    assert!(!run(
+        &circuit,
+        TransferCircuit::instance(root + Fr::one(), instance[1], instance[2])
+    ));
+    // the wide note is in the tree, but its value is out of range
+    let wide_out = Note {
+        value: wide.value,
+        ..to_bob
+    };
+    let wrap = TransferCircuit::new(alice, wide, tree.path(index + 1), to_bob.owner, to_bob.salt);
+    assert!(!run(
+        &wrap,
+        TransferCircuit::instance(root, wide.nullifier(alice), wide_out.commitment())
+    ));
+
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &TransferCircuit::default()).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &TransferCircuit::default()).expect("keygen_pk");
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &[&instance],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[&instance],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+    println!(
+        "shielded transfer from a tree of depth {}: proof length {}",
+        DEPTH,
+        proof.len()
+    );
+}
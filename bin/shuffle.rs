@@ -0,0 +1,165 @@
+use halo2_playground::{
+    audit::fit_k,
+    chips::shuffle::{ShuffleChip, ShuffleConfig},
+    entropy::SeededEntropy,
+    prover::{run_proof, ProofOptions},
+    report::report_if_requested,
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk, Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+const N: usize = 6;
+
+#[derive(Clone, Debug)]
+struct Config {
+    shuffle: ShuffleConfig,
+    advice: Column<Advice>,
+    instance: Column<Instance>,
+}
+
+/// Proves that a private list is a permutation of the public one in instance rows `0..N`.
+#[derive(Clone)]
+struct PermutationCircuit {
+    shuffled: Value<[Fr; N]>,
+}
+
+impl Default for PermutationCircuit {
+    fn default() -> Self {
+        Self {
+            shuffled: Value::unknown(),
+        }
+    }
+}
+
+impl Circuit<Fr> for PermutationCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+        Config {
+            shuffle: ShuffleChip::configure(meta),
+            advice,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let (original, shuffled) = layouter.assign_region(
+            || "load lists",
+            |mut region| {
+                let original = (0..N)
+                    .map(|i| {
+                        region.assign_advice_from_instance(
+                            || "original",
+                            config.instance,
+                            i,
+                            config.advice,
+                            i,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let shuffled = (0..N)
+                    .map(|i| {
+                        let value = self.shuffled.map(|list| list[i]);
+                        region.assign_advice(|| "shuffled", config.advice, N + i, || value)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok((original, shuffled))
+            },
+        )?;
+        ShuffleChip::construct(config.shuffle).assign(
+            layouter.namespace(|| "shuffle"),
+            &original,
+            &shuffled,
+        )
+    }
+}
+
+fn run(circuit: &PermutationCircuit, public: &[Fr]) -> bool {
+    let k = fit_k::<Fr, _>(circuit).expect("fit_k");
+    MockProver::run(k, circuit, vec![public.to_vec()])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+fn main() {
+    let list: [Fr; N] = [3, 1, 4, 1, 5, 9].map(Fr::from);
+    let shuffled: [Fr; N] = [9, 1, 3, 5, 1, 4].map(Fr::from);
+    let circuit = PermutationCircuit {
+        shuffled: Value::known(shuffled),
+    };
+    report_if_requested("shuffle", &circuit);
+
+    assert!(run(&circuit, &list));
+    // the same list is a permutation of itself
+    let identity = PermutationCircuit {
+        shuffled: Value::known(list),
+    };
+    assert!(run(&identity, &list));
+    // same set of values, different multiplicities
+    let multiplicity = PermutationCircuit {
+        shuffled: Value::known([9, 3, 3, 5, 1, 4].map(Fr::from)),
+    };
+    assert!(!run(&multiplicity, &list));
+    let changed = PermutationCircuit {
+        shuffled: Value::known([9, 1, 3, 5, 1, 2].map(Fr::from)),
+    };
+    assert!(!run(&changed, &list));
+
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &PermutationCircuit::default()).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &PermutationCircuit::default()).expect("keygen_pk");
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &[&list],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[&list],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+    let mut other = list;
+    other[0] = Fr::from(2);
+    assert!(check_proof(
+        &params,
+        pk.get_vk(),
+        &[&other],
+        &proof,
+        ProofOptions::default()
+    )
+    .into_result()
+    .is_err());
+    println!(
+        "permutation of {} values proven, {}-byte proof",
+        N,
+        proof.len()
+    );
+}
@@ -0,0 +1,111 @@
+use halo2_playground::{
+    audit::fit_k,
+    circuits::smt::SmtCircuit,
+    entropy::SeededEntropy,
+    merkle::SparseMerkleTree,
+    prover::{run_proof, ProofOptions},
+    report::report_if_requested,
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::Value,
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+const DEPTH: usize = 8;
+type Smt = SmtCircuit<DEPTH>;
+
+fn run(circuit: &Smt, instance: Vec<Fr>) -> bool {
+    let k = fit_k::<Fr, _>(circuit).expect("fit_k");
+    MockProver::run(k, circuit, vec![instance])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+fn main() {
+    report_if_requested("smt", &Smt::default());
+    // a nullifier set: spent nullifiers map to the leaf 1
+    let spent = Fr::one();
+    let mut tree = SparseMerkleTree::new(DEPTH);
+    let empty_root = tree.root();
+    for key in [3, 17, 200] {
+        tree.insert(key, spent);
+    }
+    for key in 0..1 << DEPTH {
+        assert_eq!(
+            tree.path(key).root(tree.get(key)),
+            tree.root(),
+            "key {}",
+            key
+        );
+    }
+    tree.insert(200, Fr::zero());
+    tree.insert(3, Fr::zero());
+    tree.insert(17, Fr::zero());
+    assert_eq!(tree.root(), empty_root, "removing every key");
+    for key in [3, 17, 200] {
+        tree.insert(key, spent);
+    }
+
+    // 42 is not spent yet
+    let key = 42;
+    let root = tree.root();
+    let absent = Smt::new(key, tree.path(key));
+    assert!(run(&absent, Smt::instance(root, key, Fr::zero())));
+    // claiming it spent, or the path for another key. Bit 5 is where 42's path meets 17's, so
+    // the pair at that level is not symmetric.
+    assert!(!run(&absent, Smt::instance(root, key, spent)));
+    assert!(!run(&absent, Smt::instance(root, key ^ 32, Fr::zero())));
+    let witness_key = Smt {
+        key: Value::known(key ^ 32),
+        path: absent.path.clone(),
+    };
+    assert!(!run(&witness_key, Smt::instance(root, key, Fr::zero())));
+    // a spent key has no non-membership proof
+    let spent_key = Smt::new(17, tree.path(17));
+    assert!(run(&spent_key, Smt::instance(root, 17, spent)));
+    assert!(!run(&spent_key, Smt::instance(root, 17, Fr::zero())));
+
+    // spending 42 makes its old proof stale
+    tree.insert(key, spent);
+    let new_root = tree.root();
+    let member = Smt::new(key, tree.path(key));
+    assert!(run(&member, Smt::instance(new_root, key, spent)));
+    assert!(!run(&absent, Smt::instance(new_root, key, Fr::zero())));
+    assert!(!run(&member, Smt::instance(new_root, key, Fr::zero())));
+
+    let key = 99;
+    let instance = Smt::instance(new_root, key, Fr::zero());
+    let circuit = Smt::new(key, tree.path(key));
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &Smt::default()).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &Smt::default()).expect("keygen_pk");
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &[&instance],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[&instance],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+    println!(
+        "depth {} non-membership proof length : {}",
+        DEPTH,
+        proof.len()
+    );
+}
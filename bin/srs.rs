@@ -0,0 +1,139 @@
+use std::{fs, path::Path};
+
+use halo2_playground::{
+    srs::{
+        parse_provider, read_hermez, read_ptau, FromFile, RandomLocal, SrsError, SrsProvider,
+        UnsafeDeterministic,
+    },
+    storage::write_params,
+    GOD_PRIVATE_KEY,
+};
+use halo2_proofs::{
+    arithmetic::{CurveAffine, Field, FieldExt},
+    halo2curves::{
+        bn256::{Bn256, Fq, Fr, G1Affine, G2Affine},
+        group::{ff::PrimeField, prime::PrimeCurveAffine, Curve},
+    },
+    poly::{commitment::Params, kzg::commitment::ParamsKZG},
+};
+
+const POWER: u32 = 4;
+
+fn montgomery(value: Fq) -> Vec<u8> {
+    (value * Fq::from(2).pow_vartime(&[256])).to_repr().to_vec()
+}
+
+fn section(file: &mut Vec<u8>, kind: u32, data: &[u8]) {
+    file.extend_from_slice(&kind.to_le_bytes());
+    file.extend_from_slice(&(data.len() as u64).to_le_bytes());
+    file.extend_from_slice(data);
+}
+
+/// A minimal snarkjs ptau file of `2^POWER` powers of `tau`, with `[tau]_2` replaced by
+/// `[s_g2_tau]_2`.
+fn write_ptau(path: &Path, tau: Fr, s_g2_tau: Fr) {
+    let mut header = 32u32.to_le_bytes().to_vec();
+    let mut modulus = (-Fq::one()).to_repr();
+    modulus[0] += 1;
+    header.extend_from_slice(&modulus);
+    header.extend_from_slice(&POWER.to_le_bytes());
+    header.extend_from_slice(&POWER.to_le_bytes());
+
+    let mut tau_g1 = vec![];
+    let mut power = Fr::one();
+    for _ in 0..(2 << POWER) - 1 {
+        let point = (G1Affine::generator() * power).to_affine();
+        let coordinates = point.coordinates().unwrap();
+        tau_g1.extend(montgomery(*coordinates.x()));
+        tau_g1.extend(montgomery(*coordinates.y()));
+        power *= tau;
+    }
+
+    let mut tau_g2 = vec![];
+    for scalar in [Fr::one(), s_g2_tau] {
+        let point = (G2Affine::generator() * scalar).to_affine();
+        for coordinate in [point.x.c0, point.x.c1, point.y.c0, point.y.c1] {
+            tau_g2.extend(montgomery(coordinate));
+        }
+    }
+
+    let mut file = b"ptau".to_vec();
+    file.extend_from_slice(&1u32.to_le_bytes());
+    file.extend_from_slice(&3u32.to_le_bytes());
+    // out of order on purpose
+    section(&mut file, 2, &tau_g1);
+    section(&mut file, 1, &header);
+    section(&mut file, 3, &tau_g2);
+    fs::write(path, file).unwrap();
+}
+
+fn params_bytes(params: &ParamsKZG<Bn256>) -> Vec<u8> {
+    let mut bytes = vec![];
+    params.write(&mut bytes).unwrap();
+    bytes
+}
+
+fn main() {
+    let dir = std::env::temp_dir().join(format!("halo2-playground-srs-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let tau = Fr::from_u128(GOD_PRIVATE_KEY);
+    let expected = params_bytes(&ParamsKZG::<Bn256>::unsafe_setup_with_s(POWER - 1, tau));
+
+    let ptau = dir.join("test.ptau");
+    write_ptau(&ptau, tau, tau);
+    let params = read_ptau(&ptau, POWER - 1).expect("read_ptau");
+    assert_eq!(params_bytes(&params), expected);
+    assert!(matches!(
+        read_ptau(&ptau, POWER + 1),
+        Err(SrsError::TooSmall { k: 5, max_k: 4 })
+    ));
+
+    let forged = dir.join("forged.ptau");
+    write_ptau(&forged, tau, tau + Fr::one());
+    assert!(matches!(
+        read_ptau(&forged, POWER - 1),
+        Err(SrsError::Inconsistent(_))
+    ));
+    fs::write(&forged, b"not a ptau file").unwrap();
+    assert!(matches!(
+        read_ptau(&forged, POWER - 1),
+        Err(SrsError::Format(_))
+    ));
+
+    let hermez = dir.join("hermez-raw-4");
+    write_params(
+        &hermez,
+        &ParamsKZG::<Bn256>::unsafe_setup_with_s(POWER, tau),
+    )
+    .unwrap();
+    let params = read_hermez(&hermez, POWER - 1).expect("read_hermez");
+    assert_eq!(params_bytes(&params), expected);
+
+    // providers: files by extension, one random setup per k, and only `unsafe` toxic
+    for file in [&ptau, &hermez] {
+        let params = FromFile(file.clone()).params(POWER - 1).expect("FromFile");
+        assert_eq!(params_bytes(&params), expected);
+    }
+    let unsafe_default = UnsafeDeterministic(GOD_PRIVATE_KEY);
+    assert_eq!(
+        params_bytes(&unsafe_default.params(POWER - 1).unwrap()),
+        expected
+    );
+    let random = params_bytes(&RandomLocal.params(POWER - 1).unwrap());
+    assert_ne!(random, expected);
+    assert_eq!(
+        params_bytes(&RandomLocal.params(POWER - 1).unwrap()),
+        random
+    );
+    assert!(unsafe_default.is_toxic() && !RandomLocal.is_toxic());
+    for (arg, toxic) in [("unsafe", true), ("unsafe:7", true), ("random", false)] {
+        assert_eq!(parse_provider(arg).unwrap().is_toxic(), toxic, "{}", arg);
+    }
+    assert!(parse_provider("unsafe:x").is_err());
+    let path = hermez.to_str().unwrap();
+    let params = parse_provider(path).unwrap().params(POWER - 1).unwrap();
+    assert_eq!(params_bytes(&params), expected);
+
+    fs::remove_dir_all(&dir).unwrap();
+    println!("srs: ptau and hermez loading and providers ok");
+}
@@ -0,0 +1,134 @@
+use std::{fs, io::ErrorKind};
+
+use halo2_playground::{
+    audit::fit_k,
+    circuits::mul::MulCircuit,
+    storage::{cached_params, cached_proving_key},
+    store::{proof_id, ArtifactStore, Category, KeyKind, StoreLimits},
+    verifier::vk_digest,
+};
+use halo2_proofs::halo2curves::bn256::Fr;
+
+fn main() {
+    let root = std::env::temp_dir().join(format!("halo2-playground-store-{}", std::process::id()));
+    let store = ArtifactStore::open(&root).expect("open");
+
+    store.write_params(4, b"params").expect("write params");
+    store
+        .write_key("multiplication", 4, KeyKind::Verifying, b"vk")
+        .expect("write vk");
+    store
+        .write_key("hash", 4, KeyKind::Verifying, b"other vk")
+        .expect("write vk");
+    store
+        .write_proof("multiplication", "first", b"proof")
+        .expect("write proof");
+
+    assert_eq!(store.read_params(4).unwrap(), b"params");
+    assert_eq!(
+        store
+            .read_key("multiplication", 4, KeyKind::Verifying)
+            .unwrap(),
+        b"vk"
+    );
+    assert_eq!(
+        store.read_key("hash", 4, KeyKind::Verifying).unwrap(),
+        b"other vk"
+    );
+    assert_eq!(
+        store.read_proof("multiplication", "first").unwrap(),
+        b"proof"
+    );
+
+    // keys are separated by k, and names can't escape the layout
+    let missing = store.read_key("multiplication", 5, KeyKind::Verifying);
+    assert_eq!(missing.unwrap_err().kind(), ErrorKind::NotFound);
+    assert!(store.proof_path("../keys", "first").is_err());
+    assert!(store.proof_path("multiplication", "").is_err());
+
+    // corruption is caught on read
+    let path = store.proof_path("multiplication", "first").unwrap();
+    fs::write(&path, b"tampered").unwrap();
+    let corrupted = store.read_proof("multiplication", "first");
+    assert_eq!(corrupted.unwrap_err().kind(), ErrorKind::InvalidData);
+
+    // least recently used proofs are evicted first
+    let store = store.with_limits(StoreLimits {
+        proofs: Some(10),
+        ..StoreLimits::default()
+    });
+    for id in ["a", "b", "c"] {
+        store
+            .write_proof("hash", id, b"12345")
+            .expect("write proof");
+    }
+    store.read_proof("hash", "a").expect("read proof");
+    let report = store.gc().expect("gc");
+    assert_eq!(report.freed, 5 + 8);
+    assert!(store.read_proof("hash", "a").is_ok());
+    assert!(store.read_proof("hash", "b").is_err());
+    assert!(store.read_proof("hash", "c").is_ok());
+    assert!(!store
+        .proof_path("hash", "b")
+        .unwrap()
+        .parent()
+        .unwrap()
+        .exists());
+    assert!(store.usage(Category::Proofs).unwrap() <= 10);
+    assert_eq!(store.read_params(4).unwrap(), b"params");
+
+    // content-addressed proofs deduplicate
+    let id = store.put_proof("hash", b"proof bytes").expect("put proof");
+    assert_eq!(id, proof_id("hash", b"proof bytes"));
+    assert_eq!(store.put_proof("hash", b"proof bytes").unwrap(), id);
+    assert_ne!(
+        store.put_proof("multiplication", b"proof bytes").unwrap(),
+        id
+    );
+    assert_eq!(
+        store.lookup_proof(&id).unwrap(),
+        Some(("hash".to_string(), b"proof bytes".to_vec()))
+    );
+    assert_eq!(
+        store.lookup_proof(&proof_id("hash", b"other")).unwrap(),
+        None
+    );
+
+    // tenants don't see each other's artifacts and have their own quotas
+    let alice = store.tenant("alice", StoreLimits::default()).unwrap();
+    let bob = store
+        .tenant(
+            "bob",
+            StoreLimits {
+                proofs: Some(0),
+                ..StoreLimits::default()
+            },
+        )
+        .unwrap();
+    let id = alice.put_proof("hash", b"alice's proof").unwrap();
+    bob.put_proof("hash", b"bob's proof").unwrap();
+    assert!(bob.lookup_proof(&id).unwrap().is_none());
+    assert!(store.lookup_proof(&id).unwrap().is_none());
+    assert_eq!(bob.gc().unwrap().removed.len(), 1);
+    assert!(alice.gc().unwrap().removed.is_empty());
+    assert!(alice.lookup_proof(&id).unwrap().is_some());
+    assert_eq!(store.tenants().unwrap(), vec!["alice", "bob"]);
+    assert!(store.tenant("../alice", StoreLimits::default()).is_err());
+
+    // a circuit's vk is stored once per source, and read back instead of regenerated
+    let keys = ArtifactStore::open(root.join("keys-cache")).unwrap();
+    let circuit = MulCircuit::<Fr>::default();
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let first = cached_proving_key(&keys, "mul", "source-a", &params, &circuit).unwrap();
+    let again = cached_proving_key(&keys, "mul", "source-a", &params, &circuit).unwrap();
+    assert_eq!(
+        vk_digest(&params, first.get_vk()),
+        vk_digest(&params, again.get_vk())
+    );
+    assert_eq!(keys.artifacts(Category::Keys).unwrap().len(), 1);
+    cached_proving_key(&keys, "mul", "source-b", &params, &circuit).unwrap();
+    assert_eq!(keys.artifacts(Category::Keys).unwrap().len(), 2);
+
+    fs::remove_dir_all(&root).expect("clean up");
+    println!("artifact store checks passed");
+}
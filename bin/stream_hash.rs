@@ -0,0 +1,93 @@
+//! Hashes a file in chunks, one proof per chunk, and verifies the chain:
+//! `cargo run --bin stream_hash [path]`. Without a path it hashes generated data.
+
+use std::{env, fs};
+
+use halo2_playground::{
+    audit::fit_k,
+    entropy::SeededEntropy,
+    fmt::to_short,
+    storage::cached_params,
+    stream::{
+        absorb, initial_state, instance, prove_stream, stream_hash, verify_stream, words,
+        ChunkProof, StreamChunkCircuit, RATE, WORD_BYTES,
+    },
+};
+use halo2_proofs::{
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+/// 32 words, 992 bytes a chunk.
+const BLOCKS: usize = 16;
+
+fn main() {
+    let (name, bytes) = match env::args().nth(1) {
+        Some(path) => {
+            let bytes = fs::read(&path).unwrap_or_else(|e| panic!("reading {}: {}", path, e));
+            (path, bytes)
+        }
+        None => (
+            "generated data".to_string(),
+            (0..5_000u32).map(|i| (i * 31 % 251) as u8).collect(),
+        ),
+    };
+
+    // one chunk by itself, and with the wrong state out
+    let padded = words::<BLOCKS>(&bytes);
+    let first = &padded[..BLOCKS * RATE];
+    let mut state_out = initial_state();
+    absorb(&mut state_out, first);
+    let circuit = StreamChunkCircuit::<BLOCKS>::new(first.to_vec());
+    let k = fit_k::<Fr, _>(&circuit).expect("fit_k");
+    let prover =
+        MockProver::run(k, &circuit, vec![instance(&initial_state(), &state_out)]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+    let mut wrong = state_out;
+    wrong[2] += Fr::from(1);
+    let prover = MockProver::run(k, &circuit, vec![instance(&initial_state(), &wrong)]).unwrap();
+    assert!(prover.verify().is_err());
+
+    let params = cached_params(k).expect("cached_params");
+    let vk = keygen_vk(&params, &StreamChunkCircuit::<BLOCKS>::default()).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &StreamChunkCircuit::<BLOCKS>::default()).expect("keygen_pk");
+    let proofs = prove_stream::<BLOCKS, _>(
+        &params,
+        &pk,
+        &bytes,
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+    )
+    .expect("prove_stream");
+    let digest = verify_stream(&params, pk.get_vk(), &proofs).expect("verify_stream");
+    assert_eq!(digest, Some(stream_hash::<BLOCKS>(&bytes)));
+
+    // reordered or dropped chunks break the chain, though every proof is valid
+    if proofs.len() > 1 {
+        let mut swapped = proofs.clone();
+        swapped.swap(0, 1);
+        let verify = |proofs: &[ChunkProof]| {
+            verify_stream(&params, pk.get_vk(), proofs).expect("verify_stream")
+        };
+        assert_eq!(verify(&swapped), None);
+        assert_eq!(verify(&proofs[1..]), None);
+    }
+    // and a proof doesn't verify for another chunk's states
+    let mut forged = proofs.clone();
+    forged.last_mut().unwrap().state_out = wrong;
+    assert!(!matches!(
+        verify_stream(&params, pk.get_vk(), &forged),
+        Ok(Some(_))
+    ));
+
+    println!(
+        "{}: {} bytes in {} chunks of {} bytes, k = {}, {} bytes of proofs",
+        name,
+        bytes.len(),
+        proofs.len(),
+        BLOCKS * RATE * WORD_BYTES,
+        k,
+        proofs.iter().map(|chunk| chunk.proof.len()).sum::<usize>()
+    );
+    println!("digest: {}", to_short(&digest.unwrap()));
+}
@@ -0,0 +1,289 @@
+use halo2_playground::{
+    audit::fit_k,
+    chips::shuffle::{ShuffleChip, ShuffleConfig},
+    entropy::SeededEntropy,
+    prover::{run_proof, ProofOptions},
+    report::{report_if_requested, CostReport},
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{
+        keygen_pk, keygen_vk, Advice, Circuit, Column, ConstraintSystem, Error, Expression,
+        Instance, Selector, TableColumn,
+    },
+    poly::Rotation,
+};
+
+type Grid = [[u64; 9]; 9];
+
+/// Blanks are zeros.
+const PUZZLE: Grid = [
+    [5, 3, 0, 0, 7, 0, 0, 0, 0],
+    [6, 0, 0, 1, 9, 5, 0, 0, 0],
+    [0, 9, 8, 0, 0, 0, 0, 6, 0],
+    [8, 0, 0, 0, 6, 0, 0, 0, 3],
+    [4, 0, 0, 8, 0, 3, 0, 0, 1],
+    [7, 0, 0, 0, 2, 0, 0, 0, 6],
+    [0, 6, 0, 0, 0, 0, 2, 8, 0],
+    [0, 0, 0, 4, 1, 9, 0, 0, 5],
+    [0, 0, 0, 0, 8, 0, 0, 7, 9],
+];
+
+const SOLUTION: Grid = [
+    [5, 3, 4, 6, 7, 8, 9, 1, 2],
+    [6, 7, 2, 1, 9, 5, 3, 4, 8],
+    [1, 9, 8, 3, 4, 2, 5, 6, 7],
+    [8, 5, 9, 7, 6, 1, 4, 2, 3],
+    [4, 2, 6, 8, 5, 3, 7, 9, 1],
+    [7, 1, 3, 9, 2, 4, 8, 5, 6],
+    [9, 6, 1, 5, 3, 7, 2, 8, 4],
+    [2, 8, 7, 4, 1, 9, 6, 3, 5],
+    [3, 4, 5, 2, 8, 6, 1, 7, 9],
+];
+
+/// The rows, columns and boxes, as indices of the cells in row-major order.
+fn units() -> Vec<Vec<usize>> {
+    let rows = (0..9).map(|r| (0..9).map(|j| 9 * r + j).collect());
+    let columns = (0..9).map(|c| (0..9).map(|j| c + 9 * j).collect());
+    let boxes = (0..9).map(|b| {
+        (0..9)
+            .map(|j| (b / 3 * 3 + j / 3) * 9 + b % 3 * 3 + j % 3)
+            .collect()
+    });
+    rows.chain(columns).chain(boxes).collect()
+}
+
+fn is_solution(puzzle: &Grid, grid: &Grid) -> bool {
+    let cells = grid.concat();
+    let clues = puzzle.concat();
+    let agrees = cells
+        .iter()
+        .zip(clues.iter())
+        .all(|(cell, clue)| *clue == 0 || cell == clue);
+    agrees
+        && units().iter().all(|unit| {
+            let mut digits: Vec<u64> = unit.iter().map(|i| cells[*i]).collect();
+            digits.sort_unstable();
+            digits == (1..=9).collect::<Vec<_>>()
+        })
+}
+
+fn instance(puzzle: &Grid) -> Vec<Fr> {
+    puzzle.concat().into_iter().map(Fr::from).collect()
+}
+
+#[derive(Clone, Debug)]
+struct Config {
+    cell: Column<Advice>,
+    clue: Column<Advice>,
+    q_cell: Selector,
+    digits: TableColumn,
+    instance: Column<Instance>,
+    shuffle: ShuffleConfig,
+}
+
+/// Proves knowledge of a filled grid agreeing with every clue of the public puzzle in instance
+/// rows `0..81`, row by row with blanks as zeros. Each cell is looked up in a table of the
+/// digits, and each of the 27 rows, columns and boxes is a shuffle of the digits 1 to 9. The
+/// shuffles alone imply the range, but the lookup names the offending cell when one is out of
+/// range.
+#[derive(Clone, Debug)]
+struct SudokuCircuit {
+    grid: Value<Grid>,
+}
+
+impl Default for SudokuCircuit {
+    fn default() -> Self {
+        Self {
+            grid: Value::unknown(),
+        }
+    }
+}
+
+impl SudokuCircuit {
+    fn new(grid: Grid) -> Self {
+        Self {
+            grid: Value::known(grid),
+        }
+    }
+}
+
+impl Circuit<Fr> for SudokuCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let cell = meta.advice_column();
+        let clue = meta.advice_column();
+        let constants = meta.fixed_column();
+        let q_cell = meta.complex_selector();
+        let digits = meta.lookup_table_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(cell);
+        meta.enable_equality(clue);
+        meta.enable_equality(instance);
+        meta.enable_constant(constants);
+
+        // a blank is zero, so it allows any cell
+        meta.create_gate("clue", |meta| {
+            let q = meta.query_selector(q_cell);
+            let cell = meta.query_advice(cell, Rotation::cur());
+            let clue = meta.query_advice(clue, Rotation::cur());
+            vec![q * clue.clone() * (cell - clue)]
+        });
+        // rows without the selector look up 1, which is in the table
+        meta.lookup("digit", |meta| {
+            let q = meta.query_selector(q_cell);
+            let cell = meta.query_advice(cell, Rotation::cur());
+            let one = Expression::Constant(Fr::one());
+            vec![(q.clone() * cell + (one - q), digits)]
+        });
+
+        Config {
+            cell,
+            clue,
+            q_cell,
+            digits,
+            instance,
+            shuffle: ShuffleChip::configure(meta),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        layouter.assign_table(
+            || "digits",
+            |mut table| {
+                for digit in 1..=9u64 {
+                    table.assign_cell(
+                        || "digit",
+                        config.digits,
+                        digit as usize - 1,
+                        || Value::known(Fr::from(digit)),
+                    )?;
+                }
+                Ok(())
+            },
+        )?;
+        let digits = layouter.assign_region(
+            || "digits",
+            |mut region| {
+                (1..=9u64)
+                    .map(|digit| {
+                        region.assign_advice_from_constant(
+                            || "digit",
+                            config.cell,
+                            digit as usize - 1,
+                            Fr::from(digit),
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            },
+        )?;
+        let cells: Vec<AssignedCell<Fr, Fr>> = layouter.assign_region(
+            || "grid",
+            |mut region| {
+                (0..81)
+                    .map(|i| {
+                        config.q_cell.enable(&mut region, i)?;
+                        region.assign_advice_from_instance(
+                            || "clue",
+                            config.instance,
+                            i,
+                            config.clue,
+                            i,
+                        )?;
+                        region.assign_advice(
+                            || "cell",
+                            config.cell,
+                            i,
+                            || self.grid.map(|grid| Fr::from(grid[i / 9][i % 9])),
+                        )
+                    })
+                    .collect()
+            },
+        )?;
+
+        let shuffle = ShuffleChip::construct(config.shuffle.clone());
+        for (n, unit) in units().iter().enumerate() {
+            let unit: Vec<_> = unit.iter().map(|i| cells[*i].clone()).collect();
+            shuffle.assign(layouter.namespace(|| format!("unit {}", n)), &digits, &unit)?;
+        }
+        Ok(())
+    }
+}
+
+fn run(grid: Grid, puzzle: &Grid) -> bool {
+    let circuit = SudokuCircuit::new(grid);
+    let k = fit_k::<Fr, _>(&circuit).expect("fit_k");
+    MockProver::run(k, &circuit, vec![instance(puzzle)])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+fn main() {
+    report_if_requested("sudoku", &SudokuCircuit::default());
+    assert!(is_solution(&PUZZLE, &SOLUTION));
+    assert!(run(SOLUTION, &PUZZLE));
+    // an empty puzzle takes any solution
+    assert!(run(SOLUTION, &[[0; 9]; 9]));
+
+    // swapping two cells of a row keeps the row but breaks both columns
+    let mut swapped = SOLUTION;
+    swapped[0].swap(2, 3);
+    assert!(!is_solution(&[[0; 9]; 9], &swapped));
+    assert!(!run(swapped, &[[0; 9]; 9]));
+    // relabelling the digits gives another valid grid, against the clues
+    let relabelled = SOLUTION.map(|row| row.map(|digit| digit % 9 + 1));
+    assert!(is_solution(&[[0; 9]; 9], &relabelled));
+    assert!(!run(relabelled, &PUZZLE));
+    // a zero in place of a blank fails the lookup
+    let mut blank = SOLUTION;
+    blank[0][2] = 0;
+    assert!(!run(blank, &PUZZLE));
+
+    let circuit = SudokuCircuit::new(SOLUTION);
+    let instance = instance(&PUZZLE);
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &SudokuCircuit::default()).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &SudokuCircuit::default()).expect("keygen_pk");
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit.clone(),
+        &[&instance],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[&instance],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+    let report = CostReport::measure(&circuit).expect("measure");
+    println!(
+        "sudoku with {} clues: k = {}, {} advice columns, {}-byte proof",
+        PUZZLE.concat().iter().filter(|clue| **clue != 0).count(),
+        report.k,
+        report.advice_columns,
+        proof.len()
+    );
+}
@@ -0,0 +1,147 @@
+use halo2_playground::{
+    audit::fit_k,
+    chips::{
+        range_check::RangeCheckConfig,
+        standard_plonk::{StandardPlonkChip, StandardPlonkConfig},
+        tables::Tables,
+        uint::{UintChip, Width},
+    },
+    report::report_if_requested,
+    schema::{DescribeInstances, InstanceLayout, OutOfRange},
+};
+use halo2_proofs::{
+    arithmetic::{Field, FieldExt},
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+#[derive(Clone, Debug)]
+struct Config {
+    plonk: StandardPlonkConfig,
+    tables: Tables,
+    uint: RangeCheckConfig,
+    instance: Column<Instance>,
+}
+
+/// Withdraws the public `u64` amount in instance row 0 from a private `u128` balance, exposing
+/// the remaining `u128` in row 1. Overdrawing wraps the remainder around the field, so the
+/// width check on it is what rejects the withdrawal.
+#[derive(Clone, Default)]
+struct WithdrawCircuit {
+    balance: Value<Fr>,
+    amount: Value<Fr>,
+}
+
+impl DescribeInstances for WithdrawCircuit {
+    fn instance_layout() -> InstanceLayout {
+        InstanceLayout::new("withdraw")
+            .enforced_field(0, "amount", Width::U64.encoding(), "amount withdrawn")
+            .enforced_field(0, "remaining", Width::U128.encoding(), "balance afterwards")
+    }
+}
+
+impl Circuit<Fr> for WithdrawCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        let mut tables = Tables::new();
+        let uint = UintChip::configure(meta, &mut tables);
+        Config {
+            plonk: StandardPlonkChip::configure(meta),
+            tables,
+            uint,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let plonk = StandardPlonkChip::construct(config.plonk);
+        let uint = UintChip::construct(config.uint);
+        config.tables.load(&mut layouter)?;
+        let balance = plonk.assign(layouter.namespace(|| "balance"), self.balance)?;
+        let amount = plonk.assign(layouter.namespace(|| "amount"), self.amount)?;
+        let remaining = plonk.sub(layouter.namespace(|| "remaining"), &balance, &amount)?;
+        uint.check(layouter.namespace(|| "balance"), &balance, Width::U128)?;
+        uint.expose(
+            layouter.namespace(|| "amount"),
+            &amount,
+            Width::U64,
+            config.instance,
+            0,
+        )?;
+        uint.expose(
+            layouter.namespace(|| "remaining"),
+            &remaining,
+            Width::U128,
+            config.instance,
+            1,
+        )
+    }
+}
+
+fn run(balance: Fr, amount: Fr) -> bool {
+    let circuit = WithdrawCircuit {
+        balance: Value::known(balance),
+        amount: Value::known(amount),
+    };
+    let k = fit_k::<Fr, _>(&circuit).expect("fit_k");
+    MockProver::run(k, &circuit, vec![vec![amount, balance - amount]])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+fn main() {
+    let u128_max = Fr::from_u128(u128::MAX);
+    let u64_max = Fr::from(u64::MAX);
+    report_if_requested(
+        "uint",
+        &WithdrawCircuit {
+            balance: Value::known(u128_max),
+            amount: Value::known(u64_max),
+        },
+    );
+
+    assert!(run(Fr::from(1000), Fr::from(300)));
+    assert!(run(u128_max, u64_max));
+    assert!(run(u64_max, u64_max));
+    // overdrawing and amounts wider than a u64 have no proof
+    assert!(!run(Fr::from(300), Fr::from(1000)));
+    assert!(!run(u128_max, u64_max + Fr::one()));
+    // nor does a balance wider than a u128, even with an in-range remainder
+    let wide = u128_max + Fr::from(6);
+    assert!(!run(wide, Fr::from(10)));
+
+    // verifiers reject the same instances by name, before verifying
+    let layout = WithdrawCircuit::instance_layout();
+    assert_eq!(
+        layout.check_ranges(&[vec![Fr::from(300), Fr::from(700)]]),
+        Ok(())
+    );
+    assert_eq!(
+        layout.check_ranges(&[vec![Fr::from(1000), -Fr::from(700)]]),
+        Err(OutOfRange {
+            name: "remaining".to_string(),
+            column: 0,
+            row: 1,
+            encoding: Width::U128.encoding(),
+        })
+    );
+    assert!(layout
+        .check_ranges(&[vec![u64_max + Fr::one(), Fr::zero()]])
+        .is_err());
+    print!("{}", layout.to_table());
+}
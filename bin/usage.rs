@@ -0,0 +1,189 @@
+use halo2_playground::{
+    circuits::mul::MulCircuit,
+    cli::{CliCircuit, PoseidonCircuit},
+    usage::UsageReport,
+};
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Selector, TableColumn},
+    poly::Rotation,
+};
+
+const ROWS: usize = 4;
+
+#[derive(Clone, Debug)]
+struct Config {
+    a: Column<Advice>,
+    b: Column<Advice>,
+    c: Column<Advice>,
+    q_double: Selector,
+    q_copy: Selector,
+    q_nibble: Selector,
+    nibbles: TableColumn,
+}
+
+/// Four rows of `b = 2a`, four of `c = a`, four of `a` a nibble, and a region that fills two
+/// of its eight cells: `b` and `c` are never on one row, and the two simple selectors never
+/// either, so keygen combines them.
+#[derive(Clone, Default)]
+struct UnevenCircuit;
+
+impl Circuit<Fr> for UnevenCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let [a, b, c] = [(); 3].map(|_| meta.advice_column());
+        let (q_double, q_copy) = (meta.selector(), meta.selector());
+        let q_nibble = meta.complex_selector();
+        let nibbles = meta.lookup_table_column();
+        meta.create_gate("double", |meta| {
+            let q = meta.query_selector(q_double);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            vec![q * (b - a.clone() - a)]
+        });
+        meta.create_gate("copy", |meta| {
+            let q = meta.query_selector(q_copy);
+            let a = meta.query_advice(a, Rotation::cur());
+            let c = meta.query_advice(c, Rotation::cur());
+            vec![q * (c - a)]
+        });
+        meta.lookup(|meta| {
+            let q = meta.query_selector(q_nibble);
+            vec![(q * meta.query_advice(a, Rotation::cur()), nibbles)]
+        });
+        Config {
+            a,
+            b,
+            c,
+            q_double,
+            q_copy,
+            q_nibble,
+            nibbles,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        layouter.assign_table(
+            || "nibbles",
+            |mut table| {
+                for row in 0..16 {
+                    table.assign_cell(
+                        || "nibble",
+                        config.nibbles,
+                        row,
+                        || Value::known(Fr::from(row as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )?;
+        let one = Value::known(Fr::one());
+        let two = Value::known(Fr::from(2));
+        for (name, q, out, value) in [
+            ("double", config.q_double, config.b, two),
+            ("copy", config.q_copy, config.c, one),
+        ] {
+            layouter.assign_region(
+                || name,
+                |mut region| {
+                    for row in 0..ROWS {
+                        q.enable(&mut region, row)?;
+                        region.assign_advice(|| "a", config.a, row, || one)?;
+                        region.assign_advice(|| name, out, row, || value)?;
+                    }
+                    Ok(())
+                },
+            )?;
+        }
+        layouter.assign_region(
+            || "nibble",
+            |mut region| {
+                for row in 0..ROWS {
+                    config.q_nibble.enable(&mut region, row)?;
+                    region.assign_advice(|| "a", config.a, row, || one)?;
+                }
+                Ok(())
+            },
+        )?;
+        layouter.assign_region(
+            || "sparse",
+            |mut region| {
+                region.assign_advice(|| "a", config.a, 0, || one)?;
+                region.assign_advice(|| "b", config.b, ROWS - 1, || one)?;
+                Ok(())
+            },
+        )
+    }
+}
+
+fn main() {
+    let report = UsageReport::measure(&UnevenCircuit).expect("measure usage");
+    println!("uneven circuit:\n{}", report);
+    // the simple selectors share one column, the lookup's has its own
+    assert!(report.unplaced.is_empty());
+    let mut shared: Vec<usize> = report
+        .selector_columns
+        .iter()
+        .map(|column| column.selectors.len())
+        .collect();
+    shared.sort_unstable();
+    assert_eq!(shared, [1, 2]);
+    assert!(report
+        .selector_columns
+        .iter()
+        .flat_map(|column| column.selectors.iter())
+        .all(|(_, rows)| *rows == ROWS));
+
+    let cells: Vec<(&str, usize)> = report
+        .columns
+        .iter()
+        .map(|column| (column.column.as_str(), column.cells))
+        .collect();
+    assert_eq!(
+        cells,
+        [
+            ("a0", 3 * ROWS + 1),
+            ("a1", ROWS + 1),
+            ("a2", ROWS),
+            ("f0", 16)
+        ]
+    );
+    assert!(report
+        .mergeable()
+        .contains(&("a2".to_string(), "a1".to_string())));
+
+    let sparse = report
+        .regions
+        .iter()
+        .find(|region| region.name == "sparse")
+        .expect("the sparse region");
+    assert_eq!(sparse.columns, ["a0", "a1"]);
+    assert_eq!(
+        (sparse.rows, sparse.cells, sparse.area),
+        (ROWS, 2, 2 * ROWS)
+    );
+    assert_eq!(sparse.empty_rows, ROWS - 2);
+    let suggestions = report.suggestions();
+    assert!(suggestions.iter().any(|s| s.contains("\"sparse\"")));
+    assert!(!suggestions.iter().any(|s| s.contains("\"double\"")));
+
+    for (name, report) in [
+        ("mul", UsageReport::measure(&MulCircuit::<Fr>::empty())),
+        ("poseidon", UsageReport::measure(&PoseidonCircuit::empty())),
+    ] {
+        let report = report.expect("measure usage");
+        assert!(report.unplaced.is_empty(), "{}: unplaced selectors", name);
+        println!("{}:\n{}", name, report);
+    }
+}
@@ -0,0 +1,89 @@
+use halo2_playground::{
+    audit::fit_k,
+    chips::var_len::var_len_hash,
+    circuits::var_len::VarLenHashCircuit,
+    entropy::SeededEntropy,
+    poseidon::MySpec,
+    prover::{run_proof, ProofOptions},
+    report::report_if_requested,
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+/// Padded words: messages of up to 7 elements.
+const N: usize = 8;
+type VarLen = VarLenHashCircuit<Fr, MySpec<3, 2>, 3, 2, N>;
+
+fn run(circuit: &VarLen, instance: Vec<Fr>) -> bool {
+    let k = fit_k::<Fr, _>(circuit).expect("fit_k");
+    MockProver::run(k, circuit, vec![instance])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+fn main() {
+    report_if_requested("var_len", &VarLen::default());
+    let message: Vec<Fr> = (1..=VarLen::CAPACITY as u64).map(Fr::from).collect();
+
+    for len in [0, 1, 2, 5, VarLen::CAPACITY] {
+        let message = &message[..len];
+        let circuit = VarLen::new(message.to_vec());
+        let instance = VarLen::instance(message);
+
+        assert!(run(&circuit, instance.clone()), "length {}", len);
+        // the length is part of the statement
+        let (hash, len_fr) = (instance[0], instance[1]);
+        assert!(
+            !run(&circuit, vec![hash, len_fr + Fr::one()]),
+            "length {}",
+            len
+        );
+        assert!(
+            !run(&circuit, vec![hash + Fr::one(), len_fr]),
+            "length {}",
+            len
+        );
+    }
+
+    // the padding marker separates messages that differ only by trailing zeros
+    let hash = |m: &[Fr]| var_len_hash::<Fr, MySpec<3, 2>, 3, 2, N>(m);
+    assert_ne!(hash(&[Fr::one()]), hash(&[Fr::one(), Fr::zero()]));
+    assert_ne!(hash(&[]), hash(&[Fr::zero()]));
+
+    // one pair of keys proves every length
+    let params = cached_params(fit_k(&VarLen::default()).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &VarLen::default()).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &VarLen::default()).expect("keygen_pk");
+    for len in [1, 6] {
+        let message = &message[..len];
+        let instance = VarLen::instance(message);
+        let proof = run_proof(
+            &params,
+            &pk,
+            VarLen::new(message.to_vec()),
+            &[&instance],
+            SeededEntropy::new(0x5962_be5d_763d_318d),
+            ProofOptions::default(),
+        )
+        .expect("create_proof");
+        check_proof(
+            &params,
+            pk.get_vk(),
+            &[&instance],
+            &proof,
+            ProofOptions::default(),
+        )
+        .into_result()
+        .expect("verify_proof");
+        println!("length {}: proof length {}", len, proof.len());
+    }
+
+    println!("variable-length Poseidon checks passed");
+}
@@ -0,0 +1,77 @@
+use std::time::Instant;
+
+use halo2_playground::{
+    circuits::vdf::{vdf_eval, vdf_verify, VdfCircuit},
+    entropy::SeededEntropy,
+    prover::{run_proof, ProofOptions},
+    report::report_if_requested,
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+const K: u32 = 8;
+const STEPS: usize = 200;
+
+fn main() {
+    let x = Fr::from(7);
+    let start = Instant::now();
+    let y = vdf_eval(x, STEPS);
+    let eval_time = start.elapsed();
+    let start = Instant::now();
+    assert!(vdf_verify(x, y, STEPS));
+    println!(
+        "vdf: T = {}, eval {:?}, native verify {:?}",
+        STEPS,
+        eval_time,
+        start.elapsed()
+    );
+    assert!(!vdf_verify(x, y + Fr::one(), STEPS));
+    assert!(!vdf_verify(x, y, STEPS - 1));
+
+    let circuit = VdfCircuit::new(STEPS);
+    report_if_requested("vdf", &circuit);
+    let instance = VdfCircuit::instance(x, y, STEPS);
+    let prover = MockProver::run(K, &circuit, vec![instance.clone()]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+    // the wrong output, or the right output claimed for another T
+    for wrong in [
+        VdfCircuit::instance(x, y + Fr::one(), STEPS),
+        VdfCircuit::instance(x, y, STEPS + 1),
+    ] {
+        let prover = MockProver::run(K, &circuit, vec![wrong]).unwrap();
+        assert!(prover.verify().is_err());
+    }
+
+    let params = cached_params(K).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &[&instance],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    let start = Instant::now();
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[&instance],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+    println!(
+        "vdf: proof {} bytes, verified in {:?}",
+        proof.len(),
+        start.elapsed()
+    );
+}
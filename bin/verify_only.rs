@@ -0,0 +1,144 @@
+//! Verifies a proof made by `playground prove`, built without the prover: only params
+//! loading, vk deserialization and `verify_proof` are compiled in. `make verifier-size`
+//! compares its size with the full playground's.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    process,
+};
+
+use clap::{Parser, ValueEnum};
+use halo2_playground::{
+    circuits::{mul::MulCircuit, poseidon::PoseidonHashCircuit},
+    encoding::{decode_base64, decode_hex, decode_instances, parse_columns, Reduction},
+    poseidon::MySpec,
+    prover::{MultiOpen, ProofOptions, TranscriptHash},
+    storage::{read_params, read_vk},
+    transcript_registry::{self, TRANSCRIPTS},
+    verifier::check_proof,
+};
+use halo2_proofs::{halo2curves::bn256::Fr, plonk::Circuit};
+
+#[derive(Parser)]
+struct Cli {
+    #[clap(long, value_enum)]
+    circuit: CircuitName,
+    #[clap(long)]
+    params: PathBuf,
+    #[clap(long)]
+    vk: PathBuf,
+    /// Read as JSON if named `.json`
+    #[clap(long)]
+    instances: PathBuf,
+    /// Read as hex or base64 if named `.hex` or `.b64`
+    #[clap(long)]
+    proof: PathBuf,
+    #[clap(long, value_enum, default_value = "shplonk")]
+    multiopen: MultiOpenArg,
+    #[clap(long, default_value = "blake2b", value_parser = parse_transcript)]
+    transcript: TranscriptHash,
+    /// Also print the size of this binary
+    #[clap(long)]
+    report: bool,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum CircuitName {
+    Mul,
+    Poseidon,
+}
+
+#[derive(Clone, Copy, ValueEnum)]
+enum MultiOpenArg {
+    Shplonk,
+    Gwc,
+}
+
+fn parse_transcript(id: &str) -> Result<TranscriptHash, String> {
+    transcript_registry::by_id(id)
+        .map(|entry| entry.hash)
+        .ok_or_else(|| {
+            let ids: Vec<_> = TRANSCRIPTS.iter().map(|entry| entry.id).collect();
+            format!(
+                "unknown transcript {:?}, expected one of {}",
+                id,
+                ids.join(", ")
+            )
+        })
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map_or(false, |ext| extensions.contains(&ext))
+}
+
+fn read_instances(path: &Path) -> Result<Vec<Vec<Fr>>, String> {
+    let text = fs::read_to_string(path).map_err(|e| format!("{}: {}", path.display(), e))?;
+    if has_extension(path, &["json"]) {
+        decode_instances(&text, Reduction::Reject)
+    } else {
+        parse_columns(&text, Reduction::Reject)
+    }
+    .map_err(|e| format!("{}: {}", path.display(), e))
+}
+
+fn read_proof(path: &Path) -> Result<Vec<u8>, String> {
+    let error = |e: &dyn std::fmt::Display| format!("{}: {}", path.display(), e);
+    if has_extension(path, &["hex", "b64"]) {
+        let text = fs::read_to_string(path).map_err(|e| error(&e))?;
+        if has_extension(path, &["hex"]) {
+            decode_hex(&text)
+        } else {
+            decode_base64(&text)
+        }
+        .map_err(|e| error(&e))
+    } else {
+        fs::read(path).map_err(|e| error(&e))
+    }
+}
+
+fn verify<C: Circuit<Fr>>(cli: &Cli) -> Result<(), String> {
+    let error = |path: &Path, e: std::io::Error| format!("{}: {}", path.display(), e);
+    let params = read_params(&cli.params).map_err(|e| error(&cli.params, e))?;
+    let vk = read_vk::<C>(&cli.vk, &params).map_err(|e| error(&cli.vk, e))?;
+    let instances = read_instances(&cli.instances)?;
+    let columns: Vec<&[Fr]> = instances.iter().map(Vec::as_slice).collect();
+    let proof = read_proof(&cli.proof)?;
+    let options = ProofOptions {
+        multiopen: match cli.multiopen {
+            MultiOpenArg::Shplonk => MultiOpen::Shplonk,
+            MultiOpenArg::Gwc => MultiOpen::Gwc,
+        },
+        transcript: cli.transcript,
+    };
+    check_proof(&params, &vk, &columns, &proof, options)
+        .into_result()
+        .map_err(|report| format!("verify_proof: {}", report))
+}
+
+fn main() {
+    let cli = Cli::parse();
+    let result = match cli.circuit {
+        CircuitName::Mul => verify::<MulCircuit<Fr>>(&cli),
+        CircuitName::Poseidon => verify::<PoseidonHashCircuit<Fr, MySpec<3, 2>, 3, 2, 2>>(&cli),
+    };
+    if cli.report {
+        match std::env::current_exe().and_then(fs::metadata) {
+            Ok(metadata) => println!(
+                "verify_only binary: {} bytes ({:.1} MiB)",
+                metadata.len(),
+                metadata.len() as f64 / (1 << 20) as f64
+            ),
+            Err(e) => eprintln!("binary size: {}", e),
+        }
+    }
+    match result {
+        Ok(()) => println!("proof verified"),
+        Err(err) => {
+            eprintln!("error: {}", err);
+            process::exit(1);
+        }
+    }
+}
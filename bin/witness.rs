@@ -0,0 +1,137 @@
+use halo2_playground::{
+    audit::fit_k,
+    chips::standard_plonk::{StandardPlonkChip, StandardPlonkConfig},
+    schema::{Encoding, WitnessLayout},
+    witness::{CompletionError, Witness, WitnessPlan},
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+#[derive(Clone, Debug)]
+struct Config {
+    plonk: StandardPlonkConfig,
+    instance: Column<Instance>,
+}
+
+/// Proves that the product of the private `a` and `b`, in instance row 0, is invertible. The
+/// product and its inverse are witnesses too, but only `a` and `b` are free.
+#[derive(Clone, Default)]
+struct InvertibleProduct {
+    a: Value<Fr>,
+    b: Value<Fr>,
+    product: Value<Fr>,
+    inverse: Value<Fr>,
+}
+
+fn layout() -> WitnessLayout {
+    WitnessLayout::new("invertible product")
+        .field("a", Encoding::Field, "first factor")
+        .field("b", Encoding::Field, "second factor")
+}
+
+fn plan() -> WitnessPlan<Fr> {
+    WitnessPlan::new(&layout())
+        .product("product", "a", "b")
+        .inverse("inverse", "product")
+}
+
+impl InvertibleProduct {
+    fn from_witness(witness: &Witness<Fr>) -> Self {
+        Self {
+            a: witness.value("a"),
+            b: witness.value("b"),
+            product: witness.value("product"),
+            inverse: witness.value("inverse"),
+        }
+    }
+}
+
+impl Circuit<Fr> for InvertibleProduct {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        Config {
+            plonk: StandardPlonkChip::configure(meta),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let chip = StandardPlonkChip::construct(config.plonk);
+        let a = chip.assign(layouter.namespace(|| "a"), self.a)?;
+        let b = chip.assign(layouter.namespace(|| "b"), self.b)?;
+        let product = chip.assign(layouter.namespace(|| "product"), self.product)?;
+        let inverse = chip.assign(layouter.namespace(|| "inverse"), self.inverse)?;
+        let computed = chip.mul(layouter.namespace(|| "a * b"), &a, &b)?;
+        chip.constrain_equal(layouter.namespace(|| "product"), &computed, &product)?;
+        let one = chip.mul(
+            layouter.namespace(|| "product * inverse"),
+            &product,
+            &inverse,
+        )?;
+        let expected = chip.assign_constant(layouter.namespace(|| "1"), Fr::one())?;
+        chip.constrain_equal(layouter.namespace(|| "invertible"), &one, &expected)?;
+        layouter.constrain_instance(product.cell(), config.instance, 0)
+    }
+}
+
+fn main() {
+    // the witness file only lists the free inputs
+    let free: Vec<Fr> = layout().read("3 5").expect("read witness");
+    let witness = plan().complete(&free).expect("complete");
+    assert_eq!(witness.get("product"), Some(Fr::from(15)));
+    assert_eq!(witness.get("inverse"), Some(Fr::from(15).invert().unwrap()));
+    assert_eq!(plan().names(), ["a", "b", "product", "inverse"]);
+
+    let circuit = InvertibleProduct::from_witness(&witness);
+    let k = fit_k::<Fr, _>(&circuit).expect("fit_k");
+    let prover = MockProver::run(k, &circuit, vec![vec![Fr::from(15)]]).unwrap();
+    assert_eq!(prover.verify(), Ok(()));
+
+    // a derived value out of step with the free inputs fails the circuit
+    let mut stale = circuit;
+    stale.product = Value::known(Fr::from(16));
+    let prover = MockProver::run(k, &stale, vec![vec![Fr::from(16)]]).unwrap();
+    assert!(prover.verify().is_err());
+
+    // derivations report inputs they can't handle
+    let zero = plan().complete(&[Fr::zero(), Fr::from(5)]);
+    assert!(matches!(zero, Err(CompletionError::Derivation { .. })));
+    assert_eq!(
+        plan().complete(&[Fr::one()]).unwrap_err(),
+        CompletionError::Count {
+            expected: 2,
+            found: 1
+        }
+    );
+
+    let bits = WitnessPlan::new(&WitnessLayout::new("bits").field(
+        "x",
+        Encoding::Integer { bits: 8 },
+        "a byte",
+    ))
+    .bits("x", "x", 4);
+    let witness = bits.complete(&[Fr::from(0b1010)]).expect("complete");
+    let decomposed: Vec<Fr> = (0..4)
+        .map(|i| witness.get(&format!("x{}", i)).unwrap())
+        .collect();
+    assert_eq!(decomposed, [0, 1, 0, 1].map(Fr::from));
+    assert!(bits.complete(&[Fr::from(0b10000)]).is_err());
+    println!("witness completed from {} free inputs", free.len());
+}
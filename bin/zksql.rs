@@ -0,0 +1,329 @@
+use std::convert::TryInto;
+
+use halo2_gadgets::poseidon::{primitives::ConstantLength, Hash, Pow5Chip, Pow5Config};
+use halo2_playground::{
+    audit::fit_k,
+    chips::{
+        branch::{BranchChip, BranchConfig, Condition},
+        lt::{LtChip, LtConfig},
+        range_check::{RangeCheckChip, RangeCheckConfig},
+        standard_plonk::{StandardPlonkChip, StandardPlonkConfig},
+        tables::Tables,
+    },
+    circuits::poseidon::PoseidonHashCircuit,
+    entropy::SeededEntropy,
+    poseidon::MySpec,
+    prover::{run_proof, ProofOptions},
+    report::report_if_requested,
+    schema::{DescribeInstances, Encoding, InstanceLayout},
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk, Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+const ROWS: usize = 8;
+/// Both columns of every row, in the order they are committed to.
+const CELLS: usize = 2 * ROWS;
+const N_BYTES: usize = 4;
+const WIDTH: usize = 3;
+const RATE: usize = 2;
+
+/// A row of the table, `(age, amount)`.
+type Row = (u32, u32);
+
+fn commitment(table: &[Row; ROWS]) -> Fr {
+    let cells = table
+        .iter()
+        .flat_map(|(age, amount)| [Fr::from(*age as u64), Fr::from(*amount as u64)])
+        .collect::<Vec<_>>();
+    PoseidonHashCircuit::<Fr, MySpec<WIDTH, RATE>, WIDTH, RATE, CELLS>::hash(
+        cells.try_into().unwrap(),
+    )
+}
+
+/// `SELECT SUM(amount) WHERE age >= min_age`, the query the circuit answers.
+fn query(table: &[Row; ROWS], min_age: u32) -> u64 {
+    table
+        .iter()
+        .filter(|(age, _)| *age >= min_age)
+        .map(|(_, amount)| *amount as u64)
+        .sum()
+}
+
+#[derive(Clone, Debug)]
+struct Config {
+    load: Column<Advice>,
+    instance: Column<Instance>,
+    plonk: StandardPlonkConfig,
+    branch: BranchConfig,
+    tables: Tables,
+    lt: LtConfig<N_BYTES>,
+    range: RangeCheckConfig,
+    poseidon_config: Pow5Config<Fr, WIDTH, RATE>,
+}
+
+/// Answers `SELECT SUM(amount) WHERE age >= min_age` over a private table of `ROWS` rows whose
+/// Poseidon commitment is instance row 0, with `min_age` in row 1 and the sum in row 2. Each
+/// row compares its age to the bound, selects its amount or zero on the result and adds it to
+/// the running sum. The cells are range checked to `u32`s, which keeps the comparison sound and
+/// the sum from wrapping around the field.
+#[derive(Clone, Debug)]
+struct FilteredSumCircuit {
+    table: Value<[Row; ROWS]>,
+}
+
+impl Default for FilteredSumCircuit {
+    fn default() -> Self {
+        Self {
+            table: Value::unknown(),
+        }
+    }
+}
+
+impl FilteredSumCircuit {
+    fn new(table: [Row; ROWS]) -> Self {
+        Self {
+            table: Value::known(table),
+        }
+    }
+
+    fn instance(commitment: Fr, min_age: u32, sum: u64) -> Vec<Fr> {
+        vec![commitment, Fr::from(min_age as u64), Fr::from(sum)]
+    }
+}
+
+impl DescribeInstances for FilteredSumCircuit {
+    fn instance_layout() -> InstanceLayout {
+        InstanceLayout::new("filtered sum")
+            .field(0, "table", Encoding::Field, "commitment to the table")
+            .enforced_field(
+                0,
+                "min age",
+                Encoding::Integer {
+                    bits: 8 * N_BYTES as u32,
+                },
+                "lower bound on the age of the rows summed",
+            )
+            .field(
+                0,
+                "sum",
+                Encoding::Integer {
+                    bits: 8 * N_BYTES as u32 + 3,
+                },
+                "sum of the amounts of the matching rows",
+            )
+    }
+}
+
+impl Circuit<Fr> for FilteredSumCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let state = (0..WIDTH).map(|_| meta.advice_column()).collect::<Vec<_>>();
+        let partial_sbox = meta.advice_column();
+        let rc_a = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        let rc_b = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        meta.enable_constant(rc_b[0]);
+        let load = meta.advice_column();
+        meta.enable_equality(load);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        let mut tables = Tables::new();
+        let lt = LtChip::<Fr, N_BYTES>::configure(meta, &mut tables);
+        let range = RangeCheckChip::<Fr, 8>::configure(meta, &mut tables);
+        Config {
+            load,
+            instance,
+            plonk: StandardPlonkChip::configure(meta),
+            branch: BranchChip::configure(meta),
+            tables,
+            lt,
+            range,
+            poseidon_config: Pow5Chip::configure::<MySpec<WIDTH, RATE>>(
+                meta,
+                state.try_into().unwrap(),
+                partial_sbox,
+                rc_a.try_into().unwrap(),
+                rc_b.try_into().unwrap(),
+            ),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let plonk = StandardPlonkChip::construct(config.plonk.clone());
+        let branch = BranchChip::construct(config.branch.clone());
+        let lt = LtChip::<Fr, N_BYTES>::construct(config.lt.clone());
+        let range = RangeCheckChip::<Fr, 8>::construct(config.range.clone());
+        config.tables.load(&mut layouter)?;
+
+        let (min_age, cells) = layouter.assign_region(
+            || "load table",
+            |mut region| {
+                let min_age = region.assign_advice_from_instance(
+                    || "min age",
+                    config.instance,
+                    1,
+                    config.load,
+                    0,
+                )?;
+                let cells = (0..CELLS)
+                    .map(|i| {
+                        let cell = self.table.map(|table| {
+                            let (age, amount) = table[i / 2];
+                            Fr::from((if i % 2 == 0 { age } else { amount }) as u64)
+                        });
+                        region.assign_advice(|| "cell", config.load, 1 + i, || cell)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok((min_age, cells))
+            },
+        )?;
+        for (i, cell) in std::iter::once(&min_age).chain(cells.iter()).enumerate() {
+            range.range_check(
+                layouter.namespace(|| format!("range {}", i)),
+                cell,
+                8 * N_BYTES,
+            )?;
+        }
+
+        let hasher = Hash::<_, _, MySpec<WIDTH, RATE>, ConstantLength<CELLS>, WIDTH, RATE>::init(
+            Pow5Chip::construct(config.poseidon_config.clone()),
+            layouter.namespace(|| "init commitment"),
+        )?;
+        let commitment = hasher.hash(
+            layouter.namespace(|| "commitment"),
+            cells.clone().try_into().unwrap(),
+        )?;
+        layouter.constrain_instance(commitment.cell(), config.instance, 0)?;
+
+        let zero = plonk.assign_constant(layouter.namespace(|| "zero"), Fr::zero())?;
+        let mut sum = zero.clone();
+        for (row, pair) in cells.chunks(2).enumerate() {
+            let (age, amount) = (&pair[0], &pair[1]);
+            let below = lt.lt(
+                layouter.namespace(|| format!("row {} age < min age", row)),
+                age,
+                &min_age,
+            )?;
+            let (_, term) = branch.select(
+                layouter.namespace(|| format!("row {} filter", row)),
+                Condition::Copy(&below),
+                &zero,
+                amount,
+            )?;
+            sum = plonk.add(
+                layouter.namespace(|| format!("row {} sum", row)),
+                &sum,
+                &term,
+            )?;
+        }
+        layouter.constrain_instance(sum.cell(), config.instance, 2)
+    }
+}
+
+fn run(table: [Row; ROWS], instance: Vec<Fr>) -> bool {
+    let circuit = FilteredSumCircuit::new(table);
+    let k = fit_k::<Fr, _>(&circuit).expect("fit_k");
+    MockProver::run(k, &circuit, vec![instance])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+fn main() {
+    let table: [Row; ROWS] = [
+        (17, 120),
+        (34, 500),
+        (21, 75),
+        (65, 1_000),
+        (18, 40),
+        (20, 300),
+        (90, u32::MAX),
+        (12, 9),
+    ];
+    report_if_requested("zksql", &FilteredSumCircuit::new(table));
+    let root = commitment(&table);
+
+    for min_age in [0, 18, 21, 66, 91] {
+        let sum = query(&table, min_age);
+        assert!(
+            run(table, FilteredSumCircuit::instance(root, min_age, sum)),
+            "min age {}",
+            min_age
+        );
+        assert!(!run(
+            table,
+            FilteredSumCircuit::instance(root, min_age, sum + 1)
+        ));
+    }
+    // the bound is inclusive: 21 counts towards `age >= 21`, not towards `age >= 22`
+    assert_eq!(query(&table, 21) - query(&table, 22), 75);
+    // another table has another commitment, even with the same answer
+    let mut other = table;
+    other[7] = (12, 10);
+    assert_eq!(query(&other, 18), query(&table, 18));
+    assert!(!run(
+        other,
+        FilteredSumCircuit::instance(root, 18, query(&table, 18))
+    ));
+    // a "negative" bound, below every age in the field's order, is not a u32
+    let instance = FilteredSumCircuit::instance(root, 0, query(&table, 0));
+    assert!(!run(table, vec![instance[0], -Fr::one(), instance[2]]));
+
+    let min_age = 21;
+    let instance = FilteredSumCircuit::instance(root, min_age, query(&table, min_age));
+    let circuit = FilteredSumCircuit::new(table);
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &FilteredSumCircuit::default()).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &FilteredSumCircuit::default()).expect("keygen_pk");
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &[&instance],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[&instance],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("verify_proof");
+    let lower = FilteredSumCircuit::instance(root, min_age, query(&table, min_age) - 1);
+    assert!(check_proof(
+        &params,
+        pk.get_vk(),
+        &[&lower],
+        &proof,
+        ProofOptions::default()
+    )
+    .into_result()
+    .is_err());
+    println!(
+        "SUM(amount) WHERE age >= {} = {} over {} private rows, {}-byte proof",
+        min_age,
+        query(&table, min_age),
+        ROWS,
+        proof.len()
+    );
+}
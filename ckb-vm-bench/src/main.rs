@@ -0,0 +1,38 @@
+//! Verifies one fixture written by `riscv_bench` through the byte-slice entry points and
+//! exits 0 if it verifies, so the instructions an emulator counts are those of a verifier as
+//! a RISC-V chain would run it, plus reading the four files.
+
+use std::{env, fs, process};
+
+use halo2_playground::{
+    circuits::mul::MulCircuit,
+    onchain::{verify, verify_circuit},
+};
+use halo2_proofs::halo2curves::bn256::Fr;
+
+fn main() {
+    let args: Vec<String> = env::args().collect();
+    if args.len() != 3 {
+        eprintln!("usage: {} <poseidon|mul> <fixture dir>", args[0]);
+        process::exit(2);
+    }
+    let (circuit, dir) = (&args[1], &args[2]);
+    let read = |ext: &str| {
+        let path = format!("{}/{}.{}", dir, circuit, ext);
+        fs::read(&path).unwrap_or_else(|e| {
+            eprintln!("{}: {}", path, e);
+            process::exit(2);
+        })
+    };
+    let (vk, params, instances, proof) =
+        (read("vk"), read("params"), read("instances"), read("proof"));
+    let verified = match circuit.as_str() {
+        "poseidon" => verify(&vk, &params, &instances, &proof),
+        "mul" => verify_circuit::<MulCircuit<Fr>>(&vk, &params, &instances, &proof),
+        other => {
+            eprintln!("unknown circuit {:?}", other);
+            process::exit(2);
+        }
+    };
+    process::exit(if verified { 0 } else { 1 });
+}
@@ -0,0 +1,46 @@
+/* Verifies a proof through the C ABI: example <poseidon|mul> <params> <vk> <instances> <proof>.
+ * The instances file is in the byte form `onchain::encode_columns` writes, as
+ * `riscv_bench` stores it. Built and run by `make ffi-example`. */
+
+#include <stdio.h>
+#include <string.h>
+
+#include "halo2_playground.h"
+
+static uint8_t *read_file(const char *path, size_t *len) {
+    FILE *file = fopen(path, "rb");
+    if (!file) {
+        perror(path);
+        exit(2);
+    }
+    fseek(file, 0, SEEK_END);
+    *len = (size_t)ftell(file);
+    rewind(file);
+    uint8_t *bytes = malloc(*len ? *len : 1);
+    if (!bytes || fread(bytes, 1, *len, file) != *len) {
+        perror(path);
+        exit(2);
+    }
+    fclose(file);
+    return bytes;
+}
+
+int main(int argc, char **argv) {
+    if (argc != 6) {
+        fprintf(stderr, "usage: %s <poseidon|mul> <params> <vk> <instances> <proof>\n", argv[0]);
+        return 2;
+    }
+    uint32_t circuit = strcmp(argv[1], "mul") == 0 ? HP_CIRCUIT_MUL : HP_CIRCUIT_POSEIDON;
+    size_t lens[4];
+    uint8_t *inputs[4];
+    for (int i = 0; i < 4; i++) {
+        inputs[i] = read_file(argv[i + 2], &lens[i]);
+    }
+    int status = hp_verify_kzg_proof(circuit, inputs[0], lens[0], inputs[1], lens[1], inputs[2],
+                                     lens[2], inputs[3], lens[3]);
+    for (int i = 0; i < 4; i++) {
+        free(inputs[i]);
+    }
+    printf("hp_verify_kzg_proof: %d\n", status);
+    return status == HP_VERIFIED ? 0 : 1;
+}
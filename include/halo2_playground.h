@@ -0,0 +1,70 @@
+/* Generated by `make ffi-header` from src/ffi.rs; do not edit. */
+
+#ifndef HALO2_PLAYGROUND_H
+#define HALO2_PLAYGROUND_H
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+/**
+ * The Poseidon circuit of `playground prove --circuit poseidon`.
+ */
+#define HP_CIRCUIT_POSEIDON 0
+
+/**
+ * The multiplication circuit of `playground prove --circuit mul`.
+ */
+#define HP_CIRCUIT_MUL 1
+
+/**
+ * The proof verified.
+ */
+#define HP_VERIFIED 1
+
+/**
+ * The proof was rejected, or an input didn't parse.
+ */
+#define HP_REJECTED 0
+
+/**
+ * A null pointer with a non-zero length, or an unknown circuit.
+ */
+#define HP_INVALID_ARGUMENT -1
+
+/**
+ * The verifier panicked; nothing unwinds into the host.
+ */
+#define HP_INTERNAL_ERROR -2
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+/**
+ * Verifies a KZG proof made with the default options (SHPLONK, Blake2b) of `circuit`, one
+ * of the `HP_CIRCUIT_*` constants. The formats are those of `onchain::verify_circuit`.
+ * Returns one of the `HP_*` status codes.
+ *
+ * # Safety
+ *
+ * Each pointer must be null with its length zero, or point to that many readable bytes that
+ * stay unchanged during the call.
+ */
+int hp_verify_kzg_proof(uint32_t circuit,
+                        const uint8_t *params,
+                        size_t params_len,
+                        const uint8_t *vk,
+                        size_t vk_len,
+                        const uint8_t *instances,
+                        size_t instances_len,
+                        const uint8_t *proof,
+                        size_t proof_len);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* HALO2_PLAYGROUND_H */
@@ -0,0 +1,158 @@
+//! Verifies a Poseidon proof and a multiplication proof, of different shapes, inside one
+//! circuit and proves that, leaving a single deferred pairing on their folded accumulator to
+//! check natively. The outer circuit needs `k = 22`, so run it in release: `make aggregation`.
+
+use std::time::Instant;
+
+use halo2_playground::{
+    audit::fit_k,
+    circuits::mul::MulCircuit,
+    entropy::SeededEntropy,
+    onchain::PoseidonCircuit,
+    prover::{run_proof, ProofOptions},
+    schema::DescribeInstances,
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_playground_recursion::{
+    aggregation::{
+        aggregate, instance_layout, mul_snarks, outer_instances, poseidon_snarks,
+        AggregationCircuit,
+    },
+    recursion::{decide, poseidon_k, ACCUMULATOR_LIMBS},
+};
+use halo2_proofs::{
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+const OUTER_K: u32 = 22;
+
+fn main() {
+    let messages = [[Fr::from(3), Fr::from(5)], [Fr::from(8), Fr::from(13)]];
+    // one params for both inner circuits, at the larger of their k
+    let mul_k = fit_k(&MulCircuit::new(Fr::zero(), Fr::zero())).expect("fit_k");
+    let inner_k = poseidon_k().expect("poseidon_k").max(mul_k);
+    let inner = cached_params(inner_k).expect("cached_params");
+    let poseidon = poseidon_snarks(&inner, &messages, SeededEntropy::new(0x5962_be5d_763d_318d))
+        .expect("poseidon_snarks");
+    let (limbs, _) =
+        aggregate(&inner, &poseidon, SeededEntropy::new(0x5962_be5d_763d_318d)).expect("aggregate");
+    // the final pairing check, natively, for both proofs at once
+    assert!(decide(&inner, &limbs));
+
+    // one forged statement spoils the folded accumulator
+    let mut forged = poseidon.clone();
+    forged[1].instances[0][0] = PoseidonCircuit::hash([Fr::from(8), Fr::from(14)]);
+    let (forged, _) =
+        aggregate(&inner, &forged, SeededEntropy::new(0x5962_be5d_763d_318d)).expect("aggregate");
+    assert!(!decide(&inner, &forged));
+    assert!(aggregate(&inner, &[], SeededEntropy::new(0x5962_be5d_763d_318d)).is_err());
+
+    // proofs of different circuits, one instance row against two, fold the same way
+    let mul = mul_snarks(
+        &inner,
+        &[(Fr::from(3), Fr::from(5))],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+    )
+    .expect("mul_snarks");
+    let snarks = vec![poseidon[0].clone(), mul[0].clone()];
+    let (limbs, as_proof) =
+        aggregate(&inner, &snarks, SeededEntropy::new(0x5962_be5d_763d_318d)).expect("aggregate");
+    assert!(decide(&inner, &limbs));
+    let mut forged = snarks.clone();
+    forged[1].instances[0][1] = Fr::from(16);
+    let (forged, _) =
+        aggregate(&inner, &forged, SeededEntropy::new(0x5962_be5d_763d_318d)).expect("aggregate");
+    assert!(!decide(&inner, &forged));
+
+    // the combined layout names every outer public input
+    let public = outer_instances(&limbs, &snarks);
+    let layout = instance_layout(&[
+        PoseidonCircuit::instance_layout(),
+        MulCircuit::<Fr>::instance_layout(),
+    ]);
+    assert_eq!(public.len(), ACCUMULATOR_LIMBS + 1 + 2);
+    assert_eq!(layout.fields.len(), public.len());
+    assert_eq!(layout.check_ranges(&[public.clone()]), Ok(()));
+    let row = |name: &str| {
+        layout
+            .fields
+            .iter()
+            .find(|f| f.name == name)
+            .unwrap_or_else(|| panic!("no field {}", name))
+            .row
+    };
+    assert_eq!(
+        public[row("proof0.hash")],
+        PoseidonCircuit::hash(messages[0])
+    );
+    assert_eq!(public[row("proof1.product")], Fr::from(15));
+    assert_eq!(row("proof1.unused"), ACCUMULATOR_LIMBS + 1);
+    let proofs = snarks.len();
+    let circuit = AggregationCircuit::new(&inner, snarks, as_proof);
+    let outer = cached_params(OUTER_K).expect("cached_params");
+    let start = Instant::now();
+    let vk = keygen_vk(&outer, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&outer, vk, &circuit).expect("keygen_pk");
+    let keygen = start.elapsed();
+    let start = Instant::now();
+    let proof = run_proof(
+        &outer,
+        &pk,
+        circuit,
+        &[&public],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("run_proof");
+    let prove = start.elapsed();
+    let start = Instant::now();
+    check_proof(
+        &outer,
+        pk.get_vk(),
+        &[&public],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("check_proof");
+    assert!(decide(&inner, &public));
+    let verify = start.elapsed();
+
+    // the outer proof binds the folded accumulator, and each inner statement
+    let mut other = public.clone();
+    other[0] += Fr::one();
+    assert!(!check_proof(
+        &outer,
+        pk.get_vk(),
+        &[&other],
+        &proof,
+        ProofOptions::default()
+    )
+    .is_verified());
+    assert!(!decide(&inner, &other));
+    for row in ACCUMULATOR_LIMBS..public.len() {
+        let mut other = public.clone();
+        other[row] += Fr::one();
+        assert!(!check_proof(
+            &outer,
+            pk.get_vk(),
+            &[&other],
+            &proof,
+            ProofOptions::default()
+        )
+        .is_verified());
+    }
+
+    println!(
+        "aggregation: {} proofs, k = {}, keygen {:.2?}, prove {:.2?}, verify and decide {:.2?}, \
+         {}-byte proof",
+        proofs,
+        OUTER_K,
+        keygen,
+        prove,
+        verify,
+        proof.len()
+    );
+}
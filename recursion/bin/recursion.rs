@@ -0,0 +1,103 @@
+//! Verifies a Poseidon proof inside a circuit and proves that, leaving only the deferred
+//! pairing on the inner proof's accumulator to check natively. The outer circuit needs
+//! `k = 21`, so run it in release: `make recursion`.
+
+use std::time::Instant;
+
+use halo2_playground::{
+    entropy::SeededEntropy,
+    onchain::PoseidonCircuit,
+    prover::{run_proof, ProofOptions},
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_playground_recursion::recursion::{
+    accumulator_limbs, decide, outer_instances, poseidon_k, poseidon_snark, RecursionCircuit,
+    ACCUMULATOR_LIMBS,
+};
+use halo2_proofs::{
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+const OUTER_K: u32 = 21;
+
+fn main() {
+    let message = [Fr::from(3), Fr::from(5)];
+    let inner = cached_params(poseidon_k().expect("poseidon_k")).expect("cached_params");
+    let snark = poseidon_snark(&inner, message, SeededEntropy::new(0x5962_be5d_763d_318d))
+        .expect("poseidon_snark");
+    let limbs = accumulator_limbs(&inner, &snark).expect("accumulator_limbs");
+    assert!(decide(&inner, &limbs));
+
+    // claiming another hash still yields an accumulator, one the pairing rejects
+    let mut forged = snark.clone();
+    forged.instances[0][0] = PoseidonCircuit::hash([Fr::from(3), Fr::from(6)]);
+    assert!(!decide(
+        &inner,
+        &accumulator_limbs(&inner, &forged).expect("accumulator_limbs")
+    ));
+
+    let public = outer_instances(&limbs, &snark);
+    let circuit = RecursionCircuit::new(&inner, snark);
+    let outer = cached_params(OUTER_K).expect("cached_params");
+    let start = Instant::now();
+    let vk = keygen_vk(&outer, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&outer, vk, &circuit).expect("keygen_pk");
+    let keygen = start.elapsed();
+    let start = Instant::now();
+    let proof = run_proof(
+        &outer,
+        &pk,
+        circuit,
+        &[&public],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("run_proof");
+    let prove = start.elapsed();
+    let start = Instant::now();
+    check_proof(
+        &outer,
+        pk.get_vk(),
+        &[&public],
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()
+    .expect("check_proof");
+    assert!(decide(&inner, &public));
+    let verify = start.elapsed();
+
+    // the outer proof binds the accumulator it exposes, and the statement the inner proof was of
+    let mut other = public.clone();
+    other[0] += Fr::one();
+    assert!(!check_proof(
+        &outer,
+        pk.get_vk(),
+        &[&other],
+        &proof,
+        ProofOptions::default()
+    )
+    .is_verified());
+    assert!(!decide(&inner, &other));
+    let mut other = public.clone();
+    other[ACCUMULATOR_LIMBS] = PoseidonCircuit::hash([Fr::from(3), Fr::from(6)]);
+    assert!(!check_proof(
+        &outer,
+        pk.get_vk(),
+        &[&other],
+        &proof,
+        ProofOptions::default()
+    )
+    .is_verified());
+
+    println!(
+        "recursion: k = {}, keygen {:.2?}, prove {:.2?}, verify and decide {:.2?}, {}-byte proof",
+        OUTER_K,
+        keygen,
+        prove,
+        verify,
+        proof.len()
+    );
+}
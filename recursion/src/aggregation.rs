@@ -0,0 +1,373 @@
+//! Aggregation: a circuit that verifies several proofs with one deferred pairing between
+//! them. Each inner proof is verified up to its KZG accumulator as in
+//! [`recursion`](crate::recursion), then the accumulators are folded into one with
+//! `plonk_verifier`'s KZG accumulation scheme, whose folding proof is a witness too. The
+//! circuit exposes the folded accumulator's limbs, followed by every inner proof's instances
+//! in order, and [`decide`](crate::recursion::decide) checks the one pairing natively.
+//!
+//! The inner proofs needn't be of the same circuit: each carries its own compiled `Protocol`,
+//! so proofs of different shapes, such as the Poseidon and multiplication circuits, fold into
+//! one accumulator as long as they are made under the same params. Those are at the largest
+//! `k` among the inner circuits, so every proof shares the `[s]_2` the pairing is checked
+//! against. The outer key fixes the inner circuits and their order, and [`instance_layout`]
+//! describes the outer public inputs for them.
+//!
+//! The inner proofs use SHPLONK openings over `plonk_verifier`'s Poseidon transcript.
+
+use std::rc::Rc;
+
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{create_proof, keygen_pk, keygen_vk, Circuit, ConstraintSystem, Error},
+    poly::kzg::{
+        commitment::{KZGCommitmentScheme, ParamsKZG},
+        multiopen::ProverSHPLONK,
+    },
+};
+use plonk_verifier::{
+    loader::{
+        halo2::halo2_wrong_ecc::maingate::{MainGateInstructions, RegionCtx},
+        native::NativeLoader,
+    },
+    pcs::{
+        kzg::{Bdfg21, Kzg, KzgAs},
+        AccumulationScheme, AccumulationSchemeProver,
+    },
+    system::halo2::{compile, Config},
+    verifier::{self, PlonkVerifier},
+    Protocol,
+};
+
+use halo2_playground::{
+    circuits::mul::MulCircuit,
+    entropy::EntropySource,
+    error::PlaygroundError,
+    onchain::PoseidonCircuit,
+    schema::{Encoding, InstanceField, InstanceLayout},
+};
+
+use crate::recursion::{
+    assigned_limbs, native_limbs, Halo2Loader, RecursionConfig, Snark, Svk, Transcript,
+    ACCUMULATOR_LIMBS, BITS, LIMBS,
+};
+
+type Pcs = Kzg<Bn256, Bdfg21>;
+type Plonk = verifier::Plonk<Pcs>;
+type As = KzgAs<Pcs>;
+
+/// Proves each circuit on its instance columns, under one key, in the form
+/// [`AggregationCircuit`] verifies. The key comes from the first circuit without its
+/// witnesses, so all of them must share its instance shape.
+pub fn snarks<C: Circuit<Fr>, R: EntropySource>(
+    params: &ParamsKZG<Bn256>,
+    circuits: Vec<(C, Vec<Vec<Fr>>)>,
+    mut rng: R,
+) -> Result<Vec<Snark>, PlaygroundError> {
+    let (circuit, instances) = match circuits.first() {
+        Some(first) => first,
+        None => return Ok(vec![]),
+    };
+    let shape: Vec<usize> = instances.iter().map(Vec::len).collect();
+    let circuit = circuit.without_witnesses();
+    let vk = keygen_vk(params, &circuit).map_err(PlaygroundError::Keygen)?;
+    let pk = keygen_pk(params, vk, &circuit).map_err(PlaygroundError::Keygen)?;
+    let protocol = compile(
+        params,
+        pk.get_vk(),
+        Config::kzg().with_num_instance(shape.clone()),
+    );
+
+    circuits
+        .into_iter()
+        .map(|(circuit, instances)| {
+            if !instances.iter().map(Vec::len).eq(shape.iter().copied()) {
+                return Err(PlaygroundError::Other(format!(
+                    "instance columns of {:?} rows, the key's are {:?}",
+                    instances.iter().map(Vec::len).collect::<Vec<_>>(),
+                    shape
+                )));
+            }
+            let columns: Vec<&[Fr]> = instances.iter().map(Vec::as_slice).collect();
+            let mut transcript = Transcript::<NativeLoader, _>::new(vec![]);
+            create_proof::<KZGCommitmentScheme<Bn256>, ProverSHPLONK<'_, Bn256>, _, _, _, _>(
+                params,
+                &pk,
+                &[circuit],
+                &[&columns[..]],
+                &mut rng,
+                &mut transcript,
+            )
+            .map_err(PlaygroundError::Prove)?;
+            Ok(Snark {
+                protocol: protocol.clone(),
+                instances,
+                proof: transcript.finalize(),
+            })
+        })
+        .collect()
+}
+
+/// Proves the Poseidon circuit on each of `messages`, as [`snarks`].
+pub fn poseidon_snarks<R: EntropySource>(
+    params: &ParamsKZG<Bn256>,
+    messages: &[[Fr; 2]],
+    rng: R,
+) -> Result<Vec<Snark>, PlaygroundError> {
+    let circuits = messages
+        .iter()
+        .map(|&message| {
+            let instances = vec![vec![PoseidonCircuit::hash(message)]];
+            (PoseidonCircuit::new(message), instances)
+        })
+        .collect();
+    snarks(params, circuits, rng)
+}
+
+/// Proves the multiplication circuit on each pair of `factors`, as [`snarks`].
+pub fn mul_snarks<R: EntropySource>(
+    params: &ParamsKZG<Bn256>,
+    factors: &[(Fr, Fr)],
+    rng: R,
+) -> Result<Vec<Snark>, PlaygroundError> {
+    let circuits = factors
+        .iter()
+        .map(|&(a, b)| (MulCircuit::new(a, b), vec![MulCircuit::instance(a * b)]))
+        .collect();
+    snarks(params, circuits, rng)
+}
+
+/// Everything but the pairing of verifying `snarks`, done natively: the folded accumulator's
+/// public limbs, and the folding proof [`AggregationCircuit`] takes as a witness.
+pub fn aggregate<R: EntropySource>(
+    params: &ParamsKZG<Bn256>,
+    snarks: &[Snark],
+    rng: R,
+) -> Result<(Vec<Fr>, Vec<u8>), PlaygroundError> {
+    if snarks.is_empty() {
+        return Err(PlaygroundError::Other("no proofs to aggregate".to_string()));
+    }
+    let svk: Svk = params.get_g()[0].into();
+    let mut accumulators = Vec::new();
+    for snark in snarks {
+        let mut transcript = Transcript::<NativeLoader, _>::new(snark.proof.as_slice());
+        let proof = Plonk::read_proof(&svk, &snark.protocol, &snark.instances, &mut transcript)
+            .map_err(|e| PlaygroundError::Verify(format!("read_proof: {:?}", e)))?;
+        accumulators.extend(
+            Plonk::succinct_verify(&svk, &snark.protocol, &snark.instances, &proof)
+                .map_err(|e| PlaygroundError::Verify(format!("succinct_verify: {:?}", e)))?,
+        );
+    }
+
+    let mut transcript = Transcript::<NativeLoader, _>::new(vec![]);
+    let accumulator = As::create_proof(&Default::default(), &accumulators, &mut transcript, rng)
+        .map_err(|e| PlaygroundError::Verify(format!("accumulate: {:?}", e)))?;
+    Ok((native_limbs(accumulator), transcript.finalize()))
+}
+
+/// The outer proof's public inputs for `snarks`: the folded accumulator's `limbs`, then every
+/// inner instance, proof by proof and column by column.
+pub fn outer_instances(limbs: &[Fr], snarks: &[Snark]) -> Vec<Fr> {
+    limbs
+        .iter()
+        .chain(
+            snarks
+                .iter()
+                .flat_map(|snark| snark.instances.iter().flatten()),
+        )
+        .copied()
+        .collect()
+}
+
+/// The outer proof's public inputs for inner proofs of circuits with the `inner` layouts, in
+/// order, as [`outer_instances`] lays them out: the folded accumulator's limbs, least
+/// significant first, then each inner field as `proof<i>.<name>`. The inner columns are taken
+/// to be as long as their last declared row, as an `InstanceBuilder` builds them.
+pub fn instance_layout(inner: &[InstanceLayout]) -> InstanceLayout {
+    let circuits: Vec<&str> = inner.iter().map(|layout| layout.circuit.as_str()).collect();
+    let mut layout = InstanceLayout::new(format!("aggregation of {}", circuits.join(", ")));
+    for coordinate in ["lhs.x", "lhs.y", "rhs.x", "rhs.y"] {
+        for limb in 0..LIMBS {
+            layout = layout.field(
+                0,
+                format!("accumulator.{}[{}]", coordinate, limb),
+                Encoding::Integer { bits: BITS as u32 },
+                format!(
+                    "limb {} of the folded accumulator's {}, for `recursion::decide`",
+                    limb, coordinate
+                ),
+            );
+        }
+    }
+    let mut row = ACCUMULATOR_LIMBS;
+    for (proof, proof_layout) in inner.iter().enumerate() {
+        for column in 0..proof_layout.num_columns() {
+            let fields = proof_layout.fields.iter().filter(|f| f.column == column);
+            let len = fields.clone().map(|f| f.row + 1).max().unwrap_or(0);
+            for f in fields {
+                layout.fields.push(InstanceField {
+                    name: format!("proof{}.{}", proof, f.name),
+                    column: 0,
+                    row: row + f.row,
+                    encoding: f.encoding,
+                    enforced: f.enforced,
+                    semantics: format!(
+                        "{}, of proof {} ({})",
+                        f.semantics, proof, proof_layout.circuit
+                    ),
+                });
+            }
+            row += len;
+        }
+    }
+    layout
+}
+
+#[derive(Clone, Debug)]
+struct SnarkWitness {
+    protocol: Protocol<G1Affine>,
+    instances: Vec<Vec<Value<Fr>>>,
+    proof: Value<Vec<u8>>,
+}
+
+impl SnarkWitness {
+    fn without_witnesses(&self) -> Self {
+        SnarkWitness {
+            protocol: self.protocol.clone(),
+            instances: self
+                .instances
+                .iter()
+                .map(|column| vec![Value::unknown(); column.len()])
+                .collect(),
+            proof: Value::unknown(),
+        }
+    }
+}
+
+impl From<Snark> for SnarkWitness {
+    fn from(snark: Snark) -> Self {
+        SnarkWitness {
+            protocol: snark.protocol,
+            instances: snark
+                .instances
+                .iter()
+                .map(|column| column.iter().copied().map(Value::known).collect())
+                .collect(),
+            proof: Value::known(snark.proof),
+        }
+    }
+}
+
+/// Verifies inner proofs up to their pairings and folds their accumulators, exposing
+/// the folded accumulator's limbs in instance rows `0..ACCUMULATOR_LIMBS` and the inner
+/// instances in the rows after them (see [`outer_instances`]).
+#[derive(Clone, Debug)]
+pub struct AggregationCircuit {
+    svk: Svk,
+    snarks: Vec<SnarkWitness>,
+    as_proof: Value<Vec<u8>>,
+}
+
+impl AggregationCircuit {
+    /// Aggregating `snarks`, made under `params`, with the folding proof from [`aggregate`].
+    pub fn new(params: &ParamsKZG<Bn256>, snarks: Vec<Snark>, as_proof: Vec<u8>) -> Self {
+        AggregationCircuit {
+            svk: params.get_g()[0].into(),
+            snarks: snarks.into_iter().map(SnarkWitness::from).collect(),
+            as_proof: Value::known(as_proof),
+        }
+    }
+}
+
+impl Circuit<Fr> for AggregationCircuit {
+    type Config = RecursionConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        AggregationCircuit {
+            svk: self.svk,
+            snarks: self
+                .snarks
+                .iter()
+                .map(SnarkWitness::without_witnesses)
+                .collect(),
+            as_proof: Value::unknown(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        RecursionConfig::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let main_gate = config.main_gate();
+        config.load_range(&mut layouter)?;
+
+        let (limbs, instances) = layouter.assign_region(
+            || "aggregate inner proofs",
+            |region| {
+                let loader = Halo2Loader::new(config.ecc_chip(), RegionCtx::new(region, 0));
+                let mut accumulators = Vec::new();
+                let mut public = Vec::new();
+                for snark in &self.snarks {
+                    let protocol = snark.protocol.loaded(&loader);
+                    let instances: Vec<Vec<_>> = snark
+                        .instances
+                        .iter()
+                        .map(|column| {
+                            column
+                                .iter()
+                                .map(|value| loader.assign_scalar(*value))
+                                .collect()
+                        })
+                        .collect();
+                    let mut transcript = Transcript::<Rc<Halo2Loader>, _>::new(
+                        &loader,
+                        snark.proof.as_ref().map(Vec::as_slice),
+                    );
+                    let proof =
+                        Plonk::read_proof(&self.svk, &protocol, &instances, &mut transcript)
+                            .map_err(|_| Error::Synthesis)?;
+                    accumulators.extend(
+                        Plonk::succinct_verify(&self.svk, &protocol, &instances, &proof)
+                            .map_err(|_| Error::Synthesis)?,
+                    );
+                    public.extend(
+                        instances
+                            .iter()
+                            .flatten()
+                            .map(|instance| instance.assigned()),
+                    );
+                }
+                if accumulators.is_empty() {
+                    return Err(Error::Synthesis);
+                }
+
+                let mut transcript = Transcript::<Rc<Halo2Loader>, _>::new(
+                    &loader,
+                    self.as_proof.as_ref().map(Vec::as_slice),
+                );
+                let proof = As::read_proof(&Default::default(), &accumulators, &mut transcript)
+                    .map_err(|_| Error::Synthesis)?;
+                let accumulator = As::verify(&Default::default(), &accumulators, &proof)
+                    .map_err(|_| Error::Synthesis)?;
+                Ok((assigned_limbs(accumulator), public))
+            },
+        )?;
+        for (row, limb) in limbs.into_iter().enumerate() {
+            main_gate.expose_public(layouter.namespace(|| "accumulator"), limb, row)?;
+        }
+        for (row, instance) in instances.into_iter().enumerate() {
+            main_gate.expose_public(
+                layouter.namespace(|| "inner instance"),
+                instance,
+                ACCUMULATOR_LIMBS + row,
+            )?;
+        }
+        Ok(())
+    }
+}
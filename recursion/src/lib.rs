@@ -0,0 +1,5 @@
+//! Proofs of the playground's circuits verified inside other circuits with `plonk_verifier`,
+//! their pairings deferred to whoever verifies the outer proof.
+
+pub mod aggregation;
+pub mod recursion;
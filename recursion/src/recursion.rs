@@ -0,0 +1,333 @@
+//! One level of recursion: a circuit that verifies a proof of the Poseidon circuit. The inner
+//! proof is a witness; the circuit replays its transcript and checks its openings with
+//! `plonk_verifier`'s halo2 loader, on halo2wrong's non-native BN254 arithmetic, up to the
+//! final pairing. That pairing can't be done in the circuit, so it is deferred: the circuit
+//! exposes the KZG accumulator `(lhs, rhs)` as public limbs, followed by the inner proof's
+//! instances so the outer proof says which statement was verified, and whoever verifies the
+//! outer proof checks `e(lhs, [1]_2) = e(rhs, [s]_2)` with [`decide`].
+//!
+//! The inner proof is made with GWC openings over `plonk_verifier`'s Poseidon transcript,
+//! which is cheap to replay in the circuit; the outer proof can use any of the playground's.
+
+use std::rc::Rc;
+
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    halo2curves::{
+        bn256::{Bn256, Fq, Fr, G1Affine},
+        pairing::Engine,
+    },
+    plonk::{create_proof, keygen_pk, keygen_vk, Circuit, ConstraintSystem, Error},
+    poly::kzg::{
+        commitment::{KZGCommitmentScheme, ParamsKZG},
+        multiopen::ProverGWC,
+    },
+};
+use plonk_verifier::{
+    loader::{
+        self,
+        halo2::halo2_wrong_ecc::{
+            self,
+            integer::rns::Rns,
+            maingate::{
+                AssignedValue, MainGate, MainGateConfig, MainGateInstructions, RangeChip,
+                RangeConfig, RangeInstructions, RegionCtx,
+            },
+            EccConfig,
+        },
+        native::NativeLoader,
+    },
+    pcs::kzg::{Gwc19, Kzg, KzgAccumulator, KzgSuccinctVerifyingKey},
+    system::halo2::{compile, transcript::halo2::PoseidonTranscript, Config},
+    util::arithmetic::{fe_from_limbs, fe_to_limbs},
+    verifier::{self, PlonkVerifier},
+    Protocol,
+};
+
+use halo2_playground::{
+    audit::fit_k, entropy::EntropySource, error::PlaygroundError, onchain::PoseidonCircuit,
+};
+
+/// Limbs of a base field element in the circuit, and their bits.
+pub const LIMBS: usize = 4;
+pub const BITS: usize = 68;
+/// Public limbs of the accumulator: the coordinates of `lhs` and `rhs`.
+pub const ACCUMULATOR_LIMBS: usize = 4 * LIMBS;
+
+// the transcript's Poseidon: width 5, rate 4, 8 full and 60 partial rounds
+const T: usize = 5;
+const RATE: usize = 4;
+const R_F: usize = 8;
+const R_P: usize = 60;
+
+type Plonk = verifier::Plonk<Kzg<Bn256, Gwc19>>;
+pub(crate) type Svk = KzgSuccinctVerifyingKey<G1Affine>;
+pub(crate) type BaseFieldEccChip = halo2_wrong_ecc::BaseFieldEccChip<G1Affine, LIMBS, BITS>;
+pub(crate) type Halo2Loader<'a> = loader::halo2::Halo2Loader<'a, G1Affine, BaseFieldEccChip>;
+pub(crate) type Transcript<L, S> = PoseidonTranscript<G1Affine, L, S, T, RATE, R_F, R_P>;
+
+/// A proof with what verifying it takes besides the params: the inner circuit compiled to a
+/// `Protocol`, and its public inputs.
+#[derive(Clone, Debug)]
+pub struct Snark {
+    pub protocol: Protocol<G1Affine>,
+    pub instances: Vec<Vec<Fr>>,
+    pub proof: Vec<u8>,
+}
+
+/// Proves the Poseidon circuit on `message` in the form [`RecursionCircuit`] verifies.
+pub fn poseidon_snark<R: EntropySource>(
+    params: &ParamsKZG<Bn256>,
+    message: [Fr; 2],
+    rng: R,
+) -> Result<Snark, PlaygroundError> {
+    let circuit = PoseidonCircuit::new(message);
+    let vk = keygen_vk(params, &circuit).map_err(PlaygroundError::Keygen)?;
+    let pk = keygen_pk(params, vk, &circuit).map_err(PlaygroundError::Keygen)?;
+    let protocol = compile(
+        params,
+        pk.get_vk(),
+        Config::kzg().with_num_instance(vec![1]),
+    );
+    let instances = vec![vec![PoseidonCircuit::hash(message)]];
+
+    let mut transcript = Transcript::<NativeLoader, _>::new(vec![]);
+    create_proof::<KZGCommitmentScheme<Bn256>, ProverGWC<'_, Bn256>, _, _, _, _>(
+        params,
+        &pk,
+        &[circuit],
+        &[&[&instances[0]]],
+        rng,
+        &mut transcript,
+    )
+    .map_err(PlaygroundError::Prove)?;
+    Ok(Snark {
+        protocol,
+        instances,
+        proof: transcript.finalize(),
+    })
+}
+
+/// The `k` the inner Poseidon proof needs params for.
+pub fn poseidon_k() -> Result<u32, PlaygroundError> {
+    fit_k(&PoseidonCircuit::new([Fr::zero(); 2]))
+}
+
+/// Everything but the pairing of verifying `snark`, done natively: the accumulator the
+/// circuit exposes for it, as its public limbs.
+pub fn accumulator_limbs(
+    params: &ParamsKZG<Bn256>,
+    snark: &Snark,
+) -> Result<Vec<Fr>, PlaygroundError> {
+    let svk: Svk = params.get_g()[0].into();
+    let mut transcript = Transcript::<NativeLoader, _>::new(snark.proof.as_slice());
+    let proof = Plonk::read_proof(&svk, &snark.protocol, &snark.instances, &mut transcript)
+        .map_err(|e| PlaygroundError::Verify(format!("read_proof: {:?}", e)))?;
+    let mut accumulators = Plonk::succinct_verify(&svk, &snark.protocol, &snark.instances, &proof)
+        .map_err(|e| PlaygroundError::Verify(format!("succinct_verify: {:?}", e)))?;
+    match (accumulators.pop(), accumulators.is_empty()) {
+        (Some(accumulator), true) => Ok(native_limbs(accumulator)),
+        _ => Err(PlaygroundError::Verify(
+            "expected one accumulator".to_string(),
+        )),
+    }
+}
+
+/// The outer proof's public inputs for `snark`: the accumulator's `limbs`, then every inner
+/// instance, column by column.
+pub fn outer_instances(limbs: &[Fr], snark: &Snark) -> Vec<Fr> {
+    limbs
+        .iter()
+        .chain(snark.instances.iter().flatten())
+        .copied()
+        .collect()
+}
+
+/// The deferred pairing: whether the accumulator in the first `ACCUMULATOR_LIMBS` of a
+/// recursion proof's public inputs holds under the inner proof's `params`.
+pub fn decide(params: &ParamsKZG<Bn256>, limbs: &[Fr]) -> bool {
+    if limbs.len() < ACCUMULATOR_LIMBS {
+        return false;
+    }
+    let coordinate = |i: usize| {
+        let mut element = [Fr::zero(); LIMBS];
+        element.copy_from_slice(&limbs[i * LIMBS..(i + 1) * LIMBS]);
+        fe_from_limbs::<Fq, Fr, LIMBS, BITS>(element)
+    };
+    let point =
+        |i: usize| Option::<G1Affine>::from(G1Affine::from_xy(coordinate(i), coordinate(i + 1)));
+    match (point(0), point(2)) {
+        (Some(lhs), Some(rhs)) => {
+            Bn256::pairing(&lhs, &params.g2()) == Bn256::pairing(&rhs, &params.s_g2())
+        }
+        _ => false,
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RecursionConfig {
+    main_gate: MainGateConfig,
+    range: RangeConfig,
+}
+
+impl RecursionConfig {
+    /// The main gate and the range checks of the non-native arithmetic, for any circuit
+    /// verifying proofs with the halo2 loader.
+    pub(crate) fn configure(meta: &mut ConstraintSystem<Fr>) -> Self {
+        let main_gate = MainGate::<Fr>::configure(meta);
+        let range = RangeChip::<Fr>::configure(
+            meta,
+            &main_gate,
+            vec![BITS / LIMBS],
+            Rns::<Fq, Fr, LIMBS, BITS>::construct().overflow_lengths(),
+        );
+        RecursionConfig { main_gate, range }
+    }
+
+    pub(crate) fn main_gate(&self) -> MainGate<Fr> {
+        MainGate::<Fr>::new(self.main_gate.clone())
+    }
+
+    /// Loads the range table, once per circuit.
+    pub(crate) fn load_range(&self, layouter: &mut impl Layouter<Fr>) -> Result<(), Error> {
+        RangeChip::<Fr>::new(self.range.clone()).load_table(layouter)
+    }
+
+    pub(crate) fn ecc_chip(&self) -> BaseFieldEccChip {
+        BaseFieldEccChip::new(EccConfig::new(self.range.clone(), self.main_gate.clone()))
+    }
+}
+
+/// The public limbs of an accumulator, `lhs.x` first, as native values.
+pub(crate) fn native_limbs(accumulator: KzgAccumulator<G1Affine, NativeLoader>) -> Vec<Fr> {
+    let KzgAccumulator { lhs, rhs } = accumulator;
+    [lhs.x, lhs.y, rhs.x, rhs.y]
+        .into_iter()
+        .flat_map(fe_to_limbs::<Fq, Fr, LIMBS, BITS>)
+        .collect()
+}
+
+/// The limbs of an accumulator assigned by the halo2 loader, in the order of `native_limbs`.
+pub(crate) fn assigned_limbs(
+    accumulator: KzgAccumulator<G1Affine, Rc<Halo2Loader<'_>>>,
+) -> Vec<AssignedValue<Fr>> {
+    let KzgAccumulator { lhs, rhs } = accumulator;
+    let (lhs, rhs) = (lhs.assigned(), rhs.assigned());
+    [lhs.x(), lhs.y(), rhs.x(), rhs.y()]
+        .into_iter()
+        .flat_map(|coordinate| {
+            coordinate
+                .limbs()
+                .iter()
+                .map(|limb| limb.as_ref().clone())
+                .collect::<Vec<_>>()
+        })
+        .collect()
+}
+
+/// Verifies an inner Poseidon proof up to its pairing, exposing the accumulator's limbs in
+/// instance rows `0..ACCUMULATOR_LIMBS`, `lhs.x` first, and the inner instances in the rows
+/// after them (see [`outer_instances`]).
+#[derive(Clone, Debug)]
+pub struct RecursionCircuit {
+    svk: Svk,
+    protocol: Protocol<G1Affine>,
+    instances: Vec<Vec<Value<Fr>>>,
+    proof: Value<Vec<u8>>,
+}
+
+impl RecursionCircuit {
+    /// Verifying `snark`, made under `params`.
+    pub fn new(params: &ParamsKZG<Bn256>, snark: Snark) -> Self {
+        RecursionCircuit {
+            svk: params.get_g()[0].into(),
+            protocol: snark.protocol,
+            instances: snark
+                .instances
+                .iter()
+                .map(|column| column.iter().copied().map(Value::known).collect())
+                .collect(),
+            proof: Value::known(snark.proof),
+        }
+    }
+}
+
+impl Circuit<Fr> for RecursionCircuit {
+    type Config = RecursionConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        RecursionCircuit {
+            svk: self.svk,
+            protocol: self.protocol.clone(),
+            instances: self
+                .instances
+                .iter()
+                .map(|column| vec![Value::unknown(); column.len()])
+                .collect(),
+            proof: Value::unknown(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        RecursionConfig::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let main_gate = config.main_gate();
+        config.load_range(&mut layouter)?;
+
+        let (limbs, instances) = layouter.assign_region(
+            || "verify inner proof",
+            |region| {
+                let loader = Halo2Loader::new(config.ecc_chip(), RegionCtx::new(region, 0));
+                let protocol = self.protocol.loaded(&loader);
+                let instances: Vec<Vec<_>> = self
+                    .instances
+                    .iter()
+                    .map(|column| {
+                        column
+                            .iter()
+                            .map(|value| loader.assign_scalar(*value))
+                            .collect()
+                    })
+                    .collect();
+                let mut transcript = Transcript::<Rc<Halo2Loader>, _>::new(
+                    &loader,
+                    self.proof.as_ref().map(Vec::as_slice),
+                );
+                let proof = Plonk::read_proof(&self.svk, &protocol, &instances, &mut transcript)
+                    .map_err(|_| Error::Synthesis)?;
+                let mut accumulators =
+                    Plonk::succinct_verify(&self.svk, &protocol, &instances, &proof)
+                        .map_err(|_| Error::Synthesis)?;
+                let limbs = match (accumulators.pop(), accumulators.is_empty()) {
+                    (Some(accumulator), true) => assigned_limbs(accumulator),
+                    _ => return Err(Error::Synthesis),
+                };
+                let instances = instances
+                    .iter()
+                    .flatten()
+                    .map(|instance| instance.assigned())
+                    .collect::<Vec<_>>();
+                Ok((limbs, instances))
+            },
+        )?;
+        for (row, limb) in limbs.into_iter().enumerate() {
+            main_gate.expose_public(layouter.namespace(|| "accumulator"), limb, row)?;
+        }
+        // the statement: without these the inner instances would be free witnesses
+        for (row, instance) in instances.into_iter().enumerate() {
+            main_gate.expose_public(
+                layouter.namespace(|| "inner instance"),
+                instance,
+                ACCUMULATOR_LIMBS + row,
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,438 @@
+//! The MSM and FFT hot paths the playground runs itself, on the CPU or, with the `gpu` feature,
+//! on a CUDA device through icicle. `commit_instances` and its variants commit through
+//! [`Bases`], `instance_stream` through [`msm`], and [`Backend::lagrange_to_coeff`] and
+//! [`Backend::coeff_to_lagrange`] convert bn256 columns for anything that wants coefficients.
+//!
+//! halo2's `keygen_pk` and `create_proof` call the fork's `best_multiexp` and `best_fft`
+//! directly, with nothing to swap them through, so proving stays on the CPU under either
+//! backend until the fork grows such a hook.
+//!
+//! Only bn256 goes to the device. Other curves, inputs shorter than [`GPU_MIN_LEN`] and device
+//! errors, warned about once, take the CPU path: the backend changes timings, never results.
+
+use std::{
+    any::{Any, TypeId},
+    io::{self, Write},
+    sync::{Arc, Mutex},
+};
+
+use halo2_proofs::{
+    arithmetic::{best_fft, best_multiexp, CurveAffine, Field},
+    halo2curves::{
+        bn256::{Fr, G1Affine, G1},
+        group::GroupEncoding,
+    },
+    poly::{
+        commitment::{Blind, Params},
+        Coeff, EvaluationDomain, LagrangeCoeff, Polynomial,
+    },
+};
+
+const POINT_BYTES: usize = 32;
+
+/// MSMs and FFTs shorter than this stay on the CPU, which finishes them before a transfer to
+/// the device would.
+pub const GPU_MIN_LEN: usize = 1 << 12;
+
+/// Where an MSM or FFT runs.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Backend {
+    Cpu,
+    /// A CUDA device through icicle.
+    #[cfg(feature = "gpu")]
+    Gpu,
+}
+
+/// The GPU if the `gpu` feature found a device, the CPU otherwise.
+pub fn backend() -> Backend {
+    #[cfg(feature = "gpu")]
+    if gpu::device() {
+        return Backend::Gpu;
+    }
+    Backend::Cpu
+}
+
+/// Every backend this process can run on, the CPU first, e.g. to compare them.
+pub fn backends() -> Vec<Backend> {
+    let mut backends = vec![Backend::Cpu];
+    if backend() != Backend::Cpu {
+        backends.push(backend());
+    }
+    backends
+}
+
+/// `sum scalars[i] * bases[i]` on the active backend, as `best_multiexp` computes it.
+pub fn msm(scalars: &[Fr], bases: &[G1Affine]) -> G1 {
+    backend().msm(scalars, bases)
+}
+
+impl Backend {
+    pub fn name(self) -> &'static str {
+        match self {
+            Backend::Cpu => "cpu",
+            #[cfg(feature = "gpu")]
+            Backend::Gpu => "gpu",
+        }
+    }
+
+    /// `sum scalars[i] * bases[i]`, as `best_multiexp` computes it. For bases used more than
+    /// once, [`Bases`] keeps them on the device.
+    pub fn msm(self, scalars: &[Fr], bases: &[G1Affine]) -> G1 {
+        assert_eq!(scalars.len(), bases.len(), "one base per scalar");
+        #[cfg(feature = "gpu")]
+        if self == Backend::Gpu && scalars.len() >= GPU_MIN_LEN {
+            if let Some(result) = gpu::msm(scalars, &gpu::host_points(bases)) {
+                return result;
+            }
+        }
+        best_multiexp(scalars, bases)
+    }
+
+    /// The commitment to `values`, the first rows of a column, as `commit_lagrange` without a
+    /// blind computes it.
+    pub fn commit(self, bases: &Bases, values: &[Fr]) -> G1 {
+        let n = bases.points.len();
+        assert!(values.len() <= n, "more values than the params have bases");
+        #[cfg(feature = "gpu")]
+        if self == Backend::Gpu && values.len() >= GPU_MIN_LEN {
+            if let Some(device) = &bases.device {
+                let mut padded = values.to_vec();
+                padded.resize(n, Fr::zero());
+                if let Some(result) = gpu::msm(&padded, device.points()) {
+                    return result;
+                }
+            }
+        }
+        best_multiexp(values, &bases.points[..values.len()])
+    }
+
+    /// The coefficients of the polynomial taking `values`, padded with zeros, on the points
+    /// of `domain`, as `EvaluationDomain::lagrange_to_coeff` computes them.
+    pub fn lagrange_to_coeff(
+        self,
+        domain: &EvaluationDomain<Fr>,
+        values: Vec<Fr>,
+    ) -> Polynomial<Fr, Coeff> {
+        let values = pad(domain, values);
+        #[cfg(feature = "gpu")]
+        if self == Backend::Gpu && values.len() >= GPU_MIN_LEN {
+            if let Some(coeffs) = gpu::ntt(&values, domain.k(), true) {
+                return domain.coeff_from_vec(coeffs);
+            }
+        }
+        domain.lagrange_to_coeff(domain.lagrange_from_vec(values))
+    }
+
+    /// The values on the points of `domain` of the polynomial with `coeffs`, padded with zeros.
+    pub fn coeff_to_lagrange(
+        self,
+        domain: &EvaluationDomain<Fr>,
+        coeffs: Vec<Fr>,
+    ) -> Polynomial<Fr, LagrangeCoeff> {
+        let mut values = pad(domain, coeffs);
+        #[cfg(feature = "gpu")]
+        if self == Backend::Gpu && values.len() >= GPU_MIN_LEN {
+            if let Some(values) = gpu::ntt(&values, domain.k(), false) {
+                return domain.lagrange_from_vec(values);
+            }
+        }
+        best_fft(&mut values, domain.get_omega(), domain.k());
+        domain.lagrange_from_vec(values)
+    }
+}
+
+fn pad(domain: &EvaluationDomain<Fr>, mut values: Vec<Fr>) -> Vec<Fr> {
+    let n = 1 << domain.k();
+    assert!(values.len() <= n, "more values than the domain has points");
+    values.resize(n, Fr::zero());
+    values
+}
+
+/// The Lagrange bases of bn256 params, also on the device when there is one, to commit to
+/// full columns without going through the params each time.
+pub struct Bases {
+    points: Vec<G1Affine>,
+    /// IPA's `w`, which a commitment adds `blind` times; `None` for KZG, which ignores blinds.
+    blind_base: Option<G1Affine>,
+    #[cfg(feature = "gpu")]
+    device: Option<gpu::DevicePoints>,
+}
+
+impl Bases {
+    /// Reads the bases out of halo2's serialization of `params`, the only way to them the
+    /// params fields allow; as slow as reading a params file, once per SRS.
+    pub fn new<'params, P: Params<'params, G1Affine>>(params: &P) -> io::Result<Self> {
+        Self::read(params)
+    }
+
+    /// `new` for params of any curve, which must be bn256's.
+    fn read<'params, C: CurveAffine, P: Params<'params, C>>(params: &P) -> io::Result<Self> {
+        let n = params.n() as usize;
+        let mut bytes = Vec::with_capacity(4 + 2 * n * POINT_BYTES);
+        params.write(&mut bytes)?;
+        let start = 4 + n * POINT_BYTES;
+        let end = start + n * POINT_BYTES;
+        let point = |chunk: &[u8]| {
+            let mut repr = <G1Affine as GroupEncoding>::Repr::default();
+            repr.as_mut().copy_from_slice(chunk);
+            Option::<G1Affine>::from(G1Affine::from_bytes(&repr)).ok_or_else(|| {
+                io::Error::new(io::ErrorKind::InvalidData, "invalid Lagrange basis point")
+            })
+        };
+        let points = bytes
+            .get(start..end)
+            .ok_or_else(|| io::Error::new(io::ErrorKind::UnexpectedEof, "no Lagrange bases"))?
+            .chunks(POINT_BYTES)
+            .map(point)
+            .collect::<io::Result<Vec<_>>>()?;
+        // IPA params end in the G1 points `w` and `u`, KZG params in two G2 points
+        let blind_base = match &bytes[end..] {
+            trailer if trailer.len() == 2 * POINT_BYTES => Some(point(&trailer[..POINT_BYTES])?),
+            _ => None,
+        };
+        Ok(Self {
+            #[cfg(feature = "gpu")]
+            device: if gpu::device() {
+                gpu::upload(&points)
+            } else {
+                None
+            },
+            points,
+            blind_base,
+        })
+    }
+
+    pub fn points(&self) -> &[G1Affine] {
+        &self.points
+    }
+}
+
+/// The bases of `params` for `commit_lagrange`, when they are worth having: bn256 params and a
+/// device to multiply them on. The last params asked about are kept, told apart by `k`,
+/// `[1]_1` and `[s]_1`, the start of their serialization.
+pub(crate) fn cached_bases<'params, C: CurveAffine, P: Params<'params, C>>(
+    params: &P,
+) -> Option<Arc<Bases>> {
+    static CACHE: Mutex<Option<(Vec<u8>, Arc<Bases>)>> = Mutex::new(None);
+    if backend() == Backend::Cpu || TypeId::of::<C>() != TypeId::of::<G1Affine>() {
+        return None;
+    }
+    let mut key = Prefix {
+        bytes: vec![],
+        len: 4 + 2 * POINT_BYTES,
+    };
+    // stops with an error once the prefix is written
+    let _ = params.write(&mut key);
+    let mut cache = CACHE
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner());
+    if let Some((cached, bases)) = cache.as_ref() {
+        if *cached == key.bytes {
+            return Some(bases.clone());
+        }
+    }
+    let bases = Arc::new(Bases::read(params).ok()?);
+    *cache = Some((key.bytes, bases.clone()));
+    Some(bases)
+}
+
+/// `params.commit_lagrange` of `values`, all `n` of them, through `bases` when there are some,
+/// adding the blind's multiple of IPA's `w` as the params would.
+pub(crate) fn commit_lagrange<'params, C: CurveAffine, P: Params<'params, C>>(
+    params: &P,
+    bases: Option<&Bases>,
+    domain: &EvaluationDomain<C::Scalar>,
+    values: Vec<C::Scalar>,
+    blind: C::Scalar,
+) -> C::Curve {
+    if let Some(bases) = bases {
+        let fr = (&values as &dyn Any).downcast_ref::<Vec<Fr>>();
+        if let (Some(values), Some(blind)) = (fr, (&blind as &dyn Any).downcast_ref::<Fr>()) {
+            let mut commitment = backend().commit(bases, values);
+            if let Some(w) = bases.blind_base {
+                commitment += w * *blind;
+            }
+            let commitment: Box<dyn Any> = Box::new(commitment);
+            if let Ok(commitment) = commitment.downcast::<C::Curve>() {
+                return *commitment;
+            }
+        }
+    }
+    params.commit_lagrange(&domain.lagrange_from_vec(values), Blind(blind))
+}
+
+/// Keeps the first `len` bytes written to it, then refuses more.
+struct Prefix {
+    bytes: Vec<u8>,
+    len: usize,
+}
+
+impl Write for Prefix {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let taken = (self.len - self.bytes.len()).min(buf.len());
+        self.bytes.extend_from_slice(&buf[..taken]);
+        Ok(taken)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        Ok(())
+    }
+}
+
+#[cfg(feature = "gpu")]
+mod gpu {
+    use std::{
+        fmt,
+        sync::{Mutex, Once},
+    };
+
+    use halo2_proofs::{
+        arithmetic::{CurveAffine, Field},
+        halo2curves::{
+            bn256::{Fq, Fr, G1Affine, G1},
+            group::{ff::PrimeField, prime::PrimeCurveAffine, Group},
+        },
+    };
+    use icicle_bn254::curve::{BaseField, CurveCfg, G1Projective, ScalarField};
+    use icicle_core::{
+        curve::Affine,
+        msm::{self, MSMConfig},
+        ntt::{self, NTTConfig, NTTDir},
+        traits::FieldImpl,
+    };
+    use icicle_cuda_runtime::{device, device_context::DeviceContext, memory::HostOrDeviceSlice};
+
+    type Point = Affine<CurveCfg>;
+
+    /// The largest NTT, `2^MAX_K` points. icicle computes the twiddles of one domain per
+    /// process, 512 MiB of device memory for this one; larger transforms stay on the CPU.
+    const MAX_K: u32 = 24;
+
+    pub fn device() -> bool {
+        static DEVICE: Mutex<Option<bool>> = Mutex::new(None);
+        *DEVICE
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get_or_insert_with(|| matches!(device::get_device_count(), Ok(count) if count > 0))
+    }
+
+    fn fall_back<T>(operation: &str, err: impl fmt::Debug) -> Option<T> {
+        static WARNING: Once = Once::new();
+        WARNING.call_once(|| {
+            eprintln!(
+                "warning: {} failed on the GPU ({:?}), running on the CPU",
+                operation, err
+            )
+        });
+        None
+    }
+
+    fn scalar(value: &Fr) -> ScalarField {
+        ScalarField::from_bytes_le(value.to_repr().as_ref())
+    }
+
+    fn from_scalar(value: &ScalarField) -> Fr {
+        let mut repr = [0u8; 32];
+        repr.copy_from_slice(&value.to_bytes_le());
+        Option::from(Fr::from_repr(repr)).expect("icicle returns canonical scalars")
+    }
+
+    fn point(point: &G1Affine) -> Point {
+        let coordinates: Option<_> = point.coordinates().into();
+        coordinates.map_or(Point::zero(), |coordinates| Point {
+            x: BaseField::from_bytes_le(coordinates.x().to_repr().as_ref()),
+            y: BaseField::from_bytes_le(coordinates.y().to_repr().as_ref()),
+        })
+    }
+
+    fn from_projective(point: G1Projective) -> G1 {
+        let point = Point::from(point);
+        if point == Point::zero() {
+            return G1::identity();
+        }
+        let coordinate = |value: &BaseField| {
+            let mut repr = [0u8; 32];
+            repr.copy_from_slice(&value.to_bytes_le());
+            Option::<Fq>::from(Fq::from_repr(repr)).expect("icicle returns canonical coordinates")
+        };
+        Option::<G1Affine>::from(G1Affine::from_xy(
+            coordinate(&point.x),
+            coordinate(&point.y),
+        ))
+        .expect("icicle returns points on the curve")
+        .to_curve()
+    }
+
+    pub fn host_points(points: &[G1Affine]) -> HostOrDeviceSlice<'static, Point> {
+        HostOrDeviceSlice::Host(points.iter().map(point).collect())
+    }
+
+    /// Points uploaded once, for every MSM with them.
+    pub struct DevicePoints(HostOrDeviceSlice<'static, Point>);
+
+    impl DevicePoints {
+        pub fn points(&self) -> &HostOrDeviceSlice<'static, Point> {
+            &self.0
+        }
+    }
+
+    pub fn upload(points: &[G1Affine]) -> Option<DevicePoints> {
+        let host: Vec<Point> = points.iter().map(point).collect();
+        let mut device = match HostOrDeviceSlice::cuda_malloc(host.len()) {
+            Ok(device) => device,
+            Err(err) => return fall_back("cuda_malloc", err),
+        };
+        if let Err(err) = device.copy_from_host(&host) {
+            return fall_back("copy_from_host", err);
+        }
+        Some(DevicePoints(device))
+    }
+
+    /// An MSM of as many scalars as there are points.
+    pub fn msm(scalars: &[Fr], points: &HostOrDeviceSlice<'_, Point>) -> Option<G1> {
+        let scalars = HostOrDeviceSlice::Host(scalars.iter().map(scalar).collect::<Vec<_>>());
+        let mut result = HostOrDeviceSlice::Host(vec![G1Projective::zero()]);
+        if let Err(err) = msm::msm(&scalars, points, &MSMConfig::default(), &mut result) {
+            return fall_back("msm", err);
+        }
+        Some(from_projective(result.as_slice()[0]))
+    }
+
+    /// icicle's domain of `2^MAX_K` points, from the same root of unity halo2 squares down to
+    /// each domain's `omega`, so its smaller transforms use the same roots as halo2's.
+    fn domain() -> bool {
+        static DOMAIN: Mutex<Option<bool>> = Mutex::new(None);
+        *DOMAIN
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .get_or_insert_with(|| {
+                let mut root = Fr::root_of_unity();
+                for _ in MAX_K..Fr::S {
+                    root = root.square();
+                }
+                match ntt::initialize_domain(scalar(&root), &DeviceContext::default(), false) {
+                    Ok(()) => true,
+                    Err(err) => fall_back::<()>("initialize_domain", err).is_some(),
+                }
+            })
+    }
+
+    /// The forward NTT over the `2^k` points of halo2's domain, or the inverse one with its
+    /// division by `2^k`.
+    pub fn ntt(values: &[Fr], k: u32, inverse: bool) -> Option<Vec<Fr>> {
+        if k > MAX_K || !domain() {
+            return None;
+        }
+        let dir = if inverse {
+            NTTDir::kInverse
+        } else {
+            NTTDir::kForward
+        };
+        let input = HostOrDeviceSlice::Host(values.iter().map(scalar).collect::<Vec<_>>());
+        let mut output = HostOrDeviceSlice::Host(vec![ScalarField::zero(); values.len()]);
+        if let Err(err) = ntt::ntt(&input, dir, &NTTConfig::default(), &mut output) {
+            return fall_back("ntt", err);
+        }
+        Some(output.as_slice().iter().map(from_scalar).collect())
+    }
+}
@@ -0,0 +1,169 @@
+//! One digest of a proof's instance commitments, for referencing its public inputs from a
+//! blockchain transaction or another system that can't hold them all. The digest commits to
+//! how many proofs and columns there are, and to every commitment's affine coordinates.
+//!
+//! Keccak256 hashes the coordinates as 32-byte big-endian words, the EVM's layout. Poseidon
+//! chains `h = Poseidon(h, limb)` over 128-bit limbs of the coordinates, which are base field
+//! elements and don't fit the scalar field whole, so a circuit can recompute it.
+
+use std::fmt;
+
+use halo2_proofs::{
+    arithmetic::{CurveAffine, Field, FieldExt},
+    halo2curves::{
+        bn256::{Bn256, Fq, Fr, G1Affine},
+        group::ff::PrimeField,
+    },
+    plonk::{Error, VerifyingKey},
+    poly::kzg::{
+        commitment::{KZGCommitmentScheme, ParamsKZG},
+        multiopen::VerifierSHPLONK,
+    },
+};
+use sha3::{Digest, Keccak256};
+
+use crate::{
+    cache::InstanceCommitmentCache, circuits::poseidon::PoseidonHashCircuit, commit_instances,
+    poseidon::MySpec,
+};
+
+/// Separates anchors from other digests of the same bytes.
+const DOMAIN: &[u8] = b"halo2-playground instance anchor v1";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AnchorHash {
+    Keccak256,
+    Poseidon,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Anchor {
+    Keccak256([u8; 32]),
+    Poseidon(Fr),
+}
+
+impl Anchor {
+    pub fn hash(&self) -> AnchorHash {
+        match self {
+            Anchor::Keccak256(_) => AnchorHash::Keccak256,
+            Anchor::Poseidon(_) => AnchorHash::Poseidon,
+        }
+    }
+
+    /// The digest as one big-endian word.
+    pub fn to_bytes(&self) -> [u8; 32] {
+        match self {
+            Anchor::Keccak256(bytes) => *bytes,
+            Anchor::Poseidon(value) => {
+                let mut bytes = value.to_repr();
+                bytes.reverse();
+                bytes
+            }
+        }
+    }
+}
+
+impl fmt::Display for Anchor {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "0x")?;
+        for byte in self.to_bytes() {
+            write!(f, "{:02x}", byte)?;
+        }
+        Ok(())
+    }
+}
+
+/// `(x, y)` as big-endian words, `(0, 0)` for the identity as on the EVM.
+fn coordinate_words(point: &G1Affine) -> [[u8; 32]; 2] {
+    let coordinates: Option<_> = point.coordinates().into();
+    let (x, y) = coordinates
+        .map(|c| (*c.x(), *c.y()))
+        .unwrap_or((Fq::zero(), Fq::zero()));
+    [x, y].map(|coordinate| {
+        let mut word = coordinate.to_repr();
+        word.reverse();
+        word
+    })
+}
+
+fn poseidon2(left: Fr, right: Fr) -> Fr {
+    PoseidonHashCircuit::<Fr, MySpec<3, 2>, 3, 2, 2>::hash([left, right])
+}
+
+fn keccak_anchor(commitments: &[Vec<G1Affine>]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(DOMAIN);
+    hasher.update((commitments.len() as u64).to_be_bytes());
+    for proof in commitments {
+        hasher.update((proof.len() as u64).to_be_bytes());
+        for word in proof.iter().flat_map(coordinate_words) {
+            hasher.update(word);
+        }
+    }
+    hasher.finalize().into()
+}
+
+fn domain_tag() -> Fr {
+    let mut wide = [0; 64];
+    wide[..DOMAIN.len()].copy_from_slice(DOMAIN);
+    Fr::from_bytes_wide(&wide)
+}
+
+fn poseidon_anchor(commitments: &[Vec<G1Affine>]) -> Fr {
+    // the shape, then two limbs per coordinate, high first
+    let mut state = poseidon2(domain_tag(), Fr::from(commitments.len() as u64));
+    for proof in commitments {
+        state = poseidon2(state, Fr::from(proof.len() as u64));
+        for word in proof.iter().flat_map(coordinate_words) {
+            for limb in word.chunks(16) {
+                let limb = u128::from_be_bytes(limb.try_into().unwrap());
+                state = poseidon2(state, Fr::from_u128(limb));
+            }
+        }
+    }
+    state
+}
+
+/// The anchor of commitments from `commit_instances`, one `Vec` per proof.
+pub fn anchor(commitments: &[Vec<G1Affine>], hash: AnchorHash) -> Anchor {
+    match hash {
+        AnchorHash::Keccak256 => Anchor::Keccak256(keccak_anchor(commitments)),
+        AnchorHash::Poseidon => Anchor::Poseidon(poseidon_anchor(commitments)),
+    }
+}
+
+/// Commits to `instances` under `vk` and anchors the commitments.
+pub fn anchor_instances(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    instances: &[&[&[Fr]]],
+    hash: AnchorHash,
+) -> Result<Anchor, Error> {
+    let commitments = commit_instances::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<'_, Bn256>>(
+        params, vk, instances,
+    )?;
+    Ok(anchor(&commitments, hash))
+}
+
+/// The verifier's side: whether `anchor` commits to exactly these instances.
+pub fn check_anchor(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    instances: &[&[&[Fr]]],
+    anchor: &Anchor,
+) -> Result<bool, Error> {
+    Ok(anchor_instances(params, vk, instances, anchor.hash())? == *anchor)
+}
+
+/// `check_anchor` with the commitments from `cache`, for a verifier that sees the same
+/// instances again, e.g. a fixed root.
+pub fn check_anchor_cached(
+    cache: &mut InstanceCommitmentCache<G1Affine>,
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    instances: &[&[&[Fr]]],
+    anchor: &Anchor,
+) -> Result<bool, Error> {
+    let commitments = cache.get_or_commit(params, vk, instances)?;
+    Ok(self::anchor(&commitments, anchor.hash()) == *anchor)
+}
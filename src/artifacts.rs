@@ -0,0 +1,114 @@
+//! A machine-readable record of one keygen, prove and verify run, for scripts comparing
+//! circuit variants without parsing the human-readable output.
+
+use std::time::{Duration, Instant};
+
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr},
+    plonk::{keygen_pk, keygen_vk, Circuit},
+    poly::{commitment::Params, kzg::commitment::ParamsKZG},
+};
+
+use crate::{
+    audit_log::{json_string, vk_fingerprint},
+    entropy::EntropySource,
+    error::PlaygroundError,
+    fmt::to_hex,
+    prover::{run_proof, ProofOptions},
+    verifier::check_proof,
+};
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn serialized_len(write: impl FnOnce(&mut Vec<u8>) -> std::io::Result<()>) -> usize {
+    let mut bytes = vec![];
+    write(&mut bytes).expect("writing to a Vec doesn't fail");
+    bytes.len()
+}
+
+/// What one run produced and how long each step took.
+#[derive(Clone, Debug)]
+pub struct ProofArtifacts {
+    pub circuit: String,
+    pub k: u32,
+    pub proof: Vec<u8>,
+    /// As [`vk_fingerprint`].
+    pub vk_hash: String,
+    pub vk_bytes: usize,
+    pub params_bytes: usize,
+    pub instances: Vec<Vec<Fr>>,
+    /// Both keys.
+    pub keygen: Duration,
+    pub prove: Duration,
+    pub verify: Duration,
+}
+
+impl ProofArtifacts {
+    /// Generates the keys of `circuit` under `params`, then proves and verifies it with
+    /// `options`, timing each step. A proof that doesn't verify is an error.
+    pub fn measure<C: Circuit<Fr>, R: EntropySource>(
+        name: &str,
+        params: &ParamsKZG<Bn256>,
+        circuit: C,
+        instances: Vec<Vec<Fr>>,
+        rng: R,
+        options: ProofOptions,
+    ) -> Result<Self, PlaygroundError> {
+        let start = Instant::now();
+        let vk = keygen_vk(params, &circuit).map_err(PlaygroundError::Keygen)?;
+        let pk = keygen_pk(params, vk, &circuit).map_err(PlaygroundError::Keygen)?;
+        let keygen = start.elapsed();
+
+        let columns: Vec<&[Fr]> = instances.iter().map(Vec::as_slice).collect();
+        let start = Instant::now();
+        let proof = run_proof(params, &pk, circuit, &columns, rng, options)?;
+        let prove = start.elapsed();
+        let start = Instant::now();
+        check_proof(params, pk.get_vk(), &columns, &proof, options).into_result()?;
+        let verify = start.elapsed();
+
+        let vk = pk.get_vk();
+        Ok(Self {
+            circuit: name.to_string(),
+            k: params.k(),
+            proof,
+            vk_hash: vk_fingerprint(vk),
+            vk_bytes: serialized_len(|bytes| vk.write(bytes)),
+            params_bytes: serialized_len(|bytes| params.write(bytes)),
+            instances,
+            keygen,
+            prove,
+            verify,
+        })
+    }
+
+    /// One JSON object. The proof is unprefixed hex, the instances `0x` hex by column and the
+    /// times are in milliseconds.
+    pub fn to_json(&self) -> String {
+        let instances: Vec<String> = self
+            .instances
+            .iter()
+            .map(|column| {
+                let values: Vec<String> = column.iter().map(|v| json_string(&to_hex(v))).collect();
+                format!("[{}]", values.join(","))
+            })
+            .collect();
+        let ms = |d: Duration| d.as_secs_f64() * 1e3;
+        format!(
+            "{{\"circuit\":{},\"k\":{},\"proof\":{},\"proof_bytes\":{},\"vk_hash\":{},\"vk_bytes\":{},\"params_bytes\":{},\"instances\":[{}],\"keygen_ms\":{:.3},\"prove_ms\":{:.3},\"verify_ms\":{:.3}}}",
+            json_string(&self.circuit),
+            self.k,
+            json_string(&hex(&self.proof)),
+            self.proof.len(),
+            json_string(&self.vk_hash),
+            self.vk_bytes,
+            self.params_bytes,
+            instances.join(","),
+            ms(self.keygen),
+            ms(self.prove),
+            ms(self.verify)
+        )
+    }
+}
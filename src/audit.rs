@@ -0,0 +1,219 @@
+//! Semantic lint pass over circuit parameter choices.
+
+use std::fmt;
+
+use halo2_gadgets::poseidon::primitives::Spec;
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+use crate::{
+    dev::mock_check,
+    error::PlaygroundError,
+    poseidon::{secure_partial_rounds, SECURE_FULL_ROUNDS, SECURE_PARTIAL_ROUNDS},
+};
+
+const MAX_K: u32 = 28;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Severity {
+    Warning,
+    Error,
+}
+
+#[derive(Clone, Debug)]
+pub struct Finding {
+    pub severity: Severity,
+    pub rule: &'static str,
+    pub message: String,
+}
+
+impl fmt::Display for Finding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let severity = match self.severity {
+            Severity::Warning => "warning",
+            Severity::Error => "error",
+        };
+        write!(f, "{}[{}]: {}", severity, self.rule, self.message)
+    }
+}
+
+fn finding(severity: Severity, rule: &'static str, message: String) -> Finding {
+    Finding {
+        severity,
+        rule,
+        message,
+    }
+}
+
+pub fn audit_poseidon<S: Spec<Fr, WIDTH, RATE>, const WIDTH: usize, const RATE: usize>(
+) -> Vec<Finding> {
+    let name = std::any::type_name::<S>();
+    let mut findings = vec![];
+    if RATE + 1 != WIDTH {
+        findings.push(finding(
+            Severity::Warning,
+            "poseidon-capacity",
+            format!("{}: capacity of {} words, expected 1", name, WIDTH - RATE),
+        ));
+    }
+    if S::sbox(Fr::from(2)) != Fr::from(32) {
+        findings.push(finding(
+            Severity::Warning,
+            "poseidon-sbox",
+            format!("{}: round rules assume the x^5 S-box", name),
+        ));
+    }
+    if S::full_rounds() < SECURE_FULL_ROUNDS {
+        findings.push(finding(
+            Severity::Error,
+            "poseidon-full-rounds",
+            format!(
+                "{}: {} full rounds, at least {} needed",
+                name,
+                S::full_rounds(),
+                SECURE_FULL_ROUNDS
+            ),
+        ));
+    }
+    if !(2..2 + SECURE_PARTIAL_ROUNDS.len()).contains(&WIDTH) {
+        findings.push(finding(
+            Severity::Error,
+            "poseidon-width",
+            format!("{}: no recommended round numbers for width {}", name, WIDTH),
+        ));
+    } else if S::partial_rounds() < secure_partial_rounds(WIDTH) {
+        findings.push(finding(
+            Severity::Error,
+            "poseidon-partial-rounds",
+            format!(
+                "{}: {} partial rounds, at least {} needed for width {}",
+                name,
+                S::partial_rounds(),
+                secure_partial_rounds(WIDTH),
+                WIDTH
+            ),
+        ));
+    }
+    findings
+}
+
+/// A range check over `bits` bits must stay clear of the modulus, and should use whole limbs.
+pub fn audit_range_bits<F: FieldExt>(name: &str, bits: usize, limb_bits: usize) -> Vec<Finding> {
+    let mut findings = vec![];
+    if bits + 1 >= F::NUM_BITS as usize {
+        findings.push(finding(
+            Severity::Error,
+            "range-width",
+            format!(
+                "{}: {}-bit range check wraps around a {}-bit modulus",
+                name,
+                bits,
+                F::NUM_BITS
+            ),
+        ));
+    }
+    if bits % limb_bits != 0 {
+        findings.push(finding(
+            Severity::Warning,
+            "range-limbs",
+            format!(
+                "{}: {} bits is not a multiple of the {}-bit limb, the top limb needs its own check",
+                name, bits, limb_bits
+            ),
+        ));
+    }
+    findings
+}
+
+/// Hashes used for different purposes (commitments, nullifiers, ...) must be domain separated.
+pub fn audit_domain_separation<F: FieldExt>(domains: &[(&str, F)]) -> Vec<Finding> {
+    let mut findings = vec![];
+    for (i, (name, tag)) in domains.iter().enumerate() {
+        for (other, other_tag) in domains[..i].iter() {
+            if tag == other_tag {
+                findings.push(finding(
+                    Severity::Error,
+                    "domain-separation",
+                    format!("{} and {} share the same domain tag", other, name),
+                ));
+            }
+        }
+    }
+    findings
+}
+
+/// The smallest `k` with rows for `circuit` and `instances`, going by the errors that say
+/// there are too few alone.
+fn fitting_k<F: FieldExt, C: Circuit<F>>(
+    circuit: &C,
+    instances: &[Vec<F>],
+) -> Result<u32, PlaygroundError> {
+    (1..=MAX_K)
+        .find(|k| {
+            !matches!(
+                MockProver::run(*k, circuit, instances.to_vec()),
+                Err(Error::NotEnoughRowsAvailable { .. } | Error::InstanceTooLarge)
+            )
+        })
+        .ok_or_else(|| {
+            PlaygroundError::Setup(format!("the circuit does not fit in 2^{} rows", MAX_K))
+        })
+}
+
+/// The smallest `k` whose rows fit `circuit`, whether or not its witness satisfies it, or a
+/// [`PlaygroundError::Setup`] if nothing up to `2^28` rows fits.
+pub fn fit_k<F: FieldExt, C: Circuit<F>>(circuit: &C) -> Result<u32, PlaygroundError> {
+    let mut cs = ConstraintSystem::default();
+    C::configure(&mut cs);
+    fitting_k(circuit, &vec![vec![]; cs.num_instance_columns()])
+}
+
+/// The smallest `k` that fits `circuit`, if `instances` satisfy it there. More rows don't
+/// make a witness any more satisfying, so that one `k` is the only one checked.
+fn min_k<F: FieldExt, C: Circuit<F>>(
+    circuit: &C,
+    instances: &[Vec<F>],
+) -> Result<u32, PlaygroundError> {
+    let k = fitting_k(circuit, instances)?;
+    mock_check(k, circuit, instances.to_vec())?;
+    Ok(k)
+}
+
+/// Checks that `k` fits the circuit, and warns when there are no spare rows left.
+pub fn audit_k_headroom<F: FieldExt, C: Circuit<F>>(
+    name: &str,
+    k: u32,
+    circuit: &C,
+    instances: &[Vec<F>],
+) -> Vec<Finding> {
+    match min_k(circuit, instances) {
+        Err(PlaygroundError::Unprovable(failures)) => vec![finding(
+            Severity::Error,
+            "satisfied",
+            format!("{}: the instances don't satisfy it, {}", name, failures),
+        )],
+        Err(err) => vec![finding(
+            Severity::Error,
+            "k-headroom",
+            format!("{}: {}", name, err),
+        )],
+        Ok(min) if k < min => vec![finding(
+            Severity::Error,
+            "k-headroom",
+            format!("{}: k = {} but at least {} is needed", name, k, min),
+        )],
+        Ok(min) if k == min => vec![finding(
+            Severity::Warning,
+            "k-headroom",
+            format!(
+                "{}: k = {} has no headroom, any extra row needs k + 1",
+                name, k
+            ),
+        )],
+        Ok(_) => vec![],
+    }
+}
@@ -0,0 +1,181 @@
+//! Append-only, hash-chained JSONL log of keygen, prove and verify operations.
+//!
+//! Every line ends with `"hash":"<h>"}` where `h` hashes the previous line's hash together
+//! with the rest of the line, so editing or dropping an entry breaks every hash after it.
+
+use std::{
+    fmt,
+    fs::{self, OpenOptions},
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use halo2_proofs::{
+    halo2curves::bn256::{Fr, G1Affine},
+    plonk::VerifyingKey,
+};
+
+use crate::cache::instance_key;
+
+/// `prev` of the first entry.
+const GENESIS: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+const HASH_FIELD: &str = ",\"hash\":\"";
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Operation {
+    Keygen,
+    Prove,
+    Verify,
+}
+
+impl fmt::Display for Operation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Operation::Keygen => "keygen",
+            Operation::Prove => "prove",
+            Operation::Verify => "verify",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+fn chain_hash(prev: &str, body: &str) -> String {
+    let hash = blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(b"Audit-Log")
+        .to_state()
+        .update(prev.as_bytes())
+        .update(body.as_bytes())
+        .finalize();
+    hex(hash.as_bytes())
+}
+
+pub(crate) fn json_string(s: &str) -> String {
+    let mut out = String::with_capacity(s.len() + 2);
+    out.push('"');
+    for c in s.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Hash of the serialized verifying key.
+pub fn vk_fingerprint(vk: &VerifyingKey<G1Affine>) -> String {
+    let mut bytes = vec![];
+    vk.write(&mut bytes).expect("writing to a Vec doesn't fail");
+    let hash = blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(b"Vk-Fingerprint")
+        .hash(&bytes);
+    hex(hash.as_bytes())
+}
+
+/// Splits a line into its hashed body and its hash.
+fn split_line(line: &str) -> Option<(&str, &str)> {
+    let (body, hash) = line.rsplit_once(HASH_FIELD)?;
+    Some((body, hash.strip_suffix("\"}")?))
+}
+
+/// Checks the chain of every line of the log at `path`, returning the number of entries and
+/// the hash of the last one.
+pub fn verify_log(path: &Path) -> io::Result<(u64, String)> {
+    let text = match fs::read_to_string(path) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+        Err(err) => return Err(err),
+    };
+    let mut prev = GENESIS.to_string();
+    let mut entries = 0;
+    for (i, line) in text.lines().enumerate() {
+        let (body, hash) =
+            split_line(line).ok_or_else(|| invalid_data(format!("line {}: no hash", i + 1)))?;
+        let links = body.ends_with(&format!("\"prev\":\"{}\"", prev));
+        if !links || chain_hash(&prev, body) != hash {
+            return Err(invalid_data(format!("line {}: broken hash chain", i + 1)));
+        }
+        prev = hash.to_string();
+        entries += 1;
+    }
+    Ok((entries, prev))
+}
+
+pub struct AuditLog {
+    path: PathBuf,
+    entries: u64,
+    last_hash: String,
+}
+
+impl AuditLog {
+    /// Opens or creates the log at `path`, checking the existing entries first.
+    pub fn open(path: impl Into<PathBuf>) -> io::Result<Self> {
+        let path = path.into();
+        let (entries, last_hash) = verify_log(&path)?;
+        Ok(Self {
+            path,
+            entries,
+            last_hash,
+        })
+    }
+
+    pub fn entries(&self) -> u64 {
+        self.entries
+    }
+
+    /// Appends an entry; `outcome` is the error message of a failed operation.
+    pub fn record(
+        &mut self,
+        operation: Operation,
+        circuit: &str,
+        vk: &VerifyingKey<G1Affine>,
+        instances: &[&[Fr]],
+        outcome: Result<(), &str>,
+    ) -> io::Result<()> {
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+        let (ok, error) = match outcome {
+            Ok(()) => (true, "null".to_string()),
+            Err(err) => (false, json_string(err)),
+        };
+        let body = format!(
+            "{{\"seq\":{},\"timestamp\":{},\"operation\":\"{}\",\"circuit\":{},\"vk\":\"{}\",\
+             \"instances\":\"{}\",\"ok\":{},\"error\":{},\"prev\":\"{}\"",
+            self.entries,
+            timestamp,
+            operation,
+            json_string(circuit),
+            vk_fingerprint(vk),
+            hex(&instance_key(1 << vk.domain.k(), &[instances])),
+            ok,
+            error,
+            self.last_hash
+        );
+        let hash = chain_hash(&self.last_hash, &body);
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.path)?;
+        writeln!(file, "{}{}{}\"}}", body, HASH_FIELD, hash)?;
+        file.sync_data()?;
+        self.entries += 1;
+        self.last_hash = hash;
+        Ok(())
+    }
+}
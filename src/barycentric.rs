@@ -0,0 +1,59 @@
+//! Instance polynomial evaluations, computed the way an external (e.g. Solidity) verifier has
+//! to, from the instance values without committing to them.
+
+use halo2_proofs::{
+    arithmetic::Field,
+    halo2curves::bn256::{Fr, G1Affine},
+    plonk::{Error, VerifyingKey},
+};
+
+/// Evaluates the polynomial taking `values[i]` at `omega^i`, and zero on the rest of the
+/// domain, at `point`: `sum_i values[i] * omega^i * (point^n - 1) / (n * (point - omega^i))`.
+pub fn evaluate_lagrange(vk: &VerifyingKey<G1Affine>, values: &[Fr], point: Fr) -> Fr {
+    let n = 1u64 << vk.domain.k();
+    let omega = vk.domain.get_omega();
+    let vanishing = point.pow_vartime(&[n]) - Fr::one();
+
+    let mut omega_i = Fr::one();
+    if vanishing == Fr::zero() {
+        // `point` is on the domain, so only one basis polynomial is non-zero there
+        for value in values.iter() {
+            if omega_i == point {
+                return *value;
+            }
+            omega_i *= omega;
+        }
+        return Fr::zero();
+    }
+
+    let mut sum = Fr::zero();
+    for value in values.iter() {
+        sum += *value * omega_i * (Fr::from(n) * (point - omega_i)).invert().unwrap();
+        omega_i *= omega;
+    }
+    sum * vanishing
+}
+
+/// The instance evaluations a verifier needs for one proof, in the order of the constraint
+/// system's instance queries: column `c` opened at `x * omega^rotation`.
+pub fn instance_evaluations(
+    vk: &VerifyingKey<G1Affine>,
+    instances: &[&[Fr]],
+    x: Fr,
+) -> Result<Vec<Fr>, Error> {
+    if instances.len() != vk.cs.num_instance_columns() {
+        return Err(Error::InvalidInstances);
+    }
+    let omega = vk.domain.get_omega();
+    let omega_inv = vk.domain.get_omega_inv();
+    Ok(vk
+        .cs
+        .instance_queries()
+        .iter()
+        .map(|(column, rotation)| {
+            let step = if rotation.0 < 0 { omega_inv } else { omega };
+            let point = x * step.pow_vartime(&[rotation.0.unsigned_abs() as u64]);
+            evaluate_lagrange(vk, instances[column.index()], point)
+        })
+        .collect())
+}
@@ -0,0 +1,121 @@
+//! Batch verification: every proof's transcript is replayed into one `AccumulatorStrategy`,
+//! and a single MSM and pairing checks them all. If that final check fails, the proofs are
+//! re-verified one by one to point at the culprit.
+//!
+//! The proofs of a batch are all made with its [`ProofOptions`], by default SHPLONK over the
+//! Blake2b transcript.
+
+use std::fmt;
+
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::VerifyingKey,
+    poly::kzg::{commitment::ParamsKZG, strategy::AccumulatorStrategy},
+};
+
+use crate::{
+    prover::ProofOptions,
+    verifier::{check_proof, final_check, precheck_proof, VerificationReport},
+};
+
+struct Entry<'vk> {
+    vk: &'vk VerifyingKey<G1Affine>,
+    instances: Vec<Vec<Fr>>,
+    proof: Vec<u8>,
+}
+
+#[derive(Debug)]
+pub enum BatchError {
+    /// No proofs were pushed, so there is nothing the batch could vouch for.
+    Empty,
+    /// Proof `index` is malformed or fails its own check.
+    Invalid {
+        index: usize,
+        error: VerificationReport,
+    },
+    /// The accumulated check failed but every proof passes alone. Should not happen.
+    Accumulated,
+}
+
+impl fmt::Display for BatchError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BatchError::Invalid { index, error } => {
+                write!(f, "proof {} does not verify: {}", index, error)
+            }
+            BatchError::Empty => write!(f, "no proofs to verify"),
+            BatchError::Accumulated => write!(f, "the batched check failed"),
+        }
+    }
+}
+
+impl std::error::Error for BatchError {}
+
+/// Proofs to verify together, possibly under different verifying keys of the same params.
+#[derive(Default)]
+pub struct Batch<'vk> {
+    entries: Vec<Entry<'vk>>,
+    options: ProofOptions,
+}
+
+impl<'vk> Batch<'vk> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn options(mut self, options: ProofOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn push(
+        &mut self,
+        vk: &'vk VerifyingKey<G1Affine>,
+        instances: Vec<Vec<Fr>>,
+        proof: Vec<u8>,
+    ) {
+        self.entries.push(Entry {
+            vk,
+            instances,
+            proof,
+        });
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Fails on an empty batch rather than vouching for no proofs.
+    pub fn verify(&self, params: &ParamsKZG<Bn256>) -> Result<(), BatchError> {
+        if self.entries.is_empty() {
+            return Err(BatchError::Empty);
+        }
+        let mut strategy = AccumulatorStrategy::new(params);
+        for (index, entry) in self.entries.iter().enumerate() {
+            let columns: Vec<&[Fr]> = entry.instances.iter().map(Vec::as_slice).collect();
+            strategy = precheck_proof(
+                params,
+                entry.vk,
+                strategy,
+                &[&columns],
+                &entry.proof,
+                self.options,
+            )
+            .map_err(|error| BatchError::Invalid { index, error })?;
+        }
+        if final_check(strategy, self.options.multiopen) {
+            return Ok(());
+        }
+        for (index, entry) in self.entries.iter().enumerate() {
+            let columns: Vec<&[Fr]> = entry.instances.iter().map(Vec::as_slice).collect();
+            check_proof(params, entry.vk, &columns, &entry.proof, self.options)
+                .into_result()
+                .map_err(|error| BatchError::Invalid { index, error })?;
+        }
+        Err(BatchError::Accumulated)
+    }
+}
@@ -0,0 +1,295 @@
+//! Proving under a wall-clock budget, for CI smoke tests and for sizing constrained machines:
+//! the proof is abandoned at the first checkpoint past the budget, reporting the phase it
+//! reached and a rough estimate of the work left.
+//!
+//! The checkpoints are the start and end of synthesis and every draw from the prover's
+//! randomness, which halo2 makes while blinding the witness columns and the lookup and
+//! permutation arguments. Computing the quotient and the openings draws nothing, so a prover
+//! that gets there runs to the end.
+
+use std::{
+    fmt,
+    panic::{self, AssertUnwindSafe},
+    sync::{
+        atomic::{AtomicBool, AtomicU64, AtomicU8, Ordering},
+        Arc,
+    },
+    time::{Duration, Instant},
+};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::Layouter,
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{Circuit, ConstraintSystem, Error, ProvingKey, VerifyingKey},
+    poly::kzg::commitment::ParamsKZG,
+};
+use rand::RngCore;
+
+use crate::{
+    entropy::EntropySource,
+    error::PlaygroundError,
+    prover::{run_proof, ProofOptions},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Phase {
+    Synthesis,
+    /// Committing to the blinded witness and the lookup and permutation arguments.
+    Commitments,
+    /// The quotient polynomial and the multiopen argument.
+    Quotient,
+}
+
+impl Phase {
+    const ALL: [Phase; 3] = [Phase::Synthesis, Phase::Commitments, Phase::Quotient];
+}
+
+impl fmt::Display for Phase {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Phase::Synthesis => write!(f, "synthesis"),
+            Phase::Commitments => write!(f, "commitments"),
+            Phase::Quotient => write!(f, "quotient"),
+        }
+    }
+}
+
+/// How far a proof got before its budget ran out.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Progress {
+    pub phase: Phase,
+    pub elapsed: Duration,
+    pub budget: Duration,
+    /// Extrapolated from the time the finished phases took, `None` while still in the first.
+    pub estimated_remaining: Option<Duration>,
+}
+
+impl fmt::Display for Progress {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "proving stopped in {} after {:.2?}, over its {:.2?} budget",
+            self.phase, self.elapsed, self.budget
+        )?;
+        if let Some(remaining) = self.estimated_remaining {
+            write!(f, "; roughly {:.2?} of work left", remaining)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Debug)]
+pub enum BudgetError {
+    Exceeded(Progress),
+    Proof(PlaygroundError),
+}
+
+impl fmt::Display for BudgetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BudgetError::Exceeded(progress) => write!(f, "{}", progress),
+            BudgetError::Proof(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for BudgetError {}
+
+/// Relative cost of each phase, counting a field operation per row and column for synthesis,
+/// `k` per point for the MSMs of the commitments and `extended_k` per point for the FFTs of
+/// the quotient. Only the ratios matter.
+fn phase_work(vk: &VerifyingKey<G1Affine>) -> [f64; 3] {
+    let cs = &vk.cs;
+    let (k, extended_k) = (vk.domain.k(), vk.domain.extended_k());
+    let rows = (1u64 << k) as f64;
+    let extended_rows = (1u64 << extended_k) as f64;
+    let chunk_len = cs.degree() - 2;
+    let permutation_columns = cs.permutation().get_columns().len();
+    let products = (permutation_columns + chunk_len - 1) / chunk_len;
+    let committed = cs.num_advice_columns() + 3 * cs.lookups().len() + products;
+    let polys =
+        committed + cs.num_fixed_columns() + cs.num_instance_columns() + permutation_columns;
+    [
+        rows * cs.num_advice_columns() as f64,
+        rows * k as f64 * committed as f64,
+        extended_rows * extended_k as f64 * polys as f64,
+    ]
+}
+
+/// Panic payload unwinding out of the prover at a checkpoint past the budget.
+struct OverBudget;
+
+struct Watch {
+    start: Instant,
+    budget: Duration,
+    phase: AtomicU8,
+    exceeded: AtomicBool,
+    /// When each phase started, in microseconds since `start`.
+    phase_starts: [AtomicU64; 3],
+}
+
+impl Watch {
+    fn phase(&self) -> Phase {
+        Phase::ALL[self.phase.load(Ordering::Relaxed) as usize]
+    }
+
+    fn enter(&self, phase: Phase) {
+        let elapsed = self.start.elapsed().as_micros() as u64;
+        self.phase_starts[phase as usize].store(elapsed, Ordering::Relaxed);
+        self.phase.store(phase as u8, Ordering::Relaxed);
+    }
+
+    /// Whether the budget is spent, remembering it if so.
+    fn over(&self) -> bool {
+        let over = self.start.elapsed() >= self.budget;
+        if over {
+            self.exceeded.store(true, Ordering::Relaxed);
+        }
+        over
+    }
+
+    fn progress(&self, vk: &VerifyingKey<G1Affine>) -> Progress {
+        let phase = self.phase();
+        let elapsed = self.start.elapsed();
+        let work = phase_work(vk);
+        let done: f64 = work[..phase as usize].iter().sum();
+        let left: f64 = work[phase as usize..].iter().sum();
+        // the finished phases took us from the start to the current one
+        let done_in =
+            Duration::from_micros(self.phase_starts[phase as usize].load(Ordering::Relaxed));
+        let estimated_remaining = if done > 0.0 && !done_in.is_zero() {
+            Some(
+                done_in
+                    .mul_f64(left / done)
+                    .saturating_sub(elapsed - done_in),
+            )
+        } else {
+            None
+        };
+        Progress {
+            phase,
+            elapsed,
+            budget: self.budget,
+            estimated_remaining,
+        }
+    }
+}
+
+struct TimedCircuit<C> {
+    circuit: C,
+    watch: Arc<Watch>,
+}
+
+impl<F: FieldExt, C: Circuit<F>> Circuit<F> for TimedCircuit<C> {
+    type Config = C::Config;
+    type FloorPlanner = C::FloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            circuit: self.circuit.without_witnesses(),
+            watch: self.watch.clone(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        C::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<F>) -> Result<(), Error> {
+        if self.watch.over() {
+            return Err(Error::Synthesis);
+        }
+        self.circuit.synthesize(config, layouter)?;
+        self.watch.enter(Phase::Commitments);
+        if self.watch.over() {
+            return Err(Error::Synthesis);
+        }
+        Ok(())
+    }
+}
+
+struct TimedEntropy<R> {
+    rng: R,
+    watch: Arc<Watch>,
+}
+
+impl<R: EntropySource> TimedEntropy<R> {
+    fn checkpoint(&self) {
+        if self.watch.over() {
+            // skips the panic hook, the unwind is caught in `prove_within`
+            panic::resume_unwind(Box::new(OverBudget));
+        }
+    }
+}
+
+impl<R: EntropySource> RngCore for TimedEntropy<R> {
+    fn next_u32(&mut self) -> u32 {
+        self.checkpoint();
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.checkpoint();
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.checkpoint();
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.checkpoint();
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+impl<R: EntropySource> EntropySource for TimedEntropy<R> {
+    fn describe(&self) -> String {
+        self.rng.describe()
+    }
+}
+
+/// `run_proof` that gives up once `budget` has passed. The proof is the same `run_proof`
+/// would produce from the same randomness.
+pub fn prove_within<C: Circuit<Fr>, R: EntropySource>(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    circuit: C,
+    instances: &[&[Fr]],
+    rng: R,
+    options: ProofOptions,
+    budget: Duration,
+) -> Result<Vec<u8>, BudgetError> {
+    let watch = Arc::new(Watch {
+        start: Instant::now(),
+        budget,
+        phase: AtomicU8::new(Phase::Synthesis as u8),
+        exceeded: AtomicBool::new(false),
+        phase_starts: Default::default(),
+    });
+    let circuit = TimedCircuit {
+        circuit,
+        watch: watch.clone(),
+    };
+    let rng = TimedEntropy {
+        rng,
+        watch: watch.clone(),
+    };
+    let result = panic::catch_unwind(AssertUnwindSafe(|| {
+        run_proof(params, pk, circuit, instances, rng, options)
+    }));
+    let exceeded = || BudgetError::Exceeded(watch.progress(pk.get_vk()));
+    match result {
+        Ok(Ok(proof)) => Ok(proof),
+        Ok(Err(PlaygroundError::Prove(Error::Synthesis)))
+            if watch.exceeded.load(Ordering::Relaxed) =>
+        {
+            Err(exceeded())
+        }
+        Ok(Err(err)) => Err(BudgetError::Proof(err)),
+        Err(payload) if payload.is::<OverBudget>() => Err(exceeded()),
+        Err(payload) => panic::resume_unwind(payload),
+    }
+}
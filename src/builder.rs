@@ -0,0 +1,81 @@
+//! A region-free front-end for prototyping: a `Prototype` is straight-line arithmetic on
+//! `AssignedValue`s, and `BuilderCircuit` turns it into an ordinary `Circuit` whose outputs
+//! are the public instance. Every operation gets its own row in one region, so this trades all
+//! layout control for writing a circuit in a handful of lines.
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+use crate::chips::arith::{ArithChip, ArithConfig, ArithContext, AssignedValue};
+
+/// Straight-line circuit code. `Default` must be the witness-free version used at keygen.
+pub trait Prototype<F: FieldExt>: Clone + Default {
+    /// Records the computation, returning the values exposed in instance rows 0, 1, ...
+    fn build<'a>(&self, ctx: &'a ArithContext<F>) -> Vec<AssignedValue<'a, F>>;
+}
+
+#[derive(Clone, Debug)]
+pub struct BuilderConfig {
+    pub arith: ArithConfig,
+    pub instance: Column<Instance>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct BuilderCircuit<P>(pub P);
+
+impl<P> BuilderCircuit<P> {
+    /// The outputs the prototype computes from its witness, i.e. the instance column to prove
+    /// against. `None` without a witness.
+    pub fn instance<F: FieldExt>(&self) -> Option<Vec<F>>
+    where
+        P: Prototype<F>,
+    {
+        let ctx = ArithContext::new();
+        let mut instance = Some(vec![]);
+        for output in self.0.build(&ctx) {
+            let mut value = None;
+            output.value().map(|v| value = Some(v));
+            match (instance.as_mut(), value) {
+                (Some(instance), Some(value)) => instance.push(value),
+                _ => instance = None,
+            }
+        }
+        instance
+    }
+}
+
+impl<F: FieldExt, P: Prototype<F>> Circuit<F> for BuilderCircuit<P> {
+    type Config = BuilderConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        BuilderConfig {
+            arith: ArithChip::configure(meta),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let ctx = ArithContext::new();
+        let outputs = self.0.build(&ctx);
+        let cells =
+            ArithChip::construct(config.arith).assign(layouter.namespace(|| "build"), &ctx)?;
+        for (row, output) in outputs.iter().enumerate() {
+            layouter.constrain_instance(cells[output.index()].cell(), config.instance, row)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,507 @@
+//! `Prover` and `Verifier` builders over [`run_proof`] and [`check_proof`], producing and
+//! consuming a [`ProofBundle`]: the proof together with the public instances and options its
+//! verifier needs, so they can't get separated on the way.
+//!
+//! A bundle's byte form starts with a header declaring `k` and the sizes of what follows, so
+//! a verifier facing untrusted input checks them against its [`ResourceLimits`] before
+//! reading or allocating anything else. The header also carries the [`vk_digest`] the proof
+//! was made for, so a bundle paired with the wrong key files is refused by name rather than
+//! failing somewhere in the transcript.
+//!
+//! An artifact [`Bundle`] wraps one with the serialized key, the digest of the SRS and where
+//! the proof came from, a single file to ship between machines in place of four.
+
+use std::{
+    convert::TryInto,
+    fmt,
+    io::{self, Read, Write},
+};
+
+#[cfg(feature = "prover")]
+use halo2_proofs::plonk::ProvingKey;
+use halo2_proofs::{
+    halo2curves::{
+        bn256::{Bn256, Fr, G1Affine},
+        group::ff::PrimeField,
+    },
+    plonk::{Circuit, VerifyingKey},
+    poly::kzg::commitment::ParamsKZG,
+};
+
+use crate::{
+    encoding::{encode_hex, field_from_le_bytes, Reduction},
+    limits::{LimitError, ResourceLimits},
+    prover::{MultiOpen, ProofOptions},
+    srs::srs_digest,
+    transcript_registry,
+    verifier::{check_digest, check_proof, VerificationReport},
+};
+#[cfg(feature = "prover")]
+use crate::{
+    entropy::EntropySource,
+    error::PlaygroundError,
+    prover::{run_proof, TranscriptHash},
+    verifier::vk_digest,
+};
+
+const MAGIC: &[u8; 4] = b"H2PB";
+const VERSION: u8 = 2;
+const ARTIFACT_MAGIC: &[u8; 4] = b"H2PA";
+const ARTIFACT_VERSION: u8 = 1;
+const FIELD_BYTES: usize = 32;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProofBundle {
+    pub options: ProofOptions,
+    /// One instance column per entry.
+    pub instances: Vec<Vec<Fr>>,
+    pub proof: Vec<u8>,
+    /// The [`vk_digest`] of the key and params the proof was made with.
+    pub vk_digest: [u8; 32],
+}
+
+impl ProofBundle {
+    pub fn columns(&self) -> Vec<&[Fr]> {
+        self.instances.iter().map(Vec::as_slice).collect()
+    }
+
+    /// Little-endian header of magic, version, options, `vk`'s `k`, the column count, each
+    /// column's length, the proof's and the vk digest, followed by the instances and the
+    /// proof.
+    pub fn to_bytes(&self, vk: &VerifyingKey<G1Affine>) -> Vec<u8> {
+        self.encode(vk.domain.k())
+    }
+
+    fn encode(&self, k: u32) -> Vec<u8> {
+        let mut bytes = MAGIC.to_vec();
+        bytes.extend([
+            VERSION,
+            multiopen_tag(self.options.multiopen),
+            transcript_registry::entry(self.options.transcript).tag,
+        ]);
+        bytes.extend(k.to_le_bytes());
+        bytes.extend((self.instances.len() as u32).to_le_bytes());
+        for column in self.instances.iter() {
+            bytes.extend((column.len() as u32).to_le_bytes());
+        }
+        bytes.extend((self.proof.len() as u32).to_le_bytes());
+        bytes.extend(self.vk_digest);
+        for value in self.instances.iter().flatten() {
+            bytes.extend(value.to_repr().as_ref());
+        }
+        bytes.extend(&self.proof);
+        bytes
+    }
+
+    /// Parses `bytes` into the declared `k` and the bundle, rejecting declared sizes over
+    /// `limits` before reading past the header.
+    pub fn from_bytes(
+        bytes: &[u8],
+        limits: &ResourceLimits,
+    ) -> Result<(u32, ProofBundle), EnvelopeError> {
+        let mut reader = Reader { bytes };
+        if reader.take(MAGIC.len())? != MAGIC {
+            return Err(EnvelopeError::Format("not a proof bundle".to_string()));
+        }
+        let version = reader.u8()?;
+        if version != VERSION {
+            return Err(EnvelopeError::Format(format!(
+                "unsupported version {}",
+                version
+            )));
+        }
+        let multiopen = match reader.u8()? {
+            0 => MultiOpen::Shplonk,
+            1 => MultiOpen::Gwc,
+            tag => return Err(EnvelopeError::Format(format!("unknown multiopen {}", tag))),
+        };
+        let tag = reader.u8()?;
+        let transcript = transcript_registry::by_tag(tag)
+            .ok_or_else(|| EnvelopeError::Format(format!("unknown transcript {}", tag)))?
+            .hash;
+        let k = reader.u32()?;
+        limits.check_k(k).map_err(EnvelopeError::Limit)?;
+        let columns = reader.u32()? as usize;
+        let mut lens = vec![];
+        for column in 0..columns {
+            let len = reader.u32()? as usize;
+            if len > limits.max_instance_len {
+                return Err(EnvelopeError::Limit(LimitError::InstanceLength {
+                    column,
+                    len,
+                    max: limits.max_instance_len,
+                }));
+            }
+            lens.push(len);
+        }
+        let proof_len = reader.u32()? as usize;
+        if proof_len > limits.max_proof_bytes {
+            return Err(EnvelopeError::Limit(LimitError::ProofSize {
+                bytes: proof_len,
+                max: limits.max_proof_bytes,
+            }));
+        }
+        let vk_digest = reader.take(32)?.try_into().unwrap();
+        let declared = lens
+            .iter()
+            .try_fold(proof_len, |total, len| {
+                len.checked_mul(FIELD_BYTES)
+                    .and_then(|bytes| total.checked_add(bytes))
+            })
+            .ok_or_else(|| EnvelopeError::Format("declared sizes overflow".to_string()))?;
+        if declared != reader.bytes.len() {
+            return Err(EnvelopeError::Format(format!(
+                "header declares {} bytes of contents, found {}",
+                declared,
+                reader.bytes.len()
+            )));
+        }
+        let instances = lens
+            .iter()
+            .map(|len| {
+                (0..*len)
+                    .map(|_| {
+                        field_from_le_bytes::<Fr>(reader.take(FIELD_BYTES)?, Reduction::Reject)
+                            .map_err(|err| EnvelopeError::Format(err.to_string()))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok((
+            k,
+            ProofBundle {
+                options: ProofOptions {
+                    multiopen,
+                    transcript,
+                },
+                instances,
+                proof: reader.bytes.to_vec(),
+                vk_digest,
+            },
+        ))
+    }
+}
+
+fn multiopen_tag(multiopen: MultiOpen) -> u8 {
+    match multiopen {
+        MultiOpen::Shplonk => 0,
+        MultiOpen::Gwc => 1,
+    }
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], EnvelopeError> {
+        if self.bytes.len() < n {
+            return Err(EnvelopeError::Format("truncated bundle".to_string()));
+        }
+        let (taken, rest) = self.bytes.split_at(n);
+        self.bytes = rest;
+        Ok(taken)
+    }
+
+    fn u8(&mut self) -> Result<u8, EnvelopeError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, EnvelopeError> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    /// Bytes after their `u32` length.
+    fn prefixed(&mut self) -> Result<&'a [u8], EnvelopeError> {
+        let len = self.u32()? as usize;
+        self.take(len)
+    }
+
+    fn string(&mut self) -> Result<String, EnvelopeError> {
+        String::from_utf8(self.prefixed()?.to_vec())
+            .map_err(|_| EnvelopeError::Format("a string that isn't UTF-8".to_string()))
+    }
+}
+
+fn prefix(bytes: &mut Vec<u8>, field: &[u8]) {
+    bytes.extend((field.len() as u32).to_le_bytes());
+    bytes.extend(field);
+}
+
+#[derive(Debug)]
+pub enum EnvelopeError {
+    /// Not a bundle of this version, or one whose contents don't match its header.
+    Format(String),
+    Limit(LimitError),
+    /// The bundle declares another `k` than the verifying key's.
+    K {
+        declared: u32,
+        expected: u32,
+    },
+    Verify(VerificationReport),
+    /// An artifact bundle proved under another SRS, or another size of it, than the params
+    /// given.
+    Srs {
+        declared: [u8; 32],
+        expected: [u8; 32],
+    },
+    Io(String),
+}
+
+impl fmt::Display for EnvelopeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EnvelopeError::Format(err) => write!(f, "malformed bundle: {}", err),
+            EnvelopeError::Limit(err) => write!(f, "{}", err),
+            EnvelopeError::K { declared, expected } => write!(
+                f,
+                "bundle declares k = {}, the verifying key has k = {}",
+                declared, expected
+            ),
+            EnvelopeError::Verify(report) => write!(f, "{}", report),
+            EnvelopeError::Srs { declared, expected } => write!(
+                f,
+                "the bundle was proved under SRS {}, the params given are {}",
+                encode_hex(declared),
+                encode_hex(expected)
+            ),
+            EnvelopeError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for EnvelopeError {}
+
+/// Proves under one proving key, with `ProofOptions::default()` unless told otherwise.
+#[cfg(feature = "prover")]
+#[derive(Clone, Copy)]
+pub struct Prover<'a> {
+    params: &'a ParamsKZG<Bn256>,
+    pk: &'a ProvingKey<G1Affine>,
+    options: ProofOptions,
+}
+
+#[cfg(feature = "prover")]
+impl<'a> Prover<'a> {
+    pub fn new(params: &'a ParamsKZG<Bn256>, pk: &'a ProvingKey<G1Affine>) -> Self {
+        Self {
+            params,
+            pk,
+            options: ProofOptions::default(),
+        }
+    }
+
+    pub fn options(mut self, options: ProofOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    pub fn multiopen(mut self, multiopen: MultiOpen) -> Self {
+        self.options.multiopen = multiopen;
+        self
+    }
+
+    pub fn transcript(mut self, transcript: TranscriptHash) -> Self {
+        self.options.transcript = transcript;
+        self
+    }
+
+    pub fn prove<C: Circuit<Fr>, R: EntropySource>(
+        &self,
+        circuit: C,
+        instances: Vec<Vec<Fr>>,
+        rng: R,
+    ) -> Result<ProofBundle, PlaygroundError> {
+        let columns: Vec<&[Fr]> = instances.iter().map(Vec::as_slice).collect();
+        let proof = run_proof(self.params, self.pk, circuit, &columns, rng, self.options)?;
+        Ok(ProofBundle {
+            options: self.options,
+            instances,
+            proof,
+            vk_digest: vk_digest(self.params, self.pk.get_vk()),
+        })
+    }
+}
+
+/// Verifies bundles under one verifying key, with the options each bundle records.
+#[derive(Clone, Copy)]
+pub struct Verifier<'a> {
+    params: &'a ParamsKZG<Bn256>,
+    vk: &'a VerifyingKey<G1Affine>,
+}
+
+impl<'a> Verifier<'a> {
+    pub fn new(params: &'a ParamsKZG<Bn256>, vk: &'a VerifyingKey<G1Affine>) -> Self {
+        Self { params, vk }
+    }
+
+    /// Refuses a bundle made for another key or SRS before checking its proof.
+    pub fn verify(&self, bundle: &ProofBundle) -> VerificationReport {
+        if let Err(report) = check_digest(self.params, self.vk, &bundle.vk_digest) {
+            return report;
+        }
+        check_proof(
+            self.params,
+            self.vk,
+            &bundle.columns(),
+            &bundle.proof,
+            bundle.options,
+        )
+    }
+
+    /// Verifies an encoded bundle from an untrusted source, within `limits`.
+    pub fn verify_bytes(
+        &self,
+        bytes: &[u8],
+        limits: &ResourceLimits,
+    ) -> Result<ProofBundle, EnvelopeError> {
+        let (declared, bundle) = ProofBundle::from_bytes(bytes, limits)?;
+        let expected = self.vk.domain.k();
+        if declared != expected {
+            return Err(EnvelopeError::K { declared, expected });
+        }
+        self.verify(&bundle)
+            .into_result()
+            .map_err(EnvelopeError::Verify)?;
+        Ok(bundle)
+    }
+}
+
+/// Where an artifact bundle came from.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Metadata {
+    /// The name of the circuit whose constraint system the key is read back with.
+    pub circuit: String,
+    /// The revision of the playground that made the proof, when known.
+    pub git_rev: Option<String>,
+}
+
+/// One file with everything a verifier on another machine needs but the SRS: the proof and
+/// its instances and options, the serialized verifying key, and the digest of the SRS it was
+/// made under, which the verifier has to hold on its own. The key is only as trustworthy as
+/// whoever sent the bundle; [`Bundle::vk_digest`] is what to compare with a known one.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Bundle {
+    pub metadata: Metadata,
+    pub srs_digest: [u8; 32],
+    pub k: u32,
+    pub vk: Vec<u8>,
+    pub proof: ProofBundle,
+}
+
+impl Bundle {
+    pub fn new(
+        metadata: Metadata,
+        params: &ParamsKZG<Bn256>,
+        vk: &VerifyingKey<G1Affine>,
+        proof: ProofBundle,
+    ) -> Self {
+        let mut bytes = vec![];
+        vk.write(&mut bytes).expect("writing to a Vec doesn't fail");
+        Bundle {
+            metadata,
+            srs_digest: srs_digest(params),
+            k: vk.domain.k(),
+            vk: bytes,
+            proof,
+        }
+    }
+
+    /// The [`vk_digest`](crate::verifier::vk_digest) the proof declares for its key.
+    pub fn vk_digest(&self) -> [u8; 32] {
+        self.proof.vk_digest
+    }
+
+    /// Magic and version, then the circuit name, the git revision (empty when unknown), the
+    /// SRS digest and the key, each but the digest after its `u32` length, then the proof
+    /// bundle's own encoding, which carries `k`.
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut bytes = ARTIFACT_MAGIC.to_vec();
+        bytes.push(ARTIFACT_VERSION);
+        prefix(&mut bytes, self.metadata.circuit.as_bytes());
+        prefix(
+            &mut bytes,
+            self.metadata.git_rev.as_deref().unwrap_or("").as_bytes(),
+        );
+        bytes.extend(self.srs_digest);
+        prefix(&mut bytes, &self.vk);
+        bytes.extend(self.proof.encode(self.k));
+        bytes
+    }
+
+    /// Parses `bytes`, holding the proof bundle inside to `limits`.
+    pub fn from_bytes(bytes: &[u8], limits: &ResourceLimits) -> Result<Self, EnvelopeError> {
+        let mut reader = Reader { bytes };
+        if reader.take(ARTIFACT_MAGIC.len())? != ARTIFACT_MAGIC {
+            return Err(EnvelopeError::Format("not an artifact bundle".to_string()));
+        }
+        let version = reader.u8()?;
+        if version != ARTIFACT_VERSION {
+            return Err(EnvelopeError::Format(format!(
+                "unsupported artifact version {}",
+                version
+            )));
+        }
+        let circuit = reader.string()?;
+        let git_rev = Some(reader.string()?).filter(|rev| !rev.is_empty());
+        let srs_digest = reader.take(32)?.try_into().unwrap();
+        let vk = reader.prefixed()?.to_vec();
+        let (k, proof) = ProofBundle::from_bytes(reader.bytes, limits)?;
+        Ok(Bundle {
+            metadata: Metadata { circuit, git_rev },
+            srs_digest,
+            k,
+            vk,
+            proof,
+        })
+    }
+
+    pub fn write(&self, mut writer: impl Write) -> io::Result<()> {
+        writer.write_all(&self.to_bytes())
+    }
+
+    pub fn read(mut reader: impl Read, limits: &ResourceLimits) -> Result<Self, EnvelopeError> {
+        let mut bytes = vec![];
+        reader
+            .read_to_end(&mut bytes)
+            .map_err(|e| EnvelopeError::Io(e.to_string()))?;
+        Self::from_bytes(&bytes, limits)
+    }
+
+    /// The key, its constraint system rebuilt from `C`, which has to be the circuit the
+    /// metadata names, once `params` are known to be the SRS the bundle names.
+    pub fn verifying_key<C: Circuit<Fr>>(
+        &self,
+        params: &ParamsKZG<Bn256>,
+    ) -> Result<VerifyingKey<G1Affine>, EnvelopeError> {
+        let expected = srs_digest(params);
+        if expected != self.srs_digest {
+            return Err(EnvelopeError::Srs {
+                declared: self.srs_digest,
+                expected,
+            });
+        }
+        let vk = VerifyingKey::<G1Affine>::read::<_, C>(&mut &self.vk[..], params)
+            .map_err(|e| EnvelopeError::Format(format!("verifying key: {}", e)))?;
+        if vk.domain.k() != self.k {
+            return Err(EnvelopeError::K {
+                declared: self.k,
+                expected: vk.domain.k(),
+            });
+        }
+        Ok(vk)
+    }
+
+    /// Verifies the proof under the bundle's own key, returning the key.
+    pub fn verify<C: Circuit<Fr>>(
+        &self,
+        params: &ParamsKZG<Bn256>,
+    ) -> Result<VerifyingKey<G1Affine>, EnvelopeError> {
+        let vk = self.verifying_key::<C>(params)?;
+        Verifier::new(params, &vk)
+            .verify(&self.proof)
+            .into_result()
+            .map_err(EnvelopeError::Verify)?;
+        Ok(vk)
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::HashMap;
+
+use halo2_proofs::halo2curves::group::ff::PrimeField;
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    plonk::{Error, VerifyingKey},
+    poly::commitment::Params,
+};
+
+use crate::{commit_instances_with_params, multicore::MaybeSync};
+
+pub type InstanceKey = [u8; 32];
+
+/// Hash of the instance values together with the domain size they are committed over.
+pub fn instance_key<F: PrimeField>(n: u64, instances: &[&[&[F]]]) -> InstanceKey {
+    let mut state = blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(b"Instance-Cache")
+        .to_state();
+    state.update(&n.to_le_bytes());
+    state.update(&(instances.len() as u64).to_le_bytes());
+    for instance in instances.iter() {
+        state.update(&(instance.len() as u64).to_le_bytes());
+        for column in instance.iter() {
+            state.update(&(column.len() as u64).to_le_bytes());
+            for value in column.iter() {
+                state.update(value.to_repr().as_ref());
+            }
+        }
+    }
+    state.finalize().as_bytes().try_into().unwrap()
+}
+
+/// Hash of everything but the SRS a verifying key commits to: the domain size and the
+/// serialized key.
+pub fn key_digest<C: CurveAffine>(vk: &VerifyingKey<C>) -> [u8; 32] {
+    let mut bytes = vec![];
+    vk.write(&mut bytes).expect("writing to a Vec doesn't fail");
+    let mut state = blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(b"Instance-Key")
+        .to_state();
+    state.update(&vk.domain.k().to_le_bytes());
+    state.update(&bytes);
+    state.finalize().as_bytes().try_into().unwrap()
+}
+
+/// Entries of a cache made with `new`.
+pub const DEFAULT_CAPACITY: usize = 1024;
+
+/// Memoizes `commit_instances` so repeated checks of the same public inputs, e.g. of an anchor
+/// through [`check_anchor_cached`](crate::anchor::check_anchor_cached), skip the Lagrange
+/// commitment MSM, keyed on the verifying key and the instance values. halo2's `verify_proof`
+/// commits to IPA instances itself and takes no commitments, so proof verification can't use
+/// it. At most `capacity` entries are kept, evicting the least recently used. A cache must only
+/// be used with a single set of params.
+pub struct InstanceCommitmentCache<C> {
+    entries: HashMap<([u8; 32], InstanceKey), Entry<C>>,
+    capacity: usize,
+    /// Incremented on every lookup, to order entries by their last use.
+    clock: u64,
+    hits: u64,
+    misses: u64,
+    evictions: u64,
+}
+
+struct Entry<C> {
+    commitments: Vec<Vec<C>>,
+    used: u64,
+}
+
+impl<C> Default for InstanceCommitmentCache<C> {
+    fn default() -> Self {
+        Self::with_capacity(DEFAULT_CAPACITY)
+    }
+}
+
+impl<C> InstanceCommitmentCache<C> {
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            entries: HashMap::new(),
+            capacity: capacity.max(1),
+            clock: 0,
+            hits: 0,
+            misses: 0,
+            evictions: 0,
+        }
+    }
+}
+
+impl<C: CurveAffine> InstanceCommitmentCache<C> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Takes verifier or prover params alike, as `commit_instances_with_params` does.
+    pub fn get_or_commit<'params, P: Params<'params, C> + MaybeSync>(
+        &mut self,
+        params: &P,
+        vk: &VerifyingKey<C>,
+        instances: &[&[&[C::Scalar]]],
+    ) -> Result<Vec<Vec<C>>, Error> {
+        self.clock += 1;
+        let key = (key_digest(vk), instance_key(params.n(), instances));
+        if let Some(entry) = self.entries.get_mut(&key) {
+            entry.used = self.clock;
+            self.hits += 1;
+            return Ok(entry.commitments.clone());
+        }
+        self.misses += 1;
+        let commitments = commit_instances_with_params(params, vk, instances)?;
+        if self.entries.len() >= self.capacity {
+            let oldest = self
+                .entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.used)
+                .map(|(key, _)| *key);
+            if let Some(oldest) = oldest {
+                self.entries.remove(&oldest);
+                self.evictions += 1;
+            }
+        }
+        self.entries.insert(
+            key,
+            Entry {
+                commitments: commitments.clone(),
+                used: self.clock,
+            },
+        );
+        Ok(commitments)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn hits(&self) -> u64 {
+        self.hits
+    }
+
+    pub fn misses(&self) -> u64 {
+        self.misses
+    }
+
+    pub fn evictions(&self) -> u64 {
+        self.evictions
+    }
+
+    /// The share of lookups answered from the cache.
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            0.0
+        } else {
+            self.hits as f64 / lookups as f64
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.entries.clear();
+    }
+}
@@ -0,0 +1,77 @@
+//! Cooperative cancellation of keygen and proving.
+//!
+//! halo2 gives no hook between prover phases, but it synthesizes the circuit once per phase,
+//! so checking the token at the start and end of synthesis aborts between phases. Chips doing
+//! long loops can call [`CancelToken::check`] themselves for finer checkpoints.
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::Layouter,
+    plonk::{Circuit, ConstraintSystem, Error},
+};
+
+#[derive(Clone, Debug, Default)]
+pub struct CancelToken(Arc<AtomicBool>);
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+
+    /// Fails with `Error::Synthesis` once the token is cancelled.
+    pub fn check(&self) -> Result<(), Error> {
+        if self.is_cancelled() {
+            Err(Error::Synthesis)
+        } else {
+            Ok(())
+        }
+    }
+}
+
+/// Wraps a circuit so that synthesizing it fails once `token` is cancelled.
+#[derive(Clone, Debug)]
+pub struct Cancellable<C> {
+    pub circuit: C,
+    pub token: CancelToken,
+}
+
+impl<C> Cancellable<C> {
+    pub fn new(circuit: C, token: CancelToken) -> Self {
+        Self { circuit, token }
+    }
+}
+
+impl<F: FieldExt, C: Circuit<F>> Circuit<F> for Cancellable<C> {
+    type Config = C::Config;
+    type FloorPlanner = C::FloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            circuit: self.circuit.without_witnesses(),
+            token: self.token.clone(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        C::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<F>) -> Result<(), Error> {
+        self.token.check()?;
+        self.circuit.synthesize(config, layouter)?;
+        self.token.check()
+    }
+}
@@ -0,0 +1,140 @@
+//! Toy ceremony: the toxic waste `s` is split into Shamir shares among participants, so that
+//! rebuilding the params takes a quorum of them rather than one known key.
+//!
+//! Real ceremonies never reconstruct `s` at all; this only shows why holding it is the whole
+//! game. Any `threshold` shares rebuild it, fewer reveal nothing about it.
+
+use std::fmt;
+
+use halo2_proofs::{
+    arithmetic::Field,
+    halo2curves::{
+        bn256::{Bn256, Fr, G2Affine},
+        group::{prime::PrimeCurveAffine, Curve},
+    },
+    poly::kzg::commitment::ParamsKZG,
+};
+
+use crate::entropy::EntropySource;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Share {
+    /// Evaluation point of the sharing polynomial, never zero.
+    pub index: u64,
+    pub value: Fr,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CeremonyError {
+    InvalidThreshold {
+        threshold: usize,
+        participants: usize,
+    },
+    NotEnoughShares {
+        shares: usize,
+        threshold: usize,
+    },
+    DuplicateShare(u64),
+    /// The shares don't rebuild the `s` the params were published for.
+    WrongSecret,
+}
+
+impl fmt::Display for CeremonyError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CeremonyError::InvalidThreshold {
+                threshold,
+                participants,
+            } => write!(
+                f,
+                "threshold {} is not in 1..={} participants",
+                threshold, participants
+            ),
+            CeremonyError::NotEnoughShares { shares, threshold } => {
+                write!(f, "{} shares given, the quorum is {}", shares, threshold)
+            }
+            CeremonyError::DuplicateShare(index) => write!(f, "share {} given twice", index),
+            CeremonyError::WrongSecret => write!(f, "shares don't match the published [s]_2"),
+        }
+    }
+}
+
+impl std::error::Error for CeremonyError {}
+
+/// Splits `secret` into `participants` shares, any `threshold` of which rebuild it.
+pub fn split_secret(
+    secret: Fr,
+    threshold: usize,
+    participants: usize,
+    mut rng: impl EntropySource,
+) -> Result<Vec<Share>, CeremonyError> {
+    if threshold == 0 || threshold > participants {
+        return Err(CeremonyError::InvalidThreshold {
+            threshold,
+            participants,
+        });
+    }
+    // f(x) = secret + c_1 x + ... + c_{t-1} x^{t-1}, share i is f(i)
+    let coefficients: Vec<Fr> = std::iter::once(secret)
+        .chain((1..threshold).map(|_| Fr::random(&mut rng)))
+        .collect();
+    let shares = (1..=participants as u64)
+        .map(|index| {
+            let x = Fr::from(index);
+            let value = coefficients
+                .iter()
+                .rev()
+                .fold(Fr::zero(), |acc, coefficient| acc * x + coefficient);
+            Share { index, value }
+        })
+        .collect();
+    Ok(shares)
+}
+
+/// Lagrange-interpolates `f(0)` from the shares. With fewer than the threshold the result is
+/// some unrelated value, which only [`setup_from_shares`] can tell apart.
+pub fn reconstruct_secret(shares: &[Share]) -> Result<Fr, CeremonyError> {
+    let mut secret = Fr::zero();
+    for (i, share) in shares.iter().enumerate() {
+        let mut numerator = Fr::one();
+        let mut denominator = Fr::one();
+        for (j, other) in shares.iter().enumerate() {
+            if i == j {
+                continue;
+            }
+            if share.index == other.index {
+                return Err(CeremonyError::DuplicateShare(share.index));
+            }
+            numerator *= Fr::from(other.index);
+            denominator *= Fr::from(other.index) - Fr::from(share.index);
+        }
+        secret += share.value * numerator * denominator.invert().unwrap();
+    }
+    Ok(secret)
+}
+
+/// Commitment to `s` published with the params, against which shares are checked.
+pub fn secret_commitment(secret: Fr) -> G2Affine {
+    (G2Affine::generator() * secret).to_affine()
+}
+
+/// Rebuilds the params for `k` from a quorum of shares, checking the result against the
+/// published `[s]_2`.
+pub fn setup_from_shares(
+    k: u32,
+    shares: &[Share],
+    threshold: usize,
+    commitment: &G2Affine,
+) -> Result<ParamsKZG<Bn256>, CeremonyError> {
+    if shares.len() < threshold {
+        return Err(CeremonyError::NotEnoughShares {
+            shares: shares.len(),
+            threshold,
+        });
+    }
+    let secret = reconstruct_secret(shares)?;
+    if secret_commitment(secret) != *commitment {
+        return Err(CeremonyError::WrongSecret);
+    }
+    Ok(ParamsKZG::<Bn256>::unsafe_setup_with_s(k, secret))
+}
@@ -0,0 +1,118 @@
+//! Off-circuit replay of the verifier's Fiat-Shamir challenges.
+
+use halo2_proofs::{
+    halo2curves::bn256::{Fr, G1Affine},
+    plonk::{Error, VerifyingKey},
+    transcript::{Blake2bRead, Challenge255, Transcript, TranscriptRead, TranscriptReadBuffer},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Challenges {
+    /// User challenges of multi-phase circuits, in declaration order.
+    pub user: Vec<Fr>,
+    pub theta: Fr,
+    pub beta: Fr,
+    pub gamma: Fr,
+    pub y: Fr,
+    /// The point every polynomial is opened at.
+    pub x: Fr,
+}
+
+/// Derives the challenges a KZG verifier squeezes from `transcript`, up to and including `x`.
+/// Only the commitments before the evaluations are read, so a proof prefix is enough.
+pub fn derive_challenges<T: TranscriptRead<G1Affine, Challenge255<G1Affine>>>(
+    vk: &VerifyingKey<G1Affine>,
+    instances: &[&[&[Fr]]],
+    transcript: &mut T,
+) -> Result<Challenges, Error> {
+    let cs = &vk.cs;
+    for instance in instances.iter() {
+        if instance.len() != cs.num_instance_columns() {
+            return Err(Error::InvalidInstances);
+        }
+    }
+
+    vk.hash_into(transcript)?;
+    // KZG doesn't commit to the instances, the values themselves are hashed
+    for instance in instances.iter() {
+        for column in instance.iter() {
+            for value in column.iter() {
+                transcript.common_scalar(*value)?;
+            }
+        }
+    }
+
+    let advice_phases = cs.advice_column_phase();
+    let challenge_phases = cs.challenge_phase();
+    let last_phase = advice_phases
+        .iter()
+        .chain(challenge_phases.iter())
+        .copied()
+        .max()
+        .unwrap_or_default();
+    let mut user = vec![Fr::zero(); challenge_phases.len()];
+    for phase in 0..=last_phase {
+        for _ in instances.iter() {
+            for _ in advice_phases.iter().filter(|p| **p == phase) {
+                transcript.read_point()?;
+            }
+        }
+        for (challenge, _) in user
+            .iter_mut()
+            .zip(challenge_phases.iter())
+            .filter(|(_, p)| **p == phase)
+        {
+            *challenge = *transcript.squeeze_challenge_scalar::<()>();
+        }
+    }
+
+    let read_points = |transcript: &mut T, n: usize| -> Result<(), Error> {
+        for _ in 0..n * instances.len() {
+            transcript.read_point()?;
+        }
+        Ok(())
+    };
+
+    let theta = *transcript.squeeze_challenge_scalar::<()>();
+    // permuted input and table of every lookup
+    read_points(transcript, 2 * cs.lookups().len())?;
+
+    let beta = *transcript.squeeze_challenge_scalar::<()>();
+    let gamma = *transcript.squeeze_challenge_scalar::<()>();
+    let chunk_len = cs.degree() - 2;
+    let permutation_chunks = (cs.permutation().get_columns().len() + chunk_len - 1) / chunk_len;
+    read_points(transcript, permutation_chunks)?;
+    read_points(transcript, cs.lookups().len())?;
+    // the vanishing argument's random polynomial is shared by all proofs
+    transcript.read_point()?;
+
+    let y = *transcript.squeeze_challenge_scalar::<()>();
+    for _ in 0..vk.domain.get_quotient_poly_degree() {
+        transcript.read_point()?;
+    }
+
+    let x = *transcript.squeeze_challenge_scalar::<()>();
+    Ok(Challenges {
+        user,
+        theta,
+        beta,
+        gamma,
+        y,
+        x,
+    })
+}
+
+/// `derive_challenges` over the Blake2b transcript used by this crate's provers. Also returns
+/// how many bytes of `proof` were consumed.
+pub fn proof_challenges(
+    vk: &VerifyingKey<G1Affine>,
+    instances: &[&[&[Fr]]],
+    proof: &[u8],
+) -> Result<(Challenges, usize), Error> {
+    let mut rest = proof;
+    let challenges = {
+        let mut transcript = Blake2bRead::<_, G1Affine, Challenge255<_>>::init(&mut rest);
+        derive_challenges(vk, instances, &mut transcript)?
+    };
+    Ok((challenges, proof.len() - rest.len()))
+}
@@ -0,0 +1,225 @@
+use std::{
+    cell::RefCell,
+    marker::PhantomData,
+    ops::{Add, Mul, Sub},
+    ptr,
+};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Selector},
+    poly::Rotation,
+};
+
+#[derive(Clone, Copy, Debug)]
+enum Op<F: FieldExt> {
+    Input,
+    Constant(F),
+    Add(usize, usize),
+    Sub(usize, usize),
+    Mul(usize, usize),
+}
+
+/// Records arithmetic on `AssignedValue`s so it can be written out later by `ArithChip::assign`,
+/// one row per operation, instead of assigning and copying cells by hand.
+pub struct ArithContext<F: FieldExt> {
+    ops: RefCell<Vec<(Op<F>, Value<F>)>>,
+}
+
+impl<F: FieldExt> Default for ArithContext<F> {
+    fn default() -> Self {
+        Self {
+            ops: RefCell::new(vec![]),
+        }
+    }
+}
+
+impl<F: FieldExt> ArithContext<F> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn push(&self, op: Op<F>, value: Value<F>) -> AssignedValue<'_, F> {
+        let mut ops = self.ops.borrow_mut();
+        ops.push((op, value));
+        AssignedValue {
+            ctx: self,
+            index: ops.len() - 1,
+        }
+    }
+
+    /// A private input.
+    pub fn input(&self, value: Value<F>) -> AssignedValue<'_, F> {
+        self.push(Op::Input, value)
+    }
+
+    /// A constant, fixed at keygen.
+    pub fn constant(&self, value: F) -> AssignedValue<'_, F> {
+        self.push(Op::Constant(value), Value::known(value))
+    }
+
+    pub fn len(&self) -> usize {
+        self.ops.borrow().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// A value recorded in an `ArithContext`. `+`, `-` and `*` record the operation and return the
+/// result; the constraints are only laid out when the context is assigned.
+#[derive(Clone, Copy)]
+pub struct AssignedValue<'a, F: FieldExt> {
+    ctx: &'a ArithContext<F>,
+    index: usize,
+}
+
+impl<'a, F: FieldExt> AssignedValue<'a, F> {
+    pub fn value(&self) -> Value<F> {
+        self.ctx.ops.borrow()[self.index].1
+    }
+
+    /// Position of the value's cell in the cells returned by `ArithChip::assign`.
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    fn binary(self, rhs: Self, op: fn(usize, usize) -> Op<F>, value: Value<F>) -> Self {
+        assert!(ptr::eq(self.ctx, rhs.ctx), "values from different contexts");
+        self.ctx.push(op(self.index, rhs.index), value)
+    }
+}
+
+impl<'a, F: FieldExt> Add for AssignedValue<'a, F> {
+    type Output = Self;
+
+    fn add(self, rhs: Self) -> Self {
+        self.binary(rhs, Op::Add, self.value() + rhs.value())
+    }
+}
+
+impl<'a, F: FieldExt> Sub for AssignedValue<'a, F> {
+    type Output = Self;
+
+    fn sub(self, rhs: Self) -> Self {
+        self.binary(rhs, Op::Sub, self.value() - rhs.value())
+    }
+}
+
+impl<'a, F: FieldExt> Mul for AssignedValue<'a, F> {
+    type Output = Self;
+
+    fn mul(self, rhs: Self) -> Self {
+        self.binary(rhs, Op::Mul, self.value() * rhs.value())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ArithConfig {
+    pub lhs: Column<Advice>,
+    pub rhs: Column<Advice>,
+    pub out: Column<Advice>,
+    pub constants: Column<Fixed>,
+    pub q_add: Selector,
+    pub q_sub: Selector,
+    pub q_mul: Selector,
+}
+
+/// Lays out an `ArithContext`: each operation is a row `lhs op rhs = out` whose operands are
+/// copied from the rows that produced them.
+pub struct ArithChip<F: FieldExt> {
+    config: ArithConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> ArithChip<F> {
+    pub fn construct(config: ArithConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> ArithConfig {
+        let lhs = meta.advice_column();
+        let rhs = meta.advice_column();
+        let out = meta.advice_column();
+        let constants = meta.fixed_column();
+        let q_add = meta.selector();
+        let q_sub = meta.selector();
+        let q_mul = meta.selector();
+        for column in [lhs, rhs, out] {
+            meta.enable_equality(column);
+        }
+        meta.enable_constant(constants);
+
+        meta.create_gate("arith", |meta| {
+            let lhs = meta.query_advice(lhs, Rotation::cur());
+            let rhs = meta.query_advice(rhs, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            let q_add = meta.query_selector(q_add);
+            let q_sub = meta.query_selector(q_sub);
+            let q_mul = meta.query_selector(q_mul);
+            vec![
+                q_add * (lhs.clone() + rhs.clone() - out.clone()),
+                q_sub * (lhs.clone() - rhs.clone() - out.clone()),
+                q_mul * (lhs * rhs - out),
+            ]
+        });
+
+        ArithConfig {
+            lhs,
+            rhs,
+            out,
+            constants,
+            q_add,
+            q_sub,
+            q_mul,
+        }
+    }
+
+    /// Assigns every recorded operation in one region, returning the cell of each value in
+    /// recording order.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        ctx: &ArithContext<F>,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let config = &self.config;
+        let ops = ctx.ops.borrow();
+        layouter.assign_region(
+            || "arith",
+            |mut region| {
+                let mut cells: Vec<AssignedCell<F, F>> = Vec::with_capacity(ops.len());
+                for (row, (op, value)) in ops.iter().enumerate() {
+                    let (selector, operands) = match *op {
+                        Op::Input => (None, None),
+                        Op::Constant(constant) => {
+                            cells.push(region.assign_advice_from_constant(
+                                || "constant",
+                                config.out,
+                                row,
+                                constant,
+                            )?);
+                            continue;
+                        }
+                        Op::Add(l, r) => (Some(config.q_add), Some((l, r))),
+                        Op::Sub(l, r) => (Some(config.q_sub), Some((l, r))),
+                        Op::Mul(l, r) => (Some(config.q_mul), Some((l, r))),
+                    };
+                    if let Some(selector) = selector {
+                        selector.enable(&mut region, row)?;
+                    }
+                    if let Some((l, r)) = operands {
+                        cells[l].copy_advice(|| "lhs", &mut region, config.lhs, row)?;
+                        cells[r].copy_advice(|| "rhs", &mut region, config.rhs, row)?;
+                    }
+                    cells.push(region.assign_advice(|| "out", config.out, row, || *value)?);
+                }
+                Ok(cells)
+            },
+        )
+    }
+}
@@ -0,0 +1,598 @@
+//! Integers of up to `N` 64-bit limbs and arithmetic modulo any modulus of `N` limbs, RSA's
+//! among them, where [`super::nonnative`] stops at 256-bit primes. A reduction is one region
+//! of the relation
+//!
+//! `a * b + d = q * p + r`
+//!
+//! with `a`, `b`, `p` and `r` of `N` limbs, `d` of up to `2N` and the quotient `q` of `N + 2`,
+//! laid out a limb to a row. The gate checks it at every limb position of the product, with
+//! signed carries between positions and the last carry zero; as every position is far below
+//! the circuit's modulus, it holds over the integers. `r < p` is then `r + s + 1 = p` for a
+//! range-checked `s`, on the addition gate. Every limb a region makes is range checked on a
+//! lane of [`RangeCheckChip`]s, the lanes taken in turn and sharing one table of the circuit's
+//! [`Tables`].
+//!
+//! The quotient has room for products of limbs below `2^64N` by moduli with a nonzero top
+//! limb; a narrower modulus leaves the relation unsatisfiable rather than unsound.
+
+use std::{cell::Cell, marker::PhantomData};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Instance, Selector},
+    poly::Rotation,
+};
+use num_bigint::{BigInt, BigUint};
+
+use super::{
+    limbs::pow2,
+    range_check::{RangeCheckChip, RangeCheckConfig},
+    tables::Tables,
+};
+
+pub const LIMB_BITS: usize = 64;
+/// Width of the range check table, which the lanes share.
+const TABLE_BITS: usize = 8;
+pub const LANES: usize = 4;
+/// Carries lie in `(-2^CARRY_SHIFT, 2^CARRY_SHIFT)` and are range checked after adding it.
+const CARRY_SHIFT: usize = 72;
+/// Limbs of the widest integers, so that no carry leaves its range.
+pub const MAX_LIMBS: usize = 64;
+
+/// The `n` little-endian limbs of `value`, dropping higher ones.
+pub fn split(value: &BigUint, n: usize) -> Vec<u64> {
+    let mut digits = value.to_u64_digits();
+    digits.resize(n, 0);
+    digits
+}
+
+fn compose(limbs: &[u64]) -> BigUint {
+    limbs
+        .iter()
+        .rev()
+        .fold(BigUint::from(0u8), |acc, limb| (acc << LIMB_BITS) + *limb)
+}
+
+fn to_field<F: FieldExt>(value: &BigUint) -> F {
+    let digits = value.to_u64_digits();
+    let digit = |i: usize| digits.get(i).copied().unwrap_or(0) as u128;
+    F::from_u128(digit(0) | digit(1) << 64)
+}
+
+/// The limbs of `value` as field elements, e.g. for instance rows read by
+/// [`BigIntChip::from_instance`].
+pub fn to_limbs<F: FieldExt>(value: &BigUint, n: usize) -> Vec<F> {
+    split(value, n).into_iter().map(F::from).collect()
+}
+
+/// An integer in 64-bit limbs, little-endian, each range checked or made of ones that are.
+#[derive(Clone, Debug)]
+pub struct Integer<F: FieldExt> {
+    pub limbs: Vec<AssignedCell<F, F>>,
+    pub value: Value<BigUint>,
+}
+
+#[derive(Clone, Debug)]
+pub struct BigIntConfig {
+    pub a: Column<Advice>,
+    pub b: Column<Advice>,
+    pub d: Column<Advice>,
+    pub q: Column<Advice>,
+    pub p: Column<Advice>,
+    /// `r` of the relation, and the sum of an addition.
+    pub r: Column<Advice>,
+    pub carry: Column<Advice>,
+    pub lanes: Vec<RangeCheckConfig>,
+    q_relation: Selector,
+    q_add: Selector,
+}
+
+pub struct BigIntChip<F: FieldExt, const N: usize> {
+    config: BigIntConfig,
+    /// The lane for the next range check.
+    lane: Cell<usize>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N: usize> BigIntChip<F, N> {
+    pub fn construct(config: BigIntConfig) -> Self {
+        Self {
+            config,
+            lane: Cell::new(0),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Needs a fixed column enabled for constants.
+    pub fn configure(meta: &mut ConstraintSystem<F>, tables: &mut Tables) -> BigIntConfig {
+        assert!((1..=MAX_LIMBS).contains(&N), "1 to {} limbs", MAX_LIMBS);
+        let [a, b, d, q, p, r, carry] = [(); 7].map(|_| meta.advice_column());
+        for column in [a, b, d, q, p, r, carry] {
+            meta.enable_equality(column);
+        }
+        let lanes = (0..LANES)
+            .map(|_| RangeCheckChip::<F, TABLE_BITS>::configure(meta, tables))
+            .collect();
+        let config = BigIntConfig {
+            a,
+            b,
+            d,
+            q,
+            p,
+            r,
+            carry,
+            lanes,
+            q_relation: meta.selector(),
+            q_add: meta.selector(),
+        };
+
+        meta.create_gate("bigint relation", |meta| {
+            let selector = meta.query_selector(config.q_relation);
+            let mut limbs = |column: Column<Advice>, n: usize| -> Vec<Expression<F>> {
+                (0..n)
+                    .map(|i| meta.query_advice(column, Rotation(i as i32)))
+                    .collect()
+            };
+            let (a, b, d, q, p, r) = (
+                limbs(a, N),
+                limbs(b, N),
+                limbs(d, 2 * N),
+                limbs(q, N + 2),
+                limbs(p, N),
+                limbs(r, N),
+            );
+            let positions = 2 * N + 1;
+            let carries = limbs(carry, positions - 1);
+            let base = Expression::Constant(pow2::<F>(LIMB_BITS));
+            let shift = Expression::Constant(pow2::<F>(CARRY_SHIFT));
+            let product = |x: &[Expression<F>], y: &[Expression<F>], k: usize| {
+                (0..x.len())
+                    .filter(|i| k >= *i && k - i < y.len())
+                    .map(|i| x[i].clone() * y[k - i].clone())
+                    .fold(Expression::Constant(F::zero()), |acc, term| acc + term)
+            };
+            let zero = Expression::Constant(F::zero());
+            let at =
+                |x: &[Expression<F>], k: usize| x.get(k).cloned().unwrap_or_else(|| zero.clone());
+            (0..positions)
+                .map(|k| {
+                    let carry_in = match k {
+                        0 => zero.clone(),
+                        _ => carries[k - 1].clone() - shift.clone(),
+                    };
+                    let carry_out = match carries.get(k) {
+                        Some(carry) => (carry.clone() - shift.clone()) * base.clone(),
+                        None => zero.clone(),
+                    };
+                    selector.clone()
+                        * (product(&a, &b, k) + at(&d, k) - product(&q, &p, k) - at(&r, k)
+                            + carry_in
+                            - carry_out)
+                })
+                .collect::<Vec<_>>()
+        });
+
+        // a_i + b_i + carry_i = r_i + 2^64 carry_{i+1}, with boolean carries
+        meta.create_gate("bigint add", |meta| {
+            let selector = meta.query_selector(config.q_add);
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let sum = meta.query_advice(r, Rotation::cur());
+            let carry_in = meta.query_advice(carry, Rotation::cur());
+            let carry_out = meta.query_advice(carry, Rotation::next());
+            let base = Expression::Constant(pow2::<F>(LIMB_BITS));
+            vec![
+                selector.clone() * (a + b + carry_in - sum - carry_out.clone() * base),
+                selector * carry_out.clone() * (Expression::Constant(F::one()) - carry_out),
+            ]
+        });
+        config
+    }
+
+    fn range_check(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        bits: usize,
+    ) -> Result<(), Error> {
+        let lane = self.lane.get();
+        self.lane.set((lane + 1) % LANES);
+        RangeCheckChip::<F, TABLE_BITS>::construct(self.config.lanes[lane].clone()).range_check(
+            layouter.namespace(|| "range"),
+            cell,
+            bits,
+        )?;
+        Ok(())
+    }
+
+    fn range_check_limbs(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        limbs: &[AssignedCell<F, F>],
+    ) -> Result<(), Error> {
+        for limb in limbs {
+            self.range_check(layouter, limb, LIMB_BITS)?;
+        }
+        Ok(())
+    }
+
+    /// Assigns `values`' limbs down `column`.
+    fn assign_limbs(
+        region: &mut Region<'_, F>,
+        column: Column<Advice>,
+        values: Value<Vec<u64>>,
+        n: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        (0..n)
+            .map(|i| {
+                let limb = values.as_ref().map(|limbs| F::from(limbs[i]));
+                region.assign_advice(|| "limb", column, i, || limb)
+            })
+            .collect()
+    }
+
+    /// Copies `x`'s limbs into `column` and pads them with zeros to `n`.
+    fn copy_limbs(
+        region: &mut Region<'_, F>,
+        column: Column<Advice>,
+        x: &Integer<F>,
+        n: usize,
+    ) -> Result<(), Error> {
+        assert!(
+            x.limbs.len() <= n,
+            "an operand of {} limbs over {}",
+            x.limbs.len(),
+            n
+        );
+        for i in 0..n {
+            match x.limbs.get(i) {
+                Some(limb) => limb.copy_advice(|| "limb", region, column, i)?,
+                None => region.assign_advice_from_constant(|| "zero", column, i, F::zero())?,
+            };
+        }
+        Ok(())
+    }
+
+    /// A new integer of `n` limbs.
+    pub fn witness(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<BigUint>,
+        n: usize,
+    ) -> Result<Integer<F>, Error> {
+        let column = self.config.r;
+        let limbs = layouter.assign_region(
+            || "bigint",
+            |mut region| {
+                let limbs = value.as_ref().map(|value| split(value, n));
+                Self::assign_limbs(&mut region, column, limbs, n)
+            },
+        )?;
+        self.range_check_limbs(&mut layouter, &limbs)?;
+        Ok(Integer {
+            limbs,
+            value: value.map(|value| compose(&split(&value, n))),
+        })
+    }
+
+    /// `value` in as many limbs as it takes, at least one.
+    pub fn constant(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &BigUint,
+    ) -> Result<Integer<F>, Error> {
+        let n = ((value.bits() as usize + LIMB_BITS - 1) / LIMB_BITS).max(1);
+        let column = self.config.r;
+        let limbs = layouter.assign_region(
+            || "constant bigint",
+            |mut region| {
+                split(value, n)
+                    .into_iter()
+                    .enumerate()
+                    .map(|(i, limb)| {
+                        region.assign_advice_from_constant(|| "limb", column, i, F::from(limb))
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            },
+        )?;
+        Ok(Integer {
+            limbs,
+            value: Value::known(value.clone()),
+        })
+    }
+
+    /// An integer of `n` limbs from instance rows `row` to `row + n - 1` of `column`.
+    pub fn from_instance(
+        &self,
+        mut layouter: impl Layouter<F>,
+        column: Column<Instance>,
+        row: usize,
+        n: usize,
+    ) -> Result<Integer<F>, Error> {
+        let advice = self.config.r;
+        let limbs = layouter.assign_region(
+            || "instance bigint",
+            |mut region| {
+                (0..n)
+                    .map(|i| {
+                        region.assign_advice_from_instance(|| "limb", column, row + i, advice, i)
+                    })
+                    .collect::<Result<Vec<_>, _>>()
+            },
+        )?;
+        self.range_check_limbs(&mut layouter, &limbs)?;
+        let value = limbs
+            .iter()
+            .rev()
+            .fold(Value::known(BigUint::from(0u8)), |acc, limb| {
+                acc.zip(limb.value()).map(|(acc, limb)| {
+                    (acc << LIMB_BITS) + BigUint::from_bytes_le(limb.to_repr().as_ref())
+                })
+            });
+        Ok(Integer { limbs, value })
+    }
+
+    /// `lhs + rhs + carry`, on the addition gate, with `sum`'s limbs, copies already checked or
+    /// fresh ones to check, and its top carry, a limb of its own, returned.
+    fn add_region(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        lhs: &Integer<F>,
+        rhs: &Integer<F>,
+        carry: u64,
+        sum: Option<&Integer<F>>,
+    ) -> Result<(Vec<AssignedCell<F, F>>, AssignedCell<F, F>), Error> {
+        let config = &self.config;
+        let n = lhs.limbs.len().max(rhs.limbs.len());
+        let total = lhs
+            .value
+            .clone()
+            .zip(rhs.value.clone())
+            .map(|(lhs, rhs)| lhs + rhs + carry);
+        layouter.assign_region(
+            || "bigint add",
+            |mut region| {
+                Self::copy_limbs(&mut region, config.a, lhs, n)?;
+                Self::copy_limbs(&mut region, config.b, rhs, n)?;
+                region.assign_advice_from_constant(|| "carry", config.carry, 0, F::from(carry))?;
+                let limbs = match sum {
+                    Some(sum) => {
+                        Self::copy_limbs(&mut region, config.r, sum, n)?;
+                        vec![]
+                    }
+                    None => Self::assign_limbs(
+                        &mut region,
+                        config.r,
+                        total.as_ref().map(|total| split(total, n)),
+                        n,
+                    )?,
+                };
+                let mut top = None;
+                for i in 0..n {
+                    config.q_add.enable(&mut region, i)?;
+                    // the carry out of limb i is bit 64 (i + 1) of the partial sum
+                    let carry_out = lhs.value.clone().zip(rhs.value.clone()).map(|(lhs, rhs)| {
+                        let mask = (BigUint::from(1u8) << (LIMB_BITS * (i + 1))) - 1u8;
+                        let partial = (lhs & &mask) + (rhs & &mask) + carry;
+                        let out = partial >> (LIMB_BITS * (i + 1));
+                        F::from(out.to_u64_digits().first().copied().unwrap_or(0))
+                    });
+                    top = Some(region.assign_advice(
+                        || "carry",
+                        config.carry,
+                        i + 1,
+                        || carry_out,
+                    )?);
+                }
+                Ok((limbs, top.unwrap()))
+            },
+        )
+    }
+
+    /// `lhs + rhs`, a limb wider than the wider of them.
+    pub fn add(
+        &self,
+        mut layouter: impl Layouter<F>,
+        lhs: &Integer<F>,
+        rhs: &Integer<F>,
+    ) -> Result<Integer<F>, Error> {
+        let (mut limbs, top) = self.add_region(&mut layouter, lhs, rhs, 0, None)?;
+        self.range_check_limbs(&mut layouter, &limbs)?;
+        limbs.push(top);
+        let value = lhs.value.clone().zip(rhs.value.clone()).map(|(a, b)| a + b);
+        Ok(Integer { limbs, value })
+    }
+
+    /// Constrains `lhs < rhs` as `lhs + s + 1 = rhs` for an `s` of their width.
+    pub fn assert_less_than(
+        &self,
+        mut layouter: impl Layouter<F>,
+        lhs: &Integer<F>,
+        rhs: &Integer<F>,
+    ) -> Result<(), Error> {
+        let n = lhs.limbs.len().max(rhs.limbs.len());
+        let gap = lhs.value.clone().zip(rhs.value.clone()).map(|(lhs, rhs)| {
+            if lhs < rhs {
+                rhs - lhs - 1u8
+            } else {
+                BigUint::from(0u8)
+            }
+        });
+        let gap = self.witness(layouter.namespace(|| "gap"), gap, n)?;
+        let (_, top) = self.add_region(&mut layouter, lhs, &gap, 1, Some(rhs))?;
+        layouter.assign_region(
+            || "no carry",
+            |mut region| region.constrain_constant(top.cell(), F::zero()),
+        )
+    }
+
+    fn zero() -> Integer<F> {
+        Integer {
+            limbs: vec![],
+            value: Value::known(BigUint::from(0u8)),
+        }
+    }
+
+    /// `a * b + d` modulo `p`, below it.
+    fn relation(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Integer<F>,
+        b: &Integer<F>,
+        d: &Integer<F>,
+        p: &Integer<F>,
+    ) -> Result<Integer<F>, Error> {
+        let config = &self.config;
+        let witnesses = a
+            .value
+            .clone()
+            .zip(b.value.clone())
+            .zip(d.value.clone())
+            .zip(p.value.clone())
+            .map(|(((a, b), d), p)| Witnesses::new(N, &a, &b, &d, &p));
+        let (q, r, carries) = layouter.assign_region(
+            || "bigint relation",
+            |mut region| {
+                config.q_relation.enable(&mut region, 0)?;
+                Self::copy_limbs(&mut region, config.a, a, N)?;
+                Self::copy_limbs(&mut region, config.b, b, N)?;
+                Self::copy_limbs(&mut region, config.d, d, 2 * N)?;
+                Self::copy_limbs(&mut region, config.p, p, N)?;
+                let q = Self::assign_limbs(
+                    &mut region,
+                    config.q,
+                    witnesses.as_ref().map(|w| w.q.clone()),
+                    N + 2,
+                )?;
+                let r = Self::assign_limbs(
+                    &mut region,
+                    config.r,
+                    witnesses.as_ref().map(|w| w.r.clone()),
+                    N,
+                )?;
+                let carries = (0..2 * N)
+                    .map(|k| {
+                        let carry = witnesses.as_ref().map(|w| to_field::<F>(&w.carries[k]));
+                        region.assign_advice(|| "carry", config.carry, k, || carry)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok((q, r, carries))
+            },
+        )?;
+        self.range_check_limbs(&mut layouter, &q)?;
+        self.range_check_limbs(&mut layouter, &r)?;
+        for carry in carries.iter() {
+            self.range_check(&mut layouter, carry, CARRY_SHIFT + 1)?;
+        }
+        let r = Integer {
+            limbs: r,
+            value: witnesses.map(|w| compose(&w.r)),
+        };
+        self.assert_less_than(layouter.namespace(|| "reduced"), &r, p)?;
+        Ok(r)
+    }
+
+    /// `x mod p`, for an `x` of up to `2N` limbs.
+    pub fn reduce(
+        &self,
+        layouter: impl Layouter<F>,
+        x: &Integer<F>,
+        p: &Integer<F>,
+    ) -> Result<Integer<F>, Error> {
+        self.relation(layouter, &Self::zero(), &Self::zero(), x, p)
+    }
+
+    pub fn add_mod(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Integer<F>,
+        b: &Integer<F>,
+        p: &Integer<F>,
+    ) -> Result<Integer<F>, Error> {
+        let sum = self.add(layouter.namespace(|| "sum"), a, b)?;
+        self.reduce(layouter.namespace(|| "reduce"), &sum, p)
+    }
+
+    pub fn mul_mod(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &Integer<F>,
+        b: &Integer<F>,
+        p: &Integer<F>,
+    ) -> Result<Integer<F>, Error> {
+        self.relation(layouter, a, b, &Self::zero(), p)
+    }
+
+    /// Constrains `a = b` limb by limb, the wider one's extra limbs to zero.
+    pub fn assert_equal(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &Integer<F>,
+        b: &Integer<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "bigint equal",
+            |mut region| {
+                let n = a.limbs.len().max(b.limbs.len());
+                for i in 0..n {
+                    match (a.limbs.get(i), b.limbs.get(i)) {
+                        (Some(a), Some(b)) => region.constrain_equal(a.cell(), b.cell())?,
+                        (Some(limb), None) | (None, Some(limb)) => {
+                            region.constrain_constant(limb.cell(), F::zero())?
+                        }
+                        (None, None) => unreachable!(),
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+/// What the prover witnesses for one relation: the quotient, the remainder and the carries
+/// between limb positions, shifted as they are checked.
+struct Witnesses {
+    q: Vec<u64>,
+    r: Vec<u64>,
+    carries: Vec<BigUint>,
+}
+
+impl Witnesses {
+    fn new(n: usize, a: &BigUint, b: &BigUint, d: &BigUint, p: &BigUint) -> Self {
+        let lhs = a * b + d;
+        let (q, r) = if *p == BigUint::from(0u8) {
+            (BigUint::from(0u8), BigUint::from(0u8))
+        } else {
+            (&lhs / p, &lhs % p)
+        };
+        let limbs = |value: &BigUint, len: usize| -> Vec<BigInt> {
+            split(value, len).into_iter().map(BigInt::from).collect()
+        };
+        let (a, b, d, p) = (limbs(a, n), limbs(b, n), limbs(d, 2 * n), limbs(p, n));
+        let (q_limbs, r_limbs) = (limbs(&q, n + 2), limbs(&r, n));
+        let product = |x: &[BigInt], y: &[BigInt], k: usize| -> BigInt {
+            (0..x.len())
+                .filter(|i| k >= *i && k - i < y.len())
+                .map(|i| &x[i] * &y[k - i])
+                .sum()
+        };
+        let at = |x: &[BigInt], k: usize| x.get(k).cloned().unwrap_or_default();
+        let mut carry = BigInt::from(0u8);
+        let carries = (0..2 * n)
+            .map(|k| {
+                let t = product(&a, &b, k) + at(&d, k) - product(&q_limbs, &p, k) - at(&r_limbs, k)
+                    + &carry;
+                carry = t >> LIMB_BITS;
+                (&carry + (BigInt::from(1u8) << CARRY_SHIFT))
+                    .to_biguint()
+                    .unwrap_or_default()
+            })
+            .collect();
+        Witnesses {
+            q: split(&q, n + 2),
+            r: split(&r, n),
+            carries,
+        }
+    }
+}
@@ -0,0 +1,162 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+pub struct BooleanConfig {
+    pub a: Column<Advice>,
+    pub b: Column<Advice>,
+    pub out: Column<Advice>,
+    pub q_bool: Selector,
+    pub q_not: Selector,
+    pub q_and: Selector,
+    pub q_or: Selector,
+}
+
+/// Boolean constraints and logic over cells. Every operation checks that its operands are 0
+/// or 1, so outputs are boolean by construction.
+pub struct BooleanChip<F: FieldExt> {
+    config: BooleanConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> BooleanChip<F> {
+    pub fn construct(config: BooleanConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> BooleanConfig {
+        let a = meta.advice_column();
+        let b = meta.advice_column();
+        let out = meta.advice_column();
+        let [q_bool, q_not, q_and, q_or] = [(); 4].map(|_| meta.selector());
+        for column in [a, b, out] {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("boolean", |meta| {
+            let [q_bool, q_not, q_and, q_or] =
+                [q_bool, q_not, q_and, q_or].map(|q| meta.query_selector(q));
+            let a = meta.query_advice(a, Rotation::cur());
+            let b = meta.query_advice(b, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            let one = Expression::Constant(F::one());
+            let unary = q_bool + q_not.clone() + q_and.clone() + q_or.clone();
+            let binary = q_and.clone() + q_or.clone();
+            vec![
+                unary * a.clone() * (one.clone() - a.clone()),
+                binary * b.clone() * (one.clone() - b.clone()),
+                q_not * (one - a.clone() - out.clone()),
+                q_and * (a.clone() * b.clone() - out.clone()),
+                q_or * (a.clone() + b.clone() - a * b - out),
+            ]
+        });
+
+        BooleanConfig {
+            a,
+            b,
+            out,
+            q_bool,
+            q_not,
+            q_and,
+            q_or,
+        }
+    }
+
+    /// A fresh boolean witness.
+    pub fn witness(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<bool>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = &self.config;
+        layouter.assign_region(
+            || "boolean witness",
+            |mut region| {
+                config.q_bool.enable(&mut region, 0)?;
+                region.assign_advice(|| "a", config.a, 0, || value.map(F::from))
+            },
+        )
+    }
+
+    /// Constrains `cell` to 0 or 1.
+    pub fn assert_boolean(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        let config = &self.config;
+        layouter.assign_region(
+            || "assert boolean",
+            |mut region| {
+                config.q_bool.enable(&mut region, 0)?;
+                cell.copy_advice(|| "a", &mut region, config.a, 0)?;
+                Ok(())
+            },
+        )
+    }
+
+    pub fn not(
+        &self,
+        mut layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = &self.config;
+        layouter.assign_region(
+            || "not",
+            |mut region| {
+                config.q_not.enable(&mut region, 0)?;
+                let a = a.copy_advice(|| "a", &mut region, config.a, 0)?;
+                let out = a.value().map(|a| F::one() - a);
+                region.assign_advice(|| "out", config.out, 0, || out)
+            },
+        )
+    }
+
+    fn binary(
+        &self,
+        mut layouter: impl Layouter<F>,
+        selector: Selector,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+        op: fn(F, F) -> F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = &self.config;
+        layouter.assign_region(
+            || "boolean op",
+            |mut region| {
+                selector.enable(&mut region, 0)?;
+                let a = a.copy_advice(|| "a", &mut region, config.a, 0)?;
+                let b = b.copy_advice(|| "b", &mut region, config.b, 0)?;
+                let out = a.value().zip(b.value()).map(|(a, b)| op(*a, *b));
+                region.assign_advice(|| "out", config.out, 0, || out)
+            },
+        )
+    }
+
+    pub fn and(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.binary(layouter, self.config.q_and, a, b, |a, b| a * b)
+    }
+
+    pub fn or(
+        &self,
+        layouter: impl Layouter<F>,
+        a: &AssignedCell<F, F>,
+        b: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.binary(layouter, self.config.q_or, a, b, |a, b| a + b - a * b)
+    }
+}
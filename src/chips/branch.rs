@@ -0,0 +1,141 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+/// The condition of a branch: a cell copied in from elsewhere or a fresh witness. Either way
+/// the chip constrains it to be boolean, so callers never need their own booleanity gate.
+pub enum Condition<'a, F: FieldExt> {
+    Copy(&'a AssignedCell<F, F>),
+    Witness(Value<bool>),
+}
+
+#[derive(Clone, Debug)]
+pub struct BranchConfig {
+    pub cond: Column<Advice>,
+    pub when_true: Column<Advice>,
+    pub when_false: Column<Advice>,
+    pub out: Column<Advice>,
+    pub q_branch: Selector,
+}
+
+/// `out = cond ? when_true : when_false` over cells, with `cond` constrained to 0 or 1. The
+/// hand-rolled `cond * a + (1 - cond) * b` misses the booleanity check, letting a prover pick
+/// any point on the line through `a` and `b`.
+pub struct BranchChip<F: FieldExt> {
+    config: BranchConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> BranchChip<F> {
+    pub fn construct(config: BranchConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> BranchConfig {
+        let cond = meta.advice_column();
+        let when_true = meta.advice_column();
+        let when_false = meta.advice_column();
+        let out = meta.advice_column();
+        let q_branch = meta.selector();
+        for column in [cond, when_true, when_false, out] {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("branch", |meta| {
+            let q = meta.query_selector(q_branch);
+            let cond = meta.query_advice(cond, Rotation::cur());
+            let when_true = meta.query_advice(when_true, Rotation::cur());
+            let when_false = meta.query_advice(when_false, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            let one = Expression::Constant(F::one());
+            vec![
+                q.clone() * cond.clone() * (one - cond.clone()),
+                q * (cond * (when_true - when_false.clone()) + when_false - out),
+            ]
+        });
+
+        BranchConfig {
+            cond,
+            when_true,
+            when_false,
+            out,
+            q_branch,
+        }
+    }
+
+    /// Selects between two cells, returning the (boolean) condition cell and the result so the
+    /// same condition can drive further branches.
+    pub fn select(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cond: Condition<'_, F>,
+        when_true: &AssignedCell<F, F>,
+        when_false: &AssignedCell<F, F>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        let config = &self.config;
+        layouter.assign_region(
+            || "branch",
+            |mut region| {
+                config.q_branch.enable(&mut region, 0)?;
+                let cond = match &cond {
+                    Condition::Copy(cell) => {
+                        cell.copy_advice(|| "cond", &mut region, config.cond, 0)?
+                    }
+                    Condition::Witness(value) => region.assign_advice(
+                        || "cond",
+                        config.cond,
+                        0,
+                        || value.map(|b| F::from(b as u64)),
+                    )?,
+                };
+                let when_true =
+                    when_true.copy_advice(|| "when true", &mut region, config.when_true, 0)?;
+                let when_false =
+                    when_false.copy_advice(|| "when false", &mut region, config.when_false, 0)?;
+                let out = cond
+                    .value()
+                    .zip(when_true.value().zip(when_false.value()))
+                    .map(|(cond, (t, f))| *cond * (*t - f) + f);
+                let out = region.assign_advice(|| "out", config.out, 0, || out)?;
+                Ok((cond, out))
+            },
+        )
+    }
+
+    /// Selects between pairs of cells under one condition, e.g. swapping Merkle path siblings.
+    pub fn select_all(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cond: Condition<'_, F>,
+        pairs: &[(&AssignedCell<F, F>, &AssignedCell<F, F>)],
+    ) -> Result<(AssignedCell<F, F>, Vec<AssignedCell<F, F>>), Error> {
+        assert!(!pairs.is_empty(), "branch over no values");
+        let mut outs = Vec::with_capacity(pairs.len());
+        let (first_true, first_false) = pairs[0];
+        let (cond, out) = self.select(
+            layouter.namespace(|| "pair 0"),
+            cond,
+            first_true,
+            first_false,
+        )?;
+        outs.push(out);
+        for (i, (when_true, when_false)) in pairs.iter().enumerate().skip(1) {
+            let (_, out) = self.select(
+                layouter.namespace(|| format!("pair {}", i)),
+                Condition::Copy(&cond),
+                when_true,
+                when_false,
+            )?;
+            outs.push(out);
+        }
+        Ok((cond, outs))
+    }
+}
@@ -0,0 +1,331 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+
+use super::tables::{Table, Tables};
+
+/// The byte operation tables take `2^16` rows, so circuits using this chip need `k >= MIN_K`
+/// to fit them next to the blinding rows.
+pub const MIN_K: u32 = 17;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ByteOp {
+    And,
+    Xor,
+    Or,
+}
+
+impl ByteOp {
+    pub const ALL: [ByteOp; 3] = [ByteOp::And, ByteOp::Xor, ByteOp::Or];
+
+    pub fn apply(self, lhs: u8, rhs: u8) -> u8 {
+        match self {
+            ByteOp::And => lhs & rhs,
+            ByteOp::Xor => lhs ^ rhs,
+            ByteOp::Or => lhs | rhs,
+        }
+    }
+
+    pub fn table(self) -> Table {
+        match self {
+            ByteOp::And => Table::And8,
+            ByteOp::Xor => Table::Xor8,
+            ByteOp::Or => Table::Or8,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            ByteOp::And => "byte and",
+            ByteOp::Xor => "byte xor",
+            ByteOp::Or => "byte or",
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ByteOpsConfig {
+    pub lhs: Column<Advice>,
+    pub rhs: Column<Advice>,
+    pub out: Column<Advice>,
+    /// The shift of a split row, the weight of a join row.
+    pub param: Column<Fixed>,
+    /// Indexed by `ByteOp as usize`.
+    pub q_op: [Selector; 3],
+    pub q_split: Selector,
+    pub q_join: Selector,
+}
+
+/// Byte-wise AND/XOR/OR, each a lookup into its table of the circuit's [`Tables`], and the
+/// shifted variants rotations and shifts of words are built from: a byte split at a bit
+/// through `Table::Shr8`, and the gate joining the parts of two neighbouring bytes. Shared by
+/// the bit-oriented hash circuits, which keep their words as little-endian bytes.
+pub struct ByteOpsChip<F: FieldExt> {
+    config: ByteOpsConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> ByteOpsChip<F> {
+    pub fn construct(config: ByteOpsConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Asks `tables` for the byte operation and split tables, which the circuit loads among
+    /// the rest with its `Tables::load`.
+    pub fn configure(
+        meta: &mut ConstraintSystem<F>,
+        tables: &mut Tables,
+        lhs: Column<Advice>,
+        rhs: Column<Advice>,
+        out: Column<Advice>,
+    ) -> ByteOpsConfig {
+        let param = meta.fixed_column();
+        let q_op = [(); 3].map(|_| meta.complex_selector());
+        let q_split = meta.complex_selector();
+        let q_join = meta.selector();
+
+        meta.enable_equality(lhs);
+        meta.enable_equality(rhs);
+        meta.enable_equality(out);
+
+        for (op, q_op) in ByteOp::ALL.into_iter().zip(q_op) {
+            let table = tables.binary(meta, op.table());
+            meta.lookup(op.name(), |meta| {
+                let q = meta.query_selector(q_op);
+                [lhs, rhs, out]
+                    .iter()
+                    .zip(table)
+                    .map(|(column, table)| {
+                        (
+                            q.clone() * meta.query_advice(*column, Rotation::cur()),
+                            table,
+                        )
+                    })
+                    .collect()
+            });
+        }
+        let table = tables.binary(meta, Table::Shr8);
+        meta.lookup("byte split", |meta| {
+            let q = meta.query_selector(q_split);
+            let shift = meta.query_fixed(param, Rotation::cur());
+            let byte = meta.query_advice(lhs, Rotation::cur());
+            let hi = meta.query_advice(rhs, Rotation::cur());
+            let lo = meta.query_advice(out, Rotation::cur());
+            vec![
+                (
+                    q.clone() * (shift * Expression::Constant(F::from(256)) + byte),
+                    table[0],
+                ),
+                (q.clone() * hi, table[1]),
+                (q * lo, table[2]),
+            ]
+        });
+        // the parts come from splits at the same bit, so the sum is a byte
+        meta.create_gate("byte join", |meta| {
+            let q = meta.query_selector(q_join);
+            let weight = meta.query_fixed(param, Rotation::cur());
+            let hi = meta.query_advice(lhs, Rotation::cur());
+            let lo = meta.query_advice(rhs, Rotation::cur());
+            let out = meta.query_advice(out, Rotation::cur());
+            vec![q * (hi + weight * lo - out)]
+        });
+
+        ByteOpsConfig {
+            lhs,
+            rhs,
+            out,
+            param,
+            q_op,
+            q_split,
+            q_join,
+        }
+    }
+
+    /// Applies `op` to two byte cells. The operands are copied in, so the lookup also range
+    /// checks them to 8 bits.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        op: ByteOp,
+        lhs: &AssignedCell<F, F>,
+        rhs: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = &self.config;
+        layouter.assign_region(
+            || format!("byte {:?}", op),
+            |mut region| {
+                config.q_op[op as usize].enable(&mut region, 0)?;
+                let lhs = lhs.copy_advice(|| "lhs", &mut region, config.lhs, 0)?;
+                let rhs = rhs.copy_advice(|| "rhs", &mut region, config.rhs, 0)?;
+                let out = lhs
+                    .value()
+                    .zip(rhs.value())
+                    .map(|(lhs, rhs)| F::from(op.apply(to_byte(lhs), to_byte(rhs)) as u64));
+                region.assign_advice(|| "out", config.out, 0, || out)
+            },
+        )
+    }
+
+    /// Applies `op` limb by limb to two little-endian byte decompositions of a word.
+    pub fn assign_bytes(
+        &self,
+        mut layouter: impl Layouter<F>,
+        op: ByteOp,
+        lhs: &[AssignedCell<F, F>],
+        rhs: &[AssignedCell<F, F>],
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        assert_eq!(lhs.len(), rhs.len());
+        lhs.iter()
+            .zip(rhs.iter())
+            .enumerate()
+            .map(|(i, (lhs, rhs))| {
+                self.assign(layouter.namespace(|| format!("byte {}", i)), op, lhs, rhs)
+            })
+            .collect()
+    }
+
+    /// Splits a byte at bit `shift` into `byte >> shift` and `byte mod 2^shift`. `byte` must
+    /// already be known to be a byte, as the chip's outputs are: a larger value looks up as a
+    /// byte of a later shift.
+    pub fn split(
+        &self,
+        mut layouter: impl Layouter<F>,
+        byte: &AssignedCell<F, F>,
+        shift: usize,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error> {
+        assert!(shift < 8, "a split inside the byte");
+        let config = &self.config;
+        layouter.assign_region(
+            || format!("byte split at {}", shift),
+            |mut region| {
+                config.q_split.enable(&mut region, 0)?;
+                region.assign_fixed(
+                    || "shift",
+                    config.param,
+                    0,
+                    || Value::known(F::from(shift as u64)),
+                )?;
+                let byte = byte.copy_advice(|| "byte", &mut region, config.lhs, 0)?;
+                let byte = byte.value().map(to_byte);
+                let hi = region.assign_advice(
+                    || "hi",
+                    config.rhs,
+                    0,
+                    || byte.map(|byte| F::from((byte >> shift) as u64)),
+                )?;
+                let lo = region.assign_advice(
+                    || "lo",
+                    config.out,
+                    0,
+                    || byte.map(|byte| F::from((byte & ((1 << shift) - 1)) as u64)),
+                )?;
+                Ok((hi, lo))
+            },
+        )
+    }
+
+    /// `hi + 2^(8 - shift) lo`: the byte whose low bits are the `hi` part of one split at
+    /// `shift` and whose high bits are the `lo` part of another.
+    fn join(
+        &self,
+        mut layouter: impl Layouter<F>,
+        hi: &AssignedCell<F, F>,
+        lo: &AssignedCell<F, F>,
+        shift: usize,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = &self.config;
+        let weight = F::from(1u64 << (8 - shift));
+        layouter.assign_region(
+            || format!("byte join at {}", shift),
+            |mut region| {
+                config.q_join.enable(&mut region, 0)?;
+                region.assign_fixed(|| "weight", config.param, 0, || Value::known(weight))?;
+                let hi = hi.copy_advice(|| "hi", &mut region, config.lhs, 0)?;
+                let lo = lo.copy_advice(|| "lo", &mut region, config.rhs, 0)?;
+                let out = hi.value().zip(lo.value()).map(|(hi, lo)| *hi + weight * lo);
+                region.assign_advice(|| "out", config.out, 0, || out)
+            },
+        )
+    }
+
+    /// The little-endian bytes of a word rotated right by `bits`. Whole bytes move for free;
+    /// the rest of the rotation splits every byte once and joins the neighbouring parts.
+    pub fn rotate_right(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bytes: &[AssignedCell<F, F>],
+        bits: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let n = bytes.len();
+        let (q, shift) = (bits / 8 % n, bits % 8);
+        if shift == 0 {
+            return Ok((0..n).map(|i| bytes[(i + q) % n].clone()).collect());
+        }
+        let parts = bytes
+            .iter()
+            .map(|byte| self.split(layouter.namespace(|| "rotate"), byte, shift))
+            .collect::<Result<Vec<_>, _>>()?;
+        (0..n)
+            .map(|i| {
+                let (hi, _) = &parts[(i + q) % n];
+                let (_, lo) = &parts[(i + q + 1) % n];
+                self.join(layouter.namespace(|| "rotate"), hi, lo, shift)
+            })
+            .collect()
+    }
+
+    pub fn rotate_left(
+        &self,
+        layouter: impl Layouter<F>,
+        bytes: &[AssignedCell<F, F>],
+        bits: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let width = 8 * bytes.len();
+        self.rotate_right(layouter, bytes, (width - bits % width) % width)
+    }
+
+    /// The little-endian bytes of a word shifted right by `bits`, the emptied top bytes copied
+    /// from `zero`, a cell constrained to 0.
+    pub fn shift_right(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bytes: &[AssignedCell<F, F>],
+        bits: usize,
+        zero: &AssignedCell<F, F>,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let n = bytes.len();
+        assert!(bits < 8 * n, "a shift within the word");
+        let (q, shift) = (bits / 8, bits % 8);
+        if shift == 0 {
+            return Ok((0..n)
+                .map(|i| bytes.get(i + q).unwrap_or(zero).clone())
+                .collect());
+        }
+        let parts = bytes[q..]
+            .iter()
+            .map(|byte| self.split(layouter.namespace(|| "shift"), byte, shift))
+            .collect::<Result<Vec<_>, _>>()?;
+        (0..n)
+            .map(|i| match (parts.get(i), parts.get(i + 1)) {
+                (Some((hi, _)), Some((_, lo))) => {
+                    self.join(layouter.namespace(|| "shift"), hi, lo, shift)
+                }
+                // the top byte's high part has nothing above it
+                (Some((hi, _)), None) => Ok(hi.clone()),
+                (None, _) => Ok(zero.clone()),
+            })
+            .collect()
+    }
+}
+
+fn to_byte<F: FieldExt>(value: &F) -> u8 {
+    value.get_lower_32() as u8
+}
@@ -0,0 +1,82 @@
+use std::{
+    cell::{Cell, RefCell},
+    collections::BTreeMap,
+    fmt,
+};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter},
+    plonk::{Advice, Column, Error},
+};
+
+/// Constants shared by the gadgets of a composed circuit: each distinct value is assigned once,
+/// through the circuit's `enable_constant` column, and later requests get the same cell to copy
+/// from. `column` needs equality enabled. Create the pool inside `synthesize`, since its cells
+/// belong to one synthesis.
+pub struct ConstantPool<F: FieldExt> {
+    column: Column<Advice>,
+    cells: RefCell<BTreeMap<F, AssignedCell<F, F>>>,
+    requested: Cell<usize>,
+}
+
+impl<F: FieldExt> ConstantPool<F> {
+    pub fn new(column: Column<Advice>) -> Self {
+        Self {
+            column,
+            cells: RefCell::new(BTreeMap::new()),
+            requested: Cell::new(0),
+        }
+    }
+
+    /// A cell fixed to `value`, assigned on the first request.
+    pub fn constant(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        self.requested.set(self.requested.get() + 1);
+        if let Some(cell) = self.cells.borrow().get(&value) {
+            return Ok(cell.clone());
+        }
+        let cell = layouter.assign_region(
+            || "pooled constant",
+            |mut region| region.assign_advice_from_constant(|| "constant", self.column, 0, value),
+        )?;
+        self.cells.borrow_mut().insert(value, cell.clone());
+        Ok(cell)
+    }
+
+    pub fn stats(&self) -> PoolStats {
+        PoolStats {
+            requested: self.requested.get(),
+            unique: self.cells.borrow().len(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PoolStats {
+    pub requested: usize,
+    pub unique: usize,
+}
+
+impl PoolStats {
+    /// Rows of the pool's column, and of the constants column, that duplicates would have
+    /// taken. Whether the circuit gets shorter depends on what else shares the pool's column.
+    pub fn saved_rows(&self) -> usize {
+        self.requested - self.unique
+    }
+}
+
+impl fmt::Display for PoolStats {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} constants requested, {} distinct, {} rows saved",
+            self.requested,
+            self.unique,
+            self.saved_rows()
+        )
+    }
+}
@@ -0,0 +1,225 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Selector},
+    poly::Rotation,
+};
+
+use super::{
+    limbs::pow2,
+    range_check::limbs,
+    tables::{Table, Tables},
+};
+
+/// Limbs of a value, of `p - 1 - value` and the borrows into each limb.
+type Subtraction = (Vec<u64>, Vec<u64>, Vec<u64>);
+
+/// The limbs a field element is split into, little-endian.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Decomposition {
+    Bits,
+    Bytes,
+}
+
+impl Decomposition {
+    pub fn limb_bits(self) -> usize {
+        match self {
+            Decomposition::Bits => 1,
+            Decomposition::Bytes => 8,
+        }
+    }
+
+    /// Limbs of a whole `F`, the top one possibly short.
+    pub fn limbs<F: FieldExt>(self) -> usize {
+        let bits = self.limb_bits();
+        (F::NUM_BITS as usize + bits - 1) / bits
+    }
+
+    /// The canonical limbs of `value`.
+    pub fn split<F: FieldExt>(self, value: &F) -> Vec<u64> {
+        limbs(value, self.limb_bits(), self.limbs::<F>())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct DecomposeConfig {
+    /// Running sum `z_i = limb_i + 2^w z_(i+1)` from `z_0 = value` down to 0.
+    pub z: Column<Advice>,
+    pub limb: Column<Advice>,
+    /// Limbs of `p - 1 - value`, with the borrows of that subtraction.
+    pub rem: Column<Advice>,
+    pub borrow: Column<Advice>,
+    /// Limbs of `p - 1`.
+    pub bound: Column<Fixed>,
+    /// `2^(8 - w)`, so `shift * limb` is a byte only for `w`-bit limbs.
+    pub shift: Column<Fixed>,
+    pub q_limb: Selector,
+    pub q_start: Selector,
+    pub q_end: Selector,
+}
+
+/// Decomposes a field element into all of its bits or bytes, canonically: besides recomposing
+/// to the value, the limbs are subtracted from those of `p - 1` without a final borrow, so
+/// `value + p` is never a second decomposition. The limb cells are returned for further use.
+/// Limbs are looked up in `Table::BYTE` of the circuit's [`Tables`].
+pub struct DecomposeChip<F: FieldExt> {
+    config: DecomposeConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> DecomposeChip<F> {
+    pub fn construct(config: DecomposeConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>, tables: &mut Tables) -> DecomposeConfig {
+        let z = meta.advice_column();
+        let limb = meta.advice_column();
+        let rem = meta.advice_column();
+        let borrow = meta.advice_column();
+        let bound = meta.fixed_column();
+        let shift = meta.fixed_column();
+        let q_limb = meta.complex_selector();
+        let q_start = meta.selector();
+        let q_end = meta.selector();
+        let table = tables.column(meta, Table::BYTE);
+        meta.enable_equality(z);
+        meta.enable_equality(limb);
+
+        meta.create_gate("decompose limb", |meta| {
+            let q = meta.query_selector(q_limb);
+            let z_cur = meta.query_advice(z, Rotation::cur());
+            let z_next = meta.query_advice(z, Rotation::next());
+            let limb = meta.query_advice(limb, Rotation::cur());
+            let rem = meta.query_advice(rem, Rotation::cur());
+            let borrow_cur = meta.query_advice(borrow, Rotation::cur());
+            let borrow_next = meta.query_advice(borrow, Rotation::next());
+            let bound = meta.query_fixed(bound, Rotation::cur());
+            // the limb width varies per call, so both equations are scaled by shift = 2^(8 - w)
+            // to keep 2^w out of them
+            let shift = meta.query_fixed(shift, Rotation::cur());
+            let byte = Expression::Constant(F::from(256));
+            let one = Expression::Constant(F::one());
+            vec![
+                q.clone() * (shift.clone() * (z_cur - limb.clone()) - byte.clone() * z_next),
+                q.clone()
+                    * (shift * (bound - limb - borrow_cur.clone() - rem) + byte * borrow_next),
+                q * borrow_cur.clone() * (one - borrow_cur),
+            ]
+        });
+        meta.create_gate("decompose ends", |meta| {
+            let q_start = meta.query_selector(q_start);
+            let q_end = meta.query_selector(q_end);
+            let z = meta.query_advice(z, Rotation::cur());
+            let borrow = meta.query_advice(borrow, Rotation::cur());
+            vec![q_start * borrow.clone(), q_end.clone() * z, q_end * borrow]
+        });
+
+        for column in [limb, rem] {
+            meta.lookup("decompose limb byte", |meta| {
+                let q = meta.query_selector(q_limb);
+                vec![(q * meta.query_advice(column, Rotation::cur()), table)]
+            });
+            meta.lookup("decompose limb width", |meta| {
+                let q = meta.query_selector(q_limb);
+                let shift = meta.query_fixed(shift, Rotation::cur());
+                vec![(
+                    q * shift * meta.query_advice(column, Rotation::cur()),
+                    table,
+                )]
+            });
+        }
+
+        DecomposeConfig {
+            z,
+            limb,
+            rem,
+            borrow,
+            bound,
+            shift,
+            q_limb,
+            q_start,
+            q_end,
+        }
+    }
+
+    /// The little-endian limbs of `value`.
+    pub fn decompose(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &AssignedCell<F, F>,
+        mode: Decomposition,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let config = &self.config;
+        let w = mode.limb_bits();
+        let n = mode.limbs::<F>();
+        let bound = mode.split(&-F::one());
+        let base = 1u64 << w;
+        // the subtraction p - 1 - value limb by limb, with the borrow into each limb
+        let witness = value.value().map(|value| {
+            let limbs = mode.split(value);
+            let mut borrows = vec![0u64; n + 1];
+            let mut rems = Vec::with_capacity(n);
+            for i in 0..n {
+                let (rem, borrow) = match bound[i].checked_sub(limbs[i] + borrows[i]) {
+                    Some(rem) => (rem, 0),
+                    None => (bound[i] + base - limbs[i] - borrows[i], 1),
+                };
+                rems.push(rem);
+                borrows[i + 1] = borrow;
+            }
+            (limbs, rems, borrows)
+        });
+        let inv = pow2::<F>(w).invert().unwrap();
+        layouter.assign_region(
+            || format!("decompose into {}-bit limbs", w),
+            |mut region| {
+                config.q_start.enable(&mut region, 0)?;
+                let mut z = value.copy_advice(|| "z", &mut region, config.z, 0)?;
+                let mut cells = Vec::with_capacity(n);
+                for i in 0..n {
+                    config.q_limb.enable(&mut region, i)?;
+                    region.assign_fixed(
+                        || "bound",
+                        config.bound,
+                        i,
+                        || Value::known(F::from(bound[i])),
+                    )?;
+                    region.assign_fixed(
+                        || "shift",
+                        config.shift,
+                        i,
+                        || Value::known(pow2::<F>(8 - w)),
+                    )?;
+                    let field = |pick: fn(&Subtraction) -> &Vec<u64>| {
+                        witness.as_ref().map(|witness| F::from(pick(witness)[i]))
+                    };
+                    let limb = field(|(limbs, _, _)| limbs);
+                    cells.push(region.assign_advice(|| "limb", config.limb, i, || limb)?);
+                    region.assign_advice(|| "rem", config.rem, i, || field(|(_, rems, _)| rems))?;
+                    region.assign_advice(
+                        || "borrow",
+                        config.borrow,
+                        i,
+                        || field(|(_, _, borrows)| borrows),
+                    )?;
+                    let next = z
+                        .value()
+                        .copied()
+                        .zip(limb)
+                        .map(|(z, limb)| (z - limb) * inv);
+                    z = region.assign_advice(|| "z", config.z, i + 1, || next)?;
+                }
+                config.q_end.enable(&mut region, n)?;
+                let last_borrow = witness.as_ref().map(|(_, _, borrows)| F::from(borrows[n]));
+                region.assign_advice(|| "borrow", config.borrow, n, || last_borrow)?;
+                Ok(cells)
+            },
+        )
+    }
+}
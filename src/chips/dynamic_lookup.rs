@@ -0,0 +1,118 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Selector},
+    poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+pub struct DynamicLookupConfig<const W: usize> {
+    pub table: [Column<Advice>; W],
+    pub input: [Column<Advice>; W],
+    pub q_table: Selector,
+    pub q_lookup: Selector,
+}
+
+/// A lookup whose table is made of advice columns, so its rows are witnesses the circuit
+/// computes and constrains elsewhere, as zkEVMs do with their bytecode and memory tables.
+/// `q_table` marks the table rows and `q_lookup` the inputs, each of which must equal some
+/// table row. Unlike a fixed table, nothing but the circuit's own gates stops the prover from
+/// writing any rows, so callers must constrain them.
+pub struct DynamicLookupChip<F: FieldExt, const W: usize> {
+    config: DynamicLookupConfig<W>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const W: usize> DynamicLookupChip<F, W> {
+    pub fn construct(config: DynamicLookupConfig<W>) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> DynamicLookupConfig<W> {
+        let table = [(); W].map(|_| meta.advice_column());
+        let input = [(); W].map(|_| meta.advice_column());
+        for column in table.iter().chain(input.iter()) {
+            meta.enable_equality(*column);
+        }
+        let q_table = meta.complex_selector();
+        let q_lookup = meta.complex_selector();
+
+        // A disabled input row looks up all zeros, which the table side also is on every row
+        // outside the table, so the table needs no explicit zero row.
+        meta.lookup_any("dynamic lookup", |meta| {
+            let q_table = meta.query_selector(q_table);
+            let q_lookup = meta.query_selector(q_lookup);
+            input
+                .iter()
+                .zip(table.iter())
+                .map(|(input, table)| {
+                    (
+                        q_lookup.clone() * meta.query_advice(*input, Rotation::cur()),
+                        q_table.clone() * meta.query_advice(*table, Rotation::cur()),
+                    )
+                })
+                .collect()
+        });
+
+        DynamicLookupConfig {
+            table,
+            input,
+            q_table,
+            q_lookup,
+        }
+    }
+
+    /// Assigns the table rows in one region starting at offset 0, returning their cells for
+    /// the caller to constrain. `enable` runs on each row, e.g. to turn on the caller's gates
+    /// over `config.table`.
+    pub fn assign_table(
+        &self,
+        mut layouter: impl Layouter<F>,
+        rows: &[[Value<F>; W]],
+        enable: impl Fn(&mut Region<'_, F>, usize) -> Result<(), Error>,
+    ) -> Result<Vec<Vec<AssignedCell<F, F>>>, Error> {
+        let config = &self.config;
+        layouter.assign_region(
+            || "dynamic table",
+            |mut region| {
+                rows.iter()
+                    .enumerate()
+                    .map(|(offset, row)| {
+                        config.q_table.enable(&mut region, offset)?;
+                        enable(&mut region, offset)?;
+                        row.iter()
+                            .zip(config.table.iter())
+                            .map(|(value, column)| {
+                                region.assign_advice(|| "table", *column, offset, || *value)
+                            })
+                            .collect::<Result<Vec<_>, _>>()
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    /// Constrains the tuple of `cells` to be one of the table rows.
+    pub fn lookup(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cells: [&AssignedCell<F, F>; W],
+    ) -> Result<(), Error> {
+        let config = &self.config;
+        layouter.assign_region(
+            || "dynamic lookup",
+            |mut region| {
+                config.q_lookup.enable(&mut region, 0)?;
+                for (cell, column) in cells.iter().zip(config.input.iter()) {
+                    cell.copy_advice(|| "input", &mut region, *column, 0)?;
+                }
+                Ok(())
+            },
+        )
+    }
+}
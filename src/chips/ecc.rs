@@ -0,0 +1,351 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Instance, Selector},
+    poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+pub struct EccConfig {
+    pub advice: [Column<Advice>; 4],
+    pub q_on_curve: Selector,
+    pub q_add: Selector,
+    pub q_double: Selector,
+    pub q_negate: Selector,
+    pub q_select: Selector,
+    pub q_bool: Selector,
+}
+
+/// An affine point other than the identity.
+#[derive(Clone, Debug)]
+pub struct AssignedPoint<F: FieldExt> {
+    pub x: AssignedCell<F, F>,
+    pub y: AssignedCell<F, F>,
+}
+
+/// Affine arithmetic on a curve `y^2 = x^3 + b` whose base field is the circuit's own, so
+/// every coordinate is one cell and every formula one gate. Additions need distinct `x`,
+/// which they constrain; there is no identity, so callers keep the accumulator of a scalar
+/// multiplication away from it, e.g. with an offset point of unknown discrete log.
+pub struct EccChip<F: FieldExt> {
+    config: EccConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> EccChip<F> {
+    pub fn construct(config: EccConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Needs a constant column enabled elsewhere for `constant`.
+    pub fn configure(meta: &mut ConstraintSystem<F>, b: F) -> EccConfig {
+        let advice = [(); 4].map(|_| meta.advice_column());
+        let [q_on_curve, q_add, q_double, q_negate, q_select, q_bool] =
+            [(); 6].map(|_| meta.selector());
+        for column in advice {
+            meta.enable_equality(column);
+        }
+        let [c0, c1, c2, c3] = advice;
+
+        meta.create_gate("on curve", |meta| {
+            let q = meta.query_selector(q_on_curve);
+            let x = meta.query_advice(c0, Rotation::cur());
+            let y = meta.query_advice(c1, Rotation::cur());
+            vec![q * (y.clone() * y - x.clone() * x.clone() * x - Expression::Constant(b))]
+        });
+        // x_p y_p x_q y_q
+        // x_r y_r λ   α
+        meta.create_gate("add", |meta| {
+            let q = meta.query_selector(q_add);
+            let x_p = meta.query_advice(c0, Rotation::cur());
+            let y_p = meta.query_advice(c1, Rotation::cur());
+            let x_q = meta.query_advice(c2, Rotation::cur());
+            let y_q = meta.query_advice(c3, Rotation::cur());
+            let x_r = meta.query_advice(c0, Rotation::next());
+            let y_r = meta.query_advice(c1, Rotation::next());
+            let lambda = meta.query_advice(c2, Rotation::next());
+            let alpha = meta.query_advice(c3, Rotation::next());
+            let dx = x_q.clone() - x_p.clone();
+            vec![
+                q.clone() * (lambda.clone() * dx.clone() - (y_q - y_p.clone())),
+                q.clone() * (alpha * dx - Expression::Constant(F::one())),
+                q.clone() * (x_r.clone() - (lambda.clone() * lambda.clone() - x_p.clone() - x_q)),
+                q * (y_r - (lambda * (x_p - x_r) - y_p)),
+            ]
+        });
+        // x_p y_p λ
+        // x_r y_r
+        meta.create_gate("double", |meta| {
+            let q = meta.query_selector(q_double);
+            let x_p = meta.query_advice(c0, Rotation::cur());
+            let y_p = meta.query_advice(c1, Rotation::cur());
+            let lambda = meta.query_advice(c2, Rotation::cur());
+            let x_r = meta.query_advice(c0, Rotation::next());
+            let y_r = meta.query_advice(c1, Rotation::next());
+            let [two, three] = [2, 3].map(|c| Expression::Constant(F::from(c)));
+            vec![
+                q.clone()
+                    * (two.clone() * lambda.clone() * y_p.clone()
+                        - three * x_p.clone() * x_p.clone()),
+                q.clone() * (x_r.clone() - (lambda.clone() * lambda.clone() - two * x_p.clone())),
+                q * (y_r - (lambda * (x_p - x_r) - y_p)),
+            ]
+        });
+        meta.create_gate("negate", |meta| {
+            let q = meta.query_selector(q_negate);
+            let x_p = meta.query_advice(c0, Rotation::cur());
+            let y_p = meta.query_advice(c1, Rotation::cur());
+            let x_r = meta.query_advice(c2, Rotation::cur());
+            let y_r = meta.query_advice(c3, Rotation::cur());
+            vec![q.clone() * (x_r - x_p), q * (y_r + y_p)]
+        });
+        // bit x_a y_a
+        // x_b y_b x_r y_r
+        meta.create_gate("select", |meta| {
+            let q = meta.query_selector(q_select);
+            let bit = meta.query_advice(c0, Rotation::cur());
+            let x_a = meta.query_advice(c1, Rotation::cur());
+            let y_a = meta.query_advice(c2, Rotation::cur());
+            let x_b = meta.query_advice(c0, Rotation::next());
+            let y_b = meta.query_advice(c1, Rotation::next());
+            let x_r = meta.query_advice(c2, Rotation::next());
+            let y_r = meta.query_advice(c3, Rotation::next());
+            let one = Expression::Constant(F::one());
+            vec![
+                q.clone() * bit.clone() * (one - bit.clone()),
+                q.clone() * (x_r - x_b.clone() - bit.clone() * (x_a - x_b)),
+                q * (y_r - y_b.clone() - bit * (y_a - y_b)),
+            ]
+        });
+        meta.create_gate("bit", |meta| {
+            let q = meta.query_selector(q_bool);
+            let bit = meta.query_advice(c0, Rotation::cur());
+            vec![q * bit.clone() * (Expression::Constant(F::one()) - bit)]
+        });
+
+        EccConfig {
+            advice,
+            q_on_curve,
+            q_add,
+            q_double,
+            q_negate,
+            q_select,
+            q_bool,
+        }
+    }
+
+    fn on_curve(&self, region: &mut Region<'_, F>, offset: usize) -> Result<(), Error> {
+        self.config.q_on_curve.enable(region, offset)
+    }
+
+    /// A point checked to be on the curve.
+    pub fn witness(
+        &self,
+        mut layouter: impl Layouter<F>,
+        point: Value<(F, F)>,
+    ) -> Result<AssignedPoint<F>, Error> {
+        let [c0, c1, ..] = self.config.advice;
+        layouter.assign_region(
+            || "witness point",
+            |mut region| {
+                self.on_curve(&mut region, 0)?;
+                Ok(AssignedPoint {
+                    x: region.assign_advice(|| "x", c0, 0, || point.map(|(x, _)| x))?,
+                    y: region.assign_advice(|| "y", c1, 0, || point.map(|(_, y)| y))?,
+                })
+            },
+        )
+    }
+
+    /// A point fixed at keygen, which the caller vouches is on the curve.
+    pub fn constant(
+        &self,
+        mut layouter: impl Layouter<F>,
+        point: (F, F),
+    ) -> Result<AssignedPoint<F>, Error> {
+        let [c0, c1, ..] = self.config.advice;
+        layouter.assign_region(
+            || "constant point",
+            |mut region| {
+                Ok(AssignedPoint {
+                    x: region.assign_advice_from_constant(|| "x", c0, 0, point.0)?,
+                    y: region.assign_advice_from_constant(|| "y", c1, 0, point.1)?,
+                })
+            },
+        )
+    }
+
+    /// The point with `x` in row `row` of `instance` and `y` in the next, checked to be on the
+    /// curve.
+    pub fn from_instance(
+        &self,
+        mut layouter: impl Layouter<F>,
+        instance: Column<Instance>,
+        row: usize,
+    ) -> Result<AssignedPoint<F>, Error> {
+        let [c0, c1, ..] = self.config.advice;
+        layouter.assign_region(
+            || "point from instance",
+            |mut region| {
+                self.on_curve(&mut region, 0)?;
+                Ok(AssignedPoint {
+                    x: region.assign_advice_from_instance(|| "x", instance, row, c0, 0)?,
+                    y: region.assign_advice_from_instance(|| "y", instance, row + 1, c1, 0)?,
+                })
+            },
+        )
+    }
+
+    /// Boolean witnesses, e.g. the bits of a private scalar.
+    pub fn witness_bits(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bits: Value<Vec<bool>>,
+        n: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let c0 = self.config.advice[0];
+        layouter.assign_region(
+            || "witness bits",
+            |mut region| {
+                (0..n)
+                    .map(|i| {
+                        self.config.q_bool.enable(&mut region, i)?;
+                        let bit = bits.as_ref().map(|bits| F::from(bits[i]));
+                        region.assign_advice(|| "bit", c0, i, || bit)
+                    })
+                    .collect()
+            },
+        )
+    }
+
+    pub fn add(
+        &self,
+        mut layouter: impl Layouter<F>,
+        p: &AssignedPoint<F>,
+        q: &AssignedPoint<F>,
+    ) -> Result<AssignedPoint<F>, Error> {
+        let [c0, c1, c2, c3] = self.config.advice;
+        let (x_p, y_p) = (p.x.value().copied(), p.y.value().copied());
+        let (x_q, y_q) = (q.x.value().copied(), q.y.value().copied());
+        // zero has no inverse, so equal x leave nothing for α
+        let alpha = (x_q - x_p).map(|dx| dx.invert().unwrap_or_else(F::zero));
+        let lambda = (y_q - y_p) * alpha;
+        let x_r = lambda * lambda - x_p - x_q;
+        let y_r = lambda * (x_p - x_r) - y_p;
+        layouter.assign_region(
+            || "add",
+            |mut region| {
+                self.config.q_add.enable(&mut region, 0)?;
+                p.x.copy_advice(|| "x_p", &mut region, c0, 0)?;
+                p.y.copy_advice(|| "y_p", &mut region, c1, 0)?;
+                q.x.copy_advice(|| "x_q", &mut region, c2, 0)?;
+                q.y.copy_advice(|| "y_q", &mut region, c3, 0)?;
+                region.assign_advice(|| "lambda", c2, 1, || lambda)?;
+                region.assign_advice(|| "alpha", c3, 1, || alpha)?;
+                Ok(AssignedPoint {
+                    x: region.assign_advice(|| "x_r", c0, 1, || x_r)?,
+                    y: region.assign_advice(|| "y_r", c1, 1, || y_r)?,
+                })
+            },
+        )
+    }
+
+    pub fn double(
+        &self,
+        mut layouter: impl Layouter<F>,
+        p: &AssignedPoint<F>,
+    ) -> Result<AssignedPoint<F>, Error> {
+        let [c0, c1, c2, _] = self.config.advice;
+        let (x_p, y_p) = (p.x.value().copied(), p.y.value().copied());
+        let lambda = x_p
+            .zip(y_p)
+            .map(|(x, y)| F::from(3) * x * x * (F::from(2) * y).invert().unwrap_or_else(F::zero));
+        let x_r = lambda * lambda - x_p - x_p;
+        let y_r = lambda * (x_p - x_r) - y_p;
+        layouter.assign_region(
+            || "double",
+            |mut region| {
+                self.config.q_double.enable(&mut region, 0)?;
+                p.x.copy_advice(|| "x_p", &mut region, c0, 0)?;
+                p.y.copy_advice(|| "y_p", &mut region, c1, 0)?;
+                region.assign_advice(|| "lambda", c2, 0, || lambda)?;
+                Ok(AssignedPoint {
+                    x: region.assign_advice(|| "x_r", c0, 1, || x_r)?,
+                    y: region.assign_advice(|| "y_r", c1, 1, || y_r)?,
+                })
+            },
+        )
+    }
+
+    pub fn negate(
+        &self,
+        mut layouter: impl Layouter<F>,
+        p: &AssignedPoint<F>,
+    ) -> Result<AssignedPoint<F>, Error> {
+        let [c0, c1, c2, c3] = self.config.advice;
+        layouter.assign_region(
+            || "negate",
+            |mut region| {
+                self.config.q_negate.enable(&mut region, 0)?;
+                p.x.copy_advice(|| "x_p", &mut region, c0, 0)?;
+                p.y.copy_advice(|| "y_p", &mut region, c1, 0)?;
+                Ok(AssignedPoint {
+                    x: region.assign_advice(|| "x_r", c2, 0, || p.x.value().copied())?,
+                    y: region.assign_advice(|| "y_r", c3, 0, || -p.y.value().copied())?,
+                })
+            },
+        )
+    }
+
+    /// `a` if `bit` is 1 and `b` if it is 0; `bit` is constrained to be boolean.
+    pub fn select(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bit: &AssignedCell<F, F>,
+        a: &AssignedPoint<F>,
+        b: &AssignedPoint<F>,
+    ) -> Result<AssignedPoint<F>, Error> {
+        let [c0, c1, c2, c3] = self.config.advice;
+        let pick = |a: &AssignedCell<F, F>, b: &AssignedCell<F, F>| {
+            bit.value()
+                .zip(a.value())
+                .zip(b.value())
+                .map(|((bit, a), b)| if *bit == F::one() { *a } else { *b })
+        };
+        layouter.assign_region(
+            || "select",
+            |mut region| {
+                self.config.q_select.enable(&mut region, 0)?;
+                bit.copy_advice(|| "bit", &mut region, c0, 0)?;
+                a.x.copy_advice(|| "x_a", &mut region, c1, 0)?;
+                a.y.copy_advice(|| "y_a", &mut region, c2, 0)?;
+                b.x.copy_advice(|| "x_b", &mut region, c0, 1)?;
+                b.y.copy_advice(|| "y_b", &mut region, c1, 1)?;
+                Ok(AssignedPoint {
+                    x: region.assign_advice(|| "x_r", c2, 1, || pick(&a.x, &b.x))?,
+                    y: region.assign_advice(|| "y_r", c3, 1, || pick(&a.y, &b.y))?,
+                })
+            },
+        )
+    }
+
+    pub fn assert_equal(
+        &self,
+        mut layouter: impl Layouter<F>,
+        p: &AssignedPoint<F>,
+        q: &AssignedPoint<F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "assert equal",
+            |mut region| {
+                region.constrain_equal(p.x.cell(), q.x.cell())?;
+                region.constrain_equal(p.y.cell(), q.y.cell())
+            },
+        )
+    }
+}
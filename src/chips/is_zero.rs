@@ -0,0 +1,81 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+pub struct IsZeroConfig {
+    pub value: Column<Advice>,
+    /// `value^-1`, or anything when `value` is zero.
+    pub inverse: Column<Advice>,
+    pub is_zero: Column<Advice>,
+    pub q_is_zero: Selector,
+}
+
+/// `is_zero = 1 - value * inverse` with `value * is_zero = 0`: a nonzero value forces
+/// `is_zero` to 0 whatever the inverse, and a zero value forces it to 1.
+pub struct IsZeroChip<F: FieldExt> {
+    config: IsZeroConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> IsZeroChip<F> {
+    pub fn construct(config: IsZeroConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> IsZeroConfig {
+        let value = meta.advice_column();
+        let inverse = meta.advice_column();
+        let is_zero = meta.advice_column();
+        let q_is_zero = meta.selector();
+        meta.enable_equality(value);
+        meta.enable_equality(is_zero);
+
+        meta.create_gate("is zero", |meta| {
+            let q = meta.query_selector(q_is_zero);
+            let value = meta.query_advice(value, Rotation::cur());
+            let inverse = meta.query_advice(inverse, Rotation::cur());
+            let is_zero = meta.query_advice(is_zero, Rotation::cur());
+            let one = Expression::Constant(F::one());
+            vec![
+                q.clone() * (one - value.clone() * inverse - is_zero.clone()),
+                q * value * is_zero,
+            ]
+        });
+
+        IsZeroConfig {
+            value,
+            inverse,
+            is_zero,
+            q_is_zero,
+        }
+    }
+
+    /// 1 if `value` is zero, 0 otherwise.
+    pub fn is_zero(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = &self.config;
+        layouter.assign_region(
+            || "is zero",
+            |mut region| {
+                config.q_is_zero.enable(&mut region, 0)?;
+                let value = value.copy_advice(|| "value", &mut region, config.value, 0)?;
+                let inverse = value.value().map(|v| v.invert().unwrap_or_else(F::zero));
+                region.assign_advice(|| "inverse", config.inverse, 0, || inverse)?;
+                let is_zero = value.value().map(|v| F::from(*v == F::zero()));
+                region.assign_advice(|| "is zero", config.is_zero, 0, || is_zero)
+            },
+        )
+    }
+}
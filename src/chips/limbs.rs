@@ -0,0 +1,208 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::halo2curves::group::ff::PrimeField;
+use halo2_proofs::{
+    arithmetic::{Field, FieldExt},
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Selector, TableColumn},
+    poly::Rotation,
+};
+
+pub(crate) fn pow2<F: FieldExt>(e: usize) -> F {
+    F::from(2).pow_vartime(&[e as u64])
+}
+
+pub(crate) fn lower_u64<F: FieldExt>(value: &F) -> u64 {
+    value.get_lower_128() as u64
+}
+
+/// A term of a running sum: a cell copied in from elsewhere, optionally range checked, or a
+/// fresh witness range checked to the given number of bits (at most 8).
+pub enum Limb<'a, F: FieldExt> {
+    Copy(&'a AssignedCell<F, F>),
+    CopyChecked(&'a AssignedCell<F, F>, usize),
+    Witness(Value<F>, usize),
+}
+
+#[derive(Clone, Debug)]
+pub struct LimbsConfig {
+    pub limb: Column<Advice>,
+    pub acc: Column<Advice>,
+    pub coeff: Column<Fixed>,
+    pub range_mul: Column<Fixed>,
+    pub q_first: Selector,
+    pub q_next: Selector,
+    pub q_range: Selector,
+    table: TableColumn,
+}
+
+/// Constant-weighted running sums over small range-checked limbs, the building block of the
+/// word and byte gadgets.
+pub struct LimbsChip<F: FieldExt> {
+    config: LimbsConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> LimbsChip<F> {
+    pub fn construct(config: LimbsConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> LimbsConfig {
+        let limb = meta.advice_column();
+        let acc = meta.advice_column();
+        let coeff = meta.fixed_column();
+        let range_mul = meta.fixed_column();
+        let q_first = meta.selector();
+        let q_next = meta.selector();
+        let q_range = meta.complex_selector();
+        let table = meta.lookup_table_column();
+
+        meta.enable_equality(limb);
+        meta.enable_equality(acc);
+
+        meta.create_gate("running sum first", |meta| {
+            let q_first = meta.query_selector(q_first);
+            let limb = meta.query_advice(limb, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let coeff = meta.query_fixed(coeff, Rotation::cur());
+            vec![q_first * (acc - coeff * limb)]
+        });
+        meta.create_gate("running sum next", |meta| {
+            let q_next = meta.query_selector(q_next);
+            let limb = meta.query_advice(limb, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let prev = meta.query_advice(acc, Rotation::prev());
+            let coeff = meta.query_fixed(coeff, Rotation::cur());
+            vec![q_next * (acc - prev - coeff * limb)]
+        });
+
+        // a limb is below 2^m when both `limb` and `limb * 2^(8 - m)` are bytes
+        meta.lookup("limb byte", |meta| {
+            let q_range = meta.query_selector(q_range);
+            let limb = meta.query_advice(limb, Rotation::cur());
+            vec![(q_range * limb, table)]
+        });
+        meta.lookup("limb width", |meta| {
+            let q_range = meta.query_selector(q_range);
+            let limb = meta.query_advice(limb, Rotation::cur());
+            let range_mul = meta.query_fixed(range_mul, Rotation::cur());
+            vec![(q_range * range_mul * limb, table)]
+        });
+
+        LimbsConfig {
+            limb,
+            acc,
+            coeff,
+            range_mul,
+            q_first,
+            q_next,
+            q_range,
+            table,
+        }
+    }
+
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        let table = self.config.table;
+        layouter.assign_table(
+            || "byte table",
+            |mut t| {
+                for byte in 0..256 {
+                    t.assign_cell(
+                        || "byte",
+                        table,
+                        byte,
+                        || Value::known(F::from(byte as u64)),
+                    )?;
+                }
+                Ok(())
+            },
+        )
+    }
+
+    /// Assigns `Σ coeff_i * limb_i` as a running sum, returning the limb cells and the sum.
+    pub fn running_sum(
+        &self,
+        mut layouter: impl Layouter<F>,
+        limbs: &[(Limb<'_, F>, F)],
+    ) -> Result<(Vec<AssignedCell<F, F>>, AssignedCell<F, F>), Error> {
+        let config = &self.config;
+        layouter.assign_region(
+            || "running sum",
+            |mut region| {
+                let mut cells = Vec::with_capacity(limbs.len());
+                let mut acc: Option<AssignedCell<F, F>> = None;
+                for (offset, (limb, coeff)) in limbs.iter().enumerate() {
+                    let width = match limb {
+                        Limb::Copy(_) => None,
+                        Limb::CopyChecked(_, width) | Limb::Witness(_, width) => Some(*width),
+                    };
+                    if let Some(width) = width {
+                        config.q_range.enable(&mut region, offset)?;
+                        region.assign_fixed(
+                            || "range mul",
+                            config.range_mul,
+                            offset,
+                            || Value::known(pow2::<F>(8 - width)),
+                        )?;
+                    }
+                    let cell = match limb {
+                        Limb::Copy(cell) | Limb::CopyChecked(cell, _) => {
+                            cell.copy_advice(|| "limb", &mut region, config.limb, offset)?
+                        }
+                        Limb::Witness(value, _) => {
+                            region.assign_advice(|| "limb", config.limb, offset, || *value)?
+                        }
+                    };
+                    region.assign_fixed(
+                        || "coeff",
+                        config.coeff,
+                        offset,
+                        || Value::known(*coeff),
+                    )?;
+                    let term = cell.value().map(|limb| *limb * coeff);
+                    let value = match &acc {
+                        None => {
+                            config.q_first.enable(&mut region, offset)?;
+                            term
+                        }
+                        Some(prev) => {
+                            config.q_next.enable(&mut region, offset)?;
+                            prev.value().copied() + term
+                        }
+                    };
+                    acc = Some(region.assign_advice(|| "acc", config.acc, offset, || value)?);
+                    cells.push(cell);
+                }
+                Ok((cells, acc.expect("running sum over no limbs")))
+            },
+        )
+    }
+
+    /// Witnesses the little-endian bytes of `value` and constrains them to recompose it.
+    /// `n_bytes` must be at most 31 so the decomposition cannot wrap around the modulus.
+    pub fn decompose_bytes(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &AssignedCell<F, F>,
+        n_bytes: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        assert!(n_bytes <= 31, "at most 31 bytes fit below the modulus");
+        let repr = value.value().map(|v| v.to_repr());
+        let limbs: Vec<_> = (0..n_bytes)
+            .map(|i| {
+                let byte = repr.as_ref().map(|repr| F::from(repr.as_ref()[i] as u64));
+                (Limb::Witness(byte, 8), pow2::<F>(8 * i))
+            })
+            .collect();
+        let (bytes, sum) = self.running_sum(layouter.namespace(|| "bytes"), &limbs)?;
+        layouter.assign_region(
+            || "recompose",
+            |mut region| region.constrain_equal(sum.cell(), value.cell()),
+        )?;
+        Ok(bytes)
+    }
+}
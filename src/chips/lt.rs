@@ -0,0 +1,138 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+use super::{
+    limbs::pow2,
+    range_check::limbs,
+    tables::{Table, Tables},
+};
+
+#[derive(Clone, Debug)]
+pub struct LtConfig<const N_BYTES: usize> {
+    pub lhs: Column<Advice>,
+    pub rhs: Column<Advice>,
+    pub lt: Column<Advice>,
+    /// Little-endian bytes of `lhs - rhs + lt * 2^(8 N_BYTES)`.
+    pub diff: [Column<Advice>; N_BYTES],
+    pub q_lt: Selector,
+}
+
+/// `lhs < rhs` for values below `2^(8 N_BYTES)`: with `lt` boolean,
+/// `lhs - rhs + lt * 2^(8 N_BYTES)` must decompose into `N_BYTES` bytes, each looked up in
+/// `Table::BYTE` of the circuit's [`Tables`], which only the right `lt` allows. The chip
+/// doesn't range check its operands; values from elsewhere need a range check of their own,
+/// or the result is meaningless.
+pub struct LtChip<F: FieldExt, const N_BYTES: usize> {
+    config: LtConfig<N_BYTES>,
+    _marker: PhantomData<F>,
+}
+
+/// Compares canonical representations as integers.
+fn less<F: FieldExt>(lhs: &F, rhs: &F) -> bool {
+    let (lhs, rhs) = (lhs.to_repr(), rhs.to_repr());
+    // little-endian, so compare from the top byte down
+    lhs.as_ref().iter().rev().lt(rhs.as_ref().iter().rev())
+}
+
+impl<F: FieldExt, const N_BYTES: usize> LtChip<F, N_BYTES> {
+    pub fn construct(config: LtConfig<N_BYTES>) -> Self {
+        assert!(
+            0 < N_BYTES && 8 * N_BYTES < F::NUM_BITS as usize,
+            "operands of 1 to {} bytes",
+            (F::NUM_BITS - 1) / 8
+        );
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>, tables: &mut Tables) -> LtConfig<N_BYTES> {
+        let lhs = meta.advice_column();
+        let rhs = meta.advice_column();
+        let lt = meta.advice_column();
+        let diff = [(); N_BYTES].map(|_| meta.advice_column());
+        let q_lt = meta.complex_selector();
+        let table = tables.column(meta, Table::BYTE);
+        for column in [lhs, rhs, lt] {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("less than", |meta| {
+            let q = meta.query_selector(q_lt);
+            let lhs = meta.query_advice(lhs, Rotation::cur());
+            let rhs = meta.query_advice(rhs, Rotation::cur());
+            let lt = meta.query_advice(lt, Rotation::cur());
+            let diff = diff.iter().enumerate().fold(
+                Expression::Constant(F::zero()),
+                |acc, (i, column)| {
+                    acc + meta.query_advice(*column, Rotation::cur()) * pow2::<F>(8 * i)
+                },
+            );
+            let one = Expression::Constant(F::one());
+            vec![
+                q.clone() * lt.clone() * (one - lt.clone()),
+                q * (lhs - rhs + lt * pow2::<F>(8 * N_BYTES) - diff),
+            ]
+        });
+        for column in diff {
+            meta.lookup("less than byte", |meta| {
+                let q = meta.query_selector(q_lt);
+                vec![(q * meta.query_advice(column, Rotation::cur()), table)]
+            });
+        }
+
+        LtConfig {
+            lhs,
+            rhs,
+            lt,
+            diff,
+            q_lt,
+        }
+    }
+
+    /// 1 if `lhs < rhs`, 0 otherwise.
+    pub fn lt(
+        &self,
+        mut layouter: impl Layouter<F>,
+        lhs: &AssignedCell<F, F>,
+        rhs: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = &self.config;
+        layouter.assign_region(
+            || "less than",
+            |mut region| {
+                config.q_lt.enable(&mut region, 0)?;
+                let lhs = lhs.copy_advice(|| "lhs", &mut region, config.lhs, 0)?;
+                let rhs = rhs.copy_advice(|| "rhs", &mut region, config.rhs, 0)?;
+                let lt = lhs
+                    .value()
+                    .zip(rhs.value())
+                    .map(|(lhs, rhs)| less(lhs, rhs));
+                let diff = lhs
+                    .value()
+                    .zip(rhs.value())
+                    .zip(lt)
+                    .map(|((lhs, rhs), lt)| {
+                        let shift = if lt {
+                            pow2::<F>(8 * N_BYTES)
+                        } else {
+                            F::zero()
+                        };
+                        limbs(&(*lhs - rhs + shift), 8, N_BYTES)
+                    });
+                for (i, column) in config.diff.iter().enumerate() {
+                    let byte = diff.as_ref().map(|bytes| F::from(bytes[i]));
+                    region.assign_advice(|| "diff byte", *column, 0, || byte)?;
+                }
+                region.assign_advice(|| "lt", config.lt, 0, || lt.map(F::from))
+            },
+        )
+    }
+}
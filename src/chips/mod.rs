@@ -0,0 +1,34 @@
+pub mod arith;
+#[cfg(feature = "gadgets-nonnative")]
+pub mod bigint;
+pub mod boolean;
+pub mod branch;
+#[cfg(feature = "gadgets-bytes")]
+pub mod byte_ops;
+pub mod constant_pool;
+#[cfg(feature = "gadgets-lookup")]
+pub mod decompose;
+#[cfg(feature = "gadgets-lookup")]
+pub mod dynamic_lookup;
+pub mod ecc;
+pub mod is_zero;
+pub mod limbs;
+#[cfg(feature = "gadgets-lookup")]
+pub mod lt;
+#[cfg(feature = "gadgets-nonnative")]
+pub mod nonnative;
+#[cfg(feature = "gadgets-bytes")]
+pub mod pack;
+pub mod public_digest;
+#[cfg(feature = "gadgets-lookup")]
+pub mod range_check;
+pub mod rlc;
+#[cfg(feature = "gadgets-bytes")]
+pub mod rotate;
+pub mod shuffle;
+pub mod standard_plonk;
+#[cfg(feature = "gadgets-lookup")]
+pub mod tables;
+#[cfg(feature = "gadgets-lookup")]
+pub mod uint;
+pub mod var_len;
@@ -0,0 +1,778 @@
+//! Arithmetic modulo a prime other than the circuit's, e.g. secp256k1's base field and group
+//! order inside BN254's scalar field. An element is three 88-bit limbs of an integer below
+//! `2^256`, not necessarily reduced, and every operation is one row of
+//!
+//! `k_ab * a * b + k_d * d + k_e * e + o = q * p + c`
+//!
+//! checked over the limbs modulo `2^264`, with carries, and over their compositions modulo
+//! the circuit's field; as both moduli together exceed the sides, it holds over the integers.
+//! `o` is a multiple of `p` that keeps the left side non-negative. New limbs, quotients and
+//! carries are range checked on lanes of [`RangeCheckChip`]s in columns of their own, taken in
+//! turn so the floor planner lays their regions side by side. The lanes look up one table of the
+//! circuit's [`Tables`].
+
+use std::{cell::Cell, convert::TryInto, marker::PhantomData};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Fixed, Instance, Selector},
+    poly::Rotation,
+};
+use num_bigint::{BigInt, BigUint};
+
+use super::{
+    limbs::pow2,
+    range_check::{RangeCheckChip, RangeCheckConfig},
+    tables::Tables,
+};
+
+pub const LIMB_BITS: usize = 88;
+pub const LIMBS: usize = 3;
+/// Elements are below `2^ELEMENT_BITS`.
+pub const ELEMENT_BITS: usize = 256;
+/// Width of the range check table, which the lanes share.
+const TABLE_BITS: usize = 11;
+pub const LANES: usize = 8;
+/// Carries lie in `(-2^CARRY_SHIFT, 2^CARRY_SHIFT)` and are range checked after adding it.
+const CARRY_SHIFT: usize = 94;
+
+/// Limb widths of an integer below `2^bits`.
+fn widths(bits: usize) -> [usize; LIMBS] {
+    [0, 1, 2].map(|i| bits.saturating_sub(i * LIMB_BITS).min(LIMB_BITS))
+}
+
+/// The little-endian limbs of `value`, dropping bits above `2^264`.
+pub fn split(value: &BigUint) -> [BigUint; LIMBS] {
+    let mask = (BigUint::from(1u32) << LIMB_BITS) - 1u32;
+    [0, 1, 2].map(|i| (value >> (i * LIMB_BITS)) & &mask)
+}
+
+fn to_field<F: FieldExt>(value: &BigUint) -> F {
+    let digits = value.to_u64_digits();
+    let digit = |i: usize| digits.get(i).copied().unwrap_or(0) as u128;
+    F::from_u128(digit(0) | digit(1) << 64)
+}
+
+/// The limbs of `value` as field elements, e.g. for instance rows read by
+/// [`NonNativeChip::from_instance`].
+pub fn to_limbs<F: FieldExt>(value: &BigUint) -> [F; LIMBS] {
+    split(value).map(|limb| to_field(&limb))
+}
+
+/// `value^-1` modulo the prime `modulus`, by Fermat's little theorem.
+pub fn inverse(value: &BigUint, modulus: &BigUint) -> BigUint {
+    value.modpow(&(modulus.clone() - 2u32), modulus)
+}
+
+/// A modulus below `2^256` and at least `2^250`, as the fixed limbs the relation reads.
+#[derive(Clone, Debug)]
+pub struct Modulus<F: FieldExt> {
+    value: BigUint,
+    limbs: [F; LIMBS],
+    /// Width of the quotients of this modulus.
+    q_bits: usize,
+}
+
+impl<F: FieldExt> Modulus<F> {
+    pub fn new(value: BigUint) -> Self {
+        let bits = value.bits() as usize;
+        assert!(
+            (251..=ELEMENT_BITS).contains(&bits),
+            "moduli of 251 to 256 bits"
+        );
+        // the left side is below 2^514, so quotients are below 2^(515 - bits)
+        Self {
+            limbs: to_limbs(&value),
+            q_bits: 515 - bits,
+            value,
+        }
+    }
+
+    pub fn value(&self) -> &BigUint {
+        &self.value
+    }
+}
+
+/// An integer below `2^256` held in three range-checked limbs.
+#[derive(Clone, Debug)]
+pub struct Element<F: FieldExt> {
+    pub limbs: [AssignedCell<F, F>; LIMBS],
+    pub value: Value<BigUint>,
+}
+
+/// An input of a relation: an assigned element, a new witness, range checked to `2^256`, or
+/// zero.
+#[derive(Clone, Debug)]
+pub enum Operand<'a, F: FieldExt> {
+    Element(&'a Element<F>),
+    Witness(Value<BigUint>),
+    Zero,
+}
+
+impl<'a, F: FieldExt> Operand<'a, F> {
+    fn value(&self) -> Value<BigUint> {
+        match self {
+            Operand::Element(element) => element.value.clone(),
+            Operand::Witness(value) => value.clone(),
+            Operand::Zero => Value::known(BigUint::from(0u8)),
+        }
+    }
+}
+
+/// `k_ab * a * b + k_d * d + k_e * e ≡ c (mod p)`, with `k_ab` at most 2, `|k_d|` and `|k_e|`
+/// at most 3, and `c` the reduced result when `None`.
+#[derive(Clone, Debug)]
+pub struct Relation<'a, F: FieldExt> {
+    pub kab: u64,
+    pub a: Operand<'a, F>,
+    pub b: Operand<'a, F>,
+    pub d: (i64, Operand<'a, F>),
+    pub e: (i64, Operand<'a, F>),
+    pub c: Option<Operand<'a, F>>,
+}
+
+impl<'a, F: FieldExt> Default for Relation<'a, F> {
+    fn default() -> Self {
+        Self {
+            kab: 0,
+            a: Operand::Zero,
+            b: Operand::Zero,
+            d: (0, Operand::Zero),
+            e: (0, Operand::Zero),
+            c: None,
+        }
+    }
+}
+
+/// The elements a relation assigned, in the order `a`, `b`, `d`, `e`, `c`.
+pub type Assigned<F> = [Element<F>; 5];
+
+#[derive(Clone, Debug)]
+pub struct NonNativeConfig {
+    /// `a`, `b`, `d`, `e`, `q`, `c` and the carries, three columns each.
+    pub columns: [[Column<Advice>; LIMBS]; 7],
+    pub kab: Column<Fixed>,
+    pub kd: Column<Fixed>,
+    pub ke: Column<Fixed>,
+    pub offset: [Column<Fixed>; LIMBS],
+    pub modulus: [Column<Fixed>; LIMBS],
+    pub lanes: Vec<RangeCheckConfig>,
+    q_relation: Selector,
+    q_bit: Selector,
+    q_bits_end: Selector,
+    q_select: Selector,
+}
+
+pub struct NonNativeChip<F: FieldExt> {
+    config: NonNativeConfig,
+    /// The lane for the next range check.
+    lane: Cell<usize>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> NonNativeChip<F> {
+    pub fn construct(config: NonNativeConfig) -> Self {
+        Self {
+            config,
+            lane: Cell::new(0),
+            _marker: PhantomData,
+        }
+    }
+
+    /// Needs a fixed column enabled for constants.
+    pub fn configure(meta: &mut ConstraintSystem<F>, tables: &mut Tables) -> NonNativeConfig {
+        let columns = [(); 7].map(|_| [(); LIMBS].map(|_| meta.advice_column()));
+        for column in columns.iter().flatten() {
+            meta.enable_equality(*column);
+        }
+        let kab = meta.fixed_column();
+        let kd = meta.fixed_column();
+        let ke = meta.fixed_column();
+        let offset = [(); LIMBS].map(|_| meta.fixed_column());
+        let modulus = [(); LIMBS].map(|_| meta.fixed_column());
+        let lanes = (0..LANES)
+            .map(|_| RangeCheckChip::<F, TABLE_BITS>::configure(meta, tables))
+            .collect();
+        let config = NonNativeConfig {
+            columns,
+            kab,
+            kd,
+            ke,
+            offset,
+            modulus,
+            lanes,
+            q_relation: meta.selector(),
+            q_bit: meta.selector(),
+            q_bits_end: meta.selector(),
+            q_select: meta.selector(),
+        };
+
+        meta.create_gate("non-native relation", |meta| {
+            let q = meta.query_selector(config.q_relation);
+            let [a, b, d, e, quotient, c, carries] =
+                columns.map(|limbs| limbs.map(|column| meta.query_advice(column, Rotation::cur())));
+            let kab = meta.query_fixed(kab, Rotation::cur());
+            let kd = meta.query_fixed(kd, Rotation::cur());
+            let ke = meta.query_fixed(ke, Rotation::cur());
+            let o = offset.map(|column| meta.query_fixed(column, Rotation::cur()));
+            let p = modulus.map(|column| meta.query_fixed(column, Rotation::cur()));
+            let base = Expression::Constant(pow2::<F>(LIMB_BITS));
+            let shift = Expression::Constant(pow2::<F>(CARRY_SHIFT));
+            let product = |x: &[Expression<F>; LIMBS], y: &[Expression<F>; LIMBS], k: usize| {
+                (0..=k)
+                    .map(|i| x[i].clone() * y[k - i].clone())
+                    .reduce(|acc, term| acc + term)
+                    .unwrap()
+            };
+            let t: Vec<_> = (0..LIMBS)
+                .map(|k| {
+                    kab.clone() * product(&a, &b, k)
+                        + kd.clone() * d[k].clone()
+                        + ke.clone() * e[k].clone()
+                        + o[k].clone()
+                        - product(&quotient, &p, k)
+                        - c[k].clone()
+                })
+                .collect();
+            let v: Vec<_> = carries
+                .iter()
+                .map(|carry| carry.clone() - shift.clone())
+                .collect();
+            let compose = |x: &[Expression<F>; LIMBS]| {
+                x.iter()
+                    .rev()
+                    .cloned()
+                    .reduce(|acc, limb| acc * base.clone() + limb)
+                    .unwrap()
+            };
+            let native =
+                kab * compose(&a) * compose(&b) + kd * compose(&d) + ke * compose(&e) + compose(&o)
+                    - compose(&quotient) * compose(&p)
+                    - compose(&c);
+            vec![
+                q.clone() * (t[0].clone() - v[0].clone() * base.clone()),
+                q.clone() * (t[1].clone() + v[0].clone() - v[1].clone() * base.clone()),
+                q.clone() * (t[2].clone() + v[1].clone() - v[2].clone() * base),
+                q * native,
+            ]
+        });
+
+        // z_i = bit_i + 2 z_{i+1}, ending in zero
+        let [z, bit] = [columns[0][0], columns[0][1]];
+        meta.create_gate("bit", |meta| {
+            let q = meta.query_selector(config.q_bit);
+            let z_cur = meta.query_advice(z, Rotation::cur());
+            let z_next = meta.query_advice(z, Rotation::next());
+            let bit = meta.query_advice(bit, Rotation::cur());
+            vec![
+                q.clone() * bit.clone() * (Expression::Constant(F::one()) - bit.clone()),
+                q * (z_cur - bit - Expression::Constant(F::from(2)) * z_next),
+            ]
+        });
+        meta.create_gate("bits end", |meta| {
+            let q = meta.query_selector(config.q_bits_end);
+            vec![q * meta.query_advice(z, Rotation::cur())]
+        });
+
+        // the limb of table[b1 + 2 b2]
+        meta.create_gate("select limb", |meta| {
+            let q = meta.query_selector(config.q_select);
+            let b1 = meta.query_advice(columns[0][0], Rotation::cur());
+            let b2 = meta.query_advice(columns[0][1], Rotation::cur());
+            let [t0, t1, t2] = columns[1].map(|column| meta.query_advice(column, Rotation::cur()));
+            let t3 = meta.query_advice(columns[2][0], Rotation::cur());
+            let out = meta.query_advice(columns[2][1], Rotation::cur());
+            let selected = t0.clone()
+                + b1.clone() * (t1.clone() - t0.clone())
+                + b2.clone() * (t2.clone() - t0.clone())
+                + b1 * b2 * (t3 - t2 - t1 + t0);
+            vec![q * (out - selected)]
+        });
+        config
+    }
+
+    fn range_check(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+        bits: usize,
+    ) -> Result<(), Error> {
+        if bits == 0 {
+            return layouter.assign_region(
+                || "zero limb",
+                |mut region| region.constrain_constant(cell.cell(), F::zero()),
+            );
+        }
+        let lane = self.lane.get();
+        self.lane.set((lane + 1) % LANES);
+        RangeCheckChip::<F, TABLE_BITS>::construct(self.config.lanes[lane].clone()).range_check(
+            layouter.namespace(|| "range"),
+            cell,
+            bits,
+        )?;
+        Ok(())
+    }
+
+    fn range_check_limbs(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        limbs: &[AssignedCell<F, F>; LIMBS],
+        bits: usize,
+    ) -> Result<(), Error> {
+        for (limb, width) in limbs.iter().zip(widths(bits)) {
+            self.range_check(layouter, limb, width)?;
+        }
+        Ok(())
+    }
+
+    /// A new element below `2^256`.
+    pub fn witness(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<BigUint>,
+    ) -> Result<Element<F>, Error> {
+        let columns = self.config.columns[5];
+        let limbs = layouter.assign_region(
+            || "element",
+            |mut region| {
+                let mut cells = vec![];
+                for (i, column) in columns.iter().enumerate() {
+                    let limb = value.as_ref().map(|v| to_field::<F>(&split(v)[i]));
+                    cells.push(region.assign_advice(|| "limb", *column, 0, || limb)?);
+                }
+                Ok(cells.try_into().unwrap())
+            },
+        )?;
+        self.range_check_limbs(&mut layouter, &limbs, ELEMENT_BITS)?;
+        Ok(Element { limbs, value })
+    }
+
+    pub fn constant(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &BigUint,
+    ) -> Result<Element<F>, Error> {
+        assert!(
+            value.bits() as usize <= ELEMENT_BITS,
+            "constants below 2^256"
+        );
+        let columns = self.config.columns[5];
+        let limbs = layouter.assign_region(
+            || "constant element",
+            |mut region| {
+                let mut cells = vec![];
+                for (limb, column) in split(value).iter().zip(columns.iter()) {
+                    cells.push(region.assign_advice_from_constant(
+                        || "limb",
+                        *column,
+                        0,
+                        to_field::<F>(limb),
+                    )?);
+                }
+                Ok(cells.try_into().unwrap())
+            },
+        )?;
+        Ok(Element {
+            limbs,
+            value: Value::known(value.clone()),
+        })
+    }
+
+    /// An element whose limbs are instance rows `row` to `row + 2` of `column`.
+    pub fn from_instance(
+        &self,
+        mut layouter: impl Layouter<F>,
+        column: Column<Instance>,
+        row: usize,
+    ) -> Result<Element<F>, Error> {
+        let columns = self.config.columns[5];
+        let limbs: [AssignedCell<F, F>; LIMBS] = layouter.assign_region(
+            || "instance element",
+            |mut region| {
+                let mut cells = vec![];
+                for (i, advice) in columns.iter().enumerate() {
+                    cells.push(region.assign_advice_from_instance(
+                        || "limb",
+                        column,
+                        row + i,
+                        *advice,
+                        0,
+                    )?);
+                }
+                Ok(cells.try_into().unwrap())
+            },
+        )?;
+        self.range_check_limbs(&mut layouter, &limbs, ELEMENT_BITS)?;
+        let value = limbs
+            .iter()
+            .rev()
+            .fold(Value::known(BigUint::from(0u8)), |acc, limb| {
+                acc.zip(limb.value()).map(|(acc, limb)| {
+                    (acc << LIMB_BITS) + BigUint::from_bytes_le(limb.to_repr().as_ref())
+                })
+            });
+        Ok(Element { limbs, value })
+    }
+
+    /// Assigns one row of `relation` modulo `modulus`.
+    pub fn constrain(
+        &self,
+        mut layouter: impl Layouter<F>,
+        modulus: &Modulus<F>,
+        relation: Relation<'_, F>,
+    ) -> Result<Assigned<F>, Error> {
+        let Relation {
+            kab,
+            a,
+            b,
+            d: (kd, d),
+            e: (ke, e),
+            c,
+        } = relation;
+        assert!(
+            kab <= 2 && kd.abs() <= 3 && ke.abs() <= 3,
+            "coefficients too large"
+        );
+        let config = &self.config;
+        let p = BigInt::from(modulus.value.clone());
+        let negative: i64 = [kd, ke].iter().filter(|k| **k < 0).map(|k| -k).sum();
+        // the least multiple of p at or above negative * 2^256
+        let bound = BigInt::from(negative) << ELEMENT_BITS;
+        let offset = ((&bound + &p - 1u32) / &p) * &p;
+        let offset = offset.to_biguint().unwrap();
+
+        let inputs = a.value().zip(b.value()).zip(d.value()).zip(e.value());
+        let lhs = inputs.clone().map(|(((va, vb), vd), ve)| {
+            BigInt::from(kab) * BigInt::from(va * vb)
+                + BigInt::from(kd) * BigInt::from(vd)
+                + BigInt::from(ke) * BigInt::from(ve)
+                + BigInt::from(offset.clone())
+        });
+        let c = match c {
+            Some(c) => c,
+            None => Operand::Witness(
+                lhs.clone()
+                    .map(|lhs| (lhs % &p).to_biguint().unwrap_or_default()),
+            ),
+        };
+        let quotient = lhs.zip(c.value()).map(|(lhs, c)| {
+            ((lhs - BigInt::from(c)) / &p)
+                .to_biguint()
+                .unwrap_or_default()
+        });
+        // the limbwise sums over the integers, carried into the next limb
+        let carries =
+            inputs
+                .zip(quotient.clone())
+                .zip(c.value())
+                .map(|(((((va, vb), vd), ve), vq), vc)| {
+                    let limbs = |value: &BigUint| split(value).map(BigInt::from);
+                    let (a, b, d, e, q, c) = (
+                        limbs(&va),
+                        limbs(&vb),
+                        limbs(&vd),
+                        limbs(&ve),
+                        limbs(&vq),
+                        limbs(&vc),
+                    );
+                    let (o, p) = (limbs(&offset), limbs(&modulus.value));
+                    let mut carry = BigInt::from(0u8);
+                    let mut carries = vec![];
+                    for k in 0..LIMBS {
+                        let product = |x: &[BigInt; LIMBS], y: &[BigInt; LIMBS]| -> BigInt {
+                            (0..=k).map(|i| &x[i] * &y[k - i]).sum()
+                        };
+                        let t = BigInt::from(kab) * product(&a, &b)
+                            + BigInt::from(kd) * &d[k]
+                            + BigInt::from(ke) * &e[k]
+                            + &o[k]
+                            - product(&q, &p)
+                            - &c[k]
+                            + carry;
+                        carry = t >> LIMB_BITS;
+                        carries.push(
+                            (&carry + (BigInt::from(1u8) << CARRY_SHIFT))
+                                .to_biguint()
+                                .unwrap_or_default(),
+                        );
+                    }
+                    carries
+                });
+
+        let signed = |k: i64| {
+            if k < 0 {
+                -F::from(k.unsigned_abs())
+            } else {
+                F::from(k as u64)
+            }
+        };
+        let operands = [&a, &b, &d, &e, &c];
+        let cells = layouter.assign_region(
+            || "non-native relation",
+            |mut region| {
+                config.q_relation.enable(&mut region, 0)?;
+                for (column, k) in [(config.kab, kab as i64), (config.kd, kd), (config.ke, ke)] {
+                    region.assign_fixed(|| "coefficient", column, 0, || Value::known(signed(k)))?;
+                }
+                for i in 0..LIMBS {
+                    region.assign_fixed(
+                        || "offset",
+                        config.offset[i],
+                        0,
+                        || Value::known(to_field::<F>(&split(&offset)[i])),
+                    )?;
+                    region.assign_fixed(
+                        || "modulus",
+                        config.modulus[i],
+                        0,
+                        || Value::known(modulus.limbs[i]),
+                    )?;
+                }
+                let mut cells = vec![];
+                for (index, operand) in operands.iter().enumerate() {
+                    // the quotient's columns sit between `e` and `c`
+                    let columns = config.columns[if index < 4 { index } else { 5 }];
+                    let limbs: Vec<_> = match operand {
+                        Operand::Element(element) => element
+                            .limbs
+                            .iter()
+                            .zip(columns.iter())
+                            .map(|(limb, column)| {
+                                limb.copy_advice(|| "limb", &mut region, *column, 0)
+                            })
+                            .collect::<Result<_, _>>()?,
+                        Operand::Witness(value) => (0..LIMBS)
+                            .map(|i| {
+                                let limb = value.as_ref().map(|v| to_field::<F>(&split(v)[i]));
+                                region.assign_advice(|| "limb", columns[i], 0, || limb)
+                            })
+                            .collect::<Result<_, _>>()?,
+                        Operand::Zero => columns
+                            .iter()
+                            .map(|column| {
+                                region.assign_advice_from_constant(|| "zero", *column, 0, F::zero())
+                            })
+                            .collect::<Result<_, _>>()?,
+                    };
+                    cells.push(limbs);
+                }
+                let mut fresh = |column: usize, values: Value<Vec<BigUint>>| {
+                    (0..LIMBS)
+                        .map(|i| {
+                            let value = values.as_ref().map(|v| to_field::<F>(&v[i]));
+                            region.assign_advice(|| "limb", config.columns[column][i], 0, || value)
+                        })
+                        .collect::<Result<Vec<_>, _>>()
+                };
+                let q = fresh(4, quotient.as_ref().map(|q| split(q).to_vec()))?;
+                let carries = fresh(6, carries.clone())?;
+                Ok((cells, q, carries))
+            },
+        )?;
+        let (cells, q, carries) = cells;
+        let mut elements = vec![];
+        for (limbs, operand) in cells.into_iter().zip(operands.iter()) {
+            let limbs: [AssignedCell<F, F>; LIMBS] = limbs.try_into().unwrap();
+            if let Operand::Witness(_) = operand {
+                self.range_check_limbs(&mut layouter, &limbs, ELEMENT_BITS)?;
+            }
+            elements.push(Element {
+                limbs,
+                value: operand.value(),
+            });
+        }
+        let q: [AssignedCell<F, F>; LIMBS] = q.try_into().unwrap();
+        self.range_check_limbs(&mut layouter, &q, modulus.q_bits)?;
+        for carry in carries.iter() {
+            self.range_check(&mut layouter, carry, CARRY_SHIFT + 1)?;
+        }
+        Ok(elements.try_into().unwrap())
+    }
+
+    /// The `c` of a relation given without one.
+    pub fn result(
+        &self,
+        layouter: impl Layouter<F>,
+        modulus: &Modulus<F>,
+        relation: Relation<'_, F>,
+    ) -> Result<Element<F>, Error> {
+        let [_, _, _, _, c] = self.constrain(layouter, modulus, relation)?;
+        Ok(c)
+    }
+
+    pub fn mul(
+        &self,
+        layouter: impl Layouter<F>,
+        modulus: &Modulus<F>,
+        a: &Element<F>,
+        b: &Element<F>,
+    ) -> Result<Element<F>, Error> {
+        let relation = Relation {
+            kab: 1,
+            a: Operand::Element(a),
+            b: Operand::Element(b),
+            ..Relation::default()
+        };
+        self.result(layouter, modulus, relation)
+    }
+
+    /// `kd * d + ke * e`, reduced.
+    pub fn linear(
+        &self,
+        layouter: impl Layouter<F>,
+        modulus: &Modulus<F>,
+        (kd, d): (i64, &Element<F>),
+        (ke, e): (i64, &Element<F>),
+    ) -> Result<Element<F>, Error> {
+        let relation = Relation {
+            d: (kd, Operand::Element(d)),
+            e: (ke, Operand::Element(e)),
+            ..Relation::default()
+        };
+        self.result(layouter, modulus, relation)
+    }
+
+    pub fn add(
+        &self,
+        layouter: impl Layouter<F>,
+        modulus: &Modulus<F>,
+        a: &Element<F>,
+        b: &Element<F>,
+    ) -> Result<Element<F>, Error> {
+        self.linear(layouter, modulus, (1, a), (1, b))
+    }
+
+    pub fn sub(
+        &self,
+        layouter: impl Layouter<F>,
+        modulus: &Modulus<F>,
+        a: &Element<F>,
+        b: &Element<F>,
+    ) -> Result<Element<F>, Error> {
+        self.linear(layouter, modulus, (1, a), (-1, b))
+    }
+
+    /// `num / den`, failing to satisfy the circuit when `den` is zero modulo `p`.
+    pub fn div(
+        &self,
+        layouter: impl Layouter<F>,
+        modulus: &Modulus<F>,
+        num: &Element<F>,
+        den: &Element<F>,
+    ) -> Result<Element<F>, Error> {
+        let p = modulus.value.clone();
+        let quotient = num
+            .value
+            .clone()
+            .zip(den.value.clone())
+            .map(|(num, den)| num * inverse(&den, &p) % &p);
+        let relation = Relation {
+            kab: 1,
+            a: Operand::Witness(quotient),
+            b: Operand::Element(den),
+            c: Some(Operand::Element(num)),
+            ..Relation::default()
+        };
+        let [q, ..] = self.constrain(layouter, modulus, relation)?;
+        Ok(q)
+    }
+
+    pub fn assert_equal(
+        &self,
+        layouter: impl Layouter<F>,
+        modulus: &Modulus<F>,
+        a: &Element<F>,
+        b: &Element<F>,
+    ) -> Result<(), Error> {
+        let relation = Relation {
+            d: (1, Operand::Element(a)),
+            e: (-1, Operand::Element(b)),
+            c: Some(Operand::Zero),
+            ..Relation::default()
+        };
+        self.constrain(layouter, modulus, relation)?;
+        Ok(())
+    }
+
+    /// The 256 little-endian bits of the integer `element` holds.
+    pub fn bits(
+        &self,
+        mut layouter: impl Layouter<F>,
+        element: &Element<F>,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let config = &self.config;
+        let [z, bit] = [config.columns[0][0], config.columns[0][1]];
+        let mut bits = vec![];
+        for (limb, width) in element.limbs.iter().zip(widths(ELEMENT_BITS)) {
+            let cells = layouter.assign_region(
+                || "bits",
+                |mut region| {
+                    let mut z_cell = limb.copy_advice(|| "z", &mut region, z, 0)?;
+                    let mut cells = vec![];
+                    for i in 0..width {
+                        config.q_bit.enable(&mut region, i)?;
+                        let value = z_cell
+                            .value()
+                            .map(|z| F::from(z.to_repr().as_ref()[0] & 1 == 1));
+                        cells.push(region.assign_advice(|| "bit", bit, i, || value)?);
+                        let next = z_cell
+                            .value()
+                            .zip(value)
+                            .map(|(z, bit)| (*z - bit) * F::from(2).invert().unwrap());
+                        z_cell = region.assign_advice(|| "z", z, i + 1, || next)?;
+                    }
+                    config.q_bits_end.enable(&mut region, width)?;
+                    Ok(cells)
+                },
+            )?;
+            bits.extend(cells);
+        }
+        Ok(bits)
+    }
+
+    /// `table[b1 + 2 b2]` for boolean `b1` and `b2`.
+    pub fn select(
+        &self,
+        mut layouter: impl Layouter<F>,
+        b1: &AssignedCell<F, F>,
+        b2: &AssignedCell<F, F>,
+        table: [&Element<F>; 4],
+    ) -> Result<Element<F>, Error> {
+        let config = &self.config;
+        let index = b1
+            .value()
+            .zip(b2.value())
+            .map(|(b1, b2)| (*b1 == F::one()) as usize + 2 * (*b2 == F::one()) as usize);
+        let limbs = layouter.assign_region(
+            || "select",
+            |mut region| {
+                let mut cells = vec![];
+                for i in 0..LIMBS {
+                    config.q_select.enable(&mut region, i)?;
+                    b1.copy_advice(|| "b1", &mut region, config.columns[0][0], i)?;
+                    b2.copy_advice(|| "b2", &mut region, config.columns[0][1], i)?;
+                    let entries = [
+                        config.columns[1][0],
+                        config.columns[1][1],
+                        config.columns[1][2],
+                        config.columns[2][0],
+                    ];
+                    for (entry, column) in table.iter().zip(entries) {
+                        entry.limbs[i].copy_advice(|| "entry", &mut region, column, i)?;
+                    }
+                    let value = index.and_then(|index| table[index].limbs[i].value().copied());
+                    cells.push(region.assign_advice(
+                        || "selected",
+                        config.columns[2][1],
+                        i,
+                        || value,
+                    )?);
+                }
+                Ok(cells.try_into().unwrap())
+            },
+        )?;
+        let value = index.and_then(|index| table[index].value.clone());
+        Ok(Element { limbs, value })
+    }
+}
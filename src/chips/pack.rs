@@ -0,0 +1,115 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter},
+    plonk::{ConstraintSystem, Error},
+};
+
+use super::limbs::{pow2, Limb, LimbsChip, LimbsConfig};
+use crate::reference::Endianness;
+
+#[derive(Clone, Debug)]
+pub struct PackConfig {
+    pub limbs: LimbsConfig,
+}
+
+/// Conversions between field elements, byte arrays and 32/64-bit word arrays. Every byte
+/// produced or consumed is range checked, so the results are fully constrained. Packed values
+/// are limited to 31 bytes so they never wrap around the modulus.
+pub struct PackChip<F: FieldExt> {
+    limbs: LimbsChip<F>,
+}
+
+impl<F: FieldExt> PackChip<F> {
+    pub fn construct(config: PackConfig) -> Self {
+        Self {
+            limbs: LimbsChip::construct(config.limbs),
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> PackConfig {
+        PackConfig {
+            limbs: LimbsChip::configure(meta),
+        }
+    }
+
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.limbs.load(layouter)
+    }
+
+    pub fn field_to_bytes(
+        &self,
+        layouter: impl Layouter<F>,
+        value: &AssignedCell<F, F>,
+        n_bytes: usize,
+        endianness: Endianness,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        let bytes = self.limbs.decompose_bytes(layouter, value, n_bytes)?;
+        Ok(endianness.arrange(bytes))
+    }
+
+    pub fn bytes_to_field(
+        &self,
+        layouter: impl Layouter<F>,
+        bytes: &[AssignedCell<F, F>],
+        endianness: Endianness,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(bytes.len() <= 31, "at most 31 bytes fit below the modulus");
+        let bytes = endianness.arrange(bytes.iter().collect());
+        let terms: Vec<_> = bytes
+            .into_iter()
+            .enumerate()
+            .map(|(i, byte)| (Limb::CopyChecked(byte, 8), pow2::<F>(8 * i)))
+            .collect();
+        let (_, value) = self.limbs.running_sum(layouter, &terms)?;
+        Ok(value)
+    }
+
+    /// Packs consecutive groups of `word_bytes` bytes into words.
+    pub fn bytes_to_words(
+        &self,
+        mut layouter: impl Layouter<F>,
+        bytes: &[AssignedCell<F, F>],
+        word_bytes: usize,
+        endianness: Endianness,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        assert!(
+            word_bytes == 4 || word_bytes == 8,
+            "words are 32 or 64 bits"
+        );
+        assert_eq!(bytes.len() % word_bytes, 0);
+        bytes
+            .chunks(word_bytes)
+            .enumerate()
+            .map(|(i, chunk)| {
+                self.bytes_to_field(
+                    layouter.namespace(|| format!("word {}", i)),
+                    chunk,
+                    endianness,
+                )
+            })
+            .collect()
+    }
+
+    pub fn words_to_bytes(
+        &self,
+        mut layouter: impl Layouter<F>,
+        words: &[AssignedCell<F, F>],
+        word_bytes: usize,
+        endianness: Endianness,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        assert!(
+            word_bytes == 4 || word_bytes == 8,
+            "words are 32 or 64 bits"
+        );
+        let mut bytes = Vec::with_capacity(words.len() * word_bytes);
+        for (i, word) in words.iter().enumerate() {
+            bytes.extend(self.field_to_bytes(
+                layouter.namespace(|| format!("word {}", i)),
+                word,
+                word_bytes,
+                endianness,
+            )?);
+        }
+        Ok(bytes)
+    }
+}
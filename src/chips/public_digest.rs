@@ -0,0 +1,47 @@
+//! Hashing the public inputs: instead of exposing `L` values, a circuit hashes the cells
+//! holding them and exposes the one digest, and verifiers recompute it from the values with
+//! [`public_digest`]. A verifier contract then takes one word of instances and one instance
+//! commitment however many values the statement has, for the Poseidon rows in the circuit.
+
+use halo2_gadgets::poseidon::{
+    primitives::{self as poseidon, ConstantLength, Spec},
+    Hash, Pow5Chip, Pow5Config,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter},
+    plonk::{Column, Error, Instance},
+};
+
+/// Off-circuit digest of `values`, as [`expose_digest`] computes it.
+pub fn public_digest<F, S, const WIDTH: usize, const RATE: usize, const L: usize>(
+    values: [F; L],
+) -> F
+where
+    F: FieldExt,
+    S: Spec<F, WIDTH, RATE>,
+{
+    poseidon::Hash::<_, S, ConstantLength<L>, WIDTH, RATE>::init().hash(values)
+}
+
+/// Hashes `values` with `ConstantLength<L>` Poseidon and constrains the digest to `row` of
+/// `instance`, which needs equality enabled. Returns the digest cell.
+pub fn expose_digest<F, S, const WIDTH: usize, const RATE: usize, const L: usize>(
+    mut layouter: impl Layouter<F>,
+    poseidon: Pow5Config<F, WIDTH, RATE>,
+    values: [AssignedCell<F, F>; L],
+    instance: Column<Instance>,
+    row: usize,
+) -> Result<AssignedCell<F, F>, Error>
+where
+    F: FieldExt,
+    S: Spec<F, WIDTH, RATE>,
+{
+    let hasher = Hash::<_, _, S, ConstantLength<L>, WIDTH, RATE>::init(
+        Pow5Chip::construct(poseidon),
+        layouter.namespace(|| "init"),
+    )?;
+    let digest = hasher.hash(layouter.namespace(|| "digest"), values)?;
+    layouter.constrain_instance(digest.cell(), instance, row)?;
+    Ok(digest)
+}
@@ -0,0 +1,157 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed, Selector},
+    poly::Rotation,
+};
+
+use super::{
+    limbs::pow2,
+    tables::{Table, Tables},
+};
+
+#[derive(Clone, Debug)]
+pub struct RangeCheckConfig {
+    pub z: Column<Advice>,
+    pub limb: Column<Advice>,
+    pub shift: Column<Fixed>,
+    pub q_lookup: Selector,
+    pub q_top: Selector,
+}
+
+/// `bits`-bit range checks against a fixed table of every `LIMB_BITS`-bit value. A value is
+/// split into little-endian limbs by the running sum `z_0 = value`,
+/// `z_i = limb_i + 2^LIMB_BITS * z_{i+1}`, ending in `z_n = 0`, and each limb is looked up in
+/// the table. When `bits` isn't a multiple of `LIMB_BITS` the top limb is also looked up
+/// shifted left, so its high bits must be zero. The table is `Table::Range(LIMB_BITS)` of the
+/// circuit's [`Tables`], so circuits need `2^k` above its `2^LIMB_BITS` rows.
+pub struct RangeCheckChip<F: FieldExt, const LIMB_BITS: usize> {
+    config: RangeCheckConfig,
+    _marker: PhantomData<F>,
+}
+
+/// The `n` little-endian `limb_bits`-bit limbs of `value`, dropping any higher bits.
+pub fn limbs<F: FieldExt>(value: &F, limb_bits: usize, n: usize) -> Vec<u64> {
+    let repr = value.to_repr();
+    let bit = |i: usize| {
+        let bytes = repr.as_ref();
+        i / 8 < bytes.len() && (bytes[i / 8] >> (i % 8)) & 1 == 1
+    };
+    (0..n)
+        .map(|limb| {
+            (0..limb_bits)
+                .filter(|j| bit(limb * limb_bits + j))
+                .fold(0, |acc, j| acc | 1 << j)
+        })
+        .collect()
+}
+
+impl<F: FieldExt, const LIMB_BITS: usize> RangeCheckChip<F, LIMB_BITS> {
+    pub fn construct(config: RangeCheckConfig) -> Self {
+        assert!(
+            (1..=24).contains(&LIMB_BITS),
+            "limbs of 1 to 24 bits, the table has 2^LIMB_BITS rows"
+        );
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>, tables: &mut Tables) -> RangeCheckConfig {
+        let z = meta.advice_column();
+        let limb = meta.advice_column();
+        let shift = meta.fixed_column();
+        let q_lookup = meta.complex_selector();
+        let q_top = meta.selector();
+        let table = tables.column(meta, Table::Range(LIMB_BITS));
+        meta.enable_equality(z);
+        meta.enable_equality(limb);
+
+        meta.create_gate("running sum", |meta| {
+            let q_lookup = meta.query_selector(q_lookup);
+            let z_cur = meta.query_advice(z, Rotation::cur());
+            let z_next = meta.query_advice(z, Rotation::next());
+            let limb = meta.query_advice(limb, Rotation::cur());
+            vec![q_lookup * (z_cur - limb - z_next * pow2::<F>(LIMB_BITS))]
+        });
+        meta.create_gate("running sum ends in zero", |meta| {
+            let q_top = meta.query_selector(q_top);
+            vec![q_top * meta.query_advice(z, Rotation::cur())]
+        });
+
+        meta.lookup("limb", |meta| {
+            let q_lookup = meta.query_selector(q_lookup);
+            let limb = meta.query_advice(limb, Rotation::cur());
+            vec![(q_lookup * limb, table)]
+        });
+        // `shift` is 2^(LIMB_BITS - r) on a top limb of r bits and 0, which is in the table,
+        // everywhere else
+        meta.lookup("short limb", |meta| {
+            let q_lookup = meta.query_selector(q_lookup);
+            let shift = meta.query_fixed(shift, Rotation::cur());
+            let limb = meta.query_advice(limb, Rotation::cur());
+            vec![(q_lookup * shift * limb, table)]
+        });
+
+        RangeCheckConfig {
+            z,
+            limb,
+            shift,
+            q_lookup,
+            q_top,
+        }
+    }
+
+    /// Constrains `value` to be below `2^bits`, returning its little-endian limbs, the top
+    /// one `bits % LIMB_BITS` bits wide if that isn't zero.
+    pub fn range_check(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &AssignedCell<F, F>,
+        bits: usize,
+    ) -> Result<Vec<AssignedCell<F, F>>, Error> {
+        assert!(
+            0 < bits && bits < F::NUM_BITS as usize,
+            "range checks of 1 to {} bits",
+            F::NUM_BITS - 1
+        );
+        let config = &self.config;
+        let n = (bits + LIMB_BITS - 1) / LIMB_BITS;
+        let top_bits = bits - (n - 1) * LIMB_BITS;
+        let limbs = value.value().map(|value| limbs(value, LIMB_BITS, n));
+        let inv = pow2::<F>(LIMB_BITS).invert().unwrap();
+        layouter.assign_region(
+            || format!("range check {} bits", bits),
+            |mut region| {
+                let mut z = value.copy_advice(|| "z", &mut region, config.z, 0)?;
+                let mut cells = Vec::with_capacity(n);
+                for i in 0..n {
+                    config.q_lookup.enable(&mut region, i)?;
+                    if i == n - 1 && top_bits < LIMB_BITS {
+                        region.assign_fixed(
+                            || "shift",
+                            config.shift,
+                            i,
+                            || Value::known(pow2::<F>(LIMB_BITS - top_bits)),
+                        )?;
+                    }
+                    let limb = limbs.as_ref().map(|limbs| F::from(limbs[i]));
+                    let cell = region.assign_advice(|| "limb", config.limb, i, || limb)?;
+                    // z_{i+1} = (z_i - limb_i) / 2^LIMB_BITS
+                    let next = z
+                        .value()
+                        .copied()
+                        .zip(limb)
+                        .map(|(z, limb)| (z - limb) * inv);
+                    z = region.assign_advice(|| "z", config.z, i + 1, || next)?;
+                    cells.push(cell);
+                }
+                config.q_top.enable(&mut region, n)?;
+                Ok(cells)
+            },
+        )
+    }
+}
@@ -0,0 +1,116 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{
+        Advice, Challenge, Column, ConstraintSystem, Error, FirstPhase, SecondPhase, Selector,
+    },
+    poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+pub struct RlcConfig {
+    /// First-phase values, committed before the challenge is drawn.
+    pub value: Column<Advice>,
+    /// Second-phase running sums, which depend on the challenge.
+    pub acc: Column<Advice>,
+    pub challenge: Challenge,
+    pub q_first: Selector,
+    pub q_next: Selector,
+}
+
+/// Random linear combinations `v_0 r^(n-1) + ... + v_(n-1)` of a list under a verifier
+/// challenge `r`. The values sit in a first-phase column, so the prover commits to them
+/// before `r` exists; the running sum is a second-phase column assigned once `r` is known.
+/// Two lists with equal combinations are equal except with probability about `n / |F|`, which
+/// replaces one copy constraint per element with one per list.
+pub struct RlcChip<F: FieldExt> {
+    config: RlcConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> RlcChip<F> {
+    pub fn construct(config: RlcConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> RlcConfig {
+        let value = meta.advice_column_in(FirstPhase);
+        let acc = meta.advice_column_in(SecondPhase);
+        // drawn after every first-phase column is committed
+        let challenge = meta.challenge_usable_after(FirstPhase);
+        let q_first = meta.selector();
+        let q_next = meta.selector();
+        meta.enable_equality(value);
+        meta.enable_equality(acc);
+
+        meta.create_gate("rlc first", |meta| {
+            let q_first = meta.query_selector(q_first);
+            let value = meta.query_advice(value, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![q_first * (acc - value)]
+        });
+        meta.create_gate("rlc next", |meta| {
+            let q_next = meta.query_selector(q_next);
+            let value = meta.query_advice(value, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let prev = meta.query_advice(acc, Rotation::prev());
+            let r = meta.query_challenge(challenge);
+            vec![q_next * (acc - (prev * r + value))]
+        });
+
+        RlcConfig {
+            value,
+            acc,
+            challenge,
+            q_first,
+            q_next,
+        }
+    }
+
+    /// The challenge, unknown while the first phase is synthesized.
+    pub fn challenge(&self, layouter: &impl Layouter<F>) -> Value<F> {
+        layouter.get_challenge(self.config.challenge)
+    }
+
+    /// Combines `cells`, copied into the value column, returning the combination's cell.
+    pub fn combine(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cells: &[AssignedCell<F, F>],
+    ) -> Result<AssignedCell<F, F>, Error> {
+        assert!(!cells.is_empty(), "combination of no values");
+        let config = &self.config;
+        let r = self.challenge(&layouter);
+        layouter.assign_region(
+            || "rlc",
+            |mut region| {
+                let mut acc: Option<AssignedCell<F, F>> = None;
+                for (offset, cell) in cells.iter().enumerate() {
+                    let value = cell.copy_advice(|| "value", &mut region, config.value, offset)?;
+                    let sum = match &acc {
+                        None => {
+                            config.q_first.enable(&mut region, offset)?;
+                            value.value().copied()
+                        }
+                        Some(prev) => {
+                            config.q_next.enable(&mut region, offset)?;
+                            prev.value().copied() * r + value.value().copied()
+                        }
+                    };
+                    acc = Some(region.assign_advice(|| "acc", config.acc, offset, || sum)?);
+                }
+                Ok(acc.expect("at least one value"))
+            },
+        )
+    }
+}
+
+/// `values` combined under `r` the way `RlcChip::combine` does.
+pub fn rlc<F: FieldExt>(values: &[F], r: F) -> F {
+    values.iter().fold(F::zero(), |acc, v| acc * r + *v)
+}
@@ -0,0 +1,151 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter},
+    plonk::{ConstraintSystem, Error},
+};
+
+use super::limbs::{lower_u64, pow2, Limb, LimbsChip, LimbsConfig};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum WordOp {
+    RotateRight(usize),
+    RotateLeft(usize),
+    ShiftRight(usize),
+    ShiftLeft(usize),
+}
+
+impl WordOp {
+    pub fn apply(self, word: u64, bits: usize) -> u64 {
+        let mask = if bits == 64 {
+            u64::MAX
+        } else {
+            (1 << bits) - 1
+        };
+        let word = word & mask;
+        match self {
+            WordOp::RotateRight(n) if n % bits == 0 => word,
+            WordOp::RotateRight(n) => ((word >> n) | (word << (bits - n))) & mask,
+            WordOp::RotateLeft(n) => WordOp::RotateRight((bits - n) % bits).apply(word, bits),
+            WordOp::ShiftRight(n) => word >> n,
+            WordOp::ShiftLeft(n) => (word << n) & mask,
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct RotateConfig {
+    pub limbs: LimbsConfig,
+}
+
+/// Fixed rotations and shifts of 32/64-bit words. A word is cut into range-checked bytes, the
+/// byte containing the cut point is re-sliced into two range-checked parts, and the result is
+/// a constant-weighted sum of those limbs.
+pub struct RotateChip<F: FieldExt> {
+    limbs: LimbsChip<F>,
+}
+
+struct Split<F: FieldExt> {
+    bytes: Vec<AssignedCell<F, F>>,
+    lo: AssignedCell<F, F>,
+    hi: AssignedCell<F, F>,
+    // the cut is `8 * q + r` bits from the bottom, inside byte `q`
+    q: usize,
+}
+
+impl<F: FieldExt> RotateChip<F> {
+    pub fn construct(config: RotateConfig) -> Self {
+        Self {
+            limbs: LimbsChip::construct(config.limbs),
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> RotateConfig {
+        RotateConfig {
+            limbs: LimbsChip::configure(meta),
+        }
+    }
+
+    pub fn load(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        self.limbs.load(layouter)
+    }
+
+    /// Decomposes `word` into bytes and splits byte `at / 8` at bit `at % 8`.
+    fn split(
+        &self,
+        mut layouter: impl Layouter<F>,
+        word: &AssignedCell<F, F>,
+        bits: usize,
+        at: usize,
+    ) -> Result<Split<F>, Error> {
+        assert!(bits == 32 || bits == 64, "words are 32 or 64 bits");
+        assert!(at < bits);
+        let (q, r) = (at / 8, at % 8);
+        let value = word.value().map(lower_u64);
+        let bytes = self
+            .limbs
+            .decompose_bytes(layouter.namespace(|| "word"), word, bits / 8)?;
+
+        let cut = value.map(|w| (w >> (8 * q)) & 0xff);
+        let lo = cut.map(|b| F::from(b & ((1 << r) - 1)));
+        let hi = cut.map(|b| F::from(b >> r));
+        let (parts, sum) = self.limbs.running_sum(
+            layouter.namespace(|| "cut byte"),
+            &[
+                (Limb::Witness(lo, r), F::one()),
+                (Limb::Witness(hi, 8 - r), pow2::<F>(r)),
+            ],
+        )?;
+        layouter.assign_region(
+            || "cut byte",
+            |mut region| region.constrain_equal(sum.cell(), bytes[q].cell()),
+        )?;
+
+        Ok(Split {
+            bytes,
+            lo: parts[0].clone(),
+            hi: parts[1].clone(),
+            q,
+        })
+    }
+
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        word: &AssignedCell<F, F>,
+        bits: usize,
+        op: WordOp,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let (at, high_weight, low_weight): (usize, Option<usize>, Option<usize>) = match op {
+            // rotr: high part moves to the bottom, low part moves to the top
+            WordOp::RotateRight(n) => (n % bits, Some(0), Some(bits - n % bits)),
+            WordOp::RotateLeft(n) => ((bits - n % bits) % bits, Some(0), Some(n % bits)),
+            WordOp::ShiftRight(n) => (n, Some(0), None),
+            WordOp::ShiftLeft(n) => (bits - n, None, Some(n)),
+        };
+        // `bits - n` is out of range for a zero shift left
+        if at == bits {
+            return self.assign(layouter, word, bits, WordOp::RotateRight(0));
+        }
+        let split = self.split(layouter.namespace(|| "split"), word, bits, at)?;
+
+        // word = low + high * 2^at, with low = bytes[..q] + lo * 2^(8q)
+        // and high = hi + bytes[q + 1..] * 2^(8i - at)
+        let mut terms = vec![];
+        if let Some(shift) = low_weight {
+            for (i, byte) in split.bytes[..split.q].iter().enumerate() {
+                terms.push((Limb::Copy(byte), pow2::<F>(8 * i + shift)));
+            }
+            terms.push((Limb::Copy(&split.lo), pow2::<F>(8 * split.q + shift)));
+        }
+        if let Some(shift) = high_weight {
+            terms.push((Limb::Copy(&split.hi), pow2::<F>(shift)));
+            for (i, byte) in split.bytes.iter().enumerate().skip(split.q + 1) {
+                terms.push((Limb::Copy(byte), pow2::<F>(8 * i - at + shift)));
+            }
+        }
+        let (_, out) = self
+            .limbs
+            .running_sum(layouter.namespace(|| format!("{:?}", op)), &terms)?;
+        Ok(out)
+    }
+}
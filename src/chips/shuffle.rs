@@ -0,0 +1,110 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{
+        Advice, Challenge, Column, ConstraintSystem, Error, Expression, FirstPhase, SecondPhase,
+        Selector,
+    },
+    poly::Rotation,
+};
+
+#[derive(Clone, Debug)]
+pub struct ShuffleConfig {
+    pub original: Column<Advice>,
+    pub shuffled: Column<Advice>,
+    /// Second-phase grand product.
+    pub z: Column<Advice>,
+    pub gamma: Challenge,
+    pub q_shuffle: Selector,
+    pub q_ends: Selector,
+}
+
+/// Proves one list is a permutation of another with a grand product: for a challenge `γ`
+/// drawn after both lists are committed, `z_0 = 1`, `z_(i+1) (shuffled_i + γ) = z_i
+/// (original_i + γ)` and `z_n = 1`, so `Π (original_i + γ) = Π (shuffled_i + γ)`, which for a
+/// random `γ` means the lists are equal as multisets except with probability about `n / |F|`.
+/// This fork of halo2 predates its built-in shuffle argument.
+pub struct ShuffleChip<F: FieldExt> {
+    config: ShuffleConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> ShuffleChip<F> {
+    pub fn construct(config: ShuffleConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> ShuffleConfig {
+        let original = meta.advice_column_in(FirstPhase);
+        let shuffled = meta.advice_column_in(FirstPhase);
+        let z = meta.advice_column_in(SecondPhase);
+        let gamma = meta.challenge_usable_after(FirstPhase);
+        let q_shuffle = meta.selector();
+        let q_ends = meta.selector();
+        meta.enable_equality(original);
+        meta.enable_equality(shuffled);
+
+        meta.create_gate("shuffle product", |meta| {
+            let q_shuffle = meta.query_selector(q_shuffle);
+            let original = meta.query_advice(original, Rotation::cur());
+            let shuffled = meta.query_advice(shuffled, Rotation::cur());
+            let z_cur = meta.query_advice(z, Rotation::cur());
+            let z_next = meta.query_advice(z, Rotation::next());
+            let gamma = meta.query_challenge(gamma);
+            vec![q_shuffle * (z_next * (shuffled + gamma.clone()) - z_cur * (original + gamma))]
+        });
+        meta.create_gate("shuffle product ends", |meta| {
+            let q_ends = meta.query_selector(q_ends);
+            let z = meta.query_advice(z, Rotation::cur());
+            vec![q_ends * (z - Expression::Constant(F::one()))]
+        });
+
+        ShuffleConfig {
+            original,
+            shuffled,
+            z,
+            gamma,
+            q_shuffle,
+            q_ends,
+        }
+    }
+
+    /// Constrains `shuffled` to be a permutation of `original`, copying both in.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        original: &[AssignedCell<F, F>],
+        shuffled: &[AssignedCell<F, F>],
+    ) -> Result<(), Error> {
+        assert_eq!(original.len(), shuffled.len(), "lists of different lengths");
+        let config = &self.config;
+        let gamma = layouter.get_challenge(config.gamma);
+        layouter.assign_region(
+            || "shuffle",
+            |mut region| {
+                let one = Value::known(F::one());
+                let mut z = one;
+                config.q_ends.enable(&mut region, 0)?;
+                region.assign_advice(|| "z", config.z, 0, || z)?;
+                for (row, (a, b)) in original.iter().zip(shuffled.iter()).enumerate() {
+                    config.q_shuffle.enable(&mut region, row)?;
+                    let a = a.copy_advice(|| "original", &mut region, config.original, row)?;
+                    let b = b.copy_advice(|| "shuffled", &mut region, config.shuffled, row)?;
+                    // b + γ is zero with negligible probability; the gate then fails
+                    let ratio = a.value().zip(b.value()).zip(gamma).map(|((a, b), gamma)| {
+                        (*a + gamma) * (*b + gamma).invert().unwrap_or_else(F::zero)
+                    });
+                    z = z * ratio;
+                    region.assign_advice(|| "z", config.z, row + 1, || z)?;
+                }
+                config.q_ends.enable(&mut region, original.len())?;
+                Ok(())
+            },
+        )
+    }
+}
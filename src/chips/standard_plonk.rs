@@ -0,0 +1,192 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Region, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Fixed},
+    poly::Rotation,
+};
+
+/// Source of this module, hashed into the provenance of keys for circuits built on it.
+pub const SOURCE: &str = include_str!("standard_plonk.rs");
+
+#[derive(Clone, Debug)]
+pub struct StandardPlonkConfig {
+    pub a: Column<Advice>,
+    pub b: Column<Advice>,
+    pub c: Column<Advice>,
+    pub q_l: Column<Fixed>,
+    pub q_r: Column<Fixed>,
+    pub q_m: Column<Fixed>,
+    pub q_o: Column<Fixed>,
+    pub q_c: Column<Fixed>,
+}
+
+/// The vanilla PLONK gate `q_l a + q_r b + q_m a b + q_o c + q_c = 0`, one operation per row
+/// with its coefficients in fixed columns. Operands are copied in, so results can be reused
+/// anywhere in the circuit.
+pub struct StandardPlonkChip<F: FieldExt> {
+    config: StandardPlonkConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> StandardPlonkChip<F> {
+    pub fn construct(config: StandardPlonkConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> StandardPlonkConfig {
+        let [a, b, c] = [(); 3].map(|_| meta.advice_column());
+        let [q_l, q_r, q_m, q_o, q_c] = [(); 5].map(|_| meta.fixed_column());
+        for column in [a, b, c] {
+            meta.enable_equality(column);
+        }
+
+        meta.create_gate("standard plonk", |meta| {
+            let [a, b, c] = [a, b, c].map(|column| meta.query_advice(column, Rotation::cur()));
+            let [q_l, q_r, q_m, q_o, q_c] =
+                [q_l, q_r, q_m, q_o, q_c].map(|column| meta.query_fixed(column, Rotation::cur()));
+            vec![q_l * a.clone() + q_r * b.clone() + q_m * a * b + q_o * c + q_c]
+        });
+
+        StandardPlonkConfig {
+            a,
+            b,
+            c,
+            q_l,
+            q_r,
+            q_m,
+            q_o,
+            q_c,
+        }
+    }
+
+    /// Sets the coefficients of row 0 of `region`; the ones not given stay zero.
+    fn coefficients(
+        &self,
+        region: &mut Region<'_, F>,
+        coefficients: &[(Column<Fixed>, F)],
+    ) -> Result<(), Error> {
+        for (column, value) in coefficients.iter() {
+            region.assign_fixed(|| "coefficient", *column, 0, || Value::known(*value))?;
+        }
+        Ok(())
+    }
+
+    /// A free witness, constrained by nothing but what it is later used in.
+    pub fn assign(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "witness",
+            |mut region| region.assign_advice(|| "witness", self.config.a, 0, || value),
+        )
+    }
+
+    /// A cell fixed to `constant` at keygen: `a - constant = 0`.
+    pub fn assign_constant(
+        &self,
+        mut layouter: impl Layouter<F>,
+        constant: F,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = &self.config;
+        layouter.assign_region(
+            || "constant",
+            |mut region| {
+                self.coefficients(
+                    &mut region,
+                    &[(config.q_l, F::one()), (config.q_c, -constant)],
+                )?;
+                region.assign_advice(|| "constant", config.a, 0, || Value::known(constant))
+            },
+        )
+    }
+
+    fn binary(
+        &self,
+        mut layouter: impl Layouter<F>,
+        lhs: &AssignedCell<F, F>,
+        rhs: &AssignedCell<F, F>,
+        coefficients: [F; 3],
+        value: Value<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let config = &self.config;
+        let [q_l, q_r, q_m] = coefficients;
+        layouter.assign_region(
+            || "binary",
+            |mut region| {
+                self.coefficients(
+                    &mut region,
+                    &[
+                        (config.q_l, q_l),
+                        (config.q_r, q_r),
+                        (config.q_m, q_m),
+                        (config.q_o, -F::one()),
+                    ],
+                )?;
+                lhs.copy_advice(|| "lhs", &mut region, config.a, 0)?;
+                rhs.copy_advice(|| "rhs", &mut region, config.b, 0)?;
+                region.assign_advice(|| "out", config.c, 0, || value)
+            },
+        )
+    }
+
+    pub fn add(
+        &self,
+        layouter: impl Layouter<F>,
+        lhs: &AssignedCell<F, F>,
+        rhs: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let value = lhs.value().copied() + rhs.value();
+        self.binary(layouter, lhs, rhs, [F::one(), F::one(), F::zero()], value)
+    }
+
+    pub fn sub(
+        &self,
+        layouter: impl Layouter<F>,
+        lhs: &AssignedCell<F, F>,
+        rhs: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let value = lhs.value().copied() - rhs.value();
+        self.binary(layouter, lhs, rhs, [F::one(), -F::one(), F::zero()], value)
+    }
+
+    pub fn mul(
+        &self,
+        layouter: impl Layouter<F>,
+        lhs: &AssignedCell<F, F>,
+        rhs: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        let value = lhs.value().copied() * rhs.value();
+        self.binary(layouter, lhs, rhs, [F::zero(), F::zero(), F::one()], value)
+    }
+
+    /// A new cell holding the value of `cell`, tied to it by the permutation argument.
+    pub fn copy(
+        &self,
+        mut layouter: impl Layouter<F>,
+        cell: &AssignedCell<F, F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "copy",
+            |mut region| cell.copy_advice(|| "copy", &mut region, self.config.a, 0),
+        )
+    }
+
+    pub fn constrain_equal(
+        &self,
+        mut layouter: impl Layouter<F>,
+        lhs: &AssignedCell<F, F>,
+        rhs: &AssignedCell<F, F>,
+    ) -> Result<(), Error> {
+        layouter.assign_region(
+            || "equal",
+            |mut region| region.constrain_equal(lhs.cell(), rhs.cell()),
+        )
+    }
+}
@@ -0,0 +1,164 @@
+//! Fixed lookup tables shared between chips. A circuit makes one [`Tables`] in `configure` and
+//! hands it to each chip, which asks for the tables its lookups need; a table asked for twice
+//! is allocated once, so two chips range checking bytes look up the same column. The circuit
+//! then loads them all with one [`Tables::load`] in `synthesize`.
+//!
+//! Every table holds 0 in each of its columns on some row, the input of a lookup whose
+//! selector is off.
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, Value},
+    plonk::{ConstraintSystem, Error, TableColumn},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Table {
+    /// `0..2^bits`, for 1 to 24 bits.
+    Range(usize),
+    /// `(lhs, rhs, lhs ^ rhs)` over byte pairs.
+    Xor8,
+    /// `(lhs, rhs, lhs & rhs)` over byte pairs.
+    And8,
+    /// `(lhs, rhs, lhs | rhs)` over byte pairs.
+    Or8,
+    /// `(256 s + x, x >> s, x mod 2^s)` over shifts `s` of 0 to 7 and bytes `x`: a byte split
+    /// at bit `s`, which with `s = 0` is a byte range check.
+    Shr8,
+    /// The 7-bit ASCII codes, `0..128`.
+    Ascii,
+}
+
+impl Table {
+    /// Bytes, the same table as `Range(8)`.
+    pub const BYTE: Table = Table::Range(8);
+
+    pub fn columns(self) -> usize {
+        match self {
+            Table::Range(_) | Table::Ascii => 1,
+            Table::Xor8 | Table::And8 | Table::Or8 | Table::Shr8 => 3,
+        }
+    }
+
+    pub fn rows(self) -> usize {
+        match self {
+            Table::Range(bits) => 1 << bits,
+            Table::Xor8 | Table::And8 | Table::Or8 => 1 << 16,
+            Table::Shr8 => 8 << 8,
+            Table::Ascii => 128,
+        }
+    }
+
+    fn row(self, offset: usize) -> [u64; 3] {
+        let (lhs, rhs) = ((offset >> 8) as u64, (offset & 0xff) as u64);
+        match self {
+            Table::Range(_) | Table::Ascii => [offset as u64, 0, 0],
+            Table::Xor8 => [lhs, rhs, lhs ^ rhs],
+            Table::And8 => [lhs, rhs, lhs & rhs],
+            Table::Or8 => [lhs, rhs, lhs | rhs],
+            Table::Shr8 => [offset as u64, rhs >> lhs, rhs & ((1 << lhs) - 1)],
+        }
+    }
+
+    fn name(self) -> String {
+        match self {
+            Table::Range(bits) => format!("{}-bit range table", bits),
+            Table::Xor8 => "xor8 table".to_string(),
+            Table::And8 => "and8 table".to_string(),
+            Table::Or8 => "or8 table".to_string(),
+            Table::Shr8 => "shr8 table".to_string(),
+            Table::Ascii => "ascii table".to_string(),
+        }
+    }
+}
+
+/// The tables a circuit's chips asked for, each with its columns.
+#[derive(Clone, Debug, Default)]
+pub struct Tables {
+    tables: Vec<(Table, Vec<TableColumn>)>,
+}
+
+impl Tables {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The columns of `table`, allocated the first time any chip asks for it.
+    pub fn get<F: FieldExt>(
+        &mut self,
+        meta: &mut ConstraintSystem<F>,
+        table: Table,
+    ) -> Vec<TableColumn> {
+        if let Table::Range(bits) = table {
+            assert!((1..=24).contains(&bits), "range tables of 1 to 24 bits");
+        }
+        if let Some((_, columns)) = self.tables.iter().find(|(t, _)| *t == table) {
+            return columns.clone();
+        }
+        let columns: Vec<_> = (0..table.columns())
+            .map(|_| meta.lookup_table_column())
+            .collect();
+        self.tables.push((table, columns.clone()));
+        columns
+    }
+
+    /// The column of a one-column table: a range or ASCII.
+    pub fn column<F: FieldExt>(
+        &mut self,
+        meta: &mut ConstraintSystem<F>,
+        table: Table,
+    ) -> TableColumn {
+        assert_eq!(table.columns(), 1, "{:?} is not a one-column table", table);
+        self.get(meta, table)[0]
+    }
+
+    /// The three columns of a byte operation table, or of `Shr8`.
+    pub fn binary<F: FieldExt>(
+        &mut self,
+        meta: &mut ConstraintSystem<F>,
+        table: Table,
+    ) -> [TableColumn; 3] {
+        assert_eq!(
+            table.columns(),
+            3,
+            "{:?} is not a byte operation table",
+            table
+        );
+        let columns = self.get(meta, table);
+        [columns[0], columns[1], columns[2]]
+    }
+
+    /// The tables allocated, in the order they were first asked for.
+    pub fn tables(&self) -> impl Iterator<Item = Table> + '_ {
+        self.tables.iter().map(|(table, _)| *table)
+    }
+
+    /// Rows the longest table takes, which `2^k` has to fit next to the blinding rows.
+    pub fn rows(&self) -> usize {
+        self.tables().map(Table::rows).max().unwrap_or(0)
+    }
+
+    /// Assigns every table, once each.
+    pub fn load<F: FieldExt>(&self, layouter: &mut impl Layouter<F>) -> Result<(), Error> {
+        for (table, columns) in self.tables.iter() {
+            layouter.assign_table(
+                || table.name(),
+                |mut t| {
+                    for offset in 0..table.rows() {
+                        let row = table.row(offset);
+                        for (column, value) in columns.iter().zip(row.iter()) {
+                            t.assign_cell(
+                                || table.name(),
+                                *column,
+                                offset,
+                                || Value::known(F::from(*value)),
+                            )?;
+                        }
+                    }
+                    Ok(())
+                },
+            )?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+//! Public inputs that must fit in a machine word of an external system, e.g. a `u64` capacity
+//! of a CKB cell or a `uint128` on the EVM. The circuit range checks the cell it copies into
+//! the instance column, so no proof exists for a larger value, and the matching
+//! [`Encoding`] lets verifiers reject such instances before verifying at all.
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter},
+    plonk::{Column, ConstraintSystem, Error, Instance},
+};
+
+use super::{
+    range_check::{RangeCheckChip, RangeCheckConfig},
+    tables::Tables,
+};
+use crate::schema::Encoding;
+
+/// Bits per range-checked limb, for a 256-row table.
+const LIMB_BITS: usize = 8;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Width {
+    U64,
+    U128,
+}
+
+impl Width {
+    pub fn bits(self) -> u32 {
+        match self {
+            Width::U64 => 64,
+            Width::U128 => 128,
+        }
+    }
+
+    /// The instance-layout encoding of a value exposed at this width.
+    pub fn encoding(self) -> Encoding {
+        Encoding::Integer { bits: self.bits() }
+    }
+}
+
+/// Exposes cells as public inputs of a fixed width.
+pub struct UintChip<F: FieldExt> {
+    range: RangeCheckChip<F, LIMB_BITS>,
+}
+
+impl<F: FieldExt> UintChip<F> {
+    pub fn construct(config: RangeCheckConfig) -> Self {
+        Self {
+            range: RangeCheckChip::construct(config),
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>, tables: &mut Tables) -> RangeCheckConfig {
+        RangeCheckChip::<F, LIMB_BITS>::configure(meta, tables)
+    }
+
+    /// Constrains `value` to fit in `width`.
+    pub fn check(
+        &self,
+        layouter: impl Layouter<F>,
+        value: &AssignedCell<F, F>,
+        width: Width,
+    ) -> Result<(), Error> {
+        self.range
+            .range_check(layouter, value, width.bits() as usize)
+            .map(|_| ())
+    }
+
+    /// Constrains `value` to fit in `width` and to equal row `row` of `instance`.
+    pub fn expose(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: &AssignedCell<F, F>,
+        width: Width,
+        instance: Column<Instance>,
+        row: usize,
+    ) -> Result<(), Error> {
+        self.check(layouter.namespace(|| "range"), value, width)?;
+        layouter.constrain_instance(value.cell(), instance, row)
+    }
+}
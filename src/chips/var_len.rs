@@ -0,0 +1,172 @@
+use std::{convert::TryInto, marker::PhantomData};
+
+use halo2_gadgets::poseidon::{
+    primitives::{self as poseidon, ConstantLength, Spec},
+    Hash, Pow5Chip, Pow5Config,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Value},
+    plonk::{Advice, Column, ConstraintSystem, Error, Expression, Selector},
+    poly::Rotation,
+};
+
+/// `message` followed by a 1 and zeros up to `N` words. The marker keeps messages that only
+/// differ by trailing zeros apart.
+pub fn pad<F: FieldExt, const N: usize>(message: &[F]) -> [F; N] {
+    assert!(message.len() < N, "a message of at most {} words", N - 1);
+    let mut words = [F::zero(); N];
+    words[..message.len()].copy_from_slice(message);
+    words[message.len()] = F::one();
+    words
+}
+
+/// Off-circuit hash of a message of at most `N - 1` words, as `VarLenChip::hash` computes it.
+pub fn var_len_hash<F, S, const WIDTH: usize, const RATE: usize, const N: usize>(message: &[F]) -> F
+where
+    F: FieldExt,
+    S: Spec<F, WIDTH, RATE>,
+{
+    poseidon::Hash::<_, S, ConstantLength<N>, WIDTH, RATE>::init().hash(pad(message))
+}
+
+#[derive(Clone, Debug)]
+pub struct VarLenConfig {
+    pub message: Column<Advice>,
+    pub active: Column<Advice>,
+    pub word: Column<Advice>,
+    pub count: Column<Advice>,
+    pub q_first: Selector,
+    pub q_next: Selector,
+    pub q_last: Selector,
+}
+
+/// Pads a message whose length is only known at proving time to `N` words, one row per word:
+/// `active` is 1 on the message and 0 after it, `word` is the message word while active, 1
+/// where `active` drops and 0 after that, and `count` sums `active` up to the length. The last
+/// row must be inactive, so the message has at most `N - 1` words and always gets its marker.
+pub struct VarLenChip<F: FieldExt, const N: usize> {
+    config: VarLenConfig,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N: usize> VarLenChip<F, N> {
+    pub fn construct(config: VarLenConfig) -> Self {
+        Self {
+            config,
+            _marker: PhantomData,
+        }
+    }
+
+    pub fn configure(meta: &mut ConstraintSystem<F>) -> VarLenConfig {
+        assert!(N >= 2, "room for at least the padding marker and one word");
+        let message = meta.advice_column();
+        let active = meta.advice_column();
+        let word = meta.advice_column();
+        let count = meta.advice_column();
+        let q_first = meta.selector();
+        let q_next = meta.selector();
+        let q_last = meta.selector();
+        meta.enable_equality(word);
+        meta.enable_equality(count);
+
+        meta.create_gate("var_len padding", |meta| {
+            let q_first = meta.query_selector(q_first);
+            let q_next = meta.query_selector(q_next);
+            let q_last = meta.query_selector(q_last);
+            let message = meta.query_advice(message, Rotation::cur());
+            let active = meta.query_advice(active, Rotation::cur());
+            let word = meta.query_advice(word, Rotation::cur());
+            let count = meta.query_advice(count, Rotation::cur());
+            let active_prev = meta.query_advice(active, Rotation::prev());
+            let count_prev = meta.query_advice(count, Rotation::prev());
+            let one = Expression::Constant(F::one());
+            let active_word = active.clone() * message;
+            vec![
+                (q_first.clone() + q_next.clone())
+                    * active.clone()
+                    * (one.clone() - active.clone()),
+                // the row before the first counts as active
+                q_first.clone()
+                    * (word.clone() - active_word.clone() - (one.clone() - active.clone())),
+                q_first * (count.clone() - active.clone()),
+                q_next.clone() * (word - active_word - (active_prev.clone() - active.clone())),
+                q_next.clone() * (count - count_prev - active.clone()),
+                // once inactive, never active again
+                q_next * active.clone() * (one - active_prev),
+                q_last * active,
+            ]
+        });
+
+        VarLenConfig {
+            message,
+            active,
+            word,
+            count,
+            q_first,
+            q_next,
+            q_last,
+        }
+    }
+
+    /// Assigns the padded words and returns them with the cell holding the message length.
+    pub fn pad(
+        &self,
+        mut layouter: impl Layouter<F>,
+        message: Value<&[F]>,
+    ) -> Result<([AssignedCell<F, F>; N], AssignedCell<F, F>), Error> {
+        let config = &self.config;
+        layouter.assign_region(
+            || "var_len pad",
+            |mut region| {
+                let mut words = Vec::with_capacity(N);
+                let mut count = None;
+                for row in 0..N {
+                    if row == 0 {
+                        config.q_first.enable(&mut region, row)?;
+                    } else {
+                        config.q_next.enable(&mut region, row)?;
+                    }
+                    if row == N - 1 {
+                        config.q_last.enable(&mut region, row)?;
+                    }
+                    let len = message.map(|m| m.len());
+                    let active = len.map(|len| F::from((row < len) as u64));
+                    let word = message.map(|m| match row {
+                        row if row < m.len() => m[row],
+                        row if row == m.len() => F::one(),
+                        _ => F::zero(),
+                    });
+                    let value = message.map(|m| m.get(row).copied().unwrap_or_else(F::zero));
+                    region.assign_advice(|| "message", config.message, row, || value)?;
+                    region.assign_advice(|| "active", config.active, row, || active)?;
+                    words.push(region.assign_advice(|| "word", config.word, row, || word)?);
+                    let counted = len.map(|len| F::from(len.min(row + 1) as u64));
+                    count =
+                        Some(region.assign_advice(|| "count", config.count, row, || counted)?);
+                }
+                Ok((words.try_into().unwrap(), count.expect("N >= 2 rows")))
+            },
+        )
+    }
+
+    /// Pads the message and hashes the `N` words with `ConstantLength<N>` Poseidon, returning
+    /// the hash and the length cell.
+    pub fn hash<S, const WIDTH: usize, const RATE: usize>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        poseidon: Pow5Config<F, WIDTH, RATE>,
+        message: Value<&[F]>,
+    ) -> Result<(AssignedCell<F, F>, AssignedCell<F, F>), Error>
+    where
+        S: Spec<F, WIDTH, RATE>,
+    {
+        let (words, len) = self.pad(layouter.namespace(|| "pad"), message)?;
+        let hasher = Hash::<_, _, S, ConstantLength<N>, WIDTH, RATE>::init(
+            Pow5Chip::construct(poseidon),
+            layouter.namespace(|| "init"),
+        )?;
+        let hash = hasher.hash(layouter.namespace(|| "hash"), words)?;
+        Ok((hash, len))
+    }
+}
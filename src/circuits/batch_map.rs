@@ -0,0 +1,155 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Expression, Instance, Selector},
+    poly::Rotation,
+};
+
+use crate::schema::{DescribeInstances, DescribeWitness, Encoding, InstanceLayout, WitnessLayout};
+
+/// Source of this module, hashed into the provenance of its keys.
+pub const SOURCE: &str = include_str!("batch_map.rs");
+
+/// The largest batch the layouts have names for.
+pub const MAX_BATCH: usize = 8;
+const INPUT_NAMES: [&str; MAX_BATCH] = ["x_0", "x_1", "x_2", "x_3", "x_4", "x_5", "x_6", "x_7"];
+const OUTPUT_NAMES: [&str; MAX_BATCH] = ["y_0", "y_1", "y_2", "y_3", "y_4", "y_5", "y_6", "y_7"];
+
+#[derive(Clone, Debug)]
+pub struct BatchMapConfig {
+    pub x: Column<Advice>,
+    pub y: Column<Advice>,
+    pub q_map: Selector,
+    /// Instance column 0.
+    pub inputs: Column<Instance>,
+    /// Instance column 1.
+    pub outputs: Column<Instance>,
+}
+
+/// Proves `y_i = x_i^3 + x_i + 5` for a batch of `N` inputs, with the inputs in rows 0 to
+/// `N - 1` of instance column 0 and the outputs in the same rows of instance column 1. Each
+/// input is copied into row `i` of one region, where the gate computes its output next to it.
+/// There is nothing private; the circuit is the simplest one with two instance columns.
+#[derive(Clone, Debug, Default)]
+pub struct BatchMapCircuit<F: FieldExt, const N: usize> {
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N: usize> BatchMapCircuit<F, N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The function applied to every input.
+    pub fn map(x: F) -> F {
+        x * x * x + x + F::from(5)
+    }
+
+    /// The inputs and outputs columns, in that order.
+    pub fn instance(inputs: [F; N]) -> Vec<Vec<F>> {
+        let outputs = inputs.iter().map(|x| Self::map(*x)).collect();
+        vec![inputs.to_vec(), outputs]
+    }
+}
+
+impl<F: FieldExt, const N: usize> DescribeInstances for BatchMapCircuit<F, N> {
+    fn instance_layout() -> InstanceLayout {
+        let layout = INPUT_NAMES.iter().take(N).fold(
+            InstanceLayout::new(format!("batch_map (n = {})", N)),
+            |layout, name| layout.field(0, *name, Encoding::Field, "an input x"),
+        );
+        OUTPUT_NAMES.iter().take(N).fold(layout, |layout, name| {
+            layout.field(
+                1,
+                *name,
+                Encoding::Field,
+                "x^3 + x + 5 of the input in its row",
+            )
+        })
+    }
+}
+
+/// The inputs, for the CLI, which writes both columns from them.
+impl<F: FieldExt, const N: usize> DescribeWitness for BatchMapCircuit<F, N> {
+    fn witness_layout() -> WitnessLayout {
+        INPUT_NAMES.iter().take(N).fold(
+            WitnessLayout::new(format!("batch_map (n = {})", N)),
+            |layout, name| layout.field(*name, Encoding::Field, "an input"),
+        )
+    }
+}
+
+impl<F: FieldExt, const N: usize> Circuit<F> for BatchMapCircuit<F, N> {
+    type Config = BatchMapConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        assert!(
+            (1..=MAX_BATCH).contains(&N),
+            "batches of 1 to {} inputs",
+            MAX_BATCH
+        );
+        let x = meta.advice_column();
+        let y = meta.advice_column();
+        let inputs = meta.instance_column();
+        let outputs = meta.instance_column();
+        let q_map = meta.selector();
+        for column in [x, y] {
+            meta.enable_equality(column);
+        }
+        meta.enable_equality(inputs);
+        meta.enable_equality(outputs);
+
+        meta.create_gate("y = x^3 + x + 5", |meta| {
+            let q = meta.query_selector(q_map);
+            let x = meta.query_advice(x, Rotation::cur());
+            let y = meta.query_advice(y, Rotation::cur());
+            let cube = x.clone() * x.clone() * x.clone();
+            vec![q * (cube + x + Expression::Constant(F::from(5)) - y)]
+        });
+
+        BatchMapConfig {
+            x,
+            y,
+            q_map,
+            inputs,
+            outputs,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let outputs = layouter.assign_region(
+            || "batch",
+            |mut region| {
+                (0..N)
+                    .map(|row| {
+                        config.q_map.enable(&mut region, row)?;
+                        let x = region.assign_advice_from_instance(
+                            || "x",
+                            config.inputs,
+                            row,
+                            config.x,
+                            row,
+                        )?;
+                        let y = x.value().map(|x| Self::map(*x));
+                        region.assign_advice(|| "y", config.y, row, || y)
+                    })
+                    .collect::<Result<Vec<_>, Error>>()
+            },
+        )?;
+        for (row, y) in outputs.iter().enumerate() {
+            layouter.constrain_instance(y.cell(), config.outputs, row)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,496 @@
+//! ECDSA verification over secp256k1, the curve of Bitcoin and Ethereum signatures, on the
+//! [`NonNativeChip`]: the curve's coordinates live in its base field and the scalars in its
+//! group order, neither of which is the circuit's field.
+//!
+//! `u1 G + u2 Q` is computed by one double-and-add over the bits of both scalars, adding one of
+//! `D`, `D + G`, `D + Q` and `D + G + Q` at every step. `D` is a point of unknown discrete log,
+//! so the accumulator never meets the point it adds or the identity, which the incomplete
+//! addition formulas can't handle; its `(2^256 - 1) D` is subtracted at the end.
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+use num_bigint::BigUint;
+use sha3::{Digest, Keccak256};
+
+use crate::{
+    chips::{
+        nonnative::{
+            inverse, to_limbs, Element, Modulus, NonNativeChip, NonNativeConfig, Operand, Relation,
+            LIMBS,
+        },
+        tables::Tables,
+    },
+    schema::{DescribeInstances, Encoding, InstanceLayout},
+};
+
+/// Source of this module, hashed into the provenance of its keys.
+pub const SOURCE: &str = include_str!("ecdsa.rs");
+
+const FIELD_MODULUS: &str = "fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f";
+const GROUP_ORDER: &str = "fffffffffffffffffffffffffffffffebaaedce6af48a03bbfd25e8cd0364141";
+const GENERATOR: (&str, &str) = (
+    "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798",
+    "483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8",
+);
+const SCALAR_BITS: usize = 256;
+
+fn hex(digits: &str) -> BigUint {
+    BigUint::parse_bytes(digits.as_bytes(), 16).unwrap()
+}
+
+/// `p`, the modulus of the coordinates.
+pub fn field_modulus() -> BigUint {
+    hex(FIELD_MODULUS)
+}
+
+/// `n`, the order of the generator and the modulus of scalars.
+pub fn group_order() -> BigUint {
+    hex(GROUP_ORDER)
+}
+
+/// An affine point of `y^2 = x^3 + 7` other than the identity, which the operations return as
+/// `None`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Point {
+    pub x: BigUint,
+    pub y: BigUint,
+}
+
+impl Point {
+    pub fn generator() -> Self {
+        Point {
+            x: hex(GENERATOR.0),
+            y: hex(GENERATOR.1),
+        }
+    }
+
+    /// A point hashed from a fixed string, so nobody knows its discrete log.
+    pub fn auxiliary() -> Self {
+        let p = field_modulus();
+        // p = 3 (mod 4), so a square root of a square a is a^((p + 1) / 4)
+        let root = (p.clone() + 1u32) >> 2;
+        (0u32..)
+            .find_map(|counter| {
+                let mut hasher = Keccak256::new();
+                hasher.update(b"halo2-playground ecdsa auxiliary point");
+                hasher.update(counter.to_le_bytes());
+                let x = BigUint::from_bytes_le(&hasher.finalize()) % &p;
+                let rhs = (&x * &x * &x + 7u32) % &p;
+                let y = rhs.modpow(&root, &p);
+                (&y * &y % &p == rhs).then(|| Point { x, y })
+            })
+            .unwrap()
+    }
+
+    pub fn is_on_curve(&self) -> bool {
+        let p = field_modulus();
+        self.x < p
+            && self.y < p
+            && (&self.y * &self.y) % &p == (&self.x * &self.x * &self.x + 7u32) % &p
+    }
+
+    pub fn negate(&self) -> Self {
+        Point {
+            x: self.x.clone(),
+            y: (field_modulus() - &self.y) % field_modulus(),
+        }
+    }
+
+    pub fn add(&self, other: &Point) -> Option<Point> {
+        let p = field_modulus();
+        let lambda = if self.x == other.x {
+            if self.y != other.y || self.y == BigUint::from(0u8) {
+                return None;
+            }
+            &self.x * &self.x * 3u32 * inverse(&(&self.y * 2u32), &p)
+        } else {
+            (&other.y + &p - &self.y) * inverse(&((&other.x + &p - &self.x) % &p), &p)
+        } % &p;
+        let x = (&lambda * &lambda + &p * 2u32 - &self.x - &other.x) % &p;
+        let y = (lambda * ((&self.x + &p - &x) % &p) + &p - &self.y) % &p;
+        Some(Point { x, y })
+    }
+
+    /// `scalar` times this point, most significant bit first.
+    pub fn mul(&self, scalar: &BigUint) -> Option<Point> {
+        (0..scalar.bits())
+            .rev()
+            .fold(None, |acc: Option<Point>, i| {
+                let acc = acc.and_then(|acc| acc.add(&acc));
+                if !scalar.bit(i) {
+                    acc
+                } else {
+                    match acc {
+                        Some(acc) => acc.add(self),
+                        None => Some(self.clone()),
+                    }
+                }
+            })
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Signature {
+    pub r: BigUint,
+    pub s: BigUint,
+}
+
+/// The public key of `secret`.
+pub fn public_key(secret: &BigUint) -> Point {
+    Point::generator()
+        .mul(&(secret % group_order()))
+        .expect("a secret that isn't a multiple of n")
+}
+
+/// Signs the hash `z` under `secret` with the given `nonce`, which must never be reused.
+pub fn sign(secret: &BigUint, z: &BigUint, nonce: &BigUint) -> Signature {
+    let n = group_order();
+    let point = Point::generator()
+        .mul(&(nonce % &n))
+        .expect("a nonce that isn't a multiple of n");
+    let r = point.x % &n;
+    let s = inverse(&(nonce % &n), &n) * (z + &r * secret) % &n;
+    assert!(
+        r != BigUint::from(0u8) && s != BigUint::from(0u8),
+        "a degenerate nonce"
+    );
+    Signature { r, s }
+}
+
+/// The off-circuit check the circuit proves.
+pub fn verify(public: &Point, z: &BigUint, signature: &Signature) -> bool {
+    let n = group_order();
+    let zero = BigUint::from(0u8);
+    let Signature { r, s } = signature;
+    if !public.is_on_curve() || *r == zero || *s == zero || *r >= n || *s >= n {
+        return false;
+    }
+    let w = inverse(s, &n);
+    let u1 = z * &w % &n;
+    let u2 = r * &w % &n;
+    let point = match (Point::generator().mul(&u1), public.mul(&u2)) {
+        (Some(a), Some(b)) => a.add(&b),
+        (a, b) => a.or(b),
+    };
+    point.map_or(false, |point| point.x % &n == *r)
+}
+
+/// A point of the circuit, its coordinates modulo `p`.
+#[derive(Clone, Debug)]
+struct AssignedPoint<F: FieldExt> {
+    x: Element<F>,
+    y: Element<F>,
+}
+
+/// Affine arithmetic on secp256k1 coordinates. Additions need distinct `x`, doublings a point
+/// other than the identity.
+struct Curve<'a, F: FieldExt> {
+    chip: &'a NonNativeChip<F>,
+    p: Modulus<F>,
+    one: Element<F>,
+}
+
+impl<'a, F: FieldExt> Curve<'a, F> {
+    fn constant(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        point: &Point,
+    ) -> Result<AssignedPoint<F>, Error> {
+        Ok(AssignedPoint {
+            x: self.chip.constant(layouter.namespace(|| "x"), &point.x)?,
+            y: self.chip.constant(layouter.namespace(|| "y"), &point.y)?,
+        })
+    }
+
+    fn assert_on_curve(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        point: &AssignedPoint<F>,
+    ) -> Result<(), Error> {
+        let (chip, p) = (self.chip, &self.p);
+        let x2 = chip.mul(layouter.namespace(|| "x^2"), p, &point.x, &point.x)?;
+        let y2 = chip.mul(layouter.namespace(|| "y^2"), p, &point.y, &point.y)?;
+        let seven = chip.constant(layouter.namespace(|| "b"), &BigUint::from(7u8))?;
+        let relation = Relation {
+            kab: 1,
+            a: Operand::Element(&x2),
+            b: Operand::Element(&point.x),
+            d: (1, Operand::Element(&seven)),
+            e: (-1, Operand::Element(&y2)),
+            c: Some(Operand::Zero),
+        };
+        chip.constrain(layouter.namespace(|| "on curve"), p, relation)?;
+        Ok(())
+    }
+
+    /// The point with slope `lambda` through `a`, and `b`'s `x`: `x = λ^2 - x_a - x_b` and
+    /// `y = λ (x_a - x) - y_a`.
+    fn chord(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        lambda: &Element<F>,
+        a: &AssignedPoint<F>,
+        b_x: &Element<F>,
+    ) -> Result<AssignedPoint<F>, Error> {
+        let (chip, p) = (self.chip, &self.p);
+        let relation = Relation {
+            kab: 1,
+            a: Operand::Element(lambda),
+            b: Operand::Element(lambda),
+            d: (-1, Operand::Element(&a.x)),
+            e: (-1, Operand::Element(b_x)),
+            c: None,
+        };
+        let x = chip.result(layouter.namespace(|| "x"), p, relation)?;
+        let t = chip.sub(layouter.namespace(|| "x_a - x"), p, &a.x, &x)?;
+        let relation = Relation {
+            kab: 1,
+            a: Operand::Element(lambda),
+            b: Operand::Element(&t),
+            e: (-1, Operand::Element(&a.y)),
+            ..Relation::default()
+        };
+        let y = chip.result(layouter.namespace(|| "y"), p, relation)?;
+        Ok(AssignedPoint { x, y })
+    }
+
+    fn double(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        a: &AssignedPoint<F>,
+    ) -> Result<AssignedPoint<F>, Error> {
+        let (chip, p) = (self.chip, &self.p);
+        let m = p.value();
+        let lambda =
+            a.x.value
+                .clone()
+                .zip(a.y.value.clone())
+                .map(|(x, y)| &x * &x * 3u32 % m * inverse(&(y * 2u32 % m), m) % m);
+        let x2 = chip.mul(layouter.namespace(|| "x^2"), p, &a.x, &a.x)?;
+        // 2 λ y = 3 x^2
+        let relation = Relation {
+            kab: 2,
+            a: Operand::Witness(lambda),
+            b: Operand::Element(&a.y),
+            d: (-3, Operand::Element(&x2)),
+            c: Some(Operand::Zero),
+            ..Relation::default()
+        };
+        let [lambda, ..] = chip.constrain(layouter.namespace(|| "tangent"), p, relation)?;
+        self.chord(layouter, &lambda, a, &a.x)
+    }
+
+    fn add(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        a: &AssignedPoint<F>,
+        b: &AssignedPoint<F>,
+    ) -> Result<AssignedPoint<F>, Error> {
+        let (chip, p) = (self.chip, &self.p);
+        let m = p.value();
+        let t = chip.sub(layouter.namespace(|| "x_b - x_a"), p, &b.x, &a.x)?;
+        // with x_a = x_b the slope below would be free
+        chip.div(layouter.namespace(|| "distinct x"), p, &self.one, &t)?;
+        let lambda = t
+            .value
+            .clone()
+            .zip(a.y.value.clone())
+            .zip(b.y.value.clone())
+            .map(|((t, y_a), y_b)| (y_b + m - y_a % m) * inverse(&(t % m), m) % m);
+        // λ (x_b - x_a) = y_b - y_a
+        let relation = Relation {
+            kab: 1,
+            a: Operand::Witness(lambda),
+            b: Operand::Element(&t),
+            d: (1, Operand::Element(&a.y)),
+            e: (-1, Operand::Element(&b.y)),
+            c: Some(Operand::Zero),
+        };
+        let [lambda, ..] = chip.constrain(layouter.namespace(|| "chord"), p, relation)?;
+        self.chord(layouter, &lambda, a, &b.x)
+    }
+
+    fn select(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        b1: &AssignedCell<F, F>,
+        b2: &AssignedCell<F, F>,
+        table: &[AssignedPoint<F>; 4],
+    ) -> Result<AssignedPoint<F>, Error> {
+        let [t0, t1, t2, t3] = table;
+        Ok(AssignedPoint {
+            x: self.chip.select(
+                layouter.namespace(|| "x"),
+                b1,
+                b2,
+                [&t0.x, &t1.x, &t2.x, &t3.x],
+            )?,
+            y: self.chip.select(
+                layouter.namespace(|| "y"),
+                b1,
+                b2,
+                [&t0.y, &t1.y, &t2.y, &t3.y],
+            )?,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EcdsaConfig {
+    pub tables: Tables,
+    pub nonnative: NonNativeConfig,
+    pub instance: Column<Instance>,
+}
+
+/// Proves that `(r, s)` is a signature of the message hash `z` under the public key `Q`. The
+/// instance column holds the three 88-bit limbs of `z`, `Q.x` and `Q.y`, least significant
+/// first, in rows 0 to 8; the signature is private. `r` and `s` are checked modulo `n`, so the
+/// circuit also accepts their non-canonical encodings below `2^256`.
+#[derive(Clone, Debug)]
+pub struct EcdsaCircuit {
+    pub signature: Value<Signature>,
+}
+
+impl Default for EcdsaCircuit {
+    fn default() -> Self {
+        Self {
+            signature: Value::unknown(),
+        }
+    }
+}
+
+impl EcdsaCircuit {
+    pub fn new(signature: Signature) -> Self {
+        Self {
+            signature: Value::known(signature),
+        }
+    }
+
+    pub fn instance<F: FieldExt>(z: &BigUint, public: &Point) -> Vec<F> {
+        [z, &public.x, &public.y]
+            .iter()
+            .flat_map(|value| to_limbs::<F>(value))
+            .collect()
+    }
+}
+
+const INSTANCE_LIMBS: [(&str, &str); 3 * LIMBS] = [
+    ("z_0", "z"),
+    ("z_1", "z"),
+    ("z_2", "z"),
+    ("q_x_0", "the public key's x"),
+    ("q_x_1", "the public key's x"),
+    ("q_x_2", "the public key's x"),
+    ("q_y_0", "the public key's y"),
+    ("q_y_1", "the public key's y"),
+    ("q_y_2", "the public key's y"),
+];
+
+impl DescribeInstances for EcdsaCircuit {
+    fn instance_layout() -> InstanceLayout {
+        INSTANCE_LIMBS.iter().enumerate().fold(
+            InstanceLayout::new("ecdsa (secp256k1)"),
+            |layout, (i, (name, of))| {
+                let bits = if i % LIMBS == LIMBS - 1 { 80 } else { 88 };
+                layout.field(
+                    0,
+                    *name,
+                    Encoding::Integer { bits },
+                    format!(
+                        "limb {} of {}, bits {} and up",
+                        i % LIMBS,
+                        of,
+                        88 * (i % LIMBS)
+                    ),
+                )
+            },
+        )
+    }
+}
+
+impl<F: FieldExt> Circuit<F> for EcdsaCircuit {
+    type Config = EcdsaConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        let mut tables = Tables::new();
+        let nonnative = NonNativeChip::configure(meta, &mut tables);
+        EcdsaConfig {
+            tables,
+            nonnative,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.tables.load(&mut layouter)?;
+        let chip = NonNativeChip::construct(config.nonnative);
+        let n = Modulus::new(group_order());
+        let curve = Curve {
+            chip: &chip,
+            p: Modulus::new(field_modulus()),
+            one: chip.constant(layouter.namespace(|| "one"), &BigUint::from(1u8))?,
+        };
+
+        let z = chip.from_instance(layouter.namespace(|| "z"), config.instance, 0)?;
+        let public = AssignedPoint {
+            x: chip.from_instance(layouter.namespace(|| "Q.x"), config.instance, LIMBS)?,
+            y: chip.from_instance(layouter.namespace(|| "Q.y"), config.instance, 2 * LIMBS)?,
+        };
+        curve.assert_on_curve(&mut layouter, &public)?;
+        let r = chip.witness(
+            layouter.namespace(|| "r"),
+            self.signature.as_ref().map(|signature| signature.r.clone()),
+        )?;
+        let s = chip.witness(
+            layouter.namespace(|| "s"),
+            self.signature.as_ref().map(|signature| signature.s.clone()),
+        )?;
+        // r, s != 0 (mod n)
+        chip.div(layouter.namespace(|| "r invertible"), &n, &curve.one, &r)?;
+        let w = chip.div(layouter.namespace(|| "1 / s"), &n, &curve.one, &s)?;
+        let u1 = chip.mul(layouter.namespace(|| "u1"), &n, &z, &w)?;
+        let u2 = chip.mul(layouter.namespace(|| "u2"), &n, &r, &w)?;
+        let u1 = chip.bits(layouter.namespace(|| "u1 bits"), &u1)?;
+        let u2 = chip.bits(layouter.namespace(|| "u2 bits"), &u2)?;
+
+        let auxiliary = Point::auxiliary();
+        let generator = Point::generator();
+        let d = curve.constant(&mut layouter, &auxiliary)?;
+        let dg = curve.constant(&mut layouter, &auxiliary.add(&generator).unwrap())?;
+        let dq = curve.add(&mut layouter, &d, &public)?;
+        let dgq = curve.add(&mut layouter, &dg, &public)?;
+        let table = [d, dg, dq, dgq];
+        let top = SCALAR_BITS - 1;
+        let mut acc = curve.select(&mut layouter, &u1[top], &u2[top], &table)?;
+        for i in (0..top).rev() {
+            acc = curve.double(&mut layouter, &acc)?;
+            let addend = curve.select(&mut layouter, &u1[i], &u2[i], &table)?;
+            acc = curve.add(&mut layouter, &acc, &addend)?;
+        }
+        // acc = u1 G + u2 Q + (2^256 - 1) D
+        let scale = (BigUint::from(1u8) << SCALAR_BITS) - 1u32;
+        let correction = auxiliary.mul(&(scale % group_order())).unwrap().negate();
+        let correction = curve.constant(&mut layouter, &correction)?;
+        let point = curve.add(&mut layouter, &acc, &correction)?;
+
+        // x is held below 2^256 rather than below p; the two differ only for x below
+        // 2^256 - p, which no prover can aim u1 G + u2 Q at
+        chip.assert_equal(layouter.namespace(|| "R.x = r"), &n, &point.x, &r)
+    }
+}
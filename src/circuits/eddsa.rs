@@ -0,0 +1,498 @@
+//! Ed25519 verification on the [`NonNativeChip`], the Edwards counterpart of
+//! [`ecdsa`](super::ecdsa): `[S] B = R + [k] A` over `-x^2 + y^2 = 1 + d x^2 y^2` modulo
+//! `2^255 - 19`.
+//!
+//! The Edwards formulas are complete, so the double-and-add over the bits of `S` and `k` adds
+//! one of `O`, `B`, `-A` and `B - A` at every step with no exceptional cases to steer around,
+//! but each addition takes a few more relations than an affine Weierstrass one. SHA-512 stays
+//! outside the circuit: the challenge `k = H(R || A || M)` is a public input the verifier
+//! computes from the other public values and the message.
+
+use std::convert::TryInto;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+use num_bigint::BigUint;
+use sha2::{Digest, Sha512};
+
+use crate::{
+    chips::{
+        nonnative::{
+            inverse, to_limbs, Element, Modulus, NonNativeChip, NonNativeConfig, Operand, Relation,
+            LIMBS,
+        },
+        tables::Tables,
+    },
+    schema::{DescribeInstances, Encoding, InstanceLayout},
+};
+
+/// Source of this module, hashed into the provenance of its keys.
+pub const SOURCE: &str = include_str!("eddsa.rs");
+
+const SCALAR_BITS: usize = 256;
+
+/// `p = 2^255 - 19`, the modulus of the coordinates.
+pub fn field_modulus() -> BigUint {
+    (BigUint::from(1u8) << 255) - 19u32
+}
+
+/// `l`, the order of the base point and the modulus of scalars.
+pub fn group_order() -> BigUint {
+    let tail = BigUint::parse_bytes(b"27742317777372353535851937790883648493", 10).unwrap();
+    (BigUint::from(1u8) << 252) + tail
+}
+
+/// `d = -121665 / 121666`.
+fn curve_d() -> BigUint {
+    let p = field_modulus();
+    (&p - 121665u32) * inverse(&BigUint::from(121666u32), &p) % &p
+}
+
+/// An affine point of edwards25519.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Point {
+    pub x: BigUint,
+    pub y: BigUint,
+}
+
+impl Point {
+    pub fn identity() -> Self {
+        Point {
+            x: BigUint::from(0u8),
+            y: BigUint::from(1u8),
+        }
+    }
+
+    /// The base point, `y = 4/5` with even `x`.
+    pub fn base() -> Self {
+        let p = field_modulus();
+        Self::recover(
+            BigUint::from(4u8) * inverse(&BigUint::from(5u8), &p) % &p,
+            false,
+        )
+        .unwrap()
+    }
+
+    /// The point with `y` and the given parity of `x`, if there is one.
+    fn recover(y: BigUint, odd: bool) -> Option<Self> {
+        let p = field_modulus();
+        let yy = &y * &y % &p;
+        let xx = (&yy + &p - 1u32) * inverse(&((curve_d() * &yy + 1u32) % &p), &p) % &p;
+        // p = 5 (mod 8): a square root of xx is xx^((p + 3) / 8), times sqrt(-1) if need be
+        let mut x = xx.modpow(&((p.clone() + 3u32) >> 3), &p);
+        if &x * &x % &p != xx {
+            let sqrt_m1 = BigUint::from(2u8).modpow(&((p.clone() - 1u32) >> 2), &p);
+            x = x * sqrt_m1 % &p;
+        }
+        if &x * &x % &p != xx || (x == BigUint::from(0u8) && odd) {
+            return None;
+        }
+        if x.bit(0) != odd {
+            x = &p - x;
+        }
+        Some(Point { x, y })
+    }
+
+    /// Parses the RFC 8032 encoding: `y` in little-endian with the parity of `x` on top.
+    pub fn decompress(bytes: &[u8; 32]) -> Option<Self> {
+        let mut bytes = *bytes;
+        let odd = bytes[31] >> 7 == 1;
+        bytes[31] &= 0x7f;
+        let y = BigUint::from_bytes_le(&bytes);
+        if y >= field_modulus() {
+            return None;
+        }
+        Self::recover(y, odd)
+    }
+
+    pub fn compress(&self) -> [u8; 32] {
+        let mut bytes = [0u8; 32];
+        let y = self.y.to_bytes_le();
+        bytes[..y.len()].copy_from_slice(&y);
+        bytes[31] |= (self.x.bit(0) as u8) << 7;
+        bytes
+    }
+
+    pub fn is_on_curve(&self) -> bool {
+        let p = field_modulus();
+        let (xx, yy) = (&self.x * &self.x % &p, &self.y * &self.y % &p);
+        self.x < p && self.y < p && (&yy + &p - &xx) % &p == (curve_d() * &xx * &yy + 1u32) % &p
+    }
+
+    pub fn negate(&self) -> Self {
+        let p = field_modulus();
+        Point {
+            x: (&p - &self.x) % &p,
+            y: self.y.clone(),
+        }
+    }
+
+    pub fn add(&self, other: &Point) -> Point {
+        let p = field_modulus();
+        let t = &self.x * &other.x % &p;
+        let u = &self.y * &other.y % &p;
+        let w = curve_d() * &t * &u % &p;
+        let x = (&self.x * &other.y + &self.y * &other.x) * inverse(&((&w + 1u32) % &p), &p);
+        let y = (u + t) * inverse(&((&p + 1u32 - w) % &p), &p);
+        Point {
+            x: x % &p,
+            y: y % &p,
+        }
+    }
+
+    pub fn mul(&self, scalar: &BigUint) -> Point {
+        (0..scalar.bits()).rev().fold(Point::identity(), |acc, i| {
+            let acc = acc.add(&acc);
+            if scalar.bit(i) {
+                acc.add(self)
+            } else {
+                acc
+            }
+        })
+    }
+}
+
+/// `k = SHA-512(R || A || M) mod l` of the encodings of `R` and `A`.
+pub fn challenge(r: &[u8; 32], public: &[u8; 32], message: &[u8]) -> BigUint {
+    let mut hasher = Sha512::new();
+    hasher.update(r);
+    hasher.update(public);
+    hasher.update(message);
+    BigUint::from_bytes_le(&hasher.finalize()) % group_order()
+}
+
+/// The off-circuit check the circuit proves, cofactorless and with a canonical `S`.
+pub fn verify(public: &[u8; 32], message: &[u8], signature: &[u8; 64]) -> bool {
+    let r: [u8; 32] = signature[..32].try_into().unwrap();
+    let s = BigUint::from_bytes_le(&signature[32..]);
+    match (Point::decompress(public), Point::decompress(&r)) {
+        (Some(a), Some(r_point)) if s < group_order() => {
+            let k = challenge(&r, public, message);
+            Point::base().mul(&s) == r_point.add(&a.mul(&k))
+        }
+        _ => false,
+    }
+}
+
+#[derive(Clone, Debug)]
+struct AssignedPoint<F: FieldExt> {
+    x: Element<F>,
+    y: Element<F>,
+}
+
+/// Edwards arithmetic on curve25519 coordinates, complete for points on the curve.
+struct Curve<'a, F: FieldExt> {
+    chip: &'a NonNativeChip<F>,
+    p: Modulus<F>,
+    one: Element<F>,
+    d: Element<F>,
+}
+
+impl<'a, F: FieldExt> Curve<'a, F> {
+    fn constant(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        point: &Point,
+    ) -> Result<AssignedPoint<F>, Error> {
+        Ok(AssignedPoint {
+            x: self.chip.constant(layouter.namespace(|| "x"), &point.x)?,
+            y: self.chip.constant(layouter.namespace(|| "y"), &point.y)?,
+        })
+    }
+
+    /// The point whose coordinates' limbs are instance rows `row` to `row + 5` of `column`.
+    fn from_instance(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        column: Column<Instance>,
+        row: usize,
+    ) -> Result<AssignedPoint<F>, Error> {
+        Ok(AssignedPoint {
+            x: self
+                .chip
+                .from_instance(layouter.namespace(|| "x"), column, row)?,
+            y: self
+                .chip
+                .from_instance(layouter.namespace(|| "y"), column, row + LIMBS)?,
+        })
+    }
+
+    fn assert_on_curve(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        point: &AssignedPoint<F>,
+    ) -> Result<(), Error> {
+        let (chip, p) = (self.chip, &self.p);
+        let xx = chip.mul(layouter.namespace(|| "x^2"), p, &point.x, &point.x)?;
+        let yy = chip.mul(layouter.namespace(|| "y^2"), p, &point.y, &point.y)?;
+        let w = chip.mul(layouter.namespace(|| "x^2 y^2"), p, &xx, &yy)?;
+        let minus_one = chip.constant(layouter.namespace(|| "-1"), &(field_modulus() - 1u32))?;
+        // d x^2 y^2 + x^2 - y^2 = -1
+        let relation = Relation {
+            kab: 1,
+            a: Operand::Element(&self.d),
+            b: Operand::Element(&w),
+            d: (1, Operand::Element(&xx)),
+            e: (-1, Operand::Element(&yy)),
+            c: Some(Operand::Element(&minus_one)),
+        };
+        chip.constrain(layouter.namespace(|| "on curve"), p, relation)?;
+        Ok(())
+    }
+
+    /// `x = (x_a y_b + y_a x_b) / (1 + d t u)` and `y = (u + t) / (1 - d t u)`, with
+    /// `t = x_a x_b` and `u = y_a y_b`.
+    fn add(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        a: &AssignedPoint<F>,
+        b: &AssignedPoint<F>,
+    ) -> Result<AssignedPoint<F>, Error> {
+        let (chip, p) = (self.chip, &self.p);
+        let t = chip.mul(layouter.namespace(|| "t"), p, &a.x, &b.x)?;
+        let u = chip.mul(layouter.namespace(|| "u"), p, &a.y, &b.y)?;
+        let w = chip.mul(layouter.namespace(|| "t u"), p, &t, &u)?;
+        let den = |sign| Relation {
+            kab: 1,
+            a: Operand::Element(&self.d),
+            b: Operand::Element(&w),
+            d: (sign, Operand::Element(&self.one)),
+            ..Relation::default()
+        };
+        let den_x = chip.result(layouter.namespace(|| "1 + d t u"), p, den(1))?;
+        // d t u - 1, the negated denominator of y
+        let den_y = chip.result(layouter.namespace(|| "d t u - 1"), p, den(-1))?;
+        let cross = chip.mul(layouter.namespace(|| "x_a y_b"), p, &a.x, &b.y)?;
+        let relation = Relation {
+            kab: 1,
+            a: Operand::Element(&a.y),
+            b: Operand::Element(&b.x),
+            d: (1, Operand::Element(&cross)),
+            ..Relation::default()
+        };
+        let num_x = chip.result(layouter.namespace(|| "x numerator"), p, relation)?;
+        let num_y = chip.linear(layouter.namespace(|| "-(u + t)"), p, (-1, &u), (-1, &t))?;
+        Ok(AssignedPoint {
+            x: chip.div(layouter.namespace(|| "x"), p, &num_x, &den_x)?,
+            y: chip.div(layouter.namespace(|| "y"), p, &num_y, &den_y)?,
+        })
+    }
+
+    /// `x = 2 x y / (y^2 - x^2)` and `y = (x^2 + y^2) / (2 + x^2 - y^2)`.
+    fn double(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        a: &AssignedPoint<F>,
+    ) -> Result<AssignedPoint<F>, Error> {
+        let (chip, p) = (self.chip, &self.p);
+        let xx = chip.mul(layouter.namespace(|| "x^2"), p, &a.x, &a.x)?;
+        let yy = chip.mul(layouter.namespace(|| "y^2"), p, &a.y, &a.y)?;
+        let xy = chip.mul(layouter.namespace(|| "x y"), p, &a.x, &a.y)?;
+        let den_x = chip.sub(layouter.namespace(|| "y^2 - x^2"), p, &yy, &xx)?;
+        let x = den_x.value.clone().zip(xy.value.clone()).map(|(den, xy)| {
+            let m = p.value();
+            xy * 2u32 % m * inverse(&(den % m), m) % m
+        });
+        let relation = Relation {
+            kab: 1,
+            a: Operand::Witness(x),
+            b: Operand::Element(&den_x),
+            d: (-2, Operand::Element(&xy)),
+            c: Some(Operand::Zero),
+            ..Relation::default()
+        };
+        let [x, ..] = chip.constrain(layouter.namespace(|| "x"), p, relation)?;
+        let relation = Relation {
+            kab: 2,
+            a: Operand::Element(&self.one),
+            b: Operand::Element(&self.one),
+            d: (1, Operand::Element(&xx)),
+            e: (-1, Operand::Element(&yy)),
+            c: None,
+        };
+        let den_y = chip.result(layouter.namespace(|| "2 + x^2 - y^2"), p, relation)?;
+        let num_y = chip.add(layouter.namespace(|| "x^2 + y^2"), p, &xx, &yy)?;
+        let y = chip.div(layouter.namespace(|| "y"), p, &num_y, &den_y)?;
+        Ok(AssignedPoint { x, y })
+    }
+
+    fn select(
+        &self,
+        layouter: &mut impl Layouter<F>,
+        b1: &AssignedCell<F, F>,
+        b2: &AssignedCell<F, F>,
+        table: &[AssignedPoint<F>; 4],
+    ) -> Result<AssignedPoint<F>, Error> {
+        let [t0, t1, t2, t3] = table;
+        let chip = self.chip;
+        Ok(AssignedPoint {
+            x: chip.select(
+                layouter.namespace(|| "x"),
+                b1,
+                b2,
+                [&t0.x, &t1.x, &t2.x, &t3.x],
+            )?,
+            y: chip.select(
+                layouter.namespace(|| "y"),
+                b1,
+                b2,
+                [&t0.y, &t1.y, &t2.y, &t3.y],
+            )?,
+        })
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct EddsaConfig {
+    pub tables: Tables,
+    pub nonnative: NonNativeConfig,
+    pub instance: Column<Instance>,
+}
+
+/// Proves knowledge of the `S` of an Ed25519 signature `(R, S)` under the public key `A`,
+/// given the challenge `k`. The instance column holds the three 88-bit limbs of `A.x`,
+/// `A.y`, `R.x`, `R.y` and `k`, least significant first, in rows 0 to 14. `S` is taken
+/// modulo `l`, so non-canonical encodings of it pass too.
+#[derive(Clone, Debug)]
+pub struct EddsaCircuit {
+    pub s: Value<BigUint>,
+}
+
+impl Default for EddsaCircuit {
+    fn default() -> Self {
+        Self {
+            s: Value::unknown(),
+        }
+    }
+}
+
+impl EddsaCircuit {
+    /// The circuit for the 64-byte signature `R || S`.
+    pub fn new(signature: &[u8; 64]) -> Self {
+        Self {
+            s: Value::known(BigUint::from_bytes_le(&signature[32..])),
+        }
+    }
+
+    pub fn instance<F: FieldExt>(public: &Point, r: &Point, k: &BigUint) -> Vec<F> {
+        [&public.x, &public.y, &r.x, &r.y, k]
+            .iter()
+            .flat_map(|value| to_limbs::<F>(value))
+            .collect()
+    }
+}
+
+const INSTANCE_LIMBS: [(&str, &str); 5 * LIMBS] = [
+    ("a_x_0", "the public key's x"),
+    ("a_x_1", "the public key's x"),
+    ("a_x_2", "the public key's x"),
+    ("a_y_0", "the public key's y"),
+    ("a_y_1", "the public key's y"),
+    ("a_y_2", "the public key's y"),
+    ("r_x_0", "R's x"),
+    ("r_x_1", "R's x"),
+    ("r_x_2", "R's x"),
+    ("r_y_0", "R's y"),
+    ("r_y_1", "R's y"),
+    ("r_y_2", "R's y"),
+    ("k_0", "the challenge k"),
+    ("k_1", "the challenge k"),
+    ("k_2", "the challenge k"),
+];
+
+impl DescribeInstances for EddsaCircuit {
+    fn instance_layout() -> InstanceLayout {
+        INSTANCE_LIMBS.iter().enumerate().fold(
+            InstanceLayout::new("eddsa (ed25519)"),
+            |layout, (i, (name, of))| {
+                let bits = if i % LIMBS == LIMBS - 1 { 80 } else { 88 };
+                layout.field(
+                    0,
+                    *name,
+                    Encoding::Integer { bits },
+                    format!(
+                        "limb {} of {}, bits {} and up",
+                        i % LIMBS,
+                        of,
+                        88 * (i % LIMBS)
+                    ),
+                )
+            },
+        )
+    }
+}
+
+impl<F: FieldExt> Circuit<F> for EddsaCircuit {
+    type Config = EddsaConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        let mut tables = Tables::new();
+        let nonnative = NonNativeChip::configure(meta, &mut tables);
+        EddsaConfig {
+            tables,
+            nonnative,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.tables.load(&mut layouter)?;
+        let chip = NonNativeChip::construct(config.nonnative);
+        let curve = Curve {
+            chip: &chip,
+            p: Modulus::new(field_modulus()),
+            one: chip.constant(layouter.namespace(|| "one"), &BigUint::from(1u8))?,
+            d: chip.constant(layouter.namespace(|| "d"), &curve_d())?,
+        };
+        let public = curve.from_instance(&mut layouter, config.instance, 0)?;
+        let r = curve.from_instance(&mut layouter, config.instance, 2 * LIMBS)?;
+        curve.assert_on_curve(&mut layouter, &public)?;
+        curve.assert_on_curve(&mut layouter, &r)?;
+        let k = chip.from_instance(layouter.namespace(|| "k"), config.instance, 4 * LIMBS)?;
+        let s = chip.witness(layouter.namespace(|| "S"), self.s.clone())?;
+        let s = chip.bits(layouter.namespace(|| "S bits"), &s)?;
+        let k = chip.bits(layouter.namespace(|| "k bits"), &k)?;
+
+        // [S] B - [k] A
+        let identity = curve.constant(&mut layouter, &Point::identity())?;
+        let base = curve.constant(&mut layouter, &Point::base())?;
+        let negated = AssignedPoint {
+            x: chip.result(
+                layouter.namespace(|| "-A.x"),
+                &curve.p,
+                Relation {
+                    d: (-1, Operand::Element(&public.x)),
+                    ..Relation::default()
+                },
+            )?,
+            y: public.y.clone(),
+        };
+        let difference = curve.add(&mut layouter, &base, &negated)?;
+        let table = [identity, base, negated, difference];
+        let top = SCALAR_BITS - 1;
+        let mut acc = curve.select(&mut layouter, &s[top], &k[top], &table)?;
+        for i in (0..top).rev() {
+            acc = curve.double(&mut layouter, &acc)?;
+            let addend = curve.select(&mut layouter, &s[i], &k[i], &table)?;
+            acc = curve.add(&mut layouter, &acc, &addend)?;
+        }
+        chip.assert_equal(layouter.namespace(|| "x = R.x"), &curve.p, &acc.x, &r.x)?;
+        chip.assert_equal(layouter.namespace(|| "y = R.y"), &curve.p, &acc.y, &r.y)
+    }
+}
@@ -0,0 +1,125 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
+    poly::Rotation,
+};
+
+use crate::schema::{DescribeInstances, Encoding, InstanceLayout};
+
+/// Source of this module, hashed into the provenance of its keys.
+pub const SOURCE: &str = include_str!("fibonacci.rs");
+
+#[derive(Clone, Debug)]
+pub struct FibonacciConfig {
+    pub advice: Column<Advice>,
+    pub instance: Column<Instance>,
+    pub q_fib: Selector,
+}
+
+/// Proves that the sequence starting with instance rows 0 and 1 and continuing by
+/// `f(i + 2) = f(i + 1) + f(i)` reaches instance row 2 at `f(N)`. The whole sequence is one
+/// region of `N + 1` rows in a single advice column, with the gate on each row reaching the
+/// two below it through `Rotation::next()` and `Rotation(2)`.
+#[derive(Clone, Debug, Default)]
+pub struct FibonacciCircuit<F: FieldExt, const N: usize> {
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const N: usize> FibonacciCircuit<F, N> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The first `N + 1` values of the sequence.
+    pub fn sequence(f0: F, f1: F) -> Vec<F> {
+        let mut values = vec![f0, f1];
+        while values.len() <= N {
+            let len = values.len();
+            values.push(values[len - 1] + values[len - 2]);
+        }
+        values.truncate(N + 1);
+        values
+    }
+
+    pub fn instance(f0: F, f1: F) -> Vec<F> {
+        vec![f0, f1, Self::sequence(f0, f1)[N]]
+    }
+}
+
+impl<F: FieldExt, const N: usize> DescribeInstances for FibonacciCircuit<F, N> {
+    fn instance_layout() -> InstanceLayout {
+        InstanceLayout::new(format!("fibonacci (n = {})", N))
+            .field(0, "f0", Encoding::Field, "first value of the sequence")
+            .field(0, "f1", Encoding::Field, "second value of the sequence")
+            .field(0, "f_n", Encoding::Field, "value at position n")
+    }
+}
+
+impl<F: FieldExt, const N: usize> Circuit<F> for FibonacciCircuit<F, N> {
+    type Config = FibonacciConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        assert!(N >= 1, "the sequence starts at f(0) and f(1)");
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        let q_fib = meta.selector();
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+
+        meta.create_gate("fibonacci", |meta| {
+            let q = meta.query_selector(q_fib);
+            let a = meta.query_advice(advice, Rotation::cur());
+            let b = meta.query_advice(advice, Rotation::next());
+            let c = meta.query_advice(advice, Rotation(2));
+            vec![q * (a + b - c)]
+        });
+
+        FibonacciConfig {
+            advice,
+            instance,
+            q_fib,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let last = layouter.assign_region(
+            || "sequence",
+            |mut region| {
+                let mut a = region.assign_advice_from_instance(
+                    || "f0",
+                    config.instance,
+                    0,
+                    config.advice,
+                    0,
+                )?;
+                let mut b = region.assign_advice_from_instance(
+                    || "f1",
+                    config.instance,
+                    1,
+                    config.advice,
+                    1,
+                )?;
+                for row in 2..=N {
+                    config.q_fib.enable(&mut region, row - 2)?;
+                    let c = a.value().copied() + b.value();
+                    a = b;
+                    b = region.assign_advice(|| "f", config.advice, row, || c)?;
+                }
+                Ok(b)
+            },
+        )?;
+        layouter.constrain_instance(last.cell(), config.instance, 2)
+    }
+}
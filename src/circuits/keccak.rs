@@ -0,0 +1,466 @@
+//! Keccak-256 preimage circuit, the Ethereum hash, built from bytes like the SHA-256 circuit:
+//! every 64-bit lane is eight byte cells and each step of Keccak-f[1600] goes through the byte
+//! ops chip. There are no additions, so no carries; θ and ι are XORs, ρ is the chip's rotation,
+//! and χ is an OR and two XORs. Only the digest lanes are packed into field elements.
+
+use std::convert::TryInto;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Expression, Instance, Selector},
+    poly::Rotation,
+};
+
+use crate::{
+    chips::{
+        byte_ops::{ByteOp, ByteOpsChip, ByteOpsConfig},
+        constant_pool::ConstantPool,
+        tables::{Table, Tables},
+    },
+    schema::{DescribeInstances, Encoding, InstanceLayout},
+};
+
+/// Source of this module, hashed into the provenance of its keys.
+pub const SOURCE: &str = include_str!("keccak.rs");
+
+/// Bytes absorbed per permutation, for a 512-bit capacity.
+const RATE: usize = 136;
+const RATE_LANES: usize = RATE / 8;
+const ROUNDS: usize = 24;
+
+/// The ρ rotation of lane `x + 5 y`.
+const RHO: [u32; 25] = [
+    0, 1, 62, 28, 27, 36, 44, 6, 55, 20, 3, 10, 43, 25, 39, 41, 45, 15, 21, 8, 18, 2, 61, 56, 14,
+];
+
+const RC: [u64; ROUNDS] = [
+    0x0000000000000001,
+    0x0000000000008082,
+    0x800000000000808a,
+    0x8000000080008000,
+    0x000000000000808b,
+    0x0000000080000001,
+    0x8000000080008081,
+    0x8000000000008009,
+    0x000000000000008a,
+    0x0000000000000088,
+    0x0000000080008009,
+    0x000000008000000a,
+    0x000000008000808b,
+    0x800000000000008b,
+    0x8000000000008089,
+    0x8000000000008003,
+    0x8000000000008002,
+    0x8000000000000080,
+    0x000000000000800a,
+    0x800000008000000a,
+    0x8000000080008081,
+    0x8000000000008080,
+    0x0000000080000001,
+    0x8000000080008008,
+];
+
+/// Where π moves lane `x + 5 y`: to `y + 5 (2 x + 3 y)`.
+fn pi(lane: usize) -> usize {
+    let (x, y) = (lane % 5, lane / 5);
+    y + 5 * ((2 * x + 3 * y) % 5)
+}
+
+/// The padded message as little-endian lanes, in blocks of `RATE_LANES`. This is the original
+/// Keccak padding, not SHA-3's.
+fn pad(message: &[u8]) -> Vec<u64> {
+    let mut bytes = message.to_vec();
+    bytes.push(0x01);
+    while bytes.len() % RATE != 0 {
+        bytes.push(0);
+    }
+    *bytes.last_mut().unwrap() |= 0x80;
+    bytes
+        .chunks(8)
+        .map(|lane| u64::from_le_bytes(lane.try_into().unwrap()))
+        .collect()
+}
+
+fn permute(a: &mut [u64; 25]) {
+    for rc in RC {
+        let c: Vec<u64> = (0..5)
+            .map(|x| (0..5).fold(0, |acc, y| acc ^ a[x + 5 * y]))
+            .collect();
+        for x in 0..5 {
+            let d = c[(x + 4) % 5] ^ c[(x + 1) % 5].rotate_left(1);
+            for y in 0..5 {
+                a[x + 5 * y] ^= d;
+            }
+        }
+        let mut b = [0; 25];
+        for (lane, value) in a.iter().enumerate() {
+            b[pi(lane)] = value.rotate_left(RHO[lane]);
+        }
+        for lane in 0..25 {
+            let (x, y) = (lane % 5, 5 * (lane / 5));
+            a[lane] = b[lane] ^ (!b[(x + 1) % 5 + y] & b[(x + 2) % 5 + y]);
+        }
+        a[0] ^= rc;
+    }
+}
+
+/// Keccak-256 of `message`, computed off-circuit.
+pub fn keccak256(message: &[u8]) -> [u8; 32] {
+    let mut state = [0; 25];
+    for block in pad(message).chunks(RATE_LANES) {
+        for (lane, word) in state.iter_mut().zip(block) {
+            *lane ^= word;
+        }
+        permute(&mut state);
+    }
+    let mut digest = [0; 32];
+    for (bytes, lane) in digest.chunks_mut(8).zip(state.iter()) {
+        bytes.copy_from_slice(&lane.to_le_bytes());
+    }
+    digest
+}
+
+#[derive(Clone, Debug)]
+pub struct KeccakConfig {
+    pub tables: Tables,
+    pub byte_ops: ByteOpsConfig,
+    /// The little-endian bytes of a lane, packed into `lane`.
+    pub bytes: [Column<Advice>; 8],
+    pub lane: Column<Advice>,
+    pub instance: Column<Instance>,
+    q_lane: Selector,
+}
+
+/// A 64-bit lane as eight cells known to hold bytes, least significant first.
+#[derive(Clone, Debug)]
+struct Lane<F: FieldExt> {
+    bytes: Vec<AssignedCell<F, F>>,
+}
+
+impl<F: FieldExt> Lane<F> {
+    fn value(&self) -> Value<u64> {
+        self.bytes.iter().rev().fold(Value::known(0), |acc, byte| {
+            acc.zip(byte.value())
+                .map(|(acc, byte)| acc << 8 | (byte.get_lower_32() & 0xff) as u64)
+        })
+    }
+}
+
+impl KeccakConfig {
+    fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> Self {
+        let [lhs, rhs, out] = [(); 3].map(|_| meta.advice_column());
+        let bytes = [(); 8].map(|_| meta.advice_column());
+        let lane = meta.advice_column();
+        let constants = meta.fixed_column();
+        let instance = meta.instance_column();
+        for column in bytes.iter().chain([&lane]) {
+            meta.enable_equality(*column);
+        }
+        meta.enable_equality(instance);
+        meta.enable_constant(constants);
+        let mut tables = Tables::new();
+        let byte_ops = ByteOpsChip::configure(meta, &mut tables, lhs, rhs, out);
+        let byte = tables.column(meta, Table::BYTE);
+        let q_lane = meta.complex_selector();
+
+        for column in bytes {
+            meta.lookup("lane byte", |meta| {
+                let q = meta.query_selector(q_lane);
+                vec![(q * meta.query_advice(column, Rotation::cur()), byte)]
+            });
+        }
+        meta.create_gate("lane bytes", |meta| {
+            let q = meta.query_selector(q_lane);
+            let lane = meta.query_advice(lane, Rotation::cur());
+            let packed = bytes
+                .iter()
+                .rev()
+                .fold(Expression::Constant(F::zero()), |acc, column| {
+                    acc * Expression::Constant(F::from(256))
+                        + meta.query_advice(*column, Rotation::cur())
+                });
+            vec![q * (lane - packed)]
+        });
+        Self {
+            tables,
+            byte_ops,
+            bytes,
+            lane,
+            instance,
+            q_lane,
+        }
+    }
+
+    fn chip<F: FieldExt>(&self) -> ByteOpsChip<F> {
+        ByteOpsChip::construct(self.byte_ops.clone())
+    }
+
+    fn witness<F: FieldExt>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<u64>,
+    ) -> Result<Lane<F>, Error> {
+        layouter.assign_region(
+            || "message lane",
+            |mut region| {
+                self.q_lane.enable(&mut region, 0)?;
+                let bytes = self
+                    .bytes
+                    .iter()
+                    .enumerate()
+                    .map(|(i, column)| {
+                        region.assign_advice(
+                            || format!("byte {}", i),
+                            *column,
+                            0,
+                            || value.map(|v| F::from((v >> (8 * i)) & 0xff)),
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                region.assign_advice(|| "lane", self.lane, 0, || value.map(F::from))?;
+                Ok(Lane { bytes })
+            },
+        )
+    }
+
+    /// The cell of `lane` packed into one field element.
+    fn pack<F: FieldExt>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        lane: &Lane<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        layouter.assign_region(
+            || "pack lane",
+            |mut region| {
+                self.q_lane.enable(&mut region, 0)?;
+                for (byte, column) in lane.bytes.iter().zip(self.bytes.iter()) {
+                    byte.copy_advice(|| "byte", &mut region, *column, 0)?;
+                }
+                region.assign_advice(|| "lane", self.lane, 0, || lane.value().map(F::from))
+            },
+        )
+    }
+
+    fn constant<F: FieldExt>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        pool: &ConstantPool<F>,
+        value: u64,
+    ) -> Result<Lane<F>, Error> {
+        let bytes = (0..8)
+            .map(|i| {
+                pool.constant(
+                    layouter.namespace(|| "byte"),
+                    F::from((value >> (8 * i)) & 0xff),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Lane { bytes })
+    }
+
+    fn op<F: FieldExt>(
+        &self,
+        layouter: impl Layouter<F>,
+        op: ByteOp,
+        a: &Lane<F>,
+        b: &Lane<F>,
+    ) -> Result<Lane<F>, Error> {
+        let bytes = self.chip().assign_bytes(layouter, op, &a.bytes, &b.bytes)?;
+        Ok(Lane { bytes })
+    }
+
+    fn rotate_left<F: FieldExt>(
+        &self,
+        layouter: impl Layouter<F>,
+        lane: &Lane<F>,
+        bits: u32,
+    ) -> Result<Lane<F>, Error> {
+        let bytes = self
+            .chip()
+            .rotate_left(layouter, &lane.bytes, bits as usize)?;
+        Ok(Lane { bytes })
+    }
+
+    fn permute<F: FieldExt>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        pool: &ConstantPool<F>,
+        mut a: Vec<Lane<F>>,
+    ) -> Result<Vec<Lane<F>>, Error> {
+        for rc in RC {
+            let mut c = Vec::with_capacity(5);
+            for x in 0..5 {
+                let mut acc = a[x].clone();
+                for y in 1..5 {
+                    acc = self.op(
+                        layouter.namespace(|| "θ column"),
+                        ByteOp::Xor,
+                        &acc,
+                        &a[x + 5 * y],
+                    )?;
+                }
+                c.push(acc);
+            }
+            let mut d = Vec::with_capacity(5);
+            for x in 0..5 {
+                let rotated = self.rotate_left(layouter.namespace(|| "θ"), &c[(x + 1) % 5], 1)?;
+                d.push(self.op(
+                    layouter.namespace(|| "θ"),
+                    ByteOp::Xor,
+                    &c[(x + 4) % 5],
+                    &rotated,
+                )?);
+            }
+            // θ's last XOR, then ρ's rotation, written to π's destination
+            let mut b = a.clone();
+            for (lane, value) in a.iter().enumerate() {
+                let xored =
+                    self.op(layouter.namespace(|| "θ"), ByteOp::Xor, value, &d[lane % 5])?;
+                b[pi(lane)] = self.rotate_left(layouter.namespace(|| "ρ π"), &xored, RHO[lane])?;
+            }
+            // !b1 & b2 = (b1 | b2) ^ b1
+            for lane in 0..25 {
+                let (x, y) = (lane % 5, 5 * (lane / 5));
+                let (b1, b2) = (&b[(x + 1) % 5 + y], &b[(x + 2) % 5 + y]);
+                let either = self.op(layouter.namespace(|| "χ"), ByteOp::Or, b1, b2)?;
+                let masked = self.op(layouter.namespace(|| "χ"), ByteOp::Xor, &either, b1)?;
+                a[lane] = self.op(layouter.namespace(|| "χ"), ByteOp::Xor, &b[lane], &masked)?;
+            }
+            // only the bytes of the round constant that aren't zero change
+            for (i, byte) in rc.to_le_bytes().into_iter().enumerate() {
+                if byte != 0 {
+                    let rc =
+                        pool.constant(layouter.namespace(|| "ι constant"), F::from(byte as u64))?;
+                    a[0].bytes[i] = self.chip().assign(
+                        layouter.namespace(|| "ι"),
+                        ByteOp::Xor,
+                        &a[0].bytes[i],
+                        &rc,
+                    )?;
+                }
+            }
+        }
+        Ok(a)
+    }
+}
+
+/// Proves knowledge of a `LEN`-byte preimage whose Keccak-256 digest is the public input, as
+/// four little-endian 64-bit lanes in instance rows 0 to 3. `LEN` must be a multiple of 8, so
+/// the message fills whole lanes and the padding is constant.
+#[derive(Clone, Copy, Debug)]
+pub struct KeccakCircuit<const LEN: usize> {
+    pub preimage: Value<[u8; LEN]>,
+}
+
+impl<const LEN: usize> Default for KeccakCircuit<LEN> {
+    fn default() -> Self {
+        Self {
+            preimage: Value::unknown(),
+        }
+    }
+}
+
+impl<const LEN: usize> KeccakCircuit<LEN> {
+    pub fn new(preimage: [u8; LEN]) -> Self {
+        Self {
+            preimage: Value::known(preimage),
+        }
+    }
+
+    pub fn instance<F: FieldExt>(digest: &[u8; 32]) -> Vec<F> {
+        digest
+            .chunks(8)
+            .map(|lane| F::from(u64::from_le_bytes(lane.try_into().unwrap())))
+            .collect()
+    }
+}
+
+const DIGEST_LANES: [&str; 4] = ["digest_0", "digest_1", "digest_2", "digest_3"];
+
+impl<const LEN: usize> DescribeInstances for KeccakCircuit<LEN> {
+    fn instance_layout() -> InstanceLayout {
+        DIGEST_LANES.iter().enumerate().fold(
+            InstanceLayout::new(format!("keccak256 ({} bytes)", LEN)),
+            |layout, (i, name)| {
+                layout.field(
+                    0,
+                    name,
+                    Encoding::Integer { bits: 64 },
+                    format!(
+                        "little-endian lane {} of the Keccak-256 digest, bytes {} to {}",
+                        i,
+                        8 * i,
+                        8 * i + 7
+                    ),
+                )
+            },
+        )
+    }
+}
+
+impl<F: FieldExt, const LEN: usize> Circuit<F> for KeccakCircuit<LEN> {
+    type Config = KeccakConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        KeccakConfig::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        assert_eq!(LEN % 8, 0, "the preimage fills whole lanes");
+        config.tables.load(&mut layouter)?;
+        let pool = ConstantPool::new(config.lane);
+        let padded = pad(&[0; LEN]);
+        let mut lanes = Vec::with_capacity(padded.len());
+        for (i, padding) in padded.iter().enumerate() {
+            let lane = if i < LEN / 8 {
+                let value = self.preimage.map(|preimage| {
+                    u64::from_le_bytes(preimage[8 * i..8 * i + 8].try_into().unwrap())
+                });
+                config.witness(layouter.namespace(|| "preimage"), value)?
+            } else {
+                config.constant(layouter.namespace(|| "padding"), &pool, *padding)?
+            };
+            lanes.push(lane);
+        }
+        let mut state: Option<Vec<Lane<F>>> = None;
+        for block in lanes.chunks(RATE_LANES) {
+            let absorbed = match state {
+                // the first block XORs into zeros
+                None => {
+                    let zero = config.constant(layouter.namespace(|| "capacity"), &pool, 0)?;
+                    let mut lanes = block.to_vec();
+                    lanes.resize(25, zero);
+                    lanes
+                }
+                Some(mut state) => {
+                    for (i, lane) in block.iter().enumerate() {
+                        state[i] = config.op(
+                            layouter.namespace(|| "absorb"),
+                            ByteOp::Xor,
+                            &state[i],
+                            lane,
+                        )?;
+                    }
+                    state
+                }
+            };
+            state = Some(config.permute(layouter.namespace(|| "keccak-f"), &pool, absorbed)?);
+        }
+        let state = state.expect("at least one block");
+        for (row, lane) in state[..4].iter().enumerate() {
+            let lane = config.pack(layouter.namespace(|| "digest"), lane)?;
+            layouter.constrain_instance(lane.cell(), config.instance, row)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,341 @@
+use std::convert::TryInto;
+
+use halo2_gadgets::poseidon::{primitives::ConstantLength, Hash, Pow5Chip, Pow5Config};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Expression, Instance, Selector},
+    poly::Rotation,
+};
+
+use crate::{
+    merkle::{hash_children, KvPath},
+    poseidon::MySpec,
+    schema::{DescribeInstances, Encoding, InstanceLayout},
+};
+
+/// Source of this module, hashed into the provenance of its keys.
+pub const SOURCE: &str = include_str!("kv.rs");
+
+#[derive(Debug, Clone)]
+pub struct KvConfig<const WIDTH: usize, const RATE: usize> {
+    /// The key divided by `RATE^level`, ending in zero on the root row.
+    pub acc: Column<Advice>,
+    pub digit: Column<Advice>,
+    pub node: Column<Advice>,
+    pub new_node: Column<Advice>,
+    /// One-hot encoding of the digit.
+    pub bits: [Column<Advice>; RATE],
+    pub children: [Column<Advice>; RATE],
+    pub new_children: [Column<Advice>; RATE],
+    pub q_level: Selector,
+    pub instance: Column<Instance>,
+    pub poseidon_config: Pow5Config<Fr, WIDTH, RATE>,
+}
+
+/// Proves that writing instance row 2 at the public key (instance row 0) of the key-value map
+/// committed to by the `RATE`-ary tree with root row 3 replaces the value in row 1 and gives
+/// the root in row 4. A read is the update writing the value back, with equal roots.
+///
+/// Each level is one row holding every child of the node above, old and new, with the digit
+/// of the key selecting which one is on the path, so a wider tree trades fewer levels for a
+/// wider permutation per level. `WIDTH` is always `RATE + 1`.
+#[derive(Clone, Debug)]
+pub struct KvCircuit<const WIDTH: usize, const RATE: usize, const DEPTH: usize> {
+    pub path: Value<KvPath>,
+}
+
+impl<const WIDTH: usize, const RATE: usize, const DEPTH: usize> Default
+    for KvCircuit<WIDTH, RATE, DEPTH>
+{
+    fn default() -> Self {
+        Self {
+            path: Value::unknown(),
+        }
+    }
+}
+
+impl<const WIDTH: usize, const RATE: usize, const DEPTH: usize> KvCircuit<WIDTH, RATE, DEPTH> {
+    pub fn new(path: KvPath) -> Self {
+        assert_eq!(path.depth(), DEPTH, "a path of depth {}", DEPTH);
+        Self {
+            path: Value::known(path),
+        }
+    }
+
+    pub fn instance(key: u64, old_value: Fr, new_value: Fr, old_root: Fr, new_root: Fr) -> Vec<Fr> {
+        vec![Fr::from(key), old_value, new_value, old_root, new_root]
+    }
+
+    /// The bits of the largest key, `RATE^DEPTH - 1`.
+    fn key_bits() -> u32 {
+        let last = (RATE as u64).pow(DEPTH as u32) - 1;
+        64 - last.leading_zeros()
+    }
+}
+
+impl<const WIDTH: usize, const RATE: usize, const DEPTH: usize> DescribeInstances
+    for KvCircuit<WIDTH, RATE, DEPTH>
+{
+    fn instance_layout() -> InstanceLayout {
+        InstanceLayout::new(format!("kv (arity {}, depth {})", RATE, DEPTH))
+            .field(
+                0,
+                "key",
+                Encoding::Integer {
+                    bits: Self::key_bits(),
+                },
+                "key written",
+            )
+            .field(
+                0,
+                "old value",
+                Encoding::Field,
+                "value at the key before, 0 if absent",
+            )
+            .field(
+                0,
+                "new value",
+                Encoding::Field,
+                "value at the key after, 0 to remove it",
+            )
+            .field(0, "old root", Encoding::Field, "root before the write")
+            .field(0, "new root", Encoding::Field, "root after the write")
+    }
+}
+
+impl<const WIDTH: usize, const RATE: usize, const DEPTH: usize> Circuit<Fr>
+    for KvCircuit<WIDTH, RATE, DEPTH>
+{
+    type Config = KvConfig<WIDTH, RATE>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        assert_eq!(WIDTH, RATE + 1, "one capacity element");
+        let state = (0..WIDTH).map(|_| meta.advice_column()).collect::<Vec<_>>();
+        let partial_sbox = meta.advice_column();
+        let rc_a = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        let rc_b = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        meta.enable_constant(rc_b[0]);
+        let acc = meta.advice_column();
+        let digit = meta.advice_column();
+        let node = meta.advice_column();
+        let new_node = meta.advice_column();
+        let bits = [(); RATE].map(|_| meta.advice_column());
+        let children = [(); RATE].map(|_| meta.advice_column());
+        let new_children = [(); RATE].map(|_| meta.advice_column());
+        for column in [acc, node, new_node]
+            .into_iter()
+            .chain(children)
+            .chain(new_children)
+        {
+            meta.enable_equality(column);
+        }
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let q_level = meta.selector();
+        meta.create_gate("level", |meta| {
+            let q = meta.query_selector(q_level);
+            let acc_next = meta.query_advice(acc, Rotation::next());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let digit = meta.query_advice(digit, Rotation::cur());
+            let node = meta.query_advice(node, Rotation::cur());
+            let new_node = meta.query_advice(new_node, Rotation::cur());
+            let one = Expression::Constant(Fr::one());
+            let mut constraints = vec![acc - digit.clone() - acc_next * Fr::from(RATE as u64)];
+            let mut sum = Expression::Constant(Fr::zero());
+            let mut index = Expression::Constant(Fr::zero());
+            for j in 0..RATE {
+                let bit = meta.query_advice(bits[j], Rotation::cur());
+                let child = meta.query_advice(children[j], Rotation::cur());
+                let new_child = meta.query_advice(new_children[j], Rotation::cur());
+                constraints.push(bit.clone() * (one.clone() - bit.clone()));
+                // the node is the selected child, and only the selected child changes
+                constraints.push(bit.clone() * (child.clone() - node.clone()));
+                constraints
+                    .push(new_child - child - bit.clone() * (new_node.clone() - node.clone()));
+                sum = sum + bit.clone();
+                index = index + bit * Fr::from(j as u64);
+            }
+            constraints.push(sum - one);
+            constraints.push(index - digit);
+            constraints
+                .into_iter()
+                .map(|c| q.clone() * c)
+                .collect::<Vec<_>>()
+        });
+
+        KvConfig {
+            acc,
+            digit,
+            node,
+            new_node,
+            bits,
+            children,
+            new_children,
+            q_level,
+            instance,
+            poseidon_config: Pow5Chip::configure::<MySpec<WIDTH, RATE>>(
+                meta,
+                state.try_into().unwrap(),
+                partial_sbox,
+                rc_a.try_into().unwrap(),
+                rc_b.try_into().unwrap(),
+            ),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        type Cells = Vec<AssignedCell<Fr, Fr>>;
+        #[allow(clippy::type_complexity)]
+        let (key, nodes, new_nodes, groups, new_groups): (
+            _,
+            Cells,
+            Cells,
+            Vec<Cells>,
+            Vec<Cells>,
+        ) = layouter.assign_region(
+            || "levels",
+            |mut region| {
+                let mut node = region.assign_advice_from_instance(
+                    || "old value",
+                    config.instance,
+                    1,
+                    config.node,
+                    0,
+                )?;
+                let mut new_node = region.assign_advice_from_instance(
+                    || "new value",
+                    config.instance,
+                    2,
+                    config.new_node,
+                    0,
+                )?;
+                let (mut nodes, mut new_nodes) = (vec![], vec![]);
+                let (mut groups, mut new_groups) = (vec![], vec![]);
+                let mut key = None;
+                for level in 0..DEPTH {
+                    config.q_level.enable(&mut region, level)?;
+                    let digit = self.path.as_ref().map(|path| path.digits[level]);
+                    let rest = self.path.as_ref().map(|path| {
+                        path.digits[level..]
+                            .iter()
+                            .rev()
+                            .fold(0u64, |acc, digit| acc * RATE as u64 + *digit as u64)
+                    });
+                    let acc =
+                        region.assign_advice(|| "acc", config.acc, level, || rest.map(Fr::from))?;
+                    key.get_or_insert(acc);
+                    region.assign_advice(
+                        || "digit",
+                        config.digit,
+                        level,
+                        || digit.map(|digit| Fr::from(digit as u64)),
+                    )?;
+                    let (mut group, mut new_group) = (vec![], vec![]);
+                    for j in 0..RATE {
+                        region.assign_advice(
+                            || format!("bit {}", j),
+                            config.bits[j],
+                            level,
+                            || digit.map(|digit| Fr::from(digit == j)),
+                        )?;
+                        let sibling = self.path.as_ref().map(|path| path.groups[level][j]);
+                        let child = digit.zip(sibling.zip(node.value().copied())).map(
+                            |(digit, (sibling, node))| {
+                                if digit == j {
+                                    node
+                                } else {
+                                    sibling
+                                }
+                            },
+                        );
+                        let new_child = digit.zip(sibling.zip(new_node.value().copied())).map(
+                            |(digit, (sibling, node))| {
+                                if digit == j {
+                                    node
+                                } else {
+                                    sibling
+                                }
+                            },
+                        );
+                        group.push(region.assign_advice(
+                            || format!("child {}", j),
+                            config.children[j],
+                            level,
+                            || child,
+                        )?);
+                        new_group.push(region.assign_advice(
+                            || format!("new child {}", j),
+                            config.new_children[j],
+                            level,
+                            || new_child,
+                        )?);
+                    }
+                    let hash = |group: &Cells| {
+                        group
+                            .iter()
+                            .map(|cell| cell.value().copied())
+                            .collect::<Value<Vec<_>>>()
+                            .map(|children| {
+                                hash_children::<WIDTH, RATE>(children.try_into().unwrap())
+                            })
+                    };
+                    let parent = hash(&group);
+                    let new_parent = hash(&new_group);
+                    nodes.push(node);
+                    new_nodes.push(new_node);
+                    node = region.assign_advice(|| "node", config.node, level + 1, || parent)?;
+                    new_node = region.assign_advice(
+                        || "new node",
+                        config.new_node,
+                        level + 1,
+                        || new_parent,
+                    )?;
+                    groups.push(group);
+                    new_groups.push(new_group);
+                }
+                // nothing is left of a key in range above the root
+                let zero =
+                    region.assign_advice_from_constant(|| "acc", config.acc, DEPTH, Fr::zero())?;
+                nodes.push(node);
+                new_nodes.push(new_node);
+                Ok((key.unwrap_or(zero), nodes, new_nodes, groups, new_groups))
+            },
+        )?;
+        layouter.constrain_instance(key.cell(), config.instance, 0)?;
+
+        for (level, (group, new_group)) in groups.iter().zip(new_groups.iter()).enumerate() {
+            for (name, group, parent) in [
+                ("old", group, &nodes[level + 1]),
+                ("new", new_group, &new_nodes[level + 1]),
+            ] {
+                let hasher =
+                    Hash::<_, _, MySpec<WIDTH, RATE>, ConstantLength<RATE>, WIDTH, RATE>::init(
+                        Pow5Chip::construct(config.poseidon_config.clone()),
+                        layouter.namespace(|| format!("init {} level {}", name, level)),
+                    )?;
+                let hash = hasher.hash(
+                    layouter.namespace(|| format!("hash {} level {}", name, level)),
+                    group.clone().try_into().unwrap(),
+                )?;
+                layouter.assign_region(
+                    || format!("{} level {} parent", name, level),
+                    |mut region| region.constrain_equal(hash.cell(), parent.cell()),
+                )?;
+            }
+        }
+
+        layouter.constrain_instance(nodes[DEPTH].cell(), config.instance, 3)?;
+        layouter.constrain_instance(new_nodes[DEPTH].cell(), config.instance, 4)
+    }
+}
@@ -0,0 +1,161 @@
+use std::convert::TryInto;
+
+use halo2_gadgets::poseidon::{primitives::ConstantLength, Hash, Pow5Chip, Pow5Config};
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+use crate::{
+    chips::branch::{BranchChip, BranchConfig, Condition},
+    merkle::MerklePath,
+    poseidon::MySpec,
+    schema::{DescribeInstances, Encoding, InstanceLayout},
+};
+
+/// Source of this module, hashed into the provenance of its keys.
+pub const SOURCE: &str = include_str!("merkle.rs");
+
+const WIDTH: usize = 3;
+const RATE: usize = 2;
+
+#[derive(Debug, Clone)]
+pub struct MerklePathConfig {
+    pub load: Column<Advice>,
+    pub instance: Column<Instance>,
+    pub branch: BranchConfig,
+    pub poseidon_config: Pow5Config<Fr, WIDTH, RATE>,
+}
+
+/// Proves that the leaf in instance row 1 is in the depth-`DEPTH` tree whose root is instance
+/// row 0. The siblings and the directions of the path are private.
+#[derive(Clone, Debug)]
+pub struct MerklePathCircuit<const DEPTH: usize> {
+    pub path: Value<MerklePath>,
+}
+
+impl<const DEPTH: usize> Default for MerklePathCircuit<DEPTH> {
+    fn default() -> Self {
+        Self {
+            path: Value::unknown(),
+        }
+    }
+}
+
+impl<const DEPTH: usize> MerklePathCircuit<DEPTH> {
+    pub fn new(path: MerklePath) -> Self {
+        assert_eq!(path.depth(), DEPTH, "a path of depth {}", DEPTH);
+        Self {
+            path: Value::known(path),
+        }
+    }
+
+    pub fn instance(root: Fr, leaf: Fr) -> Vec<Fr> {
+        vec![root, leaf]
+    }
+}
+
+impl<const DEPTH: usize> DescribeInstances for MerklePathCircuit<DEPTH> {
+    fn instance_layout() -> InstanceLayout {
+        InstanceLayout::new(format!("merkle (depth {})", DEPTH))
+            .field(
+                0,
+                "root",
+                Encoding::Field,
+                "root of the Poseidon Merkle tree",
+            )
+            .field(
+                0,
+                "leaf",
+                Encoding::Field,
+                "leaf whose membership is proven, at a private position",
+            )
+    }
+}
+
+impl<const DEPTH: usize> Circuit<Fr> for MerklePathCircuit<DEPTH> {
+    type Config = MerklePathConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let state = (0..WIDTH).map(|_| meta.advice_column()).collect::<Vec<_>>();
+        let partial_sbox = meta.advice_column();
+        let rc_a = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        let rc_b = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        meta.enable_constant(rc_b[0]);
+        let load = meta.advice_column();
+        meta.enable_equality(load);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        MerklePathConfig {
+            load,
+            instance,
+            branch: BranchChip::configure(meta),
+            poseidon_config: Pow5Chip::configure::<MySpec<WIDTH, RATE>>(
+                meta,
+                state.try_into().unwrap(),
+                partial_sbox,
+                rc_a.try_into().unwrap(),
+                rc_b.try_into().unwrap(),
+            ),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let branch = BranchChip::construct(config.branch.clone());
+        let (leaf, siblings) = layouter.assign_region(
+            || "load leaf and siblings",
+            |mut region| {
+                let leaf = region.assign_advice_from_instance(
+                    || "leaf",
+                    config.instance,
+                    1,
+                    config.load,
+                    0,
+                )?;
+                let siblings = (0..DEPTH)
+                    .map(|level| {
+                        region.assign_advice(
+                            || format!("sibling {}", level),
+                            config.load,
+                            1 + level,
+                            || self.path.as_ref().map(|path| path.siblings[level]),
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok((leaf, siblings))
+            },
+        )?;
+
+        let mut node = leaf;
+        for (level, sibling) in siblings.iter().enumerate() {
+            // a right child hashes as (sibling, node)
+            let is_right = self.path.as_ref().map(|path| path.directions[level]);
+            let (_, pair) = branch.select_all(
+                layouter.namespace(|| format!("order level {}", level)),
+                Condition::Witness(is_right),
+                &[(sibling, &node), (&node, sibling)],
+            )?;
+            let hasher = Hash::<_, _, MySpec<WIDTH, RATE>, ConstantLength<2>, WIDTH, RATE>::init(
+                Pow5Chip::construct(config.poseidon_config.clone()),
+                layouter.namespace(|| format!("init level {}", level)),
+            )?;
+            node = hasher.hash(
+                layouter.namespace(|| format!("hash level {}", level)),
+                pair.try_into().unwrap(),
+            )?;
+        }
+
+        layouter.constrain_instance(node.cell(), config.instance, 0)
+    }
+}
@@ -0,0 +1,127 @@
+//! Example circuits shared by the binaries. `mul`, `fibonacci`, `poseidon` and `batch_map` are
+//! always built; the others sit behind the `circuits-*` features, and [`registry`] lists the
+//! ones this build has.
+
+use halo2_proofs::halo2curves::bn256::Fr;
+
+use crate::{
+    poseidon::MySpec,
+    schema::{DescribeInstances, InstanceLayout},
+};
+
+pub mod batch_map;
+#[cfg(feature = "circuits-signature")]
+pub mod ecdsa;
+#[cfg(feature = "circuits-signature")]
+pub mod eddsa;
+pub mod fibonacci;
+#[cfg(feature = "circuits-hash")]
+pub mod keccak;
+#[cfg(feature = "circuits-hash")]
+pub mod kv;
+#[cfg(feature = "circuits-hash")]
+pub mod merkle;
+pub mod mul;
+pub mod poseidon;
+#[cfg(feature = "circuits-hash")]
+pub mod poseidon_chain;
+#[cfg(feature = "circuits-hash")]
+pub mod products;
+#[cfg(feature = "circuits-signature")]
+pub mod rsa;
+#[cfg(feature = "circuits-signature")]
+pub mod schnorr;
+#[cfg(feature = "circuits-hash")]
+pub mod sha256;
+#[cfg(feature = "circuits-hash")]
+pub mod smt;
+#[cfg(feature = "circuits-state")]
+pub mod transition;
+#[cfg(feature = "circuits-hash")]
+pub mod var_len;
+#[cfg(feature = "circuits-state")]
+pub mod vdf;
+
+/// A circuit compiled into this build. Generic circuits are described at the parameters
+/// the binaries use.
+#[derive(Clone, Copy, Debug)]
+pub struct CircuitEntry {
+    pub name: &'static str,
+    /// The feature that builds it, `None` for the circuits that are always in.
+    pub feature: Option<&'static str>,
+    pub source: &'static str,
+    pub instance_layout: fn() -> InstanceLayout,
+}
+
+fn entry<C: DescribeInstances>(
+    name: &'static str,
+    feature: Option<&'static str>,
+    source: &'static str,
+) -> CircuitEntry {
+    CircuitEntry {
+        name,
+        feature,
+        source,
+        instance_layout: C::instance_layout,
+    }
+}
+
+/// The circuits enabled in this build.
+pub fn registry() -> Vec<CircuitEntry> {
+    #[allow(unused_mut)]
+    let mut entries = vec![
+        entry::<mul::MulCircuit<Fr>>("mul", None, mul::SOURCE),
+        entry::<fibonacci::FibonacciCircuit<Fr, 64>>("fibonacci", None, fibonacci::SOURCE),
+        entry::<poseidon::PoseidonHashCircuit<Fr, MySpec<3, 2>, 3, 2, 2>>(
+            "poseidon",
+            None,
+            poseidon::SOURCE,
+        ),
+        entry::<batch_map::BatchMapCircuit<Fr, 4>>("batch_map", None, batch_map::SOURCE),
+    ];
+    #[cfg(feature = "circuits-hash")]
+    entries.extend([
+        entry::<merkle::MerklePathCircuit<4>>("merkle", Some("circuits-hash"), merkle::SOURCE),
+        entry::<sha256::Sha256Circuit<32>>("sha256", Some("circuits-hash"), sha256::SOURCE),
+        entry::<keccak::KeccakCircuit<32>>("keccak", Some("circuits-hash"), keccak::SOURCE),
+        entry::<smt::SmtCircuit<8>>("smt", Some("circuits-hash"), smt::SOURCE),
+        entry::<kv::KvCircuit<5, 4, 4>>("kv", Some("circuits-hash"), kv::SOURCE),
+        entry::<var_len::VarLenHashCircuit<Fr, MySpec<3, 2>, 3, 2, 8>>(
+            "var_len",
+            Some("circuits-hash"),
+            var_len::SOURCE,
+        ),
+        entry::<poseidon_chain::PoseidonChainCircuit>(
+            "poseidon_chain",
+            Some("circuits-hash"),
+            poseidon_chain::SOURCE,
+        ),
+        entry::<products::DigestedProducts<16>>(
+            "products_digest",
+            Some("circuits-hash"),
+            products::SOURCE,
+        ),
+    ]);
+    #[cfg(feature = "circuits-state")]
+    entries.extend([
+        entry::<transition::StateTransitionCircuit>(
+            "transition",
+            Some("circuits-state"),
+            transition::SOURCE,
+        ),
+        entry::<vdf::VdfCircuit>("vdf", Some("circuits-state"), vdf::SOURCE),
+    ]);
+    #[cfg(feature = "circuits-signature")]
+    entries.extend([
+        entry::<ecdsa::EcdsaCircuit>("ecdsa", Some("circuits-signature"), ecdsa::SOURCE),
+        entry::<eddsa::EddsaCircuit>("eddsa", Some("circuits-signature"), eddsa::SOURCE),
+        entry::<rsa::RsaCircuit>("rsa", Some("circuits-signature"), rsa::SOURCE),
+        entry::<schnorr::SchnorrCircuit>("schnorr", Some("circuits-signature"), schnorr::SOURCE),
+    ]);
+    entries
+}
+
+/// The enabled circuit called `name`.
+pub fn find(name: &str) -> Option<CircuitEntry> {
+    registry().into_iter().find(|entry| entry.name == name)
+}
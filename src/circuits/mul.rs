@@ -0,0 +1,98 @@
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+use crate::{
+    chips::standard_plonk::{StandardPlonkChip, StandardPlonkConfig},
+    schema::{DescribeInstances, DescribeWitness, Encoding, InstanceLayout, WitnessLayout},
+};
+
+/// Source of this module, hashed into the provenance of its keys.
+pub const SOURCE: &str = include_str!("mul.rs");
+
+#[derive(Clone, Debug)]
+pub struct MulConfig {
+    pub plonk: StandardPlonkConfig,
+    pub instance: Column<Instance>,
+}
+
+/// Proves knowledge of `a` and `b` whose product is the public input in instance row 1.
+/// Instance row 0 is unused.
+#[derive(Clone, Debug, Default)]
+pub struct MulCircuit<F: FieldExt> {
+    pub a: Value<F>,
+    pub b: Value<F>,
+}
+
+impl<F: FieldExt> MulCircuit<F> {
+    pub fn new(a: F, b: F) -> Self {
+        Self {
+            a: Value::known(a),
+            b: Value::known(b),
+        }
+    }
+
+    /// The instance column proving `a * b = c`.
+    pub fn instance(c: F) -> Vec<F> {
+        vec![F::zero(), c]
+    }
+}
+
+impl<F: FieldExt> DescribeInstances for MulCircuit<F> {
+    fn instance_layout() -> InstanceLayout {
+        InstanceLayout::new("mul")
+            .field(
+                0,
+                "unused",
+                Encoding::Zero,
+                "not constrained, kept for compatibility",
+            )
+            .field(
+                0,
+                "product",
+                Encoding::Field,
+                "a * b for the private a and b",
+            )
+    }
+}
+
+impl<F: FieldExt> DescribeWitness for MulCircuit<F> {
+    fn witness_layout() -> WitnessLayout {
+        WitnessLayout::new("mul")
+            .field("a", Encoding::Field, "first factor")
+            .field("b", Encoding::Field, "second factor")
+    }
+}
+
+impl<F: FieldExt> Circuit<F> for MulCircuit<F> {
+    type Config = MulConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        MulConfig {
+            plonk: StandardPlonkChip::configure(meta),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = StandardPlonkChip::construct(config.plonk);
+        let a = chip.assign(layouter.namespace(|| "load private a"), self.a)?;
+        let b = chip.assign(layouter.namespace(|| "load private b"), self.b)?;
+        let c = chip.mul(layouter.namespace(|| "a * b"), &a, &b)?;
+        layouter.constrain_instance(c.cell(), config.instance, 1)?;
+        Ok(())
+    }
+}
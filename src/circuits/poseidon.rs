@@ -0,0 +1,176 @@
+use std::{convert::TryInto, marker::PhantomData};
+
+use halo2_gadgets::poseidon::{
+    primitives::{self as poseidon, ConstantLength, Spec},
+    Hash, Pow5Chip, Pow5Config,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+use crate::schema::{DescribeInstances, DescribeWitness, Encoding, InstanceLayout, WitnessLayout};
+
+/// Source of this module, hashed into the provenance of its keys.
+pub const SOURCE: &str = include_str!("poseidon.rs");
+
+#[derive(Debug, Clone)]
+pub struct PoseidonHashConfig<F: FieldExt, const WIDTH: usize, const RATE: usize, const L: usize> {
+    /// The message is loaded `RATE` elements per row.
+    pub input: [Column<Advice>; RATE],
+    pub expected: Column<Instance>,
+    pub poseidon_config: Pow5Config<F, WIDTH, RATE>,
+}
+
+/// Proves knowledge of an `L`-element message whose constant-length Poseidon hash under `S`
+/// is the public input in instance row 0.
+#[derive(Clone, Copy, Debug)]
+pub struct PoseidonHashCircuit<F, S, const WIDTH: usize, const RATE: usize, const L: usize>
+where
+    F: FieldExt,
+    S: Spec<F, WIDTH, RATE> + Clone + Copy,
+{
+    pub message: Value<[F; L]>,
+    _spec: PhantomData<S>,
+}
+
+impl<F, S, const WIDTH: usize, const RATE: usize, const L: usize>
+    PoseidonHashCircuit<F, S, WIDTH, RATE, L>
+where
+    F: FieldExt,
+    S: Spec<F, WIDTH, RATE> + Clone + Copy,
+{
+    pub fn new(message: [F; L]) -> Self {
+        Self {
+            message: Value::known(message),
+            _spec: PhantomData,
+        }
+    }
+
+    /// The hash the circuit exposes, computed off-circuit.
+    pub fn hash(message: [F; L]) -> F {
+        poseidon::Hash::<_, S, ConstantLength<L>, WIDTH, RATE>::init().hash(message)
+    }
+}
+
+impl<F, S, const WIDTH: usize, const RATE: usize, const L: usize> DescribeInstances
+    for PoseidonHashCircuit<F, S, WIDTH, RATE, L>
+where
+    F: FieldExt,
+    S: Spec<F, WIDTH, RATE> + Clone + Copy,
+{
+    fn instance_layout() -> InstanceLayout {
+        InstanceLayout::new(format!(
+            "poseidon (width {}, rate {}, {} elements)",
+            WIDTH, RATE, L
+        ))
+        .field(
+            0,
+            "hash",
+            Encoding::Field,
+            format!(
+                "constant-length Poseidon hash of the private {}-element message",
+                L
+            ),
+        )
+    }
+}
+
+impl<F, S, const WIDTH: usize, const RATE: usize, const L: usize> DescribeWitness
+    for PoseidonHashCircuit<F, S, WIDTH, RATE, L>
+where
+    F: FieldExt,
+    S: Spec<F, WIDTH, RATE> + Clone + Copy,
+{
+    fn witness_layout() -> WitnessLayout {
+        (0..L).fold(
+            WitnessLayout::new(format!(
+                "poseidon (width {}, rate {}, {} elements)",
+                WIDTH, RATE, L
+            )),
+            |layout, i| {
+                layout.field(
+                    format!("message_{}", i),
+                    Encoding::Field,
+                    format!("message element {}", i),
+                )
+            },
+        )
+    }
+}
+
+impl<F, S, const WIDTH: usize, const RATE: usize, const L: usize> Circuit<F>
+    for PoseidonHashCircuit<F, S, WIDTH, RATE, L>
+where
+    F: FieldExt,
+    S: Spec<F, WIDTH, RATE> + Copy + Clone,
+{
+    type Config = PoseidonHashConfig<F, WIDTH, RATE, L>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            message: Value::unknown(),
+            _spec: PhantomData,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let state = (0..WIDTH).map(|_| meta.advice_column()).collect::<Vec<_>>();
+        let expected = meta.instance_column();
+        meta.enable_equality(expected);
+        let partial_sbox = meta.advice_column();
+
+        let rc_a = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        let rc_b = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+
+        meta.enable_constant(rc_b[0]);
+
+        Self::Config {
+            input: state[..RATE].try_into().unwrap(),
+            expected,
+            poseidon_config: Pow5Chip::configure::<S>(
+                meta,
+                state.try_into().unwrap(),
+                partial_sbox,
+                rc_a.try_into().unwrap(),
+                rc_b.try_into().unwrap(),
+            ),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = Pow5Chip::construct(config.poseidon_config.clone());
+
+        let message = layouter.assign_region(
+            || "load message",
+            |mut region| {
+                let message_word = |i: usize| {
+                    let value = self.message.map(|message_vals| message_vals[i]);
+                    region.assign_advice(
+                        || format!("load message_{}", i),
+                        config.input[i % RATE],
+                        i / RATE,
+                        || value,
+                    )
+                };
+
+                let message: Result<Vec<_>, Error> = (0..L).map(message_word).collect();
+                Ok(message?.try_into().unwrap())
+            },
+        )?;
+
+        let hasher = Hash::<_, _, S, ConstantLength<L>, WIDTH, RATE>::init(
+            chip,
+            layouter.namespace(|| "init"),
+        )?;
+        let output = hasher.hash(layouter.namespace(|| "hash"), message)?;
+
+        layouter.constrain_instance(output.cell(), config.expected, 0)
+    }
+}
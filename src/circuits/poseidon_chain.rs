@@ -0,0 +1,130 @@
+//! `length` chained Poseidon hashes, `h_{i+1} = H(h_i)`, each permutation in its own region so
+//! the circuit grows with `length` alone. Sized to fill a given `k`, it stress-tests proving on
+//! large domains: memory, the FFTs and the MSMs scale with the rows rather than the columns.
+
+use std::convert::TryInto;
+
+use halo2_gadgets::poseidon::{
+    primitives::{self as poseidon, ConstantLength},
+    Hash, Pow5Chip, Pow5Config,
+};
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+use crate::{
+    poseidon::MySpec,
+    schema::{DescribeInstances, Encoding, InstanceLayout},
+};
+
+/// Source of this module, hashed into the provenance of its keys.
+pub const SOURCE: &str = include_str!("poseidon_chain.rs");
+
+type Spec = MySpec<3, 2>;
+
+#[derive(Clone, Debug)]
+pub struct PoseidonChainConfig {
+    pub seed: Column<Advice>,
+    pub head: Column<Instance>,
+    pub poseidon_config: Pow5Config<Fr, 3, 2>,
+}
+
+/// Proves knowledge of a seed whose `length`-fold Poseidon hash is the public input in
+/// instance row 0. `length` is fixed at keygen.
+#[derive(Clone, Debug)]
+pub struct PoseidonChainCircuit {
+    pub seed: Value<Fr>,
+    pub length: usize,
+}
+
+impl PoseidonChainCircuit {
+    pub fn new(seed: Fr, length: usize) -> Self {
+        Self {
+            seed: Value::known(seed),
+            length,
+        }
+    }
+
+    /// The end of the chain, computed off-circuit.
+    pub fn head(seed: Fr, length: usize) -> Fr {
+        (0..length).fold(seed, |h, _| {
+            poseidon::Hash::<_, Spec, ConstantLength<1>, 3, 2>::init().hash([h])
+        })
+    }
+}
+
+impl Default for PoseidonChainCircuit {
+    fn default() -> Self {
+        Self {
+            seed: Value::unknown(),
+            length: 1,
+        }
+    }
+}
+
+impl DescribeInstances for PoseidonChainCircuit {
+    fn instance_layout() -> InstanceLayout {
+        InstanceLayout::new("poseidon_chain").field(
+            0,
+            "head",
+            Encoding::Field,
+            "the private seed hashed `length` times",
+        )
+    }
+}
+
+impl Circuit<Fr> for PoseidonChainCircuit {
+    type Config = PoseidonChainConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            seed: Value::unknown(),
+            length: self.length,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let state = (0..3).map(|_| meta.advice_column()).collect::<Vec<_>>();
+        let partial_sbox = meta.advice_column();
+        let rc_a = (0..3).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        let rc_b = (0..3).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        let head = meta.instance_column();
+        meta.enable_equality(head);
+        meta.enable_constant(rc_b[0]);
+
+        PoseidonChainConfig {
+            seed: state[0],
+            head,
+            poseidon_config: Pow5Chip::configure::<Spec>(
+                meta,
+                state.try_into().unwrap(),
+                partial_sbox,
+                rc_a.try_into().unwrap(),
+                rc_b.try_into().unwrap(),
+            ),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let mut head = layouter.assign_region(
+            || "load seed",
+            |mut region| region.assign_advice(|| "seed", config.seed, 0, || self.seed),
+        )?;
+        for i in 0..self.length {
+            let chip = Pow5Chip::construct(config.poseidon_config.clone());
+            let hasher = Hash::<_, _, Spec, ConstantLength<1>, 3, 2>::init(
+                chip,
+                layouter.namespace(|| format!("init {}", i)),
+            )?;
+            head = hasher.hash(layouter.namespace(|| format!("link {}", i)), [head])?;
+        }
+        layouter.constrain_instance(head.cell(), config.head, 0)
+    }
+}
@@ -0,0 +1,167 @@
+//! `N` products `a_i * b_i = c_i` of private factors, whose products are the statement. With
+//! `DIGEST = false` each product is an instance row; with `DIGEST = true` the circuit hashes
+//! them with [`expose_digest`] and instance row 0 is the digest alone, the pattern to follow
+//! when calldata or the verifier's instance commitment is what costs.
+
+use std::convert::TryInto;
+
+use halo2_gadgets::poseidon::{Pow5Chip, Pow5Config};
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
+    poly::Rotation,
+};
+
+use crate::{
+    chips::public_digest::{expose_digest, public_digest},
+    poseidon::MySpec,
+    schema::{DescribeInstances, Encoding, InstanceLayout},
+};
+
+/// Source of this module, hashed into the provenance of its keys.
+pub const SOURCE: &str = include_str!("products.rs");
+
+type Spec = MySpec<3, 2>;
+
+#[derive(Clone, Debug)]
+pub struct ProductsConfig {
+    pub advice: [Column<Advice>; 3],
+    pub q_mul: Selector,
+    pub instance: Column<Instance>,
+    pub poseidon_config: Option<Pow5Config<Fr, 3, 2>>,
+}
+
+#[derive(Clone, Debug)]
+pub struct ProductsCircuit<const N: usize, const DIGEST: bool> {
+    pub factors: Value<[(Fr, Fr); N]>,
+}
+
+/// The products exposed one per instance row.
+pub type ExposedProducts<const N: usize> = ProductsCircuit<N, false>;
+/// The products exposed as their Poseidon digest.
+pub type DigestedProducts<const N: usize> = ProductsCircuit<N, true>;
+
+impl<const N: usize, const DIGEST: bool> Default for ProductsCircuit<N, DIGEST> {
+    fn default() -> Self {
+        Self {
+            factors: Value::unknown(),
+        }
+    }
+}
+
+impl<const N: usize, const DIGEST: bool> ProductsCircuit<N, DIGEST> {
+    pub fn new(factors: [(Fr, Fr); N]) -> Self {
+        Self {
+            factors: Value::known(factors),
+        }
+    }
+
+    /// The instance column for `products`: the products themselves, or their digest.
+    pub fn instance(products: [Fr; N]) -> Vec<Fr> {
+        if DIGEST {
+            vec![public_digest::<Fr, Spec, 3, 2, N>(products)]
+        } else {
+            products.to_vec()
+        }
+    }
+}
+
+impl<const N: usize> DescribeInstances for DigestedProducts<N> {
+    fn instance_layout() -> InstanceLayout {
+        InstanceLayout::new(format!("products ({} digested)", N)).field(
+            0,
+            "digest",
+            Encoding::Field,
+            format!(
+                "constant-length Poseidon hash of the {} products a_i * b_i",
+                N
+            ),
+        )
+    }
+}
+
+impl<const N: usize, const DIGEST: bool> Circuit<Fr> for ProductsCircuit<N, DIGEST> {
+    type Config = ProductsConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let advice = [(); 3].map(|_| meta.advice_column());
+        let instance = meta.instance_column();
+        meta.enable_equality(advice[2]);
+        meta.enable_equality(instance);
+        let q_mul = meta.selector();
+        meta.create_gate("product", |meta| {
+            let q = meta.query_selector(q_mul);
+            let [a, b, c] = advice.map(|column| meta.query_advice(column, Rotation::cur()));
+            vec![q * (a * b - c)]
+        });
+
+        let poseidon_config = DIGEST.then(|| {
+            let state = [(); 3].map(|_| meta.advice_column());
+            let partial_sbox = meta.advice_column();
+            let rc_a = [(); 3].map(|_| meta.fixed_column());
+            let rc_b = [(); 3].map(|_| meta.fixed_column());
+            meta.enable_constant(rc_b[0]);
+            Pow5Chip::configure::<Spec>(meta, state, partial_sbox, rc_a, rc_b)
+        });
+
+        ProductsConfig {
+            advice,
+            q_mul,
+            instance,
+            poseidon_config,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let [a, b, c] = config.advice;
+        let products = layouter.assign_region(
+            || "products",
+            |mut region| {
+                let mut products = Vec::with_capacity(N);
+                for row in 0..N {
+                    config.q_mul.enable(&mut region, row)?;
+                    let factors = self.factors.map(|factors| factors[row]);
+                    region.assign_advice(|| "a", a, row, || factors.map(|(a, _)| a))?;
+                    region.assign_advice(|| "b", b, row, || factors.map(|(_, b)| b))?;
+                    products.push(region.assign_advice(
+                        || "c",
+                        c,
+                        row,
+                        || factors.map(|(a, b)| a * b),
+                    )?);
+                }
+                Ok(products)
+            },
+        )?;
+
+        match config.poseidon_config {
+            Some(poseidon) => {
+                let products: [_; N] = products.try_into().unwrap();
+                expose_digest::<_, Spec, 3, 2, N>(
+                    layouter.namespace(|| "public digest"),
+                    poseidon,
+                    products,
+                    config.instance,
+                    0,
+                )?;
+                Ok(())
+            }
+            None => {
+                for (row, product) in products.iter().enumerate() {
+                    layouter.constrain_instance(product.cell(), config.instance, row)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
@@ -0,0 +1,304 @@
+//! RSA-2048 signature verification, PKCS#1 v1.5 with SHA-256, on the [`BigIntChip`]: the
+//! signature `s` is raised to `e = 65537` modulo the public `n` by sixteen squarings and a
+//! multiplication, and the result compared with the padded hash.
+//!
+//! The padding `EM = 0x00 || 0x01 || 0xff.. || 0x00 || DigestInfo || H` is a constant but for
+//! its last 32 bytes, so the circuit only reads the hash from its instance.
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+use num_bigint::{BigInt, BigUint};
+use rand::RngCore;
+
+use crate::{
+    chips::{
+        bigint::{to_limbs, BigIntChip, BigIntConfig, Integer, LIMB_BITS},
+        tables::Tables,
+    },
+    schema::{DescribeInstances, Encoding, InstanceLayout},
+};
+
+/// Source of this module, hashed into the provenance of its keys.
+pub const SOURCE: &str = include_str!("rsa.rs");
+
+pub const MODULUS_BITS: usize = 2048;
+/// Limbs of the modulus and the signature.
+pub const LIMBS: usize = MODULUS_BITS / LIMB_BITS;
+/// Limbs of the SHA-256 hash.
+pub const HASH_LIMBS: usize = 256 / LIMB_BITS;
+/// The public exponent, `2^16 + 1`.
+pub const EXPONENT: u32 = 65537;
+const SQUARINGS: usize = 16;
+
+/// The DER encoding of SHA-256's `DigestInfo`, up to the hash itself.
+const DIGEST_INFO: [u8; 19] = [
+    0x30, 0x31, 0x30, 0x0d, 0x06, 0x09, 0x60, 0x86, 0x48, 0x01, 0x65, 0x03, 0x04, 0x02, 0x01, 0x05,
+    0x00, 0x04, 0x20,
+];
+
+/// The EMSA-PKCS1-v1_5 encoding of a SHA-256 `hash`, as the big-endian integer of its
+/// `MODULUS_BITS / 8` bytes.
+pub fn encode(hash: &[u8; 32]) -> BigUint {
+    let padding = MODULUS_BITS / 8 - 3 - DIGEST_INFO.len() - hash.len();
+    let mut em = vec![0x00, 0x01];
+    em.extend(std::iter::repeat(0xff).take(padding));
+    em.push(0x00);
+    em.extend(DIGEST_INFO);
+    em.extend(hash);
+    BigUint::from_bytes_be(&em)
+}
+
+/// The encoding with a hash of zeros, which the hash is added to.
+fn encoded_prefix() -> BigUint {
+    encode(&[0; 32])
+}
+
+/// The integer of the big-endian `hash`.
+fn hash_integer(hash: &[u8; 32]) -> BigUint {
+    BigUint::from_bytes_be(hash)
+}
+
+const SMALL_PRIMES: [u32; 24] = [
+    3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37, 41, 43, 47, 53, 59, 61, 67, 71, 73, 79, 83, 89, 97,
+];
+
+fn random_below(rng: &mut impl RngCore, bound: &BigUint) -> BigUint {
+    let mut bytes = vec![0; (bound.bits() as usize + 7) / 8 + 8];
+    rng.fill_bytes(&mut bytes);
+    BigUint::from_bytes_le(&bytes) % bound
+}
+
+/// Miller-Rabin with `rounds` random bases, for an odd `n` above 100.
+fn is_probable_prime(rng: &mut impl RngCore, n: &BigUint, rounds: usize) -> bool {
+    if SMALL_PRIMES.iter().any(|p| n % *p == BigUint::from(0u8)) {
+        return false;
+    }
+    let one = BigUint::from(1u8);
+    let n_1 = n - 1u8;
+    let shift = n_1.trailing_zeros().unwrap_or(0);
+    let odd = &n_1 >> shift;
+    (0..rounds).all(|_| {
+        let base = random_below(rng, &(n - 3u8)) + 2u8;
+        let mut x = base.modpow(&odd, n);
+        if x == one || x == n_1 {
+            return true;
+        }
+        (1..shift).any(|_| {
+            x = &x * &x % n;
+            x == n_1
+        })
+    })
+}
+
+/// A random prime of `bits` bits, its top two set so that two of them multiply to
+/// `2 bits` bits, with `p - 1` coprime to the exponent.
+fn random_prime(rng: &mut impl RngCore, bits: usize) -> BigUint {
+    loop {
+        let mut bytes = vec![0; bits / 8];
+        rng.fill_bytes(&mut bytes);
+        let top = BigUint::from(3u8) << (bits - 2);
+        let candidate = (BigUint::from_bytes_le(&bytes) | top) | BigUint::from(1u8);
+        if (&candidate - 1u8) % EXPONENT != BigUint::from(0u8)
+            && is_probable_prime(rng, &candidate, 32)
+        {
+            return candidate;
+        }
+    }
+}
+
+/// `value^-1` modulo `modulus`, by the extended Euclidean algorithm; `None` if they share a
+/// factor.
+fn inverse(value: &BigUint, modulus: &BigUint) -> Option<BigUint> {
+    let (mut r0, mut r1) = (BigInt::from(modulus.clone()), BigInt::from(value.clone()));
+    let (mut t0, mut t1) = (BigInt::from(0u8), BigInt::from(1u8));
+    while r1 != BigInt::from(0u8) {
+        let quotient = &r0 / &r1;
+        let r2 = &r0 - &quotient * &r1;
+        let t2 = &t0 - &quotient * &t1;
+        r0 = std::mem::replace(&mut r1, r2);
+        t0 = std::mem::replace(&mut t1, t2);
+    }
+    if r0 != BigInt::from(1u8) {
+        return None;
+    }
+    let modulus = BigInt::from(modulus.clone());
+    (((t0 % &modulus) + &modulus) % &modulus).to_biguint()
+}
+
+/// An RSA key with the public exponent [`EXPONENT`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct PrivateKey {
+    /// The public modulus.
+    pub n: BigUint,
+    pub d: BigUint,
+}
+
+impl PrivateKey {
+    /// A fresh [`MODULUS_BITS`]-bit key.
+    pub fn generate(rng: &mut impl RngCore) -> Self {
+        let p = random_prime(rng, MODULUS_BITS / 2);
+        let q = loop {
+            let q = random_prime(rng, MODULUS_BITS / 2);
+            if q != p {
+                break q;
+            }
+        };
+        let phi = (&p - 1u8) * (&q - 1u8);
+        let d = inverse(&BigUint::from(EXPONENT), &phi).expect("p - 1 and q - 1 coprime to e");
+        PrivateKey { n: p * q, d }
+    }
+
+    /// Signs a SHA-256 `hash`.
+    pub fn sign(&self, hash: &[u8; 32]) -> BigUint {
+        encode(hash).modpow(&self.d, &self.n)
+    }
+}
+
+/// The off-circuit check the circuit proves.
+pub fn verify(n: &BigUint, hash: &[u8; 32], signature: &BigUint) -> bool {
+    signature < n && signature.modpow(&BigUint::from(EXPONENT), n) == encode(hash)
+}
+
+#[derive(Clone, Debug)]
+pub struct RsaConfig {
+    pub tables: Tables,
+    pub bigint: BigIntConfig,
+    pub instance: Column<Instance>,
+}
+
+/// Proves that `s` is a signature of a SHA-256 hash under the modulus `n`. The instance
+/// column holds the 64-bit limbs of `n`, least significant first, in rows 0 to 31, then those
+/// of the hash read as a big-endian integer in rows 32 to 35; the signature is private and
+/// checked to be below `n`. `n` needs its top bit set, as every key [`PrivateKey::generate`]
+/// makes has.
+#[derive(Clone, Debug)]
+pub struct RsaCircuit {
+    pub signature: Value<BigUint>,
+}
+
+impl Default for RsaCircuit {
+    fn default() -> Self {
+        Self {
+            signature: Value::unknown(),
+        }
+    }
+}
+
+impl RsaCircuit {
+    pub fn new(signature: BigUint) -> Self {
+        Self {
+            signature: Value::known(signature),
+        }
+    }
+
+    pub fn instance<F: FieldExt>(n: &BigUint, hash: &[u8; 32]) -> Vec<F> {
+        let mut instance = to_limbs::<F>(n, LIMBS);
+        instance.extend(to_limbs::<F>(&hash_integer(hash), HASH_LIMBS));
+        instance
+    }
+}
+
+const MODULUS_LIMB_NAMES: [&str; LIMBS] = [
+    "n_0", "n_1", "n_2", "n_3", "n_4", "n_5", "n_6", "n_7", "n_8", "n_9", "n_10", "n_11", "n_12",
+    "n_13", "n_14", "n_15", "n_16", "n_17", "n_18", "n_19", "n_20", "n_21", "n_22", "n_23", "n_24",
+    "n_25", "n_26", "n_27", "n_28", "n_29", "n_30", "n_31",
+];
+const HASH_LIMB_NAMES: [&str; HASH_LIMBS] = ["h_0", "h_1", "h_2", "h_3"];
+
+impl DescribeInstances for RsaCircuit {
+    fn instance_layout() -> InstanceLayout {
+        let layout = MODULUS_LIMB_NAMES.iter().enumerate().fold(
+            InstanceLayout::new("rsa (2048-bit, PKCS#1 v1.5, SHA-256)"),
+            |layout, (i, name)| {
+                layout.field(
+                    0,
+                    *name,
+                    Encoding::Integer { bits: 64 },
+                    format!("limb {} of the modulus, bits {} and up", i, 64 * i),
+                )
+            },
+        );
+        HASH_LIMB_NAMES
+            .iter()
+            .enumerate()
+            .fold(layout, |layout, (i, name)| {
+                layout.field(
+                    0,
+                    *name,
+                    Encoding::Integer { bits: 64 },
+                    format!(
+                        "limb {} of the SHA-256 hash as a big-endian integer, bits {} and up",
+                        i,
+                        64 * i
+                    ),
+                )
+            })
+    }
+}
+
+impl<F: FieldExt> Circuit<F> for RsaCircuit {
+    type Config = RsaConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        let mut tables = Tables::new();
+        let bigint = BigIntChip::<F, LIMBS>::configure(meta, &mut tables);
+        RsaConfig {
+            tables,
+            bigint,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        config.tables.load(&mut layouter)?;
+        let chip = BigIntChip::<F, LIMBS>::construct(config.bigint);
+        let n = chip.from_instance(layouter.namespace(|| "n"), config.instance, 0, LIMBS)?;
+        let hash = chip.from_instance(
+            layouter.namespace(|| "hash"),
+            config.instance,
+            LIMBS,
+            HASH_LIMBS,
+        )?;
+        let s = chip.witness(layouter.namespace(|| "s"), self.signature.clone(), LIMBS)?;
+        chip.assert_less_than(layouter.namespace(|| "s < n"), &s, &n)?;
+
+        // s^(2^16 + 1)
+        let mut x = s.clone();
+        for _ in 0..SQUARINGS {
+            x = chip.mul_mod(layouter.namespace(|| "square"), &x, &x, &n)?;
+        }
+        let x = chip.mul_mod(layouter.namespace(|| "times s"), &x, &s, &n)?;
+
+        let prefix = encoded_prefix() >> 256u32;
+        let prefix = chip.constant(layouter.namespace(|| "padding"), &prefix)?;
+        let em = Integer {
+            limbs: hash
+                .limbs
+                .iter()
+                .chain(prefix.limbs.iter())
+                .cloned()
+                .collect(),
+            value: hash
+                .value
+                .zip(prefix.value)
+                .map(|(hash, prefix)| (prefix << 256u32) + hash),
+        };
+        chip.assert_equal(layouter.namespace(|| "s^e = EM"), &x, &em)
+    }
+}
@@ -0,0 +1,368 @@
+//! Schnorr signatures over Grumpkin, `y^2 = x^3 - 17` over the BN256 scalar field, whose
+//! group order is in turn the BN256 base field. Its coordinates are native field elements, so
+//! verification costs a few thousand rows on the [`EccChip`] where ECDSA over secp256k1 needs
+//! non-native arithmetic; this is the embedded curve of shielded-pool designs.
+//!
+//! The challenge is the Poseidon hash `e = H(R.x, R.y, P.x, P.y, m)` and a signature `(R, s)`
+//! is valid if `s G = R + e P`. The circuit computes `s G - e P` by one double-and-add over the
+//! bits of both scalars, offset by a point `D` of unknown discrete log as in the ECDSA circuit.
+
+use std::convert::TryInto;
+
+use halo2_gadgets::poseidon::{
+    primitives::{self as poseidon, ConstantLength},
+    Hash, Pow5Chip, Pow5Config,
+};
+use halo2_proofs::{
+    arithmetic::{Field, FieldExt},
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    halo2curves::{
+        bn256::{Fq, Fr},
+        group::ff::PrimeField,
+    },
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+use sha3::{Digest, Keccak256};
+
+use crate::{
+    chips::{
+        decompose::{DecomposeChip, DecomposeConfig, Decomposition},
+        ecc::{AssignedPoint, EccChip, EccConfig},
+        tables::Tables,
+    },
+    poseidon::MySpec,
+    schema::{DescribeInstances, Encoding, InstanceLayout},
+};
+
+/// Source of this module, hashed into the provenance of its keys.
+pub const SOURCE: &str = include_str!("schnorr.rs");
+
+const WIDTH: usize = 3;
+const RATE: usize = 2;
+/// Bits of both scalars; the group order is below `2^254`.
+const SCALAR_BITS: usize = 254;
+
+/// The `b` of `y^2 = x^3 + b`.
+pub fn curve_b() -> Fr {
+    -Fr::from(17)
+}
+
+/// An affine point of Grumpkin other than the identity, which the operations return as
+/// `None`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Point {
+    pub x: Fr,
+    pub y: Fr,
+}
+
+/// The even square root of `value`, if it has any.
+fn even_sqrt(value: Fr) -> Option<Fr> {
+    Option::<Fr>::from(value.sqrt()).map(|y| if y.to_repr()[0] & 1 == 0 { y } else { -y })
+}
+
+impl Point {
+    /// `(1, y)` with the even `y`.
+    pub fn generator() -> Self {
+        let x = Fr::one();
+        Point {
+            x,
+            y: even_sqrt(x * x * x + curve_b()).unwrap(),
+        }
+    }
+
+    /// A point hashed from a fixed string, so nobody knows its discrete log.
+    pub fn auxiliary() -> Self {
+        (0u32..)
+            .find_map(|counter| {
+                let mut hasher = Keccak256::new();
+                hasher.update(b"halo2-playground schnorr auxiliary point");
+                hasher.update(counter.to_le_bytes());
+                let mut wide = [0u8; 64];
+                wide[..32].copy_from_slice(&hasher.finalize());
+                let x = Fr::from_bytes_wide(&wide);
+                even_sqrt(x * x * x + curve_b()).map(|y| Point { x, y })
+            })
+            .unwrap()
+    }
+
+    pub fn is_on_curve(&self) -> bool {
+        self.y * self.y == self.x * self.x * self.x + curve_b()
+    }
+
+    pub fn negate(&self) -> Self {
+        Point {
+            x: self.x,
+            y: -self.y,
+        }
+    }
+
+    pub fn add(&self, other: &Point) -> Option<Point> {
+        let lambda = if self.x == other.x {
+            if self.y != other.y || self.y == Fr::zero() {
+                return None;
+            }
+            Fr::from(3) * self.x * self.x * (self.y + self.y).invert().unwrap()
+        } else {
+            (other.y - self.y) * (other.x - self.x).invert().unwrap()
+        };
+        let x = lambda * lambda - self.x - other.x;
+        Some(Point {
+            x,
+            y: lambda * (self.x - x) - self.y,
+        })
+    }
+
+    /// `scalar` times this point, most significant bit first.
+    pub fn mul(&self, scalar: &Fq) -> Option<Point> {
+        let bits = bits(scalar);
+        bits.iter().rev().fold(None, |acc: Option<Point>, bit| {
+            let acc = acc.and_then(|acc| acc.add(&acc));
+            match (acc, *bit) {
+                (acc, false) => acc,
+                (Some(acc), true) => acc.add(self),
+                (None, true) => Some(*self),
+            }
+        })
+    }
+}
+
+/// The little-endian bits of a scalar.
+fn bits(scalar: &Fq) -> Vec<bool> {
+    let repr = scalar.to_repr();
+    (0..SCALAR_BITS)
+        .map(|i| (repr[i / 8] >> (i % 8)) & 1 == 1)
+        .collect()
+}
+
+/// A challenge as a scalar, which it always is since `r < q`.
+fn to_scalar(e: Fr) -> Fq {
+    Fq::from_repr(e.to_repr()).unwrap()
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Signature {
+    pub r: Point,
+    pub s: Fq,
+}
+
+pub fn public_key(secret: &Fq) -> Point {
+    Point::generator().mul(secret).expect("a nonzero secret")
+}
+
+pub fn challenge(r: &Point, public: &Point, message: Fr) -> Fr {
+    poseidon::Hash::<_, MySpec<WIDTH, RATE>, ConstantLength<5>, WIDTH, RATE>::init()
+        .hash([r.x, r.y, public.x, public.y, message])
+}
+
+/// Signs `message` under `secret` with the given `nonce`, which must never be reused.
+pub fn sign(secret: &Fq, message: Fr, nonce: &Fq) -> Signature {
+    let r = Point::generator().mul(nonce).expect("a nonzero nonce");
+    let e = to_scalar(challenge(&r, &public_key(secret), message));
+    Signature {
+        r,
+        s: *nonce + e * secret,
+    }
+}
+
+/// The off-circuit check the circuit proves.
+pub fn verify(public: &Point, message: Fr, signature: &Signature) -> bool {
+    let Signature { r, s } = signature;
+    if !public.is_on_curve() || !r.is_on_curve() {
+        return false;
+    }
+    let e = to_scalar(challenge(r, public, message));
+    let rhs = match public.mul(&e) {
+        Some(ep) => r.add(&ep),
+        None => Some(*r),
+    };
+    Point::generator().mul(s) == rhs
+}
+
+#[derive(Clone, Debug)]
+pub struct SchnorrConfig {
+    /// The decomposition's byte table; a circuit composing the config adds its own chips'
+    /// tables here before loading them.
+    pub tables: Tables,
+    pub ecc: EccConfig,
+    pub decompose: DecomposeConfig,
+    pub poseidon: Pow5Config<Fr, WIDTH, RATE>,
+    pub load: Column<Advice>,
+    pub instance: Column<Instance>,
+}
+
+impl SchnorrConfig {
+    pub fn configure(meta: &mut ConstraintSystem<Fr>) -> Self {
+        let state = (0..WIDTH).map(|_| meta.advice_column()).collect::<Vec<_>>();
+        let partial_sbox = meta.advice_column();
+        let rc_a = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        let rc_b = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        meta.enable_constant(rc_b[0]);
+        let load = meta.advice_column();
+        meta.enable_equality(load);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let mut tables = Tables::new();
+        SchnorrConfig {
+            ecc: EccChip::configure(meta, curve_b()),
+            decompose: DecomposeChip::configure(meta, &mut tables),
+            tables,
+            poseidon: Pow5Chip::configure::<MySpec<WIDTH, RATE>>(
+                meta,
+                state.try_into().unwrap(),
+                partial_sbox,
+                rc_a.try_into().unwrap(),
+                rc_b.try_into().unwrap(),
+            ),
+            load,
+            instance,
+        }
+    }
+}
+
+/// Proves knowledge of a signature `(R, s)` of the message in instance row 2 under the public
+/// key whose coordinates are in rows 0 and 1. `s` is taken modulo the group order, so its
+/// non-canonical encodings below `2^254` pass too.
+#[derive(Clone, Debug)]
+pub struct SchnorrCircuit {
+    pub signature: Value<Signature>,
+}
+
+impl Default for SchnorrCircuit {
+    fn default() -> Self {
+        Self {
+            signature: Value::unknown(),
+        }
+    }
+}
+
+impl SchnorrCircuit {
+    pub fn new(signature: Signature) -> Self {
+        Self {
+            signature: Value::known(signature),
+        }
+    }
+
+    pub fn instance(public: &Point, message: Fr) -> Vec<Fr> {
+        vec![public.x, public.y, message]
+    }
+}
+
+impl DescribeInstances for SchnorrCircuit {
+    fn instance_layout() -> InstanceLayout {
+        InstanceLayout::new("schnorr (grumpkin)")
+            .field(0, "p_x", Encoding::Field, "the public key's x")
+            .field(0, "p_y", Encoding::Field, "the public key's y")
+            .field(0, "message", Encoding::Field, "the signed message")
+    }
+}
+
+impl Circuit<Fr> for SchnorrCircuit {
+    type Config = SchnorrConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        SchnorrConfig::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let ecc = EccChip::construct(config.ecc.clone());
+        config.tables.load(&mut layouter)?;
+
+        let public = ecc.from_instance(layouter.namespace(|| "P"), config.instance, 0)?;
+        let message = layouter.assign_region(
+            || "message",
+            |mut region| {
+                region.assign_advice_from_instance(|| "m", config.instance, 2, config.load, 0)
+            },
+        )?;
+        assert_signature(&config, &mut layouter, &public, message, self.signature)
+    }
+}
+
+/// Constrains `signature` to be valid for `message` under `public`, whose coordinates are on
+/// the curve. The caller loads `config.tables` once for all signatures.
+pub fn assert_signature(
+    config: &SchnorrConfig,
+    layouter: &mut impl Layouter<Fr>,
+    public: &AssignedPoint<Fr>,
+    message: AssignedCell<Fr, Fr>,
+    signature: Value<Signature>,
+) -> Result<(), Error> {
+    let ecc = EccChip::construct(config.ecc.clone());
+    let decompose = DecomposeChip::construct(config.decompose.clone());
+    let r = ecc.witness(
+        layouter.namespace(|| "R"),
+        signature
+            .as_ref()
+            .map(|signature| (signature.r.x, signature.r.y)),
+    )?;
+    let hasher = Hash::<_, _, MySpec<WIDTH, RATE>, ConstantLength<5>, WIDTH, RATE>::init(
+        Pow5Chip::construct(config.poseidon.clone()),
+        layouter.namespace(|| "init challenge"),
+    )?;
+    let e = hasher.hash(
+        layouter.namespace(|| "challenge"),
+        [
+            r.x.clone(),
+            r.y.clone(),
+            public.x.clone(),
+            public.y.clone(),
+            message,
+        ],
+    )?;
+    // canonical, or e + r would be a second challenge
+    let e = decompose.decompose(layouter.namespace(|| "e bits"), &e, Decomposition::Bits)?;
+    let s = ecc.witness_bits(
+        layouter.namespace(|| "s bits"),
+        signature.map(|signature| bits(&signature.s)),
+        SCALAR_BITS,
+    )?;
+
+    let auxiliary = Point::auxiliary();
+    let coordinates = |point: Point| (point.x, point.y);
+    let d = ecc.constant(layouter.namespace(|| "D"), coordinates(auxiliary))?;
+    let dg = ecc.constant(
+        layouter.namespace(|| "D + G"),
+        coordinates(auxiliary.add(&Point::generator()).unwrap()),
+    )?;
+    let neg_public = ecc.negate(layouter.namespace(|| "-P"), public)?;
+    let dp = ecc.add(layouter.namespace(|| "D - P"), &d, &neg_public)?;
+    let dgp = ecc.add(layouter.namespace(|| "D + G - P"), &dg, &neg_public)?;
+    let table = [d, dg, dp, dgp];
+    let top = SCALAR_BITS - 1;
+    let mut acc = select(&ecc, layouter, &s[top], &e[top], &table)?;
+    for i in (0..top).rev() {
+        acc = ecc.double(layouter.namespace(|| "double"), &acc)?;
+        let addend = select(&ecc, layouter, &s[i], &e[i], &table)?;
+        acc = ecc.add(layouter.namespace(|| "add"), &acc, &addend)?;
+    }
+    // acc = s G - e P + (2^254 - 1) D
+    let scale = (0..SCALAR_BITS).fold(Fq::zero(), |acc, _| acc.double() + Fq::one());
+    let correction = auxiliary.mul(&scale).unwrap().negate();
+    let correction = ecc.constant(layouter.namespace(|| "correction"), coordinates(correction))?;
+    let point = ecc.add(layouter.namespace(|| "remove offset"), &acc, &correction)?;
+    ecc.assert_equal(layouter.namespace(|| "s G - e P = R"), &point, &r)
+}
+
+/// One of `D`, `D + G`, `D - P` and `D + G - P`, by the bits of `s` and `e`.
+fn select(
+    ecc: &EccChip<Fr>,
+    layouter: &mut impl Layouter<Fr>,
+    s: &AssignedCell<Fr, Fr>,
+    e: &AssignedCell<Fr, Fr>,
+    table: &[AssignedPoint<Fr>; 4],
+) -> Result<AssignedPoint<Fr>, Error> {
+    let [d, dg, dp, dgp] = table;
+    let without = ecc.select(layouter.namespace(|| "by s"), s, dg, d)?;
+    let with = ecc.select(layouter.namespace(|| "by s"), s, dgp, dp)?;
+    ecc.select(layouter.namespace(|| "by e"), e, &with, &without)
+}
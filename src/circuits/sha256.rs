@@ -0,0 +1,561 @@
+//! SHA-256 preimage circuit built from bytes rather than field arithmetic: every 32-bit word is
+//! four range-checked byte cells, the σ/Σ rotations and shifts and the Ch and Maj functions go
+//! through the byte ops chip's lookups, and additions mod 2^32 pack the bytes and carry into
+//! one cell. It is the bitwise-heavy counterpart of the Poseidon example, and needs
+//! `k >= byte_ops::MIN_K` for the byte tables.
+
+use std::convert::TryInto;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, Region, SimpleFloorPlanner, Value},
+    plonk::{
+        Advice, Circuit, Column, ConstraintSystem, Error, Expression, Fixed, Instance, Selector,
+        VirtualCells,
+    },
+    poly::Rotation,
+};
+
+use crate::{
+    chips::{
+        byte_ops::{ByteOp, ByteOpsChip, ByteOpsConfig},
+        constant_pool::ConstantPool,
+        tables::{Table, Tables},
+    },
+    schema::{DescribeInstances, Encoding, InstanceLayout},
+};
+
+/// Source of this module, hashed into the provenance of its keys.
+pub const SOURCE: &str = include_str!("sha256.rs");
+
+const IV: [u32; 8] = [
+    0x6a09e667, 0xbb67ae85, 0x3c6ef372, 0xa54ff53a, 0x510e527f, 0x9b05688c, 0x1f83d9ab, 0x5be0cd19,
+];
+
+const K: [u32; 64] = [
+    0x428a2f98, 0x71374491, 0xb5c0fbcf, 0xe9b5dba5, 0x3956c25b, 0x59f111f1, 0x923f82a4, 0xab1c5ed5,
+    0xd807aa98, 0x12835b01, 0x243185be, 0x550c7dc3, 0x72be5d74, 0x80deb1fe, 0x9bdc06a7, 0xc19bf174,
+    0xe49b69c1, 0xefbe4786, 0x0fc19dc6, 0x240ca1cc, 0x2de92c6f, 0x4a7484aa, 0x5cb0a9dc, 0x76f988da,
+    0x983e5152, 0xa831c66d, 0xb00327c8, 0xbf597fc7, 0xc6e00bf3, 0xd5a79147, 0x06ca6351, 0x14292967,
+    0x27b70a85, 0x2e1b2138, 0x4d2c6dfc, 0x53380d13, 0x650a7354, 0x766a0abb, 0x81c2c92e, 0x92722c85,
+    0xa2bfe8a1, 0xa81a664b, 0xc24b8b70, 0xc76c51a3, 0xd192e819, 0xd6990624, 0xf40e3585, 0x106aa070,
+    0x19a4c116, 0x1e376c08, 0x2748774c, 0x34b0bcb5, 0x391c0cb3, 0x4ed8aa4a, 0x5b9cca4f, 0x682e6ff3,
+    0x748f82ee, 0x78a5636f, 0x84c87814, 0x8cc70208, 0x90befffa, 0xa4506ceb, 0xbef9a3f7, 0xc67178f2,
+];
+
+/// The padded message, as big-endian words in blocks of 16.
+fn pad(message: &[u8]) -> Vec<u32> {
+    let mut bytes = message.to_vec();
+    bytes.push(0x80);
+    while bytes.len() % 64 != 56 {
+        bytes.push(0);
+    }
+    bytes.extend((8 * message.len() as u64).to_be_bytes());
+    bytes
+        .chunks(4)
+        .map(|word| u32::from_be_bytes(word.try_into().unwrap()))
+        .collect()
+}
+
+/// How the rotations and shifts of a σ function combine.
+#[derive(Clone, Copy, Debug)]
+enum Sigma {
+    /// `Σ0`, rotations by 2, 13 and 22.
+    UpperZero,
+    /// `Σ1`, rotations by 6, 11 and 25.
+    UpperOne,
+    /// `σ0`, rotations by 7 and 18 and a shift by 3.
+    LowerZero,
+    /// `σ1`, rotations by 17 and 19 and a shift by 10.
+    LowerOne,
+}
+
+impl Sigma {
+    /// Two rotations and a rotation, or a shift when the flag is set.
+    fn amounts(self) -> (u32, u32, u32, bool) {
+        match self {
+            Sigma::UpperZero => (2, 13, 22, false),
+            Sigma::UpperOne => (6, 11, 25, false),
+            Sigma::LowerZero => (7, 18, 3, true),
+            Sigma::LowerOne => (17, 19, 10, true),
+        }
+    }
+
+    fn apply(self, x: u32) -> u32 {
+        let (r1, r2, r3, shift) = self.amounts();
+        let third = if shift { x >> r3 } else { x.rotate_right(r3) };
+        x.rotate_right(r1) ^ x.rotate_right(r2) ^ third
+    }
+}
+
+fn compress(state: &mut [u32; 8], block: &[u32]) {
+    let mut w = block.to_vec();
+    for t in 16..64 {
+        let word = Sigma::LowerOne
+            .apply(w[t - 2])
+            .wrapping_add(w[t - 7])
+            .wrapping_add(Sigma::LowerZero.apply(w[t - 15]))
+            .wrapping_add(w[t - 16]);
+        w.push(word);
+    }
+    let [mut a, mut b, mut c, mut d, mut e, mut f, mut g, mut h] = *state;
+    for t in 0..64 {
+        let t1 = h
+            .wrapping_add(Sigma::UpperOne.apply(e))
+            .wrapping_add((e & f) ^ (!e & g))
+            .wrapping_add(K[t])
+            .wrapping_add(w[t]);
+        let t2 = Sigma::UpperZero
+            .apply(a)
+            .wrapping_add((a & b) ^ (a & c) ^ (b & c));
+        h = g;
+        g = f;
+        f = e;
+        e = d.wrapping_add(t1);
+        d = c;
+        c = b;
+        b = a;
+        a = t1.wrapping_add(t2);
+    }
+    for (word, new) in state.iter_mut().zip([a, b, c, d, e, f, g, h]) {
+        *word = word.wrapping_add(new);
+    }
+}
+
+/// SHA-256 of `message`, computed off-circuit.
+pub fn sha256(message: &[u8]) -> [u8; 32] {
+    let mut state = IV;
+    for block in pad(message).chunks(16) {
+        compress(&mut state, block);
+    }
+    let mut digest = [0; 32];
+    for (bytes, word) in digest.chunks_mut(4).zip(state.iter()) {
+        bytes.copy_from_slice(&word.to_be_bytes());
+    }
+    digest
+}
+
+#[derive(Clone, Debug)]
+pub struct Sha256Config {
+    pub tables: Tables,
+    pub byte_ops: ByteOpsConfig,
+    /// The little-endian bytes of a word, one term of a sum on each row.
+    pub bytes: [Column<Advice>; 4],
+    pub word: Column<Advice>,
+    pub carry: Column<Advice>,
+    pub addend: Column<Fixed>,
+    pub instance: Column<Instance>,
+    q_word: Selector,
+    q_add: Selector,
+}
+
+/// A 32-bit word as four cells known to hold bytes, least significant first.
+#[derive(Clone, Debug)]
+struct Word<F: FieldExt> {
+    bytes: Vec<AssignedCell<F, F>>,
+}
+
+impl<F: FieldExt> Word<F> {
+    fn value(&self) -> Value<u32> {
+        self.bytes.iter().rev().fold(Value::known(0), |acc, byte| {
+            acc.zip(byte.value())
+                .map(|(acc, byte)| acc << 8 | byte.get_lower_32() & 0xff)
+        })
+    }
+}
+
+/// `Σ 256^i byte_i` over the byte columns at `row`.
+fn packed<F: FieldExt>(
+    meta: &mut VirtualCells<'_, F>,
+    bytes: &[Column<Advice>; 4],
+    row: i32,
+) -> Expression<F> {
+    bytes
+        .iter()
+        .rev()
+        .fold(Expression::Constant(F::zero()), |acc, column| {
+            acc * Expression::Constant(F::from(256)) + meta.query_advice(*column, Rotation(row))
+        })
+}
+
+impl Sha256Config {
+    fn configure<F: FieldExt>(meta: &mut ConstraintSystem<F>) -> Self {
+        let [lhs, rhs, out] = [(); 3].map(|_| meta.advice_column());
+        let bytes = [(); 4].map(|_| meta.advice_column());
+        let word = meta.advice_column();
+        let carry = meta.advice_column();
+        let addend = meta.fixed_column();
+        let constants = meta.fixed_column();
+        let instance = meta.instance_column();
+        for column in bytes.iter().chain([&word]) {
+            meta.enable_equality(*column);
+        }
+        meta.enable_equality(instance);
+        meta.enable_constant(constants);
+        let mut tables = Tables::new();
+        let byte_ops = ByteOpsChip::configure(meta, &mut tables, lhs, rhs, out);
+        let byte = tables.column(meta, Table::BYTE);
+        let q_word = meta.complex_selector();
+        let q_add = meta.selector();
+
+        for column in bytes {
+            meta.lookup("word byte", |meta| {
+                let q = meta.query_selector(q_word);
+                vec![(q * meta.query_advice(column, Rotation::cur()), byte)]
+            });
+        }
+        meta.create_gate("word bytes", |meta| {
+            let q = meta.query_selector(q_word);
+            let word = meta.query_advice(word, Rotation::cur());
+            vec![q * (word - packed(meta, &bytes, 0))]
+        });
+        // four terms on rows 0 to 3 and the sum on row 4; the sum is below 5 * 2^32, so the
+        // carry and the output word are unique
+        meta.create_gate("add mod 2^32", |meta| {
+            let q = meta.query_selector(q_add);
+            let addend = meta.query_fixed(addend, Rotation::cur());
+            let sum = (0..4).fold(addend, |acc, row| acc + packed(meta, &bytes, row));
+            let out = meta.query_advice(word, Rotation(4));
+            let carry = meta.query_advice(carry, Rotation(4));
+            let range = (1..=4).fold(carry.clone(), |acc, c| {
+                acc * (carry.clone() - Expression::Constant(F::from(c)))
+            });
+            vec![
+                q.clone() * range,
+                q * (sum - out - carry * Expression::Constant(F::from(1u64 << 32))),
+            ]
+        });
+        Self {
+            tables,
+            byte_ops,
+            bytes,
+            word,
+            carry,
+            addend,
+            instance,
+            q_word,
+            q_add,
+        }
+    }
+
+    fn chip<F: FieldExt>(&self) -> ByteOpsChip<F> {
+        ByteOpsChip::construct(self.byte_ops.clone())
+    }
+
+    /// Assigns the bytes of `value` and the word at `row`, range checking the bytes.
+    fn word_row<F: FieldExt>(
+        &self,
+        region: &mut Region<'_, F>,
+        row: usize,
+        value: Value<u32>,
+    ) -> Result<(Word<F>, AssignedCell<F, F>), Error> {
+        self.q_word.enable(region, row)?;
+        let bytes = self
+            .bytes
+            .iter()
+            .enumerate()
+            .map(|(i, column)| {
+                region.assign_advice(
+                    || format!("byte {}", i),
+                    *column,
+                    row,
+                    || value.map(|v| F::from(((v >> (8 * i)) & 0xff) as u64)),
+                )
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let word = region.assign_advice(
+            || "word",
+            self.word,
+            row,
+            || value.map(|v| F::from(v as u64)),
+        )?;
+        Ok((Word { bytes }, word))
+    }
+
+    fn witness<F: FieldExt>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        value: Value<u32>,
+    ) -> Result<Word<F>, Error> {
+        layouter.assign_region(
+            || "message word",
+            |mut region| Ok(self.word_row(&mut region, 0, value)?.0),
+        )
+    }
+
+    fn constant<F: FieldExt>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        pool: &ConstantPool<F>,
+        value: u32,
+    ) -> Result<Word<F>, Error> {
+        let bytes = (0..4)
+            .map(|i| {
+                let byte = F::from(((value >> (8 * i)) & 0xff) as u64);
+                pool.constant(layouter.namespace(|| "byte"), byte)
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Word { bytes })
+    }
+
+    fn op<F: FieldExt>(
+        &self,
+        layouter: impl Layouter<F>,
+        op: ByteOp,
+        lhs: &Word<F>,
+        rhs: &Word<F>,
+    ) -> Result<Word<F>, Error> {
+        let bytes = self
+            .chip()
+            .assign_bytes(layouter, op, &lhs.bytes, &rhs.bytes)?;
+        Ok(Word { bytes })
+    }
+
+    /// `zero` is a cell constrained to 0, shifted in by `σ0` and `σ1`.
+    fn sigma<F: FieldExt>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        sigma: Sigma,
+        x: &Word<F>,
+        zero: &AssignedCell<F, F>,
+    ) -> Result<Word<F>, Error> {
+        let chip = self.chip();
+        let (r1, r2, r3, shift) = sigma.amounts();
+        let first = chip.rotate_right(layouter.namespace(|| "rotate"), &x.bytes, r1 as usize)?;
+        let second = chip.rotate_right(layouter.namespace(|| "rotate"), &x.bytes, r2 as usize)?;
+        let third = if shift {
+            chip.shift_right(layouter.namespace(|| "shift"), &x.bytes, r3 as usize, zero)?
+        } else {
+            chip.rotate_right(layouter.namespace(|| "rotate"), &x.bytes, r3 as usize)?
+        };
+        let partial = self.op(
+            layouter.namespace(|| "xor"),
+            ByteOp::Xor,
+            &Word { bytes: first },
+            &Word { bytes: second },
+        )?;
+        self.op(
+            layouter.namespace(|| "xor"),
+            ByteOp::Xor,
+            &partial,
+            &Word { bytes: third },
+        )
+    }
+
+    /// The sum of up to four words and `addend`, mod 2^32, with the cell of the packed sum.
+    fn add<F: FieldExt>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        terms: &[&Word<F>],
+        addend: u32,
+    ) -> Result<(Word<F>, AssignedCell<F, F>), Error> {
+        assert!(terms.len() <= 4, "at most four terms");
+        let sum = terms.iter().fold(Value::known(addend as u64), |acc, term| {
+            acc.zip(term.value()).map(|(acc, v)| acc + v as u64)
+        });
+        layouter.assign_region(
+            || "add",
+            |mut region| {
+                self.q_add.enable(&mut region, 0)?;
+                for row in 0..4 {
+                    for (i, column) in self.bytes.iter().enumerate() {
+                        match terms.get(row) {
+                            Some(term) => {
+                                term.bytes[i].copy_advice(|| "term", &mut region, *column, row)?;
+                            }
+                            None => {
+                                region.assign_advice_from_constant(
+                                    || "term",
+                                    *column,
+                                    row,
+                                    F::zero(),
+                                )?;
+                            }
+                        }
+                    }
+                }
+                region.assign_fixed(
+                    || "addend",
+                    self.addend,
+                    0,
+                    || Value::known(F::from(addend as u64)),
+                )?;
+                region.assign_advice(
+                    || "carry",
+                    self.carry,
+                    4,
+                    || sum.map(|s| F::from(s >> 32)),
+                )?;
+                self.word_row(&mut region, 4, sum.map(|s| s as u32))
+            },
+        )
+    }
+
+    /// The new state, each word with the cell of its packed value.
+    fn compress<F: FieldExt>(
+        &self,
+        mut layouter: impl Layouter<F>,
+        state: &[Word<F>],
+        block: &[Word<F>],
+        zero: &AssignedCell<F, F>,
+    ) -> Result<Vec<(Word<F>, AssignedCell<F, F>)>, Error> {
+        let mut w = block.to_vec();
+        for t in 16..64 {
+            let s0 = self.sigma(
+                layouter.namespace(|| "σ0"),
+                Sigma::LowerZero,
+                &w[t - 15],
+                zero,
+            )?;
+            let s1 = self.sigma(
+                layouter.namespace(|| "σ1"),
+                Sigma::LowerOne,
+                &w[t - 2],
+                zero,
+            )?;
+            let (word, _) = self.add(
+                layouter.namespace(|| "schedule"),
+                &[&s1, &w[t - 7], &s0, &w[t - 16]],
+                0,
+            )?;
+            w.push(word);
+        }
+        let mut v = state.to_vec();
+        for (t, word) in w.iter().enumerate() {
+            let (a, b, c, d, e, f, g, h) = (&v[0], &v[1], &v[2], &v[3], &v[4], &v[5], &v[6], &v[7]);
+            let s1 = self.sigma(layouter.namespace(|| "Σ1"), Sigma::UpperOne, e, zero)?;
+            // Ch(e, f, g) = g ^ (e & (f ^ g))
+            let f_xor_g = self.op(layouter.namespace(|| "ch"), ByteOp::Xor, f, g)?;
+            let masked = self.op(layouter.namespace(|| "ch"), ByteOp::And, e, &f_xor_g)?;
+            let ch = self.op(layouter.namespace(|| "ch"), ByteOp::Xor, g, &masked)?;
+            let (t1, _) = self.add(layouter.namespace(|| "t1"), &[h, &s1, &ch, word], K[t])?;
+            let s0 = self.sigma(layouter.namespace(|| "Σ0"), Sigma::UpperZero, a, zero)?;
+            // Maj(a, b, c) = (a & b) ^ (c & (a ^ b))
+            let a_xor_b = self.op(layouter.namespace(|| "maj"), ByteOp::Xor, a, b)?;
+            let both = self.op(layouter.namespace(|| "maj"), ByteOp::And, a, b)?;
+            let carried = self.op(layouter.namespace(|| "maj"), ByteOp::And, c, &a_xor_b)?;
+            let maj = self.op(layouter.namespace(|| "maj"), ByteOp::Xor, &both, &carried)?;
+            let (new_a, _) = self.add(layouter.namespace(|| "a"), &[&t1, &s0, &maj], 0)?;
+            let (new_e, _) = self.add(layouter.namespace(|| "e"), &[d, &t1], 0)?;
+            v = vec![
+                new_a,
+                a.clone(),
+                b.clone(),
+                c.clone(),
+                new_e,
+                e.clone(),
+                f.clone(),
+                g.clone(),
+            ];
+        }
+        state
+            .iter()
+            .zip(v.iter())
+            .map(|(s, v)| self.add(layouter.namespace(|| "state"), &[s, v], 0))
+            .collect()
+    }
+}
+
+/// Proves knowledge of a `LEN`-byte preimage whose SHA-256 digest is the public input, as
+/// eight big-endian 32-bit words in instance rows 0 to 7. `LEN` must be a multiple of 4, so
+/// the message fills whole words and the padding is constant.
+#[derive(Clone, Copy, Debug)]
+pub struct Sha256Circuit<const LEN: usize> {
+    pub preimage: Value<[u8; LEN]>,
+}
+
+impl<const LEN: usize> Default for Sha256Circuit<LEN> {
+    fn default() -> Self {
+        Self {
+            preimage: Value::unknown(),
+        }
+    }
+}
+
+impl<const LEN: usize> Sha256Circuit<LEN> {
+    pub fn new(preimage: [u8; LEN]) -> Self {
+        Self {
+            preimage: Value::known(preimage),
+        }
+    }
+
+    pub fn instance<F: FieldExt>(digest: &[u8; 32]) -> Vec<F> {
+        digest
+            .chunks(4)
+            .map(|word| F::from(u32::from_be_bytes(word.try_into().unwrap()) as u64))
+            .collect()
+    }
+}
+
+const DIGEST_WORDS: [&str; 8] = [
+    "digest_0", "digest_1", "digest_2", "digest_3", "digest_4", "digest_5", "digest_6", "digest_7",
+];
+
+impl<const LEN: usize> DescribeInstances for Sha256Circuit<LEN> {
+    fn instance_layout() -> InstanceLayout {
+        DIGEST_WORDS.iter().enumerate().fold(
+            InstanceLayout::new(format!("sha256 ({} bytes)", LEN)),
+            |layout, (i, name)| {
+                layout.field(
+                    0,
+                    name,
+                    Encoding::Integer { bits: 32 },
+                    format!("big-endian word {} of the SHA-256 digest", i),
+                )
+            },
+        )
+    }
+}
+
+impl<F: FieldExt, const LEN: usize> Circuit<F> for Sha256Circuit<LEN> {
+    type Config = Sha256Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        Sha256Config::configure(meta)
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        assert_eq!(LEN % 4, 0, "the preimage fills whole words");
+        config.tables.load(&mut layouter)?;
+        let pool = ConstantPool::new(config.word);
+        let zero = pool.constant(layouter.namespace(|| "zero"), F::zero())?;
+        let padded = pad(&[0; LEN]);
+        let mut words = Vec::with_capacity(padded.len());
+        for (i, padding) in padded.iter().enumerate() {
+            let word = if i < LEN / 4 {
+                let value = self.preimage.map(|preimage| {
+                    u32::from_be_bytes(preimage[4 * i..4 * i + 4].try_into().unwrap())
+                });
+                config.witness(layouter.namespace(|| "preimage"), value)?
+            } else {
+                config.constant(layouter.namespace(|| "padding"), &pool, *padding)?
+            };
+            words.push(word);
+        }
+        let mut state = IV
+            .iter()
+            .map(|iv| config.constant(layouter.namespace(|| "iv"), &pool, *iv))
+            .collect::<Result<Vec<_>, _>>()?;
+        let mut digest = vec![];
+        for block in words.chunks(16) {
+            let compressed =
+                config.compress(layouter.namespace(|| "compress"), &state, block, &zero)?;
+            let (words, cells): (Vec<_>, Vec<_>) = compressed.into_iter().unzip();
+            state = words;
+            digest = cells;
+        }
+        for (row, word) in digest.iter().enumerate() {
+            layouter.constrain_instance(word.cell(), config.instance, row)?;
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,241 @@
+use std::convert::TryInto;
+
+use halo2_gadgets::poseidon::{primitives::ConstantLength, Hash, Pow5Chip, Pow5Config};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
+    poly::Rotation,
+};
+
+use crate::{
+    chips::branch::{BranchChip, BranchConfig, Condition},
+    merkle::MerklePath,
+    poseidon::MySpec,
+    schema::{DescribeInstances, Encoding, InstanceLayout},
+};
+
+/// Source of this module, hashed into the provenance of its keys.
+pub const SOURCE: &str = include_str!("smt.rs");
+
+const WIDTH: usize = 3;
+const RATE: usize = 2;
+
+#[derive(Debug, Clone)]
+pub struct SmtConfig {
+    pub load: Column<Advice>,
+    pub bit: Column<Advice>,
+    pub acc: Column<Advice>,
+    pub q_first_bit: Selector,
+    pub q_next_bit: Selector,
+    pub instance: Column<Instance>,
+    pub branch: BranchConfig,
+    pub poseidon_config: Pow5Config<Fr, WIDTH, RATE>,
+}
+
+/// Proves that the leaf at the public `DEPTH`-bit key (instance row 1) of the sparse Merkle
+/// tree with root instance row 0 is instance row 2. A zero value proves the key absent. Unlike
+/// `MerklePathCircuit`, the directions are the key's bits, decomposed in the circuit, so the
+/// position is part of the statement.
+#[derive(Clone, Debug)]
+pub struct SmtCircuit<const DEPTH: usize> {
+    pub key: Value<u64>,
+    pub path: Value<MerklePath>,
+}
+
+impl<const DEPTH: usize> Default for SmtCircuit<DEPTH> {
+    fn default() -> Self {
+        Self {
+            key: Value::unknown(),
+            path: Value::unknown(),
+        }
+    }
+}
+
+impl<const DEPTH: usize> SmtCircuit<DEPTH> {
+    pub fn new(key: u64, path: MerklePath) -> Self {
+        assert_eq!(path.depth(), DEPTH, "a path of depth {}", DEPTH);
+        Self {
+            key: Value::known(key),
+            path: Value::known(path),
+        }
+    }
+
+    pub fn instance(root: Fr, key: u64, value: Fr) -> Vec<Fr> {
+        vec![root, Fr::from(key), value]
+    }
+
+    /// Assigns the key's bits, most significant first, with a running sum ending in the key.
+    fn decompose(
+        &self,
+        config: &SmtConfig,
+        mut layouter: impl Layouter<Fr>,
+        key: &AssignedCell<Fr, Fr>,
+    ) -> Result<Vec<AssignedCell<Fr, Fr>>, Error> {
+        layouter.assign_region(
+            || "key bits",
+            |mut region| {
+                let mut bits = Vec::with_capacity(DEPTH);
+                let mut acc = None;
+                for row in 0..DEPTH {
+                    let shift = DEPTH - 1 - row;
+                    let bit = self.key.map(|key| Fr::from((key >> shift) & 1));
+                    let sum = self.key.map(|key| Fr::from(key >> shift));
+                    if row == 0 {
+                        config.q_first_bit.enable(&mut region, row)?;
+                    } else {
+                        config.q_next_bit.enable(&mut region, row)?;
+                    }
+                    bits.push(region.assign_advice(|| "bit", config.bit, row, || bit)?);
+                    acc = Some(region.assign_advice(|| "acc", config.acc, row, || sum)?);
+                }
+                if let Some(acc) = acc {
+                    region.constrain_equal(acc.cell(), key.cell())?;
+                }
+                // least significant first, one per level from the leaf up
+                bits.reverse();
+                Ok(bits)
+            },
+        )
+    }
+}
+
+impl<const DEPTH: usize> DescribeInstances for SmtCircuit<DEPTH> {
+    fn instance_layout() -> InstanceLayout {
+        InstanceLayout::new(format!("smt (depth {})", DEPTH))
+            .field(0, "root", Encoding::Field, "root of the sparse Merkle tree")
+            .field(
+                0,
+                "key",
+                Encoding::Integer { bits: DEPTH as u32 },
+                "position of the leaf",
+            )
+            .field(
+                0,
+                "value",
+                Encoding::Field,
+                "leaf at the key, 0 to prove the key absent",
+            )
+    }
+}
+
+impl<const DEPTH: usize> Circuit<Fr> for SmtCircuit<DEPTH> {
+    type Config = SmtConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let state = (0..WIDTH).map(|_| meta.advice_column()).collect::<Vec<_>>();
+        let partial_sbox = meta.advice_column();
+        let rc_a = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        let rc_b = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        meta.enable_constant(rc_b[0]);
+        let load = meta.advice_column();
+        let bit = meta.advice_column();
+        let acc = meta.advice_column();
+        for column in [load, bit, acc] {
+            meta.enable_equality(column);
+        }
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        let q_first_bit = meta.selector();
+        let q_next_bit = meta.selector();
+        // booleanity comes from the branch rows the bits are copied into
+        meta.create_gate("key decomposition", |meta| {
+            let q_first = meta.query_selector(q_first_bit);
+            let q_next = meta.query_selector(q_next_bit);
+            let bit = meta.query_advice(bit, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            let acc_prev = meta.query_advice(acc, Rotation::prev());
+            vec![
+                q_first * (acc.clone() - bit.clone()),
+                q_next * (acc - acc_prev * Fr::from(2) - bit),
+            ]
+        });
+
+        SmtConfig {
+            load,
+            bit,
+            acc,
+            q_first_bit,
+            q_next_bit,
+            instance,
+            branch: BranchChip::configure(meta),
+            poseidon_config: Pow5Chip::configure::<MySpec<WIDTH, RATE>>(
+                meta,
+                state.try_into().unwrap(),
+                partial_sbox,
+                rc_a.try_into().unwrap(),
+                rc_b.try_into().unwrap(),
+            ),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let branch = BranchChip::construct(config.branch.clone());
+        let (key, value, siblings) = layouter.assign_region(
+            || "load key, value and siblings",
+            |mut region| {
+                let key = region.assign_advice_from_instance(
+                    || "key",
+                    config.instance,
+                    1,
+                    config.load,
+                    0,
+                )?;
+                let value = region.assign_advice_from_instance(
+                    || "value",
+                    config.instance,
+                    2,
+                    config.load,
+                    1,
+                )?;
+                let siblings = (0..DEPTH)
+                    .map(|level| {
+                        region.assign_advice(
+                            || format!("sibling {}", level),
+                            config.load,
+                            2 + level,
+                            || self.path.as_ref().map(|path| path.siblings[level]),
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok((key, value, siblings))
+            },
+        )?;
+        let bits = if DEPTH == 0 {
+            // nothing to decompose, the only key is 0
+            layouter.constrain_instance(key.cell(), config.instance, 1)?;
+            vec![]
+        } else {
+            self.decompose(&config, layouter.namespace(|| "decompose key"), &key)?
+        };
+
+        let mut node = value;
+        for (level, (sibling, bit)) in siblings.iter().zip(bits.iter()).enumerate() {
+            let (_, pair) = branch.select_all(
+                layouter.namespace(|| format!("order level {}", level)),
+                Condition::Copy(bit),
+                &[(sibling, &node), (&node, sibling)],
+            )?;
+            let hasher = Hash::<_, _, MySpec<WIDTH, RATE>, ConstantLength<2>, WIDTH, RATE>::init(
+                Pow5Chip::construct(config.poseidon_config.clone()),
+                layouter.namespace(|| format!("init level {}", level)),
+            )?;
+            node = hasher.hash(
+                layouter.namespace(|| format!("hash level {}", level)),
+                pair.try_into().unwrap(),
+            )?;
+        }
+
+        layouter.constrain_instance(node.cell(), config.instance, 0)
+    }
+}
@@ -0,0 +1,139 @@
+use std::convert::TryInto;
+
+use halo2_gadgets::poseidon::{primitives::ConstantLength, Hash, Pow5Chip, Pow5Config};
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+use crate::{
+    circuits::poseidon::PoseidonHashCircuit,
+    poseidon::MySpec,
+    schema::{DescribeInstances, DescribeWitness, Encoding, InstanceLayout, WitnessLayout},
+};
+
+/// Source of this module, hashed into the provenance of its keys.
+pub const SOURCE: &str = include_str!("transition.rs");
+
+const WIDTH: usize = 3;
+const RATE: usize = 2;
+
+#[derive(Debug, Clone)]
+pub struct StateTransitionConfig {
+    pub input: [Column<Advice>; RATE],
+    pub instance: Column<Instance>,
+    pub poseidon_config: Pow5Config<Fr, WIDTH, RATE>,
+}
+
+/// Proves that the public state root in instance row 1 is `Poseidon(root_in, update)`, where
+/// `root_in` is instance row 0 and `update` is private.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct StateTransitionCircuit {
+    pub update: Value<Fr>,
+}
+
+impl StateTransitionCircuit {
+    pub fn new(update: Fr) -> Self {
+        Self {
+            update: Value::known(update),
+        }
+    }
+
+    pub fn next_root(root: Fr, update: Fr) -> Fr {
+        PoseidonHashCircuit::<Fr, MySpec<WIDTH, RATE>, WIDTH, RATE, 2>::hash([root, update])
+    }
+}
+
+impl DescribeInstances for StateTransitionCircuit {
+    fn instance_layout() -> InstanceLayout {
+        InstanceLayout::new("transition")
+            .field(
+                0,
+                "root_in",
+                Encoding::Field,
+                "state root before the update",
+            )
+            .field(
+                0,
+                "root_out",
+                Encoding::Field,
+                "Poseidon(root_in, update) for the private update",
+            )
+    }
+}
+
+impl DescribeWitness for StateTransitionCircuit {
+    fn witness_layout() -> WitnessLayout {
+        WitnessLayout::new("transition").field(
+            "update",
+            Encoding::Field,
+            "update hashed into the state root",
+        )
+    }
+}
+
+impl Circuit<Fr> for StateTransitionCircuit {
+    type Config = StateTransitionConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let state = (0..WIDTH).map(|_| meta.advice_column()).collect::<Vec<_>>();
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        let partial_sbox = meta.advice_column();
+
+        let rc_a = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        let rc_b = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+
+        meta.enable_constant(rc_b[0]);
+
+        Self::Config {
+            input: state[..RATE].try_into().unwrap(),
+            instance,
+            poseidon_config: Pow5Chip::configure::<MySpec<WIDTH, RATE>>(
+                meta,
+                state.try_into().unwrap(),
+                partial_sbox,
+                rc_a.try_into().unwrap(),
+                rc_b.try_into().unwrap(),
+            ),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let chip = Pow5Chip::construct(config.poseidon_config.clone());
+
+        let message = layouter.assign_region(
+            || "load root and update",
+            |mut region| {
+                let root = region.assign_advice_from_instance(
+                    || "root in",
+                    config.instance,
+                    0,
+                    config.input[0],
+                    0,
+                )?;
+                let update =
+                    region.assign_advice(|| "update", config.input[1], 0, || self.update)?;
+                Ok([root, update])
+            },
+        )?;
+
+        let hasher = Hash::<_, _, MySpec<WIDTH, RATE>, ConstantLength<2>, WIDTH, RATE>::init(
+            chip,
+            layouter.namespace(|| "init"),
+        )?;
+        let root_out = hasher.hash(layouter.namespace(|| "hash"), message)?;
+
+        layouter.constrain_instance(root_out.cell(), config.instance, 1)
+    }
+}
@@ -0,0 +1,159 @@
+use std::{convert::TryInto, marker::PhantomData};
+
+use halo2_gadgets::poseidon::{primitives::Spec, Pow5Chip, Pow5Config};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+use crate::{
+    chips::var_len::{var_len_hash, VarLenChip, VarLenConfig},
+    schema::{DescribeInstances, Encoding, InstanceLayout},
+};
+
+/// Source of this module, hashed into the provenance of its keys.
+pub const SOURCE: &str = include_str!("var_len.rs");
+
+#[derive(Debug, Clone)]
+pub struct VarLenHashConfig<F: FieldExt, const WIDTH: usize, const RATE: usize> {
+    pub var_len: VarLenConfig,
+    pub instance: Column<Instance>,
+    pub poseidon_config: Pow5Config<F, WIDTH, RATE>,
+}
+
+/// Proves knowledge of a message of at most `N - 1` elements whose padded Poseidon hash under
+/// `S` is instance row 0 and whose length is instance row 1. One set of keys covers every
+/// length up to the capacity.
+#[derive(Clone, Debug)]
+pub struct VarLenHashCircuit<F, S, const WIDTH: usize, const RATE: usize, const N: usize>
+where
+    F: FieldExt,
+    S: Spec<F, WIDTH, RATE> + Clone + Copy,
+{
+    pub message: Value<Vec<F>>,
+    _spec: PhantomData<S>,
+}
+
+impl<F, S, const WIDTH: usize, const RATE: usize, const N: usize> Default
+    for VarLenHashCircuit<F, S, WIDTH, RATE, N>
+where
+    F: FieldExt,
+    S: Spec<F, WIDTH, RATE> + Clone + Copy,
+{
+    fn default() -> Self {
+        Self {
+            message: Value::unknown(),
+            _spec: PhantomData,
+        }
+    }
+}
+
+impl<F, S, const WIDTH: usize, const RATE: usize, const N: usize>
+    VarLenHashCircuit<F, S, WIDTH, RATE, N>
+where
+    F: FieldExt,
+    S: Spec<F, WIDTH, RATE> + Clone + Copy,
+{
+    /// The longest message the circuit takes.
+    pub const CAPACITY: usize = N - 1;
+
+    pub fn new(message: Vec<F>) -> Self {
+        assert!(
+            message.len() <= Self::CAPACITY,
+            "at most {} elements",
+            Self::CAPACITY
+        );
+        Self {
+            message: Value::known(message),
+            _spec: PhantomData,
+        }
+    }
+
+    /// The instance column: the hash, then the length.
+    pub fn instance(message: &[F]) -> Vec<F> {
+        vec![
+            var_len_hash::<F, S, WIDTH, RATE, N>(message),
+            F::from(message.len() as u64),
+        ]
+    }
+}
+
+impl<F, S, const WIDTH: usize, const RATE: usize, const N: usize> DescribeInstances
+    for VarLenHashCircuit<F, S, WIDTH, RATE, N>
+where
+    F: FieldExt,
+    S: Spec<F, WIDTH, RATE> + Clone + Copy,
+{
+    fn instance_layout() -> InstanceLayout {
+        InstanceLayout::new(format!(
+            "var_len (width {}, rate {}, up to {} elements)",
+            WIDTH,
+            RATE,
+            N - 1
+        ))
+        .field(
+            0,
+            "hash",
+            Encoding::Field,
+            "Poseidon hash of the private message padded with 1 and zeros",
+        )
+        .field(
+            0,
+            "length",
+            Encoding::Integer { bits: 64 },
+            format!("number of message elements, at most {}", N - 1),
+        )
+    }
+}
+
+impl<F, S, const WIDTH: usize, const RATE: usize, const N: usize> Circuit<F>
+    for VarLenHashCircuit<F, S, WIDTH, RATE, N>
+where
+    F: FieldExt,
+    S: Spec<F, WIDTH, RATE> + Copy + Clone,
+{
+    type Config = VarLenHashConfig<F, WIDTH, RATE>;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let state = (0..WIDTH).map(|_| meta.advice_column()).collect::<Vec<_>>();
+        let partial_sbox = meta.advice_column();
+        let rc_a = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        let rc_b = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        meta.enable_constant(rc_b[0]);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+
+        VarLenHashConfig {
+            var_len: VarLenChip::<F, N>::configure(meta),
+            instance,
+            poseidon_config: Pow5Chip::configure::<S>(
+                meta,
+                state.try_into().unwrap(),
+                partial_sbox,
+                rc_a.try_into().unwrap(),
+                rc_b.try_into().unwrap(),
+            ),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = VarLenChip::<F, N>::construct(config.var_len);
+        let (hash, len) = chip.hash::<S, WIDTH, RATE>(
+            layouter.namespace(|| "var_len hash"),
+            config.poseidon_config,
+            self.message.as_ref().map(Vec::as_slice),
+        )?;
+        layouter.constrain_instance(hash.cell(), config.instance, 0)?;
+        layouter.constrain_instance(len.cell(), config.instance, 1)
+    }
+}
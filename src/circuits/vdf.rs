@@ -0,0 +1,174 @@
+//! Iterated MiMC-style VDF over BN254's scalar field, in the style of MinRoot:
+//! `x_{i+1} = (x_i + i)^(1/5)`. Each fifth root is a full exponentiation, while its check is
+//! `x_{i+1}^5 = x_i + i`, so evaluating takes `T` sequential exponentiations and verifying, in
+//! or out of the circuit, takes `T` cheap ones. Fifth roots are used since `gcd(5, r - 1) = 1`
+//! makes `x^5` a permutation of Fr (unlike cubes, as `3 | r - 1`).
+
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Fixed, Instance, Selector},
+    poly::Rotation,
+};
+
+use crate::schema::{DescribeInstances, Encoding, InstanceLayout};
+
+/// Source of this module, hashed into the provenance of its keys.
+pub const SOURCE: &str = include_str!("vdf.rs");
+
+/// `5^-1 mod (r - 1)`, as little-endian limbs.
+const FIFTH_ROOT_EXPONENT: [u64; 4] = [
+    0xcfe7_f7a9_8ccc_cccd,
+    0x535c_b9d3_9494_5a0d,
+    0x9373_6af8_679a_ad17,
+    0x26b6_a528_b427_b354,
+];
+
+pub fn fifth_root(x: Fr) -> Fr {
+    x.pow_vartime(&FIFTH_ROOT_EXPONENT)
+}
+
+fn pow5(x: Fr) -> Fr {
+    x.square().square() * x
+}
+
+/// The slow direction: `steps` sequential fifth roots.
+pub fn vdf_eval(x: Fr, steps: usize) -> Fr {
+    (0..steps).fold(x, |x, i| fifth_root(x + Fr::from(i as u64)))
+}
+
+/// The fast direction, walking back from `y` to `x`.
+pub fn vdf_verify(x: Fr, y: Fr, steps: usize) -> bool {
+    (0..steps)
+        .rev()
+        .fold(y, |y, i| pow5(y) - Fr::from(i as u64))
+        == x
+}
+
+#[derive(Clone, Debug)]
+pub struct VdfConfig {
+    pub state: Column<Advice>,
+    pub round_constant: Column<Fixed>,
+    pub instance: Column<Instance>,
+    pub s_step: Selector,
+}
+
+/// Proves `y = vdf_eval(x, steps)` with instance rows 0: `x`, 1: `y` and 2: `steps`. `steps`
+/// is fixed at keygen, the instance row only makes it part of the statement. The trace follows
+/// from `x`, so the circuit carries no witness.
+#[derive(Clone, Debug)]
+pub struct VdfCircuit {
+    pub steps: usize,
+}
+
+impl VdfCircuit {
+    pub fn new(steps: usize) -> Self {
+        Self { steps }
+    }
+
+    pub fn instance(x: Fr, y: Fr, steps: usize) -> Vec<Fr> {
+        vec![x, y, Fr::from(steps as u64)]
+    }
+}
+
+impl DescribeInstances for VdfCircuit {
+    fn instance_layout() -> InstanceLayout {
+        InstanceLayout::new("vdf")
+            .field(0, "x", Encoding::Field, "VDF input")
+            .field(
+                0,
+                "y",
+                Encoding::Field,
+                "VDF output after `steps` iterations",
+            )
+            .field(
+                0,
+                "steps",
+                Encoding::Integer { bits: 64 },
+                "number of iterations, must match the one fixed at keygen",
+            )
+    }
+}
+
+impl Circuit<Fr> for VdfCircuit {
+    type Config = VdfConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let state = meta.advice_column();
+        let round_constant = meta.fixed_column();
+        let constants = meta.fixed_column();
+        let instance = meta.instance_column();
+        let s_step = meta.selector();
+
+        meta.enable_equality(state);
+        meta.enable_equality(instance);
+        meta.enable_constant(constants);
+
+        meta.create_gate("fifth root", |meta| {
+            let cur = meta.query_advice(state, Rotation::cur());
+            let next = meta.query_advice(state, Rotation::next());
+            let c = meta.query_fixed(round_constant, Rotation::cur());
+            let s_step = meta.query_selector(s_step);
+            let next2 = next.clone() * next.clone();
+            vec![s_step * (next2.clone() * next2 * next - cur - c)]
+        });
+
+        VdfConfig {
+            state,
+            round_constant,
+            instance,
+            s_step,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let y = layouter.assign_region(
+            || "vdf",
+            |mut region| {
+                let mut state = region.assign_advice_from_instance(
+                    || "x",
+                    config.instance,
+                    0,
+                    config.state,
+                    0,
+                )?;
+                for i in 0..self.steps {
+                    let c = Fr::from(i as u64);
+                    config.s_step.enable(&mut region, i)?;
+                    region.assign_fixed(
+                        || "round constant",
+                        config.round_constant,
+                        i,
+                        || Value::known(c),
+                    )?;
+                    let value = state.value().map(|x| fifth_root(*x + c));
+                    state = region.assign_advice(|| "state", config.state, i + 1, || value)?;
+                }
+                Ok(state)
+            },
+        )?;
+        layouter.constrain_instance(y.cell(), config.instance, 1)?;
+        let steps = layouter.assign_region(
+            || "steps",
+            |mut region| {
+                region.assign_advice_from_constant(
+                    || "steps",
+                    config.state,
+                    0,
+                    Fr::from(self.steps as u64),
+                )
+            },
+        )?;
+        layouter.constrain_instance(steps.cell(), config.instance, 2)
+    }
+}
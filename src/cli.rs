@@ -0,0 +1,505 @@
+//! The file-based operations behind the `playground` CLI, as library calls so scenario tests
+//! and other front-ends can drive them without a process. Errors are [`PlaygroundError`]s whose
+//! messages are for the user, prefixed with the file they concern.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::{Duration, Instant},
+};
+
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{keygen_pk, keygen_vk, Circuit, ProvingKey, VerifyingKey},
+    poly::{commitment::Params, kzg::commitment::ParamsKZG},
+};
+
+use crate::{
+    audit_log::{AuditLog, Operation},
+    batch::{Batch, BatchError},
+    budget::{prove_within, BudgetError},
+    bundle::{Bundle, EnvelopeError, Metadata, ProofBundle, Verifier as BundleVerifier},
+    chips::standard_plonk,
+    circuits::{self, batch_map::BatchMapCircuit, mul::MulCircuit, poseidon::PoseidonHashCircuit},
+    dev,
+    encoding::{
+        decode_base64, decode_hex, decode_instances, encode_base64, encode_hex, encode_instances,
+        parse_columns, Reduction,
+    },
+    entropy::OsEntropy,
+    error::PlaygroundError,
+    fmt::to_hex_padded,
+    limits::ResourceLimits,
+    poseidon::{self, MySpec},
+    provenance::{check_provenance, record_provenance, source_hash, Provenance},
+    prover::{run_proof, ProofOptions},
+    schema::{DescribeInstances, DescribeWitness},
+    storage,
+    verifier::{check_digest, check_proof, vk_digest},
+};
+
+pub type PoseidonCircuit = PoseidonHashCircuit<Fr, MySpec<3, 2>, 3, 2, 2>;
+/// The batch of two instance columns the CLI proves.
+pub type BatchMap = BatchMapCircuit<Fr, 4>;
+
+/// The params and verifying key files an operation works with.
+#[derive(Clone, Debug)]
+pub struct KeyFiles {
+    pub params: PathBuf,
+    pub vk: PathBuf,
+}
+
+/// A circuit the CLI can build from a witness file.
+pub trait CliCircuit: Circuit<Fr> + DescribeInstances + DescribeWitness {
+    const NAME: &'static str;
+    /// Sources whose hash is recorded with the keys.
+    const SOURCES: &'static [&'static str];
+    fn empty() -> Self;
+    /// `witness` has been checked against `witness_layout`.
+    fn from_witness(witness: &[Fr]) -> Result<Self, String>;
+    fn public_instances(witness: &[Fr]) -> Vec<Vec<Fr>>;
+}
+
+impl CliCircuit for MulCircuit<Fr> {
+    const NAME: &'static str = "mul";
+    const SOURCES: &'static [&'static str] = &[circuits::mul::SOURCE, standard_plonk::SOURCE];
+
+    fn empty() -> Self {
+        MulCircuit::default()
+    }
+
+    fn from_witness(witness: &[Fr]) -> Result<Self, String> {
+        match witness {
+            [a, b] => Ok(MulCircuit::new(*a, *b)),
+            _ => Err("the mul witness is two values: a b".to_string()),
+        }
+    }
+
+    fn public_instances(witness: &[Fr]) -> Vec<Vec<Fr>> {
+        vec![MulCircuit::instance(witness[0] * witness[1])]
+    }
+}
+
+/// Every width, rate and length, all named `poseidon`; [`crate::config`] picks one from a
+/// supported matrix.
+impl<const WIDTH: usize, const RATE: usize, const L: usize> CliCircuit
+    for PoseidonHashCircuit<Fr, MySpec<WIDTH, RATE>, WIDTH, RATE, L>
+{
+    const NAME: &'static str = "poseidon";
+    const SOURCES: &'static [&'static str] = &[circuits::poseidon::SOURCE, poseidon::SOURCE];
+
+    fn empty() -> Self {
+        Self::new([Fr::zero(); L]).without_witnesses()
+    }
+
+    fn from_witness(witness: &[Fr]) -> Result<Self, String> {
+        let message: [Fr; L] = witness
+            .try_into()
+            .map_err(|_| format!("the poseidon witness is a {}-value message", L))?;
+        Ok(Self::new(message))
+    }
+
+    fn public_instances(witness: &[Fr]) -> Vec<Vec<Fr>> {
+        let message: [Fr; L] = witness
+            .try_into()
+            .expect("checked against the witness layout");
+        vec![vec![Self::hash(message)]]
+    }
+}
+
+/// The witness is the inputs, both instance columns are computed from them.
+impl<const N: usize> CliCircuit for BatchMapCircuit<Fr, N> {
+    const NAME: &'static str = "batch_map";
+    const SOURCES: &'static [&'static str] = &[circuits::batch_map::SOURCE];
+
+    fn empty() -> Self {
+        Self::new()
+    }
+
+    fn from_witness(witness: &[Fr]) -> Result<Self, String> {
+        if witness.len() != N {
+            return Err(format!("the batch_map witness is {} inputs", N));
+        }
+        Ok(Self::new())
+    }
+
+    fn public_instances(witness: &[Fr]) -> Vec<Vec<Fr>> {
+        let inputs: [Fr; N] = witness
+            .try_into()
+            .expect("checked against the witness layout");
+        Self::instance(inputs)
+    }
+}
+
+pub fn read_params(path: &Path) -> Result<ParamsKZG<Bn256>, PlaygroundError> {
+    storage::read_params(path).map_err(PlaygroundError::io(path))
+}
+
+/// Reads a verifying key, warning when it wasn't generated from the current circuit source.
+pub fn read_vk<C: CliCircuit>(
+    path: &Path,
+    params: &ParamsKZG<Bn256>,
+) -> Result<VerifyingKey<G1Affine>, PlaygroundError> {
+    let vk = storage::read_vk::<C>(path, params).map_err(PlaygroundError::io(path))?;
+    match check_provenance(path, &source_hash(C::SOURCES), &vk) {
+        Ok(Provenance::Current) => {}
+        Ok(provenance) => eprintln!("warning: {}: {}", path.display(), provenance),
+        Err(err) => eprintln!("warning: {}: provenance: {}", path.display(), err),
+    }
+    Ok(vk)
+}
+
+fn has_extension(path: &Path, extensions: &[&str]) -> bool {
+    path.extension()
+        .and_then(|ext| ext.to_str())
+        .map_or(false, |ext| extensions.contains(&ext))
+}
+
+/// Instances files are one column per line, or JSON (see `encode_instances`) when named
+/// `.json`.
+pub fn read_values(path: &Path) -> Result<Vec<Vec<Fr>>, PlaygroundError> {
+    let text = fs::read_to_string(path).map_err(PlaygroundError::io(path))?;
+    if has_extension(path, &["json"]) {
+        decode_instances(&text, Reduction::Reject)
+    } else {
+        parse_columns(&text, Reduction::Reject)
+    }
+    .map_err(|e| PlaygroundError::serialization(path, e))
+}
+
+pub fn read_witness<C: CliCircuit>(path: &Path) -> Result<Vec<Fr>, PlaygroundError> {
+    let text = fs::read_to_string(path).map_err(PlaygroundError::io(path))?;
+    C::witness_layout()
+        .read(&text)
+        .map_err(|e| PlaygroundError::serialization(path, e))
+}
+
+pub fn write_values(path: &Path, columns: &[Vec<Fr>]) -> Result<(), PlaygroundError> {
+    let text: String = if has_extension(path, &["json"]) {
+        encode_instances(columns) + "\n"
+    } else {
+        columns
+            .iter()
+            .map(|column| {
+                let values: Vec<_> = column.iter().map(to_hex_padded).collect();
+                values.join(" ") + "\n"
+            })
+            .collect()
+    };
+    fs::write(path, text).map_err(PlaygroundError::io(path))
+}
+
+/// Proof files are the raw bytes, or hex or base64 text when named `.hex` or `.b64`.
+pub fn read_proof(path: &Path) -> Result<Vec<u8>, PlaygroundError> {
+    if has_extension(path, &["hex", "b64"]) {
+        let text = fs::read_to_string(path).map_err(PlaygroundError::io(path))?;
+        if has_extension(path, &["hex"]) {
+            decode_hex(&text)
+        } else {
+            decode_base64(&text)
+        }
+        .map_err(|e| PlaygroundError::serialization(path, e))
+    } else {
+        fs::read(path).map_err(PlaygroundError::io(path))
+    }
+}
+
+pub fn write_proof(path: &Path, proof: &[u8]) -> Result<(), PlaygroundError> {
+    let result = if has_extension(path, &["hex"]) {
+        fs::write(path, encode_hex(proof) + "\n")
+    } else if has_extension(path, &["b64"]) {
+        fs::write(path, encode_base64(proof) + "\n")
+    } else {
+        fs::write(path, proof)
+    };
+    result.map_err(PlaygroundError::io(path))
+}
+
+/// Where `prove` records the [`vk_digest`] a proof was made for, next to the proof.
+pub fn digest_path(proof: &Path) -> PathBuf {
+    proof.with_extension("vkdigest")
+}
+
+/// Refuses `proof` if its digest file names another key or SRS; proofs without one, made
+/// before it was written, are let through to the verifier.
+fn check_digest_file(
+    proof: &Path,
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+) -> Result<(), PlaygroundError> {
+    let path = digest_path(proof);
+    if !path.exists() {
+        return Ok(());
+    }
+    let text = fs::read_to_string(&path).map_err(PlaygroundError::io(&path))?;
+    let declared: [u8; 32] = decode_hex(&text)
+        .map_err(|e| PlaygroundError::serialization(&path, e))?
+        .try_into()
+        .map_err(|_| PlaygroundError::serialization(&path, "expected a 32-byte digest"))?;
+    check_digest(params, vk, &declared)
+        .map_err(|report| PlaygroundError::Verify(format!("{}: {}", proof.display(), report)))
+}
+
+/// Writes the params of the `--srs` provider.
+pub fn setup(k: u32, path: &Path) -> Result<(), PlaygroundError> {
+    let params = storage::cached_params(k)?;
+    storage::write_params(path, &params).map_err(PlaygroundError::io(path))
+}
+
+/// Records the outcome of an operation, passing the outcome through.
+pub fn log_operation<C: CliCircuit>(
+    log: &mut Option<AuditLog>,
+    operation: Operation,
+    vk: &VerifyingKey<G1Affine>,
+    instances: &[&[Fr]],
+    result: Result<(), PlaygroundError>,
+) -> Result<(), PlaygroundError> {
+    if let Some(log) = log {
+        let message = result.as_ref().err().map(ToString::to_string);
+        log.record(
+            operation,
+            C::NAME,
+            vk,
+            instances,
+            message.as_deref().map_or(Ok(()), Err),
+        )
+        .map_err(PlaygroundError::AuditLog)?;
+    }
+    result
+}
+
+pub fn keygen<C: CliCircuit>(
+    files: &KeyFiles,
+    log: &mut Option<AuditLog>,
+) -> Result<(), PlaygroundError> {
+    let params = read_params(&files.params)?;
+    let vk = keygen_vk(&params, &C::empty()).map_err(PlaygroundError::Keygen)?;
+    let result = storage::write_vk(&files.vk, &vk)
+        .and_then(|_| record_provenance(&files.vk, &source_hash(C::SOURCES), &vk))
+        .map_err(PlaygroundError::io(&files.vk));
+    log_operation::<C>(log, Operation::Keygen, &vk, &[], result)
+}
+
+/// The keys, public instances and proof of `witness`; errors before there is a key to log
+/// them under are returned as the outer one.
+#[allow(clippy::type_complexity)]
+fn prove_witness<C: CliCircuit>(
+    files: &KeyFiles,
+    witness: &Path,
+    options: ProofOptions,
+    budget: Option<Duration>,
+) -> Result<
+    (
+        ParamsKZG<Bn256>,
+        ProvingKey<G1Affine>,
+        Vec<Vec<Fr>>,
+        Result<Vec<u8>, PlaygroundError>,
+    ),
+    PlaygroundError,
+> {
+    let params = read_params(&files.params)?;
+    let vk = read_vk::<C>(&files.vk, &params)?;
+    let path = witness;
+    let witness = read_witness::<C>(path)?;
+    let circuit = C::from_witness(&witness).map_err(|e| PlaygroundError::serialization(path, e))?;
+    let public = C::public_instances(&witness);
+    if dev::mock_requested() {
+        dev::mock_check(params.k(), &circuit, public.clone())
+            .map_err(|e| PlaygroundError::Unprovable(format!("mock prover: {}", e)))?;
+    }
+
+    let pk = keygen_pk(&params, vk, &C::empty()).map_err(PlaygroundError::Keygen)?;
+    let columns: Vec<&[Fr]> = public.iter().map(|column| &column[..]).collect();
+    let result = match budget {
+        Some(budget) => prove_within(&params, &pk, circuit, &columns, OsEntropy, options, budget)
+            .map_err(|e| match e {
+                BudgetError::Proof(e) => e,
+                e => PlaygroundError::Unprovable(e.to_string()),
+            }),
+        None => run_proof(&params, &pk, circuit, &columns, OsEntropy, options),
+    };
+    Ok((params, pk, public, result))
+}
+
+/// Keeps a copy of `proof` in the [`storage::artifact_store`] under its content address, for
+/// `gc` to trim. The files the user asked for are the result, so a store that can't be
+/// written only warns.
+fn store_proof<C: CliCircuit>(proof: &[u8]) {
+    if let Err(err) = storage::artifact_store().and_then(|store| store.put_proof(C::NAME, proof)) {
+        eprintln!("warning: {}: {}", storage::store_dir().display(), err);
+    }
+}
+
+pub fn prove<C: CliCircuit>(
+    files: &KeyFiles,
+    witness: &Path,
+    instances: &Path,
+    proof: &Path,
+    options: ProofOptions,
+    budget: Option<Duration>,
+    log: &mut Option<AuditLog>,
+) -> Result<(), PlaygroundError> {
+    let (params, pk, public, result) = prove_witness::<C>(files, witness, options, budget)?;
+    let result = result.and_then(|bytes| {
+        write_values(instances, &public)?;
+        write_proof(proof, &bytes)?;
+        store_proof::<C>(&bytes);
+        let digest = digest_path(proof);
+        fs::write(&digest, encode_hex(&vk_digest(&params, pk.get_vk())) + "\n")
+            .map_err(PlaygroundError::io(&digest))
+    });
+    let columns: Vec<&[Fr]> = public.iter().map(|column| &column[..]).collect();
+    log_operation::<C>(log, Operation::Prove, pk.get_vk(), &columns, result)
+}
+
+/// Like [`prove`], writing one artifact bundle (see [`Bundle`]) to `out` instead of the
+/// instances, proof and digest files, with `git_rev` in its metadata.
+pub fn prove_bundle<C: CliCircuit>(
+    files: &KeyFiles,
+    witness: &Path,
+    out: &Path,
+    options: ProofOptions,
+    budget: Option<Duration>,
+    git_rev: Option<&str>,
+    log: &mut Option<AuditLog>,
+) -> Result<(), PlaygroundError> {
+    let (params, pk, public, result) = prove_witness::<C>(files, witness, options, budget)?;
+    let columns: Vec<&[Fr]> = public.iter().map(|column| &column[..]).collect();
+    let result = result.and_then(|proof| {
+        store_proof::<C>(&proof);
+        let metadata = Metadata {
+            circuit: C::NAME.to_string(),
+            git_rev: git_rev.filter(|rev| !rev.is_empty()).map(str::to_string),
+        };
+        let proof = ProofBundle {
+            options,
+            instances: public.clone(),
+            proof,
+            vk_digest: vk_digest(&params, pk.get_vk()),
+        };
+        let bundle = Bundle::new(metadata, &params, pk.get_vk(), proof);
+        fs::write(out, bundle.to_bytes()).map_err(PlaygroundError::io(out))
+    });
+    log_operation::<C>(log, Operation::Prove, pk.get_vk(), &columns, result)
+}
+
+/// The circuit an artifact bundle names, to pick the one to verify it with.
+pub fn bundle_circuit(path: &Path) -> Result<String, PlaygroundError> {
+    read_bundle(path).map(|bundle| bundle.metadata.circuit)
+}
+
+fn read_bundle(path: &Path) -> Result<Bundle, PlaygroundError> {
+    let bytes = fs::read(path).map_err(PlaygroundError::io(path))?;
+    Bundle::from_bytes(&bytes, &ResourceLimits::default())
+        .map_err(|e| PlaygroundError::serialization(path, e))
+}
+
+/// Verifies an artifact bundle of `C` under the params at `params`, which must be the SRS it
+/// names, and under the key in `vk` if given, which the proof has to declare; without one,
+/// the bundle's own key is used and its digest printed for comparing with a trusted one.
+pub fn verify_bundle<C: CliCircuit>(
+    params: &Path,
+    bundle: &Path,
+    vk: Option<&Path>,
+    log: &mut Option<AuditLog>,
+) -> Result<(), PlaygroundError> {
+    let artifact = read_bundle(bundle)?;
+    if artifact.metadata.circuit != C::NAME {
+        return Err(PlaygroundError::serialization(
+            bundle,
+            format!(
+                "a bundle of circuit {:?}, not {}",
+                artifact.metadata.circuit,
+                C::NAME
+            ),
+        ));
+    }
+    let params = read_params(params)?;
+    let bundled = artifact.verifying_key::<C>(&params).map_err(|e| match e {
+        EnvelopeError::Srs { .. } => PlaygroundError::Setup(format!("{}: {}", bundle.display(), e)),
+        e => PlaygroundError::serialization(bundle, e),
+    })?;
+    C::instance_layout()
+        .check_ranges(&artifact.proof.instances)
+        .map_err(|e| PlaygroundError::serialization(bundle, e))?;
+    let vk = match vk {
+        Some(path) => read_vk::<C>(path, &params)?,
+        None => {
+            println!(
+                "verifying under the bundle's own key {}; pass --vk to pin a trusted one",
+                encode_hex(&artifact.vk_digest())
+            );
+            bundled
+        }
+    };
+    let columns = artifact.proof.columns();
+    let result = BundleVerifier::new(&params, &vk)
+        .verify(&artifact.proof)
+        .into_result()
+        .map_err(PlaygroundError::from);
+    log_operation::<C>(log, Operation::Verify, &vk, &columns, result)
+}
+
+pub fn verify<C: CliCircuit>(
+    files: &KeyFiles,
+    instances: &Path,
+    proof: &Path,
+    options: ProofOptions,
+    log: &mut Option<AuditLog>,
+) -> Result<(), PlaygroundError> {
+    let params = read_params(&files.params)?;
+    let vk = read_vk::<C>(&files.vk, &params)?;
+    let public = read_values(instances)?;
+    C::instance_layout()
+        .check_ranges(&public)
+        .map_err(|e| PlaygroundError::serialization(instances, e))?;
+    let columns: Vec<&[Fr]> = public.iter().map(|column| &column[..]).collect();
+    let bytes = read_proof(proof)?;
+    let result = check_digest_file(proof, &params, &vk).and_then(|_| {
+        check_proof(&params, &vk, &columns, &bytes, options)
+            .into_result()
+            .map_err(PlaygroundError::from)
+    });
+    log_operation::<C>(log, Operation::Verify, &vk, &columns, result)
+}
+
+/// Verifies all `proofs` with one accumulated check, reporting the amortized time.
+pub fn verify_batch<C: CliCircuit>(
+    files: &KeyFiles,
+    proofs: &[PathBuf],
+    options: ProofOptions,
+    log: &mut Option<AuditLog>,
+) -> Result<(), PlaygroundError> {
+    let params = read_params(&files.params)?;
+    let vk = read_vk::<C>(&files.vk, &params)?;
+    let mut batch = Batch::new().options(options);
+    for proof in proofs.iter() {
+        let instances = proof.with_extension("instances");
+        let public = read_values(&instances)?;
+        C::instance_layout()
+            .check_ranges(&public)
+            .map_err(|e| PlaygroundError::serialization(&instances, e))?;
+        check_digest_file(proof, &params, &vk)?;
+        let bytes = read_proof(proof)?;
+        batch.push(&vk, public, bytes);
+    }
+    let start = Instant::now();
+    let result = batch.verify(&params).map_err(|e| {
+        PlaygroundError::Verify(match e {
+            BatchError::Invalid { index, error } => {
+                format!("{}: verify_proof: {}", proofs[index].display(), error)
+            }
+            e => e.to_string(),
+        })
+    });
+    let elapsed = start.elapsed();
+    if result.is_ok() {
+        println!(
+            "verified {} proofs in {:.2?}, {:.2?} per proof",
+            proofs.len(),
+            elapsed,
+            elapsed / proofs.len().max(1) as u32
+        );
+    }
+    log_operation::<C>(log, Operation::Verify, &vk, &[], result)
+}
@@ -0,0 +1,209 @@
+//! Circuits written once against both the PSE fork the playground builds on and upstream
+//! zcash `halo2_proofs` 0.2 (behind `halo2-zcash`), to diff the two forks' behavior and costs
+//! from one source.
+//!
+//! The circuit APIs of the forks agree; what differs is around them: the PSE fork takes its
+//! curves from `halo2curves` and is generic over the commitment scheme, zcash has the Pasta
+//! curves and IPA only. `with_backends!` expands a body once per fork, in a module named
+//! after it, with `halo2` bound to that fork's crate and `shim` to the few items that differ:
+//! `Fp`, and keygen, proving and verification over IPA on Vesta. Their errors are the forks'
+//! own, so they are reported as a [`PlaygroundError::Other`] naming the fork.
+
+use crate::{error::PlaygroundError, scheme::SchemeRun};
+
+/// Expands the items given once in `pse` and, with `halo2-zcash`, once in `zcash`.
+macro_rules! with_backends {
+    ($($body:tt)*) => {
+        pub mod pse {
+            #[allow(unused_imports)]
+            use halo2_proofs as halo2;
+            #[allow(unused_imports)]
+            use crate::compat::pse_shim as shim;
+            $($body)*
+        }
+
+        #[cfg(feature = "halo2-zcash")]
+        pub mod zcash {
+            #[allow(unused_imports)]
+            use halo2_proofs_zcash as halo2;
+            #[allow(unused_imports)]
+            use crate::compat::zcash_shim as shim;
+            $($body)*
+        }
+    };
+}
+
+pub mod pse_shim {
+    pub use halo2_proofs::halo2curves::pasta::Fp;
+    use halo2_proofs::{
+        halo2curves::pasta::EqAffine, plonk::Circuit, poly::ipa::commitment::ParamsIPA,
+    };
+
+    use super::SchemeRun;
+    use crate::{
+        entropy::EntropySource,
+        error::PlaygroundError,
+        scheme::{prove_and_verify as prove_under, Ipa},
+    };
+
+    pub const NAME: &str = "pse";
+
+    pub fn prove_and_verify<C: Circuit<Fp>, R: EntropySource>(
+        k: u32,
+        circuit: C,
+        instances: &[&[Fp]],
+        rng: R,
+    ) -> Result<SchemeRun, PlaygroundError> {
+        let params = ParamsIPA::<EqAffine>::new(k);
+        prove_under::<Ipa, _, _>(&params, circuit, instances, rng)
+            .map_err(|e| PlaygroundError::Other(format!("{}: {:?}", NAME, e)))
+    }
+}
+
+#[cfg(feature = "halo2-zcash")]
+pub mod zcash_shim {
+    use std::time::Instant;
+
+    pub use halo2_proofs_zcash::pasta::Fp;
+    use halo2_proofs_zcash::{
+        pasta::EqAffine,
+        plonk::{create_proof, keygen_pk, keygen_vk, verify_proof, Circuit, SingleVerifier},
+        poly::commitment::Params,
+        transcript::{Blake2bRead, Blake2bWrite, Challenge255},
+    };
+
+    use super::SchemeRun;
+    use crate::{entropy::EntropySource, error::PlaygroundError};
+
+    pub const NAME: &str = "zcash";
+
+    pub fn prove_and_verify<C: Circuit<Fp>, R: EntropySource>(
+        k: u32,
+        circuit: C,
+        instances: &[&[Fp]],
+        rng: R,
+    ) -> Result<SchemeRun, PlaygroundError> {
+        let error = |e: halo2_proofs_zcash::plonk::Error| {
+            PlaygroundError::Other(format!("{}: {:?}", NAME, e))
+        };
+        let params = Params::<EqAffine>::new(k);
+        let start = Instant::now();
+        let vk = keygen_vk(&params, &circuit).map_err(error)?;
+        let pk = keygen_pk(&params, vk, &circuit).map_err(error)?;
+        let keygen = start.elapsed();
+
+        let start = Instant::now();
+        let mut transcript = Blake2bWrite::<_, EqAffine, Challenge255<_>>::init(vec![]);
+        create_proof(&params, &pk, &[circuit], &[instances], rng, &mut transcript)
+            .map_err(error)?;
+        let proof = transcript.finalize();
+        let prove = start.elapsed();
+
+        let start = Instant::now();
+        let mut transcript = Blake2bRead::<_, EqAffine, Challenge255<_>>::init(&proof[..]);
+        verify_proof(
+            &params,
+            pk.get_vk(),
+            SingleVerifier::new(&params),
+            &[instances],
+            &mut transcript,
+        )
+        .map_err(error)?;
+        Ok(SchemeRun {
+            scheme: "ipa/pasta",
+            proof_bytes: proof.len(),
+            keygen,
+            prove,
+            verify: start.elapsed(),
+        })
+    }
+}
+
+with_backends! {
+    use halo2::{
+        arithmetic::FieldExt,
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
+        poly::Rotation,
+    };
+
+    use crate::{entropy::EntropySource, error::PlaygroundError, scheme::SchemeRun};
+
+    pub const NAME: &str = shim::NAME;
+
+    #[derive(Clone, Debug)]
+    pub struct MulConfig {
+        advice: [Column<Advice>; 3],
+        q: Selector,
+        instance: Column<Instance>,
+    }
+
+    /// Proves knowledge of `a` and `b` whose product is in instance row 0, with one gate
+    /// `q * (a * b - c)` and no chips, so nothing but the fork's own API is involved.
+    #[derive(Clone, Debug, Default)]
+    pub struct MulCircuit<F: FieldExt> {
+        pub a: Value<F>,
+        pub b: Value<F>,
+    }
+
+    impl<F: FieldExt> Circuit<F> for MulCircuit<F> {
+        type Config = MulConfig;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            Self::default()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+            let advice = [(); 3].map(|_| meta.advice_column());
+            let instance = meta.instance_column();
+            meta.enable_equality(advice[2]);
+            meta.enable_equality(instance);
+            let q = meta.selector();
+            meta.create_gate("mul", |meta| {
+                let q = meta.query_selector(q);
+                let [a, b, c] = advice.map(|column| meta.query_advice(column, Rotation::cur()));
+                vec![q * (a * b - c)]
+            });
+            MulConfig {
+                advice,
+                q,
+                instance,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<F>,
+        ) -> Result<(), Error> {
+            let [a, b, c] = config.advice;
+            let product = layouter.assign_region(
+                || "a * b",
+                |mut region| {
+                    config.q.enable(&mut region, 0)?;
+                    region.assign_advice(|| "a", a, 0, || self.a)?;
+                    region.assign_advice(|| "b", b, 0, || self.b)?;
+                    let product = self.a.zip(self.b).map(|(a, b)| a * b);
+                    region.assign_advice(|| "c", c, 0, || product)
+                },
+            )?;
+            layouter.constrain_instance(product.cell(), config.instance, 0)
+        }
+    }
+
+    /// Keygen, a proof that `a * b = c` and its verification over IPA on Vesta, timed.
+    pub fn run<R: EntropySource>(
+        k: u32,
+        a: u64,
+        b: u64,
+        c: u64,
+        rng: R,
+    ) -> Result<SchemeRun, PlaygroundError> {
+        let circuit = MulCircuit {
+            a: Value::known(shim::Fp::from(a)),
+            b: Value::known(shim::Fp::from(b)),
+        };
+        shim::prove_and_verify(k, circuit, &[&[shim::Fp::from(c)]], rng)
+    }
+}
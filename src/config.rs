@@ -0,0 +1,400 @@
+//! Runs described by a TOML file rather than by `main()`: which circuit, at which Poseidon
+//! width, rate and message length, the `k`, transcript, SRS and where everything is written.
+//! `playground run --config <file>` sets up, keygens, proves and verifies as it says.
+//!
+//! ```toml
+//! circuit = "poseidon"      # or "mul"
+//! witness = "message.txt"   # as for `playground prove --witness`
+//! k = 9                     # optional, the smallest that fits by default
+//! transcript = "blake2b"    # optional, a registered transcript id
+//! multiopen = "shplonk"     # optional, or "gwc"
+//! srs = "unsafe"            # optional, as `--srs`; `--srs` or PLAYGROUND_SRS otherwise
+//!
+//! [poseidon]                # for circuit = "poseidon", one of POSEIDON_MATRIX
+//! width = 3
+//! rate = 2
+//! length = 2
+//!
+//! [output]
+//! params = "out/params.bin"
+//! vk = "out/poseidon.vk"
+//! proof = "out/poseidon.proof"
+//! instances = "out/poseidon.instances"
+//! ```
+//!
+//! Relative paths are relative to the config file. The generic parameters are compiled in, so
+//! a config can only pick from [`POSEIDON_MATRIX`].
+
+use std::{
+    fmt, fs,
+    path::{Path, PathBuf},
+};
+
+use halo2_proofs::halo2curves::bn256::Fr;
+use toml::{value::Table, Value};
+
+use crate::{
+    audit::fit_k,
+    audit_log::AuditLog,
+    circuits::{mul::MulCircuit, poseidon::PoseidonHashCircuit},
+    cli::{self, CliCircuit, KeyFiles},
+    error::PlaygroundError,
+    poseidon::MySpec,
+    prover::{MultiOpen, ProofOptions, TranscriptHash},
+    srs, storage, transcript_registry,
+};
+
+/// The `(width, rate, length)` a config can ask Poseidon for: widths 3, 9 and 12 at their
+/// largest rate, over short, medium and long messages, as `hash --sweep` compares them.
+pub const POSEIDON_MATRIX: [(usize, usize, usize); 9] = [
+    (3, 2, 2),
+    (3, 2, 8),
+    (3, 2, 32),
+    (9, 8, 2),
+    (9, 8, 8),
+    (9, 8, 32),
+    (12, 11, 2),
+    (12, 11, 8),
+    (12, 11, 32),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CircuitConfig {
+    Mul,
+    Poseidon {
+        width: usize,
+        rate: usize,
+        length: usize,
+    },
+}
+
+/// Where a run writes the params, verifying key, proof and instances.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutputPaths {
+    pub params: PathBuf,
+    pub vk: PathBuf,
+    pub proof: PathBuf,
+    pub instances: PathBuf,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RunConfig {
+    pub circuit: CircuitConfig,
+    pub witness: PathBuf,
+    pub k: Option<u32>,
+    pub options: ProofOptions,
+    /// A provider as `--srs` takes it, checked when parsing.
+    pub srs: Option<String>,
+    pub output: OutputPaths,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConfigError {
+    Toml(String),
+    Missing(&'static str),
+    /// A key this format doesn't have, likely a typo.
+    Unknown(String),
+    Invalid {
+        key: &'static str,
+        message: String,
+    },
+    /// A Poseidon instance outside [`POSEIDON_MATRIX`].
+    Unsupported {
+        width: usize,
+        rate: usize,
+        length: usize,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ConfigError::Toml(message) => write!(f, "{}", message),
+            ConfigError::Missing(key) => write!(f, "missing `{}`", key),
+            ConfigError::Unknown(key) => write!(f, "unknown key `{}`", key),
+            ConfigError::Invalid { key, message } => write!(f, "`{}`: {}", key, message),
+            ConfigError::Unsupported {
+                width,
+                rate,
+                length,
+            } => {
+                let supported: Vec<_> = POSEIDON_MATRIX
+                    .iter()
+                    .map(|(w, r, l)| format!("{}/{}/{}", w, r, l))
+                    .collect();
+                write!(
+                    f,
+                    "poseidon width {}, rate {}, length {} is not compiled in; width/rate/length \
+                     is one of {}",
+                    width,
+                    rate,
+                    length,
+                    supported.join(", ")
+                )
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+fn check_keys(table: &Table, prefix: &str, known: &[&str]) -> Result<(), ConfigError> {
+    match table.keys().find(|key| !known.contains(&key.as_str())) {
+        Some(key) => Err(ConfigError::Unknown(format!("{}{}", prefix, key))),
+        None => Ok(()),
+    }
+}
+
+fn invalid(key: &'static str, message: impl Into<String>) -> ConfigError {
+    ConfigError::Invalid {
+        key,
+        message: message.into(),
+    }
+}
+
+fn string<'a>(table: &'a Table, key: &'static str) -> Result<Option<&'a str>, ConfigError> {
+    table
+        .get(key.rsplit('.').next().unwrap())
+        .map(|value| {
+            value
+                .as_str()
+                .ok_or_else(|| invalid(key, "expected a string"))
+        })
+        .transpose()
+}
+
+fn integer(table: &Table, key: &'static str) -> Result<Option<usize>, ConfigError> {
+    table
+        .get(key.rsplit('.').next().unwrap())
+        .map(|value| {
+            value
+                .as_integer()
+                .and_then(|n| usize::try_from(n).ok())
+                .ok_or_else(|| invalid(key, "expected a non-negative integer"))
+        })
+        .transpose()
+}
+
+fn table<'a>(root: &'a Table, key: &'static str) -> Result<Option<&'a Table>, ConfigError> {
+    root.get(key)
+        .map(|value| {
+            value
+                .as_table()
+                .ok_or_else(|| invalid(key, "expected a table"))
+        })
+        .transpose()
+}
+
+impl RunConfig {
+    /// Parses a config, resolving relative paths against `base`.
+    pub fn parse(text: &str, base: &Path) -> Result<Self, ConfigError> {
+        let root = match text.parse::<Value>() {
+            Ok(Value::Table(root)) => root,
+            Ok(_) => return Err(ConfigError::Toml("expected a table".to_string())),
+            Err(err) => return Err(ConfigError::Toml(err.to_string())),
+        };
+        check_keys(
+            &root,
+            "",
+            &[
+                "circuit",
+                "witness",
+                "k",
+                "transcript",
+                "multiopen",
+                "srs",
+                "poseidon",
+                "output",
+            ],
+        )?;
+        let path = |text: &str| base.join(text);
+
+        let poseidon = table(&root, "poseidon")?;
+        let circuit = match string(&root, "circuit")?.ok_or(ConfigError::Missing("circuit"))? {
+            "mul" => {
+                if poseidon.is_some() {
+                    return Err(invalid("poseidon", "only for circuit = \"poseidon\""));
+                }
+                CircuitConfig::Mul
+            }
+            "poseidon" => {
+                let poseidon = poseidon.ok_or(ConfigError::Missing("poseidon"))?;
+                check_keys(poseidon, "poseidon.", &["width", "rate", "length"])?;
+                let width = integer(poseidon, "poseidon.width")?
+                    .ok_or(ConfigError::Missing("poseidon.width"))?;
+                let rate = integer(poseidon, "poseidon.rate")?
+                    .ok_or(ConfigError::Missing("poseidon.rate"))?;
+                let length = integer(poseidon, "poseidon.length")?
+                    .ok_or(ConfigError::Missing("poseidon.length"))?;
+                if !POSEIDON_MATRIX.contains(&(width, rate, length)) {
+                    return Err(ConfigError::Unsupported {
+                        width,
+                        rate,
+                        length,
+                    });
+                }
+                CircuitConfig::Poseidon {
+                    width,
+                    rate,
+                    length,
+                }
+            }
+            other => {
+                return Err(invalid(
+                    "circuit",
+                    format!("unknown circuit {:?}, expected mul or poseidon", other),
+                ))
+            }
+        };
+
+        let k = integer(&root, "k")?
+            .map(|k| u32::try_from(k).map_err(|_| invalid("k", "too large")))
+            .transpose()?;
+        let transcript = match string(&root, "transcript")? {
+            Some(id) => {
+                transcript_registry::by_id(id)
+                    .ok_or_else(|| invalid("transcript", format!("unknown transcript {:?}", id)))?
+                    .hash
+            }
+            None => TranscriptHash::default(),
+        };
+        let multiopen = match string(&root, "multiopen")? {
+            Some("shplonk") | None => MultiOpen::Shplonk,
+            Some("gwc") => MultiOpen::Gwc,
+            Some(other) => {
+                return Err(invalid(
+                    "multiopen",
+                    format!("{:?}, expected shplonk or gwc", other),
+                ))
+            }
+        };
+        let srs = string(&root, "srs")?
+            .map(|arg| {
+                srs::parse_provider(arg)
+                    .map(|_| arg.to_string())
+                    .map_err(|e| invalid("srs", e.to_string()))
+            })
+            .transpose()?;
+
+        let output = table(&root, "output")?.ok_or(ConfigError::Missing("output"))?;
+        check_keys(output, "output.", &["params", "vk", "proof", "instances"])?;
+        let output_path = |key: &'static str| {
+            string(output, key)?
+                .map(path)
+                .ok_or(ConfigError::Missing(key))
+        };
+        let output = OutputPaths {
+            params: output_path("output.params")?,
+            vk: output_path("output.vk")?,
+            proof: output_path("output.proof")?,
+            instances: output_path("output.instances")?,
+        };
+
+        Ok(RunConfig {
+            circuit,
+            witness: path(string(&root, "witness")?.ok_or(ConfigError::Missing("witness"))?),
+            k,
+            options: ProofOptions {
+                multiopen,
+                transcript,
+            },
+            srs,
+            output,
+        })
+    }
+
+    /// Reads a config file; its relative paths are relative to its directory.
+    pub fn read(path: &Path) -> Result<Self, PlaygroundError> {
+        let text = fs::read_to_string(path).map_err(PlaygroundError::io(path))?;
+        let base = path.parent().unwrap_or_else(|| Path::new(""));
+        Self::parse(&text, base).map_err(|e| PlaygroundError::serialization(path, e))
+    }
+}
+
+type Poseidon<const WIDTH: usize, const RATE: usize, const L: usize> =
+    PoseidonHashCircuit<Fr, MySpec<WIDTH, RATE>, WIDTH, RATE, L>;
+
+/// Sets up, keygens, proves and verifies as `config` says, through the same calls as the
+/// `setup`, `keygen`, `prove` and `verify` subcommands.
+pub fn run(config: &RunConfig, log: &mut Option<AuditLog>) -> Result<(), PlaygroundError> {
+    match config.circuit {
+        CircuitConfig::Mul => run_with::<MulCircuit<Fr>>(config, log),
+        CircuitConfig::Poseidon {
+            width,
+            rate,
+            length,
+        } => match (width, rate, length) {
+            (3, 2, 2) => run_with::<Poseidon<3, 2, 2>>(config, log),
+            (3, 2, 8) => run_with::<Poseidon<3, 2, 8>>(config, log),
+            (3, 2, 32) => run_with::<Poseidon<3, 2, 32>>(config, log),
+            (9, 8, 2) => run_with::<Poseidon<9, 8, 2>>(config, log),
+            (9, 8, 8) => run_with::<Poseidon<9, 8, 8>>(config, log),
+            (9, 8, 32) => run_with::<Poseidon<9, 8, 32>>(config, log),
+            (12, 11, 2) => run_with::<Poseidon<12, 11, 2>>(config, log),
+            (12, 11, 8) => run_with::<Poseidon<12, 11, 8>>(config, log),
+            (12, 11, 32) => run_with::<Poseidon<12, 11, 32>>(config, log),
+            _ => Err(PlaygroundError::Other(
+                ConfigError::Unsupported {
+                    width,
+                    rate,
+                    length,
+                }
+                .to_string(),
+            )),
+        },
+    }
+}
+
+fn run_with<C: CliCircuit>(
+    config: &RunConfig,
+    log: &mut Option<AuditLog>,
+) -> Result<(), PlaygroundError> {
+    let needed = fit_k::<Fr, _>(&C::empty())?;
+    let k = match config.k {
+        Some(k) if k < needed => {
+            return Err(PlaygroundError::Setup(format!(
+                "k = {} is too small, {} needs at least {}",
+                k,
+                C::NAME,
+                needed
+            )))
+        }
+        Some(k) => k,
+        None => needed,
+    };
+    let params = match &config.srs {
+        Some(arg) => srs::parse_provider(arg)
+            .and_then(|provider| provider.params(k))
+            .map_err(|e| PlaygroundError::Setup(format!("params for k = {}: {}", k, e)))?,
+        None => storage::cached_params(k)?,
+    };
+
+    let output = &config.output;
+    for path in [&output.params, &output.vk, &output.proof, &output.instances] {
+        if let Some(dir) = path.parent() {
+            fs::create_dir_all(dir).map_err(PlaygroundError::io(dir))?;
+        }
+    }
+    storage::write_params(&output.params, &params).map_err(PlaygroundError::io(&output.params))?;
+    let files = KeyFiles {
+        params: output.params.clone(),
+        vk: output.vk.clone(),
+    };
+    cli::keygen::<C>(&files, log)?;
+    cli::prove::<C>(
+        &files,
+        &config.witness,
+        &output.instances,
+        &output.proof,
+        config.options,
+        None,
+        log,
+    )?;
+    cli::verify::<C>(
+        &files,
+        &output.instances,
+        &output.proof,
+        config.options,
+        log,
+    )
+}
@@ -0,0 +1,221 @@
+use std::marker::PhantomData;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{Layouter, SimpleFloorPlanner},
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{
+        create_proof, Advice, Circuit, Column, ConstraintSystem, Error, Expression, Instance,
+        ProvingKey, Selector, VerifyingKey,
+    },
+    poly::{
+        kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::ProverSHPLONK,
+            strategy::AccumulatorStrategy,
+        },
+        Rotation,
+    },
+    transcript::{Blake2bWrite, Challenge255, TranscriptWriterBuffer},
+};
+
+use crate::{
+    cancel::{CancelToken, Cancellable},
+    entropy::EntropySource,
+    prover::{MultiOpen, ProofOptions},
+    verifier::{final_check, precheck_proof, VerificationReport},
+};
+
+pub const STEP_CONSTANT: u64 = 5;
+
+/// One step of the chained computation: `x -> x^3 + STEP_CONSTANT`.
+pub fn step<F: FieldExt>(x: F) -> F {
+    x.square() * x + F::from(STEP_CONSTANT)
+}
+
+pub fn run_steps<F: FieldExt>(mut x: F, steps: usize) -> F {
+    for _ in 0..steps {
+        x = step(x);
+    }
+    x
+}
+
+#[derive(Clone, Debug)]
+pub struct ChainChunkConfig {
+    pub advice: Column<Advice>,
+    pub instance: Column<Instance>,
+    pub s_step: Selector,
+}
+
+/// Proves `STEPS` applications of `step`, exposing the boundary states as instance rows 0 (in)
+/// and 1 (out) so consecutive chunks can be linked. The whole chunk is determined by its public
+/// input state, so the circuit carries no witness.
+#[derive(Clone, Default)]
+pub struct ChainChunkCircuit<F: FieldExt, const STEPS: usize> {
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt, const STEPS: usize> ChainChunkCircuit<F, STEPS> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl<F: FieldExt, const STEPS: usize> Circuit<F> for ChainChunkCircuit<F, STEPS> {
+    type Config = ChainChunkConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        let s_step = meta.selector();
+
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+
+        meta.create_gate("step", |meta| {
+            let cur = meta.query_advice(advice, Rotation::cur());
+            let next = meta.query_advice(advice, Rotation::next());
+            let s_step = meta.query_selector(s_step);
+            let constant = Expression::Constant(F::from(STEP_CONSTANT));
+            vec![s_step * (next - (cur.clone() * cur.clone() * cur + constant))]
+        });
+
+        ChainChunkConfig {
+            advice,
+            instance,
+            s_step,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let state_out = layouter.assign_region(
+            || "chain chunk",
+            |mut region| {
+                let mut state = region.assign_advice_from_instance(
+                    || "state in",
+                    config.instance,
+                    0,
+                    config.advice,
+                    0,
+                )?;
+                for offset in 0..STEPS {
+                    config.s_step.enable(&mut region, offset)?;
+                    let value = state.value().map(|x| step(*x));
+                    state =
+                        region.assign_advice(|| "state", config.advice, offset + 1, || value)?;
+                }
+                Ok(state)
+            },
+        )?;
+        layouter.constrain_instance(state_out.cell(), config.instance, 1)
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ChunkProof {
+    pub state_in: Fr,
+    pub state_out: Fr,
+    pub proof: Vec<u8>,
+}
+
+/// Proves `chunks` consecutive chunks starting from `initial`.
+pub fn prove_chain<const STEPS: usize, R: EntropySource>(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    initial: Fr,
+    chunks: usize,
+    rng: R,
+) -> Result<Vec<ChunkProof>, Error> {
+    prove_chain_cancellable::<STEPS, R>(params, pk, initial, chunks, rng, &CancelToken::new())
+}
+
+/// `prove_chain` that stops with `Error::Synthesis` once `token` is cancelled, between chunks
+/// or between the phases of a chunk's proof.
+pub fn prove_chain_cancellable<const STEPS: usize, R: EntropySource>(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    initial: Fr,
+    chunks: usize,
+    mut rng: R,
+    token: &CancelToken,
+) -> Result<Vec<ChunkProof>, Error> {
+    let mut state_in = initial;
+    let mut proofs = Vec::with_capacity(chunks);
+    for _ in 0..chunks {
+        token.check()?;
+        let state_out = run_steps(state_in, STEPS);
+        let circuit = Cancellable::new(ChainChunkCircuit::<Fr, STEPS>::new(), token.clone());
+        let mut transcript = Blake2bWrite::<_, G1Affine, Challenge255<_>>::init(vec![]);
+        create_proof::<
+            KZGCommitmentScheme<Bn256>,
+            ProverSHPLONK<'_, Bn256>,
+            Challenge255<G1Affine>,
+            _,
+            Blake2bWrite<Vec<u8>, G1Affine, Challenge255<G1Affine>>,
+            Cancellable<ChainChunkCircuit<Fr, STEPS>>,
+        >(
+            params,
+            pk,
+            &[circuit],
+            &[&[&[state_in, state_out]]],
+            &mut rng,
+            &mut transcript,
+        )?;
+        proofs.push(ChunkProof {
+            state_in,
+            state_out,
+            proof: transcript.finalize(),
+        });
+        state_in = state_out;
+    }
+    Ok(proofs)
+}
+
+/// Checks that there is at least one chunk proof, that they start at `initial` and end at
+/// `final_state`, that each chunk starts where the previous one ended, and that every proof
+/// verifies. Without `final_state`, a chain cut short after any chunk would pass. The pairings
+/// are batched into one final check.
+pub fn verify_chain(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    initial: Fr,
+    final_state: Fr,
+    proofs: &[ChunkProof],
+) -> Result<bool, VerificationReport> {
+    if proofs.is_empty() {
+        return Ok(false);
+    }
+    let mut expected_in = initial;
+    for chunk in proofs.iter() {
+        if chunk.state_in != expected_in {
+            return Ok(false);
+        }
+        expected_in = chunk.state_out;
+    }
+    if expected_in != final_state {
+        return Ok(false);
+    }
+
+    let mut strategy = AccumulatorStrategy::new(params);
+    for chunk in proofs.iter() {
+        let instances: &[&[&[Fr]]] = &[&[&[chunk.state_in, chunk.state_out]]];
+        strategy = precheck_proof(
+            params,
+            vk,
+            strategy,
+            instances,
+            &chunk.proof,
+            ProofOptions::default(),
+        )?;
+    }
+    Ok(final_check(strategy, MultiOpen::Shplonk))
+}
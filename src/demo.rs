@@ -0,0 +1,162 @@
+//! `playground demo`: proves and verifies the multiplication and Poseidon circuits with no
+//! setup. The params come from `cached_params`, so `embedded-srs` compiles them in, and
+//! `embedded-demo` also compiles in the verifying keys, written by `make demo-keys` to the
+//! files named by `PLAYGROUND_DEMO_MUL_VK` and `PLAYGROUND_DEMO_POSEIDON_VK`. Without them the
+//! keys are generated on the spot.
+
+use std::{
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{keygen_pk, keygen_vk, Circuit, VerifyingKey},
+    poly::kzg::commitment::ParamsKZG,
+};
+
+use crate::{
+    artifacts::ProofArtifacts,
+    audit::fit_k,
+    circuits::{mul::MulCircuit, poseidon::PoseidonHashCircuit},
+    entropy::OsEntropy,
+    error::PlaygroundError,
+    poseidon::MySpec,
+    prover::{run_proof, ProofOptions},
+    storage::{cached_params, write_vk},
+    verifier::check_proof,
+};
+
+pub type DemoPoseidon = PoseidonHashCircuit<Fr, MySpec<3, 2>, 3, 2, 2>;
+
+#[cfg(feature = "embedded-demo")]
+const MUL_VK: Option<&[u8]> = Some(include_bytes!(env!("PLAYGROUND_DEMO_MUL_VK")));
+#[cfg(feature = "embedded-demo")]
+const POSEIDON_VK: Option<&[u8]> = Some(include_bytes!(env!("PLAYGROUND_DEMO_POSEIDON_VK")));
+#[cfg(not(feature = "embedded-demo"))]
+const MUL_VK: Option<&[u8]> = None;
+#[cfg(not(feature = "embedded-demo"))]
+const POSEIDON_VK: Option<&[u8]> = None;
+
+#[derive(Clone, Debug)]
+pub struct DemoRun {
+    pub circuit: &'static str,
+    pub k: u32,
+    /// Whether the verifying key was compiled in rather than generated.
+    pub embedded_vk: bool,
+    pub proof_bytes: usize,
+    pub elapsed: Duration,
+}
+
+fn mul() -> (MulCircuit<Fr>, Vec<Vec<Fr>>) {
+    let (a, b) = (Fr::from(3), Fr::from(5));
+    (MulCircuit::new(a, b), vec![MulCircuit::instance(a * b)])
+}
+
+fn poseidon() -> (DemoPoseidon, Vec<Vec<Fr>>) {
+    let message = [Fr::from(1), Fr::from(2)];
+    (
+        DemoPoseidon::new(message),
+        vec![vec![DemoPoseidon::hash(message)]],
+    )
+}
+
+/// The params and the verifying key of `circuit`, the key read from `embedded` if given.
+fn demo_keys<C: Circuit<Fr>>(
+    circuit: &C,
+    embedded: Option<&[u8]>,
+) -> Result<(ParamsKZG<Bn256>, VerifyingKey<G1Affine>), PlaygroundError> {
+    let params = cached_params(fit_k(circuit)?)?;
+    let vk = match embedded {
+        Some(mut bytes) => VerifyingKey::read::<_, C>(&mut bytes, &params)
+            .map_err(|e| PlaygroundError::Serialization(format!("embedded vk: {}", e)))?,
+        None => keygen_vk(&params, circuit).map_err(PlaygroundError::Keygen)?,
+    };
+    Ok((params, vk))
+}
+
+fn run_demo<C: Circuit<Fr>>(
+    name: &'static str,
+    circuit: C,
+    instances: Vec<Vec<Fr>>,
+    embedded: Option<&[u8]>,
+) -> Result<DemoRun, PlaygroundError> {
+    let start = Instant::now();
+    let (params, vk) = demo_keys(&circuit, embedded)?;
+    let k = vk.domain.k();
+    let pk = keygen_pk(&params, vk, &circuit).map_err(PlaygroundError::Keygen)?;
+    let columns: Vec<&[Fr]> = instances.iter().map(Vec::as_slice).collect();
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &columns,
+        OsEntropy,
+        ProofOptions::default(),
+    )?;
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &columns,
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()?;
+    Ok(DemoRun {
+        circuit: name,
+        k,
+        embedded_vk: embedded.is_some(),
+        proof_bytes: proof.len(),
+        elapsed: start.elapsed(),
+    })
+}
+
+/// Proves and verifies both demo circuits.
+pub fn run() -> Result<Vec<DemoRun>, PlaygroundError> {
+    let (mul, mul_instances) = mul();
+    let (poseidon, poseidon_instances) = poseidon();
+    Ok(vec![
+        run_demo("mul", mul, mul_instances, MUL_VK)?,
+        run_demo("poseidon", poseidon, poseidon_instances, POSEIDON_VK)?,
+    ])
+}
+
+fn measure<C: Circuit<Fr>>(
+    name: &str,
+    circuit: C,
+    instances: Vec<Vec<Fr>>,
+) -> Result<ProofArtifacts, PlaygroundError> {
+    let params = cached_params(fit_k(&circuit)?)?;
+    ProofArtifacts::measure(
+        name,
+        &params,
+        circuit,
+        instances,
+        OsEntropy,
+        ProofOptions::default(),
+    )
+}
+
+/// Proves and verifies both demo circuits with keys generated on the spot, recording what each
+/// run produced.
+pub fn artifacts() -> Result<Vec<ProofArtifacts>, PlaygroundError> {
+    let (mul, mul_instances) = mul();
+    let (poseidon, poseidon_instances) = poseidon();
+    Ok(vec![
+        measure("mul", mul, mul_instances)?,
+        measure("poseidon", poseidon, poseidon_instances)?,
+    ])
+}
+
+/// Writes the verifying keys `run` uses to `dir`, as `mul.vk` and `poseidon.vk`.
+pub fn write_keys(dir: &Path) -> Result<(), PlaygroundError> {
+    let keys = [
+        ("mul", demo_keys(&mul().0, None)?.1),
+        ("poseidon", demo_keys(&poseidon().0, None)?.1),
+    ];
+    for (name, vk) in keys {
+        let path = dir.join(format!("{}.vk", name));
+        write_vk(&path, &vk).map_err(PlaygroundError::io(&path))?;
+    }
+    Ok(())
+}
@@ -0,0 +1,791 @@
+//! Debugging aids for circuit authors. [`Assignments`] records what a circuit's synthesis
+//! assigns and evaluates gate polynomials on concrete rows, split into the terms of their
+//! top-level sum, so a failing gate from `MockProver` can be traced to the term that is off.
+//! [`mock_if_requested`] runs `MockProver` ahead of keygen, so a binary reports which gate in
+//! which region failed rather than an opaque proof failure. [`dump_constraint_system`] prints
+//! what `configure` built, to check a change to it. [`AssignedRegion`]s record which cells
+//! each region took, for [`crate::usage`]. With `dev-graph`, [`render_layout`] draws
+//! any circuit's layout to an SVG or PNG.
+
+use std::{collections::HashSet, fmt, ops::Range, path::Path};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::Value,
+    dev::{MockProver, VerifyFailure},
+    plonk::{
+        Advice, Any, Assigned, Assignment, Challenge, Circuit, Column, ConstraintSystem, Error,
+        Expression, Fixed, FloorPlanner, Instance, Selector,
+    },
+};
+
+use crate::{entropy::SeededEntropy, error::PlaygroundError, fmt::to_short};
+
+/// The cells and selectors a region assigned, by column and row.
+#[derive(Clone, Debug, Default)]
+pub struct AssignedRegion {
+    pub name: String,
+    pub cells: HashSet<(Column<Any>, usize)>,
+    pub selectors: HashSet<(Selector, usize)>,
+}
+
+impl AssignedRegion {
+    fn new(name: String) -> Self {
+        AssignedRegion {
+            name,
+            ..Default::default()
+        }
+    }
+
+    /// The rows from the first to the last the region assigned, empty ones between included.
+    pub fn rows(&self) -> Range<usize> {
+        let rows = self
+            .cells
+            .iter()
+            .map(|(_, row)| *row)
+            .chain(self.selectors.iter().map(|(_, row)| *row));
+        let (start, end) = rows.fold((usize::MAX, 0), |(start, end), row| {
+            (start.min(row), end.max(row + 1))
+        });
+        start.min(end)..end
+    }
+}
+
+/// Everything a synthesis assigned, indexed by column and row. Unassigned cells read as zero;
+/// copy constraints are not recorded.
+pub struct Assignments<F: FieldExt> {
+    n: usize,
+    usable_rows: usize,
+    used_rows: usize,
+    cs: ConstraintSystem<F>,
+    advice: Vec<Vec<F>>,
+    fixed: Vec<Vec<F>>,
+    instance: Vec<Vec<F>>,
+    selectors: HashSet<(Selector, usize)>,
+    challenges: Vec<F>,
+    regions: Vec<AssignedRegion>,
+    /// What is assigned outside regions: tables and the constants a floor planner places.
+    outside: AssignedRegion,
+    in_region: bool,
+}
+
+impl<F: FieldExt> Assignments<F> {
+    /// Synthesizes `circuit` with `2^k` rows. Challenges are drawn from a fixed seed, so runs
+    /// are reproducible, and every phase is assigned in the one pass.
+    pub fn synthesize<C: Circuit<F>>(
+        k: u32,
+        circuit: &C,
+        instance: Vec<Vec<F>>,
+    ) -> Result<Self, Error> {
+        let n = 1 << k;
+        let mut cs = ConstraintSystem::default();
+        let config = C::configure(&mut cs);
+        let usable_rows = n - (cs.blinding_factors() + 1);
+        if instance.len() != cs.num_instance_columns()
+            || instance.iter().any(|column| column.len() > usable_rows)
+        {
+            return Err(Error::InvalidInstances);
+        }
+        let instance = instance
+            .into_iter()
+            .map(|mut column| {
+                column.resize(n, F::zero());
+                column
+            })
+            .collect();
+        let mut rng = SeededEntropy::new(0x5962_be5d_763d_318d);
+        let challenges = (0..cs.num_challenges())
+            .map(|_| F::random(&mut rng))
+            .collect();
+        let constants = cs.constants().clone();
+        let mut assignments = Assignments {
+            n,
+            usable_rows,
+            used_rows: 0,
+            advice: vec![vec![F::zero(); n]; cs.num_advice_columns()],
+            fixed: vec![vec![F::zero(); n]; cs.num_fixed_columns()],
+            instance,
+            selectors: HashSet::new(),
+            challenges,
+            cs,
+            regions: vec![],
+            outside: AssignedRegion::new("outside regions".to_string()),
+            in_region: false,
+        };
+        C::FloorPlanner::synthesize(&mut assignments, circuit, config, constants)?;
+        Ok(assignments)
+    }
+
+    pub fn constraint_system(&self) -> &ConstraintSystem<F> {
+        &self.cs
+    }
+
+    pub fn usable_rows(&self) -> usize {
+        self.usable_rows
+    }
+
+    /// One past the last row that got an advice or fixed cell, or a selector; constants
+    /// filled to the end of a column don't count.
+    pub fn used_rows(&self) -> usize {
+        self.used_rows
+    }
+
+    /// The regions in the order they were entered; a region entered twice is listed twice.
+    pub fn regions(&self) -> &[AssignedRegion] {
+        &self.regions
+    }
+
+    /// The cells assigned outside any region. Constants filled to the end of a column are
+    /// not recorded.
+    pub fn outside_regions(&self) -> &AssignedRegion {
+        &self.outside
+    }
+
+    fn current_region(&mut self) -> &mut AssignedRegion {
+        match self.regions.last_mut() {
+            Some(region) if self.in_region => region,
+            _ => &mut self.outside,
+        }
+    }
+
+    fn check_row(&self, row: usize) -> Result<(), Error> {
+        if row < self.usable_rows {
+            Ok(())
+        } else {
+            Err(Error::not_enough_rows_available(self.n.trailing_zeros()))
+        }
+    }
+
+    fn cell(&self, column: &[Vec<F>], index: usize, row: usize, rotation: i32) -> F {
+        let row = (row as i64 + rotation as i64).rem_euclid(self.n as i64) as usize;
+        column[index][row]
+    }
+
+    /// Value of `expression` with its current-row queries at `row`. Rotations wrap around.
+    pub fn evaluate(&self, expression: &Expression<F>, row: usize) -> F {
+        expression.evaluate(
+            &|constant| constant,
+            &|selector| {
+                if self.selectors.contains(&(selector, row)) {
+                    F::one()
+                } else {
+                    F::zero()
+                }
+            },
+            &|query| self.cell(&self.fixed, query.column_index(), row, query.rotation().0),
+            &|query| self.cell(&self.advice, query.column_index(), row, query.rotation().0),
+            &|query| {
+                self.cell(
+                    &self.instance,
+                    query.column_index(),
+                    row,
+                    query.rotation().0,
+                )
+            },
+            &|challenge| self.challenges[challenge.index()],
+            &|a| -a,
+            &|a, b| a + b,
+            &|a, b| a * b,
+            &|a, scalar| a * scalar,
+        )
+    }
+
+    /// The terms of `expression`'s top-level sum at `row`, selector factors distributed over
+    /// them, so `q * (a + b - c)` gives `q * a`, `q * b` and `q * -c`.
+    pub fn terms(&self, expression: &Expression<F>, row: usize) -> Vec<Term<F>> {
+        split(expression)
+            .into_iter()
+            .map(|term| Term {
+                value: self.evaluate(&term, row),
+                expression: render(&term),
+            })
+            .collect()
+    }
+
+    /// Every constraint of every gate at `row`.
+    pub fn constraints(&self, row: usize) -> Vec<ConstraintEvaluation<F>> {
+        self.cs
+            .gates()
+            .iter()
+            .flat_map(|gate| {
+                gate.polynomials()
+                    .iter()
+                    .enumerate()
+                    .map(move |(index, polynomial)| ConstraintEvaluation {
+                        gate: gate.name().to_string(),
+                        constraint: gate.constraint_name(index).to_string(),
+                        index,
+                        row,
+                        value: self.evaluate(polynomial, row),
+                        terms: self.terms(polynomial, row),
+                    })
+            })
+            .collect()
+    }
+
+    /// The constraints that do not vanish at `row`.
+    pub fn failing(&self, row: usize) -> Vec<ConstraintEvaluation<F>> {
+        self.constraints(row)
+            .into_iter()
+            .filter(|constraint| constraint.value != F::zero())
+            .collect()
+    }
+
+    /// The failing constraints of all usable rows.
+    pub fn all_failing(&self) -> Vec<ConstraintEvaluation<F>> {
+        (0..self.usable_rows)
+            .flat_map(|row| self.failing(row))
+            .collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct Term<F> {
+    pub expression: String,
+    pub value: F,
+}
+
+#[derive(Clone, Debug)]
+pub struct ConstraintEvaluation<F> {
+    pub gate: String,
+    pub constraint: String,
+    /// Position of the constraint in its gate.
+    pub index: usize,
+    pub row: usize,
+    pub value: F,
+    pub terms: Vec<Term<F>>,
+}
+
+impl<F: FieldExt> fmt::Display for ConstraintEvaluation<F> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "gate \"{}\" constraint {}", self.gate, self.index)?;
+        if !self.constraint.is_empty() {
+            write!(f, " (\"{}\")", self.constraint)?;
+        }
+        write!(f, " at row {} = {}", self.row, to_short(&self.value))?;
+        for term in self.terms.iter() {
+            let marker = if term.value == F::zero() { " " } else { "*" };
+            write!(
+                f,
+                "\n  {} {} = {}",
+                marker,
+                term.expression,
+                to_short(&term.value)
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Whether to mock-prove before keygen: `--mock` or `--no-mock`, the last one given winning,
+/// and on by default in debug builds.
+pub fn mock_requested() -> bool {
+    std::env::args()
+        .skip(1)
+        .fold(cfg!(debug_assertions), |on, arg| match arg.as_str() {
+            "--mock" => true,
+            "--no-mock" => false,
+            _ => on,
+        })
+}
+
+/// `failures` numbered, one to a paragraph, with the region, gate and cell names `MockProver`
+/// recorded.
+pub fn format_failures(failures: &[VerifyFailure]) -> String {
+    let mut out = format!(
+        "{} failure{}:",
+        failures.len(),
+        if failures.len() == 1 { "" } else { "s" }
+    );
+    for (index, failure) in failures.iter().enumerate() {
+        let text = failure.to_string();
+        let mut lines = text.lines();
+        out += &format!("\n{:>3}. {}", index + 1, lines.next().unwrap_or_default());
+        for line in lines {
+            out += &format!("\n     {}", line);
+        }
+    }
+    out
+}
+
+/// Runs `MockProver` on `circuit` with `2^k` rows, describing whatever it rejects in a
+/// [`PlaygroundError::Unprovable`].
+pub fn mock_check<F: FieldExt, C: Circuit<F>>(
+    k: u32,
+    circuit: &C,
+    instance: Vec<Vec<F>>,
+) -> Result<(), PlaygroundError> {
+    let prover = MockProver::run(k, circuit, instance)
+        .map_err(|e| PlaygroundError::Unprovable(format!("MockProver::run: {:?}", e)))?;
+    prover
+        .verify()
+        .map_err(|failures| PlaygroundError::Unprovable(format_failures(&failures)))
+}
+
+/// [`mock_check`] when [`mock_requested`], saying so when `name` is satisfied.
+pub fn mock_if_requested<F: FieldExt, C: Circuit<F>>(
+    name: &str,
+    k: u32,
+    circuit: &C,
+    instance: Vec<Vec<F>>,
+) -> Result<(), PlaygroundError> {
+    if !mock_requested() {
+        return Ok(());
+    }
+    mock_check(k, circuit, instance)
+        .map_err(|e| PlaygroundError::Unprovable(format!("{} mock prover: {}", name, e)))?;
+    println!("{} mock prover: satisfied", name);
+    Ok(())
+}
+
+pub(crate) fn column_name(column: &Column<Any>) -> String {
+    let prefix = match column.column_type() {
+        Any::Advice(_) => "a",
+        Any::Fixed => "f",
+        Any::Instance => "i",
+    };
+    format!("{}{}", prefix, column.index())
+}
+
+/// The columns and selectors `expression` queries, as [`render`] names them, without their
+/// rotations.
+fn queried<F: FieldExt>(expression: &Expression<F>) -> Vec<String> {
+    let mut names = expression.evaluate(
+        &|_| vec![],
+        &|selector| vec![format!("{:?}", selector)],
+        &|query| vec![format!("f{}", query.column_index())],
+        &|query| vec![format!("a{}", query.column_index())],
+        &|query| vec![format!("i{}", query.column_index())],
+        &|_| vec![],
+        &|a| a,
+        &|a, b| [a, b].concat(),
+        &|a, b| [a, b].concat(),
+        &|a, _| a,
+    );
+    names.sort();
+    names.dedup();
+    names
+}
+
+/// One line per column, listing the rotations it is queried at.
+fn rotations(queries: impl Iterator<Item = (usize, i32)>, prefix: &str) -> Vec<String> {
+    let mut queries: Vec<_> = queries.collect();
+    queries.sort_unstable();
+    let mut groups: Vec<(usize, Vec<String>)> = vec![];
+    for (column, rotation) in queries {
+        let rotation = format!("{:+}", rotation);
+        match groups.last_mut() {
+            Some((last, rotations)) if *last == column => rotations.push(rotation),
+            _ => groups.push((column, vec![rotation])),
+        }
+    }
+    groups
+        .into_iter()
+        .map(|(column, rotations)| format!("{}{}: {}", prefix, column, rotations.join(", ")))
+        .collect()
+}
+
+/// What `C::configure` puts in a fresh constraint system, in the algebraic form of [`render`]:
+/// each gate's polynomials with their degrees, each lookup's input and table expressions,
+/// the columns under the permutation argument, and the rotations each column is queried at.
+pub fn dump_constraint_system<F: FieldExt, C: Circuit<F>>() -> String {
+    let mut cs = ConstraintSystem::default();
+    C::configure(&mut cs);
+    let mut out = format!(
+        "{}: degree {}, {} advice, {} fixed, {} instance columns, {} selectors, {} blinding rows\n",
+        std::any::type_name::<C>(),
+        cs.degree(),
+        cs.num_advice_columns(),
+        cs.num_fixed_columns(),
+        cs.num_instance_columns(),
+        cs.num_selectors(),
+        cs.blinding_factors() + 1
+    );
+    for gate in cs.gates().iter() {
+        out += &format!("gate \"{}\"\n", gate.name());
+        for (index, polynomial) in gate.polynomials().iter().enumerate() {
+            let name = gate.constraint_name(index);
+            out += &format!("  {}", index);
+            if !name.is_empty() {
+                out += &format!(" \"{}\"", name);
+            }
+            out += &format!(
+                " (degree {}): {}\n",
+                polynomial.degree(),
+                render(polynomial)
+            );
+        }
+    }
+    for (index, lookup) in cs.lookups().iter().enumerate() {
+        out += &format!("lookup {}\n", index);
+        for (input, table) in lookup
+            .input_expressions()
+            .iter()
+            .zip(lookup.table_expressions().iter())
+        {
+            out += &format!("  {} in {}\n", render(input), render(table));
+        }
+    }
+    let permuted: Vec<String> = cs
+        .permutation()
+        .get_columns()
+        .iter()
+        .map(column_name)
+        .collect();
+    out += &format!("permutation: {}\n", permuted.join(", "));
+    out += "queries:\n";
+    let queries = rotations(
+        cs.advice_queries()
+            .iter()
+            .map(|(column, rotation)| (column.index(), rotation.0)),
+        "a",
+    )
+    .into_iter()
+    .chain(rotations(
+        cs.fixed_queries()
+            .iter()
+            .map(|(column, rotation)| (column.index(), rotation.0)),
+        "f",
+    ))
+    .chain(rotations(
+        cs.instance_queries()
+            .iter()
+            .map(|(column, rotation)| (column.index(), rotation.0)),
+        "i",
+    ));
+    for line in queries {
+        out += &format!("  {}\n", line);
+    }
+    out
+}
+
+/// The same constraint system as a Graphviz digraph: an edge from each gate and lookup to
+/// every column and selector it queries, and the permutation as a node of its own.
+pub fn constraint_system_dot<F: FieldExt, C: Circuit<F>>() -> String {
+    let mut cs = ConstraintSystem::default();
+    C::configure(&mut cs);
+    let mut out = "digraph constraints {\n  rankdir=LR;\n".to_string();
+    for (index, gate) in cs.gates().iter().enumerate() {
+        let node = format!("gate{}", index);
+        out += &format!(
+            "  {} [shape=box, label={:?}];\n",
+            node,
+            format!("gate {}", gate.name())
+        );
+        let mut columns: Vec<String> = gate.polynomials().iter().flat_map(queried).collect();
+        columns.sort();
+        columns.dedup();
+        for column in columns {
+            out += &format!("  {} -> {:?};\n", node, column);
+        }
+    }
+    for (index, lookup) in cs.lookups().iter().enumerate() {
+        let node = format!("lookup{}", index);
+        out += &format!("  {} [shape=diamond, label=\"lookup {}\"];\n", node, index);
+        let mut inputs: Vec<String> = lookup
+            .input_expressions()
+            .iter()
+            .flat_map(queried)
+            .collect();
+        inputs.sort();
+        inputs.dedup();
+        for column in inputs {
+            out += &format!("  {:?} -> {};\n", column, node);
+        }
+        let mut tables: Vec<String> = lookup
+            .table_expressions()
+            .iter()
+            .flat_map(queried)
+            .collect();
+        tables.sort();
+        tables.dedup();
+        for column in tables {
+            out += &format!("  {} -> {:?} [style=dashed];\n", node, column);
+        }
+    }
+    out += "  permutation [shape=ellipse];\n";
+    for column in cs.permutation().get_columns().iter() {
+        out += &format!(
+            "  permutation -> {:?} [style=dotted];\n",
+            column_name(column)
+        );
+    }
+    out + "}\n"
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum LayoutFormat {
+    Svg,
+    Png,
+}
+
+impl LayoutFormat {
+    /// The format `path`'s extension names.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        match path.extension()?.to_str()? {
+            "svg" => Some(LayoutFormat::Svg),
+            "png" => Some(LayoutFormat::Png),
+            _ => None,
+        }
+    }
+}
+
+/// How [`render_layout`] draws a circuit.
+#[derive(Clone, Debug)]
+pub struct LayoutOptions {
+    pub format: LayoutFormat,
+    /// In pixels.
+    pub size: (u32, u32),
+    pub title: Option<String>,
+    /// The rows drawn, all of them if `None`.
+    pub rows: Option<Range<usize>>,
+    /// The columns drawn, instance then advice then fixed, all of them if `None`.
+    pub columns: Option<Range<usize>>,
+    pub region_labels: bool,
+    /// Mark the cells in copy constraints and draw the constraints between them.
+    pub equality: bool,
+}
+
+impl Default for LayoutOptions {
+    fn default() -> Self {
+        LayoutOptions {
+            format: LayoutFormat::Svg,
+            size: (1024, 768),
+            title: None,
+            rows: None,
+            columns: None,
+            region_labels: true,
+            equality: true,
+        }
+    }
+}
+
+impl LayoutOptions {
+    /// The defaults, in the format `path` is named for.
+    pub fn for_path(path: &Path) -> Result<Self, PlaygroundError> {
+        let format = LayoutFormat::from_path(path).ok_or_else(|| {
+            PlaygroundError::Other(format!("{}: expected a .svg or .png file", path.display()))
+        })?;
+        Ok(LayoutOptions {
+            format,
+            ..LayoutOptions::default()
+        })
+    }
+}
+
+/// Draws `circuit`'s layout with `2^k` rows to `path`.
+#[cfg(feature = "dev-graph")]
+pub fn render_layout<F: FieldExt, C: Circuit<F>>(
+    circuit: &C,
+    k: u32,
+    path: &Path,
+    options: &LayoutOptions,
+) -> Result<(), PlaygroundError> {
+    use plotters::prelude::{BitMapBackend, IntoDrawingArea, SVGBackend};
+
+    let drawn = match options.format {
+        LayoutFormat::Svg => draw_layout(
+            circuit,
+            k,
+            SVGBackend::new(path, options.size).into_drawing_area(),
+            options,
+        ),
+        LayoutFormat::Png => draw_layout(
+            circuit,
+            k,
+            BitMapBackend::new(path, options.size).into_drawing_area(),
+            options,
+        ),
+    };
+    drawn.map_err(|e| PlaygroundError::Other(format!("{}: {}", path.display(), e)))
+}
+
+#[cfg(feature = "dev-graph")]
+fn draw_layout<F: FieldExt, C: Circuit<F>, DB: plotters::prelude::DrawingBackend>(
+    circuit: &C,
+    k: u32,
+    root: plotters::prelude::DrawingArea<DB, plotters::coord::Shift>,
+    options: &LayoutOptions,
+) -> Result<(), plotters::drawing::DrawingAreaErrorKind<DB::ErrorType>> {
+    root.fill(&plotters::style::WHITE)?;
+    let root = match &options.title {
+        Some(title) => root.titled(title, ("sans-serif", 20))?,
+        None => root,
+    };
+    let mut layout = halo2_proofs::dev::CircuitLayout::default()
+        .show_labels(options.region_labels)
+        .mark_equality_cells(options.equality)
+        .show_equality_constraints(options.equality);
+    if let Some(rows) = options.rows.clone() {
+        layout = layout.view_height(rows);
+    }
+    if let Some(columns) = options.columns.clone() {
+        layout = layout.view_width(columns);
+    }
+    layout.render(k, circuit, &root)?;
+    root.present()
+}
+
+fn split<F: FieldExt>(expression: &Expression<F>) -> Vec<Expression<F>> {
+    match expression {
+        Expression::Sum(a, b) => split(a).into_iter().chain(split(b)).collect(),
+        Expression::Negated(a) => split(a).into_iter().map(|term| -term).collect(),
+        Expression::Scaled(a, scalar) => split(a).into_iter().map(|term| term * *scalar).collect(),
+        Expression::Product(a, b) => match (a.as_ref(), b.as_ref()) {
+            (Expression::Selector(_), _) => split(b)
+                .into_iter()
+                .map(|term| a.as_ref().clone() * term)
+                .collect(),
+            (_, Expression::Selector(_)) => split(a)
+                .into_iter()
+                .map(|term| term * b.as_ref().clone())
+                .collect(),
+            _ => vec![expression.clone()],
+        },
+        _ => vec![expression.clone()],
+    }
+}
+
+fn parenthesized(term: String) -> String {
+    if term.contains(' ') {
+        format!("({})", term)
+    } else {
+        term
+    }
+}
+
+fn rotated(prefix: &str, column: usize, rotation: i32) -> String {
+    match rotation {
+        0 => format!("{}{}", prefix, column),
+        r => format!("{}{}[{:+}]", prefix, column, r),
+    }
+}
+
+/// `expression` in algebraic form: `a1`, `f0[+1]` and `i0` for advice, fixed and instance
+/// queries, `c0` for challenges and the selector's debug form for selectors.
+pub fn render<F: FieldExt>(expression: &Expression<F>) -> String {
+    expression.evaluate(
+        &|constant| to_short(&constant),
+        &|selector| format!("{:?}", selector),
+        &|query| rotated("f", query.column_index(), query.rotation().0),
+        &|query| rotated("a", query.column_index(), query.rotation().0),
+        &|query| rotated("i", query.column_index(), query.rotation().0),
+        &|challenge| format!("c{}", challenge.index()),
+        &|a| format!("-{}", parenthesized(a)),
+        &|a, b| match b.strip_prefix('-') {
+            Some(b) => format!("{} - {}", a, b),
+            None => format!("{} + {}", a, b),
+        },
+        &|a, b| format!("{} * {}", parenthesized(a), parenthesized(b)),
+        &|a, scalar| format!("{} * {}", to_short(&scalar), parenthesized(a)),
+    )
+}
+
+fn value<F: FieldExt, V: Into<Assigned<F>>>(to: Value<V>) -> Option<F> {
+    let mut value = None;
+    to.map(|v| value = Some(v.into().evaluate()));
+    value
+}
+
+impl<F: FieldExt> Assignment<F> for Assignments<F> {
+    fn enter_region<NR, N>(&mut self, name: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+        self.regions.push(AssignedRegion::new(name().into()));
+        self.in_region = true;
+    }
+
+    fn exit_region(&mut self) {
+        self.in_region = false;
+    }
+
+    fn enable_selector<A, AR>(&mut self, _: A, selector: &Selector, row: usize) -> Result<(), Error>
+    where
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.check_row(row)?;
+        self.used_rows = self.used_rows.max(row + 1);
+        self.selectors.insert((*selector, row));
+        self.current_region().selectors.insert((*selector, row));
+        Ok(())
+    }
+
+    fn query_instance(&self, column: Column<Instance>, row: usize) -> Result<Value<F>, Error> {
+        self.check_row(row)?;
+        Ok(Value::known(self.instance[column.index()][row]))
+    }
+
+    fn assign_advice<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        column: Column<Advice>,
+        row: usize,
+        to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.check_row(row)?;
+        self.used_rows = self.used_rows.max(row + 1);
+        self.advice[column.index()][row] = value(to()).unwrap_or_else(F::zero);
+        self.current_region().cells.insert((column.into(), row));
+        Ok(())
+    }
+
+    fn assign_fixed<V, VR, A, AR>(
+        &mut self,
+        _: A,
+        column: Column<Fixed>,
+        row: usize,
+        to: V,
+    ) -> Result<(), Error>
+    where
+        V: FnOnce() -> Value<VR>,
+        VR: Into<Assigned<F>>,
+        A: FnOnce() -> AR,
+        AR: Into<String>,
+    {
+        self.check_row(row)?;
+        self.used_rows = self.used_rows.max(row + 1);
+        self.fixed[column.index()][row] = value(to()).unwrap_or_else(F::zero);
+        self.current_region().cells.insert((column.into(), row));
+        Ok(())
+    }
+
+    fn copy(&mut self, _: Column<Any>, _: usize, _: Column<Any>, _: usize) -> Result<(), Error> {
+        Ok(())
+    }
+
+    fn fill_from_row(
+        &mut self,
+        column: Column<Fixed>,
+        row: usize,
+        to: Value<Assigned<F>>,
+    ) -> Result<(), Error> {
+        self.check_row(row)?;
+        let to = value(to).unwrap_or_else(F::zero);
+        for cell in self.fixed[column.index()][row..self.usable_rows].iter_mut() {
+            *cell = to;
+        }
+        Ok(())
+    }
+
+    fn get_challenge(&self, challenge: Challenge) -> Value<F> {
+        Value::known(self.challenges[challenge.index()])
+    }
+
+    fn push_namespace<NR, N>(&mut self, _: N)
+    where
+        NR: Into<String>,
+        N: FnOnce() -> NR,
+    {
+    }
+
+    fn pop_namespace(&mut self, _: Option<String>) {}
+}
@@ -0,0 +1,267 @@
+//! Parsing of witness and instance values from text.
+//!
+//! Values are decimal or `0x`-prefixed big-endian hex. A value at or above the modulus would
+//! otherwise wrap silently, so it is rejected unless the caller asks for it to be reduced.
+//!
+//! Instances also have a JSON form, an array of columns of `0x` hex strings, and proofs a hex
+//! and a base64 one, for passing them through tools that only handle text.
+
+use std::fmt;
+
+use halo2_proofs::arithmetic::FieldExt;
+
+use crate::{fmt::to_hex_padded, json::Json};
+
+/// Wider than any supported modulus, so every value that fits can be reduced.
+const WIDE_BYTES: usize = 64;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Reduction {
+    Reject,
+    Reduce,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum EncodingError {
+    Empty,
+    InvalidDigit(char),
+    TooLarge,
+    NonCanonical(String),
+    /// Bytes left over after the last whole 32-byte word.
+    PartialWord(usize),
+    Json(String),
+    /// Text that is not a whole number of bytes in this encoding.
+    Length {
+        encoding: &'static str,
+        len: usize,
+    },
+}
+
+impl fmt::Display for EncodingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            EncodingError::Empty => write!(f, "empty field element"),
+            EncodingError::InvalidDigit(c) => write!(f, "invalid digit {:?}", c),
+            EncodingError::TooLarge => write!(f, "value does not fit in {} bytes", WIDE_BYTES),
+            EncodingError::NonCanonical(s) => {
+                write!(f, "{} is not below the field modulus", s)
+            }
+            EncodingError::PartialWord(bytes) => {
+                write!(f, "{} bytes do not fill a 32-byte word", bytes)
+            }
+            EncodingError::Json(err) => write!(f, "invalid JSON: {}", err),
+            EncodingError::Length { encoding, len } => {
+                write!(f, "{} {} digits do not make whole bytes", len, encoding)
+            }
+        }
+    }
+}
+
+impl std::error::Error for EncodingError {}
+
+/// Multiplies the little-endian integer `acc` by `radix` and adds `digit`.
+fn mul_add(acc: &mut [u8; WIDE_BYTES], radix: u32, digit: u32) -> Result<(), EncodingError> {
+    let mut carry = digit;
+    for byte in acc.iter_mut() {
+        let v = *byte as u32 * radix + carry;
+        *byte = v as u8;
+        carry = v >> 8;
+    }
+    if carry != 0 {
+        return Err(EncodingError::TooLarge);
+    }
+    Ok(())
+}
+
+fn parse_wide(s: &str) -> Result<[u8; WIDE_BYTES], EncodingError> {
+    let (digits, radix) = match s.strip_prefix("0x").or_else(|| s.strip_prefix("0X")) {
+        Some(hex) => (hex, 16),
+        None => (s, 10),
+    };
+    if digits.is_empty() {
+        return Err(EncodingError::Empty);
+    }
+    let mut acc = [0u8; WIDE_BYTES];
+    for c in digits.chars().filter(|c| *c != '_') {
+        let digit = c.to_digit(radix).ok_or(EncodingError::InvalidDigit(c))?;
+        mul_add(&mut acc, radix, digit)?;
+    }
+    Ok(acc)
+}
+
+/// Interprets little-endian bytes as a field element, checking that it is canonical.
+pub fn field_from_le_bytes<F: FieldExt>(
+    bytes: &[u8],
+    reduction: Reduction,
+) -> Result<F, EncodingError> {
+    if bytes.len() > WIDE_BYTES {
+        return Err(EncodingError::TooLarge);
+    }
+    let mut wide = [0u8; WIDE_BYTES];
+    wide[..bytes.len()].copy_from_slice(bytes);
+
+    let mut repr = F::Repr::default();
+    let repr_len = repr.as_ref().len();
+    let canonical = if wide[repr_len..].iter().all(|b| *b == 0) {
+        repr.as_mut().copy_from_slice(&wide[..repr_len]);
+        Option::from(F::from_repr(repr))
+    } else {
+        None
+    };
+    match (canonical, reduction) {
+        (Some(value), _) => Ok(value),
+        (None, Reduction::Reduce) => Ok(F::from_bytes_wide(&wide)),
+        (None, Reduction::Reject) => {
+            let hex: String = bytes.iter().rev().map(|b| format!("{:02x}", b)).collect();
+            Err(EncodingError::NonCanonical(format!("0x{}", hex)))
+        }
+    }
+}
+
+/// Parses a decimal or `0x`-prefixed hex field element.
+pub fn parse_field<F: FieldExt>(s: &str, reduction: Reduction) -> Result<F, EncodingError> {
+    let s = s.trim();
+    let wide = parse_wide(s)?;
+    field_from_le_bytes(&wide, reduction).map_err(|err| match err {
+        EncodingError::NonCanonical(_) => EncodingError::NonCanonical(s.to_string()),
+        err => err,
+    })
+}
+
+/// Parses whitespace or comma separated field elements, one instance column per line.
+pub fn parse_columns<F: FieldExt>(
+    text: &str,
+    reduction: Reduction,
+) -> Result<Vec<Vec<F>>, EncodingError> {
+    text.lines()
+        .map(|line| line.split('#').next().unwrap_or_default())
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| {
+            line.split(|c: char| c == ',' || c.is_whitespace())
+                .filter(|s| !s.is_empty())
+                .map(|s| parse_field(s, reduction))
+                .collect()
+        })
+        .collect()
+}
+
+/// Instance columns as a JSON array of arrays of full-width hex strings.
+pub fn encode_instances<F: FieldExt>(columns: &[Vec<F>]) -> String {
+    let columns: Vec<String> = columns
+        .iter()
+        .map(|column| {
+            let values: Vec<String> = column
+                .iter()
+                .map(|v| format!("\"{}\"", to_hex_padded(v)))
+                .collect();
+            format!("[{}]", values.join(","))
+        })
+        .collect();
+    format!("[{}]", columns.join(","))
+}
+
+/// Parses instance columns from a JSON array of arrays whose values are strings, as accepted by
+/// [`parse_field`], or unsigned integers.
+pub fn decode_instances<F: FieldExt>(
+    text: &str,
+    reduction: Reduction,
+) -> Result<Vec<Vec<F>>, EncodingError> {
+    let json = Json::parse(text).map_err(EncodingError::Json)?;
+    let not_columns = || EncodingError::Json("expected an array of arrays".to_string());
+    json.as_array()
+        .ok_or_else(not_columns)?
+        .iter()
+        .map(|column| -> Result<Vec<F>, EncodingError> {
+            column
+                .as_array()
+                .ok_or_else(not_columns)?
+                .iter()
+                .map(|value| {
+                    let value = value.as_scalar().ok_or_else(|| {
+                        EncodingError::Json(format!(
+                            "expected a string or integer, found {:?}",
+                            value
+                        ))
+                    })?;
+                    parse_field(value, reduction)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+pub fn encode_hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Parses hex digits, with or without a `0x` prefix, ignoring surrounding whitespace.
+pub fn decode_hex(text: &str) -> Result<Vec<u8>, EncodingError> {
+    let text = text.trim();
+    let digits = text
+        .strip_prefix("0x")
+        .or_else(|| text.strip_prefix("0X"))
+        .unwrap_or(text);
+    if digits.len() % 2 != 0 {
+        return Err(EncodingError::Length {
+            encoding: "hex",
+            len: digits.len(),
+        });
+    }
+    let digit = |c: char| c.to_digit(16).ok_or(EncodingError::InvalidDigit(c));
+    let chars: Vec<char> = digits.chars().collect();
+    chars
+        .chunks(2)
+        .map(|pair| Ok((digit(pair[0])? << 4 | digit(pair[1])?) as u8))
+        .collect()
+}
+
+const BASE64: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+/// Standard base64 with padding.
+pub fn encode_base64(bytes: &[u8]) -> String {
+    let mut out = String::with_capacity((bytes.len() + 2) / 3 * 4);
+    for chunk in bytes.chunks(3) {
+        let mut group = [0u8; 3];
+        group[..chunk.len()].copy_from_slice(chunk);
+        let n = (group[0] as u32) << 16 | (group[1] as u32) << 8 | group[2] as u32;
+        for i in 0..4 {
+            if i <= chunk.len() {
+                out.push(BASE64[(n >> (18 - 6 * i) & 0x3f) as usize] as char);
+            } else {
+                out.push('=');
+            }
+        }
+    }
+    out
+}
+
+/// Parses standard padded base64, ignoring surrounding whitespace.
+pub fn decode_base64(text: &str) -> Result<Vec<u8>, EncodingError> {
+    let text = text.trim();
+    if text.len() % 4 != 0 {
+        return Err(EncodingError::Length {
+            encoding: "base64",
+            len: text.len(),
+        });
+    }
+    let body = text.trim_end_matches('=');
+    if text.len() - body.len() > 2 {
+        return Err(EncodingError::InvalidDigit('='));
+    }
+    let mut out = Vec::with_capacity(text.len() / 4 * 3);
+    let (mut acc, mut bits) = (0u32, 0);
+    for c in body.chars() {
+        let value = BASE64
+            .iter()
+            .position(|b| *b as char == c)
+            .ok_or(EncodingError::InvalidDigit(c))?;
+        acc = acc << 6 | value as u32;
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            out.push((acc >> bits) as u8);
+            acc &= (1 << bits) - 1;
+        }
+    }
+    Ok(out)
+}
@@ -0,0 +1,293 @@
+//! Where proving randomness comes from.
+//!
+//! The prover wrappers take an [`EntropySource`] rather than any `RngCore`, so every caller
+//! states its randomness policy: OS entropy for real proofs, a seed for reproducible runs, the
+//! CPU's hardware generator, or a public randomness beacon. Binaries take theirs from
+//! `--seed <hex>` or `--random` through [`entropy_from_args`].
+
+use rand::{
+    rngs::{OsRng, StdRng},
+    CryptoRng, RngCore, SeedableRng,
+};
+
+use crate::error::PlaygroundError;
+
+pub trait EntropySource: RngCore {
+    /// Short description for logs and reports.
+    fn describe(&self) -> String;
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct OsEntropy;
+
+impl RngCore for OsEntropy {
+    fn next_u32(&mut self) -> u32 {
+        OsRng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        OsRng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        OsRng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        OsRng.try_fill_bytes(dest)
+    }
+}
+
+impl CryptoRng for OsEntropy {}
+
+impl EntropySource for OsEntropy {
+    fn describe(&self) -> String {
+        "os".to_string()
+    }
+}
+
+/// Deterministic randomness, for reproducible proofs. Not zero knowledge if the seed leaks.
+#[derive(Clone, Debug)]
+pub struct SeededEntropy {
+    seed: u64,
+    rng: StdRng,
+}
+
+impl SeededEntropy {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            rng: StdRng::seed_from_u64(seed),
+        }
+    }
+}
+
+impl RngCore for SeededEntropy {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+impl EntropySource for SeededEntropy {
+    fn describe(&self) -> String {
+        format!("seeded({})", self.seed)
+    }
+}
+
+/// The CPU's RDRAND instruction.
+#[derive(Clone, Copy, Debug)]
+pub struct HardwareEntropy {
+    _private: (),
+}
+
+impl HardwareEntropy {
+    /// `None` unless the CPU supports RDRAND.
+    pub fn new() -> Option<Self> {
+        #[cfg(target_arch = "x86_64")]
+        if is_x86_feature_detected!("rdrand") {
+            return Some(Self { _private: () });
+        }
+        None
+    }
+
+    #[cfg(target_arch = "x86_64")]
+    fn rdrand(&self) -> Option<u64> {
+        // RDRAND may transiently fail, retrying a few times is the documented approach
+        for _ in 0..10 {
+            let mut value = 0;
+            // SAFETY: `new` checked that the CPU supports RDRAND
+            if unsafe { std::arch::x86_64::_rdrand64_step(&mut value) } == 1 {
+                return Some(value);
+            }
+        }
+        None
+    }
+
+    #[cfg(not(target_arch = "x86_64"))]
+    fn rdrand(&self) -> Option<u64> {
+        None
+    }
+}
+
+impl RngCore for HardwareEntropy {
+    fn next_u32(&mut self) -> u32 {
+        self.next_u64() as u32
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rdrand().expect("RDRAND failed")
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.try_fill_bytes(dest).expect("RDRAND failed")
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        for chunk in dest.chunks_mut(8) {
+            let value = self
+                .rdrand()
+                .ok_or_else(|| rand::Error::new("RDRAND failed"))?;
+            chunk.copy_from_slice(&value.to_le_bytes()[..chunk.len()]);
+        }
+        Ok(())
+    }
+}
+
+impl CryptoRng for HardwareEntropy {}
+
+impl EntropySource for HardwareEntropy {
+    fn describe(&self) -> String {
+        "rdrand".to_string()
+    }
+}
+
+/// Randomness expanded from a public beacon round (e.g. drand), fetched by the caller. Anyone
+/// can recompute it, so it suits ceremonies and public coins, not proof blinding.
+#[derive(Clone, Debug)]
+pub struct BeaconEntropy {
+    round: u64,
+    rng: StdRng,
+}
+
+impl BeaconEntropy {
+    pub fn new(round: u64, randomness: &[u8]) -> Self {
+        let seed = blake2b_simd::Params::new()
+            .hash_length(32)
+            .personal(b"Beacon-Entropy")
+            .to_state()
+            .update(&round.to_le_bytes())
+            .update(randomness)
+            .finalize();
+        Self {
+            round,
+            rng: StdRng::from_seed(seed.as_bytes().try_into().unwrap()),
+        }
+    }
+}
+
+impl RngCore for BeaconEntropy {
+    fn next_u32(&mut self) -> u32 {
+        self.rng.next_u32()
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        self.rng.next_u64()
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.rng.fill_bytes(dest)
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        self.rng.try_fill_bytes(dest)
+    }
+}
+
+impl EntropySource for BeaconEntropy {
+    fn describe(&self) -> String {
+        format!("beacon(round {})", self.round)
+    }
+}
+
+impl<E: EntropySource + ?Sized> EntropySource for &mut E {
+    fn describe(&self) -> String {
+        (**self).describe()
+    }
+}
+
+/// The proving randomness a binary was run with, see [`entropy_from_args`].
+#[derive(Clone, Debug)]
+pub enum ChosenEntropy {
+    Seeded(SeededEntropy),
+    Os(OsEntropy),
+}
+
+impl RngCore for ChosenEntropy {
+    fn next_u32(&mut self) -> u32 {
+        match self {
+            ChosenEntropy::Seeded(rng) => rng.next_u32(),
+            ChosenEntropy::Os(rng) => rng.next_u32(),
+        }
+    }
+
+    fn next_u64(&mut self) -> u64 {
+        match self {
+            ChosenEntropy::Seeded(rng) => rng.next_u64(),
+            ChosenEntropy::Os(rng) => rng.next_u64(),
+        }
+    }
+
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        match self {
+            ChosenEntropy::Seeded(rng) => rng.fill_bytes(dest),
+            ChosenEntropy::Os(rng) => rng.fill_bytes(dest),
+        }
+    }
+
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), rand::Error> {
+        match self {
+            ChosenEntropy::Seeded(rng) => rng.try_fill_bytes(dest),
+            ChosenEntropy::Os(rng) => rng.try_fill_bytes(dest),
+        }
+    }
+}
+
+impl EntropySource for ChosenEntropy {
+    fn describe(&self) -> String {
+        match self {
+            ChosenEntropy::Seeded(rng) => rng.describe(),
+            ChosenEntropy::Os(rng) => rng.describe(),
+        }
+    }
+}
+
+/// Parses a hex seed, with or without `0x`.
+pub fn parse_seed(hex: &str) -> Result<u64, PlaygroundError> {
+    let digits = hex.trim_start_matches("0x").replace('_', "");
+    u64::from_str_radix(&digits, 16)
+        .map_err(|_| PlaygroundError::Serialization(format!("invalid hex seed {:?}", hex)))
+}
+
+/// `--seed <hex>` as a [`SeededEntropy`], `--random` as [`OsEntropy`], otherwise
+/// `default_seed`, so a binary's proofs are reproducible unless asked otherwise.
+pub fn entropy_from_args(default_seed: u64) -> Result<ChosenEntropy, PlaygroundError> {
+    let mut args = std::env::args().skip(1);
+    let mut chosen = ChosenEntropy::Seeded(SeededEntropy::new(default_seed));
+    let (mut seeded, mut random) = (false, false);
+    while let Some(arg) = args.next() {
+        let seed = match arg.as_str() {
+            "--random" => {
+                random = true;
+                chosen = ChosenEntropy::Os(OsEntropy);
+                continue;
+            }
+            "--seed" => args
+                .next()
+                .ok_or_else(|| PlaygroundError::Other("--seed needs a value".to_string()))?,
+            _ => match arg.strip_prefix("--seed=") {
+                Some(seed) => seed.to_string(),
+                None => continue,
+            },
+        };
+        seeded = true;
+        chosen = ChosenEntropy::Seeded(SeededEntropy::new(parse_seed(&seed)?));
+    }
+    if seeded && random {
+        return Err(PlaygroundError::Other(
+            "--seed and --random exclude each other".to_string(),
+        ));
+    }
+    Ok(chosen)
+}
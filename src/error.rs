@@ -0,0 +1,82 @@
+//! The error of the playground's library helpers, one enum from setup to verification so a
+//! program driving them can tell what failed without parsing a message, and the CLI can
+//! exit with a status per kind (see [`PlaygroundError::exit_code`]).
+
+use std::{
+    fmt, io,
+    path::{Path, PathBuf},
+};
+
+use halo2_proofs::plonk::Error;
+use thiserror::Error;
+
+use crate::verifier::VerificationReport;
+
+#[derive(Debug, Error)]
+pub enum PlaygroundError {
+    /// Params that couldn't be generated, fetched or checked, or a circuit too large for any.
+    #[error("setup: {0}")]
+    Setup(String),
+    /// `keygen_vk` or `keygen_pk` rejected the circuit, e.g. under params too small for it.
+    #[error("keygen: {0:?}")]
+    Keygen(Error),
+    /// `create_proof` failed, most often on instances that don't fit the circuit.
+    #[error("create_proof: {0:?}")]
+    Prove(Error),
+    /// Nothing to prove: a witness the mock prover rejects, or a proof past its time budget.
+    #[error("{0}")]
+    Unprovable(String),
+    /// A proof, or one of a batch, that doesn't verify, with what failed.
+    #[error("{0}")]
+    Verify(String),
+    /// A file that couldn't be read or written, params and keys that don't parse included.
+    #[error("{}: {}", .path.display(), .source)]
+    Io {
+        path: PathBuf,
+        #[source]
+        source: io::Error,
+    },
+    /// Text or bytes that don't decode, or decode to values the circuit doesn't accept.
+    #[error("{0}")]
+    Serialization(String),
+    #[error("audit log: {0}")]
+    AuditLog(#[source] io::Error),
+    /// Anything else, e.g. options an operation doesn't support.
+    #[error("{0}")]
+    Other(String),
+}
+
+impl PlaygroundError {
+    /// An [`PlaygroundError::Io`] on `path`, for `map_err`.
+    pub fn io(path: &Path) -> impl FnOnce(io::Error) -> Self + '_ {
+        move |source| PlaygroundError::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    /// A [`PlaygroundError::Serialization`] prefixed with the file it concerns.
+    pub fn serialization(path: &Path, err: impl fmt::Display) -> Self {
+        PlaygroundError::Serialization(format!("{}: {}", path.display(), err))
+    }
+
+    /// The process exit status for the error: 1 for a proof that doesn't verify, as before
+    /// there were kinds, and one above clap's 2 for usage errors per kind of failure.
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            PlaygroundError::Verify(_) | PlaygroundError::Other(_) => 1,
+            PlaygroundError::Setup(_) => 3,
+            PlaygroundError::Keygen(_) => 4,
+            PlaygroundError::Prove(_) | PlaygroundError::Unprovable(_) => 5,
+            PlaygroundError::Serialization(_) => 6,
+            PlaygroundError::Io { .. } | PlaygroundError::AuditLog(_) => 7,
+        }
+    }
+}
+
+/// A proof that doesn't verify, for `?` on [`VerificationReport::into_result`].
+impl From<VerificationReport> for PlaygroundError {
+    fn from(report: VerificationReport) -> Self {
+        PlaygroundError::Verify(format!("verify_proof: {}", report))
+    }
+}
@@ -0,0 +1,275 @@
+//! On-chain artifacts for proofs made with the Keccak256 transcript: calldata, a gas estimate,
+//! and the verifying key as a Solidity library.
+//!
+//! The EVM reads a field element as a big-endian `uint256`, while halo2's representations are
+//! little-endian; [`to_word`] and [`from_word`] are the one place that order flips.
+//!
+//! snark-verifier is built against a different halo2 fork, so the verifier contract itself
+//! isn't generated here; the library holds the constants it needs.
+
+use std::{collections::BTreeSet, convert::TryInto, fmt::Write};
+
+use halo2_proofs::{
+    arithmetic::{CurveAffine, Field},
+    halo2curves::{
+        bn256::{Bn256, Fq, Fr, G1Affine, G2Affine},
+        group::ff::PrimeField,
+    },
+    plonk::VerifyingKey,
+    poly::kzg::commitment::ParamsKZG,
+};
+
+use crate::{
+    encoding::{field_from_le_bytes, EncodingError, Reduction},
+    prover::MultiOpen,
+};
+
+const WORD: usize = 32;
+
+/// Gas of the precompiles (EIP-1108) and of calldata (EIP-2028).
+const EC_ADD_GAS: u64 = 150;
+const EC_MUL_GAS: u64 = 6_000;
+const PAIRING_BASE_GAS: u64 = 45_000;
+const PAIRING_PER_PAIR_GAS: u64 = 34_000;
+const TX_BASE_GAS: u64 = 21_000;
+const CALLDATA_ZERO_GAS: u64 = 4;
+const CALLDATA_NONZERO_GAS: u64 = 16;
+
+/// `value` as the big-endian 32-byte word a contract reads as `uint256`.
+pub fn to_word<F: PrimeField>(value: &F) -> [u8; WORD] {
+    let mut word = [0; WORD];
+    word.copy_from_slice(value.to_repr().as_ref());
+    word.reverse();
+    word
+}
+
+/// The field element of a big-endian word, rejecting words at or above the modulus, which
+/// a verifier contract rejects too.
+pub fn from_word(word: &[u8; WORD]) -> Result<Fr, EncodingError> {
+    let mut le = *word;
+    le.reverse();
+    field_from_le_bytes(&le, Reduction::Reject)
+}
+
+/// `values` as a packed `bytes32` array: their words back to back, without a length prefix.
+pub fn to_words(values: &[Fr]) -> Vec<u8> {
+    values.iter().flat_map(to_word).collect()
+}
+
+/// The inverse of [`to_words`].
+pub fn from_words(bytes: &[u8]) -> Result<Vec<Fr>, EncodingError> {
+    if bytes.len() % WORD != 0 {
+        return Err(EncodingError::PartialWord(bytes.len() % WORD));
+    }
+    bytes
+        .chunks(WORD)
+        .map(|word| from_word(word.try_into().unwrap()))
+        .collect()
+}
+
+fn word<F: PrimeField>(value: &F) -> String {
+    let hex: String = to_word(value)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("0x{}", hex)
+}
+
+/// Instances as big-endian words followed by the proof, the layout the verifier reads.
+pub fn encode_calldata(instances: &[&[Fr]], proof: &[u8]) -> Vec<u8> {
+    let mut calldata = to_words(&instances.concat());
+    calldata.extend_from_slice(proof);
+    calldata
+}
+
+pub fn calldata_gas(calldata: &[u8]) -> u64 {
+    calldata
+        .iter()
+        .map(|b| {
+            if *b == 0 {
+                CALLDATA_ZERO_GAS
+            } else {
+                CALLDATA_NONZERO_GAS
+            }
+        })
+        .sum()
+}
+
+/// Points a verifier opens at for GWC, one per distinct rotation.
+fn opening_points(vk: &VerifyingKey<G1Affine>) -> usize {
+    let cs = &vk.cs;
+    let mut rotations: BTreeSet<i32> = cs
+        .advice_queries()
+        .iter()
+        .map(|(_, rotation)| rotation.0)
+        .chain(cs.fixed_queries().iter().map(|(_, rotation)| rotation.0))
+        .chain(cs.instance_queries().iter().map(|(_, rotation)| rotation.0))
+        .collect();
+    // the vanishing argument opens at x, the permutation at x, ωx and the last usable row
+    rotations.insert(0);
+    if !cs.permutation().get_columns().is_empty() {
+        rotations.insert(1);
+        rotations.insert(-(cs.blinding_factors() as i32 + 1));
+    }
+    if !cs.lookups().is_empty() {
+        rotations.insert(1);
+        rotations.insert(-1);
+    }
+    rotations.len()
+}
+
+/// Number of curve points in one proof.
+pub fn proof_points(vk: &VerifyingKey<G1Affine>, multiopen: MultiOpen) -> usize {
+    let cs = &vk.cs;
+    let chunk_len = cs.degree() - 2;
+    let permutation_chunks = (cs.permutation().get_columns().len() + chunk_len - 1) / chunk_len;
+    let opening = match multiopen {
+        MultiOpen::Shplonk => 2,
+        MultiOpen::Gwc => opening_points(vk),
+    };
+    cs.num_advice_columns()
+        + 3 * cs.lookups().len()
+        + permutation_chunks
+        + 1
+        + vk.domain.get_quotient_poly_degree()
+        + opening
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GasEstimate {
+    pub transaction: u64,
+    pub calldata: u64,
+    pub ec_mul: u64,
+    pub ec_add: u64,
+    pub pairing: u64,
+}
+
+impl GasEstimate {
+    pub fn total(&self) -> u64 {
+        self.transaction + self.calldata + self.ec_mul + self.ec_add + self.pairing
+    }
+}
+
+/// Lower bound on the gas of verifying `calldata` on chain: the transaction, the calldata and
+/// the precompile calls of one MSM over every commitment and one two-pair pairing check.
+/// Field arithmetic in the contract comes on top.
+pub fn estimate_gas(
+    vk: &VerifyingKey<G1Affine>,
+    multiopen: MultiOpen,
+    calldata: &[u8],
+) -> GasEstimate {
+    let msm = vk.fixed_commitments().len()
+        + vk.permutation().commitments().len()
+        + proof_points(vk, multiopen);
+    GasEstimate {
+        transaction: TX_BASE_GAS,
+        calldata: calldata_gas(calldata),
+        ec_mul: msm as u64 * EC_MUL_GAS,
+        ec_add: msm as u64 * EC_ADD_GAS,
+        pairing: PAIRING_BASE_GAS + 2 * PAIRING_PER_PAIR_GAS,
+    }
+}
+
+fn g1_words(point: &G1Affine) -> String {
+    let coordinates: Option<_> = point.coordinates().into();
+    match coordinates {
+        Some(coordinates) => format!(
+            "[uint256({}), {}]",
+            word(coordinates.x()),
+            word(coordinates.y())
+        ),
+        None => format!("[uint256({}), {}]", word(&Fq::zero()), word(&Fq::zero())),
+    }
+}
+
+/// The EIP-197 order of a G2 point's words: `x.c1, x.c0, y.c1, y.c0`.
+fn g2_words(point: &G2Affine) -> String {
+    format!(
+        "[uint256({}), {}, {}, {}]",
+        word(&point.x.c1),
+        word(&point.x.c0),
+        word(&point.y.c1),
+        word(&point.y.c0)
+    )
+}
+
+fn write_points(out: &mut String, name: &str, points: &[G1Affine]) {
+    writeln!(
+        out,
+        "    function {}() internal pure returns (uint256[2][{}] memory points) {{",
+        name,
+        points.len()
+    )
+    .unwrap();
+    for (i, point) in points.iter().enumerate() {
+        writeln!(out, "        points[{}] = {};", i, g1_words(point)).unwrap();
+    }
+    writeln!(out, "    }}").unwrap();
+}
+
+/// The verifying key and the verifier's G2 points as a Solidity library named `name`.
+pub fn solidity_vk(name: &str, vk: &VerifyingKey<G1Affine>, params: &ParamsKZG<Bn256>) -> String {
+    let domain = &vk.domain;
+    let n_inv = Fr::from(1u64 << domain.k()).invert().unwrap();
+    let mut out = String::new();
+    writeln!(out, "// SPDX-License-Identifier: MIT").unwrap();
+    writeln!(out, "// Generated by halo2-playground, do not edit.").unwrap();
+    writeln!(out, "pragma solidity ^0.8.0;").unwrap();
+    writeln!(out).unwrap();
+    writeln!(out, "library {} {{", name).unwrap();
+    writeln!(out, "    uint256 internal constant K = {};", domain.k()).unwrap();
+    writeln!(
+        out,
+        "    uint256 internal constant OMEGA = {};",
+        word(&domain.get_omega())
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    uint256 internal constant OMEGA_INV = {};",
+        word(&domain.get_omega_inv())
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    uint256 internal constant N_INV = {};",
+        word(&n_inv)
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    uint256 internal constant NUM_INSTANCE_COLUMNS = {};",
+        vk.cs.num_instance_columns()
+    )
+    .unwrap();
+    writeln!(
+        out,
+        "    uint256 internal constant BLINDING_FACTORS = {};",
+        vk.cs.blinding_factors()
+    )
+    .unwrap();
+    writeln!(out).unwrap();
+    write_points(&mut out, "fixedCommitments", vk.fixed_commitments());
+    writeln!(out).unwrap();
+    write_points(
+        &mut out,
+        "permutationCommitments",
+        vk.permutation().commitments(),
+    );
+    writeln!(out).unwrap();
+    writeln!(
+        out,
+        "    function g2() internal pure returns (uint256[4] memory) {{\n        return {};\n    }}",
+        g2_words(&params.g2())
+    )
+    .unwrap();
+    writeln!(out).unwrap();
+    writeln!(
+        out,
+        "    function sG2() internal pure returns (uint256[4] memory) {{\n        return {};\n    }}",
+        g2_words(&params.s_g2())
+    )
+    .unwrap();
+    writeln!(out, "}}").unwrap();
+    out
+}
@@ -0,0 +1,477 @@
+//! Circuits compiled from arithmetic relations such as `(a + b) * c - d^3 == out`, one
+//! standard PLONK row per operation, so ad-hoc statements can be proved without writing a
+//! circuit.
+//!
+//! A relation is two expressions over `+`, `-`, `*`, `^` with an integer exponent, parentheses,
+//! literals (decimal or `0x` hex) and variables. The variables on the right of `==` are public,
+//! in order of first appearance, and the others are private. When one side is a single variable
+//! left unset, its value is derived from the other side.
+
+use std::{fmt, marker::PhantomData};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+use crate::{
+    chips::standard_plonk::{StandardPlonkChip, StandardPlonkConfig},
+    encoding::{parse_field, Reduction},
+};
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Expr<F> {
+    /// An index into [`Relation::variables`].
+    Var(usize),
+    Const(F),
+    Add(Box<Expr<F>>, Box<Expr<F>>),
+    Sub(Box<Expr<F>>, Box<Expr<F>>),
+    Mul(Box<Expr<F>>, Box<Expr<F>>),
+    Pow(Box<Expr<F>>, u64),
+}
+
+impl<F: FieldExt> Expr<F> {
+    pub fn eval(&self, values: &[F]) -> F {
+        match self {
+            Expr::Var(i) => values[*i],
+            Expr::Const(c) => *c,
+            Expr::Add(a, b) => a.eval(values) + b.eval(values),
+            Expr::Sub(a, b) => a.eval(values) - b.eval(values),
+            Expr::Mul(a, b) => a.eval(values) * b.eval(values),
+            Expr::Pow(a, e) => a.eval(values).pow_vartime(&[*e]),
+        }
+    }
+
+    fn collect_vars(&self, vars: &mut Vec<usize>) {
+        match self {
+            Expr::Var(i) => {
+                if !vars.contains(i) {
+                    vars.push(*i);
+                }
+            }
+            Expr::Const(_) => {}
+            Expr::Add(a, b) | Expr::Sub(a, b) | Expr::Mul(a, b) => {
+                a.collect_vars(vars);
+                b.collect_vars(vars);
+            }
+            Expr::Pow(a, _) => a.collect_vars(vars),
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExprError {
+    /// A syntax error at a byte offset of the relation.
+    Parse {
+        position: usize,
+        message: String,
+    },
+    UnknownVariable(String),
+    Missing(Vec<String>),
+    /// The values given do not satisfy the relation.
+    Unsatisfied,
+}
+
+impl fmt::Display for ExprError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExprError::Parse { position, message } => {
+                write!(f, "at offset {}: {}", position, message)
+            }
+            ExprError::UnknownVariable(name) => write!(f, "no variable `{}`", name),
+            ExprError::Missing(names) => write!(f, "no value for {}", names.join(", ")),
+            ExprError::Unsatisfied => write!(f, "the values do not satisfy the relation"),
+        }
+    }
+}
+
+impl std::error::Error for ExprError {}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Token {
+    Number(String),
+    Ident(String),
+    Op(char),
+    Eq,
+    End,
+}
+
+fn tokenize(text: &str) -> Result<Vec<(usize, Token)>, ExprError> {
+    let mut tokens = vec![];
+    let mut chars = text.char_indices().peekable();
+    while let Some((position, c)) = chars.next() {
+        let token = match c {
+            c if c.is_whitespace() => continue,
+            '+' | '-' | '*' | '^' | '(' | ')' => Token::Op(c),
+            '=' if matches!(chars.peek(), Some((_, '='))) => {
+                chars.next();
+                Token::Eq
+            }
+            c if c.is_ascii_alphanumeric() || c == '_' => {
+                let mut word = c.to_string();
+                while let Some((_, c)) = chars.peek().copied() {
+                    if !(c.is_ascii_alphanumeric() || c == '_') {
+                        break;
+                    }
+                    word.push(c);
+                    chars.next();
+                }
+                if c.is_ascii_digit() {
+                    Token::Number(word)
+                } else {
+                    Token::Ident(word)
+                }
+            }
+            c => {
+                return Err(ExprError::Parse {
+                    position,
+                    message: format!("unexpected {:?}", c),
+                })
+            }
+        };
+        tokens.push((position, token));
+    }
+    tokens.push((text.len(), Token::End));
+    Ok(tokens)
+}
+
+struct Parser<F> {
+    tokens: Vec<(usize, Token)>,
+    next: usize,
+    variables: Vec<String>,
+    _marker: PhantomData<F>,
+}
+
+impl<F: FieldExt> Parser<F> {
+    fn peek(&self) -> &Token {
+        &self.tokens[self.next].1
+    }
+
+    fn error<T>(&self, message: impl Into<String>) -> Result<T, ExprError> {
+        Err(ExprError::Parse {
+            position: self.tokens[self.next].0,
+            message: message.into(),
+        })
+    }
+
+    fn expect(&mut self, token: Token, what: &str) -> Result<(), ExprError> {
+        if *self.peek() != token {
+            return self.error(format!("expected {}", what));
+        }
+        self.next += 1;
+        Ok(())
+    }
+
+    fn sum(&mut self) -> Result<Expr<F>, ExprError> {
+        let mut expr = self.product()?;
+        loop {
+            match self.peek() {
+                Token::Op('+') => {
+                    self.next += 1;
+                    expr = Expr::Add(Box::new(expr), Box::new(self.product()?));
+                }
+                Token::Op('-') => {
+                    self.next += 1;
+                    expr = Expr::Sub(Box::new(expr), Box::new(self.product()?));
+                }
+                _ => return Ok(expr),
+            }
+        }
+    }
+
+    fn product(&mut self) -> Result<Expr<F>, ExprError> {
+        let mut expr = self.unary()?;
+        while *self.peek() == Token::Op('*') {
+            self.next += 1;
+            expr = Expr::Mul(Box::new(expr), Box::new(self.unary()?));
+        }
+        Ok(expr)
+    }
+
+    /// `-x` is `0 - x`, and binds looser than `^`.
+    fn unary(&mut self) -> Result<Expr<F>, ExprError> {
+        if *self.peek() == Token::Op('-') {
+            self.next += 1;
+            return Ok(Expr::Sub(
+                Box::new(Expr::Const(F::zero())),
+                Box::new(self.unary()?),
+            ));
+        }
+        let base = self.atom()?;
+        if *self.peek() != Token::Op('^') {
+            return Ok(base);
+        }
+        self.next += 1;
+        let exponent = match self.peek() {
+            Token::Number(digits) => digits.replace('_', "").parse::<u64>().ok(),
+            _ => None,
+        };
+        match exponent {
+            Some(exponent) => {
+                self.next += 1;
+                Ok(Expr::Pow(Box::new(base), exponent))
+            }
+            None => self.error("expected an exponent below 2^64"),
+        }
+    }
+
+    fn atom(&mut self) -> Result<Expr<F>, ExprError> {
+        match self.peek().clone() {
+            Token::Number(digits) => match parse_field(&digits, Reduction::Reject) {
+                Ok(value) => {
+                    self.next += 1;
+                    Ok(Expr::Const(value))
+                }
+                Err(err) => self.error(err.to_string()),
+            },
+            Token::Ident(name) => {
+                self.next += 1;
+                let index = match self.variables.iter().position(|v| *v == name) {
+                    Some(index) => index,
+                    None => {
+                        self.variables.push(name);
+                        self.variables.len() - 1
+                    }
+                };
+                Ok(Expr::Var(index))
+            }
+            Token::Op('(') => {
+                self.next += 1;
+                let expr = self.sum()?;
+                self.expect(Token::Op(')'), "`)`")?;
+                Ok(expr)
+            }
+            _ => self.error("expected a number, a variable or `(`"),
+        }
+    }
+}
+
+/// `lhs == rhs` over the named variables.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Relation<F> {
+    pub lhs: Expr<F>,
+    pub rhs: Expr<F>,
+    /// Every variable, in order of first appearance.
+    pub variables: Vec<String>,
+    /// The indices of the public variables, in instance order.
+    pub public: Vec<usize>,
+}
+
+impl<F: FieldExt> Relation<F> {
+    pub fn parse(text: &str) -> Result<Self, ExprError> {
+        let mut parser = Parser {
+            tokens: tokenize(text)?,
+            next: 0,
+            variables: vec![],
+            _marker: PhantomData,
+        };
+        let lhs = parser.sum()?;
+        parser.expect(Token::Eq, "`==`")?;
+        let rhs = parser.sum()?;
+        parser.expect(Token::End, "the end of the relation")?;
+        let mut public = vec![];
+        rhs.collect_vars(&mut public);
+        Ok(Self {
+            lhs,
+            rhs,
+            variables: parser.variables,
+            public,
+        })
+    }
+
+    /// The names of the public variables, in instance order.
+    pub fn public_names(&self) -> Vec<&str> {
+        self.public
+            .iter()
+            .map(|i| self.variables[*i].as_str())
+            .collect()
+    }
+
+    /// The value of every variable, deriving a side that is a single unset variable from the
+    /// other one.
+    pub fn complete(&self, assignment: &[(&str, F)]) -> Result<Vec<F>, ExprError> {
+        let mut values = vec![None; self.variables.len()];
+        for (name, value) in assignment.iter() {
+            let index = self
+                .variables
+                .iter()
+                .position(|v| v == name)
+                .ok_or_else(|| ExprError::UnknownVariable(name.to_string()))?;
+            values[index] = Some(*value);
+        }
+        for (side, other) in [(&self.rhs, &self.lhs), (&self.lhs, &self.rhs)] {
+            if let Expr::Var(target) = side {
+                let mut inputs = vec![];
+                other.collect_vars(&mut inputs);
+                let ready = inputs.iter().all(|i| values[*i].is_some());
+                if values[*target].is_none() && !inputs.contains(target) && ready {
+                    let known: Vec<F> = values.iter().map(|v| v.unwrap_or_else(F::zero)).collect();
+                    values[*target] = Some(other.eval(&known));
+                }
+            }
+        }
+        let missing: Vec<String> = values
+            .iter()
+            .zip(self.variables.iter())
+            .filter(|(value, _)| value.is_none())
+            .map(|(_, name)| name.clone())
+            .collect();
+        if !missing.is_empty() {
+            return Err(ExprError::Missing(missing));
+        }
+        let values: Vec<F> = values.into_iter().map(Option::unwrap).collect();
+        if self.lhs.eval(&values) != self.rhs.eval(&values) {
+            return Err(ExprError::Unsatisfied);
+        }
+        Ok(values)
+    }
+
+    pub fn instance(&self, values: &[F]) -> Vec<F> {
+        self.public.iter().map(|i| values[*i]).collect()
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ExprConfig {
+    pub plonk: StandardPlonkConfig,
+    pub instance: Column<Instance>,
+}
+
+/// Proves knowledge of private values satisfying `relation` with the public ones in the
+/// instance. The constraint system is the same for every relation; the relation only decides
+/// the rows, so each one has its own keys.
+#[derive(Clone, Debug)]
+pub struct ExprCircuit<F: FieldExt> {
+    pub relation: Relation<F>,
+    pub values: Value<Vec<F>>,
+}
+
+impl<F: FieldExt> ExprCircuit<F> {
+    pub fn new(relation: Relation<F>, values: Vec<F>) -> Self {
+        assert_eq!(
+            values.len(),
+            relation.variables.len(),
+            "one value per variable"
+        );
+        Self {
+            relation,
+            values: Value::known(values),
+        }
+    }
+
+    fn compile(
+        chip: &StandardPlonkChip<F>,
+        layouter: &mut impl Layouter<F>,
+        vars: &[AssignedCell<F, F>],
+        expr: &Expr<F>,
+    ) -> Result<AssignedCell<F, F>, Error> {
+        Ok(match expr {
+            Expr::Var(i) => vars[*i].clone(),
+            Expr::Const(c) => chip.assign_constant(layouter.namespace(|| "constant"), *c)?,
+            Expr::Add(a, b) => {
+                let a = Self::compile(chip, layouter, vars, a)?;
+                let b = Self::compile(chip, layouter, vars, b)?;
+                chip.add(layouter.namespace(|| "add"), &a, &b)?
+            }
+            Expr::Sub(a, b) => {
+                let a = Self::compile(chip, layouter, vars, a)?;
+                let b = Self::compile(chip, layouter, vars, b)?;
+                chip.sub(layouter.namespace(|| "sub"), &a, &b)?
+            }
+            Expr::Mul(a, b) => {
+                let a = Self::compile(chip, layouter, vars, a)?;
+                let b = Self::compile(chip, layouter, vars, b)?;
+                chip.mul(layouter.namespace(|| "mul"), &a, &b)?
+            }
+            Expr::Pow(a, exponent) => {
+                let base = Self::compile(chip, layouter, vars, a)?;
+                // square and multiply from the top bit
+                let mut acc: Option<AssignedCell<F, F>> = None;
+                for bit in (0..64 - exponent.leading_zeros()).rev() {
+                    if let Some(value) = acc.take() {
+                        acc = Some(chip.mul(layouter.namespace(|| "square"), &value, &value)?);
+                    }
+                    if (exponent >> bit) & 1 == 1 {
+                        acc = Some(match acc {
+                            Some(value) => {
+                                chip.mul(layouter.namespace(|| "times"), &value, &base)?
+                            }
+                            None => base.clone(),
+                        });
+                    }
+                }
+                match acc {
+                    Some(value) => value,
+                    None => chip.assign_constant(layouter.namespace(|| "x^0"), F::one())?,
+                }
+            }
+        })
+    }
+}
+
+impl<F: FieldExt> Circuit<F> for ExprCircuit<F> {
+    type Config = ExprConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            relation: self.relation.clone(),
+            values: Value::unknown(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        ExprConfig {
+            plonk: StandardPlonkChip::configure(meta),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<F>,
+    ) -> Result<(), Error> {
+        let chip = StandardPlonkChip::construct(config.plonk.clone());
+        let relation = &self.relation;
+        let vars = (0..relation.variables.len())
+            .map(|i| {
+                let name = &relation.variables[i];
+                match relation.public.iter().position(|p| *p == i) {
+                    Some(row) => layouter.assign_region(
+                        || format!("public {}", name),
+                        |mut region| {
+                            region.assign_advice_from_instance(
+                                || name.as_str(),
+                                config.instance,
+                                row,
+                                config.plonk.a,
+                                0,
+                            )
+                        },
+                    ),
+                    None => chip.assign(
+                        layouter.namespace(|| format!("private {}", name)),
+                        self.values.as_ref().map(|values| values[i]),
+                    ),
+                }
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+        let lhs = Self::compile(
+            &chip,
+            &mut layouter.namespace(|| "lhs"),
+            &vars,
+            &relation.lhs,
+        )?;
+        let rhs = Self::compile(
+            &chip,
+            &mut layouter.namespace(|| "rhs"),
+            &vars,
+            &relation.rhs,
+        )?;
+        chip.constrain_equal(layouter.namespace(|| "=="), &lhs, &rhs)
+    }
+}
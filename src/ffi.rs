@@ -0,0 +1,80 @@
+//! A C ABI over the byte-slice verifier of [`crate::onchain`], for linking the verifier into
+//! a C host. `include/halo2_playground.h` is generated from this file with `make ffi-header`;
+//! link the `staticlib` or `cdylib` built with `--features ffi`.
+
+use std::{os::raw::c_int, panic, slice};
+
+use halo2_proofs::halo2curves::bn256::Fr;
+
+use crate::{
+    circuits::mul::MulCircuit,
+    onchain::{verify_circuit, PoseidonCircuit},
+};
+
+/// The Poseidon circuit of `playground prove --circuit poseidon`.
+pub const HP_CIRCUIT_POSEIDON: u32 = 0;
+/// The multiplication circuit of `playground prove --circuit mul`.
+pub const HP_CIRCUIT_MUL: u32 = 1;
+
+/// The proof verified.
+pub const HP_VERIFIED: c_int = 1;
+/// The proof was rejected, or an input didn't parse.
+pub const HP_REJECTED: c_int = 0;
+/// A null pointer with a non-zero length, or an unknown circuit.
+pub const HP_INVALID_ARGUMENT: c_int = -1;
+/// The verifier panicked; nothing unwinds into the host.
+pub const HP_INTERNAL_ERROR: c_int = -2;
+
+/// # Safety
+///
+/// Each pointer is null with length zero, or valid for reads of its length.
+unsafe fn bytes<'a>(ptr: *const u8, len: usize) -> Option<&'a [u8]> {
+    if ptr.is_null() {
+        (len == 0).then_some(&[][..])
+    } else {
+        Some(slice::from_raw_parts(ptr, len))
+    }
+}
+
+/// Verifies a KZG proof made with the default options (SHPLONK, Blake2b) of `circuit`, one
+/// of the `HP_CIRCUIT_*` constants. The formats are those of `onchain::verify_circuit`.
+/// Returns one of the `HP_*` status codes.
+///
+/// # Safety
+///
+/// Each pointer must be null with its length zero, or point to that many readable bytes that
+/// stay unchanged during the call.
+#[no_mangle]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C" fn hp_verify_kzg_proof(
+    circuit: u32,
+    params: *const u8,
+    params_len: usize,
+    vk: *const u8,
+    vk_len: usize,
+    instances: *const u8,
+    instances_len: usize,
+    proof: *const u8,
+    proof_len: usize,
+) -> c_int {
+    let verify = match circuit {
+        HP_CIRCUIT_POSEIDON => verify_circuit::<PoseidonCircuit>,
+        HP_CIRCUIT_MUL => verify_circuit::<MulCircuit<Fr>>,
+        _ => return HP_INVALID_ARGUMENT,
+    };
+    let inputs = (
+        bytes(params, params_len),
+        bytes(vk, vk_len),
+        bytes(instances, instances_len),
+        bytes(proof, proof_len),
+    );
+    let (params, vk, instances, proof) = match inputs {
+        (Some(params), Some(vk), Some(instances), Some(proof)) => (params, vk, instances, proof),
+        _ => return HP_INVALID_ARGUMENT,
+    };
+    match panic::catch_unwind(|| verify(vk, params, instances, proof)) {
+        Ok(true) => HP_VERIFIED,
+        Ok(false) => HP_REJECTED,
+        Err(_) => HP_INTERNAL_ERROR,
+    }
+}
@@ -0,0 +1,69 @@
+//! Consistent rendering of field elements in output and diagnostics.
+
+use halo2_proofs::arithmetic::FieldExt;
+
+/// Hex digits kept on each side by [`to_short`].
+const SHORT_DIGITS: usize = 4;
+
+fn be_bytes<F: FieldExt>(value: &F) -> Vec<u8> {
+    let mut bytes = value.to_repr().as_ref().to_vec();
+    bytes.reverse();
+    bytes
+}
+
+/// Minimal `0x`-prefixed big-endian hex, `0x0` for zero.
+pub fn to_hex<F: FieldExt>(value: &F) -> String {
+    let hex: String = be_bytes(value)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    let hex = hex.trim_start_matches('0');
+    format!("0x{}", if hex.is_empty() { "0" } else { hex })
+}
+
+/// Full-width `0x`-prefixed big-endian hex, as used by CSV dumps.
+pub fn to_hex_padded<F: FieldExt>(value: &F) -> String {
+    let hex: String = be_bytes(value)
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect();
+    format!("0x{}", hex)
+}
+
+pub fn to_decimal<F: FieldExt>(value: &F) -> String {
+    let mut digits = vec![];
+    let mut n = be_bytes(value);
+    while n.iter().any(|b| *b != 0) {
+        let mut rem = 0u32;
+        for byte in n.iter_mut() {
+            let v = (rem << 8) | *byte as u32;
+            *byte = (v / 10) as u8;
+            rem = v % 10;
+        }
+        digits.push(char::from(b'0' + rem as u8));
+    }
+    if digits.is_empty() {
+        return "0".to_string();
+    }
+    digits.iter().rev().collect()
+}
+
+/// Small values in decimal, negated small values as `-n`, anything else as truncated hex.
+pub fn to_short<F: FieldExt>(value: &F) -> String {
+    let small = |v: &F| v.get_lower_128() < 1 << 32 && *v == F::from_u128(v.get_lower_128());
+    if small(value) {
+        return to_decimal(value);
+    }
+    if small(&-*value) {
+        return format!("-{}", to_decimal(&-*value));
+    }
+    let hex = to_hex(value);
+    if hex.len() <= 2 + 2 * SHORT_DIGITS + 1 {
+        return hex;
+    }
+    format!(
+        "0x{}…{}",
+        &hex[2..2 + SHORT_DIGITS],
+        &hex[hex.len() - SHORT_DIGITS..]
+    )
+}
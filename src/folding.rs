@@ -0,0 +1,199 @@
+//! Experimental Nova-style folding for the `continuation` step relation `x -> x^3 + c`.
+//!
+//! Each step is written as a relaxed R1CS instance over `z = (u, x_in, x_out, x_in^2)` with two
+//! constraints. Folding replaces the per-chunk proofs of `continuation` with one accumulated
+//! instance and a single decider check. There is no augmented verifier circuit, so the linkage
+//! between consecutive steps is tracked by the accumulator rather than proven.
+
+use halo2_proofs::{
+    arithmetic::{best_multiexp, FieldExt},
+    halo2curves::{
+        bn256::{Fr, G1Affine, G1},
+        group::{ff::Field, Curve, Group, GroupEncoding},
+    },
+};
+use rand::RngCore;
+
+use crate::continuation::{step, STEP_CONSTANT};
+
+pub const NUM_CONSTRAINTS: usize = 2;
+pub const NUM_WITNESSES: usize = 1;
+
+/// Pedersen generators for the witness and error vector commitments.
+#[derive(Clone, Debug)]
+pub struct FoldingParams {
+    w_generators: [G1Affine; NUM_WITNESSES],
+    e_generators: [G1Affine; NUM_CONSTRAINTS],
+}
+
+impl FoldingParams {
+    pub fn new(mut rng: impl RngCore) -> Self {
+        let mut generator = || (G1::generator() * Fr::random(&mut rng)).to_affine();
+        Self {
+            w_generators: [generator()],
+            e_generators: [generator(), generator()],
+        }
+    }
+
+    fn commit_w(&self, w: &[Fr; NUM_WITNESSES]) -> G1Affine {
+        best_multiexp(w, &self.w_generators).to_affine()
+    }
+
+    fn commit_e(&self, e: &[Fr; NUM_CONSTRAINTS]) -> G1Affine {
+        best_multiexp(e, &self.e_generators).to_affine()
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RelaxedInstance {
+    pub u: Fr,
+    pub x_in: Fr,
+    pub x_out: Fr,
+    pub comm_w: G1Affine,
+    pub comm_e: G1Affine,
+}
+
+#[derive(Clone, Debug)]
+pub struct RelaxedWitness {
+    pub w: [Fr; NUM_WITNESSES],
+    pub e: [Fr; NUM_CONSTRAINTS],
+}
+
+type Vector = [Fr; NUM_CONSTRAINTS];
+
+fn z(instance: &RelaxedInstance, witness: &RelaxedWitness) -> [Fr; 4] {
+    [instance.u, instance.x_in, instance.x_out, witness.w[0]]
+}
+
+// x_in * x_in = u * x_sq
+// x_sq * x_in = u * (x_out - c * u)
+fn abc(z: &[Fr; 4]) -> (Vector, Vector, Vector) {
+    let [u, x_in, x_out, x_sq] = *z;
+    (
+        [x_in, x_sq],
+        [x_in, x_in],
+        [x_sq, x_out - u * Fr::from(STEP_CONSTANT)],
+    )
+}
+
+fn cross_term(
+    instance1: &RelaxedInstance,
+    witness1: &RelaxedWitness,
+    instance2: &RelaxedInstance,
+    witness2: &RelaxedWitness,
+) -> Vector {
+    let (a1, b1, c1) = abc(&z(instance1, witness1));
+    let (a2, b2, c2) = abc(&z(instance2, witness2));
+    std::array::from_fn(|i| {
+        a1[i] * b2[i] + a2[i] * b1[i] - instance1.u * c2[i] - instance2.u * c1[i]
+    })
+}
+
+fn challenge(instance1: &RelaxedInstance, instance2: &RelaxedInstance, comm_t: &G1Affine) -> Fr {
+    let mut state = blake2b_simd::Params::new()
+        .hash_length(64)
+        .personal(b"Playground-Fold")
+        .to_state();
+    for instance in [instance1, instance2] {
+        for scalar in [instance.u, instance.x_in, instance.x_out] {
+            state.update(scalar.to_bytes().as_ref());
+        }
+        state.update(instance.comm_w.to_bytes().as_ref());
+        state.update(instance.comm_e.to_bytes().as_ref());
+    }
+    state.update(comm_t.to_bytes().as_ref());
+    Fr::from_bytes_wide(state.finalize().as_bytes().try_into().unwrap())
+}
+
+/// The verifier side of folding: combines two instances given only the cross-term commitment.
+pub fn fold_instances(
+    instance1: &RelaxedInstance,
+    instance2: &RelaxedInstance,
+    comm_t: &G1Affine,
+) -> RelaxedInstance {
+    let r = challenge(instance1, instance2, comm_t);
+    RelaxedInstance {
+        u: instance1.u + r * instance2.u,
+        x_in: instance1.x_in + r * instance2.x_in,
+        x_out: instance1.x_out + r * instance2.x_out,
+        comm_w: (G1::from(instance1.comm_w) + G1::from(instance2.comm_w) * r).to_affine(),
+        comm_e: (G1::from(instance1.comm_e)
+            + G1::from(*comm_t) * r
+            + G1::from(instance2.comm_e) * r.square())
+        .to_affine(),
+    }
+}
+
+/// A strict (u = 1, E = 0) instance for a single step starting at `x_in`.
+pub fn step_instance(params: &FoldingParams, x_in: Fr) -> (RelaxedInstance, RelaxedWitness) {
+    let witness = RelaxedWitness {
+        w: [x_in.square()],
+        e: [Fr::zero(); NUM_CONSTRAINTS],
+    };
+    let instance = RelaxedInstance {
+        u: Fr::one(),
+        x_in,
+        x_out: step(x_in),
+        comm_w: params.commit_w(&witness.w),
+        comm_e: params.commit_e(&witness.e),
+    };
+    (instance, witness)
+}
+
+/// Checks the relaxed R1CS relation and both commitments.
+pub fn is_satisfied(
+    params: &FoldingParams,
+    instance: &RelaxedInstance,
+    witness: &RelaxedWitness,
+) -> bool {
+    let (a, b, c) = abc(&z(instance, witness));
+    (0..NUM_CONSTRAINTS).all(|i| a[i] * b[i] == instance.u * c[i] + witness.e[i])
+        && params.commit_w(&witness.w) == instance.comm_w
+        && params.commit_e(&witness.e) == instance.comm_e
+}
+
+/// Running accumulator over consecutive steps of the chained computation.
+#[derive(Clone, Debug)]
+pub struct FoldingAccumulator {
+    pub instance: RelaxedInstance,
+    pub witness: RelaxedWitness,
+    pub state_in: Fr,
+    pub state_out: Fr,
+    pub steps: usize,
+}
+
+impl FoldingAccumulator {
+    pub fn new(params: &FoldingParams, state_in: Fr) -> Self {
+        let (instance, witness) = step_instance(params, state_in);
+        let state_out = instance.x_out;
+        Self {
+            instance,
+            witness,
+            state_in,
+            state_out,
+            steps: 1,
+        }
+    }
+
+    /// Folds the next step into the accumulator and returns the cross-term commitment a
+    /// verifier needs to follow along with `fold_instances`.
+    pub fn fold_step(&mut self, params: &FoldingParams) -> G1Affine {
+        let (instance, witness) = step_instance(params, self.state_out);
+        let t = cross_term(&self.instance, &self.witness, &instance, &witness);
+        let comm_t = params.commit_e(&t);
+        let r = challenge(&self.instance, &instance, &comm_t);
+
+        self.witness = RelaxedWitness {
+            w: std::array::from_fn(|i| self.witness.w[i] + r * witness.w[i]),
+            e: std::array::from_fn(|i| self.witness.e[i] + r * t[i] + r.square() * witness.e[i]),
+        };
+        self.instance = fold_instances(&self.instance, &instance, &comm_t);
+        self.state_out = instance.x_out;
+        self.steps += 1;
+        comm_t
+    }
+
+    pub fn decide(&self, params: &FoldingParams) -> bool {
+        is_satisfied(params, &self.instance, &self.witness)
+    }
+}
@@ -0,0 +1,290 @@
+//! Instance columns too long to hold in memory, read from a stream. The commitment of a column
+//! is the MSM of its values with the Lagrange bases of the params, so both are read a chunk at
+//! a time: the bases straight from a params file, without loading the rest of it. The instance
+//! evaluations an external verifier needs (see `barycentric`) are accumulated in the same
+//! single pass. halo2's own `verify_proof` takes the values as slices, so it can't be fed a
+//! stream; these are for verifiers working from commitments or evaluations.
+
+use std::{
+    fmt,
+    io::{self, Read, Seek, SeekFrom},
+};
+
+use halo2_proofs::{
+    arithmetic::Field,
+    halo2curves::{
+        bn256::{Fr, G1Affine, G1},
+        group::{Curve, Group, GroupEncoding},
+    },
+    plonk::VerifyingKey,
+};
+
+use crate::{
+    accel,
+    encoding::{field_from_le_bytes, EncodingError, Reduction},
+};
+
+const WORD_BYTES: usize = 32;
+const POINT_BYTES: usize = 32;
+
+#[derive(Debug)]
+pub enum StreamError {
+    Io(io::Error),
+    Encoding {
+        row: usize,
+        error: EncodingError,
+    },
+    /// A Lagrange basis point that doesn't decode.
+    Point(usize),
+    KeyMismatch {
+        params_k: u32,
+        vk_k: u32,
+    },
+    /// Not one stream per instance column of the key.
+    Columns {
+        expected: usize,
+        found: usize,
+    },
+    TooLong {
+        column: usize,
+        max_rows: usize,
+    },
+}
+
+impl fmt::Display for StreamError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StreamError::Io(err) => write!(f, "{}", err),
+            StreamError::Encoding { row, error } => write!(f, "row {}: {}", row, error),
+            StreamError::Point(index) => write!(f, "Lagrange basis point {} is invalid", index),
+            StreamError::KeyMismatch { params_k, vk_k } => write!(
+                f,
+                "params for k = {} but the verifying key is for k = {}",
+                params_k, vk_k
+            ),
+            StreamError::Columns { expected, found } => {
+                write!(f, "expected {} instance columns, found {}", expected, found)
+            }
+            StreamError::TooLong { column, max_rows } => write!(
+                f,
+                "instance column {} is longer than its {} usable rows",
+                column, max_rows
+            ),
+        }
+    }
+}
+
+impl std::error::Error for StreamError {}
+
+impl From<io::Error> for StreamError {
+    fn from(err: io::Error) -> Self {
+        StreamError::Io(err)
+    }
+}
+
+/// The values of one instance column as consecutive 32-byte little-endian words, as
+/// `Fr::to_repr` writes them. Non-canonical words are errors.
+pub struct InstanceReader<R> {
+    reader: R,
+    row: usize,
+}
+
+impl<R: Read> InstanceReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self { reader, row: 0 }
+    }
+}
+
+impl<R: Read> Iterator for InstanceReader<R> {
+    type Item = Result<Fr, StreamError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let mut word = [0u8; WORD_BYTES];
+        let mut filled = 0;
+        while filled < WORD_BYTES {
+            match self.reader.read(&mut word[filled..]) {
+                Ok(0) if filled == 0 => return None,
+                Ok(0) => {
+                    return Some(Err(StreamError::Encoding {
+                        row: self.row,
+                        error: EncodingError::PartialWord(filled),
+                    }))
+                }
+                Ok(read) => filled += read,
+                Err(err) if err.kind() == io::ErrorKind::Interrupted => {}
+                Err(err) => return Some(Err(err.into())),
+            }
+        }
+        let row = self.row;
+        self.row += 1;
+        Some(
+            field_from_le_bytes(&word, Reduction::Reject)
+                .map_err(|error| StreamError::Encoding { row, error }),
+        )
+    }
+}
+
+/// The Lagrange bases of a KZG params file, read on demand. The file is `k` as a little-endian
+/// `u32`, the `2^k` monomial bases, then the `2^k` Lagrange ones, all compressed.
+pub struct LagrangeBases<R> {
+    reader: R,
+    k: u32,
+}
+
+impl<R: Read + Seek> LagrangeBases<R> {
+    pub fn new(mut reader: R) -> Result<Self, StreamError> {
+        reader.seek(SeekFrom::Start(0))?;
+        let mut k = [0u8; 4];
+        reader.read_exact(&mut k)?;
+        Ok(Self {
+            reader,
+            k: u32::from_le_bytes(k),
+        })
+    }
+
+    pub fn k(&self) -> u32 {
+        self.k
+    }
+
+    /// The `count` bases from index `start`.
+    pub fn read(&mut self, start: usize, count: usize) -> Result<Vec<G1Affine>, StreamError> {
+        let n = 1usize << self.k;
+        let offset = 4 + (n + start) * POINT_BYTES;
+        self.reader.seek(SeekFrom::Start(offset as u64))?;
+        (start..start + count)
+            .map(|index| {
+                let mut repr = <G1Affine as GroupEncoding>::Repr::default();
+                self.reader.read_exact(repr.as_mut())?;
+                Option::<G1Affine>::from(G1Affine::from_bytes(&repr))
+                    .ok_or(StreamError::Point(index))
+            })
+            .collect()
+    }
+}
+
+/// Commits to each column, `chunk_rows` values and bases at a time. Like `commit_instances`,
+/// without a blind.
+pub fn commit_instances_streamed<R, I>(
+    bases: &mut LagrangeBases<R>,
+    vk: &VerifyingKey<G1Affine>,
+    columns: Vec<I>,
+    chunk_rows: usize,
+) -> Result<Vec<G1Affine>, StreamError>
+where
+    R: Read + Seek,
+    I: Iterator<Item = Result<Fr, StreamError>>,
+{
+    assert!(chunk_rows > 0, "at least one row per chunk");
+    check_shape(bases.k(), vk, columns.len())?;
+    let max_rows = max_rows(vk);
+    columns
+        .into_iter()
+        .enumerate()
+        .map(|(column, mut values)| {
+            let mut acc = G1::identity();
+            let mut rows = 0;
+            loop {
+                let chunk = values
+                    .by_ref()
+                    .take(chunk_rows)
+                    .collect::<Result<Vec<_>, _>>()?;
+                if chunk.is_empty() {
+                    return Ok(acc.to_affine());
+                }
+                if rows + chunk.len() > max_rows {
+                    return Err(StreamError::TooLong { column, max_rows });
+                }
+                acc += accel::msm(&chunk, &bases.read(rows, chunk.len())?);
+                rows += chunk.len();
+            }
+        })
+        .collect()
+}
+
+/// The evaluations `barycentric::instance_evaluations` computes, in the same order, reading each
+/// column once.
+pub fn instance_evaluations_streamed<I>(
+    vk: &VerifyingKey<G1Affine>,
+    columns: Vec<I>,
+    x: Fr,
+) -> Result<Vec<Fr>, StreamError>
+where
+    I: Iterator<Item = Result<Fr, StreamError>>,
+{
+    check_shape(vk.domain.k(), vk, columns.len())?;
+    let n = 1u64 << vk.domain.k();
+    let omega = vk.domain.get_omega();
+    let omega_inv = vk.domain.get_omega_inv();
+    let queries = vk.cs.instance_queries();
+    let points: Vec<Fr> = queries
+        .iter()
+        .map(|(_, rotation)| {
+            let step = if rotation.0 < 0 { omega_inv } else { omega };
+            x * step.pow_vartime(&[rotation.0.unsigned_abs() as u64])
+        })
+        .collect();
+    let vanishing: Vec<Fr> = points
+        .iter()
+        .map(|point| point.pow_vartime(&[n]) - Fr::one())
+        .collect();
+    let mut sums = vec![Fr::zero(); queries.len()];
+    let max_rows = max_rows(vk);
+    for (column, values) in columns.into_iter().enumerate() {
+        let mut omega_i = Fr::one();
+        for (row, value) in values.enumerate() {
+            if row >= max_rows {
+                return Err(StreamError::TooLong { column, max_rows });
+            }
+            let value = value?;
+            for (query, (query_column, _)) in queries.iter().enumerate() {
+                if query_column.index() != column {
+                    continue;
+                }
+                let point = points[query];
+                sums[query] += if vanishing[query] == Fr::zero() {
+                    // on the domain only one basis is non-zero, and it is one there
+                    if point == omega_i {
+                        value
+                    } else {
+                        Fr::zero()
+                    }
+                } else {
+                    value * omega_i * (Fr::from(n) * (point - omega_i)).invert().unwrap()
+                };
+            }
+            omega_i *= omega;
+        }
+    }
+    Ok(sums
+        .into_iter()
+        .zip(vanishing)
+        .map(|(sum, vanishing)| {
+            if vanishing == Fr::zero() {
+                sum
+            } else {
+                sum * vanishing
+            }
+        })
+        .collect())
+}
+
+fn check_shape(k: u32, vk: &VerifyingKey<G1Affine>, columns: usize) -> Result<(), StreamError> {
+    if k != vk.domain.k() {
+        return Err(StreamError::KeyMismatch {
+            params_k: k,
+            vk_k: vk.domain.k(),
+        });
+    }
+    if columns != vk.cs.num_instance_columns() {
+        return Err(StreamError::Columns {
+            expected: vk.cs.num_instance_columns(),
+            found: columns,
+        });
+    }
+    Ok(())
+}
+
+/// The rows above the blinding factors.
+fn max_rows(vk: &VerifyingKey<G1Affine>) -> usize {
+    (1usize << vk.domain.k()) - (vk.cs.blinding_factors() + 1)
+}
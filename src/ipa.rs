@@ -0,0 +1,37 @@
+//! The IPA commitment scheme over the Pasta curves, for comparing against KZG over BN256.
+//! IPA needs no trusted setup, at the cost of larger proofs and a verifier linear in `2^k`.
+
+use halo2_proofs::{
+    halo2curves::pasta::{EqAffine, Fp},
+    plonk::{Circuit, Error, ProvingKey, VerifyingKey},
+    poly::{commitment::ParamsProver, ipa::commitment::ParamsIPA},
+};
+
+use crate::{
+    entropy::EntropySource,
+    scheme::{self, Ipa},
+};
+
+/// Transparent params for `k` over Vesta, whose scalar field is Pallas' base field `Fp`.
+pub fn setup(k: u32) -> ParamsIPA<EqAffine> {
+    ParamsIPA::<EqAffine>::new(k)
+}
+
+pub fn prove<C: Circuit<Fp>, R: EntropySource>(
+    params: &ParamsIPA<EqAffine>,
+    pk: &ProvingKey<EqAffine>,
+    circuit: C,
+    instances: &[&[Fp]],
+    rng: R,
+) -> Result<Vec<u8>, Error> {
+    scheme::prove::<Ipa, _, _>(params, pk, circuit, instances, rng)
+}
+
+pub fn verify(
+    params: &ParamsIPA<EqAffine>,
+    vk: &VerifyingKey<EqAffine>,
+    instances: &[&[Fp]],
+    proof: &[u8],
+) -> Result<(), Error> {
+    scheme::verify::<Ipa>(params, vk, instances, proof)
+}
@@ -0,0 +1,191 @@
+//! The one JSON reader of the crate, behind the instance and witness files. It takes the whole
+//! grammar apart from numbers: those are kept as the text of an unsigned integer, since field
+//! elements don't fit any native number and fractions or signs mean nothing to them.
+
+/// Nesting deeper than this is rejected rather than recursed into.
+const MAX_DEPTH: usize = 64;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Json {
+    Null,
+    Bool(bool),
+    /// The decimal digits, as written.
+    Integer(String),
+    String(String),
+    Array(Vec<Json>),
+    /// The members in file order, duplicates included.
+    Object(Vec<(String, Json)>),
+}
+
+impl Json {
+    /// Parses one value, surrounded by nothing but whitespace. Errors name what was expected
+    /// and the text where it wasn't found.
+    pub fn parse(text: &str) -> Result<Json, String> {
+        let mut parser = Parser { rest: text };
+        let value = parser.value(0)?;
+        parser.rest = parser.rest.trim_start();
+        if !parser.rest.is_empty() {
+            return Err(parser.error("the end"));
+        }
+        Ok(value)
+    }
+
+    pub fn as_array(&self) -> Option<&[Json]> {
+        match self {
+            Json::Array(items) => Some(items),
+            _ => None,
+        }
+    }
+
+    pub fn as_object(&self) -> Option<&[(String, Json)]> {
+        match self {
+            Json::Object(members) => Some(members),
+            _ => None,
+        }
+    }
+
+    /// The text of a string or an integer, the two ways a field element is written.
+    pub fn as_scalar(&self) -> Option<&str> {
+        match self {
+            Json::Integer(digits) => Some(digits),
+            Json::String(s) => Some(s),
+            _ => None,
+        }
+    }
+}
+
+struct Parser<'a> {
+    rest: &'a str,
+}
+
+impl<'a> Parser<'a> {
+    fn error(&self, expected: &str) -> String {
+        let context: String = self.rest.chars().take(16).collect();
+        format!("expected {} at {:?}", expected, context)
+    }
+
+    fn eat(&mut self, prefix: &str) -> bool {
+        match self.rest.trim_start().strip_prefix(prefix) {
+            Some(rest) => {
+                self.rest = rest;
+                true
+            }
+            None => false,
+        }
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.eat(c.encode_utf8(&mut [0; 4])) {
+            Ok(())
+        } else {
+            Err(self.error(&format!("`{}`", c)))
+        }
+    }
+
+    fn value(&mut self, depth: usize) -> Result<Json, String> {
+        if depth > MAX_DEPTH {
+            return Err(format!("nesting deeper than {}", MAX_DEPTH));
+        }
+        self.rest = self.rest.trim_start();
+        if self.rest.starts_with('"') {
+            return self.string().map(Json::String);
+        }
+        if self.rest.starts_with('[') {
+            return self
+                .sequence('[', ']', |parser| parser.value(depth + 1))
+                .map(Json::Array);
+        }
+        if self.rest.starts_with('{') {
+            let members = self.sequence('{', '}', |parser| {
+                let key = parser.string()?;
+                parser.expect(':')?;
+                Ok((key, parser.value(depth + 1)?))
+            })?;
+            return Ok(Json::Object(members));
+        }
+        for (word, value) in [
+            ("null", Json::Null),
+            ("true", Json::Bool(true)),
+            ("false", Json::Bool(false)),
+        ] {
+            if self.eat(word) {
+                return Ok(value);
+            }
+        }
+        let end = self
+            .rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(self.rest.len());
+        if end == 0 {
+            return Err(self.error("a value"));
+        }
+        let (digits, rest) = self.rest.split_at(end);
+        if rest.starts_with(|c| matches!(c, '.' | 'e' | 'E')) {
+            return Err(self.error("an unsigned integer"));
+        }
+        self.rest = rest;
+        Ok(Json::Integer(digits.to_string()))
+    }
+
+    /// Comma separated items between `open` and `close`, without a trailing comma.
+    fn sequence<T>(
+        &mut self,
+        open: char,
+        close: char,
+        mut item: impl FnMut(&mut Self) -> Result<T, String>,
+    ) -> Result<Vec<T>, String> {
+        self.expect(open)?;
+        let mut items = vec![];
+        if self.eat(close.encode_utf8(&mut [0; 4])) {
+            return Ok(items);
+        }
+        loop {
+            items.push(item(self)?);
+            if !self.eat(",") {
+                self.expect(close)?;
+                return Ok(items);
+            }
+        }
+    }
+
+    fn string(&mut self) -> Result<String, String> {
+        self.expect('"')?;
+        let mut out = String::new();
+        let mut chars = self.rest.char_indices();
+        while let Some((i, c)) = chars.next() {
+            match c {
+                '"' => {
+                    self.rest = &self.rest[i + 1..];
+                    return Ok(out);
+                }
+                '\\' => {
+                    let escaped = match chars.next().map(|(_, c)| c) {
+                        Some(c @ ('"' | '\\' | '/')) => c,
+                        Some('b') => '\u{8}',
+                        Some('f') => '\u{c}',
+                        Some('n') => '\n',
+                        Some('r') => '\r',
+                        Some('t') => '\t',
+                        Some('u') => {
+                            let hex: String = chars.by_ref().take(4).map(|(_, c)| c).collect();
+                            Some(&hex)
+                                .filter(|hex| {
+                                    hex.len() == 4 && hex.chars().all(|c| c.is_ascii_hexdigit())
+                                })
+                                .and_then(|hex| u32::from_str_radix(hex, 16).ok())
+                                .and_then(char::from_u32)
+                                .ok_or_else(|| format!("unsupported escape \\u{}", hex))?
+                        }
+                        _ => return Err(format!("invalid escape in {:?}", out)),
+                    };
+                    out.push(escaped);
+                }
+                c if (c as u32) < 0x20 => {
+                    return Err(format!("unescaped control character in {:?}", out))
+                }
+                c => out.push(c),
+            }
+        }
+        Err("unterminated string".to_string())
+    }
+}
@@ -0,0 +1,249 @@
+//! Compares two layouts of one gadget, `x^n` by repeated multiplication: one region per
+//! operation on the standard PLONK chip, and one region whose rows are chained by rotations.
+//! The numbers behind the trade-off are the cost report's plus a measured proof.
+
+use std::{
+    fmt,
+    time::{Duration, Instant},
+};
+
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    halo2curves::bn256::Fr,
+    plonk::{
+        keygen_pk, keygen_vk, Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector,
+    },
+    poly::Rotation,
+};
+
+use crate::{
+    chips::standard_plonk::{StandardPlonkChip, StandardPlonkConfig},
+    entropy::SeededEntropy,
+    error::PlaygroundError,
+    prover::{run_proof, ProofOptions},
+    report::CostReport,
+    storage::cached_params,
+};
+
+/// `x^exponent` in instance row 0, one `StandardPlonkChip::mul` region per factor.
+#[derive(Clone)]
+struct PerOperation {
+    x: Value<Fr>,
+    exponent: usize,
+}
+
+#[derive(Clone, Debug)]
+struct PerOperationConfig {
+    plonk: StandardPlonkConfig,
+    instance: Column<Instance>,
+}
+
+impl Circuit<Fr> for PerOperation {
+    type Config = PerOperationConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            x: Value::unknown(),
+            exponent: self.exponent,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        PerOperationConfig {
+            plonk: StandardPlonkChip::configure(meta),
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let chip = StandardPlonkChip::construct(config.plonk);
+        let x = chip.assign(layouter.namespace(|| "x"), self.x)?;
+        let mut acc = x.clone();
+        for i in 1..self.exponent {
+            acc = chip.mul(layouter.namespace(|| format!("factor {}", i)), &acc, &x)?;
+        }
+        layouter.constrain_instance(acc.cell(), config.instance, 0)
+    }
+}
+
+/// The same power in one region: row `i` holds `x` and `x^(i+1)`, and the gate carries both to
+/// the next row.
+#[derive(Clone)]
+struct SingleRegion {
+    x: Value<Fr>,
+    exponent: usize,
+}
+
+#[derive(Clone, Debug)]
+struct SingleRegionConfig {
+    x: Column<Advice>,
+    acc: Column<Advice>,
+    q_start: Selector,
+    q_step: Selector,
+    instance: Column<Instance>,
+}
+
+impl Circuit<Fr> for SingleRegion {
+    type Config = SingleRegionConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            x: Value::unknown(),
+            exponent: self.exponent,
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let x = meta.advice_column();
+        let acc = meta.advice_column();
+        let q_start = meta.selector();
+        let q_step = meta.selector();
+        let instance = meta.instance_column();
+        meta.enable_equality(acc);
+        meta.enable_equality(instance);
+        meta.create_gate("power step", |meta| {
+            let q = meta.query_selector(q_step);
+            let x_cur = meta.query_advice(x, Rotation::cur());
+            let x_next = meta.query_advice(x, Rotation::next());
+            let acc_cur = meta.query_advice(acc, Rotation::cur());
+            let acc_next = meta.query_advice(acc, Rotation::next());
+            vec![
+                q.clone() * (x_next - x_cur.clone()),
+                q * (acc_next - acc_cur * x_cur),
+            ]
+        });
+        // the first row's accumulator is x itself
+        meta.create_gate("power start", |meta| {
+            let q = meta.query_selector(q_start);
+            let x = meta.query_advice(x, Rotation::cur());
+            let acc = meta.query_advice(acc, Rotation::cur());
+            vec![q * (acc - x)]
+        });
+        SingleRegionConfig {
+            x,
+            acc,
+            q_start,
+            q_step,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let out = layouter.assign_region(
+            || "power",
+            |mut region| {
+                let mut acc = self.x;
+                let mut cell = None;
+                config.q_start.enable(&mut region, 0)?;
+                for row in 0..self.exponent {
+                    if row + 1 < self.exponent {
+                        config.q_step.enable(&mut region, row)?;
+                    }
+                    region.assign_advice(|| "x", config.x, row, || self.x)?;
+                    cell = Some(region.assign_advice(|| "acc", config.acc, row, || acc)?);
+                    acc = acc * self.x;
+                }
+                Ok(cell.expect("an exponent of at least 1"))
+            },
+        )?;
+        layouter.constrain_instance(out.cell(), config.instance, 0)
+    }
+}
+
+/// One layout's numbers.
+#[derive(Clone, Debug)]
+pub struct LayoutCost {
+    pub strategy: &'static str,
+    pub report: CostReport,
+    pub prove: Duration,
+}
+
+#[derive(Clone, Debug)]
+pub struct LayoutComparison {
+    pub exponent: usize,
+    pub per_operation: LayoutCost,
+    pub single_region: LayoutCost,
+}
+
+fn measure<C: Circuit<Fr> + Clone>(
+    strategy: &'static str,
+    circuit: &C,
+    instance: Fr,
+) -> Result<LayoutCost, PlaygroundError> {
+    let report = CostReport::measure(circuit)?;
+    let params = cached_params(report.k)?;
+    let vk = keygen_vk(&params, circuit).map_err(PlaygroundError::Keygen)?;
+    let pk = keygen_pk(&params, vk, circuit).map_err(PlaygroundError::Keygen)?;
+    let start = Instant::now();
+    run_proof(
+        &params,
+        &pk,
+        circuit.clone(),
+        &[&[instance]],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )?;
+    Ok(LayoutCost {
+        strategy,
+        report,
+        prove: start.elapsed(),
+    })
+}
+
+/// Lays out and proves `x^exponent` both ways.
+pub fn compare(exponent: usize) -> Result<LayoutComparison, PlaygroundError> {
+    assert!(exponent > 0, "an exponent of at least 1");
+    let x = Fr::from(3);
+    let power = x.pow_vartime(&[exponent as u64]);
+    let x = Value::known(x);
+    Ok(LayoutComparison {
+        exponent,
+        per_operation: measure("region per operation", &PerOperation { x, exponent }, power)?,
+        single_region: measure("single region", &SingleRegion { x, exponent }, power)?,
+    })
+}
+
+impl fmt::Display for LayoutComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "x^{} laid out two ways:", self.exponent)?;
+        for cost in [&self.per_operation, &self.single_region] {
+            let report = &cost.report;
+            writeln!(
+                f,
+                "  {:<21} k = {:>2}, {:>5} rows, {} advice + {} fixed columns, \
+                 {} permutation columns, ~{} proof bytes, proved in {:.2?}",
+                cost.strategy,
+                report.k,
+                report
+                    .rows_used
+                    .map(|rows| rows.to_string())
+                    .unwrap_or_default(),
+                report.advice_columns,
+                report.fixed_columns,
+                report.permutation_columns,
+                report.proof_bytes,
+                cost.prove
+            )?;
+        }
+        write!(
+            f,
+            "  A region per operation composes anywhere but pays a copy per operand and \
+             equality on every column; one region chained by rotations drops the copies and \
+             most columns, at the price of a bespoke gate and a block the floor planner can't \
+             split."
+        )
+    }
+}
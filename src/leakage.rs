@@ -0,0 +1,200 @@
+//! Checks that proofs don't leak their witness. Two proofs of one statement under different
+//! witnesses should look unrelated, byte for byte, and two proofs of the same witness should
+//! share no advice commitment, as halo2 fills the blinding rows of every advice column with
+//! fresh randomness for each proof.
+//!
+//! Independence is a chi-squared test of the XOR of the two proofs' bytes against the uniform
+//! distribution. The top byte of every word is left out: scalars are below the field's modulus
+//! and compressed points carry flag bits there, so those bytes are biased in any proof.
+//!
+//! Both are statistical and fixed-seed checks, not proofs of zero knowledge: they catch a
+//! prover that skips or reuses its blinds, not a subtle leak.
+
+use std::fmt;
+
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{Circuit, ProvingKey, VerifyingKey},
+    poly::kzg::commitment::ParamsKZG,
+};
+
+use crate::{
+    entropy::SeededEntropy,
+    error::PlaygroundError,
+    prover::{run_proof, ProofOptions},
+};
+
+/// Bytes of a compressed point or a scalar in the transcript.
+pub const WORD_BYTES: usize = 32;
+/// Bytes compared per word, all but the biased top one.
+const SAMPLED_BYTES: usize = WORD_BYTES - 1;
+/// Enough samples for five expected in each of the 256 bins.
+pub const MIN_SAMPLES: usize = 5 * 256;
+/// The chi-squared value with 255 degrees of freedom exceeded with probability 0.001.
+pub const CRITICAL_CHI_SQUARED: f64 = 330.52;
+
+/// The advice commitments at the front of a proof of one instance. KZG hashes the instances
+/// rather than writing their commitments, and challenges are squeezed, not written, so the
+/// commitments of every phase come first.
+pub fn advice_commitments<'a>(
+    vk: &VerifyingKey<G1Affine>,
+    proof: &'a [u8],
+) -> Result<Vec<&'a [u8]>, PlaygroundError> {
+    let columns = vk.cs.num_advice_columns;
+    if proof.len() < columns * WORD_BYTES {
+        return Err(PlaygroundError::Serialization(format!(
+            "a proof of {} bytes is shorter than its {} advice commitments",
+            proof.len(),
+            columns
+        )));
+    }
+    Ok(proof[..columns * WORD_BYTES].chunks(WORD_BYTES).collect())
+}
+
+/// The byte-wise independence of pairs of proofs, each pair of equal length.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Independence {
+    pub samples: usize,
+    /// Bytes equal in both proofs of a pair, about one in 256 when independent.
+    pub equal: usize,
+    pub chi_squared: f64,
+}
+
+impl Independence {
+    pub fn measure<'a>(
+        pairs: impl IntoIterator<Item = (&'a [u8], &'a [u8])>,
+    ) -> Result<Self, PlaygroundError> {
+        let mut bins = [0usize; 256];
+        for (first, second) in pairs {
+            if first.len() != second.len() {
+                return Err(PlaygroundError::Other(format!(
+                    "proofs of {} and {} bytes",
+                    first.len(),
+                    second.len()
+                )));
+            }
+            for (a, b) in first
+                .chunks(WORD_BYTES)
+                .zip(second.chunks(WORD_BYTES))
+                .flat_map(|(a, b)| a.iter().zip(b.iter()).take(SAMPLED_BYTES))
+            {
+                bins[(a ^ b) as usize] += 1;
+            }
+        }
+        let samples: usize = bins.iter().sum();
+        if samples < MIN_SAMPLES {
+            return Err(PlaygroundError::Other(format!(
+                "{} sampled bytes, at least {} needed",
+                samples, MIN_SAMPLES
+            )));
+        }
+        let expected = samples as f64 / 256.0;
+        let chi_squared = bins
+            .iter()
+            .map(|observed| (*observed as f64 - expected).powi(2) / expected)
+            .sum();
+        Ok(Independence {
+            samples,
+            equal: bins[0],
+            chi_squared,
+        })
+    }
+
+    pub fn is_independent(&self) -> bool {
+        self.chi_squared < CRITICAL_CHI_SQUARED
+    }
+}
+
+impl fmt::Display for Independence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} bytes sampled, {} equal, chi-squared {:.1} (critical {})",
+            self.samples, self.equal, self.chi_squared, CRITICAL_CHI_SQUARED
+        )
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct LeakageReport {
+    pub proof_bytes: usize,
+    /// Pairs of proofs made, one of each witness per pair.
+    pub rounds: usize,
+    /// Between the proofs of the two witnesses.
+    pub independence: Independence,
+    pub advice_columns: usize,
+    /// Advice commitments the proofs of the first witness share, counted past their first.
+    pub repeated_commitments: usize,
+}
+
+impl LeakageReport {
+    /// Proves both `witnesses` against `instances`, which both have to satisfy, under seeds
+    /// counted up from `seed`, in as many rounds as the test needs samples and at least two.
+    pub fn measure<C: Circuit<Fr> + Clone>(
+        params: &ParamsKZG<Bn256>,
+        pk: &ProvingKey<G1Affine>,
+        witnesses: [&C; 2],
+        instances: &[&[Fr]],
+        seed: u64,
+    ) -> Result<Self, PlaygroundError> {
+        let prove = |circuit: &C, seed: u64| {
+            run_proof(
+                params,
+                pk,
+                circuit.clone(),
+                instances,
+                SeededEntropy::new(seed),
+                ProofOptions::default(),
+            )
+        };
+        let mut pairs = vec![];
+        let mut sampled = 0;
+        while pairs.len() < 2 || sampled < MIN_SAMPLES {
+            let round = 2 * pairs.len() as u64;
+            let first = prove(witnesses[0], seed.wrapping_add(round))?;
+            let second = prove(witnesses[1], seed.wrapping_add(round + 1))?;
+            sampled += first.len() / WORD_BYTES * SAMPLED_BYTES;
+            pairs.push((first, second));
+        }
+        let vk = pk.get_vk();
+        let mut commitments = vec![];
+        for (first, _) in pairs.iter() {
+            commitments.extend(advice_commitments(vk, first)?);
+        }
+        let total = commitments.len();
+        commitments.sort_unstable();
+        commitments.dedup();
+        Ok(LeakageReport {
+            proof_bytes: pairs[0].0.len(),
+            rounds: pairs.len(),
+            independence: Independence::measure(
+                pairs
+                    .iter()
+                    .map(|(first, second)| (first.as_slice(), second.as_slice())),
+            )?,
+            advice_columns: vk.cs.num_advice_columns,
+            repeated_commitments: total - commitments.len(),
+        })
+    }
+
+    pub fn passes(&self) -> bool {
+        self.independence.is_independent() && self.repeated_commitments == 0
+    }
+}
+
+impl fmt::Display for LeakageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "  {} rounds of proofs of {} bytes",
+            self.rounds, self.proof_bytes
+        )?;
+        writeln!(f, "  the two witnesses: {}", self.independence)?;
+        write!(
+            f,
+            "  the first witness: {} of {} advice commitments repeated",
+            self.repeated_commitments,
+            self.rounds * self.advice_columns
+        )
+    }
+}
@@ -1,42 +1,261 @@
-use halo2_proofs::arithmetic::Field;
+extern crate alloc;
+
+use halo2_proofs::arithmetic::{CurveAffine, Field, FieldExt};
 use halo2_proofs::halo2curves::group::Curve;
 use halo2_proofs::{
     plonk::{Error, VerifyingKey},
     poly::commitment::{Blind, CommitmentScheme, Params, Verifier},
 };
 
+use crate::{
+    entropy::EntropySource,
+    multicore::{self, MaybeSync},
+};
+
+pub mod accel;
+#[cfg(feature = "prover")]
+pub mod anchor;
+#[cfg(feature = "prover")]
+pub mod artifacts;
+#[cfg(feature = "prover")]
+pub mod audit;
+pub mod audit_log;
+#[cfg(feature = "unstable")]
+pub mod barycentric;
+pub mod batch;
+#[cfg(feature = "prover")]
+pub mod budget;
+#[cfg(feature = "prover")]
+pub mod builder;
+pub mod bundle;
+pub mod cache;
+#[cfg(feature = "prover")]
+pub mod cancel;
+#[cfg(all(feature = "prover", feature = "unstable"))]
+pub mod ceremony;
+pub mod challenges;
+pub mod chips;
+pub mod circuits;
+#[cfg(feature = "prover")]
+pub mod cli;
+#[cfg(feature = "prover")]
+pub mod compat;
+#[cfg(feature = "prover")]
+pub mod config;
+#[cfg(all(feature = "prover", feature = "unstable"))]
+pub mod continuation;
+#[cfg(feature = "prover")]
+pub mod demo;
+#[cfg(feature = "prover")]
+pub mod dev;
+pub mod encoding;
+pub mod entropy;
+pub mod error;
+#[cfg(feature = "prover")]
+pub mod evm;
+#[cfg(feature = "prover")]
+pub mod expr;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod fmt;
+#[cfg(all(feature = "prover", feature = "unstable"))]
+pub mod folding;
+pub mod instance_stream;
+#[cfg(all(feature = "prover", feature = "unstable"))]
+pub mod ipa;
+pub mod json;
+#[cfg(feature = "prover")]
+pub mod layout;
+#[cfg(feature = "prover")]
+pub mod leakage;
+pub mod limits;
+#[cfg(feature = "circuits-hash")]
+pub mod merkle;
+pub mod metrics;
+pub mod multicore;
+pub mod onchain;
+#[cfg(all(feature = "circuits-state", feature = "prover", feature = "unstable"))]
+pub mod pcd;
+pub mod poseidon;
+pub mod poseidon_transcript;
+pub mod prelude;
+#[cfg(feature = "profiling")]
+pub mod profiling;
+#[cfg(feature = "prover")]
+pub mod provenance;
+pub mod prover;
+#[cfg(feature = "prover")]
+pub mod readiness;
+pub mod reference;
+#[cfg(feature = "prover")]
+pub mod report;
+pub mod schema;
+#[cfg(feature = "prover")]
+pub mod scheme;
+pub mod srs;
+pub mod storage;
+pub mod store;
+#[cfg(all(feature = "prover", feature = "unstable"))]
+pub mod stream;
+#[cfg(feature = "prover")]
+pub mod sweep;
+pub mod transcript;
+pub mod transcript_registry;
+#[cfg(feature = "prover")]
+pub mod usage;
+pub mod verifier;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "prover")]
+pub mod witness;
+
 pub const GOD_PRIVATE_KEY: u128 = 42;
 
+/// The commitments a verifier computes to each instance column of each proof, with
+/// `Blind::default()` as halo2's IPA verifier does. A column holds
+/// at most `n - blinding_factors - 1` values, the rows above the random blinding rows and the
+/// permutation argument's last row; a longer one is `Error::InstanceTooLarge`, as for
+/// `create_proof`.
 pub fn commit_instances<'params, Scheme: CommitmentScheme, V: Verifier<'params, Scheme>>(
     params: &'params Scheme::ParamsVerifier,
     vk: &VerifyingKey<Scheme::Curve>,
     instances: &[&[&[Scheme::Scalar]]],
-) -> Result<Vec<Vec<<Scheme as CommitmentScheme>::Curve>>, Error> {
+) -> Result<Vec<Vec<<Scheme as CommitmentScheme>::Curve>>, Error>
+where
+    Scheme::ParamsVerifier: MaybeSync,
+{
+    let blinds = default_blinds(instances);
+    commit_instances_with_blinds::<Scheme, V>(params, vk, instances, &blinds)
+}
+
+/// Like `commit_instances`, under prover params, so the prover side computes the same
+/// commitments a verifier will, e.g. to cache or cross-check them.
+pub fn commit_instances_prover<Scheme: CommitmentScheme>(
+    params: &Scheme::ParamsProver,
+    vk: &VerifyingKey<Scheme::Curve>,
+    instances: &[&[&[Scheme::Scalar]]],
+) -> Result<Vec<Vec<<Scheme as CommitmentScheme>::Curve>>, Error>
+where
+    Scheme::ParamsProver: MaybeSync,
+{
+    commit_instances_with_params(params, vk, instances)
+}
+
+/// Like `commit_instances`, under any `Params`: the commitments only use `n` and the Lagrange
+/// bases, which prover and verifier params share.
+pub fn commit_instances_with_params<'params, C: CurveAffine, P: Params<'params, C> + MaybeSync>(
+    params: &P,
+    vk: &VerifyingKey<C>,
+    instances: &[&[&[C::Scalar]]],
+) -> Result<Vec<Vec<C>>, Error> {
+    commit_blinded(params, vk, instances, &default_blinds(instances))
+}
+
+/// `Blind::default()` for every column, the blind halo2's own verifier commits instances with.
+fn default_blinds<F: FieldExt>(instances: &[&[&[F]]]) -> Vec<Vec<F>> {
+    instances
+        .iter()
+        .map(|instance| vec![Blind::<F>::default().0; instance.len()])
+        .collect()
+}
+
+/// Commits to the instances with one blinding scalar per proof and column, e.g. to open the
+/// commitments later. The KZG params of this fork ignore the blind in `commit_lagrange`, so only
+/// IPA commitments are actually hiding.
+pub fn commit_instances_with_blinds<
+    'params,
+    Scheme: CommitmentScheme,
+    V: Verifier<'params, Scheme>,
+>(
+    params: &'params Scheme::ParamsVerifier,
+    vk: &VerifyingKey<Scheme::Curve>,
+    instances: &[&[&[Scheme::Scalar]]],
+    blinds: &[Vec<Scheme::Scalar>],
+) -> Result<Vec<Vec<<Scheme as CommitmentScheme>::Curve>>, Error>
+where
+    Scheme::ParamsVerifier: MaybeSync,
+{
+    commit_blinded(params, vk, instances, blinds)
+}
+
+fn commit_blinded<'params, C: CurveAffine, P: Params<'params, C> + MaybeSync>(
+    params: &P,
+    vk: &VerifyingKey<C>,
+    instances: &[&[&[C::Scalar]]],
+    blinds: &[Vec<C::Scalar>],
+) -> Result<Vec<Vec<C>>, Error> {
     // Check that instances matches the expected number of instance columns
     for instances in instances.iter() {
         if instances.len() != vk.cs.num_instance_columns {
             return Err(Error::InvalidInstances);
         }
     }
+    if blinds.len() != instances.len()
+        || blinds
+            .iter()
+            .zip(instances.iter())
+            .any(|(blinds, instances)| blinds.len() != instances.len())
+    {
+        return Err(Error::InvalidInstances);
+    }
 
+    // one job per proof and column, so many small proofs parallelize as well as wide ones
+    let jobs: Vec<(&[C::Scalar], C::Scalar)> = instances
+        .iter()
+        .zip(blinds.iter())
+        .flat_map(|(instance, blinds)| instance.iter().copied().zip(blinds.iter().copied()))
+        .collect();
+    let n = params.n() as usize;
+    let max_rows = n - (vk.cs.blinding_factors() + 1);
+    let domain = &vk.domain;
+    let bases = accel::cached_bases(params);
+    let mut commitments = multicore::try_map(&jobs, |(instance, blind)| {
+        if instance.len() > max_rows {
+            return Err(Error::InstanceTooLarge);
+        }
+        let mut poly = instance.to_vec();
+        poly.resize(n, C::Scalar::zero());
+
+        Ok(accel::commit_lagrange(params, bases.as_deref(), domain, poly, *blind).to_affine())
+    })?
+    .into_iter();
     let instance_commitments = instances
         .iter()
-        .map(|instance| {
-            instance
-                .iter()
-                .map(|instance| {
-                    if instance.len() > params.n() as usize - (vk.cs.blinding_factors() + 1) {
-                        return Err(Error::InstanceTooLarge);
-                    }
-                    let mut poly = instance.to_vec();
-                    poly.resize(params.n() as usize, Scheme::Scalar::zero());
-                    let poly = vk.domain.lagrange_from_vec(poly);
-
-                    Ok(params.commit_lagrange(&poly, Blind::default()).to_affine())
-                })
-                .collect::<Result<Vec<_>, _>>()
-        })
-        .collect::<Result<Vec<_>, _>>()?;
+        .map(|instance| commitments.by_ref().take(instance.len()).collect())
+        .collect();
 
     Ok(instance_commitments)
 }
+
+/// Like `commit_instances_with_blinds`, drawing the blinds from `rng` and returning them.
+pub fn commit_instances_hiding<
+    'params,
+    Scheme: CommitmentScheme,
+    V: Verifier<'params, Scheme>,
+    R: EntropySource,
+>(
+    params: &'params Scheme::ParamsVerifier,
+    vk: &VerifyingKey<Scheme::Curve>,
+    instances: &[&[&[Scheme::Scalar]]],
+    mut rng: R,
+) -> Result<
+    (
+        Vec<Vec<<Scheme as CommitmentScheme>::Curve>>,
+        Vec<Vec<Scheme::Scalar>>,
+    ),
+    Error,
+>
+where
+    Scheme::ParamsVerifier: MaybeSync,
+{
+    let blinds: Vec<Vec<_>> = instances
+        .iter()
+        .map(|instance| {
+            (0..instance.len())
+                .map(|_| Scheme::Scalar::random(&mut rng))
+                .collect()
+        })
+        .collect();
+    let commitments = commit_instances_with_blinds::<Scheme, V>(params, vk, instances, &blinds)?;
+    Ok((commitments, blinds))
+}
@@ -0,0 +1,222 @@
+//! Guardrails checked before a proving request starts.
+
+use std::{
+    fmt, fs, io,
+    path::Path,
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc,
+    },
+};
+
+use halo2_proofs::{
+    halo2curves::bn256::{Fr, G1Affine},
+    plonk::VerifyingKey,
+};
+
+const FIELD_BYTES: u64 = 32;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum LimitError {
+    K {
+        k: u32,
+        max: u32,
+    },
+    InstanceLength {
+        column: usize,
+        len: usize,
+        max: usize,
+    },
+    WitnessSize {
+        bytes: u64,
+        max: u64,
+    },
+    ProofSize {
+        bytes: usize,
+        max: usize,
+    },
+    Memory {
+        needed: u64,
+        available: u64,
+    },
+    Io(String),
+}
+
+impl fmt::Display for LimitError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            LimitError::K { k, max } => write!(f, "k = {} exceeds the limit of {}", k, max),
+            LimitError::InstanceLength { column, len, max } => write!(
+                f,
+                "instance column {} has {} values, the limit is {}",
+                column, len, max
+            ),
+            LimitError::WitnessSize { bytes, max } => {
+                write!(f, "witness file is {} bytes, the limit is {}", bytes, max)
+            }
+            LimitError::ProofSize { bytes, max } => {
+                write!(f, "proof is {} bytes, the limit is {}", bytes, max)
+            }
+            LimitError::Memory { needed, available } => write!(
+                f,
+                "proving needs about {} bytes of memory, only {} are available",
+                needed, available
+            ),
+            LimitError::Io(err) => write!(f, "{}", err),
+        }
+    }
+}
+
+impl std::error::Error for LimitError {}
+
+impl From<io::Error> for LimitError {
+    fn from(err: io::Error) -> Self {
+        LimitError::Io(err.to_string())
+    }
+}
+
+/// Rough size in bytes of the polynomials the prover keeps around: every column, permutation
+/// and lookup polynomial in both the base domain and the extended domain. A size past `u64`
+/// is `u64::MAX`, more than any budget.
+pub fn estimate_prover_memory(vk: &VerifyingKey<G1Affine>) -> u64 {
+    let cs = &vk.cs;
+    let chunk_len = cs.degree() - 2;
+    let permutation_columns = cs.permutation().get_columns().len();
+    let polys = [
+        cs.num_advice_columns(),
+        cs.num_instance_columns(),
+        cs.num_fixed_columns(),
+        cs.num_selectors(),
+        2 * permutation_columns,
+        (permutation_columns + chunk_len - 1) / chunk_len,
+        5 * cs.lookups().len(),
+    ]
+    .iter()
+    .try_fold(0usize, |total, polys| total.checked_add(*polys));
+    let rows = 1u64
+        .checked_shl(vk.domain.k())
+        .zip(1u64.checked_shl(vk.domain.extended_k()))
+        .and_then(|(base, extended)| base.checked_add(extended));
+    polys
+        .zip(rows)
+        .and_then(|(polys, rows)| (polys as u64).checked_mul(rows))
+        .and_then(|bytes| bytes.checked_mul(FIELD_BYTES))
+        .unwrap_or(u64::MAX)
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct ResourceLimits {
+    pub max_k: u32,
+    pub max_instance_len: usize,
+    pub max_witness_bytes: u64,
+    pub max_proof_bytes: usize,
+    /// Size of the [`MemoryBudget`] shared by all requests proving at the same time.
+    pub max_memory_bytes: u64,
+}
+
+impl Default for ResourceLimits {
+    fn default() -> Self {
+        Self {
+            max_k: 20,
+            max_instance_len: 1 << 16,
+            max_witness_bytes: 64 << 20,
+            max_proof_bytes: 1 << 20,
+            max_memory_bytes: 8 << 30,
+        }
+    }
+}
+
+impl ResourceLimits {
+    pub fn check_k(&self, k: u32) -> Result<(), LimitError> {
+        if k > self.max_k {
+            return Err(LimitError::K { k, max: self.max_k });
+        }
+        Ok(())
+    }
+
+    pub fn check_instances(&self, instances: &[&[Fr]]) -> Result<(), LimitError> {
+        for (column, values) in instances.iter().enumerate() {
+            if values.len() > self.max_instance_len {
+                return Err(LimitError::InstanceLength {
+                    column,
+                    len: values.len(),
+                    max: self.max_instance_len,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Checks the size on disk, so oversized witness files are rejected before being read.
+    pub fn check_witness_file(&self, path: &Path) -> Result<(), LimitError> {
+        let bytes = fs::metadata(path)?.len();
+        if bytes > self.max_witness_bytes {
+            return Err(LimitError::WitnessSize {
+                bytes,
+                max: self.max_witness_bytes,
+            });
+        }
+        Ok(())
+    }
+}
+
+/// Memory shared by concurrent proving requests.
+#[derive(Clone, Debug)]
+pub struct MemoryBudget {
+    total: u64,
+    used: Arc<AtomicU64>,
+}
+
+/// Memory reserved from a [`MemoryBudget`], given back when dropped.
+#[derive(Debug)]
+pub struct Reservation {
+    bytes: u64,
+    used: Arc<AtomicU64>,
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        self.used.fetch_sub(self.bytes, Ordering::SeqCst);
+    }
+}
+
+impl MemoryBudget {
+    pub fn new(total: u64) -> Self {
+        Self {
+            total,
+            used: Arc::new(AtomicU64::new(0)),
+        }
+    }
+
+    pub fn available(&self) -> u64 {
+        self.total.saturating_sub(self.used.load(Ordering::SeqCst))
+    }
+
+    pub fn try_reserve(&self, bytes: u64) -> Result<Reservation, LimitError> {
+        self.used
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |used| {
+                used.checked_add(bytes).filter(|total| *total <= self.total)
+            })
+            .map_err(|used| LimitError::Memory {
+                needed: bytes,
+                available: self.total.saturating_sub(used),
+            })?;
+        Ok(Reservation {
+            bytes,
+            used: self.used.clone(),
+        })
+    }
+}
+
+/// Runs every check for a request against `vk`, reserving its memory from `budget`. Proving
+/// should only start once this returns, and the reservation be held until it ends.
+pub fn admit(
+    limits: &ResourceLimits,
+    budget: &MemoryBudget,
+    vk: &VerifyingKey<G1Affine>,
+    instances: &[&[Fr]],
+) -> Result<Reservation, LimitError> {
+    limits.check_k(vk.domain.k())?;
+    limits.check_instances(instances)?;
+    budget.try_reserve(estimate_prover_memory(vk))
+}
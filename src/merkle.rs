@@ -0,0 +1,320 @@
+//! Off-circuit binary Merkle trees over the two-to-one Poseidon hash that `MerklePathCircuit`
+//! and `SmtCircuit` prove paths of, and the wider-arity key-value tree `KvCircuit` proves.
+
+use std::{collections::HashMap, convert::TryInto};
+
+use halo2_gadgets::poseidon::primitives::{self as poseidon, ConstantLength};
+use halo2_proofs::{arithmetic::Field, halo2curves::bn256::Fr};
+
+use crate::{circuits::poseidon::PoseidonHashCircuit, poseidon::MySpec};
+
+pub fn hash_pair(left: Fr, right: Fr) -> Fr {
+    PoseidonHashCircuit::<Fr, MySpec<3, 2>, 3, 2, 2>::hash([left, right])
+}
+
+/// The siblings from the leaf up. `directions[i]` is true when the node at level `i` is a
+/// right child, i.e. bit `i` of the leaf index.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct MerklePath {
+    pub siblings: Vec<Fr>,
+    pub directions: Vec<bool>,
+}
+
+impl MerklePath {
+    pub fn depth(&self) -> usize {
+        self.siblings.len()
+    }
+
+    /// The root `leaf` hashes up to along the path.
+    pub fn root(&self, leaf: Fr) -> Fr {
+        self.siblings
+            .iter()
+            .zip(self.directions.iter())
+            .fold(leaf, |node, (sibling, is_right)| {
+                if *is_right {
+                    hash_pair(*sibling, node)
+                } else {
+                    hash_pair(node, *sibling)
+                }
+            })
+    }
+}
+
+/// A tree of depth `depth`, with the leaves past the given ones zero.
+#[derive(Clone, Debug)]
+pub struct MerkleTree {
+    /// `levels[0]` are the leaves, the last level is the root.
+    levels: Vec<Vec<Fr>>,
+}
+
+impl MerkleTree {
+    pub fn new(leaves: &[Fr], depth: usize) -> Self {
+        assert!(
+            leaves.len() <= 1 << depth,
+            "{} leaves in a tree of depth {}",
+            leaves.len(),
+            depth
+        );
+        let mut level = leaves.to_vec();
+        level.resize(1 << depth, Fr::zero());
+        let mut levels = vec![level];
+        for _ in 0..depth {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| hash_pair(pair[0], pair[1]))
+                .collect();
+            levels.push(next);
+        }
+        Self { levels }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.levels.len() - 1
+    }
+
+    pub fn root(&self) -> Fr {
+        self.levels[self.depth()][0]
+    }
+
+    pub fn leaf(&self, index: usize) -> Fr {
+        self.levels[0][index]
+    }
+
+    pub fn path(&self, index: usize) -> MerklePath {
+        assert!(index < self.levels[0].len(), "leaf {} out of range", index);
+        let (siblings, directions) = (0..self.depth())
+            .map(|level| {
+                let node = index >> level;
+                (self.levels[level][node ^ 1], node & 1 == 1)
+            })
+            .unzip();
+        MerklePath {
+            siblings,
+            directions,
+        }
+    }
+}
+
+/// A tree of depth `depth` over `depth`-bit keys where every leaf starts out empty (zero), so
+/// only the nodes above set leaves are stored. A path to an empty leaf proves its key absent,
+/// e.g. a nullifier not spent yet.
+#[derive(Clone, Debug)]
+pub struct SparseMerkleTree {
+    depth: usize,
+    /// `defaults[level]` is the root of an empty subtree of height `level`.
+    defaults: Vec<Fr>,
+    nodes: HashMap<(usize, u64), Fr>,
+}
+
+impl SparseMerkleTree {
+    pub fn new(depth: usize) -> Self {
+        assert!(depth < 64, "keys are u64");
+        let mut defaults = vec![Fr::zero()];
+        for level in 0..depth {
+            defaults.push(hash_pair(defaults[level], defaults[level]));
+        }
+        Self {
+            depth,
+            defaults,
+            nodes: HashMap::new(),
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    fn node(&self, level: usize, index: u64) -> Fr {
+        self.nodes
+            .get(&(level, index))
+            .copied()
+            .unwrap_or(self.defaults[level])
+    }
+
+    fn check_key(&self, key: u64) {
+        assert!(key >> self.depth == 0, "key {} out of range", key);
+    }
+
+    pub fn root(&self) -> Fr {
+        self.node(self.depth, 0)
+    }
+
+    /// The leaf at `key`, zero if absent.
+    pub fn get(&self, key: u64) -> Fr {
+        self.check_key(key);
+        self.node(0, key)
+    }
+
+    /// Sets the leaf at `key`. Zero is the empty leaf, so inserting it removes the key.
+    pub fn insert(&mut self, key: u64, value: Fr) {
+        self.check_key(key);
+        let mut node = value;
+        for level in 0..=self.depth {
+            let index = key >> level;
+            if node == self.defaults[level] {
+                self.nodes.remove(&(level, index));
+            } else {
+                self.nodes.insert((level, index), node);
+            }
+            if level < self.depth {
+                let sibling = self.node(level, index ^ 1);
+                node = if index & 1 == 1 {
+                    hash_pair(sibling, node)
+                } else {
+                    hash_pair(node, sibling)
+                };
+            }
+        }
+    }
+
+    /// The path to the leaf at `key`, whose directions are the bits of `key`.
+    pub fn path(&self, key: u64) -> MerklePath {
+        self.check_key(key);
+        let (siblings, directions) = (0..self.depth)
+            .map(|level| {
+                let index = key >> level;
+                (self.node(level, index ^ 1), index & 1 == 1)
+            })
+            .unzip();
+        MerklePath {
+            siblings,
+            directions,
+        }
+    }
+}
+
+/// The hash of a node with `RATE` children, one Poseidon permutation of width `WIDTH`.
+pub fn hash_children<const WIDTH: usize, const RATE: usize>(children: [Fr; RATE]) -> Fr {
+    poseidon::Hash::<_, MySpec<WIDTH, RATE>, ConstantLength<RATE>, WIDTH, RATE>::init()
+        .hash(children)
+}
+
+/// The child groups from the leaf up. `groups[i]` holds every child of the node above level
+/// `i`, including the one on the path, which is at `digits[i]`, digit `i` of the key in base
+/// `RATE`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct KvPath {
+    pub groups: Vec<Vec<Fr>>,
+    pub digits: Vec<usize>,
+}
+
+impl KvPath {
+    pub fn depth(&self) -> usize {
+        self.groups.len()
+    }
+
+    /// The root `leaf` hashes up to along the path. The siblings are the same before and
+    /// after an update, so the new root is this with the new leaf.
+    pub fn root<const WIDTH: usize, const RATE: usize>(&self, leaf: Fr) -> Fr {
+        self.groups
+            .iter()
+            .zip(self.digits.iter())
+            .fold(leaf, |node, (group, digit)| {
+                let mut children: [Fr; RATE] = group.clone().try_into().unwrap();
+                children[*digit] = node;
+                hash_children::<WIDTH, RATE>(children)
+            })
+    }
+}
+
+/// A key-value map over the keys `0..RATE^depth` committed to by a `RATE`-ary tree, with
+/// absent keys mapping to zero. As in [`SparseMerkleTree`], only the nodes above set keys are
+/// stored. `WIDTH` is always `RATE + 1`.
+#[derive(Clone, Debug)]
+pub struct KvTree<const WIDTH: usize, const RATE: usize> {
+    depth: usize,
+    /// `defaults[level]` is the root of an empty subtree of height `level`.
+    defaults: Vec<Fr>,
+    nodes: HashMap<(usize, u64), Fr>,
+}
+
+impl<const WIDTH: usize, const RATE: usize> KvTree<WIDTH, RATE> {
+    pub fn new(depth: usize) -> Self {
+        assert_eq!(WIDTH, RATE + 1, "one capacity element");
+        assert!(RATE >= 2, "a tree of arity {}", RATE);
+        assert!(
+            (RATE as u64).checked_pow(depth as u32).is_some(),
+            "keys are u64"
+        );
+        let mut defaults = vec![Fr::zero()];
+        for level in 0..depth {
+            defaults.push(hash_children::<WIDTH, RATE>([defaults[level]; RATE]));
+        }
+        Self {
+            depth,
+            defaults,
+            nodes: HashMap::new(),
+        }
+    }
+
+    pub fn depth(&self) -> usize {
+        self.depth
+    }
+
+    /// The number of keys, `RATE^depth`.
+    pub fn capacity(&self) -> u64 {
+        (RATE as u64).pow(self.depth as u32)
+    }
+
+    fn node(&self, level: usize, index: u64) -> Fr {
+        self.nodes
+            .get(&(level, index))
+            .copied()
+            .unwrap_or(self.defaults[level])
+    }
+
+    fn check_key(&self, key: u64) {
+        assert!(key < self.capacity(), "key {} out of range", key);
+    }
+
+    fn group(&self, level: usize, index: u64) -> Vec<Fr> {
+        let first = index - index % RATE as u64;
+        (first..first + RATE as u64)
+            .map(|child| self.node(level, child))
+            .collect()
+    }
+
+    pub fn root(&self) -> Fr {
+        self.node(self.depth, 0)
+    }
+
+    /// The value at `key`, zero if absent.
+    pub fn get(&self, key: u64) -> Fr {
+        self.check_key(key);
+        self.node(0, key)
+    }
+
+    /// Sets the value at `key`. Zero is the absent value, so inserting it removes the key.
+    pub fn insert(&mut self, key: u64, value: Fr) {
+        self.check_key(key);
+        let mut node = value;
+        let mut index = key;
+        for level in 0..=self.depth {
+            if node == self.defaults[level] {
+                self.nodes.remove(&(level, index));
+            } else {
+                self.nodes.insert((level, index), node);
+            }
+            if level < self.depth {
+                node = hash_children::<WIDTH, RATE>(self.group(level, index).try_into().unwrap());
+                index /= RATE as u64;
+            }
+        }
+    }
+
+    /// The path to the value at `key`, whose digits are those of `key` in base `RATE`.
+    pub fn path(&self, key: u64) -> KvPath {
+        self.check_key(key);
+        let mut index = key;
+        let (groups, digits) = (0..self.depth)
+            .map(|level| {
+                let step = (self.group(level, index), (index % RATE as u64) as usize);
+                index /= RATE as u64;
+                step
+            })
+            .unzip();
+        KvPath { groups, digits }
+    }
+}
@@ -0,0 +1,219 @@
+//! Counters of a proving service in the Prometheus text format: proofs generated,
+//! verification failures, the time spent in each phase, the requests waiting for an answer
+//! and the process's memory. [`prover::run_proof`](crate::prover::run_proof) and the
+//! `verifier` checks record into the process's [`global`] metrics as they run, and
+//! [`readiness::serve`](crate::readiness::serve) answers `GET /metrics` with [`Metrics::render`].
+
+use std::{
+    fmt::Write,
+    sync::atomic::{AtomicU64, Ordering},
+    time::{Duration, Instant},
+};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Phase {
+    Keygen,
+    Prove,
+    Verify,
+}
+
+impl Phase {
+    const ALL: [Phase; 3] = [Phase::Keygen, Phase::Prove, Phase::Verify];
+
+    fn name(self) -> &'static str {
+        match self {
+            Phase::Keygen => "keygen",
+            Phase::Prove => "prove",
+            Phase::Verify => "verify",
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Latency {
+    count: AtomicU64,
+    nanos: AtomicU64,
+}
+
+impl Latency {
+    const fn new() -> Self {
+        Self {
+            count: AtomicU64::new(0),
+            nanos: AtomicU64::new(0),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct Metrics {
+    proofs: AtomicU64,
+    verification_failures: AtomicU64,
+    /// Indexed by `Phase as usize`.
+    latencies: [Latency; 3],
+    queue_depth: AtomicU64,
+}
+
+/// Counts a request as queued until dropped.
+pub struct Queued<'a>(&'a Metrics);
+
+impl Drop for Queued<'_> {
+    fn drop(&mut self) {
+        self.0.queue_depth.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+static GLOBAL: Metrics = Metrics::new();
+
+/// The metrics every proof and verification of this process is recorded in.
+pub fn global() -> &'static Metrics {
+    &GLOBAL
+}
+
+impl Metrics {
+    pub const fn new() -> Self {
+        Self {
+            proofs: AtomicU64::new(0),
+            verification_failures: AtomicU64::new(0),
+            latencies: [Latency::new(), Latency::new(), Latency::new()],
+            queue_depth: AtomicU64::new(0),
+        }
+    }
+
+    pub fn record(&self, phase: Phase, elapsed: Duration) {
+        let latency = &self.latencies[phase as usize];
+        latency.count.fetch_add(1, Ordering::Relaxed);
+        let nanos = u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX);
+        latency.nanos.fetch_add(nanos, Ordering::Relaxed);
+    }
+
+    /// Runs `f`, recording its time under `phase`.
+    pub fn time<T>(&self, phase: Phase, f: impl FnOnce() -> T) -> T {
+        let start = Instant::now();
+        let result = f();
+        self.record(phase, start.elapsed());
+        result
+    }
+
+    pub fn proof_generated(&self) {
+        self.proofs.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn verification_failed(&self) {
+        self.verification_failures.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub fn queued(&self) -> Queued<'_> {
+        self.queue_depth.fetch_add(1, Ordering::Relaxed);
+        Queued(self)
+    }
+
+    pub fn proofs(&self) -> u64 {
+        self.proofs.load(Ordering::Relaxed)
+    }
+
+    pub fn verification_failures(&self) -> u64 {
+        self.verification_failures.load(Ordering::Relaxed)
+    }
+
+    pub fn queue_depth(&self) -> u64 {
+        self.queue_depth.load(Ordering::Relaxed)
+    }
+
+    /// The metrics in the Prometheus text exposition format. The memory gauges are left out
+    /// where there is no procfs to read them from.
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let mut metric = |name: &str, kind: &str, help: &str, samples: &[(String, String)]| {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} {}", name, kind);
+            for (sample, value) in samples {
+                let _ = writeln!(out, "{} {}", sample, value);
+            }
+        };
+        let name = "playground_proofs_total";
+        let samples = [(name.to_string(), self.proofs().to_string())];
+        metric(name, "counter", "Proofs generated.", &samples);
+        let name = "playground_verification_failures_total";
+        let samples = [(name.to_string(), self.verification_failures().to_string())];
+        metric(
+            name,
+            "counter",
+            "Proofs that failed verification.",
+            &samples,
+        );
+
+        let samples: Vec<(String, String)> = Phase::ALL
+            .iter()
+            .flat_map(|&phase| {
+                let latency = &self.latencies[phase as usize];
+                let seconds = latency.nanos.load(Ordering::Relaxed) as f64 / 1e9;
+                let count = latency.count.load(Ordering::Relaxed);
+                [
+                    (
+                        format!("playground_phase_seconds_sum{{phase=\"{}\"}}", phase.name()),
+                        format!("{:.6}", seconds),
+                    ),
+                    (
+                        format!(
+                            "playground_phase_seconds_count{{phase=\"{}\"}}",
+                            phase.name()
+                        ),
+                        count.to_string(),
+                    ),
+                ]
+            })
+            .collect();
+        metric(
+            "playground_phase_seconds",
+            "summary",
+            "Time spent in keygen, proving and verification.",
+            &samples,
+        );
+
+        let name = "playground_queue_depth";
+        let samples = [(name.to_string(), self.queue_depth().to_string())];
+        metric(
+            name,
+            "gauge",
+            "Requests accepted and not answered yet.",
+            &samples,
+        );
+        for (name, field, help) in [
+            (
+                "playground_resident_memory_bytes",
+                "VmRSS:",
+                "Resident memory of the process.",
+            ),
+            (
+                "playground_peak_resident_memory_bytes",
+                "VmHWM:",
+                "Peak resident memory of the process.",
+            ),
+        ] {
+            if let Some(bytes) = status_bytes(field) {
+                metric(
+                    name,
+                    "gauge",
+                    help,
+                    &[(name.to_string(), bytes.to_string())],
+                );
+            }
+        }
+        out
+    }
+}
+
+/// A `kB` field of `/proc/self/status`, such as `VmRSS:`, in bytes; `None` where there is no
+/// procfs.
+pub(crate) fn status_bytes(field: &str) -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let line = status.lines().find(|line| line.starts_with(field))?;
+    let kib: u64 = line
+        .trim_start_matches(field)
+        .trim()
+        .trim_end_matches("kB")
+        .trim()
+        .parse()
+        .ok()?;
+    Some(kib * 1024)
+}
@@ -0,0 +1,60 @@
+//! Optional parallelism behind the `rayon` feature. Bounds use `MaybeSend`/`MaybeSync`, which
+//! are `Send`/`Sync` with the feature and empty without it, so serial builds keep accepting
+//! any type.
+
+#[cfg(feature = "rayon")]
+mod bounds {
+    pub trait MaybeSend: Send {}
+    impl<T: Send> MaybeSend for T {}
+    pub trait MaybeSync: Sync {}
+    impl<T: Sync> MaybeSync for T {}
+}
+
+#[cfg(not(feature = "rayon"))]
+mod bounds {
+    pub trait MaybeSend {}
+    impl<T> MaybeSend for T {}
+    pub trait MaybeSync {}
+    impl<T> MaybeSync for T {}
+}
+
+pub use bounds::{MaybeSend, MaybeSync};
+
+/// Maps `f` over `items`, on the rayon thread pool with the `rayon` feature.
+#[cfg(feature = "rayon")]
+pub fn try_map<T, U, E>(
+    items: &[T],
+    f: impl Fn(&T) -> Result<U, E> + MaybeSend + MaybeSync,
+) -> Result<Vec<U>, E>
+where
+    T: MaybeSync,
+    U: MaybeSend,
+    E: MaybeSend,
+{
+    use rayon::prelude::*;
+    items.par_iter().map(f).collect()
+}
+
+/// Maps `f` over `items`, on the rayon thread pool with the `rayon` feature.
+#[cfg(not(feature = "rayon"))]
+pub fn try_map<T, U, E>(
+    items: &[T],
+    f: impl Fn(&T) -> Result<U, E> + MaybeSend + MaybeSync,
+) -> Result<Vec<U>, E>
+where
+    T: MaybeSync,
+    U: MaybeSend,
+    E: MaybeSend,
+{
+    items.iter().map(f).collect()
+}
+
+#[cfg(feature = "rayon")]
+pub fn threads() -> usize {
+    rayon::current_num_threads()
+}
+
+#[cfg(not(feature = "rayon"))]
+pub fn threads() -> usize {
+    1
+}
@@ -0,0 +1,129 @@
+//! Verification from byte slices alone, for hosts with no filesystem, clock or entropy, such
+//! as a contract on a RISC-V VM in the style of CKB-VM. Nothing here opens a file, draws
+//! randomness or spawns a thread; it only allocates, and reaches no further than `core` and
+//! `alloc`. halo2 itself still reads keys and params through `std::io::Read`, so the crate
+//! can't be built `no_std` until the fork's verifier can; this is the surface that would stay.
+
+use alloc::vec::Vec;
+
+use halo2_proofs::{
+    halo2curves::{
+        bn256::{Bn256, Fr, G1Affine},
+        group::ff::PrimeField,
+    },
+    plonk::{Circuit, VerifyingKey},
+    poly::{commitment::Params, kzg::commitment::ParamsKZG},
+};
+
+use crate::{
+    circuits::poseidon::PoseidonHashCircuit,
+    encoding::{field_from_le_bytes, Reduction},
+    limits::ResourceLimits,
+    poseidon::MySpec,
+    prover::ProofOptions,
+    verifier::check_proof,
+};
+
+/// The Poseidon circuit `playground prove --circuit poseidon` proves.
+pub type PoseidonCircuit = PoseidonHashCircuit<Fr, MySpec<3, 2>, 3, 2, 2>;
+
+const WORD_BYTES: usize = 32;
+/// A compressed G1 point, as params store their bases.
+const G1_BYTES: usize = 32;
+
+/// Whether `proof_bytes` proves the Poseidon circuit for `instances_bytes`. See
+/// [`verify_circuit`] for the formats.
+pub fn verify(
+    vk_bytes: &[u8],
+    params_bytes: &[u8],
+    instances_bytes: &[u8],
+    proof_bytes: &[u8],
+) -> bool {
+    verify_circuit::<PoseidonCircuit>(vk_bytes, params_bytes, instances_bytes, proof_bytes)
+}
+
+/// Whether `proof_bytes` is a SHPLONK proof over the Blake2b transcript (the default options)
+/// of circuit `C` for the given instances. The key and params are as `write_vk` and
+/// `write_params` store them. Each instance column is its row count as a little-endian `u32`,
+/// then that many 32-byte little-endian words. Input that doesn't parse, including trailing
+/// bytes, is `false` like a rejected proof, and so is input over [`ResourceLimits::default`]:
+/// the sizes are checked before anything is allocated for them.
+pub fn verify_circuit<C: Circuit<Fr>>(
+    vk_bytes: &[u8],
+    params_bytes: &[u8],
+    instances_bytes: &[u8],
+    proof_bytes: &[u8],
+) -> bool {
+    let limits = ResourceLimits::default();
+    if proof_bytes.len() > limits.max_proof_bytes {
+        return false;
+    }
+    match params_k(params_bytes) {
+        Some(k) if limits.check_k(k).is_ok() => {}
+        _ => return false,
+    }
+    let params = match ParamsKZG::<Bn256>::read(&mut &params_bytes[..]) {
+        Ok(params) => params,
+        Err(_) => return false,
+    };
+    let mut vk_reader = vk_bytes;
+    let vk = match VerifyingKey::<G1Affine>::read::<_, C>(&mut vk_reader, &params) {
+        Ok(vk) if vk_reader.is_empty() => vk,
+        _ => return false,
+    };
+    let columns = vk.cs.num_instance_columns();
+    let instances = match decode_columns(instances_bytes, columns, &limits) {
+        Some(instances) => instances,
+        None => return false,
+    };
+    let columns: Vec<&[Fr]> = instances.iter().map(Vec::as_slice).collect();
+    check_proof(&params, &vk, &columns, proof_bytes, ProofOptions::default()).is_verified()
+}
+
+/// The byte form [`verify_circuit`] takes the instances in.
+pub fn encode_columns(columns: &[Vec<Fr>]) -> Vec<u8> {
+    let mut bytes = Vec::new();
+    for column in columns {
+        bytes.extend((column.len() as u32).to_le_bytes());
+        for value in column {
+            bytes.extend(value.to_repr());
+        }
+    }
+    bytes
+}
+
+/// The `k` params bytes declare, if there are at least as many bytes as its bases take: `k`
+/// as a little-endian `u32`, then `2^k` monomial and `2^k` Lagrange bases.
+fn params_k(bytes: &[u8]) -> Option<u32> {
+    let header = bytes.get(..4)?;
+    let k = u32::from_le_bytes([header[0], header[1], header[2], header[3]]);
+    let bases = 1usize.checked_shl(k)?.checked_mul(2 * G1_BYTES)?;
+    (bytes.len() - 4 >= bases).then(|| k)
+}
+
+/// At most `max_columns` columns, each within `limits`.
+fn decode_columns(
+    mut bytes: &[u8],
+    max_columns: usize,
+    limits: &ResourceLimits,
+) -> Option<Vec<Vec<Fr>>> {
+    let mut columns = Vec::new();
+    while !bytes.is_empty() {
+        if bytes.len() < 4 || columns.len() == max_columns {
+            return None;
+        }
+        let (len, rest) = bytes.split_at(4);
+        let len = u32::from_le_bytes([len[0], len[1], len[2], len[3]]) as usize;
+        if len > limits.max_instance_len || rest.len() / WORD_BYTES < len {
+            return None;
+        }
+        let (words, rest) = rest.split_at(len * WORD_BYTES);
+        let column = words
+            .chunks(WORD_BYTES)
+            .map(|word| field_from_le_bytes(word, Reduction::Reject).ok())
+            .collect::<Option<Vec<Fr>>>()?;
+        columns.push(column);
+        bytes = rest;
+    }
+    Some(columns)
+}
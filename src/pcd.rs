@@ -0,0 +1,120 @@
+//! Proof-carrying data over state roots: proof `i` shows that `root_{i+1}` is the result of
+//! applying a private update to `root_i`, and its output root is the next proof's input. A
+//! checker only needs the initial root, the proofs and their public roots.
+
+use std::fmt;
+
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{ProvingKey, VerifyingKey},
+    poly::kzg::{commitment::ParamsKZG, strategy::AccumulatorStrategy},
+};
+
+use crate::{
+    circuits::transition::StateTransitionCircuit,
+    entropy::EntropySource,
+    error::PlaygroundError,
+    prover::{run_proof, MultiOpen, ProofOptions},
+    verifier::{final_check, precheck_proof},
+};
+
+#[derive(Clone, Debug)]
+pub struct TransitionProof {
+    pub root_in: Fr,
+    pub root_out: Fr,
+    pub proof: Vec<u8>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChainError {
+    /// Proof `index` doesn't start from the previous proof's output root.
+    Broken { index: usize },
+    /// Some proof doesn't verify.
+    InvalidProof,
+    /// The final root isn't the claimed one.
+    WrongHead,
+}
+
+impl fmt::Display for ChainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ChainError::Broken { index } => {
+                write!(f, "proof {} doesn't start from the previous root", index)
+            }
+            ChainError::InvalidProof => write!(f, "a transition proof doesn't verify"),
+            ChainError::WrongHead => write!(f, "the chain doesn't end at the claimed root"),
+        }
+    }
+}
+
+impl std::error::Error for ChainError {}
+
+/// The coordinator: proves one transition per update, threading each output root into the
+/// next proof.
+pub fn prove_transitions<R: EntropySource>(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    initial: Fr,
+    updates: &[Fr],
+    mut rng: R,
+) -> Result<Vec<TransitionProof>, PlaygroundError> {
+    let mut root_in = initial;
+    let mut proofs = Vec::with_capacity(updates.len());
+    for update in updates.iter() {
+        let root_out = StateTransitionCircuit::next_root(root_in, *update);
+        let proof = run_proof(
+            params,
+            pk,
+            StateTransitionCircuit::new(*update),
+            &[&[root_in, root_out]],
+            &mut rng,
+            ProofOptions::default(),
+        )?;
+        proofs.push(TransitionProof {
+            root_in,
+            root_out,
+            proof,
+        });
+        root_in = root_out;
+    }
+    Ok(proofs)
+}
+
+/// The checker: the chain must start at `initial`, link up, end at `head` and every proof must
+/// verify. The pairings are batched into one final check.
+pub fn check_chain(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    initial: Fr,
+    head: Fr,
+    proofs: &[TransitionProof],
+) -> Result<(), ChainError> {
+    let mut expected = initial;
+    for (index, transition) in proofs.iter().enumerate() {
+        if transition.root_in != expected {
+            return Err(ChainError::Broken { index });
+        }
+        expected = transition.root_out;
+    }
+    if expected != head {
+        return Err(ChainError::WrongHead);
+    }
+
+    let mut strategy = AccumulatorStrategy::new(params);
+    for transition in proofs.iter() {
+        let instances: &[&[&[Fr]]] = &[&[&[transition.root_in, transition.root_out]]];
+        strategy = precheck_proof(
+            params,
+            vk,
+            strategy,
+            instances,
+            &transition.proof,
+            ProofOptions::default(),
+        )
+        .map_err(|_| ChainError::InvalidProof)?;
+    }
+    if !final_check(strategy, MultiOpen::Shplonk) {
+        return Err(ChainError::InvalidProof);
+    }
+    Ok(())
+}
@@ -0,0 +1,68 @@
+use halo2_gadgets::poseidon::primitives::Spec;
+use halo2_proofs::{arithmetic::FieldExt, halo2curves::bn256::Fr};
+
+/// Source of this module, hashed into the provenance of keys for circuits using [`MySpec`].
+pub const SOURCE: &str = include_str!("poseidon.rs");
+
+/// Full rounds recommended for x^5 over a 254-bit field at the 128-bit security level.
+pub const SECURE_FULL_ROUNDS: usize = 8;
+
+/// Partial rounds recommended for x^5 over a 254-bit field at the 128-bit security level,
+/// indexed by `WIDTH - 2`. These are the values used by the reference implementation.
+pub const SECURE_PARTIAL_ROUNDS: [usize; 16] = [
+    56, 57, 56, 60, 60, 63, 64, 63, 60, 66, 60, 65, 70, 60, 64, 68,
+];
+
+pub fn secure_partial_rounds(width: usize) -> usize {
+    assert!(
+        (2..2 + SECURE_PARTIAL_ROUNDS.len()).contains(&width),
+        "no recommended Poseidon rounds for width {}",
+        width
+    );
+    SECURE_PARTIAL_ROUNDS[width - 2]
+}
+
+/// Panics unless `S` uses at least the recommended number of rounds for `WIDTH`.
+pub fn assert_secure<S: Spec<Fr, WIDTH, RATE>, const WIDTH: usize, const RATE: usize>() {
+    assert!(
+        S::full_rounds() >= SECURE_FULL_ROUNDS,
+        "{}: {} full rounds, at least {} needed for width {}",
+        std::any::type_name::<S>(),
+        S::full_rounds(),
+        SECURE_FULL_ROUNDS,
+        WIDTH
+    );
+    assert!(
+        S::partial_rounds() >= secure_partial_rounds(WIDTH),
+        "{}: {} partial rounds, at least {} needed for width {}",
+        std::any::type_name::<S>(),
+        S::partial_rounds(),
+        secure_partial_rounds(WIDTH),
+        WIDTH
+    );
+}
+
+/// The recommended parameters over any field where `x^5` is a permutation, which includes the
+/// scalar fields of BN256 and of the Pasta curves.
+#[derive(Debug, Clone, Copy)]
+pub struct MySpec<const WIDTH: usize, const RATE: usize>;
+
+impl<F: FieldExt, const WIDTH: usize, const RATE: usize> Spec<F, WIDTH, RATE>
+    for MySpec<WIDTH, RATE>
+{
+    fn full_rounds() -> usize {
+        SECURE_FULL_ROUNDS
+    }
+
+    fn partial_rounds() -> usize {
+        secure_partial_rounds(WIDTH)
+    }
+
+    fn sbox(val: F) -> F {
+        val.pow_vartime(&[5])
+    }
+
+    fn secure_mds() -> usize {
+        0
+    }
+}
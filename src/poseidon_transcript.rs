@@ -0,0 +1,184 @@
+//! Poseidon transcript over BN256, for proofs whose verifier runs in a circuit over `Fr`.
+//!
+//! The transcript state is one scalar, and absorbing or squeezing is one width-3 Poseidon hash
+//! of `(state, value, tag)`, the same [`MySpec`] the circuits use, so replaying it in a circuit
+//! costs a few permutations per item where Blake2b would cost thousands of rows. Scalars are
+//! absorbed as they are. A point's base field coordinates don't fit `Fr`, so each is absorbed
+//! as its low and high 128 bits; the identity as `(0, 0)`. A challenge is the state after
+//! squeezing. Proof bytes are laid out as with Blake2b: compressed points and 32-byte scalars.
+
+use std::{
+    io::{self, Read, Write},
+    marker::PhantomData,
+};
+
+use halo2_gadgets::poseidon::primitives::{self as poseidon, ConstantLength};
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    halo2curves::{
+        bn256::{Fq, Fr, G1Affine},
+        group::{ff::PrimeField, GroupEncoding},
+    },
+    transcript::{
+        Challenge255, EncodedChallenge, Transcript, TranscriptRead, TranscriptReadBuffer,
+        TranscriptWrite, TranscriptWriterBuffer,
+    },
+};
+
+use crate::poseidon::MySpec;
+
+type Spec = MySpec<3, 2>;
+type Challenge = Challenge255<G1Affine>;
+
+const ABSORB: u64 = 0;
+const SQUEEZE: u64 = 1;
+
+#[derive(Clone, Debug, Default)]
+struct State(Fr);
+
+impl State {
+    fn update(&mut self, value: Fr, tag: u64) {
+        self.0 = poseidon::Hash::<_, Spec, ConstantLength<3>, 3, 2>::init().hash([
+            self.0,
+            value,
+            Fr::from(tag),
+        ]);
+    }
+
+    fn absorb_scalar(&mut self, scalar: Fr) {
+        self.update(scalar, ABSORB);
+    }
+
+    fn absorb_point(&mut self, point: &G1Affine) {
+        let coordinates: Option<_> = point.coordinates().into();
+        let (x, y) = match coordinates {
+            Some(coordinates) => (*coordinates.x(), *coordinates.y()),
+            None => (Fq::zero(), Fq::zero()),
+        };
+        for coordinate in [x, y] {
+            let repr = coordinate.to_repr();
+            for half in repr.as_ref().chunks(16) {
+                let mut bytes = [0; 32];
+                bytes[..16].copy_from_slice(half);
+                self.absorb_scalar(Fr::from_repr(bytes).unwrap());
+            }
+        }
+    }
+
+    fn squeeze(&mut self) -> Challenge {
+        self.update(Fr::zero(), SQUEEZE);
+        let mut wide = [0; 64];
+        wide[..32].copy_from_slice(self.0.to_repr().as_ref());
+        Challenge::new(&wide)
+    }
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+#[derive(Debug, Clone)]
+pub struct PoseidonWrite<W: Write, C: CurveAffine, E: EncodedChallenge<C>> {
+    state: State,
+    writer: W,
+    _marker: PhantomData<(C, E)>,
+}
+
+impl<W: Write> Transcript<G1Affine, Challenge> for PoseidonWrite<W, G1Affine, Challenge> {
+    fn squeeze_challenge(&mut self) -> Challenge {
+        self.state.squeeze()
+    }
+
+    fn common_point(&mut self, point: G1Affine) -> io::Result<()> {
+        self.state.absorb_point(&point);
+        Ok(())
+    }
+
+    fn common_scalar(&mut self, scalar: Fr) -> io::Result<()> {
+        self.state.absorb_scalar(scalar);
+        Ok(())
+    }
+}
+
+impl<W: Write> TranscriptWrite<G1Affine, Challenge> for PoseidonWrite<W, G1Affine, Challenge> {
+    fn write_point(&mut self, point: G1Affine) -> io::Result<()> {
+        self.common_point(point)?;
+        self.writer.write_all(point.to_bytes().as_ref())
+    }
+
+    fn write_scalar(&mut self, scalar: Fr) -> io::Result<()> {
+        self.common_scalar(scalar)?;
+        self.writer.write_all(scalar.to_repr().as_ref())
+    }
+}
+
+impl<W: Write> TranscriptWriterBuffer<W, G1Affine, Challenge>
+    for PoseidonWrite<W, G1Affine, Challenge>
+{
+    fn init(writer: W) -> Self {
+        Self {
+            state: State::default(),
+            writer,
+            _marker: PhantomData,
+        }
+    }
+
+    fn finalize(self) -> W {
+        self.writer
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PoseidonRead<R: Read, C: CurveAffine, E: EncodedChallenge<C>> {
+    state: State,
+    reader: R,
+    _marker: PhantomData<(C, E)>,
+}
+
+impl<R: Read> Transcript<G1Affine, Challenge> for PoseidonRead<R, G1Affine, Challenge> {
+    fn squeeze_challenge(&mut self) -> Challenge {
+        self.state.squeeze()
+    }
+
+    fn common_point(&mut self, point: G1Affine) -> io::Result<()> {
+        self.state.absorb_point(&point);
+        Ok(())
+    }
+
+    fn common_scalar(&mut self, scalar: Fr) -> io::Result<()> {
+        self.state.absorb_scalar(scalar);
+        Ok(())
+    }
+}
+
+impl<R: Read> TranscriptRead<G1Affine, Challenge> for PoseidonRead<R, G1Affine, Challenge> {
+    fn read_point(&mut self) -> io::Result<G1Affine> {
+        let mut bytes = <G1Affine as GroupEncoding>::Repr::default();
+        self.reader.read_exact(bytes.as_mut())?;
+        let point: G1Affine = Option::from(G1Affine::from_bytes(&bytes))
+            .ok_or_else(|| invalid_data("invalid point encoding in proof"))?;
+        self.common_point(point)?;
+        Ok(point)
+    }
+
+    fn read_scalar(&mut self) -> io::Result<Fr> {
+        let mut bytes = [0; 32];
+        self.reader.read_exact(&mut bytes)?;
+        let scalar: Fr = Option::from(Fr::from_repr(bytes))
+            .ok_or_else(|| invalid_data("invalid field element encoding in proof"))?;
+        self.common_scalar(scalar)?;
+        Ok(scalar)
+    }
+}
+
+impl<R: Read> TranscriptReadBuffer<R, G1Affine, Challenge>
+    for PoseidonRead<R, G1Affine, Challenge>
+{
+    fn init(reader: R) -> Self {
+        Self {
+            state: State::default(),
+            reader,
+            _marker: PhantomData,
+        }
+    }
+}
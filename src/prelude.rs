@@ -0,0 +1,41 @@
+//! The supported API of the playground as a library. Everything re-exported here keeps its
+//! name and meaning across minor versions, and `tests/prelude.rs` fails when one goes
+//! missing. The modules behind the `unstable` feature (continuation, folding, PCD,
+//! barycentric evaluation, the ceremony and IPA) can change in any release; the remaining
+//! public modules are supported only through what this prelude re-exports. The proving half
+//! needs the `prover` feature.
+
+#[cfg(feature = "prover")]
+pub use crate::{
+    audit::fit_k,
+    builder::{BuilderCircuit, Prototype},
+    bundle::Prover,
+    report::CostReport,
+};
+pub use crate::{
+    batch::{Batch, BatchError},
+    bundle::{ProofBundle, Verifier},
+    chips::{
+        arith::{ArithChip, ArithConfig, ArithContext, AssignedValue},
+        boolean::{BooleanChip, BooleanConfig},
+        branch::{BranchChip, BranchConfig, Condition},
+        constant_pool::{ConstantPool, PoolStats},
+        is_zero::{IsZeroChip, IsZeroConfig},
+        rlc::{RlcChip, RlcConfig},
+        shuffle::{ShuffleChip, ShuffleConfig},
+        standard_plonk::{StandardPlonkChip, StandardPlonkConfig},
+    },
+    circuits::{
+        find as find_circuit, mul::MulCircuit, poseidon::PoseidonHashCircuit, registry,
+        CircuitEntry,
+    },
+    entropy::{EntropySource, OsEntropy, SeededEntropy},
+    error::PlaygroundError,
+    poseidon::MySpec,
+    prover::{MultiOpen, ProofOptions, TranscriptHash},
+    schema::{
+        DescribeInstances, DescribeWitness, Encoding, InstanceLayout, OutOfRange, WitnessLayout,
+    },
+    storage::{cached_params, read_params, read_vk, write_params, write_vk},
+    verifier::VerificationReport,
+};
@@ -0,0 +1,297 @@
+//! Per-phase timings and peak memory of keygen, proving and verification, for telling whether
+//! keygen, the quotient or the multiopen argument dominates at a large `k`.
+//!
+//! halo2 exposes no hooks inside `create_proof`, so the proving phases are read off its
+//! transcript: every commitment and evaluation is written the moment its phase ends. The
+//! commitments run up to the vanishing argument's random polynomial, the quotient's pieces
+//! follow its extended-domain FFTs, then come the evaluations, then the multiopen argument's
+//! points. Synthesis, with the instance commitments before it, ends when the circuit's own
+//! `synthesize` returns.
+
+use std::{
+    fmt,
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    circuit::Layouter,
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{create_proof, keygen_pk, keygen_vk, Circuit, ConstraintSystem, Error, ProvingKey},
+    poly::{
+        commitment::Prover,
+        kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::{ProverGWC, ProverSHPLONK},
+        },
+    },
+    transcript::{Challenge255, Transcript, TranscriptWrite},
+};
+
+use crate::{
+    entropy::EntropySource,
+    error::PlaygroundError,
+    metrics,
+    prover::{MultiOpen, ProofOptions},
+    transcript_registry::AnyWrite,
+    verifier::check_proof,
+};
+
+type Challenge = Challenge255<G1Affine>;
+
+/// The process's peak resident set so far, in bytes, from `/proc/self/status`; `None` where
+/// there is no procfs.
+pub fn peak_rss() -> Option<u64> {
+    metrics::status_bytes("VmHWM:")
+}
+
+#[derive(Clone, Debug)]
+pub struct Span {
+    pub name: &'static str,
+    pub elapsed: Duration,
+    /// [`peak_rss`] when the span ended.
+    pub peak_rss: Option<u64>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct Profile {
+    pub spans: Vec<Span>,
+    pub proof_bytes: usize,
+}
+
+impl Profile {
+    pub fn total(&self) -> Duration {
+        self.spans.iter().map(|span| span.elapsed).sum()
+    }
+
+    /// The longest span.
+    pub fn dominant(&self) -> Option<&Span> {
+        self.spans.iter().max_by_key(|span| span.elapsed)
+    }
+}
+
+impl fmt::Display for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let total = self.total().as_secs_f64().max(f64::MIN_POSITIVE);
+        for span in self.spans.iter() {
+            write!(
+                f,
+                "  {:<12} {:>10.2?} {:>5.1}%",
+                span.name,
+                span.elapsed,
+                100.0 * span.elapsed.as_secs_f64() / total
+            )?;
+            match span.peak_rss {
+                Some(rss) => writeln!(f, "  peak rss {} MiB", rss >> 20)?,
+                None => writeln!(f)?,
+            }
+        }
+        write!(f, "  {:<12} {:>10.2?}", "total", self.total())
+    }
+}
+
+/// Appends spans, each from the end of the last one.
+struct Clock {
+    last: Instant,
+    spans: Vec<Span>,
+}
+
+impl Clock {
+    fn new() -> Self {
+        Clock {
+            last: Instant::now(),
+            spans: vec![],
+        }
+    }
+
+    fn end(&mut self, name: &'static str) {
+        self.end_at(name, Instant::now());
+    }
+
+    fn end_at(&mut self, name: &'static str, at: Instant) {
+        self.spans.push(Span {
+            name,
+            elapsed: at.saturating_duration_since(self.last),
+            peak_rss: peak_rss(),
+        });
+        self.last = at;
+    }
+}
+
+struct ProfiledCircuit<C> {
+    circuit: C,
+    /// When synthesis first ended.
+    synthesis: Arc<Mutex<Option<Instant>>>,
+}
+
+impl<F: FieldExt, C: Circuit<F>> Circuit<F> for ProfiledCircuit<C> {
+    type Config = C::Config;
+    type FloorPlanner = C::FloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self {
+            circuit: self.circuit.without_witnesses(),
+            synthesis: self.synthesis.clone(),
+        }
+    }
+
+    fn configure(meta: &mut ConstraintSystem<F>) -> Self::Config {
+        C::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<F>) -> Result<(), Error> {
+        self.circuit.synthesize(config, layouter)?;
+        self.synthesis
+            .lock()
+            .unwrap()
+            .get_or_insert_with(Instant::now);
+        Ok(())
+    }
+}
+
+/// A transcript recording when each point and scalar is written.
+struct ProfiledWrite {
+    inner: AnyWrite,
+    /// Whether each write was a point, and when.
+    writes: Vec<(bool, Instant)>,
+}
+
+impl Transcript<G1Affine, Challenge> for ProfiledWrite {
+    fn squeeze_challenge(&mut self) -> Challenge {
+        self.inner.squeeze_challenge()
+    }
+
+    fn common_point(&mut self, point: G1Affine) -> std::io::Result<()> {
+        self.inner.common_point(point)
+    }
+
+    fn common_scalar(&mut self, scalar: Fr) -> std::io::Result<()> {
+        self.inner.common_scalar(scalar)
+    }
+}
+
+impl TranscriptWrite<G1Affine, Challenge> for ProfiledWrite {
+    fn write_point(&mut self, point: G1Affine) -> std::io::Result<()> {
+        self.writes.push((true, Instant::now()));
+        self.inner.write_point(point)
+    }
+
+    fn write_scalar(&mut self, scalar: Fr) -> std::io::Result<()> {
+        self.writes.push((false, Instant::now()));
+        self.inner.write_scalar(scalar)
+    }
+}
+
+fn create_profiled<'params, P, C, R>(
+    params: &'params ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    circuit: ProfiledCircuit<C>,
+    instances: &[&[Fr]],
+    rng: R,
+    transcript: &mut ProfiledWrite,
+) -> Result<(), Error>
+where
+    P: Prover<'params, KZGCommitmentScheme<Bn256>>,
+    C: Circuit<Fr>,
+    R: EntropySource,
+{
+    create_proof::<KZGCommitmentScheme<Bn256>, P, Challenge, R, ProfiledWrite, _>(
+        params,
+        pk,
+        &[circuit],
+        &[instances],
+        rng,
+        transcript,
+    )
+}
+
+/// Splits the proving time at the transcript writes, as the module documentation describes.
+fn proving_spans(
+    clock: &mut Clock,
+    synthesis: Option<Instant>,
+    writes: &[(bool, Instant)],
+    quotient_pieces: usize,
+    end: Instant,
+) {
+    if let Some(end) = synthesis {
+        clock.end_at("synthesis", end);
+    }
+    let points = writes.iter().take_while(|(point, _)| *point).count();
+    let scalars = writes[points..]
+        .iter()
+        .take_while(|(point, _)| !*point)
+        .count();
+    if points > quotient_pieces {
+        clock.end_at("commitments", writes[points - quotient_pieces - 1].1);
+    }
+    if points > 0 {
+        clock.end_at("quotient", writes[points - 1].1);
+    }
+    if scalars > 0 {
+        clock.end_at("evaluations", writes[points + scalars - 1].1);
+    }
+    clock.end_at("multiopen", end);
+}
+
+/// Keygen, one proof of `circuit` and its verification under `params`, each split into spans.
+pub fn profile<C: Circuit<Fr>, R: EntropySource>(
+    params: &ParamsKZG<Bn256>,
+    circuit: C,
+    instances: &[&[Fr]],
+    rng: R,
+    options: ProofOptions,
+) -> Result<Profile, PlaygroundError> {
+    let mut clock = Clock::new();
+    let vk = keygen_vk(params, &circuit).map_err(PlaygroundError::Keygen)?;
+    clock.end("keygen_vk");
+    let pk = keygen_pk(params, vk, &circuit).map_err(PlaygroundError::Keygen)?;
+    clock.end("keygen_pk");
+
+    let circuit = ProfiledCircuit {
+        circuit,
+        synthesis: Arc::new(Mutex::new(None)),
+    };
+    let synthesis = circuit.synthesis.clone();
+    let mut transcript = ProfiledWrite {
+        inner: options.transcript.writer(),
+        writes: vec![],
+    };
+    match options.multiopen {
+        MultiOpen::Shplonk => create_profiled::<ProverSHPLONK<'_, Bn256>, _, _>(
+            params,
+            &pk,
+            circuit,
+            instances,
+            rng,
+            &mut transcript,
+        ),
+        MultiOpen::Gwc => create_profiled::<ProverGWC<'_, Bn256>, _, _>(
+            params,
+            &pk,
+            circuit,
+            instances,
+            rng,
+            &mut transcript,
+        ),
+    }
+    .map_err(PlaygroundError::Prove)?;
+    let proved = Instant::now();
+    let synthesis = *synthesis.lock().unwrap();
+    let quotient_pieces = pk.get_vk().domain.get_quotient_poly_degree();
+    proving_spans(
+        &mut clock,
+        synthesis,
+        &transcript.writes,
+        quotient_pieces,
+        proved,
+    );
+    let proof = transcript.inner.finalize();
+
+    check_proof(params, pk.get_vk(), instances, &proof, options).into_result()?;
+    clock.end("verify");
+    Ok(Profile {
+        spans: clock.spans,
+        proof_bytes: proof.len(),
+    })
+}
@@ -0,0 +1,119 @@
+//! Records which circuit source a verifying key was generated from, so keys left on disk after
+//! the circuit changed are caught instead of silently failing to verify (or worse, verifying
+//! the old statement).
+//!
+//! The record is a `<vk>.provenance` file next to the key holding the hash of the circuit's
+//! source and the vk's fingerprint. Only the sources passed in are hashed: a change in a chip
+//! from another crate goes unnoticed unless its source is listed too.
+
+use std::{
+    fmt, fs, io,
+    path::{Path, PathBuf},
+};
+
+use halo2_proofs::{halo2curves::bn256::G1Affine, plonk::VerifyingKey};
+
+use crate::audit_log::vk_fingerprint;
+
+fn hex(bytes: &[u8]) -> String {
+    bytes.iter().map(|b| format!("{:02x}", b)).collect()
+}
+
+/// Hash of the source files that define a circuit.
+pub fn source_hash(sources: &[&str]) -> String {
+    let mut state = blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(b"Circuit-Source")
+        .to_state();
+    for source in sources.iter() {
+        state.update(&(source.len() as u64).to_le_bytes());
+        state.update(source.as_bytes());
+    }
+    hex(state.finalize().as_bytes())
+}
+
+pub fn provenance_path(vk_path: &Path) -> PathBuf {
+    let mut path = vk_path.as_os_str().to_owned();
+    path.push(".provenance");
+    PathBuf::from(path)
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Provenance {
+    /// Generated from the current source.
+    Current,
+    /// No record, e.g. a key from before provenance was recorded.
+    Unrecorded,
+    /// The circuit source changed since keygen.
+    StaleSource { recorded: String, current: String },
+    /// The key on disk isn't the one recorded at keygen.
+    KeyChanged { recorded: String, current: String },
+}
+
+impl fmt::Display for Provenance {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Provenance::Current => write!(f, "key matches the circuit source"),
+            Provenance::Unrecorded => write!(f, "no provenance recorded for this key"),
+            Provenance::StaleSource { recorded, current } => write!(
+                f,
+                "key was generated from circuit source {}, the source is now {}; rerun keygen",
+                recorded, current
+            ),
+            Provenance::KeyChanged { recorded, current } => write!(
+                f,
+                "key fingerprint {} differs from the {} recorded at keygen",
+                current, recorded
+            ),
+        }
+    }
+}
+
+/// Writes the provenance of the key just written to `vk_path`.
+pub fn record_provenance(
+    vk_path: &Path,
+    source: &str,
+    vk: &VerifyingKey<G1Affine>,
+) -> io::Result<()> {
+    let text = format!("source {}\nvk {}\n", source, vk_fingerprint(vk));
+    fs::write(provenance_path(vk_path), text)
+}
+
+/// Compares the provenance of the key at `vk_path` against the current `source` hash.
+pub fn check_provenance(
+    vk_path: &Path,
+    source: &str,
+    vk: &VerifyingKey<G1Affine>,
+) -> io::Result<Provenance> {
+    let text = match fs::read_to_string(provenance_path(vk_path)) {
+        Ok(text) => text,
+        Err(err) if err.kind() == io::ErrorKind::NotFound => return Ok(Provenance::Unrecorded),
+        Err(err) => return Err(err),
+    };
+    let field = |name: &str| {
+        text.lines()
+            .find_map(|line| line.strip_prefix(name)?.strip_prefix(' '))
+            .map(str::to_string)
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("provenance has no {}", name),
+                )
+            })
+    };
+    let (recorded_source, recorded_vk) = (field("source")?, field("vk")?);
+    if recorded_source != source {
+        return Ok(Provenance::StaleSource {
+            recorded: recorded_source,
+            current: source.to_string(),
+        });
+    }
+    let fingerprint = vk_fingerprint(vk);
+    if recorded_vk != fingerprint {
+        return Ok(Provenance::KeyChanged {
+            recorded: recorded_vk,
+            current: fingerprint,
+        });
+    }
+    Ok(Provenance::Current)
+}
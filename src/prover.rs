@@ -0,0 +1,241 @@
+#[cfg(feature = "prover")]
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{create_proof, Circuit, Error, ProvingKey},
+    poly::{
+        commitment::Prover,
+        kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::{ProverGWC, ProverSHPLONK},
+        },
+    },
+    transcript::Challenge255,
+};
+
+#[cfg(feature = "prover")]
+use crate::{
+    entropy::EntropySource,
+    error::PlaygroundError,
+    metrics::{self, Phase},
+    transcript_registry::AnyWrite,
+};
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum MultiOpen {
+    #[default]
+    Shplonk,
+    Gwc,
+}
+
+impl MultiOpen {
+    pub const ALL: [MultiOpen; 2] = [MultiOpen::Shplonk, MultiOpen::Gwc];
+}
+
+/// A transcript hash, built through [`crate::transcript_registry`].
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum TranscriptHash {
+    #[default]
+    Blake2b,
+    /// See [`crate::transcript`], for proofs checked by an EVM verifier.
+    Keccak256,
+    /// See [`crate::poseidon_transcript`], for proofs verified in a circuit.
+    Poseidon,
+}
+
+/// How a proof is produced. The verifier has to use the same options.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ProofOptions {
+    pub multiopen: MultiOpen,
+    pub transcript: TranscriptHash,
+}
+
+#[cfg(feature = "prover")]
+fn create_with<'params, P, C, R>(
+    params: &'params ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    circuits: &[C],
+    instances: &[&[&[Fr]]],
+    rng: R,
+    transcript: TranscriptHash,
+) -> Result<Vec<u8>, Error>
+where
+    P: Prover<'params, KZGCommitmentScheme<Bn256>>,
+    C: Circuit<Fr>,
+    R: EntropySource,
+{
+    let mut transcript = transcript.writer();
+    create_proof::<KZGCommitmentScheme<Bn256>, P, Challenge255<G1Affine>, R, AnyWrite, C>(
+        params,
+        pk,
+        circuits,
+        instances,
+        rng,
+        &mut transcript,
+    )?;
+    Ok(transcript.finalize())
+}
+
+#[cfg(feature = "prover")]
+/// Proves one instance of `circuit` over KZG, failing with a [`PlaygroundError::Prove`].
+pub fn run_proof<C: Circuit<Fr>, R: EntropySource>(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    circuit: C,
+    instances: &[&[Fr]],
+    rng: R,
+    options: ProofOptions,
+) -> Result<Vec<u8>, PlaygroundError> {
+    run_multi_proof(params, pk, &[circuit], &[instances], rng, options)
+}
+
+#[cfg(feature = "prover")]
+/// Proves every circuit in `circuits`, each against its own entry of `instances`, in one proof
+/// under one key. The fixed columns and the multiopen argument are shared, so the proof grows
+/// by the per-instance commitments and evaluations only; `verifier::check_multi_proof` checks
+/// it. The proving time and the proof are recorded in [`metrics::global`].
+pub fn run_multi_proof<C: Circuit<Fr>, R: EntropySource>(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    circuits: &[C],
+    instances: &[&[&[Fr]]],
+    rng: R,
+    options: ProofOptions,
+) -> Result<Vec<u8>, PlaygroundError> {
+    if circuits.is_empty() || circuits.len() != instances.len() {
+        return Err(PlaygroundError::Prove(Error::InvalidInstances));
+    }
+    let metrics = metrics::global();
+    let proof = metrics
+        .time(Phase::Prove, || match options.multiopen {
+            MultiOpen::Shplonk => create_with::<ProverSHPLONK<'_, Bn256>, _, _>(
+                params,
+                pk,
+                circuits,
+                instances,
+                rng,
+                options.transcript,
+            ),
+            MultiOpen::Gwc => create_with::<ProverGWC<'_, Bn256>, _, _>(
+                params,
+                pk,
+                circuits,
+                instances,
+                rng,
+                options.transcript,
+            ),
+        })
+        .map_err(PlaygroundError::Prove)?;
+    metrics.proof_generated();
+    Ok(proof)
+}
+
+#[cfg(feature = "prover")]
+/// Proofs of one statement for a native and an EVM verifier.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct DualProof {
+    pub blake2b: Vec<u8>,
+    pub keccak256: Vec<u8>,
+}
+
+#[cfg(feature = "prover")]
+/// Two independent proofs of `circuit` under `multiopen`, one with the Blake2b and one with
+/// the Keccak256 transcript, run in parallel on two threads at twice the cost of one proof.
+/// Nothing but the proving key is shared: halo2's `create_proof` synthesizes, commits and
+/// squeezes in one call, so each proof redoes all of it. Sharing the commitments wouldn't be
+/// sound anyway, as openings of the same blinded polynomials at two sets of challenges reveal
+/// more than the blinding hides, so each proof gets its own randomness.
+pub fn prove_both_transcripts<C, R>(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    circuit: C,
+    instances: &[&[Fr]],
+    multiopen: MultiOpen,
+    rngs: [R; 2],
+) -> Result<DualProof, PlaygroundError>
+where
+    C: Circuit<Fr> + Clone + Send,
+    R: EntropySource + Send,
+{
+    let [blake2b_rng, keccak_rng] = rngs;
+    let options = |transcript| ProofOptions {
+        multiopen,
+        transcript,
+    };
+    std::thread::scope(|scope| {
+        let keccak_circuit = circuit.clone();
+        let keccak = scope.spawn(move || {
+            run_proof(
+                params,
+                pk,
+                keccak_circuit,
+                instances,
+                keccak_rng,
+                options(TranscriptHash::Keccak256),
+            )
+        });
+        let blake2b = run_proof(
+            params,
+            pk,
+            circuit,
+            instances,
+            blake2b_rng,
+            options(TranscriptHash::Blake2b),
+        );
+        let keccak = keccak
+            .join()
+            .unwrap_or_else(|panic| std::panic::resume_unwind(panic));
+        Ok(DualProof {
+            blake2b: blake2b?,
+            keccak256: keccak?,
+        })
+    })
+}
+
+#[cfg(feature = "prover")]
+/// Proves each `(circuit, instances)` job on its own, with up to `parallelism` proofs in
+/// flight, and returns the results in job order. Each prover holds its whole witness and
+/// extended-domain polynomials at once, so `parallelism` bounds memory as much as
+/// throughput. `rng(i)` gives job `i` its own randomness.
+pub fn prove_batch<C, R>(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    jobs: Vec<(C, Vec<Vec<Fr>>)>,
+    rng: impl Fn(usize) -> R + Sync,
+    options: ProofOptions,
+    parallelism: usize,
+) -> Vec<Result<Vec<u8>, PlaygroundError>>
+where
+    C: Circuit<Fr> + Send,
+    R: EntropySource,
+{
+    use std::sync::{
+        atomic::{AtomicUsize, Ordering},
+        Mutex,
+    };
+
+    let count = jobs.len();
+    let jobs: Vec<Mutex<Option<(C, Vec<Vec<Fr>>)>>> =
+        jobs.into_iter().map(|job| Mutex::new(Some(job))).collect();
+    let results: Vec<Mutex<Option<Result<Vec<u8>, PlaygroundError>>>> =
+        (0..count).map(|_| Mutex::new(None)).collect();
+    let next = AtomicUsize::new(0);
+    std::thread::scope(|scope| {
+        for _ in 0..parallelism.clamp(1, count.max(1)) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= count {
+                    break;
+                }
+                let (circuit, instances) = jobs[i].lock().unwrap().take().unwrap();
+                let columns: Vec<&[Fr]> = instances.iter().map(|column| &column[..]).collect();
+                let result = run_proof(params, pk, circuit, &columns, rng(i), options);
+                *results[i].lock().unwrap() = Some(result);
+            });
+        }
+    });
+    results
+        .into_iter()
+        .map(|result| result.into_inner().unwrap().unwrap())
+        .collect()
+}
@@ -0,0 +1,356 @@
+//! Warm-up and readiness for proving services. A [`Service`] holds the params and the proving
+//! keys it serves; [`Service::readiness`] confirms both are usable and that a tiny proof with
+//! those params verifies, and [`serve`] answers `GET /ready` with it so an orchestrator only
+//! routes proving traffic to warmed instances. The service's keygen, and every proof and
+//! verification of the process, are counted in [`metrics::global`], which [`serve`] answers
+//! `GET /metrics` with.
+
+use std::{
+    fmt,
+    io::{self, BufRead, BufReader, Write},
+    net::{TcpListener, TcpStream},
+    sync::{mpsc, Mutex, PoisonError},
+    thread,
+    time::{Duration, Instant},
+};
+
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{keygen_pk, keygen_vk, ProvingKey},
+    poly::{commitment::Params, kzg::commitment::ParamsKZG},
+};
+
+use crate::{
+    audit::fit_k,
+    audit_log::json_string,
+    circuits::mul::MulCircuit,
+    cli::CliCircuit,
+    entropy::OsEntropy,
+    error::PlaygroundError,
+    metrics::{self, Metrics, Phase, Queued},
+    provenance::source_hash,
+    prover::{run_proof, ProofOptions},
+    srs::downsize,
+    storage::cached_proving_key,
+    store::ArtifactStore,
+    verifier::check_proof,
+};
+
+/// One line of a readiness report.
+#[derive(Clone, Debug)]
+pub struct Check {
+    pub name: &'static str,
+    pub elapsed: Duration,
+    /// What was found, or why the check failed.
+    pub result: Result<String, String>,
+}
+
+#[derive(Clone, Debug)]
+pub struct Readiness {
+    pub checks: Vec<Check>,
+}
+
+impl Readiness {
+    pub fn is_ready(&self) -> bool {
+        self.checks.iter().all(|check| check.result.is_ok())
+    }
+
+    pub fn to_json(&self) -> String {
+        let checks: Vec<String> = self
+            .checks
+            .iter()
+            .map(|check| {
+                let (ok, detail) = match &check.result {
+                    Ok(detail) => (true, detail),
+                    Err(err) => (false, err),
+                };
+                format!(
+                    "{{\"name\":{},\"ok\":{},\"ms\":{:.3},\"detail\":{}}}",
+                    json_string(check.name),
+                    ok,
+                    check.elapsed.as_secs_f64() * 1000.0,
+                    json_string(detail)
+                )
+            })
+            .collect();
+        format!(
+            "{{\"ready\":{},\"checks\":[{}]}}",
+            self.is_ready(),
+            checks.join(",")
+        )
+    }
+}
+
+impl fmt::Display for Readiness {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            if self.is_ready() {
+                "ready"
+            } else {
+                "not ready"
+            }
+        )?;
+        for check in self.checks.iter() {
+            let (status, detail) = match &check.result {
+                Ok(detail) => ("ok", detail),
+                Err(err) => ("FAILED", err),
+            };
+            write!(
+                f,
+                "\n  {}: {} in {:.2?}, {}",
+                check.name, status, check.elapsed, detail
+            )?;
+        }
+        Ok(())
+    }
+}
+
+fn check(name: &'static str, f: impl FnOnce() -> Result<String, String>) -> Check {
+    let start = Instant::now();
+    let result = f();
+    Check {
+        name,
+        elapsed: start.elapsed(),
+        result,
+    }
+}
+
+/// The tiny circuit of the self-proof.
+fn probe_circuit() -> (MulCircuit<Fr>, Vec<Fr>) {
+    let (a, b) = (Fr::from(3), Fr::from(5));
+    (MulCircuit::new(a, b), MulCircuit::instance(a * b))
+}
+
+/// Params and proving keys loaded once at start-up.
+pub struct Service {
+    params: ParamsKZG<Bn256>,
+    keys: Vec<(&'static str, ProvingKey<G1Affine>)>,
+    /// Params and key of the self-proof, set by `warm_up`.
+    probe: Option<(ParamsKZG<Bn256>, ProvingKey<G1Affine>)>,
+    /// Where `add_key` caches verifying keys across restarts.
+    store: Option<ArtifactStore>,
+}
+
+impl Service {
+    pub fn new(params: ParamsKZG<Bn256>) -> Self {
+        Self {
+            params,
+            keys: vec![],
+            probe: None,
+            store: None,
+        }
+    }
+
+    /// Caches the keys of `add_key` in `store`, so a restart skips `keygen_vk`.
+    pub fn with_store(mut self, store: ArtifactStore) -> Self {
+        self.store = Some(store);
+        self
+    }
+
+    pub fn params(&self) -> &ParamsKZG<Bn256> {
+        &self.params
+    }
+
+    pub fn metrics(&self) -> &'static Metrics {
+        metrics::global()
+    }
+
+    pub fn key(&self, name: &str) -> Option<&ProvingKey<G1Affine>> {
+        self.keys
+            .iter()
+            .find(|(key_name, _)| *key_name == name)
+            .map(|(_, pk)| pk)
+    }
+
+    /// Generates and holds the proving key of `circuit` under the service's params, starting
+    /// from the vk in the service's store if it has one for the circuit's current source.
+    pub fn add_key<C: CliCircuit>(&mut self, circuit: &C) -> Result<(), PlaygroundError> {
+        let name = C::NAME;
+        let pk = self.metrics().time(Phase::Keygen, || match &self.store {
+            Some(store) => {
+                let source = source_hash(C::SOURCES);
+                cached_proving_key(store, name, &source, &self.params, circuit)
+            }
+            None => keygen_vk(&self.params, circuit)
+                .and_then(|vk| keygen_pk(&self.params, vk, circuit))
+                .map_err(PlaygroundError::Keygen),
+        })?;
+        self.keys.push((name, pk));
+        Ok(())
+    }
+
+    /// Prepares the self-proof over the service's own params, downsized to its circuit.
+    pub fn warm_up(&mut self) -> Result<(), PlaygroundError> {
+        let (circuit, _) = probe_circuit();
+        let params = downsize(&self.params, fit_k(&circuit)?)
+            .map_err(|e| PlaygroundError::Setup(e.to_string()))?;
+        let pk = self.metrics().time(Phase::Keygen, || {
+            keygen_vk(&params, &circuit).and_then(|vk| keygen_pk(&params, vk, &circuit))
+        });
+        self.probe = Some((params, pk.map_err(PlaygroundError::Keygen)?));
+        Ok(())
+    }
+
+    pub fn readiness(&self) -> Readiness {
+        let params = check("params", || Ok(format!("k = {}", self.params.k())));
+        let keys = check("keys", || {
+            if self.keys.is_empty() {
+                return Err("no proving keys cached".to_string());
+            }
+            for (name, pk) in self.keys.iter() {
+                let k = pk.get_vk().domain.k();
+                if k > self.params.k() {
+                    return Err(format!(
+                        "{} needs k = {}, the params have {}",
+                        name,
+                        k,
+                        self.params.k()
+                    ));
+                }
+            }
+            let names: Vec<&str> = self.keys.iter().map(|(name, _)| *name).collect();
+            Ok(names.join(", "))
+        });
+        let proof = check("self-proof", || {
+            let (params, pk) = self.probe.as_ref().ok_or("not warmed up")?;
+            let (circuit, instance) = probe_circuit();
+            let proof = run_proof(
+                params,
+                pk,
+                circuit,
+                &[&instance],
+                OsEntropy,
+                ProofOptions::default(),
+            )
+            .map_err(|e| e.to_string())?;
+            check_proof(
+                params,
+                pk.get_vk(),
+                &[&instance],
+                &proof,
+                ProofOptions::default(),
+            )
+            .into_result()
+            .map_err(|report| format!("verify_proof: {}", report))?;
+            Ok(format!("{} bytes verified", proof.len()))
+        });
+        Readiness {
+            checks: vec![params, keys, proof],
+        }
+    }
+}
+
+const JSON: &str = "application/json";
+const PROMETHEUS: &str = "text/plain; version=0.0.4";
+
+/// Status line, content type and body for a request line such as `GET /ready HTTP/1.1`. A
+/// readiness report younger than `max_age` is reused, so frequent probes don't keep the
+/// prover busy; probes arriving while one is being made wait for it.
+fn respond(
+    service: &Service,
+    request: &str,
+    last: &Mutex<Option<(Instant, Readiness)>>,
+    max_age: Duration,
+) -> (&'static str, &'static str, String) {
+    let mut parts = request.split_whitespace();
+    match (parts.next(), parts.next()) {
+        (Some("GET"), Some("/live")) => ("200 OK", JSON, "{\"live\":true}".to_string()),
+        (Some("GET"), Some("/metrics")) => ("200 OK", PROMETHEUS, service.metrics().render()),
+        (Some("GET"), Some("/ready")) => {
+            let mut last = last.lock().unwrap_or_else(PoisonError::into_inner);
+            let fresh = matches!(&*last, Some((at, _)) if at.elapsed() < max_age);
+            if !fresh {
+                *last = Some((Instant::now(), service.readiness()));
+            }
+            let (_, readiness) = last.as_ref().expect("just set");
+            let status = if readiness.is_ready() {
+                "200 OK"
+            } else {
+                "503 Service Unavailable"
+            };
+            (status, JSON, readiness.to_json())
+        }
+        (Some("GET"), _) => (
+            "404 Not Found",
+            JSON,
+            "{\"error\":\"not found\"}".to_string(),
+        ),
+        _ => (
+            "405 Method Not Allowed",
+            JSON,
+            "{\"error\":\"only GET\"}".to_string(),
+        ),
+    }
+}
+
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Reads up to the blank line ending the headers, which are ignored.
+fn skip_headers(reader: &mut impl BufRead) -> io::Result<()> {
+    let mut line = String::new();
+    loop {
+        line.clear();
+        if reader.read_line(&mut line)? == 0 || line.trim_end().is_empty() {
+            return Ok(());
+        }
+    }
+}
+
+/// Answers one connection, dropping it when the client hangs up or stalls.
+fn answer(
+    mut stream: TcpStream,
+    service: &Service,
+    last: &Mutex<Option<(Instant, Readiness)>>,
+    max_age: Duration,
+) {
+    if stream.set_read_timeout(Some(REQUEST_TIMEOUT)).is_err() {
+        return;
+    }
+    let mut reader = BufReader::new(&stream);
+    let mut request = String::new();
+    if reader.read_line(&mut request).is_err() || skip_headers(&mut reader).is_err() {
+        return;
+    }
+    let (status, content_type, body) = respond(service, &request, last, max_age);
+    let _ = write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    );
+}
+
+/// Answers `GET /ready` (200 when ready, 503 otherwise), `GET /live` and `GET /metrics` on
+/// `listener`, forever. Accepted connections wait in a queue for one of `workers` threads, so
+/// a liveness probe isn't held up behind a self-proof, and `playground_queue_depth` counts
+/// the connections queued or being answered. A failed accept, such as running out of file
+/// descriptors, is logged and the next one tried.
+pub fn serve(listener: TcpListener, service: &Service, max_age: Duration, workers: usize) {
+    let last = Mutex::new(None);
+    let (jobs, queue) = mpsc::channel::<(TcpStream, Queued<'static>)>();
+    let queue = Mutex::new(queue);
+    thread::scope(|scope| {
+        for _ in 0..workers.max(1) {
+            scope.spawn(|| loop {
+                let job = queue.lock().unwrap_or_else(PoisonError::into_inner).recv();
+                match job {
+                    Ok((stream, _queued)) => answer(stream, service, &last, max_age),
+                    Err(mpsc::RecvError) => return,
+                }
+            });
+        }
+        for stream in listener.incoming() {
+            match stream {
+                Ok(stream) => {
+                    let _ = jobs.send((stream, service.metrics().queued()));
+                }
+                Err(err) => eprintln!("warning: accept: {}", err),
+            }
+        }
+        drop(jobs);
+    });
+}
@@ -0,0 +1,88 @@
+//! Off-circuit mirrors of the byte and word gadgets in `chips`, used to compute witnesses and
+//! expected outputs.
+
+use halo2_gadgets::poseidon::primitives::Spec;
+use halo2_proofs::arithmetic::{Field, FieldExt};
+use halo2_proofs::halo2curves::group::ff::PrimeField;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Endianness {
+    Little,
+    Big,
+}
+
+impl Endianness {
+    /// Reorders little-endian limbs into this byte order, or back.
+    pub fn arrange<T>(self, mut limbs: Vec<T>) -> Vec<T> {
+        if self == Endianness::Big {
+            limbs.reverse();
+        }
+        limbs
+    }
+}
+
+/// The lowest `n_bytes` bytes of `value`.
+pub fn field_to_bytes<F: FieldExt>(value: &F, n_bytes: usize, endianness: Endianness) -> Vec<u8> {
+    let repr = value.to_repr();
+    endianness.arrange(repr.as_ref()[..n_bytes].to_vec())
+}
+
+pub fn bytes_to_field<F: FieldExt>(bytes: &[u8], endianness: Endianness) -> F {
+    endianness
+        .arrange(bytes.to_vec())
+        .iter()
+        .rev()
+        .fold(F::zero(), |acc, byte| {
+            acc * F::from(256) + F::from(*byte as u64)
+        })
+}
+
+pub fn bytes_to_words(bytes: &[u8], word_bytes: usize, endianness: Endianness) -> Vec<u64> {
+    assert!(word_bytes <= 8 && bytes.len() % word_bytes == 0);
+    bytes
+        .chunks(word_bytes)
+        .map(|chunk| {
+            endianness
+                .arrange(chunk.to_vec())
+                .iter()
+                .rev()
+                .fold(0u64, |acc, byte| (acc << 8) | *byte as u64)
+        })
+        .collect()
+}
+
+pub fn words_to_bytes(words: &[u64], word_bytes: usize, endianness: Endianness) -> Vec<u8> {
+    assert!(word_bytes <= 8);
+    words
+        .iter()
+        .flat_map(|word| endianness.arrange(word.to_le_bytes()[..word_bytes].to_vec()))
+        .collect()
+}
+
+/// The Poseidon permutation, written out from `S`'s round constants and MDS matrix.
+pub fn poseidon_permute<F: FieldExt, S: Spec<F, T, RATE>, const T: usize, const RATE: usize>(
+    state: &mut [F; T],
+) {
+    let (round_constants, mds, _) = S::constants();
+    let half_full_rounds = S::full_rounds() / 2;
+    let partial_rounds = S::partial_rounds();
+    for (round, rcs) in round_constants.iter().enumerate() {
+        for (word, rc) in state.iter_mut().zip(rcs.iter()) {
+            *word += rc;
+        }
+        if round < half_full_rounds || round >= half_full_rounds + partial_rounds {
+            for word in state.iter_mut() {
+                *word = S::sbox(*word);
+            }
+        } else {
+            state[0] = S::sbox(state[0]);
+        }
+        let words = *state;
+        for (word, row) in state.iter_mut().zip(mds.iter()) {
+            *word = row
+                .iter()
+                .zip(words.iter())
+                .fold(F::zero(), |acc, (m, w)| acc + *m * w);
+        }
+    }
+}
@@ -0,0 +1,272 @@
+//! Cost summary of a circuit: its shape, the rows it needs, and estimates of the proof size
+//! and the prover's memory. Binaries print it when run with `--report`, and tests hold
+//! gadgets to a [`CostBudget`].
+
+use std::fmt;
+
+use halo2_proofs::{
+    halo2curves::bn256::{Fr, G1Affine},
+    plonk::{keygen_vk, Circuit, VerifyingKey},
+};
+
+use crate::{
+    audit::fit_k, dev::Assignments, error::PlaygroundError, evm::proof_points,
+    limits::estimate_prover_memory, prover::MultiOpen, storage::cached_params,
+};
+
+/// Bytes of a compressed point or a scalar in the transcript.
+const WORD_BYTES: usize = 32;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CostReport {
+    pub k: u32,
+    pub advice_columns: usize,
+    pub fixed_columns: usize,
+    pub instance_columns: usize,
+    pub selectors: usize,
+    pub gates: usize,
+    pub lookups: usize,
+    pub permutation_columns: usize,
+    pub degree: usize,
+    pub blinding_factors: usize,
+    /// Rows the layout occupies; `None` when only the verifying key was measured.
+    pub rows_used: Option<usize>,
+    /// SHPLONK over Blake2b.
+    pub proof_bytes: usize,
+    pub prover_memory: u64,
+}
+
+/// Evaluations the prover writes: one per advice and fixed query, the permutation's sigma
+/// and product evaluations, five per lookup and the random polynomial's.
+fn evaluations(vk: &VerifyingKey<G1Affine>) -> usize {
+    let cs = &vk.cs;
+    let chunk_len = cs.degree() - 2;
+    let permutation_columns = cs.permutation().get_columns().len();
+    let chunks = (permutation_columns + chunk_len - 1) / chunk_len;
+    // every product is opened at x and ωx, all but the last also at the last usable row
+    let products = (3 * chunks).saturating_sub(1);
+    cs.advice_queries().len()
+        + cs.fixed_queries().len()
+        + permutation_columns
+        + products
+        + 5 * cs.lookups().len()
+        + 1
+}
+
+impl CostReport {
+    pub fn from_vk(vk: &VerifyingKey<G1Affine>) -> Self {
+        let cs = &vk.cs;
+        CostReport {
+            k: vk.domain.k(),
+            advice_columns: cs.num_advice_columns(),
+            fixed_columns: cs.num_fixed_columns(),
+            instance_columns: cs.num_instance_columns(),
+            selectors: cs.num_selectors(),
+            gates: cs.gates().len(),
+            lookups: cs.lookups().len(),
+            permutation_columns: cs.permutation().get_columns().len(),
+            degree: cs.degree(),
+            blinding_factors: cs.blinding_factors(),
+            rows_used: None,
+            proof_bytes: WORD_BYTES * (proof_points(vk, MultiOpen::Shplonk) + evaluations(vk)),
+            prover_memory: estimate_prover_memory(vk),
+        }
+    }
+
+    /// Reports on `circuit` at the smallest `k` it fits in.
+    pub fn measure<C: Circuit<Fr>>(circuit: &C) -> Result<Self, PlaygroundError> {
+        let k = fit_k(circuit)?;
+        let params = cached_params(k)?;
+        let vk = keygen_vk(&params, circuit).map_err(PlaygroundError::Keygen)?;
+        let instances = vec![vec![]; vk.cs.num_instance_columns()];
+        Ok(CostReport {
+            rows_used: Assignments::synthesize(k, circuit, instances)
+                .ok()
+                .map(|assignments| assignments.used_rows()),
+            ..Self::from_vk(&vk)
+        })
+    }
+
+    pub fn usable_rows(&self) -> usize {
+        (1 << self.k) - (self.blinding_factors + 1)
+    }
+
+    /// Layout advice for gadget authors, from the numbers alone.
+    pub fn hints(&self) -> Vec<String> {
+        let mut hints = vec![];
+        if let Some(used) = self.rows_used {
+            let half =
+                (1usize << self.k.saturating_sub(1)).saturating_sub(self.blinding_factors + 1);
+            if self.k > 1 && used > half && used - half <= self.usable_rows() / 8 {
+                hints.push(format!(
+                    "only {} rows over what k = {} allows: trimming them halves the prover's work",
+                    used - half,
+                    self.k - 1
+                ));
+            }
+        }
+        let chunk = self.degree.saturating_sub(2).max(1);
+        let chunks = (self.permutation_columns + chunk - 1) / chunk;
+        if chunks > 1 {
+            hints.push(format!(
+                "the permutation spans {} columns in {} products of {}; operations laid out in \
+                 one region, chained by rotations instead of copies, need fewer of them",
+                self.permutation_columns, chunks, chunk
+            ));
+        }
+        hints
+    }
+}
+
+impl fmt::Display for CostReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "  columns: {} advice, {} fixed, {} instance, {} selectors",
+            self.advice_columns, self.fixed_columns, self.instance_columns, self.selectors
+        )?;
+        writeln!(
+            f,
+            "  constraints: {} gates, {} lookups, {} permutation columns, degree {}",
+            self.gates, self.lookups, self.permutation_columns, self.degree
+        )?;
+        let used = self
+            .rows_used
+            .map(|used| format!("{} used, ", used))
+            .unwrap_or_default();
+        writeln!(
+            f,
+            "  rows: k = {}, {}{} usable of {}",
+            self.k,
+            used,
+            self.usable_rows(),
+            1u64 << self.k
+        )?;
+        write!(
+            f,
+            "  estimates: proof ~{} bytes, prover memory ~{} MiB",
+            self.proof_bytes,
+            self.prover_memory >> 20
+        )?;
+        for hint in self.hints() {
+            write!(f, "\n  hint: {}", hint)?;
+        }
+        Ok(())
+    }
+}
+
+/// Upper bounds on what a circuit may cost, so a gadget that grows a row or a column fails
+/// its test instead of going unnoticed. Only the bounds that are set are checked.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CostBudget {
+    rows: Option<usize>,
+    advice_columns: Option<usize>,
+    fixed_columns: Option<usize>,
+    lookups: Option<usize>,
+    degree: Option<usize>,
+}
+
+/// A cost over its budget.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Overrun {
+    pub cost: &'static str,
+    pub limit: usize,
+    pub actual: usize,
+}
+
+impl fmt::Display for Overrun {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}: {} over the budget of {}",
+            self.cost, self.actual, self.limit
+        )
+    }
+}
+
+impl CostBudget {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn rows(mut self, max: usize) -> Self {
+        self.rows = Some(max);
+        self
+    }
+
+    pub fn advice_columns(mut self, max: usize) -> Self {
+        self.advice_columns = Some(max);
+        self
+    }
+
+    pub fn fixed_columns(mut self, max: usize) -> Self {
+        self.fixed_columns = Some(max);
+        self
+    }
+
+    pub fn lookups(mut self, max: usize) -> Self {
+        self.lookups = Some(max);
+        self
+    }
+
+    pub fn degree(mut self, max: usize) -> Self {
+        self.degree = Some(max);
+        self
+    }
+
+    /// The costs of `report` over budget. Rows that weren't measured count as over any
+    /// bound on them.
+    pub fn overruns(&self, report: &CostReport) -> Vec<Overrun> {
+        [
+            ("rows", self.rows, report.rows_used.unwrap_or(usize::MAX)),
+            ("advice columns", self.advice_columns, report.advice_columns),
+            ("fixed columns", self.fixed_columns, report.fixed_columns),
+            ("lookups", self.lookups, report.lookups),
+            ("degree", self.degree, report.degree),
+        ]
+        .into_iter()
+        .filter_map(|(cost, limit, actual)| {
+            limit.filter(|limit| actual > *limit).map(|limit| Overrun {
+                cost,
+                limit,
+                actual,
+            })
+        })
+        .collect()
+    }
+
+    /// Measures `circuit` by synthesizing it, returning its report if it is within budget and
+    /// the overruns in a [`PlaygroundError::Other`] if not.
+    pub fn check<C: Circuit<Fr>>(&self, circuit: &C) -> Result<CostReport, PlaygroundError> {
+        let report = CostReport::measure(circuit)?;
+        let overruns = self.overruns(&report);
+        if overruns.is_empty() {
+            return Ok(report);
+        }
+        Err(PlaygroundError::Other(
+            overruns
+                .iter()
+                .map(Overrun::to_string)
+                .collect::<Vec<_>>()
+                .join(", "),
+        ))
+    }
+
+    /// Panics, naming `name`, unless `circuit` is within budget.
+    pub fn assert<C: Circuit<Fr>>(&self, name: &str, circuit: &C) {
+        if let Err(err) = self.check(circuit) {
+            panic!("{}: {}", name, err);
+        }
+    }
+}
+
+/// Prints the report of `circuit` when the binary was run with `--report`.
+pub fn report_if_requested<C: Circuit<Fr>>(name: &str, circuit: &C) {
+    if !std::env::args().skip(1).any(|arg| arg == "--report") {
+        return;
+    }
+    match CostReport::measure(circuit) {
+        Ok(report) => println!("{} cost report:\n{}", name, report),
+        Err(err) => eprintln!("{} cost report: {}", name, err),
+    }
+}
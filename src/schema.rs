@@ -0,0 +1,579 @@
+//! Machine- and human-readable descriptions of each circuit's public inputs, declared next to
+//! the circuit so the interface handed to verifier integrators never drifts from the code, and
+//! of the private inputs its witness files hold, which are validated against it.
+
+use std::fmt;
+
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    plonk::{Circuit, ConstraintSystem},
+};
+
+use crate::{
+    audit_log::json_string,
+    encoding::{parse_field, Reduction},
+    json::Json,
+};
+
+/// How an instance value is interpreted. Every value is a field element on the wire; this is
+/// the subset of field elements the circuit accepts there.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Encoding {
+    /// Any element of the scalar field.
+    Field,
+    /// Always zero, e.g. a row the circuit leaves unused.
+    Zero,
+    /// An unsigned integer of at most `bits` bits.
+    Integer { bits: u32 },
+}
+
+impl fmt::Display for Encoding {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Encoding::Field => write!(f, "field"),
+            Encoding::Zero => write!(f, "zero"),
+            Encoding::Integer { bits } => write!(f, "u{}", bits),
+        }
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct InstanceField {
+    pub name: String,
+    pub column: usize,
+    pub row: usize,
+    pub encoding: Encoding,
+    /// Whether the circuit itself constrains the value to its encoding, rather than leaving
+    /// it to verifiers.
+    pub enforced: bool,
+    pub semantics: String,
+}
+
+/// The public inputs of a circuit, in instance column and row order.
+#[derive(Clone, Debug)]
+pub struct InstanceLayout {
+    pub circuit: String,
+    pub fields: Vec<InstanceField>,
+}
+
+/// How values are written in `.instances` files.
+pub const FILE_FORMAT: &str =
+    "one line per instance column, rows as whitespace-separated 0x-prefixed big-endian hex";
+
+impl InstanceLayout {
+    pub fn new(circuit: impl Into<String>) -> Self {
+        Self {
+            circuit: circuit.into(),
+            fields: vec![],
+        }
+    }
+
+    /// Appends a field at the next row of `column`.
+    pub fn field(
+        self,
+        column: usize,
+        name: impl Into<String>,
+        encoding: Encoding,
+        semantics: impl Into<String>,
+    ) -> Self {
+        self.push(column, name.into(), encoding, false, semantics.into())
+    }
+
+    /// Like `field`, for a value the circuit range checks to its encoding.
+    pub fn enforced_field(
+        self,
+        column: usize,
+        name: impl Into<String>,
+        encoding: Encoding,
+        semantics: impl Into<String>,
+    ) -> Self {
+        self.push(column, name.into(), encoding, true, semantics.into())
+    }
+
+    /// Like `field`, at an absolute `row` of `column`; rows it skips are left zero.
+    pub fn field_at(
+        self,
+        column: usize,
+        row: usize,
+        name: impl Into<String>,
+        encoding: Encoding,
+        semantics: impl Into<String>,
+    ) -> Self {
+        self.insert(column, row, name.into(), encoding, false, semantics.into())
+    }
+
+    fn push(
+        self,
+        column: usize,
+        name: String,
+        encoding: Encoding,
+        enforced: bool,
+        semantics: String,
+    ) -> Self {
+        let row = self
+            .fields
+            .iter()
+            .filter(|f| f.column == column)
+            .map(|f| f.row + 1)
+            .max()
+            .unwrap_or(0);
+        self.insert(column, row, name, encoding, enforced, semantics)
+    }
+
+    fn insert(
+        mut self,
+        column: usize,
+        row: usize,
+        name: String,
+        encoding: Encoding,
+        enforced: bool,
+        semantics: String,
+    ) -> Self {
+        self.fields.push(InstanceField {
+            name,
+            column,
+            row,
+            encoding,
+            enforced,
+            semantics,
+        });
+        self.fields.sort_by_key(|f| (f.column, f.row));
+        self
+    }
+
+    /// Checks the integer fields of instance columns before verifying, so a value that doesn't
+    /// fit its width is reported by name whether or not the circuit enforces it. Missing rows
+    /// are left to the verifier's shape check.
+    pub fn check_ranges<F: FieldExt>(&self, columns: &[Vec<F>]) -> Result<(), OutOfRange> {
+        let integers = self
+            .fields
+            .iter()
+            .filter(|f| matches!(f.encoding, Encoding::Integer { .. }));
+        for f in integers {
+            let value = columns.get(f.column).and_then(|column| column.get(f.row));
+            if matches!(value, Some(value) if !f.encoding.contains(value)) {
+                return Err(OutOfRange {
+                    name: f.name.clone(),
+                    column: f.column,
+                    row: f.row,
+                    encoding: f.encoding,
+                });
+            }
+        }
+        Ok(())
+    }
+
+    pub fn num_columns(&self) -> usize {
+        self.fields.iter().map(|f| f.column + 1).max().unwrap_or(0)
+    }
+
+    pub fn to_json(&self) -> String {
+        let fields: Vec<String> = self
+            .fields
+            .iter()
+            .map(|f| {
+                format!(
+                    "{{\"name\":{},\"column\":{},\"row\":{},\"encoding\":{},\"enforced\":{},\"semantics\":{}}}",
+                    json_string(&f.name),
+                    f.column,
+                    f.row,
+                    json_string(&f.encoding.to_string()),
+                    f.enforced,
+                    json_string(&f.semantics)
+                )
+            })
+            .collect();
+        format!(
+            "{{\"circuit\":{},\"format\":{},\"columns\":{},\"fields\":[{}]}}",
+            json_string(&self.circuit),
+            json_string(FILE_FORMAT),
+            self.num_columns(),
+            fields.join(",")
+        )
+    }
+
+    /// A Markdown table.
+    pub fn to_table(&self) -> String {
+        let mut out = format!(
+            "Public inputs of `{}` ({}).\n\n| column | row | name | encoding | meaning |\n|---|---|---|---|---|\n",
+            self.circuit, FILE_FORMAT
+        );
+        for f in self.fields.iter() {
+            out.push_str(&format!(
+                "| {} | {} | `{}` | {}{} | {} |\n",
+                f.column,
+                f.row,
+                f.name,
+                f.encoding,
+                if f.enforced { " (in circuit)" } else { "" },
+                f.semantics
+            ));
+        }
+        out
+    }
+}
+
+/// A public input outside the width its layout declares.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OutOfRange {
+    pub name: String,
+    pub column: usize,
+    pub row: usize,
+    pub encoding: Encoding,
+}
+
+impl fmt::Display for OutOfRange {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "public input `{}` (column {}, row {}) is not a {}",
+            self.name, self.column, self.row, self.encoding
+        )
+    }
+}
+
+impl std::error::Error for OutOfRange {}
+
+/// Implemented by circuits next to their definition.
+pub trait DescribeInstances {
+    fn instance_layout() -> InstanceLayout;
+}
+
+/// Fills a circuit's public inputs by name, each at the column and row its [`InstanceLayout`]
+/// declares, so callers never place values by index. `Zero` fields and rows no field declares
+/// are left zero.
+#[derive(Clone, Debug)]
+pub struct InstanceBuilder<F> {
+    layout: InstanceLayout,
+    num_columns: usize,
+    values: Vec<Option<F>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum InstanceError {
+    /// The layout declares a column the circuit doesn't have.
+    Columns {
+        layout: usize,
+        circuit: usize,
+    },
+    Unknown(String),
+    Missing(String),
+    OutOfRange(OutOfRange),
+}
+
+impl fmt::Display for InstanceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            InstanceError::Columns { layout, circuit } => write!(
+                f,
+                "the layout declares {} instance columns, the circuit has {}",
+                layout, circuit
+            ),
+            InstanceError::Unknown(name) => write!(f, "unknown public input `{}`", name),
+            InstanceError::Missing(name) => write!(f, "public input `{}` is not set", name),
+            InstanceError::OutOfRange(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for InstanceError {}
+
+impl<F: FieldExt> InstanceBuilder<F> {
+    /// For `layout`, checked against the instance columns of `cs`.
+    pub fn new(layout: InstanceLayout, cs: &ConstraintSystem<F>) -> Result<Self, InstanceError> {
+        let num_columns = cs.num_instance_columns();
+        if layout.num_columns() > num_columns {
+            return Err(InstanceError::Columns {
+                layout: layout.num_columns(),
+                circuit: num_columns,
+            });
+        }
+        Ok(Self {
+            values: vec![None; layout.fields.len()],
+            layout,
+            num_columns,
+        })
+    }
+
+    pub fn for_circuit<C: Circuit<F> + DescribeInstances>() -> Result<Self, InstanceError> {
+        let mut cs = ConstraintSystem::default();
+        C::configure(&mut cs);
+        Self::new(C::instance_layout(), &cs)
+    }
+
+    pub fn set(mut self, name: &str, value: F) -> Result<Self, InstanceError> {
+        let index = self
+            .layout
+            .fields
+            .iter()
+            .position(|f| f.name == name)
+            .ok_or_else(|| InstanceError::Unknown(name.to_string()))?;
+        let field = &self.layout.fields[index];
+        if !field.encoding.contains(&value) {
+            return Err(InstanceError::OutOfRange(OutOfRange {
+                name: field.name.clone(),
+                column: field.column,
+                row: field.row,
+                encoding: field.encoding,
+            }));
+        }
+        self.values[index] = Some(value);
+        Ok(self)
+    }
+
+    /// One vector per instance column of the circuit, each as long as its last declared row;
+    /// borrow them with [`as_columns`] to prove or verify.
+    pub fn build(&self) -> Result<Vec<Vec<F>>, InstanceError> {
+        let mut columns = vec![vec![]; self.num_columns];
+        for (field, value) in self.layout.fields.iter().zip(self.values.iter()) {
+            let value = match (value, field.encoding) {
+                (Some(value), _) => *value,
+                (None, Encoding::Zero) => F::zero(),
+                (None, _) => return Err(InstanceError::Missing(field.name.clone())),
+            };
+            let column: &mut Vec<F> = &mut columns[field.column];
+            if column.len() <= field.row {
+                column.resize(field.row + 1, F::zero());
+            }
+            column[field.row] = value;
+        }
+        Ok(columns)
+    }
+}
+
+/// The `&[&[F]]` form of instance columns that provers and verifiers take.
+pub fn as_columns<F>(columns: &[Vec<F>]) -> Vec<&[F]> {
+    columns.iter().map(|column| &column[..]).collect()
+}
+
+/// A private input of a circuit, in the order witness files list them.
+#[derive(Clone, Debug)]
+pub struct WitnessField {
+    pub name: String,
+    pub encoding: Encoding,
+    pub semantics: String,
+}
+
+/// The private inputs a circuit builds its witness from. A witness file holds either the values
+/// in field order, separated by whitespace or commas, or a flat JSON object from each field's
+/// name to its value as a decimal or `0x` hex string (see `json_schema`).
+#[derive(Clone, Debug)]
+pub struct WitnessLayout {
+    pub circuit: String,
+    pub fields: Vec<WitnessField>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum WitnessError {
+    /// The file looks like JSON but isn't a flat object of strings.
+    Json(String),
+    Count {
+        expected: usize,
+        found: usize,
+    },
+    Missing(String),
+    Unknown(String),
+    /// A value that doesn't parse, or parses outside its field's encoding.
+    Field {
+        name: String,
+        value: String,
+        expected: Encoding,
+        error: String,
+    },
+}
+
+impl fmt::Display for WitnessError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WitnessError::Json(err) => write!(f, "invalid JSON witness: {}", err),
+            WitnessError::Count { expected, found } => {
+                write!(f, "expected {} witness values, found {}", expected, found)
+            }
+            WitnessError::Missing(name) => write!(f, "witness field `{}` is missing", name),
+            WitnessError::Unknown(name) => write!(f, "unknown witness field `{}`", name),
+            WitnessError::Field {
+                name,
+                value,
+                expected,
+                error,
+            } => write!(
+                f,
+                "witness field `{}` = {:?}: expected {}, {}",
+                name, value, expected, error
+            ),
+        }
+    }
+}
+
+impl std::error::Error for WitnessError {}
+
+impl Encoding {
+    /// Whether `value` is in this encoding's subset of the field.
+    pub fn contains<F: FieldExt>(&self, value: &F) -> bool {
+        match self {
+            Encoding::Field => true,
+            Encoding::Zero => *value == F::zero(),
+            Encoding::Integer { bits } => {
+                let repr = value.to_repr();
+                // little-endian, so every bit from `bits` up must be clear
+                repr.as_ref().iter().enumerate().all(|(i, byte)| {
+                    let low = 8 * i as u32;
+                    match bits.checked_sub(low) {
+                        Some(kept) if kept >= 8 => true,
+                        Some(kept) => byte >> kept == 0,
+                        None => *byte == 0,
+                    }
+                })
+            }
+        }
+    }
+
+    /// Parses a canonical decimal or hex value and checks that it is in range.
+    pub(crate) fn parse<F: FieldExt>(&self, value: &str) -> Result<F, String> {
+        let parsed: F = parse_field(value, Reduction::Reject).map_err(|e| e.to_string())?;
+        if self.contains(&parsed) {
+            Ok(parsed)
+        } else {
+            Err(format!("{} is out of range", value))
+        }
+    }
+}
+
+/// A witness file's JSON form: one object whose values are strings or unsigned integers.
+fn flat_object(text: &str) -> Result<Vec<(String, String)>, String> {
+    let json = Json::parse(text)?;
+    let members = json.as_object().ok_or("expected an object")?;
+    members
+        .iter()
+        .map(|(name, value)| match value.as_scalar() {
+            Some(value) => Ok((name.clone(), value.to_string())),
+            None => Err(format!(
+                "expected a string or integer for `{}`, found {:?}",
+                name, value
+            )),
+        })
+        .collect()
+}
+
+impl WitnessLayout {
+    pub fn new(circuit: impl Into<String>) -> Self {
+        Self {
+            circuit: circuit.into(),
+            fields: vec![],
+        }
+    }
+
+    pub fn field(
+        mut self,
+        name: impl Into<String>,
+        encoding: Encoding,
+        semantics: impl Into<String>,
+    ) -> Self {
+        self.fields.push(WitnessField {
+            name: name.into(),
+            encoding,
+            semantics: semantics.into(),
+        });
+        self
+    }
+
+    fn check<F: FieldExt>(&self, field: &WitnessField, value: &str) -> Result<F, WitnessError> {
+        field
+            .encoding
+            .parse(value)
+            .map_err(|error| WitnessError::Field {
+                name: field.name.clone(),
+                value: value.to_string(),
+                expected: field.encoding,
+                error,
+            })
+    }
+
+    /// Reads a witness file in either format, returning the values in field order.
+    pub fn read<F: FieldExt>(&self, text: &str) -> Result<Vec<F>, WitnessError> {
+        if text.trim_start().starts_with('{') {
+            let entries = flat_object(text).map_err(WitnessError::Json)?;
+            if let Some((name, _)) = entries
+                .iter()
+                .find(|(name, _)| !self.fields.iter().any(|f| &f.name == name))
+            {
+                return Err(WitnessError::Unknown(name.clone()));
+            }
+            return self
+                .fields
+                .iter()
+                .map(|field| {
+                    let (_, value) = entries
+                        .iter()
+                        .find(|(name, _)| name == &field.name)
+                        .ok_or_else(|| WitnessError::Missing(field.name.clone()))?;
+                    self.check(field, value)
+                })
+                .collect();
+        }
+        let values: Vec<&str> = text
+            .lines()
+            .map(|line| line.split('#').next().unwrap_or_default())
+            .flat_map(|line| line.split(|c: char| c == ',' || c.is_whitespace()))
+            .filter(|value| !value.is_empty())
+            .collect();
+        if values.len() != self.fields.len() {
+            return Err(WitnessError::Count {
+                expected: self.fields.len(),
+                found: values.len(),
+            });
+        }
+        self.fields
+            .iter()
+            .zip(values)
+            .map(|(field, value)| self.check(field, value))
+            .collect()
+    }
+
+    /// JSON Schema of the JSON witness format. Ranges beyond "decimal or hex" are only
+    /// described, not encoded, since JSON Schema cannot compare big integers.
+    pub fn json_schema(&self) -> String {
+        let properties: Vec<String> = self
+            .fields
+            .iter()
+            .map(|f| {
+                format!(
+                    "{}:{{\"type\":\"string\",\"pattern\":{},\"description\":{}}}",
+                    json_string(&f.name),
+                    json_string("^(0[xX][0-9a-fA-F_]+|[0-9_]+)$"),
+                    json_string(&format!("{}, {}", f.semantics, f.encoding))
+                )
+            })
+            .collect();
+        let required: Vec<String> = self.fields.iter().map(|f| json_string(&f.name)).collect();
+        format!(
+            "{{\"$schema\":\"https://json-schema.org/draft/2020-12/schema\",\"title\":{},\
+             \"type\":\"object\",\"properties\":{{{}}},\"required\":[{}],\
+             \"additionalProperties\":false}}",
+            json_string(&format!("{} witness", self.circuit)),
+            properties.join(","),
+            required.join(",")
+        )
+    }
+
+    /// A Markdown table.
+    pub fn to_table(&self) -> String {
+        let mut out = format!(
+            "Witness of `{}`: the values in this order, or a JSON object by name.\n\n\
+             | # | name | encoding | meaning |\n|---|---|---|---|\n",
+            self.circuit
+        );
+        for (i, f) in self.fields.iter().enumerate() {
+            out.push_str(&format!(
+                "| {} | `{}` | {} | {} |\n",
+                i, f.name, f.encoding, f.semantics
+            ));
+        }
+        out
+    }
+}
+
+/// Implemented by the circuits the CLI proves, next to their definition.
+pub trait DescribeWitness {
+    fn witness_layout() -> WitnessLayout;
+}
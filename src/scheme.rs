@@ -0,0 +1,147 @@
+//! Keygen, proving and verification generic over the commitment scheme, so one circuit can be
+//! compared across KZG over BN256 and IPA over Pasta. A [`Backend`] names the scheme with the
+//! multiopen prover and verifier to use; the rest of the crate stays on KZG.
+
+use std::time::{Duration, Instant};
+
+use halo2_proofs::{
+    halo2curves::{bn256::Bn256, pasta::EqAffine},
+    plonk::{
+        create_proof, keygen_pk, keygen_vk, verify_proof, Circuit, Error, ProvingKey, VerifyingKey,
+    },
+    poly::{
+        commitment::{CommitmentScheme, ParamsProver, Prover, Verifier},
+        ipa::{
+            commitment::IPACommitmentScheme,
+            multiopen::{ProverIPA, VerifierIPA},
+            strategy::SingleStrategy as IpaSingleStrategy,
+        },
+        kzg::{
+            commitment::KZGCommitmentScheme,
+            multiopen::{ProverSHPLONK, VerifierSHPLONK},
+            strategy::SingleStrategy as KzgSingleStrategy,
+        },
+        VerificationStrategy,
+    },
+    transcript::{
+        Blake2bRead, Blake2bWrite, Challenge255, TranscriptReadBuffer, TranscriptWriterBuffer,
+    },
+};
+
+use crate::entropy::EntropySource;
+
+/// A commitment scheme and the prover, verifier and strategy used with it.
+pub trait Backend<'params> {
+    const NAME: &'static str;
+    type Scheme: CommitmentScheme;
+    type Prover: Prover<'params, Self::Scheme>;
+    type Verifier: Verifier<'params, Self::Scheme>;
+    type Strategy: VerificationStrategy<'params, Self::Scheme, Self::Verifier, Output = ()>;
+}
+
+/// The crate's default: KZG over BN256 with SHPLONK.
+pub struct Kzg;
+
+impl<'params> Backend<'params> for Kzg {
+    const NAME: &'static str = "kzg/bn256";
+    type Scheme = KZGCommitmentScheme<Bn256>;
+    type Prover = ProverSHPLONK<'params, Bn256>;
+    type Verifier = VerifierSHPLONK<'params, Bn256>;
+    type Strategy = KzgSingleStrategy<'params, Bn256>;
+}
+
+/// IPA over Vesta, for circuits over Pallas' base field `Fp`.
+pub struct Ipa;
+
+impl<'params> Backend<'params> for Ipa {
+    const NAME: &'static str = "ipa/pasta";
+    type Scheme = IPACommitmentScheme<EqAffine>;
+    type Prover = ProverIPA<'params, EqAffine>;
+    type Verifier = VerifierIPA<'params, EqAffine>;
+    type Strategy = IpaSingleStrategy<'params, EqAffine>;
+}
+
+type Scalar<'params, B> = <<B as Backend<'params>>::Scheme as CommitmentScheme>::Scalar;
+type Curve<'params, B> = <<B as Backend<'params>>::Scheme as CommitmentScheme>::Curve;
+type ParamsOf<'params, B> = <<B as Backend<'params>>::Scheme as CommitmentScheme>::ParamsProver;
+
+/// A proof over the Blake2b transcript.
+pub fn prove<'params, B, C, R>(
+    params: &'params ParamsOf<'params, B>,
+    pk: &ProvingKey<Curve<'params, B>>,
+    circuit: C,
+    instances: &[&[Scalar<'params, B>]],
+    rng: R,
+) -> Result<Vec<u8>, Error>
+where
+    B: Backend<'params>,
+    C: Circuit<Scalar<'params, B>>,
+    R: EntropySource,
+{
+    let mut transcript = Blake2bWrite::<_, Curve<'params, B>, Challenge255<_>>::init(vec![]);
+    create_proof::<B::Scheme, B::Prover, _, _, _, _>(
+        params,
+        pk,
+        &[circuit],
+        &[instances],
+        rng,
+        &mut transcript,
+    )?;
+    Ok(transcript.finalize())
+}
+
+pub fn verify<'params, B: Backend<'params>>(
+    params: &'params ParamsOf<'params, B>,
+    vk: &VerifyingKey<Curve<'params, B>>,
+    instances: &[&[Scalar<'params, B>]],
+    proof: &[u8],
+) -> Result<(), Error> {
+    let params = params.verifier_params();
+    let mut transcript = Blake2bRead::<_, Curve<'params, B>, Challenge255<_>>::init(proof);
+    verify_proof::<B::Scheme, B::Verifier, _, _, B::Strategy>(
+        params,
+        vk,
+        B::Strategy::new(params),
+        &[instances],
+        &mut transcript,
+    )
+}
+
+#[derive(Clone, Debug)]
+pub struct SchemeRun {
+    pub scheme: &'static str,
+    pub proof_bytes: usize,
+    pub keygen: Duration,
+    pub prove: Duration,
+    pub verify: Duration,
+}
+
+/// Generates the keys of `circuit` under `params`, then proves and verifies it, timing each.
+pub fn prove_and_verify<'params, B, C, R>(
+    params: &'params ParamsOf<'params, B>,
+    circuit: C,
+    instances: &[&[Scalar<'params, B>]],
+    rng: R,
+) -> Result<SchemeRun, Error>
+where
+    B: Backend<'params>,
+    C: Circuit<Scalar<'params, B>>,
+    R: EntropySource,
+{
+    let start = Instant::now();
+    let vk = keygen_vk(params, &circuit)?;
+    let pk = keygen_pk(params, vk, &circuit)?;
+    let keygen = start.elapsed();
+    let start = Instant::now();
+    let proof = prove::<B, _, _>(params, &pk, circuit, instances, rng)?;
+    let prove = start.elapsed();
+    let start = Instant::now();
+    verify::<B>(params, pk.get_vk(), instances, &proof)?;
+    Ok(SchemeRun {
+        scheme: B::NAME,
+        proof_bytes: proof.len(),
+        keygen,
+        prove,
+        verify: start.elapsed(),
+    })
+}
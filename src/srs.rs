@@ -0,0 +1,401 @@
+//! Loading a production SRS in place of the playground's known toxic waste.
+//!
+//! Two formats are read: snarkjs `.ptau` files (Powers of Tau and Perpetual Powers of Tau
+//! ceremonies), and the hermez raw files, which are already in halo2's params format but may
+//! hold more powers than needed. Both are checked with pairings before being returned.
+//!
+//! Every [`cached_params`](crate::storage::cached_params) call goes through the
+//! [`SrsProvider`] a binary was run with, `--srs <provider>` or [`SRS_VAR`]: `unsafe[:<secret>]`,
+//! `random` or a file path. Without one, debug builds warn and use the playground secret;
+//! release builds refuse, so nothing is benchmarked or shipped on it by accident.
+
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufReader, Read, Seek, SeekFrom},
+    path::{Path, PathBuf},
+    sync::{Mutex, Once},
+};
+
+use halo2_proofs::{
+    arithmetic::{g_to_lagrange, CurveAffine, Field, FieldExt},
+    halo2curves::{
+        bn256::{Bn256, Fq, Fq2, Fr, G1Affine, G2Affine},
+        group::{ff::PrimeField, prime::PrimeCurveAffine, GroupEncoding},
+        pairing::Engine,
+    },
+    poly::{
+        commitment::{Params, ParamsProver},
+        kzg::commitment::ParamsKZG,
+    },
+};
+
+use crate::{entropy::OsEntropy, storage::read_params, GOD_PRIVATE_KEY};
+
+const PTAU_MAGIC: &[u8; 4] = b"ptau";
+const PTAU_HEADER: u32 = 1;
+const PTAU_TAU_G1: u32 = 2;
+const PTAU_TAU_G2: u32 = 3;
+const FQ_BYTES: usize = 32;
+
+/// Consecutive powers checked by [`check_srs`] when loading.
+pub const CHECKED_POWERS: usize = 8;
+
+#[derive(Debug)]
+pub enum SrsError {
+    Io(io::Error),
+    Format(String),
+    /// The file has fewer than `2^k` powers.
+    TooSmall {
+        k: u32,
+        max_k: u32,
+    },
+    /// The powers aren't powers of a single tau.
+    Inconsistent(String),
+}
+
+impl fmt::Display for SrsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SrsError::Io(err) => write!(f, "{}", err),
+            SrsError::Format(err) => write!(f, "malformed SRS: {}", err),
+            SrsError::TooSmall { k, max_k } => {
+                write!(
+                    f,
+                    "k = {} requested, the SRS only supports k <= {}",
+                    k, max_k
+                )
+            }
+            SrsError::Inconsistent(err) => write!(f, "inconsistent SRS: {}", err),
+        }
+    }
+}
+
+impl std::error::Error for SrsError {}
+
+impl From<io::Error> for SrsError {
+    fn from(err: io::Error) -> Self {
+        SrsError::Io(err)
+    }
+}
+
+fn format_error(message: impl Into<String>) -> SrsError {
+    SrsError::Format(message.into())
+}
+
+/// Checks that `params` starts from the generators and that its first `powers` G1 points are
+/// consecutive powers of the same tau as `s_g2`: `e(g[i], g2) == e(g[i - 1], s_g2)`.
+pub fn check_srs(params: &ParamsKZG<Bn256>, powers: usize) -> Result<(), SrsError> {
+    let g = params.get_g();
+    if g[0] != G1Affine::generator() {
+        return Err(SrsError::Inconsistent(
+            "g[0] isn't the G1 generator".to_string(),
+        ));
+    }
+    if params.g2() != G2Affine::generator() {
+        return Err(SrsError::Inconsistent(
+            "g2 isn't the G2 generator".to_string(),
+        ));
+    }
+    let s_g2 = params.s_g2();
+    for i in 1..=powers.min(g.len() - 1) {
+        if Bn256::pairing(&g[i], &params.g2()) != Bn256::pairing(&g[i - 1], &s_g2) {
+            return Err(SrsError::Inconsistent(format!(
+                "g[{}] isn't tau times g[{}]",
+                i,
+                i - 1
+            )));
+        }
+    }
+    Ok(())
+}
+
+/// Builds params from the first `2^k` powers of tau in G1 and `[1]_2, [tau]_2`, going through
+/// halo2's own serialization since the params fields aren't public.
+fn params_from_powers(
+    k: u32,
+    g: &[G1Affine],
+    g2: G2Affine,
+    s_g2: G2Affine,
+) -> Result<ParamsKZG<Bn256>, SrsError> {
+    let n = 1usize << k;
+    let g_lagrange = g_to_lagrange(g[..n].iter().map(|p| p.to_curve()).collect(), k);
+    let mut bytes = Vec::with_capacity(4 + 2 * n * FQ_BYTES + 4 * FQ_BYTES);
+    bytes.extend_from_slice(&k.to_le_bytes());
+    for point in g[..n].iter().chain(g_lagrange.iter()) {
+        bytes.extend_from_slice(point.to_bytes().as_ref());
+    }
+    bytes.extend_from_slice(g2.to_bytes().as_ref());
+    bytes.extend_from_slice(s_g2.to_bytes().as_ref());
+    Ok(ParamsKZG::<Bn256>::read(&mut &bytes[..])?)
+}
+
+/// Identifies an SRS and its size: Blake2b over `k`, `[s]_1`, `[1]_2` and `[s]_2`. Two sizes
+/// cut from one ceremony differ only in `k`.
+pub fn srs_digest(params: &ParamsKZG<Bn256>) -> [u8; 32] {
+    let mut state = blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(b"Srs-Digest")
+        .to_state();
+    state.update(&params.k().to_le_bytes());
+    state.update(params.get_g()[1].to_bytes().as_ref());
+    state.update(params.g2().to_bytes().as_ref());
+    state.update(params.s_g2().to_bytes().as_ref());
+    let mut digest = [0; 32];
+    digest.copy_from_slice(state.finalize().as_bytes());
+    digest
+}
+
+/// The first `2^k` powers of `params`, which must be at least that large.
+pub fn downsize(params: &ParamsKZG<Bn256>, k: u32) -> Result<ParamsKZG<Bn256>, SrsError> {
+    if params.k() < k {
+        return Err(SrsError::TooSmall {
+            k,
+            max_k: params.k(),
+        });
+    }
+    params_from_powers(k, &params.get_g(), params.g2(), params.s_g2())
+}
+
+/// Reads a hermez raw SRS file and downsizes it to `k`.
+pub fn read_hermez(path: &Path, k: u32) -> Result<ParamsKZG<Bn256>, SrsError> {
+    let params = read_params(path)?;
+    let params = if params.k() == k {
+        params
+    } else {
+        downsize(&params, k)?
+    };
+    check_srs(&params, CHECKED_POWERS)?;
+    Ok(params)
+}
+
+fn read_u32(reader: &mut impl Read) -> io::Result<u32> {
+    let mut bytes = [0; 4];
+    reader.read_exact(&mut bytes)?;
+    Ok(u32::from_le_bytes(bytes))
+}
+
+fn read_u64(reader: &mut impl Read) -> io::Result<u64> {
+    let mut bytes = [0; 8];
+    reader.read_exact(&mut bytes)?;
+    Ok(u64::from_le_bytes(bytes))
+}
+
+/// ptau stores field elements little-endian in Montgomery form, `a * 2^256 mod q`.
+fn read_fq(reader: &mut impl Read) -> Result<Fq, SrsError> {
+    let mut repr = <Fq as PrimeField>::Repr::default();
+    reader.read_exact(repr.as_mut())?;
+    let montgomery = Option::<Fq>::from(Fq::from_repr(repr))
+        .ok_or_else(|| format_error("coordinate isn't below the modulus"))?;
+    let r_inv = Fq::from(2).pow_vartime(&[256]).invert().unwrap();
+    Ok(montgomery * r_inv)
+}
+
+fn read_g1(reader: &mut impl Read) -> Result<G1Affine, SrsError> {
+    let x = read_fq(reader)?;
+    let y = read_fq(reader)?;
+    Option::from(G1Affine::from_xy(x, y)).ok_or_else(|| format_error("G1 point not on the curve"))
+}
+
+fn read_g2(reader: &mut impl Read) -> Result<G2Affine, SrsError> {
+    let x = Fq2 {
+        c0: read_fq(reader)?,
+        c1: read_fq(reader)?,
+    };
+    let y = Fq2 {
+        c0: read_fq(reader)?,
+        c1: read_fq(reader)?,
+    };
+    let point = G2Affine { x, y };
+    if !bool::from(point.is_on_curve()) {
+        return Err(format_error("G2 point not on the curve"));
+    }
+    Ok(point)
+}
+
+fn seek_section(
+    reader: &mut BufReader<File>,
+    offsets: &[Option<u64>],
+    kind: u32,
+) -> Result<(), SrsError> {
+    let start =
+        offsets[kind as usize].ok_or_else(|| format_error(format!("missing section {}", kind)))?;
+    reader.seek(SeekFrom::Start(start))?;
+    Ok(())
+}
+
+/// Reads the first `2^k` powers of a snarkjs `.ptau` file.
+pub fn read_ptau(path: &Path, k: u32) -> Result<ParamsKZG<Bn256>, SrsError> {
+    let mut reader = BufReader::new(File::open(path)?);
+    let mut magic = [0; 4];
+    reader.read_exact(&mut magic)?;
+    if &magic != PTAU_MAGIC {
+        return Err(format_error("not a ptau file"));
+    }
+    let _version = read_u32(&mut reader)?;
+    let sections = read_u32(&mut reader)?;
+
+    // sections may come in any order, so note where each one starts first
+    let mut offsets = [None; 4];
+    for _ in 0..sections {
+        let kind = read_u32(&mut reader)?;
+        let size = read_u64(&mut reader)?;
+        let start = reader.stream_position()?;
+        if let Some(offset) = offsets.get_mut(kind as usize) {
+            *offset = Some(start);
+        }
+        reader.seek(SeekFrom::Start(start + size))?;
+    }
+
+    seek_section(&mut reader, &offsets, PTAU_HEADER)?;
+    let n8 = read_u32(&mut reader)? as usize;
+    if n8 != FQ_BYTES {
+        return Err(format_error(format!("{}-byte field, expected BN254", n8)));
+    }
+    let mut modulus = [0; FQ_BYTES];
+    reader.read_exact(&mut modulus)?;
+    // the modulus itself isn't a field element, but the modulus minus one is; its low byte
+    // is nonzero so subtracting one doesn't borrow
+    modulus[0] = modulus[0].wrapping_sub(1);
+    if modulus[..] != (-Fq::one()).to_repr()[..] {
+        return Err(format_error("not a BN254 ptau file"));
+    }
+    let power = read_u32(&mut reader)?;
+    if power < k {
+        return Err(SrsError::TooSmall { k, max_k: power });
+    }
+
+    seek_section(&mut reader, &offsets, PTAU_TAU_G1)?;
+    let g = (0..1usize << k)
+        .map(|_| read_g1(&mut reader))
+        .collect::<Result<Vec<_>, _>>()?;
+    seek_section(&mut reader, &offsets, PTAU_TAU_G2)?;
+    let g2 = read_g2(&mut reader)?;
+    let s_g2 = read_g2(&mut reader)?;
+
+    let params = params_from_powers(k, &g, g2, s_g2)?;
+    check_srs(&params, CHECKED_POWERS)?;
+    Ok(params)
+}
+
+/// Selects the [`SrsProvider`] when `--srs` isn't passed.
+pub const SRS_VAR: &str = "PLAYGROUND_SRS";
+
+/// Where a run's params come from.
+pub trait SrsProvider {
+    fn params(&self, k: u32) -> Result<ParamsKZG<Bn256>, SrsError>;
+
+    /// Whether the secret is known, so anyone can forge proofs under the params.
+    fn is_toxic(&self) -> bool {
+        false
+    }
+}
+
+/// A setup from a known secret, for tests only. `UnsafeDeterministic(GOD_PRIVATE_KEY)` is
+/// what the playground used before providers, and what `cached_params` caches.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct UnsafeDeterministic(pub u128);
+
+impl SrsProvider for UnsafeDeterministic {
+    fn params(&self, k: u32) -> Result<ParamsKZG<Bn256>, SrsError> {
+        Ok(ParamsKZG::<Bn256>::unsafe_setup_with_s(
+            k,
+            Fr::from_u128(self.0),
+        ))
+    }
+
+    fn is_toxic(&self) -> bool {
+        true
+    }
+}
+
+/// A setup from a secret drawn from the OS and dropped right after. Nobody else can forge
+/// under it, but nobody else has reason to trust it either; one process sees the same
+/// params for each `k`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RandomLocal;
+
+impl SrsProvider for RandomLocal {
+    fn params(&self, k: u32) -> Result<ParamsKZG<Bn256>, SrsError> {
+        static SETUPS: Mutex<Vec<(u32, ParamsKZG<Bn256>)>> = Mutex::new(Vec::new());
+        let mut setups = SETUPS
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        if let Some((_, params)) = setups.iter().find(|(setup_k, _)| *setup_k == k) {
+            return Ok(params.clone());
+        }
+        let params = ParamsKZG::<Bn256>::setup(k, OsEntropy);
+        setups.push((k, params.clone()));
+        Ok(params)
+    }
+}
+
+/// A ceremony's SRS: a `.ptau` file, or one in halo2's params format as hermez publishes
+/// them, downsized to each `k`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FromFile(pub PathBuf);
+
+impl SrsProvider for FromFile {
+    fn params(&self, k: u32) -> Result<ParamsKZG<Bn256>, SrsError> {
+        if self.0.extension().map_or(false, |ext| ext == "ptau") {
+            read_ptau(&self.0, k)
+        } else {
+            read_hermez(&self.0, k)
+        }
+    }
+}
+
+/// Parses `unsafe`, `unsafe:<secret>`, `random` or a path, failing with an
+/// [`SrsError::Format`].
+pub fn parse_provider(arg: &str) -> Result<Box<dyn SrsProvider>, SrsError> {
+    if arg == "unsafe" {
+        return Ok(Box::new(UnsafeDeterministic(GOD_PRIVATE_KEY)));
+    }
+    if let Some(secret) = arg.strip_prefix("unsafe:") {
+        let secret = secret
+            .parse()
+            .map_err(|_| SrsError::Format(format!("invalid unsafe secret {:?}", secret)))?;
+        return Ok(Box::new(UnsafeDeterministic(secret)));
+    }
+    match arg {
+        "random" => Ok(Box::new(RandomLocal)),
+        "" => Err(SrsError::Format("empty --srs".to_string())),
+        path => Ok(Box::new(FromFile(PathBuf::from(path)))),
+    }
+}
+
+/// The provider this process was run with: the value of `--srs <provider>` or
+/// `--srs=<provider>`, else [`SRS_VAR`], else `None`.
+pub fn requested_provider() -> Result<Option<Box<dyn SrsProvider>>, SrsError> {
+    let mut args = std::env::args().skip(1);
+    let mut arg = None;
+    while let Some(next) = args.next() {
+        if next == "--srs" {
+            arg = args.next();
+        } else if let Some(value) = next.strip_prefix("--srs=") {
+            arg = Some(value.to_string());
+        }
+    }
+    match arg.or_else(|| std::env::var(SRS_VAR).ok()) {
+        Some(arg) => parse_provider(&arg).map(Some),
+        None => Ok(None),
+    }
+}
+
+/// The provider of `cached_params` when none was requested: the playground secret in debug
+/// builds, with a warning, and an error in release builds.
+pub fn default_provider() -> Result<UnsafeDeterministic, SrsError> {
+    if !cfg!(debug_assertions) {
+        return Err(SrsError::Format(format!(
+            "release builds need --srs (unsafe, random or an SRS file) or {}",
+            SRS_VAR
+        )));
+    }
+    static WARNING: Once = Once::new();
+    WARNING.call_once(|| {
+        eprintln!(
+            "warning: using the playground's known SRS secret; pass --srs to choose the setup"
+        )
+    });
+    Ok(UnsafeDeterministic(GOD_PRIVATE_KEY))
+}
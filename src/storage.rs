@@ -0,0 +1,187 @@
+//! Reading and writing KZG params and verifying keys, and the process's cache of params, keys
+//! and proofs: an [`ArtifactStore`] in [`store_dir`], which `playground gc` trims to size.
+//!
+//! With the `embedded-srs` feature the playground SRS for `k <= EMBEDDED_SRS_K` is compiled
+//! into the binary from the file named by `PLAYGROUND_EMBEDDED_SRS` at build time (`make dist`
+//! generates it), so small demos need neither a setup nor a writable cache. `standalone`, for
+//! released binaries, also moves the cache from `target/store` to the user's cache directory.
+//!
+//! This halo2 fork can't serialize proving keys, so the cache holds a circuit's vk and
+//! [`cached_proving_key`] rebuilds the pk from it with `keygen_pk`, which is much cheaper than
+//! `keygen_vk`'s fixed-column commitments.
+
+use std::{
+    fs::File,
+    io::{self, BufReader, BufWriter},
+    path::{Path, PathBuf},
+};
+
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{keygen_pk, keygen_vk, Circuit, ProvingKey, VerifyingKey},
+    poly::{commitment::Params, kzg::commitment::ParamsKZG},
+};
+
+use crate::{
+    error::PlaygroundError,
+    srs::{self, SrsError, SrsProvider},
+    store::{ArtifactStore, KeyKind},
+};
+
+/// Overrides [`store_dir`].
+pub const STORE_DIR_VAR: &str = "PLAYGROUND_STORE_DIR";
+#[cfg(not(feature = "standalone"))]
+const DEFAULT_STORE_DIR: &str = "target/store";
+/// Size of the SRS compiled in with `embedded-srs`.
+pub const EMBEDDED_SRS_K: u32 = 10;
+
+pub fn write_params(path: &Path, params: &ParamsKZG<Bn256>) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    params.write(&mut writer)
+}
+
+pub fn read_params(path: &Path) -> io::Result<ParamsKZG<Bn256>> {
+    ParamsKZG::<Bn256>::read(&mut BufReader::new(File::open(path)?))
+}
+
+pub fn write_vk(path: &Path, vk: &VerifyingKey<G1Affine>) -> io::Result<()> {
+    let mut writer = BufWriter::new(File::create(path)?);
+    vk.write(&mut writer)
+}
+
+/// Reads a verifying key of circuit `C`, whose constraint system is rebuilt from `C`.
+pub fn read_vk<C: Circuit<Fr>>(
+    path: &Path,
+    params: &ParamsKZG<Bn256>,
+) -> io::Result<VerifyingKey<G1Affine>> {
+    VerifyingKey::<G1Affine>::read::<_, C>(&mut BufReader::new(File::open(path)?), params)
+}
+
+#[cfg(not(feature = "standalone"))]
+fn default_store_dir() -> PathBuf {
+    PathBuf::from(DEFAULT_STORE_DIR)
+}
+
+/// A released binary runs from anywhere, so it caches where the platform keeps caches.
+#[cfg(feature = "standalone")]
+fn default_store_dir() -> PathBuf {
+    let var = |name| std::env::var_os(name).map(PathBuf::from);
+    let base = if cfg!(windows) {
+        var("LOCALAPPDATA")
+    } else if cfg!(target_os = "macos") {
+        var("HOME").map(|home| home.join("Library/Caches"))
+    } else {
+        var("XDG_CACHE_HOME").or_else(|| var("HOME").map(|home| home.join(".cache")))
+    };
+    base.unwrap_or_else(std::env::temp_dir)
+        .join("halo2-playground")
+        .join("store")
+}
+
+pub fn store_dir() -> PathBuf {
+    std::env::var_os(STORE_DIR_VAR)
+        .map(PathBuf::from)
+        .unwrap_or_else(default_store_dir)
+}
+
+/// The cache in [`store_dir`], created if there is none yet.
+pub fn artifact_store() -> io::Result<ArtifactStore> {
+    ArtifactStore::open(store_dir())
+}
+
+/// The playground params for `k` from the SRS compiled into the binary, for
+/// `k <= EMBEDDED_SRS_K`.
+#[cfg(feature = "embedded-srs")]
+pub fn embedded_params(k: u32) -> Option<ParamsKZG<Bn256>> {
+    static SRS: &[u8] = include_bytes!(env!("PLAYGROUND_EMBEDDED_SRS"));
+    if k > EMBEDDED_SRS_K {
+        return None;
+    }
+    let params = ParamsKZG::<Bn256>::read(&mut &SRS[..]).expect("embedded SRS");
+    assert_eq!(params.k(), EMBEDDED_SRS_K, "embedded SRS of the wrong size");
+    if k == EMBEDDED_SRS_K {
+        return Some(params);
+    }
+    Some(crate::srs::downsize(&params, k).expect("downsize the embedded SRS"))
+}
+
+#[cfg(not(feature = "embedded-srs"))]
+pub fn embedded_params(_k: u32) -> Option<ParamsKZG<Bn256>> {
+    None
+}
+
+fn srs_error(err: SrsError) -> io::Error {
+    match err {
+        SrsError::Io(err) => err,
+        err => io::Error::new(io::ErrorKind::Other, err),
+    }
+}
+
+/// The params for `k` from the provider the process was run with (see [`srs`]). Without one
+/// they are the embedded SRS, or the playground's, set up once and then read back from the
+/// [`artifact_store`]; a cached copy that fails its integrity check is set up again. Fails
+/// with a [`PlaygroundError::Setup`].
+pub fn cached_params(k: u32) -> Result<ParamsKZG<Bn256>, PlaygroundError> {
+    let setup = |err: io::Error| PlaygroundError::Setup(format!("params for k = {}: {}", k, err));
+    if let Some(provider) = srs::requested_provider()
+        .map_err(srs_error)
+        .map_err(setup)?
+    {
+        return provider.params(k).map_err(srs_error).map_err(setup);
+    }
+    if let Some(params) = embedded_params(k) {
+        return Ok(params);
+    }
+    let provider = srs::default_provider().map_err(srs_error).map_err(setup)?;
+    let store = artifact_store().map_err(setup)?;
+    if let Ok(bytes) = store.read_params(k) {
+        if let Ok(params) = ParamsKZG::<Bn256>::read(&mut &bytes[..]) {
+            if params.k() == k {
+                return Ok(params);
+            }
+        }
+    }
+    let params = provider.params(k).map_err(srs_error).map_err(setup)?;
+    let mut bytes = vec![];
+    params.write(&mut bytes).map_err(setup)?;
+    store.write_params(k, &bytes).map_err(setup)?;
+    Ok(params)
+}
+
+/// The proving key of `circuit`, registered as `name`, under `params`. The vk is read from
+/// `store` when it holds one for `name` at `params.k()` made from the sources hashing to
+/// `source` (see `provenance::source_hash`), and generated and stored otherwise; the pk is
+/// rebuilt from it either way. Editing the circuit changes `source`, so a stale vk is never
+/// read back, only left for `gc` to evict.
+pub fn cached_proving_key<C: Circuit<Fr>>(
+    store: &ArtifactStore,
+    name: &str,
+    source: &str,
+    params: &ParamsKZG<Bn256>,
+    circuit: &C,
+) -> Result<ProvingKey<G1Affine>, PlaygroundError> {
+    let source = &source[..source.len().min(16)];
+    let path = store
+        .key_path(
+            &format!("{}-{}", name, source),
+            params.k(),
+            KeyKind::Verifying,
+        )
+        .map_err(|e| PlaygroundError::Other(e.to_string()))?;
+    let stored = store
+        .read(&path)
+        .ok()
+        .and_then(|bytes| VerifyingKey::<G1Affine>::read::<_, C>(&mut &bytes[..], params).ok());
+    let vk = match stored {
+        Some(vk) => vk,
+        None => {
+            let vk = keygen_vk(params, circuit).map_err(PlaygroundError::Keygen)?;
+            let mut bytes = vec![];
+            vk.write(&mut bytes)
+                .and_then(|_| store.write(&path, &bytes))
+                .map_err(PlaygroundError::io(&path))?;
+            vk
+        }
+    };
+    keygen_pk(params, vk, circuit).map_err(PlaygroundError::Keygen)
+}
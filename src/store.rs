@@ -0,0 +1,408 @@
+//! On-disk layout for params, keys and proofs:
+//!
+//! ```text
+//! <root>/params/k<k>.bin
+//! <root>/keys/<circuit>/k<k>/{vk,pk}.bin
+//! <root>/proofs/<circuit>/<id>/proof.bin
+//! <root>/tenants/<tenant>/...
+//! ```
+//!
+//! Each tenant gets a store of its own with the same layout and independent limits.
+//!
+//! Every artifact is written atomically next to a `.meta` file holding its length and hash,
+//! which is checked on every read. Reads also refresh a `.used` stamp, which `gc` uses to
+//! evict the least recently used artifacts once a category exceeds its size limit.
+
+use std::{
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+const META_EXTENSION: &str = "meta";
+const USED_EXTENSION: &str = "used";
+const CATEGORIES: [Category; 3] = [Category::Params, Category::Keys, Category::Proofs];
+
+fn digest(bytes: &[u8]) -> String {
+    blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(b"Artifact-Store")
+        .hash(bytes)
+        .to_hex()
+        .to_string()
+}
+
+/// Content-addressed ID of a proof: the hash of the circuit name and the proof bytes.
+pub fn proof_id(circuit: &str, proof: &[u8]) -> String {
+    let mut state = blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(b"Proof-Id")
+        .to_state();
+    state.update(&(circuit.len() as u64).to_le_bytes());
+    state.update(circuit.as_bytes());
+    state.update(proof);
+    state.finalize().to_hex().to_string()
+}
+
+fn sidecar_path(path: &Path, extension: &str) -> PathBuf {
+    let mut name = path.file_name().unwrap_or_default().to_os_string();
+    name.push(".");
+    name.push(extension);
+    path.with_file_name(name)
+}
+
+fn meta_path(path: &Path) -> PathBuf {
+    sidecar_path(path, META_EXTENSION)
+}
+
+fn used_path(path: &Path) -> PathBuf {
+    sidecar_path(path, USED_EXTENSION)
+}
+
+fn now_nanos() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or_default()
+}
+
+/// When the artifact was last written or read.
+fn last_used(path: &Path) -> u128 {
+    fs::read_to_string(used_path(path))
+        .ok()
+        .and_then(|stamp| stamp.trim().parse().ok())
+        .unwrap_or_default()
+}
+
+/// All artifacts below `dir`, i.e. the files that have integrity metadata.
+fn artifacts_in(dir: &Path, found: &mut Vec<PathBuf>) -> io::Result<()> {
+    if !dir.is_dir() {
+        return Ok(());
+    }
+    for entry in fs::read_dir(dir)? {
+        let path = entry?.path();
+        if path.is_dir() {
+            artifacts_in(&path, found)?;
+        } else if meta_path(&path).is_file() {
+            found.push(path);
+        }
+    }
+    Ok(())
+}
+
+/// Removes `dir` and its parents up to `root` as long as they are empty.
+fn remove_empty_dirs(mut dir: &Path, root: &Path) {
+    while dir != root && fs::remove_dir(dir).is_ok() {
+        match dir.parent() {
+            Some(parent) => dir = parent,
+            None => break,
+        }
+    }
+}
+
+fn invalid_data(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Writes `bytes` to a temporary file in the same directory and renames it over `path`.
+fn write_atomic(path: &Path, bytes: &[u8]) -> io::Result<()> {
+    let dir = path.parent().expect("artifact paths have a parent");
+    fs::create_dir_all(dir)?;
+    let mut tmp_name = path.file_name().unwrap_or_default().to_os_string();
+    tmp_name.push(format!(".tmp{}", std::process::id()));
+    let tmp = path.with_file_name(tmp_name);
+    {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(bytes)?;
+        file.sync_all()?;
+    }
+    fs::rename(&tmp, path)
+}
+
+fn check_name(name: &str) -> io::Result<()> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_' || c == '-' || c == '.')
+        && !name.starts_with('.');
+    if valid {
+        Ok(())
+    } else {
+        Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("invalid artifact name {:?}", name),
+        ))
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Category {
+    Params,
+    Keys,
+    Proofs,
+}
+
+impl Category {
+    fn dir(&self) -> &'static str {
+        match self {
+            Category::Params => "params",
+            Category::Keys => "keys",
+            Category::Proofs => "proofs",
+        }
+    }
+}
+
+/// Size limits in bytes per category, `None` for unlimited.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StoreLimits {
+    pub params: Option<u64>,
+    pub keys: Option<u64>,
+    pub proofs: Option<u64>,
+}
+
+impl StoreLimits {
+    fn get(&self, category: Category) -> Option<u64> {
+        match category {
+            Category::Params => self.params,
+            Category::Keys => self.keys,
+            Category::Proofs => self.proofs,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct GcReport {
+    pub removed: Vec<PathBuf>,
+    pub freed: u64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum KeyKind {
+    Verifying,
+    Proving,
+}
+
+#[derive(Clone, Debug)]
+pub struct ArtifactStore {
+    root: PathBuf,
+    limits: StoreLimits,
+}
+
+impl ArtifactStore {
+    pub fn open(root: impl Into<PathBuf>) -> io::Result<Self> {
+        let root = root.into();
+        for category in CATEGORIES {
+            fs::create_dir_all(root.join(category.dir()))?;
+        }
+        Ok(Self {
+            root,
+            limits: StoreLimits::default(),
+        })
+    }
+
+    pub fn with_limits(mut self, limits: StoreLimits) -> Self {
+        self.limits = limits;
+        self
+    }
+
+    pub fn limits(&self) -> StoreLimits {
+        self.limits
+    }
+
+    /// The store of `tenant`, isolated from the artifacts and limits of every other tenant.
+    pub fn tenant(&self, tenant: &str, limits: StoreLimits) -> io::Result<ArtifactStore> {
+        check_name(tenant)?;
+        Ok(ArtifactStore::open(self.root.join("tenants").join(tenant))?.with_limits(limits))
+    }
+
+    pub fn tenants(&self) -> io::Result<Vec<String>> {
+        let dir = self.root.join("tenants");
+        if !dir.is_dir() {
+            return Ok(vec![]);
+        }
+        let mut tenants = fs::read_dir(dir)?
+            .map(|entry| Ok(entry?.file_name().to_string_lossy().to_string()))
+            .collect::<io::Result<Vec<_>>>()?;
+        tenants.sort();
+        Ok(tenants)
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn params_path(&self, k: u32) -> PathBuf {
+        self.root.join("params").join(format!("k{}.bin", k))
+    }
+
+    pub fn key_path(&self, circuit: &str, k: u32, kind: KeyKind) -> io::Result<PathBuf> {
+        check_name(circuit)?;
+        let file = match kind {
+            KeyKind::Verifying => "vk.bin",
+            KeyKind::Proving => "pk.bin",
+        };
+        Ok(self
+            .root
+            .join("keys")
+            .join(circuit)
+            .join(format!("k{}", k))
+            .join(file))
+    }
+
+    pub fn proof_path(&self, circuit: &str, id: &str) -> io::Result<PathBuf> {
+        check_name(circuit)?;
+        check_name(id)?;
+        Ok(self
+            .root
+            .join("proofs")
+            .join(circuit)
+            .join(id)
+            .join("proof.bin"))
+    }
+
+    /// Atomically writes an artifact and its integrity metadata.
+    pub fn write(&self, path: &Path, bytes: &[u8]) -> io::Result<()> {
+        write_atomic(path, bytes)?;
+        let meta = format!("len {}\nblake2b {}\n", bytes.len(), digest(bytes));
+        write_atomic(&meta_path(path), meta.as_bytes())?;
+        self.touch(path)
+    }
+
+    fn touch(&self, path: &Path) -> io::Result<()> {
+        fs::write(used_path(path), now_nanos().to_string())
+    }
+
+    /// Reads an artifact, failing with `InvalidData` if it doesn't match its metadata.
+    pub fn read(&self, path: &Path) -> io::Result<Vec<u8>> {
+        let bytes = fs::read(path)?;
+        let meta = fs::read_to_string(meta_path(path))?;
+        let expected = format!("len {}\nblake2b {}\n", bytes.len(), digest(&bytes));
+        if meta != expected {
+            return Err(invalid_data(format!(
+                "{} does not match its metadata",
+                path.display()
+            )));
+        }
+        self.touch(path)?;
+        Ok(bytes)
+    }
+
+    /// Removes an artifact together with its metadata.
+    pub fn remove(&self, path: &Path) -> io::Result<u64> {
+        let len = fs::metadata(path)?.len();
+        fs::remove_file(path)?;
+        for sidecar in [meta_path(path), used_path(path)] {
+            if sidecar.is_file() {
+                fs::remove_file(sidecar)?;
+            }
+        }
+        if let Some(dir) = path.parent() {
+            remove_empty_dirs(dir, &self.root);
+        }
+        Ok(len)
+    }
+
+    pub fn artifacts(&self, category: Category) -> io::Result<Vec<PathBuf>> {
+        let mut found = vec![];
+        artifacts_in(&self.root.join(category.dir()), &mut found)?;
+        Ok(found)
+    }
+
+    /// Total size in bytes of the artifacts in `category`, metadata excluded.
+    pub fn usage(&self, category: Category) -> io::Result<u64> {
+        self.artifacts(category)?
+            .iter()
+            .map(|path| fs::metadata(path).map(|m| m.len()))
+            .sum()
+    }
+
+    /// Evicts the least recently used artifacts of every category over its limit.
+    pub fn gc(&self) -> io::Result<GcReport> {
+        let mut report = GcReport::default();
+        for category in CATEGORIES {
+            let limit = match self.limits.get(category) {
+                Some(limit) => limit,
+                None => continue,
+            };
+            let mut artifacts = self
+                .artifacts(category)?
+                .into_iter()
+                .map(|path| Ok((last_used(&path), fs::metadata(&path)?.len(), path)))
+                .collect::<io::Result<Vec<_>>>()?;
+            artifacts.sort();
+            let mut usage: u64 = artifacts.iter().map(|(_, len, _)| len).sum();
+            for (_, len, path) in artifacts {
+                if usage <= limit {
+                    break;
+                }
+                self.remove(&path)?;
+                usage -= len;
+                report.freed += len;
+                report.removed.push(path);
+            }
+        }
+        Ok(report)
+    }
+
+    pub fn exists(&self, path: &Path) -> bool {
+        path.is_file() && meta_path(path).is_file()
+    }
+
+    pub fn write_params(&self, k: u32, bytes: &[u8]) -> io::Result<()> {
+        self.write(&self.params_path(k), bytes)
+    }
+
+    pub fn read_params(&self, k: u32) -> io::Result<Vec<u8>> {
+        self.read(&self.params_path(k))
+    }
+
+    pub fn write_key(&self, circuit: &str, k: u32, kind: KeyKind, bytes: &[u8]) -> io::Result<()> {
+        self.write(&self.key_path(circuit, k, kind)?, bytes)
+    }
+
+    pub fn read_key(&self, circuit: &str, k: u32, kind: KeyKind) -> io::Result<Vec<u8>> {
+        self.read(&self.key_path(circuit, k, kind)?)
+    }
+
+    pub fn write_proof(&self, circuit: &str, id: &str, bytes: &[u8]) -> io::Result<()> {
+        self.write(&self.proof_path(circuit, id)?, bytes)
+    }
+
+    pub fn read_proof(&self, circuit: &str, id: &str) -> io::Result<Vec<u8>> {
+        self.read(&self.proof_path(circuit, id)?)
+    }
+
+    /// Stores a proof under its content-addressed ID, reusing the stored copy if there is one.
+    pub fn put_proof(&self, circuit: &str, proof: &[u8]) -> io::Result<String> {
+        let id = proof_id(circuit, proof);
+        let path = self.proof_path(circuit, &id)?;
+        if self.exists(&path) && self.read(&path).is_ok() {
+            return Ok(id);
+        }
+        self.write(&path, proof)?;
+        Ok(id)
+    }
+
+    /// Finds a proof by content-addressed ID alone, returning its circuit and bytes. The bytes
+    /// are checked to hash to `id`.
+    pub fn lookup_proof(&self, id: &str) -> io::Result<Option<(String, Vec<u8>)>> {
+        check_name(id)?;
+        for entry in fs::read_dir(self.root.join(Category::Proofs.dir()))? {
+            let circuit = entry?.file_name().to_string_lossy().to_string();
+            let path = self.proof_path(&circuit, id)?;
+            if !self.exists(&path) {
+                continue;
+            }
+            let proof = self.read(&path)?;
+            if proof_id(&circuit, &proof) != id {
+                return Err(invalid_data(format!(
+                    "{} is not addressed by its content",
+                    path.display()
+                )));
+            }
+            return Ok(Some((circuit, proof)));
+        }
+        Ok(None)
+    }
+}
@@ -0,0 +1,280 @@
+//! Hashing inputs too large for one circuit. A Poseidon sponge absorbs the input `BLOCKS`
+//! blocks at a time, one proof per chunk, and each proof exposes the sponge state it starts
+//! and ends in so the verifier can chain them, as `continuation` does for a step function.
+
+use std::convert::TryInto;
+
+use halo2_gadgets::poseidon::{PoseidonInstructions, Pow5Chip, Pow5Config, StateWord};
+use halo2_proofs::{
+    arithmetic::{Field, FieldExt},
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, ProvingKey, VerifyingKey},
+    poly::kzg::{commitment::ParamsKZG, strategy::AccumulatorStrategy},
+};
+
+use crate::{
+    chips::standard_plonk::{StandardPlonkChip, StandardPlonkConfig},
+    entropy::EntropySource,
+    error::PlaygroundError,
+    poseidon::MySpec,
+    prover::{run_proof, MultiOpen, ProofOptions},
+    reference::poseidon_permute,
+    verifier::{final_check, precheck_proof, VerificationReport},
+};
+
+pub const WIDTH: usize = 3;
+pub const RATE: usize = 2;
+/// Bytes per word, so that every word is below the modulus.
+pub const WORD_BYTES: usize = 31;
+/// The capacity word of the initial state, keeping streamed digests apart from the other uses
+/// of the permutation.
+pub const DOMAIN: u64 = 0x5354_5245_414d;
+
+pub type State = [Fr; WIDTH];
+
+pub fn initial_state() -> State {
+    [Fr::zero(), Fr::zero(), Fr::from(DOMAIN)]
+}
+
+/// `bytes` in little-endian words of `WORD_BYTES`, then a 1 marking the end, then zeros up to
+/// whole chunks of `BLOCKS` blocks. The digest is of the padded words, so it depends on
+/// `BLOCKS`, which the verifying key fixes anyway.
+pub fn words<const BLOCKS: usize>(bytes: &[u8]) -> Vec<Fr> {
+    let mut words = bytes
+        .chunks(WORD_BYTES)
+        .map(|chunk| {
+            let mut repr = [0u8; 64];
+            repr[..chunk.len()].copy_from_slice(chunk);
+            Fr::from_bytes_wide(&repr)
+        })
+        .collect::<Vec<_>>();
+    words.push(Fr::one());
+    let chunk = BLOCKS * RATE;
+    words.resize((words.len() + chunk - 1) / chunk * chunk, Fr::zero());
+    words
+}
+
+/// Adds each block of `RATE` words into the rate and permutes.
+pub fn absorb(state: &mut State, words: &[Fr]) {
+    assert_eq!(words.len() % RATE, 0, "whole blocks");
+    for block in words.chunks(RATE) {
+        for (word, input) in state.iter_mut().zip(block) {
+            *word += input;
+        }
+        poseidon_permute::<Fr, MySpec<WIDTH, RATE>, WIDTH, RATE>(state);
+    }
+}
+
+/// The first word of the state after absorbing all of `bytes`.
+pub fn stream_hash<const BLOCKS: usize>(bytes: &[u8]) -> Fr {
+    let mut state = initial_state();
+    absorb(&mut state, &words::<BLOCKS>(bytes));
+    state[0]
+}
+
+/// The public inputs of a chunk.
+pub fn instance(state_in: &State, state_out: &State) -> Vec<Fr> {
+    state_in.iter().chain(state_out.iter()).copied().collect()
+}
+
+#[derive(Clone, Debug)]
+pub struct StreamChunkConfig {
+    pub load: Column<Advice>,
+    pub instance: Column<Instance>,
+    pub plonk: StandardPlonkConfig,
+    pub poseidon_config: Pow5Config<Fr, WIDTH, RATE>,
+}
+
+/// Proves that absorbing `BLOCKS` private blocks takes the sponge state in instance rows 0 to 2
+/// to the one in rows 3 to 5.
+#[derive(Clone, Debug)]
+pub struct StreamChunkCircuit<const BLOCKS: usize> {
+    pub words: Value<Vec<Fr>>,
+}
+
+impl<const BLOCKS: usize> Default for StreamChunkCircuit<BLOCKS> {
+    fn default() -> Self {
+        Self {
+            words: Value::unknown(),
+        }
+    }
+}
+
+impl<const BLOCKS: usize> StreamChunkCircuit<BLOCKS> {
+    pub fn new(words: Vec<Fr>) -> Self {
+        assert_eq!(words.len(), BLOCKS * RATE, "a chunk of {} blocks", BLOCKS);
+        Self {
+            words: Value::known(words),
+        }
+    }
+}
+
+impl<const BLOCKS: usize> Circuit<Fr> for StreamChunkCircuit<BLOCKS> {
+    type Config = StreamChunkConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        Self::default()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let state = (0..WIDTH).map(|_| meta.advice_column()).collect::<Vec<_>>();
+        let partial_sbox = meta.advice_column();
+        let rc_a = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        let rc_b = (0..WIDTH).map(|_| meta.fixed_column()).collect::<Vec<_>>();
+        meta.enable_constant(rc_b[0]);
+        let load = meta.advice_column();
+        meta.enable_equality(load);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        StreamChunkConfig {
+            load,
+            instance,
+            plonk: StandardPlonkChip::configure(meta),
+            poseidon_config: Pow5Chip::configure::<MySpec<WIDTH, RATE>>(
+                meta,
+                state.try_into().unwrap(),
+                partial_sbox,
+                rc_a.try_into().unwrap(),
+                rc_b.try_into().unwrap(),
+            ),
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let plonk = StandardPlonkChip::construct(config.plonk.clone());
+        let chip = Pow5Chip::construct(config.poseidon_config.clone());
+        let (mut state, words) = layouter.assign_region(
+            || "load state and words",
+            |mut region| {
+                let state = (0..WIDTH)
+                    .map(|i| {
+                        region.assign_advice_from_instance(
+                            || "state in",
+                            config.instance,
+                            i,
+                            config.load,
+                            i,
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let words = (0..BLOCKS * RATE)
+                    .map(|i| {
+                        region.assign_advice(
+                            || "word",
+                            config.load,
+                            WIDTH + i,
+                            || self.words.as_ref().map(|words| words[i]),
+                        )
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                Ok((state, words))
+            },
+        )?;
+
+        for (i, block) in words.chunks(RATE).enumerate() {
+            let mut layouter = layouter.namespace(|| format!("block {}", i));
+            for (j, word) in block.iter().enumerate() {
+                state[j] = plonk.add(layouter.namespace(|| "absorb"), &state[j], word)?;
+            }
+            let words: [StateWord<Fr>; WIDTH] = state
+                .drain(..)
+                .map(StateWord::from)
+                .collect::<Vec<_>>()
+                .try_into()
+                .unwrap();
+            let permuted = <Pow5Chip<Fr, WIDTH, RATE> as PoseidonInstructions<
+                Fr,
+                MySpec<WIDTH, RATE>,
+                WIDTH,
+                RATE,
+            >>::permute(&chip, &mut layouter, &words)?;
+            state = permuted.into_iter().map(AssignedCell::from).collect();
+        }
+
+        for (i, word) in state.iter().enumerate() {
+            layouter.constrain_instance(word.cell(), config.instance, WIDTH + i)?;
+        }
+        Ok(())
+    }
+}
+
+#[derive(Clone, Debug)]
+pub struct ChunkProof {
+    pub state_in: State,
+    pub state_out: State,
+    pub proof: Vec<u8>,
+}
+
+/// Proves every chunk of `bytes`, starting from the initial state.
+pub fn prove_stream<const BLOCKS: usize, R: EntropySource>(
+    params: &ParamsKZG<Bn256>,
+    pk: &ProvingKey<G1Affine>,
+    bytes: &[u8],
+    mut rng: R,
+) -> Result<Vec<ChunkProof>, PlaygroundError> {
+    let mut state_in = initial_state();
+    words::<BLOCKS>(bytes)
+        .chunks(BLOCKS * RATE)
+        .map(|chunk| {
+            let mut state_out = state_in;
+            absorb(&mut state_out, chunk);
+            let instance = instance(&state_in, &state_out);
+            let proof = run_proof(
+                params,
+                pk,
+                StreamChunkCircuit::<BLOCKS>::new(chunk.to_vec()),
+                &[&instance],
+                &mut rng,
+                ProofOptions::default(),
+            )?;
+            let chunk = ChunkProof {
+                state_in,
+                state_out,
+                proof,
+            };
+            state_in = state_out;
+            Ok(chunk)
+        })
+        .collect()
+}
+
+/// The digest the chunk proofs attest to, if they start from the initial state, each starts
+/// where the previous one ended and every proof verifies. The pairings are batched into one
+/// final check.
+pub fn verify_stream(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    proofs: &[ChunkProof],
+) -> Result<Option<Fr>, VerificationReport> {
+    let mut expected_in = initial_state();
+    for chunk in proofs.iter() {
+        if chunk.state_in != expected_in {
+            return Ok(None);
+        }
+        expected_in = chunk.state_out;
+    }
+    let last = match proofs.last() {
+        Some(last) => last,
+        None => return Ok(None),
+    };
+
+    let mut strategy = AccumulatorStrategy::new(params);
+    for chunk in proofs.iter() {
+        let instance = instance(&chunk.state_in, &chunk.state_out);
+        strategy = precheck_proof(
+            params,
+            vk,
+            strategy,
+            &[&[&instance]],
+            &chunk.proof,
+            ProofOptions::default(),
+        )?;
+    }
+    Ok(final_check(strategy, MultiOpen::Shplonk).then(|| last.state_out[0]))
+}
@@ -0,0 +1,140 @@
+//! Runs a circuit over a grid of parameters and collects cost metrics into CSV rows, for
+//! scaling curves. A point is one circuit instantiation at one `k`; the batch sizes are
+//! measured on the same proof.
+
+use std::time::{Duration, Instant};
+
+use halo2_proofs::{
+    halo2curves::bn256::{Bn256, Fr},
+    plonk::{keygen_pk, keygen_vk, Circuit},
+    poly::kzg::commitment::ParamsKZG,
+};
+
+use crate::{
+    batch::Batch,
+    entropy::EntropySource,
+    error::PlaygroundError,
+    prover::{run_proof, ProofOptions},
+    verifier::check_proof,
+};
+
+pub const CSV_HEADER: &str =
+    "circuit,k,length,batch,status,keygen_ms,prove_ms,verify_ms,batch_verify_ms_per_proof,proof_bytes";
+
+/// Which point of the grid a row measures. `length` is the message length for hash circuits.
+#[derive(Clone, Debug)]
+pub struct SweepPoint {
+    pub circuit: &'static str,
+    pub k: u32,
+    pub length: Option<usize>,
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct SweepCosts {
+    pub keygen: Duration,
+    pub prove: Duration,
+    pub verify: Duration,
+    pub proof_bytes: usize,
+    /// (batch size, verification time per proof)
+    pub batches: Vec<(usize, Duration)>,
+}
+
+fn millis(duration: Duration) -> String {
+    format!("{:.3}", duration.as_secs_f64() * 1000.0)
+}
+
+/// Keygen, one proof and its verification under `params`, plus a batch verification at each size
+/// in `batches`.
+pub fn measure<C: Circuit<Fr> + Clone, R: EntropySource>(
+    params: &ParamsKZG<Bn256>,
+    circuit: &C,
+    instances: &[&[Fr]],
+    batches: &[usize],
+    rng: R,
+) -> Result<SweepCosts, PlaygroundError> {
+    let start = Instant::now();
+    let vk = keygen_vk(params, circuit).map_err(PlaygroundError::Keygen)?;
+    let pk = keygen_pk(params, vk, circuit).map_err(PlaygroundError::Keygen)?;
+    let keygen = start.elapsed();
+
+    let start = Instant::now();
+    let proof = run_proof(
+        params,
+        &pk,
+        circuit.clone(),
+        instances,
+        rng,
+        ProofOptions::default(),
+    )?;
+    let prove = start.elapsed();
+
+    let start = Instant::now();
+    check_proof(
+        params,
+        pk.get_vk(),
+        instances,
+        &proof,
+        ProofOptions::default(),
+    )
+    .into_result()?;
+    let verify = start.elapsed();
+
+    let columns: Vec<Vec<Fr>> = instances.iter().map(|column| column.to_vec()).collect();
+    let mut measured = Vec::with_capacity(batches.len());
+    for &size in batches.iter() {
+        let mut batch = Batch::new();
+        for _ in 0..size {
+            batch.push(pk.get_vk(), columns.clone(), proof.clone());
+        }
+        let start = Instant::now();
+        batch
+            .verify(params)
+            .map_err(|e| PlaygroundError::Verify(e.to_string()))?;
+        measured.push((size, start.elapsed() / size.max(1) as u32));
+    }
+
+    Ok(SweepCosts {
+        keygen,
+        prove,
+        verify,
+        proof_bytes: proof.len(),
+        batches: measured,
+    })
+}
+
+/// CSV rows for one point, one per batch size. A point that failed, e.g. because the circuit
+/// does not fit in `k`, still gets a row with the error as its status.
+pub fn csv_rows(
+    point: &SweepPoint,
+    batches: &[usize],
+    costs: &Result<SweepCosts, PlaygroundError>,
+) -> Vec<String> {
+    let length = point.length.map(|l| l.to_string()).unwrap_or_default();
+    let prefix = format!("{},{},{}", point.circuit, point.k, length);
+    match costs {
+        Ok(costs) => costs
+            .batches
+            .iter()
+            .map(|(size, per_proof)| {
+                format!(
+                    "{},{},ok,{},{},{},{},{}",
+                    prefix,
+                    size,
+                    millis(costs.keygen),
+                    millis(costs.prove),
+                    millis(costs.verify),
+                    millis(*per_proof),
+                    costs.proof_bytes
+                )
+            })
+            .collect(),
+        Err(err) => batches
+            .iter()
+            .map(|size| {
+                // keep the status a single CSV field
+                let status = err.to_string().replace(&['"', ','][..], " ");
+                format!("{},{},{},,,,,", prefix, size, status)
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,189 @@
+//! Keccak256 transcript laid out for an EVM verifier.
+//!
+//! Points are stored and hashed uncompressed as `x || y` and scalars as a single word, all in
+//! 32-byte big-endian words, so a Solidity verifier can read the proof from calldata and
+//! recompute challenges with `keccak256`. A challenge hashes everything absorbed since the
+//! previous challenge, chained through that previous challenge.
+
+use std::{
+    io::{self, Read, Write},
+    marker::PhantomData,
+};
+
+use halo2_proofs::{
+    arithmetic::CurveAffine,
+    halo2curves::group::ff::PrimeField,
+    transcript::{
+        Challenge255, EncodedChallenge, Transcript, TranscriptRead, TranscriptReadBuffer,
+        TranscriptWrite, TranscriptWriterBuffer,
+    },
+};
+use sha3::{Digest, Keccak256};
+
+const WORD: usize = 32;
+
+/// Big-endian word of a field element whose repr is little-endian.
+fn to_word<F: PrimeField>(value: &F) -> [u8; WORD] {
+    let mut word = [0; WORD];
+    for (dst, src) in word.iter_mut().rev().zip(value.to_repr().as_ref()) {
+        *dst = *src;
+    }
+    word
+}
+
+fn from_word<F: PrimeField>(word: &[u8]) -> io::Result<F> {
+    let mut repr = F::Repr::default();
+    for (dst, src) in repr.as_mut().iter_mut().zip(word.iter().rev()) {
+        *dst = *src;
+    }
+    Option::from(F::from_repr(repr)).ok_or_else(|| invalid_data("non-canonical field element"))
+}
+
+/// The identity, which has no coordinates, is written as `(0, 0)` like the EVM precompiles.
+fn point_words<C: CurveAffine>(point: &C) -> [u8; 2 * WORD] {
+    let mut words = [0; 2 * WORD];
+    let coordinates: Option<_> = point.coordinates().into();
+    if let Some(coordinates) = coordinates {
+        words[..WORD].copy_from_slice(&to_word(coordinates.x()));
+        words[WORD..].copy_from_slice(&to_word(coordinates.y()));
+    }
+    words
+}
+
+fn point_from_words<C: CurveAffine>(words: &[u8]) -> io::Result<C> {
+    if words.iter().all(|b| *b == 0) {
+        return Ok(C::identity());
+    }
+    let x = from_word(&words[..WORD])?;
+    let y = from_word(&words[WORD..])?;
+    Option::from(C::from_xy(x, y)).ok_or_else(|| invalid_data("point not on the curve"))
+}
+
+fn invalid_data(message: &str) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
+
+/// Hashes `buffer` into a challenge, leaving only the challenge in it.
+fn squeeze<C: CurveAffine>(buffer: &mut Vec<u8>) -> Challenge255<C> {
+    let hash = Keccak256::digest(&buffer[..]);
+    buffer.clear();
+    buffer.extend_from_slice(&hash);
+    // read the hash as a big-endian number, reduced modulo the scalar field
+    let mut wide = [0; 64];
+    for (dst, src) in wide.iter_mut().zip(hash.iter().rev()) {
+        *dst = *src;
+    }
+    Challenge255::<C>::new(&wide)
+}
+
+#[derive(Debug, Clone)]
+pub struct Keccak256Write<W: Write, C: CurveAffine, E: EncodedChallenge<C>> {
+    buffer: Vec<u8>,
+    writer: W,
+    _marker: PhantomData<(C, E)>,
+}
+
+impl<W: Write, C: CurveAffine> Transcript<C, Challenge255<C>>
+    for Keccak256Write<W, C, Challenge255<C>>
+{
+    fn squeeze_challenge(&mut self) -> Challenge255<C> {
+        squeeze(&mut self.buffer)
+    }
+
+    fn common_point(&mut self, point: C) -> io::Result<()> {
+        self.buffer.extend_from_slice(&point_words(&point));
+        Ok(())
+    }
+
+    fn common_scalar(&mut self, scalar: C::Scalar) -> io::Result<()> {
+        self.buffer.extend_from_slice(&to_word(&scalar));
+        Ok(())
+    }
+}
+
+impl<W: Write, C: CurveAffine> TranscriptWrite<C, Challenge255<C>>
+    for Keccak256Write<W, C, Challenge255<C>>
+{
+    fn write_point(&mut self, point: C) -> io::Result<()> {
+        self.common_point(point)?;
+        self.writer.write_all(&point_words(&point))
+    }
+
+    fn write_scalar(&mut self, scalar: C::Scalar) -> io::Result<()> {
+        self.common_scalar(scalar)?;
+        self.writer.write_all(&to_word(&scalar))
+    }
+}
+
+impl<W: Write, C: CurveAffine> TranscriptWriterBuffer<W, C, Challenge255<C>>
+    for Keccak256Write<W, C, Challenge255<C>>
+{
+    fn init(writer: W) -> Self {
+        Self {
+            buffer: vec![],
+            writer,
+            _marker: PhantomData,
+        }
+    }
+
+    fn finalize(self) -> W {
+        self.writer
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct Keccak256Read<R: Read, C: CurveAffine, E: EncodedChallenge<C>> {
+    buffer: Vec<u8>,
+    reader: R,
+    _marker: PhantomData<(C, E)>,
+}
+
+impl<R: Read, C: CurveAffine> Transcript<C, Challenge255<C>>
+    for Keccak256Read<R, C, Challenge255<C>>
+{
+    fn squeeze_challenge(&mut self) -> Challenge255<C> {
+        squeeze(&mut self.buffer)
+    }
+
+    fn common_point(&mut self, point: C) -> io::Result<()> {
+        self.buffer.extend_from_slice(&point_words(&point));
+        Ok(())
+    }
+
+    fn common_scalar(&mut self, scalar: C::Scalar) -> io::Result<()> {
+        self.buffer.extend_from_slice(&to_word(&scalar));
+        Ok(())
+    }
+}
+
+impl<R: Read, C: CurveAffine> TranscriptRead<C, Challenge255<C>>
+    for Keccak256Read<R, C, Challenge255<C>>
+{
+    fn read_point(&mut self) -> io::Result<C> {
+        let mut words = [0; 2 * WORD];
+        self.reader.read_exact(&mut words)?;
+        let point = point_from_words(&words)?;
+        self.common_point(point)?;
+        Ok(point)
+    }
+
+    fn read_scalar(&mut self) -> io::Result<C::Scalar> {
+        let mut word = [0; WORD];
+        self.reader.read_exact(&mut word)?;
+        let scalar = from_word(&word)?;
+        self.common_scalar(scalar)?;
+        Ok(scalar)
+    }
+}
+
+impl<R: Read, C: CurveAffine> TranscriptReadBuffer<R, C, Challenge255<C>>
+    for Keccak256Read<R, C, Challenge255<C>>
+{
+    fn init(reader: R) -> Self {
+        Self {
+            buffer: vec![],
+            reader,
+            _marker: PhantomData,
+        }
+    }
+}
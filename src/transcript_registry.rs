@@ -0,0 +1,258 @@
+//! The transcript hashes proofs can be made with, by the names the CLI takes and the tags proof
+//! bundles record. Each [`TranscriptEntry`] builds its hash's writer and reader behind
+//! object-safe traits, so the prover and verifier are written once against [`AnyWrite`] and
+//! [`AnyRead`], and another hash is one more entry in [`TRANSCRIPTS`].
+
+use std::io;
+
+use halo2_proofs::{
+    halo2curves::bn256::{Fr, G1Affine},
+    transcript::{
+        Blake2bRead, Blake2bWrite, Challenge255, Transcript, TranscriptRead, TranscriptReadBuffer,
+        TranscriptWrite, TranscriptWriterBuffer,
+    },
+};
+
+use crate::{
+    poseidon_transcript::{PoseidonRead, PoseidonWrite},
+    prover::TranscriptHash,
+    transcript::{Keccak256Read, Keccak256Write},
+};
+
+type Challenge = Challenge255<G1Affine>;
+
+/// [`TranscriptWrite`] without its generic methods, so it can be boxed.
+pub trait DynWrite {
+    fn squeeze_challenge(&mut self) -> Challenge;
+    fn common_point(&mut self, point: G1Affine) -> io::Result<()>;
+    fn common_scalar(&mut self, scalar: Fr) -> io::Result<()>;
+    fn write_point(&mut self, point: G1Affine) -> io::Result<()>;
+    fn write_scalar(&mut self, scalar: Fr) -> io::Result<()>;
+    fn finalize(self: Box<Self>) -> Vec<u8>;
+}
+
+impl<T: TranscriptWriterBuffer<Vec<u8>, G1Affine, Challenge>> DynWrite for T {
+    fn squeeze_challenge(&mut self) -> Challenge {
+        Transcript::squeeze_challenge(self)
+    }
+
+    fn common_point(&mut self, point: G1Affine) -> io::Result<()> {
+        Transcript::common_point(self, point)
+    }
+
+    fn common_scalar(&mut self, scalar: Fr) -> io::Result<()> {
+        Transcript::common_scalar(self, scalar)
+    }
+
+    fn write_point(&mut self, point: G1Affine) -> io::Result<()> {
+        TranscriptWrite::write_point(self, point)
+    }
+
+    fn write_scalar(&mut self, scalar: Fr) -> io::Result<()> {
+        TranscriptWrite::write_scalar(self, scalar)
+    }
+
+    fn finalize(self: Box<Self>) -> Vec<u8> {
+        TranscriptWriterBuffer::finalize(*self)
+    }
+}
+
+/// [`TranscriptRead`] without its generic methods, so it can be boxed.
+pub trait DynRead {
+    fn squeeze_challenge(&mut self) -> Challenge;
+    fn common_point(&mut self, point: G1Affine) -> io::Result<()>;
+    fn common_scalar(&mut self, scalar: Fr) -> io::Result<()>;
+    fn read_point(&mut self) -> io::Result<G1Affine>;
+    fn read_scalar(&mut self) -> io::Result<Fr>;
+}
+
+impl<T: TranscriptRead<G1Affine, Challenge>> DynRead for T {
+    fn squeeze_challenge(&mut self) -> Challenge {
+        Transcript::squeeze_challenge(self)
+    }
+
+    fn common_point(&mut self, point: G1Affine) -> io::Result<()> {
+        Transcript::common_point(self, point)
+    }
+
+    fn common_scalar(&mut self, scalar: Fr) -> io::Result<()> {
+        Transcript::common_scalar(self, scalar)
+    }
+
+    fn read_point(&mut self) -> io::Result<G1Affine> {
+        TranscriptRead::read_point(self)
+    }
+
+    fn read_scalar(&mut self) -> io::Result<Fr> {
+        TranscriptRead::read_scalar(self)
+    }
+}
+
+/// The writer of whichever hash an entry names.
+pub struct AnyWrite(Box<dyn DynWrite>);
+
+impl AnyWrite {
+    pub fn finalize(self) -> Vec<u8> {
+        self.0.finalize()
+    }
+}
+
+impl Transcript<G1Affine, Challenge> for AnyWrite {
+    fn squeeze_challenge(&mut self) -> Challenge {
+        self.0.squeeze_challenge()
+    }
+
+    fn common_point(&mut self, point: G1Affine) -> io::Result<()> {
+        self.0.common_point(point)
+    }
+
+    fn common_scalar(&mut self, scalar: Fr) -> io::Result<()> {
+        self.0.common_scalar(scalar)
+    }
+}
+
+impl TranscriptWrite<G1Affine, Challenge> for AnyWrite {
+    fn write_point(&mut self, point: G1Affine) -> io::Result<()> {
+        self.0.write_point(point)
+    }
+
+    fn write_scalar(&mut self, scalar: Fr) -> io::Result<()> {
+        self.0.write_scalar(scalar)
+    }
+}
+
+/// The reader of whichever hash an entry names, over a proof.
+pub struct AnyRead<'proof>(Box<dyn DynRead + 'proof>);
+
+impl Transcript<G1Affine, Challenge> for AnyRead<'_> {
+    fn squeeze_challenge(&mut self) -> Challenge {
+        self.0.squeeze_challenge()
+    }
+
+    fn common_point(&mut self, point: G1Affine) -> io::Result<()> {
+        self.0.common_point(point)
+    }
+
+    fn common_scalar(&mut self, scalar: Fr) -> io::Result<()> {
+        self.0.common_scalar(scalar)
+    }
+}
+
+impl TranscriptRead<G1Affine, Challenge> for AnyRead<'_> {
+    fn read_point(&mut self) -> io::Result<G1Affine> {
+        self.0.read_point()
+    }
+
+    fn read_scalar(&mut self) -> io::Result<Fr> {
+        self.0.read_scalar()
+    }
+}
+
+#[derive(Clone, Copy)]
+pub struct TranscriptEntry {
+    pub hash: TranscriptHash,
+    /// As the CLI takes it.
+    pub id: &'static str,
+    /// As proof bundles record it; never reused.
+    pub tag: u8,
+    pub writer: fn() -> AnyWrite,
+    pub reader: fn(&[u8]) -> AnyRead<'_>,
+}
+
+impl std::fmt::Debug for TranscriptEntry {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("TranscriptEntry")
+            .field("hash", &self.hash)
+            .field("id", &self.id)
+            .field("tag", &self.tag)
+            .finish()
+    }
+}
+
+fn blake2b_writer() -> AnyWrite {
+    AnyWrite(Box::new(Blake2bWrite::<_, G1Affine, Challenge>::init(
+        vec![],
+    )))
+}
+
+fn blake2b_reader(proof: &[u8]) -> AnyRead<'_> {
+    AnyRead(Box::new(Blake2bRead::<_, G1Affine, Challenge>::init(proof)))
+}
+
+fn keccak256_writer() -> AnyWrite {
+    AnyWrite(Box::new(Keccak256Write::<_, G1Affine, Challenge>::init(
+        vec![],
+    )))
+}
+
+fn keccak256_reader(proof: &[u8]) -> AnyRead<'_> {
+    AnyRead(Box::new(Keccak256Read::<_, G1Affine, Challenge>::init(
+        proof,
+    )))
+}
+
+fn poseidon_writer() -> AnyWrite {
+    AnyWrite(Box::new(PoseidonWrite::<_, G1Affine, Challenge>::init(
+        vec![],
+    )))
+}
+
+fn poseidon_reader(proof: &[u8]) -> AnyRead<'_> {
+    AnyRead(Box::new(PoseidonRead::<_, G1Affine, Challenge>::init(
+        proof,
+    )))
+}
+
+/// Every transcript hash, the default first.
+pub const TRANSCRIPTS: &[TranscriptEntry] = &[
+    TranscriptEntry {
+        hash: TranscriptHash::Blake2b,
+        id: "blake2b",
+        tag: 0,
+        writer: blake2b_writer,
+        reader: blake2b_reader,
+    },
+    TranscriptEntry {
+        hash: TranscriptHash::Keccak256,
+        id: "keccak256",
+        tag: 1,
+        writer: keccak256_writer,
+        reader: keccak256_reader,
+    },
+    TranscriptEntry {
+        hash: TranscriptHash::Poseidon,
+        id: "poseidon",
+        tag: 2,
+        writer: poseidon_writer,
+        reader: poseidon_reader,
+    },
+];
+
+pub fn entry(hash: TranscriptHash) -> &'static TranscriptEntry {
+    TRANSCRIPTS
+        .iter()
+        .find(|entry| entry.hash == hash)
+        .expect("every transcript hash is registered")
+}
+
+pub fn by_id(id: &str) -> Option<&'static TranscriptEntry> {
+    TRANSCRIPTS.iter().find(|entry| entry.id == id)
+}
+
+pub fn by_tag(tag: u8) -> Option<&'static TranscriptEntry> {
+    TRANSCRIPTS.iter().find(|entry| entry.tag == tag)
+}
+
+impl TranscriptHash {
+    pub fn id(self) -> &'static str {
+        entry(self).id
+    }
+
+    pub fn writer(self) -> AnyWrite {
+        (entry(self).writer)()
+    }
+
+    pub fn reader(self, proof: &[u8]) -> AnyRead<'_> {
+        (entry(self).reader)(proof)
+    }
+}
@@ -0,0 +1,432 @@
+//! Where a circuit's area goes, for tuning its size without reading its layout drawing: the
+//! fixed columns keygen combined the selectors into, how full each column is, and the cells
+//! each region leaves empty, with the columns that could be merged.
+//!
+//! Keygen replaces every selector with queries of fixed columns it appends after the circuit's
+//! own, so a selector's column is found by comparing each gate and lookup before and after.
+//! The selectors of a gate that queries several are told apart by the other gates they are in;
+//! those that can't be are listed as unplaced.
+
+use std::{
+    collections::{BTreeMap, BTreeSet, HashMap},
+    fmt,
+    ops::Range,
+};
+
+use halo2_proofs::{
+    halo2curves::bn256::Fr,
+    plonk::{keygen_vk, Any, Circuit, ConstraintSystem, Expression},
+};
+
+use crate::{
+    audit::fit_k,
+    dev::{column_name, AssignedRegion, Assignments},
+    error::PlaygroundError,
+    storage::cached_params,
+};
+
+/// Columns filled under this many percent are worth merging.
+const SPARSE_PERCENT: usize = 50;
+
+/// A fixed column keygen appended, and the selectors it holds with the rows each is on.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SelectorColumn {
+    pub column: usize,
+    pub selectors: Vec<(String, usize)>,
+}
+
+/// The rows of a column that hold a cell, as runs.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ColumnFill {
+    pub column: String,
+    pub cells: usize,
+    pub runs: Vec<Range<usize>>,
+}
+
+impl ColumnFill {
+    fn new(column: String, rows: &BTreeSet<usize>) -> Self {
+        let mut runs: Vec<Range<usize>> = vec![];
+        for &row in rows {
+            match runs.last_mut() {
+                Some(run) if run.end == row => run.end += 1,
+                _ => runs.push(row..row + 1),
+            }
+        }
+        ColumnFill {
+            column,
+            cells: rows.len(),
+            runs,
+        }
+    }
+
+    /// Whether the two columns hold a cell on some row in common.
+    pub fn overlaps(&self, other: &ColumnFill) -> bool {
+        let (mut i, mut j) = (0, 0);
+        while i < self.runs.len() && j < other.runs.len() {
+            let (a, b) = (&self.runs[i], &other.runs[j]);
+            if a.start < b.end && b.start < a.end {
+                return true;
+            }
+            if a.end <= b.end {
+                i += 1;
+            } else {
+                j += 1;
+            }
+        }
+        false
+    }
+}
+
+/// The regions of one name, added up: each spans its rows over the columns it assigned.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct RegionFill {
+    pub name: String,
+    pub regions: usize,
+    pub rows: usize,
+    pub columns: Vec<String>,
+    /// Cells assigned, out of the `area` the regions' rows and columns span.
+    pub cells: usize,
+    pub area: usize,
+    /// Rows in the spans that got neither a cell nor a selector.
+    pub empty_rows: usize,
+}
+
+impl RegionFill {
+    fn add(&mut self, region: &AssignedRegion) {
+        let rows = region.rows();
+        let mut columns: Vec<String> = region
+            .cells
+            .iter()
+            .map(|(column, _)| column_name(column))
+            .collect();
+        columns.sort();
+        columns.dedup();
+        let occupied: BTreeSet<usize> = region
+            .cells
+            .iter()
+            .map(|(_, row)| *row)
+            .chain(region.selectors.iter().map(|(_, row)| *row))
+            .collect();
+        self.regions += 1;
+        self.rows += rows.len();
+        self.cells += region.cells.len();
+        self.area += rows.len() * columns.len();
+        self.empty_rows += rows.len() - occupied.len();
+        self.columns.extend(columns);
+        self.columns.sort();
+        self.columns.dedup();
+    }
+
+    pub fn empty_cells(&self) -> usize {
+        self.area - self.cells
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct UsageReport {
+    pub k: u32,
+    pub usable_rows: usize,
+    pub selectors: usize,
+    /// The fixed columns the circuit allocated itself; selector columns come after them.
+    pub fixed_columns: usize,
+    pub selector_columns: Vec<SelectorColumn>,
+    /// Selectors whose column couldn't be told, with the columns it is one of.
+    pub unplaced: Vec<(String, Vec<usize>)>,
+    /// Advice and fixed columns, selector columns excluded.
+    pub columns: Vec<ColumnFill>,
+    pub regions: Vec<RegionFill>,
+}
+
+/// The selectors `expression` queries, in their debug form, and the fixed columns.
+fn uses(expression: &Expression<Fr>) -> (BTreeSet<String>, BTreeSet<usize>) {
+    expression.evaluate(
+        &|_| Default::default(),
+        &|selector| ([format!("{:?}", selector)].into(), Default::default()),
+        &|query| (Default::default(), [query.column_index()].into()),
+        &|_| Default::default(),
+        &|_| Default::default(),
+        &|_| Default::default(),
+        &|a| a,
+        &|(mut a, mut b), (c, d)| {
+            a.extend(c);
+            b.extend(d);
+            (a, b)
+        },
+        &|(mut a, mut b), (c, d)| {
+            a.extend(c);
+            b.extend(d);
+            (a, b)
+        },
+        &|a, _| a,
+    )
+}
+
+/// The selectors and the fixed columns each gate, then each lookup, queries.
+fn constraint_uses(cs: &ConstraintSystem<Fr>) -> Vec<(BTreeSet<String>, BTreeSet<usize>)> {
+    let gates = cs
+        .gates()
+        .iter()
+        .map(|gate| gate.polynomials().iter().collect::<Vec<_>>());
+    let lookups = cs
+        .lookups()
+        .iter()
+        .map(|lookup| lookup.input_expressions().iter().collect());
+    gates
+        .chain(lookups)
+        .map(|expressions| {
+            expressions
+                .into_iter()
+                .map(uses)
+                .fold(Default::default(), |(mut a, mut b), (c, d)| {
+                    a.extend(c);
+                    b.extend(d);
+                    (a, b)
+                })
+        })
+        .collect()
+}
+
+impl UsageReport {
+    /// Reports on `circuit` at the smallest `k` it fits in.
+    pub fn measure<C: Circuit<Fr>>(circuit: &C) -> Result<Self, PlaygroundError> {
+        let k = fit_k(circuit)?;
+        let params = cached_params(k)?;
+        let vk = keygen_vk(&params, circuit).map_err(PlaygroundError::Keygen)?;
+        let instances = vec![vec![]; vk.cs.num_instance_columns()];
+        let assignments = Assignments::synthesize(k, circuit, instances)
+            .map_err(|e| PlaygroundError::Unprovable(format!("synthesize: {:?}", e)))?;
+        let cs = assignments.constraint_system();
+        let fixed_columns = cs.num_fixed_columns();
+        let all_regions = || {
+            assignments
+                .regions()
+                .iter()
+                .chain([assignments.outside_regions()])
+        };
+
+        let mut enabled: BTreeMap<String, BTreeSet<usize>> = BTreeMap::new();
+        for (selector, row) in all_regions().flat_map(|region| region.selectors.iter()) {
+            enabled
+                .entry(format!("{:?}", selector))
+                .or_default()
+                .insert(*row);
+        }
+        // each selector is in the columns of every constraint it was in
+        let mut candidates: HashMap<String, BTreeSet<usize>> = HashMap::new();
+        for ((selectors, _), (_, fixed)) in
+            constraint_uses(cs).into_iter().zip(constraint_uses(&vk.cs))
+        {
+            let appended: BTreeSet<usize> = fixed.range(fixed_columns..).copied().collect();
+            for selector in selectors {
+                let columns = candidates
+                    .entry(selector)
+                    .or_insert_with(|| appended.clone());
+                *columns = columns.intersection(&appended).copied().collect();
+            }
+        }
+        let rows = |selector: &str| enabled.get(selector).map_or(0, BTreeSet::len);
+        let mut selector_columns: Vec<SelectorColumn> = (fixed_columns..vk.cs.num_fixed_columns())
+            .map(|column| SelectorColumn {
+                column,
+                selectors: vec![],
+            })
+            .collect();
+        let mut unplaced = vec![];
+        let mut names: BTreeSet<String> = enabled.keys().cloned().collect();
+        names.extend(candidates.keys().cloned());
+        for name in names {
+            let columns = candidates.remove(&name).unwrap_or_default();
+            let first = columns.iter().next().copied();
+            match first {
+                Some(column) if columns.len() == 1 => selector_columns[column - fixed_columns]
+                    .selectors
+                    .push((name.clone(), rows(&name))),
+                _ => unplaced.push((name, columns.into_iter().collect())),
+            }
+        }
+
+        // advice columns sort first
+        let mut occupied: BTreeMap<(bool, usize), BTreeSet<usize>> = (0..cs.num_advice_columns())
+            .map(|index| (false, index))
+            .chain((0..fixed_columns).map(|index| (true, index)))
+            .map(|column| (column, BTreeSet::new()))
+            .collect();
+        for (column, row) in all_regions().flat_map(|region| region.cells.iter()) {
+            let fixed = *column.column_type() == Any::Fixed;
+            occupied
+                .entry((fixed, column.index()))
+                .or_default()
+                .insert(*row);
+        }
+        let columns = occupied
+            .iter()
+            .map(|((fixed, index), rows)| {
+                let prefix = if *fixed { "f" } else { "a" };
+                ColumnFill::new(format!("{}{}", prefix, index), rows)
+            })
+            .collect();
+
+        let mut regions: Vec<RegionFill> = vec![];
+        for region in all_regions() {
+            let index = match regions.iter().position(|fill| fill.name == region.name) {
+                Some(index) => index,
+                None => {
+                    regions.push(RegionFill {
+                        name: region.name.clone(),
+                        regions: 0,
+                        rows: 0,
+                        columns: vec![],
+                        cells: 0,
+                        area: 0,
+                        empty_rows: 0,
+                    });
+                    regions.len() - 1
+                }
+            };
+            regions[index].add(region);
+        }
+        regions.retain(|fill| fill.rows > 0);
+
+        Ok(UsageReport {
+            k,
+            usable_rows: assignments.usable_rows(),
+            selectors: cs.num_selectors(),
+            fixed_columns,
+            selector_columns,
+            unplaced,
+            columns,
+            regions,
+        })
+    }
+
+    pub fn fill_percent(&self, column: &ColumnFill) -> usize {
+        100 * column.cells / self.usable_rows.max(1)
+    }
+
+    /// Pairs of advice columns, and of fixed columns, that never hold a cell on the same row,
+    /// so one column could hold both, each column in one pair at most.
+    pub fn mergeable(&self) -> Vec<(String, String)> {
+        let mut pairs = vec![];
+        for prefix in ["a", "f"] {
+            let mut sparse: Vec<&ColumnFill> = self
+                .columns
+                .iter()
+                .filter(|column| column.column.starts_with(prefix) && column.cells > 0)
+                .filter(|column| self.fill_percent(column) < SPARSE_PERCENT)
+                .collect();
+            sparse.sort_by_key(|column| column.cells);
+            while let Some(column) = sparse.first().copied() {
+                sparse.remove(0);
+                if let Some(index) = sparse.iter().position(|other| !column.overlaps(other)) {
+                    let other = sparse.remove(index);
+                    pairs.push((column.column.clone(), other.column.clone()));
+                }
+            }
+        }
+        pairs
+    }
+
+    /// Where columns and rows could be saved.
+    pub fn suggestions(&self) -> Vec<String> {
+        let mut suggestions = vec![];
+        for column in self.columns.iter().filter(|column| column.cells == 0) {
+            suggestions.push(format!("{} holds no cells", column.column));
+        }
+        for (a, b) in self.mergeable() {
+            suggestions.push(format!(
+                "{} and {} never hold a cell on the same row: if the gates querying them can \
+                 share a column, one column holds both",
+                a, b
+            ));
+        }
+        for column in self.selector_columns.iter() {
+            if let [(selector, _)] = &column.selectors[..] {
+                if self.selector_columns.len() > 1 {
+                    suggestions.push(format!(
+                        "{} has f{} to itself: a lookup queries it, or it is enabled on a row \
+                         with each of the others",
+                        selector, column.column
+                    ));
+                }
+            }
+        }
+        for region in self.regions.iter() {
+            if region.area > 0 && 2 * region.empty_cells() > region.area {
+                suggestions.push(format!(
+                    "region \"{}\" leaves {} of its {} cells empty: its columns are used \
+                     unevenly, and a shorter column or a cell moved to another would save rows",
+                    region.name,
+                    region.empty_cells(),
+                    region.area
+                ));
+            }
+        }
+        suggestions
+    }
+}
+
+impl fmt::Display for UsageReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "  selectors: {} in {} fixed columns from f{}",
+            self.selectors,
+            self.selector_columns.len(),
+            self.fixed_columns
+        )?;
+        for column in self.selector_columns.iter() {
+            let selectors: Vec<String> = column
+                .selectors
+                .iter()
+                .map(|(selector, rows)| format!("{} on {} rows", selector, rows))
+                .collect();
+            writeln!(f, "    f{}: {}", column.column, selectors.join(", "))?;
+        }
+        for (selector, columns) in self.unplaced.iter() {
+            let columns: Vec<String> = columns.iter().map(|c| format!("f{}", c)).collect();
+            writeln!(
+                f,
+                "    {}: one of {}",
+                selector,
+                if columns.is_empty() {
+                    "none".to_string()
+                } else {
+                    columns.join(", ")
+                }
+            )?;
+        }
+        writeln!(
+            f,
+            "  columns: {} usable rows, k = {}",
+            self.usable_rows, self.k
+        )?;
+        for column in self.columns.iter() {
+            writeln!(
+                f,
+                "    {}: {} cells ({}%) in {} runs",
+                column.column,
+                column.cells,
+                self.fill_percent(column),
+                column.runs.len()
+            )?;
+        }
+        write!(f, "  regions:")?;
+        for region in self.regions.iter() {
+            write!(
+                f,
+                "\n    \"{}\" x{}: {} rows over {}, {} of {} cells, {} empty rows",
+                region.name,
+                region.regions,
+                region.rows,
+                region.columns.join(" "),
+                region.cells,
+                region.area,
+                region.empty_rows
+            )?;
+        }
+        for suggestion in self.suggestions() {
+            write!(f, "\n  hint: {}", suggestion)?;
+        }
+        Ok(())
+    }
+}
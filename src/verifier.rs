@@ -0,0 +1,384 @@
+use std::fmt;
+
+use halo2_proofs::{
+    halo2curves::{
+        bn256::{Bn256, Fr, G1Affine},
+        group::GroupEncoding,
+    },
+    plonk::{verify_proof, Error, VerifyingKey},
+    poly::{
+        commitment::{Params, Verifier},
+        kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::{VerifierGWC, VerifierSHPLONK},
+            strategy::{AccumulatorStrategy, SingleStrategy},
+        },
+        VerificationStrategy,
+    },
+    transcript::Challenge255,
+};
+
+use crate::{
+    encoding::encode_hex,
+    metrics::{self, Phase},
+    prover::{MultiOpen, ProofOptions, TranscriptHash},
+    transcript_registry::AnyRead,
+};
+
+/// What a verification found. Only `Verified` accepts the proof; the failures say whose
+/// mistake it likely was, so a server can tell a malformed upload from a wrong statement.
+#[derive(Debug)]
+pub enum VerificationReport {
+    Verified,
+    /// The proof doesn't parse: it is truncated, or holds a point or scalar that isn't a
+    /// canonical encoding.
+    MalformedProof(Error),
+    /// The instances `verify_proof` rejected, where their shapes weren't at hand to report.
+    MalformedInstances(Error),
+    /// The instances don't have the verifying key's column count, or a column is longer than
+    /// its usable rows.
+    InstanceShape {
+        expected_columns: usize,
+        max_len: usize,
+        lens: Vec<usize>,
+    },
+    /// The params and the verifying key are for different `k`.
+    KeyMismatch {
+        params_k: u32,
+        vk_k: u32,
+    },
+    /// The proof declares it was made for another verifying key or SRS, by [`vk_digest`].
+    DigestMismatch {
+        declared: [u8; 32],
+        expected: [u8; 32],
+    },
+    /// A well-formed proof that fails the checks: the statement is false, or the proof was
+    /// made under another key or with other options.
+    Rejected(Error),
+}
+
+impl VerificationReport {
+    pub fn is_verified(&self) -> bool {
+        matches!(self, VerificationReport::Verified)
+    }
+
+    /// `Ok` when verified, the failure otherwise, for `?` and `expect`.
+    pub fn into_result(self) -> Result<(), VerificationReport> {
+        match self {
+            VerificationReport::Verified => Ok(()),
+            failure => Err(failure),
+        }
+    }
+
+    /// The instance shape failure of `instances` against `vk`, if any.
+    fn check_shape(vk: &VerifyingKey<G1Affine>, instances: &[&[Fr]]) -> Option<Self> {
+        let expected_columns = vk.cs.num_instance_columns();
+        let max_len = (1 << vk.domain.k()) - (vk.cs.blinding_factors() + 1);
+        if instances.len() == expected_columns && instances.iter().all(|c| c.len() <= max_len) {
+            return None;
+        }
+        Some(VerificationReport::InstanceShape {
+            expected_columns,
+            max_len,
+            lens: instances.iter().map(|column| column.len()).collect(),
+        })
+    }
+
+    /// The key mismatch or instance shape failure of `instances`, one entry per proof, if any.
+    fn check_statement(
+        params: &ParamsKZG<Bn256>,
+        vk: &VerifyingKey<G1Affine>,
+        instances: &[&[&[Fr]]],
+    ) -> Option<Self> {
+        if params.k() != vk.domain.k() {
+            return Some(VerificationReport::KeyMismatch {
+                params_k: params.k(),
+                vk_k: vk.domain.k(),
+            });
+        }
+        if instances.is_empty() {
+            return Some(Error::InvalidInstances.into());
+        }
+        instances
+            .iter()
+            .find_map(|columns| VerificationReport::check_shape(vk, columns))
+    }
+}
+
+/// Classifies an error of `verify_proof`.
+impl From<Error> for VerificationReport {
+    fn from(error: Error) -> Self {
+        match error {
+            Error::Transcript(_) => VerificationReport::MalformedProof(error),
+            Error::InvalidInstances | Error::InstanceTooLarge => {
+                VerificationReport::MalformedInstances(error)
+            }
+            error => VerificationReport::Rejected(error),
+        }
+    }
+}
+
+impl fmt::Display for VerificationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            VerificationReport::Verified => write!(f, "verified"),
+            VerificationReport::MalformedProof(err) => write!(f, "malformed proof: {:?}", err),
+            VerificationReport::MalformedInstances(err) => {
+                write!(f, "malformed instances: {:?}", err)
+            }
+            VerificationReport::InstanceShape {
+                expected_columns,
+                max_len,
+                lens,
+            } => write!(
+                f,
+                "instance columns of lengths {:?}, expected {} columns of at most {} values",
+                lens, expected_columns, max_len
+            ),
+            VerificationReport::KeyMismatch { params_k, vk_k } => write!(
+                f,
+                "params for k = {}, verifying key for k = {}",
+                params_k, vk_k
+            ),
+            VerificationReport::DigestMismatch { declared, expected } => write!(
+                f,
+                "the proof was made for verifying key {}, the vk and params given are {}; \
+                 are they the files it was made with?",
+                encode_hex(declared),
+                encode_hex(expected)
+            ),
+            VerificationReport::Rejected(err) => write!(f, "proof rejected: {:?}", err),
+        }
+    }
+}
+
+impl std::error::Error for VerificationReport {}
+
+/// Identifies what a proof can be verified against: Blake2b over `k`, the SRS's `[1]_2` and
+/// `[s]_2`, and the serialized `vk`. Unlike [`crate::audit_log::vk_fingerprint`] it tells
+/// apart the same key under two ceremonies.
+pub fn vk_digest(params: &ParamsKZG<Bn256>, vk: &VerifyingKey<G1Affine>) -> [u8; 32] {
+    let mut state = blake2b_simd::Params::new()
+        .hash_length(32)
+        .personal(b"Vk-Digest")
+        .to_state();
+    state.update(&vk.domain.k().to_le_bytes());
+    state.update(params.g2().to_bytes().as_ref());
+    state.update(params.s_g2().to_bytes().as_ref());
+    let mut bytes = vec![];
+    vk.write(&mut bytes).expect("writing to a Vec doesn't fail");
+    state.update(&bytes);
+    let mut digest = [0; 32];
+    digest.copy_from_slice(state.finalize().as_bytes());
+    digest
+}
+
+/// `DigestMismatch` unless a proof `declared` for [`vk_digest`] of `params` and `vk`.
+pub fn check_digest(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    declared: &[u8; 32],
+) -> Result<(), VerificationReport> {
+    let expected = vk_digest(params, vk);
+    if &expected == declared {
+        Ok(())
+    } else {
+        Err(VerificationReport::DigestMismatch {
+            declared: *declared,
+            expected,
+        })
+    }
+}
+
+/// Cheap half of verification: replays the transcript and checks the proof is well formed,
+/// deferring the final MSM and pairing into the returned accumulator. Passing the accumulator
+/// of a previous call batches the expensive part across several proofs, all made with the
+/// same `options.multiopen`. The instances are checked against `vk` first, so a shape
+/// failure reports the real column lengths.
+pub fn precheck_proof<'params>(
+    params: &'params ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    strategy: AccumulatorStrategy<'params, Bn256>,
+    instances: &[&[&[Fr]]],
+    proof: &[u8],
+    options: ProofOptions,
+) -> Result<AccumulatorStrategy<'params, Bn256>, VerificationReport> {
+    if let Some(report) = VerificationReport::check_statement(params, vk, instances) {
+        return Err(report);
+    }
+    let result = match options.multiopen {
+        MultiOpen::Shplonk => precheck_with::<VerifierSHPLONK<'params, Bn256>>(
+            params,
+            vk,
+            strategy,
+            instances,
+            proof,
+            options.transcript,
+        ),
+        MultiOpen::Gwc => precheck_with::<VerifierGWC<'params, Bn256>>(
+            params,
+            vk,
+            strategy,
+            instances,
+            proof,
+            options.transcript,
+        ),
+    };
+    result.map_err(VerificationReport::from)
+}
+
+fn precheck_with<'params, V>(
+    params: &'params ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    strategy: AccumulatorStrategy<'params, Bn256>,
+    instances: &[&[&[Fr]]],
+    proof: &[u8],
+    transcript: TranscriptHash,
+) -> Result<AccumulatorStrategy<'params, Bn256>, Error>
+where
+    V: Verifier<'params, KZGCommitmentScheme<Bn256>>,
+    AccumulatorStrategy<'params, Bn256>: VerificationStrategy<
+        'params,
+        KZGCommitmentScheme<Bn256>,
+        V,
+        Output = AccumulatorStrategy<'params, Bn256>,
+    >,
+{
+    let mut transcript = transcript.reader(proof);
+    verify_proof::<
+        KZGCommitmentScheme<Bn256>,
+        V,
+        Challenge255<G1Affine>,
+        AnyRead<'_>,
+        AccumulatorStrategy<'params, Bn256>,
+    >(params, vk, strategy, instances, &mut transcript)
+}
+
+/// Expensive half of verification: the MSM and pairing over everything accumulated by
+/// `precheck_proof` with `multiopen`.
+pub fn final_check<'params>(
+    strategy: AccumulatorStrategy<'params, Bn256>,
+    multiopen: MultiOpen,
+) -> bool {
+    match multiopen {
+        MultiOpen::Shplonk => <AccumulatorStrategy<'params, Bn256> as VerificationStrategy<
+            'params,
+            KZGCommitmentScheme<Bn256>,
+            VerifierSHPLONK<'params, Bn256>,
+        >>::finalize(strategy),
+        MultiOpen::Gwc => <AccumulatorStrategy<'params, Bn256> as VerificationStrategy<
+            'params,
+            KZGCommitmentScheme<Bn256>,
+            VerifierGWC<'params, Bn256>,
+        >>::finalize(strategy),
+    }
+}
+
+/// Runs `precheck_proof` over all proofs, made with the same `options`, then a single
+/// `final_check`. A rejected batch counts as one failure in [`metrics::global`].
+pub fn verify_split(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    proofs: &[(&[&[&[Fr]]], &[u8])],
+    options: ProofOptions,
+) -> Result<bool, VerificationReport> {
+    let metrics = metrics::global();
+    let result = metrics.time(Phase::Verify, || {
+        let mut strategy = AccumulatorStrategy::new(params);
+        for (instances, proof) in proofs.iter() {
+            strategy = precheck_proof(params, vk, strategy, instances, proof, options)?;
+        }
+        Ok(final_check(strategy, options.multiopen))
+    });
+    if !matches!(result, Ok(true)) {
+        metrics.verification_failed();
+    }
+    result
+}
+
+fn verify_with<'params, V>(
+    params: &'params ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    instances: &[&[&[Fr]]],
+    proof: &[u8],
+    transcript: TranscriptHash,
+) -> Result<(), Error>
+where
+    V: Verifier<'params, KZGCommitmentScheme<Bn256>>,
+    SingleStrategy<'params, Bn256>:
+        VerificationStrategy<'params, KZGCommitmentScheme<Bn256>, V, Output = ()>,
+{
+    let mut transcript = transcript.reader(proof);
+    verify_proof::<
+        KZGCommitmentScheme<Bn256>,
+        V,
+        Challenge255<G1Affine>,
+        AnyRead<'_>,
+        SingleStrategy<'params, Bn256>,
+    >(
+        params,
+        vk,
+        SingleStrategy::new(params),
+        instances,
+        &mut transcript,
+    )
+}
+
+/// Verifies a proof made by `prover::run_proof` with the same `options`.
+pub fn check_proof(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    instances: &[&[Fr]],
+    proof: &[u8],
+    options: ProofOptions,
+) -> VerificationReport {
+    check_multi_proof(params, vk, &[instances], proof, options)
+}
+
+/// Verifies a proof made by `prover::run_multi_proof`, with one entry of `instances` per
+/// proven circuit in the order they were proven. The time taken, and any failure, are recorded
+/// in [`metrics::global`].
+pub fn check_multi_proof(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    instances: &[&[&[Fr]]],
+    proof: &[u8],
+    options: ProofOptions,
+) -> VerificationReport {
+    let metrics = metrics::global();
+    let report = metrics.time(Phase::Verify, || {
+        check_untimed(params, vk, instances, proof, options)
+    });
+    if !matches!(report, VerificationReport::Verified) {
+        metrics.verification_failed();
+    }
+    report
+}
+
+fn check_untimed(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    instances: &[&[&[Fr]]],
+    proof: &[u8],
+    options: ProofOptions,
+) -> VerificationReport {
+    if let Some(report) = VerificationReport::check_statement(params, vk, instances) {
+        return report;
+    }
+    let result = match options.multiopen {
+        MultiOpen::Shplonk => verify_with::<VerifierSHPLONK<'_, Bn256>>(
+            params,
+            vk,
+            instances,
+            proof,
+            options.transcript,
+        ),
+        MultiOpen::Gwc => {
+            verify_with::<VerifierGWC<'_, Bn256>>(params, vk, instances, proof, options.transcript)
+        }
+    };
+    match result {
+        Ok(()) => VerificationReport::Verified,
+        Err(error) => error.into(),
+    }
+}
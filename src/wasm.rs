@@ -0,0 +1,13 @@
+//! The byte-slice verifier of [`crate::onchain`] exported to JavaScript, so the playground's
+//! Poseidon proofs can be checked in a browser. Build with `wasm-pack build -- --features wasm`;
+//! JS passes `Uint8Array`s.
+
+use wasm_bindgen::prelude::wasm_bindgen;
+
+use crate::onchain;
+
+/// `onchain::verify` with the arguments in the order the CLI's files are usually listed.
+#[wasm_bindgen]
+pub fn verify_proof_wasm(params: &[u8], vk: &[u8], instances: &[u8], proof: &[u8]) -> bool {
+    onchain::verify(vk, params, instances, proof)
+}
@@ -0,0 +1,202 @@
+//! Witness completion: a circuit declares which of its witness values follow from others, e.g.
+//! products, inverses and bit decompositions, and [`WitnessPlan::complete`] computes them from
+//! the free inputs. Witness files then list only the free inputs, in the order of the
+//! circuit's `WitnessLayout`.
+
+use std::fmt;
+
+use halo2_proofs::{arithmetic::FieldExt, circuit::Value};
+
+use crate::schema::WitnessLayout;
+
+type Compute<F> = Box<dyn Fn(&[F]) -> Result<Vec<F>, String> + Send + Sync>;
+
+struct Derivation<F> {
+    outputs: Vec<String>,
+    inputs: Vec<usize>,
+    compute: Compute<F>,
+}
+
+/// The free inputs of a witness and the values derived from them, in declaration order.
+pub struct WitnessPlan<F> {
+    names: Vec<String>,
+    free: usize,
+    derivations: Vec<Derivation<F>>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum CompletionError {
+    Count {
+        expected: usize,
+        found: usize,
+    },
+    /// A derivation rejected its inputs, e.g. the inverse of zero.
+    Derivation {
+        outputs: Vec<String>,
+        error: String,
+    },
+}
+
+impl fmt::Display for CompletionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CompletionError::Count { expected, found } => {
+                write!(f, "expected {} free inputs, found {}", expected, found)
+            }
+            CompletionError::Derivation { outputs, error } => {
+                write!(f, "deriving {}: {}", outputs.join(", "), error)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CompletionError {}
+
+impl<F: FieldExt> WitnessPlan<F> {
+    /// A plan whose free inputs are the fields of `layout`.
+    pub fn new(layout: &WitnessLayout) -> Self {
+        let names: Vec<String> = layout.fields.iter().map(|f| f.name.clone()).collect();
+        Self {
+            free: names.len(),
+            names,
+            derivations: vec![],
+        }
+    }
+
+    fn index(&self, name: &str) -> usize {
+        self.names
+            .iter()
+            .position(|n| n == name)
+            .unwrap_or_else(|| panic!("witness value `{}` is not declared before its use", name))
+    }
+
+    /// Declares `outputs` as computed from `inputs`, which must be free inputs or earlier
+    /// outputs. `compute` returns one value per output.
+    pub fn derive_many(
+        mut self,
+        outputs: &[&str],
+        inputs: &[&str],
+        compute: impl Fn(&[F]) -> Result<Vec<F>, String> + Send + Sync + 'static,
+    ) -> Self {
+        let inputs = inputs.iter().map(|name| self.index(name)).collect();
+        for output in outputs.iter() {
+            assert!(
+                !self.names.iter().any(|n| n == output),
+                "witness value `{}` declared twice",
+                output
+            );
+            self.names.push(output.to_string());
+        }
+        self.derivations.push(Derivation {
+            outputs: outputs.iter().map(|o| o.to_string()).collect(),
+            inputs,
+            compute: Box::new(compute),
+        });
+        self
+    }
+
+    pub fn derive(
+        self,
+        output: &str,
+        inputs: &[&str],
+        compute: impl Fn(&[F]) -> Result<F, String> + Send + Sync + 'static,
+    ) -> Self {
+        self.derive_many(&[output], inputs, move |values| Ok(vec![compute(values)?]))
+    }
+
+    /// `output = lhs * rhs`.
+    pub fn product(self, output: &str, lhs: &str, rhs: &str) -> Self {
+        self.derive(output, &[lhs, rhs], |v| Ok(v[0] * v[1]))
+    }
+
+    /// `output = input^-1`, failing for zero.
+    pub fn inverse(self, output: &str, input: &str) -> Self {
+        self.derive(output, &[input], |v| {
+            Option::from(v[0].invert()).ok_or_else(|| "zero has no inverse".to_string())
+        })
+    }
+
+    /// `output = input^-1`, or zero for zero, the witness of an is-zero check.
+    pub fn inverse_or_zero(self, output: &str, input: &str) -> Self {
+        self.derive(output, &[input], |v| {
+            Ok(v[0].invert().unwrap_or_else(F::zero))
+        })
+    }
+
+    /// `bits` little-endian bits of `input`, named `prefix0`, `prefix1`, ..., failing when
+    /// `input` has more.
+    pub fn bits(self, prefix: &str, input: &str, bits: usize) -> Self {
+        let outputs: Vec<String> = (0..bits).map(|i| format!("{}{}", prefix, i)).collect();
+        let outputs: Vec<&str> = outputs.iter().map(String::as_str).collect();
+        self.derive_many(&outputs, &[input], move |v| {
+            let repr = v[0].to_repr();
+            let bytes = repr.as_ref();
+            let bit = |i: usize| (bytes[i / 8] >> (i % 8)) & 1 == 1;
+            if (bits..8 * bytes.len()).any(bit) {
+                return Err(format!("does not fit in {} bits", bits));
+            }
+            Ok((0..bits).map(|i| F::from(bit(i))).collect())
+        })
+    }
+
+    /// Free inputs then derived values, in declaration order.
+    pub fn names(&self) -> &[String] {
+        &self.names
+    }
+
+    pub fn complete(&self, free: &[F]) -> Result<Witness<F>, CompletionError> {
+        if free.len() != self.free {
+            return Err(CompletionError::Count {
+                expected: self.free,
+                found: free.len(),
+            });
+        }
+        let mut values = free.to_vec();
+        for derivation in self.derivations.iter() {
+            let inputs: Vec<F> = derivation.inputs.iter().map(|&i| values[i]).collect();
+            let error = |error| CompletionError::Derivation {
+                outputs: derivation.outputs.clone(),
+                error,
+            };
+            let outputs = (derivation.compute)(&inputs).map_err(error)?;
+            if outputs.len() != derivation.outputs.len() {
+                return Err(error(format!(
+                    "{} values computed for {} outputs",
+                    outputs.len(),
+                    derivation.outputs.len()
+                )));
+            }
+            values.extend(outputs);
+        }
+        Ok(Witness {
+            names: self.names.clone(),
+            values,
+        })
+    }
+}
+
+/// A completed witness, by name.
+#[derive(Clone, Debug)]
+pub struct Witness<F> {
+    names: Vec<String>,
+    values: Vec<F>,
+}
+
+impl<F: FieldExt> Witness<F> {
+    pub fn get(&self, name: &str) -> Option<F> {
+        let index = self.names.iter().position(|n| n == name)?;
+        Some(self.values[index])
+    }
+
+    /// The value for a circuit field. Panics on a name the plan doesn't declare.
+    pub fn value(&self, name: &str) -> Value<F> {
+        Value::known(
+            self.get(name)
+                .unwrap_or_else(|| panic!("no witness value `{}`", name)),
+        )
+    }
+
+    pub fn values(&self) -> &[F] {
+        &self.values
+    }
+}
@@ -0,0 +1,72 @@
+//! Every backend `accel` can run on commits and transforms as halo2 does, at a `k` large enough
+//! for the device path; with `--features gpu` on a machine with a CUDA device, that's the GPU.
+
+use halo2_playground::{
+    accel::{self, Bases, GPU_MIN_LEN},
+    entropy::SeededEntropy,
+    storage::cached_params,
+};
+use halo2_proofs::{
+    arithmetic::{best_multiexp, Field},
+    halo2curves::bn256::Fr,
+    poly::{
+        commitment::{Blind, Params},
+        EvaluationDomain,
+    },
+};
+
+const SEED: u64 = 0x5962_be5d_763d_318d;
+const K: u32 = 13;
+
+fn random_values(len: usize) -> Vec<Fr> {
+    let mut rng = SeededEntropy::new(SEED);
+    (0..len).map(|_| Fr::random(&mut rng)).collect()
+}
+
+#[test]
+fn backends_commit_as_the_params_do() {
+    let params = cached_params(K).expect("cached_params");
+    let bases = Bases::new(&params).expect("Lagrange bases");
+    assert_eq!(bases.points().len(), params.n() as usize);
+    let domain = EvaluationDomain::<Fr>::new(1, K);
+    let values = random_values(params.n() as usize);
+    let expected =
+        params.commit_lagrange(&domain.lagrange_from_vec(values.clone()), Blind::default());
+    for backend in accel::backends() {
+        assert_eq!(backend.commit(&bases, &values), expected, "{:?}", backend);
+        assert_eq!(
+            backend.msm(&values, bases.points()),
+            expected,
+            "{:?}",
+            backend
+        );
+        // a short column is the full one with zeros after it
+        let short = &values[..GPU_MIN_LEN];
+        assert_eq!(
+            backend.commit(&bases, short),
+            best_multiexp(short, &bases.points()[..short.len()]),
+            "{:?}",
+            backend
+        );
+    }
+}
+
+#[test]
+fn backends_transform_as_the_domain_does() {
+    let domain = EvaluationDomain::<Fr>::new(1, K);
+    let values = random_values(1 << K);
+    let coeffs = domain.lagrange_to_coeff(domain.lagrange_from_vec(values.clone()));
+    for backend in accel::backends() {
+        let transformed = backend.lagrange_to_coeff(&domain, values.clone());
+        assert_eq!(&transformed[..], &coeffs[..], "{:?}", backend);
+        let back = backend.coeff_to_lagrange(&domain, transformed.to_vec());
+        assert_eq!(&back[..], &values[..], "{:?}", backend);
+    }
+    // fewer values are padded with zeros
+    let mut padded = values[..3].to_vec();
+    padded.resize(1 << K, Fr::zero());
+    assert_eq!(
+        &accel::backend().lagrange_to_coeff(&domain, values[..3].to_vec())[..],
+        &domain.lagrange_to_coeff(domain.lagrange_from_vec(padded))[..]
+    );
+}
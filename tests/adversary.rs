@@ -0,0 +1,272 @@
+//! Malicious provers: synthesize implementations that skip selectors, leave coefficients
+//! unassigned or break copies, proving under the keys of the honest circuit. Selectors, fixed
+//! columns and copies are fixed at keygen, so nothing the prover's code does can opt out of
+//! them, and the real verifier rejects every forgery. Each attack also has an honest control
+//! that must verify, so a rejection can't come from the harness itself.
+
+use std::marker::PhantomData;
+
+use halo2_playground::{
+    audit::fit_k,
+    circuits::{
+        fibonacci::{FibonacciCircuit, FibonacciConfig},
+        mul::{MulCircuit, MulConfig},
+    },
+    entropy::SeededEntropy,
+    prover::{run_proof, ProofOptions},
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{Layouter, Value},
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk, Circuit, ConstraintSystem, Error},
+};
+
+const N: usize = 16;
+type Fibonacci = FibonacciCircuit<Fr, N>;
+
+/// A synthesize run in place of `C`'s, under `C`'s configuration.
+trait Attack<C: Circuit<Fr>>: Clone {
+    fn synthesize(&self, config: C::Config, layouter: impl Layouter<Fr>) -> Result<(), Error>;
+}
+
+#[derive(Clone)]
+struct Malicious<C, A> {
+    attack: A,
+    _marker: PhantomData<C>,
+}
+
+impl<C: Circuit<Fr> + Clone, A: Attack<C>> Circuit<Fr> for Malicious<C, A> {
+    type Config = C::Config;
+    type FloorPlanner = C::FloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        C::configure(meta)
+    }
+
+    fn synthesize(&self, config: Self::Config, layouter: impl Layouter<Fr>) -> Result<(), Error> {
+        self.attack.synthesize(config, layouter)
+    }
+}
+
+/// Whether the verifier accepts a proof of `instance` made by `attack` with the proving key of
+/// `honest`.
+fn accepted<C: Circuit<Fr> + Clone, A: Attack<C>>(honest: &C, attack: A, instance: &[Fr]) -> bool {
+    let params = cached_params(fit_k(honest).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, honest).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, honest).expect("keygen_pk");
+    let malicious = Malicious::<C, A> {
+        attack,
+        _marker: PhantomData,
+    };
+    let proof = run_proof(
+        &params,
+        &pk,
+        malicious,
+        &[instance],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("the prover checks nothing, so even a forgery makes a proof");
+    check_proof(
+        &params,
+        pk.get_vk(),
+        &[instance],
+        &proof,
+        ProofOptions::default(),
+    )
+    .is_verified()
+}
+
+/// The Fibonacci layout with chosen values: `start` for rows 0 and 1, then the sum of the two
+/// rows above, with `last` in place of row `N` if given.
+#[derive(Clone)]
+struct FibonacciAttack {
+    start: [Fr; 2],
+    /// Copy rows 0 and 1 from the instance, or assign `start` without the copy.
+    copy_start: bool,
+    enable_selectors: bool,
+    last: Option<Fr>,
+}
+
+impl Attack<Fibonacci> for FibonacciAttack {
+    fn synthesize(
+        &self,
+        config: FibonacciConfig,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let last = layouter.assign_region(
+            || "sequence",
+            |mut region| {
+                let mut cells = vec![];
+                for (row, value) in self.start.iter().enumerate() {
+                    cells.push(if self.copy_start {
+                        region.assign_advice_from_instance(
+                            || "f",
+                            config.instance,
+                            row,
+                            config.advice,
+                            row,
+                        )?
+                    } else {
+                        region.assign_advice(|| "f", config.advice, row, || Value::known(*value))?
+                    });
+                }
+                let mut values = self.start.to_vec();
+                for row in 2..=N {
+                    if self.enable_selectors {
+                        config.q_fib.enable(&mut region, row - 2)?;
+                    }
+                    let mut value = values[row - 1] + values[row - 2];
+                    if row == N {
+                        value = self.last.unwrap_or(value);
+                    }
+                    values.push(value);
+                    cells.push(region.assign_advice(
+                        || "f",
+                        config.advice,
+                        row,
+                        || Value::known(value),
+                    )?);
+                }
+                Ok(cells.pop().unwrap())
+            },
+        )?;
+        layouter.constrain_instance(last.cell(), config.instance, 2)
+    }
+}
+
+fn honest_fibonacci() -> FibonacciAttack {
+    FibonacciAttack {
+        start: [Fr::zero(), Fr::one()],
+        copy_start: true,
+        enable_selectors: true,
+        last: None,
+    }
+}
+
+#[test]
+fn fibonacci_forgeries_are_rejected() {
+    let honest = Fibonacci::new();
+    let (f0, f1) = (Fr::zero(), Fr::one());
+    let instance = Fibonacci::instance(f0, f1);
+    let f_n = instance[2];
+    let claim = vec![f0, f1, f_n + Fr::one()];
+    assert!(accepted(&honest, honest_fibonacci(), &instance));
+
+    // the last value replaced, hoping the gate above it is off without its selector
+    let skipped = FibonacciAttack {
+        enable_selectors: false,
+        last: Some(claim[2]),
+        ..honest_fibonacci()
+    };
+    assert!(!accepted(&honest, skipped.clone(), &claim));
+    // the selectors alone change nothing: this is the honest witness
+    let skipped_only = FibonacciAttack {
+        last: None,
+        ..skipped
+    };
+    assert!(accepted(&honest, skipped_only, &instance));
+
+    // a sequence satisfying every gate and reaching the claim, from a first value that isn't
+    // the public f0: f(N) is linear in f0, so solve for it
+    let from = |x: Fr| Fibonacci::sequence(x, f1)[N];
+    let slope = from(Fr::one()) - from(Fr::zero());
+    let x = (claim[2] - from(Fr::zero())) * slope.invert().unwrap();
+    assert_eq!(from(x), claim[2]);
+    let uncopied = FibonacciAttack {
+        start: [x, f1],
+        copy_start: false,
+        ..honest_fibonacci()
+    };
+    assert!(!accepted(&honest, uncopied.clone(), &claim));
+    // leaving the copy out of the code path doesn't remove it: with x as the public f0 it
+    // holds, and this is an honest proof of that statement
+    assert!(accepted(&honest, uncopied, &[x, f1, claim[2]]));
+}
+
+/// The multiplication row with chosen operands: copies of the loaded `a` and `b`, or fresh
+/// cells holding `operands`, and with or without its coefficients.
+#[derive(Clone)]
+struct MulAttack {
+    a: Fr,
+    b: Fr,
+    operands: Option<[Fr; 2]>,
+    coefficients: bool,
+    out: Fr,
+}
+
+impl Attack<MulCircuit<Fr>> for MulAttack {
+    fn synthesize(&self, config: MulConfig, mut layouter: impl Layouter<Fr>) -> Result<(), Error> {
+        let plonk = &config.plonk;
+        let mut loaded = vec![];
+        for value in [self.a, self.b] {
+            loaded.push(layouter.assign_region(
+                || "witness",
+                |mut region| region.assign_advice(|| "witness", plonk.a, 0, || Value::known(value)),
+            )?);
+        }
+        let out = layouter.assign_region(
+            || "binary",
+            |mut region| {
+                if self.coefficients {
+                    for (column, value) in [(plonk.q_m, Fr::one()), (plonk.q_o, -Fr::one())] {
+                        region.assign_fixed(|| "coefficient", column, 0, || Value::known(value))?;
+                    }
+                }
+                match self.operands {
+                    Some([lhs, rhs]) => {
+                        region.assign_advice(|| "lhs", plonk.a, 0, || Value::known(lhs))?;
+                        region.assign_advice(|| "rhs", plonk.b, 0, || Value::known(rhs))?;
+                    }
+                    None => {
+                        loaded[0].copy_advice(|| "lhs", &mut region, plonk.a, 0)?;
+                        loaded[1].copy_advice(|| "rhs", &mut region, plonk.b, 0)?;
+                    }
+                }
+                region.assign_advice(|| "out", plonk.c, 0, || Value::known(self.out))
+            },
+        )?;
+        layouter.constrain_instance(out.cell(), config.instance, 1)
+    }
+}
+
+#[test]
+fn multiplication_forgeries_are_rejected() {
+    let (a, b) = (Fr::from(3), Fr::from(5));
+    let honest = MulCircuit::new(a, b);
+    let control = MulAttack {
+        a,
+        b,
+        operands: None,
+        coefficients: true,
+        out: a * b,
+    };
+    assert!(accepted(
+        &honest,
+        control.clone(),
+        &MulCircuit::instance(a * b)
+    ));
+
+    // 3 and 5 loaded, but 2 * 8 multiplied: the row holds, the copies don't
+    let claim = MulCircuit::instance(Fr::from(16));
+    let uncopied = MulAttack {
+        operands: Some([Fr::from(2), Fr::from(8)]),
+        out: Fr::from(16),
+        ..control.clone()
+    };
+    assert!(!accepted(&honest, uncopied, &claim));
+    // the row without its coefficients, as if the gate were off
+    let gate_off = MulAttack {
+        coefficients: false,
+        out: Fr::from(16),
+        ..control
+    };
+    assert!(!accepted(&honest, gate_off, &claim));
+}
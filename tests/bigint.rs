@@ -0,0 +1,254 @@
+//! The big-integer chip under `MockProver`: each operation accepts its result, edge cases
+//! included, and rejects any other, unreduced results among them.
+
+#![cfg(feature = "gadgets-nonnative")]
+
+use halo2_playground::{
+    audit::fit_k,
+    chips::{
+        bigint::{to_limbs, BigIntChip, BigIntConfig},
+        tables::Tables,
+    },
+};
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{Circuit, Column, ConstraintSystem, Error, Instance},
+};
+use num_bigint::BigUint;
+
+#[derive(Clone, Copy, Debug)]
+enum Op {
+    Add,
+    AddMod,
+    MulMod,
+    Reduce,
+    LessThan,
+}
+
+#[derive(Clone, Debug)]
+struct Config {
+    tables: Tables,
+    bigint: BigIntConfig,
+    instance: Column<Instance>,
+}
+
+/// `op` over `a`, `b` and the modulus `p`, its result constrained to the instance limbs.
+#[derive(Clone, Debug)]
+struct BigIntCircuit<const N: usize> {
+    op: Op,
+    a: BigUint,
+    b: BigUint,
+    p: BigUint,
+}
+
+/// Limbs of the operands; a reduced operand is twice as wide.
+fn width<const N: usize>(op: Op) -> usize {
+    match op {
+        Op::Reduce => 2 * N,
+        _ => N,
+    }
+}
+
+impl<const N: usize> Circuit<Fr> for BigIntCircuit<N> {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let constants = meta.fixed_column();
+        meta.enable_constant(constants);
+        let instance = meta.instance_column();
+        meta.enable_equality(instance);
+        let mut tables = Tables::new();
+        let bigint = BigIntChip::<Fr, N>::configure(meta, &mut tables);
+        Config {
+            tables,
+            bigint,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        config.tables.load(&mut layouter)?;
+        let chip = BigIntChip::<Fr, N>::construct(config.bigint);
+        let known = |value: &BigUint| Value::known(value.clone());
+        let a = chip.witness(
+            layouter.namespace(|| "a"),
+            known(&self.a),
+            width::<N>(self.op),
+        )?;
+        let b = chip.witness(layouter.namespace(|| "b"), known(&self.b), N)?;
+        let p = chip.witness(layouter.namespace(|| "p"), known(&self.p), N)?;
+        let result = match self.op {
+            Op::Add => chip.add(layouter.namespace(|| "add"), &a, &b)?,
+            Op::AddMod => chip.add_mod(layouter.namespace(|| "add mod"), &a, &b, &p)?,
+            Op::MulMod => chip.mul_mod(layouter.namespace(|| "mul mod"), &a, &b, &p)?,
+            Op::Reduce => chip.reduce(layouter.namespace(|| "reduce"), &a, &p)?,
+            Op::LessThan => return chip.assert_less_than(layouter.namespace(|| "lt"), &a, &b),
+        };
+        let expected = chip.from_instance(
+            layouter.namespace(|| "expected"),
+            config.instance,
+            0,
+            result.limbs.len(),
+        )?;
+        chip.assert_equal(layouter.namespace(|| "equal"), &result, &expected)
+    }
+}
+
+fn holds<const N: usize>(
+    op: Op,
+    a: &BigUint,
+    b: &BigUint,
+    p: &BigUint,
+    expected: &BigUint,
+) -> bool {
+    let circuit = BigIntCircuit::<N> {
+        op,
+        a: a.clone(),
+        b: b.clone(),
+        p: p.clone(),
+    };
+    let limbs = match op {
+        Op::Add => N + 1,
+        Op::LessThan => 0,
+        _ => N,
+    };
+    let instance = to_limbs::<Fr>(expected, limbs);
+    let k = fit_k::<Fr, _>(&circuit).expect("fit_k");
+    MockProver::run(k, &circuit, vec![instance])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+fn big(hex: &str) -> BigUint {
+    BigUint::parse_bytes(hex.as_bytes(), 16).unwrap()
+}
+
+/// secp256k1's base field prime.
+fn secp_p() -> BigUint {
+    big("fffffffffffffffffffffffffffffffffffffffffffffffffffffffefffffc2f")
+}
+
+/// `2^bits - 1`, every limb all ones.
+fn ones(bits: usize) -> BigUint {
+    (BigUint::from(1u8) << bits) - 1u8
+}
+
+const N: usize = 4;
+
+#[test]
+fn add_carries_across_every_limb() {
+    let p = secp_p();
+    let (a, b) = (ones(256), BigUint::from(1u8));
+    assert!(holds::<N>(Op::Add, &a, &b, &p, &(&a + &b)));
+    let (a, b) = (big("1234567890abcdef"), big("fedcba0987654321") << 64);
+    assert!(holds::<N>(Op::Add, &a, &b, &p, &(&a + &b)));
+    assert!(!holds::<N>(Op::Add, &a, &b, &p, &(&a + &b + 1u8)));
+}
+
+#[test]
+fn add_mod_wraps_around() {
+    let p = secp_p();
+    let a = &p - 1u8;
+    let b = BigUint::from(5u8);
+    assert!(holds::<N>(Op::AddMod, &a, &b, &p, &BigUint::from(4u8)));
+    assert!(holds::<N>(Op::AddMod, &a, &BigUint::from(0u8), &p, &a));
+    // the unreduced sum is the same integer plus p
+    assert!(!holds::<N>(Op::AddMod, &a, &b, &p, &(&a + &b)));
+}
+
+#[test]
+fn mul_mod_matches_bigint() {
+    let p = secp_p();
+    let a = big("79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798");
+    let b = big("483ada7726a3c4655da4fbfc0e1108a8fd17b448a68554199c47d08ffb10d4b8");
+    let product = &a * &b % &p;
+    assert!(holds::<N>(Op::MulMod, &a, &b, &p, &product));
+    assert!(!holds::<N>(Op::MulMod, &a, &b, &p, &(&product + 1u8)));
+    assert!(!holds::<N>(Op::MulMod, &a, &b, &p, &(&product + &p)));
+    // operands need not be reduced
+    let (a, b) = (ones(256), ones(256));
+    assert!(holds::<N>(Op::MulMod, &a, &b, &p, &(&a * &b % &p)));
+    let a = &p - 1u8;
+    assert!(holds::<N>(Op::MulMod, &a, &a, &p, &BigUint::from(1u8)));
+    assert!(holds::<N>(
+        Op::MulMod,
+        &a,
+        &BigUint::from(0u8),
+        &p,
+        &BigUint::from(0u8)
+    ));
+}
+
+#[test]
+fn reduce_takes_double_width_integers() {
+    let p = secp_p();
+    for x in [
+        ones(512),
+        &p * &p,
+        &p * 3u8 + 7u8,
+        p.clone(),
+        BigUint::from(0u8),
+    ] {
+        assert!(holds::<N>(
+            Op::Reduce,
+            &x,
+            &BigUint::from(0u8),
+            &p,
+            &(&x % &p)
+        ));
+    }
+    let x = &p * 3u8 + 7u8;
+    assert!(!holds::<N>(
+        Op::Reduce,
+        &x,
+        &BigUint::from(0u8),
+        &p,
+        &(&p + 7u8)
+    ));
+}
+
+#[test]
+fn reduction_modulo_zero_is_unsatisfiable() {
+    let zero = BigUint::from(0u8);
+    let a = big("1234");
+    assert!(!holds::<N>(Op::MulMod, &a, &a, &zero, &zero));
+}
+
+#[test]
+fn less_than_is_strict() {
+    let p = secp_p();
+    let a = &p - 1u8;
+    assert!(holds::<N>(Op::LessThan, &a, &p, &p, &BigUint::from(0u8)));
+    assert!(holds::<N>(
+        Op::LessThan,
+        &BigUint::from(0u8),
+        &BigUint::from(1u8),
+        &p,
+        &p
+    ));
+    assert!(!holds::<N>(Op::LessThan, &p, &p, &p, &BigUint::from(0u8)));
+    assert!(!holds::<N>(Op::LessThan, &p, &a, &p, &BigUint::from(0u8)));
+}
+
+#[test]
+fn mul_mod_of_rsa_width() {
+    // a 2048-bit odd modulus with its top limb set
+    let p = (BigUint::from(1u8) << 2047) + big("c0ffee") * big("10001") + 1u8;
+    let a = ones(2048) / 3u8;
+    let b = (&p - 2u8) >> 7u8;
+    let product = &a * &b % &p;
+    assert!(holds::<32>(Op::MulMod, &a, &b, &p, &product));
+    assert!(!holds::<32>(Op::MulMod, &a, &b, &p, &(product + 1u8)));
+}
@@ -0,0 +1,119 @@
+//! Artifact bundles: one file with the proof, its key and the SRS it needs, through the
+//! library and through `cli::prove_bundle` and `cli::verify_bundle` as `playground` uses them.
+
+use std::{fs, path::PathBuf};
+
+use halo2_playground::{
+    audit::fit_k,
+    bundle::{Bundle, EnvelopeError, Metadata, Prover},
+    circuits::mul::MulCircuit,
+    cli::{self, KeyFiles},
+    entropy::SeededEntropy,
+    error::PlaygroundError,
+    limits::ResourceLimits,
+    srs::srs_digest,
+    storage::cached_params,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+type Mul = MulCircuit<Fr>;
+
+fn dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join("bundle")
+        .join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("bundle directory");
+    dir
+}
+
+#[test]
+fn bundles_round_trip_and_pin_their_srs() {
+    let circuit = MulCircuit::new(Fr::from(3), Fr::from(5));
+    let k = fit_k(&circuit).expect("fit_k");
+    let params = cached_params(k).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let proof = Prover::new(&params, &pk)
+        .prove(
+            circuit,
+            vec![MulCircuit::instance(Fr::from(15))],
+            SeededEntropy::new(0x5962_be5d_763d_318d),
+        )
+        .expect("prove");
+    let metadata = Metadata {
+        circuit: "mul".to_string(),
+        git_rev: Some("0123abc".to_string()),
+    };
+    let bundle = Bundle::new(metadata, &params, pk.get_vk(), proof);
+    assert_eq!(bundle.srs_digest, srs_digest(&params));
+
+    let limits = ResourceLimits::default();
+    let bytes = bundle.to_bytes();
+    assert_eq!(Bundle::from_bytes(&bytes, &limits).expect("parse"), bundle);
+    let mut written = vec![];
+    bundle.write(&mut written).expect("write");
+    assert_eq!(Bundle::read(&written[..], &limits).expect("read"), bundle);
+    bundle.verify::<Mul>(&params).expect("verify");
+
+    // another SRS is refused before the key is read
+    let larger = cached_params(k + 1).expect("cached_params");
+    assert!(matches!(
+        bundle.verify::<Mul>(&larger),
+        Err(EnvelopeError::Srs { .. })
+    ));
+    // so are other instances, and bytes that aren't a bundle
+    let mut wrong = bundle.clone();
+    wrong.proof.instances[0][0] += Fr::one();
+    assert!(matches!(
+        wrong.verify::<Mul>(&params),
+        Err(EnvelopeError::Verify(_))
+    ));
+    assert!(Bundle::from_bytes(&bytes[..bytes.len() / 2], &limits).is_err());
+    assert!(Bundle::from_bytes(&bundle.proof.to_bytes(pk.get_vk()), &limits).is_err());
+}
+
+#[test]
+fn the_cli_proves_to_and_verifies_from_one_file() {
+    let dir = dir("cli");
+    let files = KeyFiles {
+        params: dir.join("params.bin"),
+        vk: dir.join("mul.vk"),
+    };
+    cli::setup(7, &files.params).expect("setup");
+    cli::keygen::<Mul>(&files, &mut None).expect("keygen");
+    fs::write(dir.join("mul.witness"), "3 5").unwrap();
+    let out = dir.join("mul.bundle");
+    cli::prove_bundle::<Mul>(
+        &files,
+        &dir.join("mul.witness"),
+        &out,
+        Default::default(),
+        None,
+        Some("0123abc"),
+        &mut None,
+    )
+    .expect("prove_bundle");
+    assert_eq!(cli::bundle_circuit(&out).expect("circuit"), "mul");
+    cli::verify_bundle::<Mul>(&files.params, &out, Some(&files.vk), &mut None)
+        .expect("verify under the pinned key");
+    cli::verify_bundle::<Mul>(&files.params, &out, None, &mut None)
+        .expect("verify under the bundled key");
+
+    // a bundle is refused under another circuit or another SRS
+    let poseidon = cli::verify_bundle::<cli::PoseidonCircuit>(&files.params, &out, None, &mut None);
+    assert!(matches!(
+        poseidon,
+        Err(PlaygroundError::Serialization(message)) if message.contains("circuit")
+    ));
+    let other = dir.join("other.bin");
+    cli::setup(8, &other).expect("setup");
+    assert!(matches!(
+        cli::verify_bundle::<Mul>(&other, &out, None, &mut None),
+        Err(PlaygroundError::Setup(_))
+    ));
+}
@@ -0,0 +1,301 @@
+//! The byte ops chip: each op, split, rotation and shift matches its reference on integers, and
+//! a row whose `out` isn't the op of its operands is rejected.
+
+#![cfg(feature = "gadgets-bytes")]
+
+use halo2_playground::chips::{
+    byte_ops::{ByteOp, ByteOpsChip, ByteOpsConfig, MIN_K},
+    tables::Tables,
+};
+use halo2_proofs::{
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+#[derive(Clone, Debug)]
+struct Config {
+    tables: Tables,
+    byte_ops: ByteOpsConfig,
+    advice: Column<Advice>,
+    instance: Column<Instance>,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Check {
+    Op(ByteOp, u8, u8),
+    /// A row of `op` laid out by hand, claiming `out`.
+    Forged(ByteOp, u64, u64, u64),
+    Split(u8, usize),
+    /// Words of `bytes` bytes, as little-endian bytes.
+    RotateRight {
+        word: u64,
+        bytes: usize,
+        bits: usize,
+    },
+    RotateLeft {
+        word: u64,
+        bytes: usize,
+        bits: usize,
+    },
+    ShiftRight {
+        word: u64,
+        bytes: usize,
+        bits: usize,
+    },
+}
+
+impl Check {
+    /// The cells the chip should output.
+    fn expected(self) -> Vec<u64> {
+        let mask = |bytes: usize| u64::MAX >> (64 - 8 * bytes);
+        let word = match self {
+            Check::Op(op, lhs, rhs) => return vec![op.apply(lhs, rhs) as u64],
+            Check::Forged(_, _, _, out) => return vec![out],
+            Check::Split(byte, shift) => {
+                return vec![(byte >> shift) as u64, (byte & ((1 << shift) - 1)) as u64]
+            }
+            Check::RotateRight { word, bytes, bits } => {
+                let width = 8 * bytes;
+                ((word >> bits) | (word << ((width - bits) % width))) & mask(bytes)
+            }
+            Check::RotateLeft { word, bytes, bits } => {
+                let width = 8 * bytes;
+                ((word << bits) | (word >> ((width - bits) % width))) & mask(bytes)
+            }
+            Check::ShiftRight { word, bits, .. } => word >> bits,
+        };
+        let bytes = match self {
+            Check::RotateRight { bytes, .. }
+            | Check::RotateLeft { bytes, .. }
+            | Check::ShiftRight { bytes, .. } => bytes,
+            _ => unreachable!(),
+        };
+        (0..bytes).map(|i| (word >> (8 * i)) & 0xff).collect()
+    }
+}
+
+#[derive(Clone)]
+struct ByteOpsCircuit(Vec<Check>);
+
+fn load(
+    config: &Config,
+    layouter: &mut impl Layouter<Fr>,
+    values: &[u64],
+) -> Result<Vec<AssignedCell<Fr, Fr>>, Error> {
+    layouter.assign_region(
+        || "inputs",
+        |mut region| {
+            values
+                .iter()
+                .enumerate()
+                .map(|(row, value)| {
+                    region.assign_advice(
+                        || "input",
+                        config.advice,
+                        row,
+                        || Value::known(Fr::from(*value)),
+                    )
+                })
+                .collect()
+        },
+    )
+}
+
+fn word_bytes(word: u64, bytes: usize) -> Vec<u64> {
+    (0..bytes).map(|i| (word >> (8 * i)) & 0xff).collect()
+}
+
+impl Circuit<Fr> for ByteOpsCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let [lhs, rhs, out, advice] = [(); 4].map(|_| meta.advice_column());
+        let constants = meta.fixed_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+        meta.enable_constant(constants);
+        let mut tables = Tables::new();
+        let byte_ops = ByteOpsChip::configure(meta, &mut tables, lhs, rhs, out);
+        Config {
+            tables,
+            byte_ops,
+            advice,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        config.tables.load(&mut layouter)?;
+        let chip = ByteOpsChip::construct(config.byte_ops.clone());
+        let zero = layouter.assign_region(
+            || "zero",
+            |mut region| {
+                region.assign_advice_from_constant(|| "zero", config.advice, 0, Fr::zero())
+            },
+        )?;
+        let mut outputs = vec![];
+        for check in self.0.iter() {
+            match *check {
+                Check::Op(op, lhs, rhs) => {
+                    let inputs = load(&config, &mut layouter, &[lhs as u64, rhs as u64])?;
+                    outputs.push(chip.assign(
+                        layouter.namespace(|| "op"),
+                        op,
+                        &inputs[0],
+                        &inputs[1],
+                    )?);
+                }
+                Check::Forged(op, lhs, rhs, out) => {
+                    let config = &config.byte_ops;
+                    outputs.push(layouter.assign_region(
+                        || "forged op",
+                        |mut region| {
+                            config.q_op[op as usize].enable(&mut region, 0)?;
+                            let mut assign =
+                                |name: &'static str, column: Column<Advice>, value: u64| {
+                                    region.assign_advice(
+                                        || name,
+                                        column,
+                                        0,
+                                        || Value::known(Fr::from(value)),
+                                    )
+                                };
+                            assign("lhs", config.lhs, lhs)?;
+                            assign("rhs", config.rhs, rhs)?;
+                            assign("out", config.out, out)
+                        },
+                    )?);
+                }
+                Check::Split(byte, shift) => {
+                    let byte = load(&config, &mut layouter, &[byte as u64])?;
+                    let (hi, lo) = chip.split(layouter.namespace(|| "split"), &byte[0], shift)?;
+                    outputs.extend([hi, lo]);
+                }
+                Check::RotateRight { word, bytes, bits } => {
+                    let word = load(&config, &mut layouter, &word_bytes(word, bytes))?;
+                    outputs.extend(chip.rotate_right(
+                        layouter.namespace(|| "rotr"),
+                        &word,
+                        bits,
+                    )?);
+                }
+                Check::RotateLeft { word, bytes, bits } => {
+                    let word = load(&config, &mut layouter, &word_bytes(word, bytes))?;
+                    outputs.extend(chip.rotate_left(layouter.namespace(|| "rotl"), &word, bits)?);
+                }
+                Check::ShiftRight { word, bytes, bits } => {
+                    let word = load(&config, &mut layouter, &word_bytes(word, bytes))?;
+                    outputs.extend(chip.shift_right(
+                        layouter.namespace(|| "shr"),
+                        &word,
+                        bits,
+                        &zero,
+                    )?);
+                }
+            }
+        }
+        for (row, cell) in outputs.iter().enumerate() {
+            layouter.constrain_instance(cell.cell(), config.instance, row)?;
+        }
+        Ok(())
+    }
+}
+
+fn run(checks: &[Check], instance: Vec<Fr>) -> bool {
+    MockProver::run(MIN_K, &ByteOpsCircuit(checks.to_vec()), vec![instance])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+fn holds(checks: &[Check]) -> bool {
+    let instance = checks
+        .iter()
+        .flat_map(|check| check.expected())
+        .map(Fr::from)
+        .collect();
+    run(checks, instance)
+}
+
+#[test]
+fn ops_match_their_reference() {
+    let mut checks = vec![];
+    for op in ByteOp::ALL {
+        for (lhs, rhs) in [
+            (0, 0),
+            (0xff, 0x00),
+            (0xa5, 0x5a),
+            (0xa5, 0x0f),
+            (0xff, 0xff),
+        ] {
+            checks.push(Check::Op(op, lhs, rhs));
+        }
+    }
+    for shift in 0..8 {
+        checks.push(Check::Split(0xa7, shift));
+    }
+    assert!(holds(&checks));
+}
+
+#[test]
+fn rotations_and_shifts_match_their_reference() {
+    let word = 0x0123_4567_89ab_cdef;
+    let mut checks = vec![];
+    // every third amount, whole bytes and splits alike
+    for bits in (0..32).step_by(3).chain([8, 16, 24]) {
+        checks.push(Check::RotateRight {
+            word: word & 0xffff_ffff,
+            bytes: 4,
+            bits,
+        });
+        checks.push(Check::ShiftRight {
+            word: word & 0xffff_ffff,
+            bytes: 4,
+            bits,
+        });
+    }
+    for bits in [0, 1, 8, 36, 62] {
+        checks.push(Check::RotateLeft {
+            word,
+            bytes: 8,
+            bits,
+        });
+    }
+    assert!(holds(&checks));
+}
+
+#[test]
+fn a_wrong_out_is_rejected() {
+    for op in ByteOp::ALL {
+        let (lhs, rhs) = (0xa5, 0x0f);
+        let out = op.apply(lhs as u8, rhs as u8) as u64;
+        assert!(holds(&[Check::Forged(op, lhs, rhs, out)]), "{:?}", op);
+        for wrong in [out ^ 1, out + 256, 0x1_0000] {
+            assert!(!holds(&[Check::Forged(op, lhs, rhs, wrong)]), "{:?}", op);
+        }
+    }
+    // one operand out of range, with the out its low byte would give
+    assert!(!holds(&[Check::Forged(ByteOp::Xor, 0, 0x100, 0)]));
+
+    // and a chip output that isn't the claimed value
+    let check = Check::RotateRight {
+        word: 0x89ab_cdef,
+        bytes: 4,
+        bits: 13,
+    };
+    let mut instance: Vec<Fr> = check.expected().into_iter().map(Fr::from).collect();
+    instance[0] += Fr::one();
+    assert!(!run(&[check], instance));
+}
@@ -0,0 +1,176 @@
+//! Runs described by TOML configs, through `config::run` as `playground run` does.
+
+use std::{fs, path::PathBuf};
+
+use halo2_playground::{
+    circuits::poseidon::PoseidonHashCircuit,
+    cli::{self, KeyFiles},
+    config::{self, CircuitConfig, ConfigError, RunConfig},
+    error::PlaygroundError,
+    poseidon::MySpec,
+    prover::{MultiOpen, ProofOptions, TranscriptHash},
+};
+use halo2_proofs::halo2curves::bn256::Fr;
+
+type Poseidon988 = PoseidonHashCircuit<Fr, MySpec<9, 8>, 9, 8, 8>;
+
+fn dir(name: &str) -> PathBuf {
+    let dir = PathBuf::from(env!("CARGO_TARGET_TMPDIR"))
+        .join("config")
+        .join(name);
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).expect("config directory");
+    dir
+}
+
+const OUTPUT: &str = r#"
+[output]
+params = "out/params.bin"
+vk = "out/circuit.vk"
+proof = "out/circuit.proof"
+instances = "out/circuit.instances"
+"#;
+
+fn parse(text: &str) -> Result<RunConfig, ConfigError> {
+    RunConfig::parse(text, &PathBuf::from("/configs"))
+}
+
+#[test]
+fn poseidon_from_the_matrix() {
+    let dir = dir("poseidon");
+    fs::write(dir.join("message.txt"), "1 2 3 4 5 6 7 8").unwrap();
+    let text = format!(
+        "circuit = \"poseidon\"\nwitness = \"message.txt\"\nsrs = \"unsafe\"\n\n\
+         [poseidon]\nwidth = 9\nrate = 8\nlength = 8\n{}",
+        OUTPUT
+    );
+    fs::write(dir.join("run.toml"), text).unwrap();
+    let run = RunConfig::read(&dir.join("run.toml")).expect("read");
+    assert_eq!(
+        run.circuit,
+        CircuitConfig::Poseidon {
+            width: 9,
+            rate: 8,
+            length: 8
+        }
+    );
+    assert_eq!(run.output.proof, dir.join("out/circuit.proof"));
+    config::run(&run, &mut None).expect("run");
+
+    // what it wrote verifies like any `prove` output
+    let files = KeyFiles {
+        params: run.output.params.clone(),
+        vk: run.output.vk.clone(),
+    };
+    cli::verify::<Poseidon988>(
+        &files,
+        &run.output.instances,
+        &run.output.proof,
+        run.options,
+        &mut None,
+    )
+    .expect("verify");
+
+    // a message of another length is refused
+    fs::write(dir.join("message.txt"), "1 2").unwrap();
+    assert!(config::run(&run, &mut None).is_err());
+}
+
+#[test]
+fn mul_with_options() {
+    let dir = dir("mul");
+    fs::write(dir.join("witness.txt"), "3 5").unwrap();
+    let text = format!(
+        "circuit = \"mul\"\nwitness = \"witness.txt\"\nk = 9\ntranscript = \"keccak256\"\n\
+         multiopen = \"gwc\"\nsrs = \"unsafe\"\n{}",
+        OUTPUT
+    );
+    fs::write(dir.join("run.toml"), text).unwrap();
+    let mut run = RunConfig::read(&dir.join("run.toml")).expect("read");
+    assert_eq!(
+        run.options,
+        ProofOptions {
+            multiopen: MultiOpen::Gwc,
+            transcript: TranscriptHash::Keccak256,
+        }
+    );
+    assert_eq!(run.k, Some(9));
+    config::run(&run, &mut None).expect("run");
+
+    run.k = Some(2);
+    let err = config::run(&run, &mut None).unwrap_err();
+    assert!(
+        matches!(&err, PlaygroundError::Setup(message) if message.contains("too small")),
+        "{}",
+        err
+    );
+}
+
+#[test]
+fn rejected_configs() {
+    let poseidon = |table: &str| {
+        parse(&format!(
+            "circuit = \"poseidon\"\nwitness = \"m\"\n[poseidon]\n{}\n{}",
+            table, OUTPUT
+        ))
+    };
+    assert!(poseidon("width = 3\nrate = 2\nlength = 32").is_ok());
+    assert_eq!(
+        poseidon("width = 3\nrate = 2\nlength = 5"),
+        Err(ConfigError::Unsupported {
+            width: 3,
+            rate: 2,
+            length: 5
+        })
+    );
+    assert_eq!(
+        poseidon("widht = 3\nrate = 2\nlength = 2"),
+        Err(ConfigError::Unknown("poseidon.widht".to_string()))
+    );
+    assert_eq!(
+        poseidon("width = 3\nrate = 2"),
+        Err(ConfigError::Missing("poseidon.length"))
+    );
+    assert!(matches!(
+        poseidon("width = -3\nrate = 2\nlength = 2"),
+        Err(ConfigError::Invalid {
+            key: "poseidon.width",
+            ..
+        })
+    ));
+
+    let mul = |extra: &str| {
+        parse(&format!(
+            "circuit = \"mul\"\nwitness = \"w\"\n{}\n{}",
+            extra, OUTPUT
+        ))
+    };
+    assert!(mul("").is_ok());
+    assert!(matches!(
+        mul("transcript = \"sha256\""),
+        Err(ConfigError::Invalid {
+            key: "transcript",
+            ..
+        })
+    ));
+    assert!(matches!(
+        mul("multiopen = \"kzg\""),
+        Err(ConfigError::Invalid {
+            key: "multiopen",
+            ..
+        })
+    ));
+    assert!(matches!(
+        mul("srs = \"\""),
+        Err(ConfigError::Invalid { key: "srs", .. })
+    ));
+    assert!(matches!(
+        mul("k = \"9\""),
+        Err(ConfigError::Invalid { key: "k", .. })
+    ));
+    assert!(matches!(
+        parse("circuit = \"mul\"\nwitness = \"w\"\n"),
+        Err(ConfigError::Missing("output"))
+    ));
+    assert!(matches!(parse("circuit = "), Err(ConfigError::Toml(_))));
+}
@@ -0,0 +1,272 @@
+//! Edge cases of the small gadgets: zero and nonzero inputs, forged witnesses, and conditions
+//! or operands that are not boolean. Their costs are held to budgets.
+
+use halo2_playground::{
+    audit::fit_k,
+    chips::{
+        boolean::{BooleanChip, BooleanConfig},
+        branch::{BranchChip, BranchConfig, Condition},
+        is_zero::{IsZeroChip, IsZeroConfig},
+    },
+    report::CostBudget,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    circuit::{AssignedCell, Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance},
+};
+
+#[derive(Clone, Debug)]
+struct Config {
+    is_zero: IsZeroConfig,
+    branch: BranchConfig,
+    boolean: BooleanConfig,
+    advice: Column<Advice>,
+    instance: Column<Instance>,
+}
+
+#[derive(Clone, Copy)]
+enum Gadget {
+    /// `is_zero(value)`, or the chip's row laid out by hand with the given inverse and output.
+    IsZero { value: Fr, forged: Option<(Fr, Fr)> },
+    /// `cond ? a : b`.
+    Select { cond: Fr, a: Fr, b: Fr },
+    /// `[not a, a and b, a or b]`.
+    Logic { a: Fr, b: Fr },
+}
+
+fn load(
+    config: &Config,
+    layouter: &mut impl Layouter<Fr>,
+    values: &[Fr],
+) -> Result<Vec<AssignedCell<Fr, Fr>>, Error> {
+    layouter.assign_region(
+        || "inputs",
+        |mut region| {
+            values
+                .iter()
+                .enumerate()
+                .map(|(row, value)| {
+                    region.assign_advice(|| "input", config.advice, row, || Value::known(*value))
+                })
+                .collect()
+        },
+    )
+}
+
+/// Runs one gadget on private inputs, exposing its outputs in instance rows 0, 1, ...
+#[derive(Clone)]
+struct GadgetCircuit(Gadget);
+
+impl Circuit<Fr> for GadgetCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+        Config {
+            is_zero: IsZeroChip::configure(meta),
+            branch: BranchChip::configure(meta),
+            boolean: BooleanChip::configure(meta),
+            advice,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        let outputs: Vec<AssignedCell<Fr, Fr>> = match self.0 {
+            Gadget::IsZero {
+                value,
+                forged: None,
+            } => {
+                let value = load(&config, &mut layouter, &[value])?;
+                let chip = IsZeroChip::construct(config.is_zero);
+                vec![chip.is_zero(layouter.namespace(|| "is zero"), &value[0])?]
+            }
+            Gadget::IsZero {
+                value,
+                forged: Some((inverse, is_zero)),
+            } => {
+                let config = &config.is_zero;
+                vec![layouter.assign_region(
+                    || "forged is zero",
+                    |mut region| {
+                        config.q_is_zero.enable(&mut region, 0)?;
+                        let mut assign = |name, column, value| {
+                            region.assign_advice(|| name, column, 0, || Value::known(value))
+                        };
+                        assign("value", config.value, value)?;
+                        assign("inverse", config.inverse, inverse)?;
+                        assign("is zero", config.is_zero, is_zero)
+                    },
+                )?]
+            }
+            Gadget::Select { cond, a, b } => {
+                let inputs = load(&config, &mut layouter, &[cond, a, b])?;
+                let chip = BranchChip::construct(config.branch);
+                let (_, out) = chip.select(
+                    layouter.namespace(|| "select"),
+                    Condition::Copy(&inputs[0]),
+                    &inputs[1],
+                    &inputs[2],
+                )?;
+                vec![out]
+            }
+            Gadget::Logic { a, b } => {
+                let inputs = load(&config, &mut layouter, &[a, b])?;
+                let chip = BooleanChip::construct(config.boolean);
+                vec![
+                    chip.not(layouter.namespace(|| "not"), &inputs[0])?,
+                    chip.and(layouter.namespace(|| "and"), &inputs[0], &inputs[1])?,
+                    chip.or(layouter.namespace(|| "or"), &inputs[0], &inputs[1])?,
+                ]
+            }
+        };
+        for (row, output) in outputs.iter().enumerate() {
+            layouter.constrain_instance(output.cell(), config.instance, row)?;
+        }
+        Ok(())
+    }
+}
+
+fn holds(gadget: Gadget, outputs: &[u64]) -> bool {
+    let circuit = GadgetCircuit(gadget);
+    let outputs = outputs.iter().map(|&v| Fr::from(v)).collect();
+    MockProver::run(
+        fit_k::<Fr, _>(&circuit).expect("fit_k"),
+        &circuit,
+        vec![outputs],
+    )
+    .unwrap()
+    .verify()
+    .is_ok()
+}
+
+fn is_zero(value: Fr) -> Gadget {
+    Gadget::IsZero {
+        value,
+        forged: None,
+    }
+}
+
+#[test]
+fn is_zero_of_zero_and_nonzero() {
+    assert!(holds(is_zero(Fr::zero()), &[1]));
+    assert!(!holds(is_zero(Fr::zero()), &[0]));
+    assert!(holds(is_zero(Fr::from(7)), &[0]));
+    assert!(holds(is_zero(-Fr::one()), &[0]));
+    assert!(!holds(is_zero(Fr::from(7)), &[1]));
+}
+
+#[test]
+fn is_zero_rejects_forged_witnesses() {
+    let forged = |value: u64, inverse: Fr, is_zero: u64| Gadget::IsZero {
+        value: Fr::from(value),
+        forged: Some((inverse, Fr::from(is_zero))),
+    };
+    let seven_inverse = Fr::from(7).invert().unwrap();
+    // the honest rows pass when laid out by hand
+    assert!(holds(forged(7, seven_inverse, 0), &[0]));
+    assert!(holds(forged(0, Fr::from(123), 1), &[1]));
+    // a nonzero value claimed zero, with a zero or a bogus inverse
+    assert!(!holds(forged(7, Fr::zero(), 1), &[1]));
+    assert!(!holds(forged(7, Fr::from(3), 1), &[1]));
+    // zero claimed nonzero: no inverse makes 1 - 0 * inverse vanish
+    assert!(!holds(forged(0, Fr::one(), 0), &[0]));
+}
+
+#[test]
+fn select_on_boolean_and_non_boolean_conditions() {
+    let select = |cond: u64| Gadget::Select {
+        cond: Fr::from(cond),
+        a: Fr::from(10),
+        b: Fr::from(20),
+    };
+    assert!(holds(select(1), &[10]));
+    assert!(holds(select(0), &[20]));
+    assert!(!holds(select(1), &[20]));
+    // cond = 2 lands on the line through a and b: 2 * (10 - 20) + 20 = 0
+    assert!(!holds(select(2), &[0]));
+}
+
+#[test]
+fn logic_truth_tables() {
+    for (a, b) in [(0, 0), (0, 1), (1, 0), (1, 1)] {
+        let gadget = Gadget::Logic {
+            a: Fr::from(a),
+            b: Fr::from(b),
+        };
+        assert!(holds(gadget, &[1 - a, a & b, a | b]));
+        assert!(!holds(gadget, &[a, a & b, a | b]));
+    }
+}
+
+#[test]
+fn logic_rejects_non_boolean_operands() {
+    // 2 and 1: not = -1, and = 2, or = 1, each consistent with its own equation
+    let gadget = Gadget::Logic {
+        a: Fr::from(2),
+        b: Fr::one(),
+    };
+    let outputs = vec![-Fr::one(), Fr::from(2), Fr::one()];
+    let circuit = GadgetCircuit(gadget);
+    let prover = MockProver::run(
+        fit_k::<Fr, _>(&circuit).expect("fit_k"),
+        &circuit,
+        vec![outputs],
+    )
+    .unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn gadgets_stay_within_budget() {
+    // the inputs take a row each of the shared column, and every operation is one row of its
+    // chip's own columns
+    let budget = CostBudget::new().advice_columns(11).lookups(0).degree(3);
+    budget
+        .clone()
+        .rows(1)
+        .assert("is zero", &GadgetCircuit(is_zero(Fr::one())));
+    let select = Gadget::Select {
+        cond: Fr::one(),
+        a: Fr::one(),
+        b: Fr::one(),
+    };
+    budget
+        .clone()
+        .rows(3)
+        .assert("select", &GadgetCircuit(select));
+    let logic = Gadget::Logic {
+        a: Fr::one(),
+        b: Fr::one(),
+    };
+    budget.rows(3).assert("logic", &GadgetCircuit(logic));
+}
+
+#[test]
+fn budget_reports_every_overrun() {
+    let err = CostBudget::new()
+        .rows(0)
+        .advice_columns(10)
+        .lookups(0)
+        .check(&GadgetCircuit(is_zero(Fr::one())))
+        .unwrap_err();
+    assert_eq!(
+        err.to_string(),
+        "rows: 1 over the budget of 0, advice columns: 11 over the budget of 10"
+    );
+}
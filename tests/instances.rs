@@ -0,0 +1,230 @@
+//! Circuits with more than one instance column, on `batch_map`: its inputs in column 0 and its
+//! outputs in column 1 are committed, proven and verified column by column.
+
+use halo2_playground::{
+    audit::fit_k,
+    circuits::batch_map::BatchMapCircuit,
+    commit_instances,
+    entropy::SeededEntropy,
+    error::PlaygroundError,
+    prover::{run_multi_proof, run_proof, ProofOptions},
+    storage::cached_params,
+    verifier::{check_multi_proof, check_proof, verify_split, VerificationReport},
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    dev::MockProver,
+    halo2curves::{
+        bn256::{Bn256, Fr, G1Affine},
+        group::Curve,
+    },
+    plonk::{keygen_pk, keygen_vk, Error, ProvingKey},
+    poly::{
+        commitment::{Blind, Params},
+        kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::VerifierSHPLONK,
+        },
+    },
+};
+
+const SEED: u64 = 0x5962_be5d_763d_318d;
+
+type Batch = BatchMapCircuit<Fr, 4>;
+
+struct Keys {
+    params: ParamsKZG<Bn256>,
+    pk: ProvingKey<G1Affine>,
+}
+
+fn keys() -> Keys {
+    let circuit = Batch::new();
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    Keys { params, pk }
+}
+
+fn batch(inputs: [u64; 4]) -> Vec<Vec<Fr>> {
+    Batch::instance(inputs.map(Fr::from))
+}
+
+fn columns(instance: &[Vec<Fr>]) -> Vec<&[Fr]> {
+    instance.iter().map(Vec::as_slice).collect()
+}
+
+impl Keys {
+    fn prove(&self, instance: &[&[Fr]]) -> Result<Vec<u8>, PlaygroundError> {
+        run_proof(
+            &self.params,
+            &self.pk,
+            Batch::new(),
+            instance,
+            SeededEntropy::new(SEED),
+            ProofOptions::default(),
+        )
+    }
+
+    fn check(&self, instance: &[&[Fr]], proof: &[u8]) -> VerificationReport {
+        check_proof(
+            &self.params,
+            self.pk.get_vk(),
+            instance,
+            proof,
+            ProofOptions::default(),
+        )
+    }
+
+    fn commit(&self, instances: &[&[&[Fr]]]) -> Result<Vec<Vec<G1Affine>>, Error> {
+        commit_instances::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<'_, Bn256>>(
+            &self.params,
+            self.pk.get_vk(),
+            instances,
+        )
+    }
+
+    /// The commitment to one column, straight from the Lagrange bases.
+    fn commit_column(&self, column: &[Fr]) -> G1Affine {
+        let mut values = column.to_vec();
+        values.resize(self.params.n() as usize, Fr::zero());
+        let poly = self.pk.get_vk().get_domain().lagrange_from_vec(values);
+        self.params
+            .commit_lagrange(&poly, Blind::default())
+            .to_affine()
+    }
+}
+
+#[test]
+fn the_mock_prover_checks_both_columns() {
+    let instance = batch([1, 2, 3, 4]);
+    let k = fit_k(&Batch::new()).expect("fit_k");
+    let prover = MockProver::run(k, &Batch::new(), instance.clone()).unwrap();
+    assert!(prover.verify().is_ok());
+
+    let mut wrong = instance;
+    wrong[1][2] += Fr::one();
+    let prover = MockProver::run(k, &Batch::new(), wrong).unwrap();
+    assert!(prover.verify().is_err());
+}
+
+#[test]
+fn batches_prove_and_verify_column_by_column() {
+    let keys = keys();
+    assert_eq!(keys.pk.get_vk().cs.num_instance_columns(), 2);
+    let instance = batch([1, 2, 3, 4]);
+    let proof = keys.prove(&columns(&instance)).expect("create_proof");
+    assert!(keys.check(&columns(&instance), &proof).is_verified());
+
+    // the columns are not interchangeable, nor is either one alone
+    let swapped = [instance[1].as_slice(), instance[0].as_slice()];
+    assert!(!keys.check(&swapped, &proof).is_verified());
+    let mut outputs = instance.clone();
+    outputs[1][3] += Fr::one();
+    assert!(!keys.check(&columns(&outputs), &proof).is_verified());
+    assert!(matches!(
+        keys.check(&[instance[0].as_slice()], &proof),
+        VerificationReport::InstanceShape {
+            expected_columns: 2,
+            ..
+        }
+    ));
+    assert!(matches!(
+        keys.prove(&[instance[0].as_slice()]),
+        Err(PlaygroundError::Prove(Error::InvalidInstances))
+    ));
+}
+
+#[test]
+fn the_split_verifier_reports_the_instance_shape() {
+    let keys = keys();
+    let instance = batch([1, 2, 3, 4]);
+    let proof = keys.prove(&columns(&instance)).expect("create_proof");
+    let vk = keys.pk.get_vk();
+    let all = columns(&instance);
+    let instances: &[&[&[Fr]]] = &[&all];
+    assert!(matches!(
+        verify_split(
+            &keys.params,
+            vk,
+            &[(instances, &proof[..])],
+            ProofOptions::default()
+        ),
+        Ok(true)
+    ));
+    let one_column: &[&[&[Fr]]] = &[&[instance[0].as_slice()]];
+    match verify_split(
+        &keys.params,
+        vk,
+        &[(one_column, &proof[..])],
+        ProofOptions::default(),
+    ) {
+        Err(VerificationReport::InstanceShape {
+            expected_columns,
+            lens,
+            ..
+        }) => assert_eq!((expected_columns, lens), (2, vec![4])),
+        other => panic!("expected an instance shape failure, got {:?}", other),
+    }
+}
+
+#[test]
+fn instance_columns_are_committed_separately() {
+    let keys = keys();
+    let instance = batch([1, 2, 3, 4]);
+    let commitments = keys.commit(&[&columns(&instance)]).expect("commit");
+    assert_eq!(commitments.len(), 1);
+    assert_eq!(commitments[0].len(), 2);
+    assert_eq!(commitments[0][0], keys.commit_column(&instance[0]));
+    assert_eq!(commitments[0][1], keys.commit_column(&instance[1]));
+    assert_ne!(commitments[0][0], commitments[0][1]);
+
+    // other outputs leave the inputs' commitment alone, and zero rows add nothing
+    let mut outputs = instance.clone();
+    outputs[1][0] += Fr::one();
+    let other = keys.commit(&[&columns(&outputs)]).expect("commit");
+    assert_eq!(other[0][0], commitments[0][0]);
+    assert_ne!(other[0][1], commitments[0][1]);
+    let mut padded = instance.clone();
+    padded[0].resize(6, Fr::zero());
+    let padded = keys.commit(&[&columns(&padded)]).expect("commit");
+    assert_eq!(padded[0], commitments[0]);
+
+    assert!(matches!(
+        keys.commit(&[&[instance[0].as_slice()]]),
+        Err(Error::InvalidInstances)
+    ));
+    let three: [&[Fr]; 3] = [instance[0].as_slice(), instance[1].as_slice(), &[]];
+    assert!(matches!(
+        keys.commit(&[&three]),
+        Err(Error::InvalidInstances)
+    ));
+}
+
+#[test]
+fn one_proof_covers_several_batches() {
+    let keys = keys();
+    let (first, second) = (batch([1, 2, 3, 4]), batch([5, 6, 7, 8]));
+    let instances = [columns(&first), columns(&second)];
+    let instances: Vec<&[&[Fr]]> = instances.iter().map(Vec::as_slice).collect();
+    let proof = run_multi_proof(
+        &keys.params,
+        &keys.pk,
+        &[Batch::new(), Batch::new()],
+        &instances,
+        SeededEntropy::new(SEED),
+        ProofOptions::default(),
+    )
+    .expect("create_proof");
+    let vk = keys.pk.get_vk();
+    let options = ProofOptions::default();
+    assert!(check_multi_proof(&keys.params, vk, &instances, &proof, options).is_verified());
+
+    let commitments = keys.commit(&instances).expect("commit");
+    assert_eq!(commitments.len(), 2);
+    assert!(commitments.iter().all(|proof| proof.len() == 2));
+    assert_eq!(commitments[1][1], keys.commit_column(&second[1]));
+
+    // the batches are bound to their place in the proof
+    let reversed = [instances[1], instances[0]];
+    assert!(!check_multi_proof(&keys.params, vk, &reversed, &proof, options).is_verified());
+}
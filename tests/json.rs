@@ -0,0 +1,103 @@
+//! The JSON reader behind instance and witness files: every kind of value parses, escapes
+//! decode, and malformed or over-deep text is rejected with what was expected.
+
+use halo2_playground::json::Json;
+
+fn string(s: &str) -> Json {
+    Json::String(s.to_string())
+}
+
+#[test]
+fn values_parse() {
+    assert_eq!(Json::parse("null"), Ok(Json::Null));
+    assert_eq!(Json::parse(" true\n"), Ok(Json::Bool(true)));
+    assert_eq!(Json::parse("false"), Ok(Json::Bool(false)));
+    // wider than any native integer, kept as written
+    let digits = "21888242871839275222246405745257275088548364400416034343698204186575808495617";
+    assert_eq!(Json::parse(digits), Ok(Json::Integer(digits.to_string())));
+    assert_eq!(Json::parse("\"0x2a\""), Ok(string("0x2a")));
+    assert_eq!(Json::parse("[]"), Ok(Json::Array(vec![])));
+    assert_eq!(Json::parse("{ }"), Ok(Json::Object(vec![])));
+    assert_eq!(
+        Json::parse(r#" { "a" : [1, [ "2" ], {}] , "b": null, "a": true } "#),
+        Ok(Json::Object(vec![
+            (
+                "a".to_string(),
+                Json::Array(vec![
+                    Json::Integer("1".to_string()),
+                    Json::Array(vec![string("2")]),
+                    Json::Object(vec![]),
+                ])
+            ),
+            ("b".to_string(), Json::Null),
+            ("a".to_string(), Json::Bool(true)),
+        ]))
+    );
+}
+
+#[test]
+fn escapes_decode() {
+    assert_eq!(
+        Json::parse(r#""q\" b\\ s\/ \b\f\n\r\t \u0041\u00e9""#),
+        Ok(string("q\" b\\ s/ \u{8}\u{c}\n\r\t A\u{e9}"))
+    );
+    assert_eq!(Json::parse("\"h\u{e9}llo\""), Ok(string("h\u{e9}llo")));
+    for broken in [
+        r#""\x""#,
+        r#""\u12""#,
+        r#""\u+123""#,
+        r#""\ud800""#,
+        "\"a\nb\"",
+        "\"open",
+        "\"\\",
+    ] {
+        assert!(Json::parse(broken).is_err(), "{:?}", broken);
+    }
+}
+
+#[test]
+fn accessors_match_the_kind() {
+    let json = Json::parse(r#"[{"x": "3"}, 4, "5"]"#).unwrap();
+    let items = json.as_array().unwrap();
+    assert_eq!(items.len(), 3);
+    assert_eq!(items[0].as_object().unwrap()[0].1.as_scalar(), Some("3"));
+    assert_eq!(items[1].as_scalar(), Some("4"));
+    assert_eq!(items[2].as_scalar(), Some("5"));
+    assert_eq!(json.as_object(), None);
+    assert_eq!(json.as_scalar(), None);
+    assert_eq!(Json::Null.as_scalar(), None);
+    assert_eq!(Json::Bool(true).as_array(), None);
+}
+
+#[test]
+fn malformed_text_is_rejected() {
+    for broken in [
+        "",
+        " ",
+        "[1",
+        "[1,]",
+        "[,1]",
+        "[1 2]",
+        "{\"a\"}",
+        "{\"a\": }",
+        "{\"a\": 1,}",
+        "{a: 1}",
+        "[1] x",
+        "1 2",
+        "nul",
+        "-1",
+        "1.5",
+        "1e3",
+    ] {
+        assert!(Json::parse(broken).is_err(), "{:?}", broken);
+    }
+    let error = Json::parse("[1, 2 3]").unwrap_err();
+    assert!(error.contains("`]`") && error.contains("3]"), "{}", error);
+}
+
+#[test]
+fn nesting_is_bounded() {
+    let nested = |depth: usize| format!("{}{}", "[".repeat(depth), "]".repeat(depth));
+    assert!(Json::parse(&nested(64)).is_ok());
+    assert!(Json::parse(&nested(100_000)).is_err());
+}
@@ -0,0 +1,60 @@
+//! The process's metrics count proofs and verifications made through `prover` and `verifier`
+//! directly, not only those of a readiness self-proof.
+
+use halo2_playground::{
+    audit::fit_k,
+    circuits::mul::MulCircuit,
+    entropy::SeededEntropy,
+    metrics,
+    prover::{run_proof, ProofOptions},
+    storage::cached_params,
+    verifier::{check_proof, verify_split},
+};
+use halo2_proofs::{
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+const SEED: u64 = 0x5962_be5d_763d_318d;
+
+// one test, as the counters are shared by every test of this binary
+#[test]
+fn proofs_and_failed_verifications_are_counted() {
+    let (a, b) = (Fr::from(3), Fr::from(5));
+    let circuit = MulCircuit::new(a, b);
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let instance = MulCircuit::instance(a * b);
+    let wrong = MulCircuit::instance(Fr::from(16));
+    let options = ProofOptions::default();
+
+    let metrics = metrics::global();
+    let (proofs, failures) = (metrics.proofs(), metrics.verification_failures());
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &[&instance],
+        SeededEntropy::new(SEED),
+        options,
+    )
+    .expect("run_proof");
+    assert_eq!(metrics.proofs(), proofs + 1);
+
+    assert!(check_proof(&params, pk.get_vk(), &[&instance], &proof, options).is_verified());
+    assert_eq!(metrics.verification_failures(), failures);
+    assert!(!check_proof(&params, pk.get_vk(), &[&wrong], &proof, options).is_verified());
+    assert_eq!(metrics.verification_failures(), failures + 1);
+    let wrong_columns: &[&[Fr]] = &[&wrong];
+    let instances: &[&[&[Fr]]] = &[wrong_columns];
+    assert!(!matches!(
+        verify_split(&params, pk.get_vk(), &[(instances, &proof[..])], options),
+        Ok(true)
+    ));
+    assert_eq!(metrics.verification_failures(), failures + 2);
+
+    let rendered = metrics.render();
+    assert!(rendered.contains("playground_phase_seconds_count{phase=\"prove\"}"));
+    assert!(rendered.contains("playground_queue_depth 0"));
+}
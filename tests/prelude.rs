@@ -0,0 +1,106 @@
+//! Guards the prelude: every supported name must stay importable with the same shape, and
+//! the builders must round-trip a proof. Changing this file is a breaking change.
+
+use halo2_playground::prelude::*;
+use halo2_proofs::{
+    arithmetic::Field,
+    halo2curves::bn256::Fr,
+    plonk::{keygen_pk, keygen_vk},
+};
+
+type Poseidon = PoseidonHashCircuit<Fr, MySpec<3, 2>, 3, 2, 2>;
+
+fn exists<T: ?Sized>() {}
+
+#[test]
+fn names() {
+    exists::<Batch<'static>>();
+    exists::<BatchError>();
+    exists::<CostReport>();
+    exists::<CircuitEntry>();
+    exists::<ArithChip<Fr>>();
+    exists::<ArithConfig>();
+    exists::<ArithContext<Fr>>();
+    exists::<AssignedValue<'static, Fr>>();
+    exists::<BooleanChip<Fr>>();
+    exists::<BooleanConfig>();
+    exists::<BranchChip<Fr>>();
+    exists::<BranchConfig>();
+    exists::<ConstantPool<Fr>>();
+    exists::<PoolStats>();
+    exists::<IsZeroChip<Fr>>();
+    exists::<IsZeroConfig>();
+    exists::<Condition<'static, Fr>>();
+    exists::<RlcChip<Fr>>();
+    exists::<RlcConfig>();
+    exists::<ShuffleChip<Fr>>();
+    exists::<ShuffleConfig>();
+    exists::<StandardPlonkChip<Fr>>();
+    exists::<StandardPlonkConfig>();
+    exists::<InstanceLayout>();
+    exists::<OutOfRange>();
+    exists::<WitnessLayout>();
+    exists::<Encoding>();
+    exists::<OsEntropy>();
+    exists::<SeededEntropy>();
+    exists::<PlaygroundError>();
+    exists::<MultiOpen>();
+    exists::<TranscriptHash>();
+    exists::<VerificationReport>();
+    let _: fn(&Poseidon) -> Result<u32, PlaygroundError> = fit_k::<Fr, Poseidon>;
+    let _ = (
+        cached_params,
+        read_params,
+        write_params,
+        write_vk,
+        registry,
+        find_circuit,
+    );
+    let _ = read_vk::<Poseidon>;
+    let _ = <MulCircuit<Fr> as DescribeInstances>::instance_layout;
+    let _ = <MulCircuit<Fr> as DescribeWitness>::witness_layout;
+}
+
+#[allow(dead_code)]
+fn prototype<P: Prototype<Fr>>(circuit: BuilderCircuit<P>) -> BuilderCircuit<P> {
+    circuit
+}
+
+#[allow(dead_code)]
+fn entropy<R: EntropySource>(rng: R) -> R {
+    rng
+}
+
+#[test]
+fn builders_round_trip() {
+    let (a, b) = (Fr::from(3), Fr::from(5));
+    let circuit = MulCircuit::new(a, b);
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let verifier = Verifier::new(&params, pk.get_vk());
+    for options in [
+        ProofOptions::default(),
+        ProofOptions {
+            multiopen: MultiOpen::Gwc,
+            transcript: TranscriptHash::Keccak256,
+        },
+    ] {
+        let bundle: ProofBundle = Prover::new(&params, &pk)
+            .options(options)
+            .prove(
+                circuit.clone(),
+                vec![MulCircuit::instance(a * b)],
+                SeededEntropy::new(0x5962_be5d_763d_318d),
+            )
+            .expect("prove");
+        assert_eq!(bundle.options, options);
+        verifier.verify(&bundle).into_result().expect("verify");
+
+        let mut wrong = bundle.clone();
+        wrong.instances[0][0] += Fr::one();
+        assert!(!verifier.verify(&wrong).is_verified());
+    }
+    assert!(registry().iter().any(|entry| entry.name == "mul"));
+    assert!(find_circuit("poseidon").is_some());
+}
@@ -0,0 +1,221 @@
+//! Properties of the example circuits over random witnesses: `MockProver` accepts every honest
+//! assignment and rejects each kind of corruption, a wrong product, a wrong hash, values out
+//! of range and limbs outside the table.
+
+use halo2_playground::{audit::fit_k, circuits::mul::MulCircuit, cli::PoseidonCircuit};
+use halo2_proofs::{
+    arithmetic::{Field, FieldExt},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::Circuit,
+};
+use proptest::prelude::*;
+
+/// Any field element, from two 128-bit halves.
+fn field() -> impl Strategy<Value = Fr> {
+    (any::<u128>(), any::<u128>()).prop_map(|(lo, hi)| {
+        Fr::from_u128(lo) + Fr::from_u128(hi) * Fr::from_u128(1 << 64).square()
+    })
+}
+
+fn nonzero() -> impl Strategy<Value = Fr> {
+    field().prop_filter("nonzero", |value| !bool::from(value.is_zero()))
+}
+
+fn accepts<C: Circuit<Fr>>(circuit: &C, instances: Vec<Vec<Fr>>) -> bool {
+    let k = fit_k::<Fr, _>(circuit).expect("fit_k");
+    MockProver::run(k, circuit, instances)
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn mul_accepts_products(a in field(), b in field()) {
+        prop_assert!(accepts(&MulCircuit::new(a, b), vec![MulCircuit::instance(a * b)]));
+    }
+
+    #[test]
+    fn mul_rejects_wrong_products(a in field(), b in field(), delta in nonzero()) {
+        let instance = MulCircuit::instance(a * b + delta);
+        prop_assert!(!accepts(&MulCircuit::new(a, b), vec![instance]));
+    }
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(16))]
+
+    #[test]
+    fn poseidon_accepts_hashes(message in [field(), field()]) {
+        let hash = PoseidonCircuit::hash(message);
+        prop_assert!(accepts(&PoseidonCircuit::new(message), vec![vec![hash]]));
+    }
+
+    #[test]
+    fn poseidon_rejects_wrong_hashes(message in [field(), field()], delta in nonzero()) {
+        let hash = PoseidonCircuit::hash(message);
+        prop_assert!(!accepts(&PoseidonCircuit::new(message), vec![vec![hash + delta]]));
+    }
+
+    #[test]
+    fn poseidon_rejects_other_messages(
+        message in [field(), field()],
+        delta in nonzero(),
+        at in 0..2usize,
+    ) {
+        let hash = PoseidonCircuit::hash(message);
+        let mut other = message;
+        other[at] += delta;
+        prop_assert!(!accepts(&PoseidonCircuit::new(other), vec![vec![hash]]));
+    }
+}
+
+#[cfg(feature = "gadgets-lookup")]
+mod range_check {
+    use halo2_playground::chips::{
+        range_check::{limbs, RangeCheckChip, RangeCheckConfig},
+        tables::Tables,
+    };
+    use halo2_proofs::{
+        circuit::{Layouter, SimpleFloorPlanner, Value},
+        plonk::{Advice, Column, ConstraintSystem, Error, Instance},
+    };
+
+    use super::*;
+
+    const LIMB_BITS: usize = 8;
+
+    #[derive(Clone, Debug)]
+    struct Config {
+        tables: Tables,
+        range: RangeCheckConfig,
+        advice: Column<Advice>,
+        instance: Column<Instance>,
+    }
+
+    /// Range checks instance row 0 to `bits` bits. With `forged` limbs, the running sum is
+    /// laid out by hand over them instead of the chip's own.
+    #[derive(Clone)]
+    struct RangeCheckCircuit {
+        bits: usize,
+        forged: Option<Vec<Fr>>,
+    }
+
+    impl Circuit<Fr> for RangeCheckCircuit {
+        type Config = Config;
+        type FloorPlanner = SimpleFloorPlanner;
+
+        fn without_witnesses(&self) -> Self {
+            self.clone()
+        }
+
+        fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+            let advice = meta.advice_column();
+            let instance = meta.instance_column();
+            meta.enable_equality(advice);
+            meta.enable_equality(instance);
+            let mut tables = Tables::new();
+            let range = RangeCheckChip::<Fr, LIMB_BITS>::configure(meta, &mut tables);
+            Config {
+                tables,
+                range,
+                advice,
+                instance,
+            }
+        }
+
+        fn synthesize(
+            &self,
+            config: Self::Config,
+            mut layouter: impl Layouter<Fr>,
+        ) -> Result<(), Error> {
+            let range = config.range.clone();
+            config.tables.load(&mut layouter)?;
+            let chip = RangeCheckChip::<Fr, LIMB_BITS>::construct(config.range);
+            let value = layouter.assign_region(
+                || "load value",
+                |mut region| {
+                    region.assign_advice_from_instance(
+                        || "value",
+                        config.instance,
+                        0,
+                        config.advice,
+                        0,
+                    )
+                },
+            )?;
+            let forged = match &self.forged {
+                Some(forged) => forged,
+                None => {
+                    chip.range_check(layouter.namespace(|| "range check"), &value, self.bits)?;
+                    return Ok(());
+                }
+            };
+            let inv = Fr::from(1 << LIMB_BITS).invert().unwrap();
+            layouter.assign_region(
+                || "forged range check",
+                |mut region| {
+                    let mut z = value.copy_advice(|| "z", &mut region, range.z, 0)?;
+                    for (i, limb) in forged.iter().enumerate() {
+                        range.q_lookup.enable(&mut region, i)?;
+                        region.assign_advice(|| "limb", range.limb, i, || Value::known(*limb))?;
+                        let next = z.value().map(|z| (*z - limb) * inv);
+                        z = region.assign_advice(|| "z", range.z, i + 1, || next)?;
+                    }
+                    range.q_top.enable(&mut region, forged.len())
+                },
+            )
+        }
+    }
+
+    fn checks(bits: usize, value: Fr, forged: Option<Vec<Fr>>) -> bool {
+        accepts(&RangeCheckCircuit { bits, forged }, vec![vec![value]])
+    }
+
+    proptest! {
+        #![proptest_config(ProptestConfig::with_cases(32))]
+
+        #[test]
+        fn accepts_values_in_range(bits in 1..=64usize, value in any::<u64>()) {
+            let value = if bits == 64 { value } else { value % (1 << bits) };
+            prop_assert!(checks(bits, Fr::from(value), None));
+        }
+
+        #[test]
+        fn rejects_values_out_of_range(bits in 1..=63usize, excess in 0..u64::MAX >> 1) {
+            let value = Fr::from_u128((1u128 << bits) + excess as u128);
+            prop_assert!(!checks(bits, value, None));
+        }
+
+        #[test]
+        fn rejects_large_field_elements(value in field(), bits in 1..=64usize) {
+            prop_assume!(limbs(&value, 32, 8)[2..].iter().any(|&limb| limb != 0));
+            prop_assert!(!checks(bits, value, None));
+        }
+
+        /// The running sum holds over limbs of `value` with one borrowed from the next and
+        /// carried into this one, which puts that limb outside the table.
+        #[test]
+        fn rejects_limbs_outside_the_table(value in 1u64 << LIMB_BITS..1 << 16, wide in 0..2usize) {
+            let honest = limbs(&Fr::from(value), LIMB_BITS, 2);
+            let mut forged: Vec<Fr> = honest.iter().map(|&limb| Fr::from(limb)).collect();
+            prop_assert!(checks(16, Fr::from(value), Some(forged.clone())));
+            let shift = Fr::from(1 << LIMB_BITS);
+            match wide {
+                0 => {
+                    forged[0] += shift;
+                    forged[1] -= Fr::one();
+                }
+                _ => {
+                    // the top limb too wide, with one more limb of -1 to end the sum
+                    forged[1] += shift;
+                    forged.push(-Fr::one());
+                }
+            }
+            prop_assert!(!checks(16, Fr::from(value), Some(forged)));
+        }
+    }
+}
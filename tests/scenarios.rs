@@ -0,0 +1,352 @@
+//! Golden end-to-end flows through the CLI's library entry points, described as data: each
+//! scenario is a list of steps run in a fresh directory, with the files `playground` would
+//! read and write, and every step states whether it must succeed.
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+    time::Duration,
+};
+
+use halo2_playground::{
+    circuits::mul::MulCircuit,
+    cli::{self, CliCircuit, KeyFiles, PoseidonCircuit},
+    prover::{MultiOpen, ProofOptions, TranscriptHash},
+};
+use halo2_proofs::halo2curves::bn256::Fr;
+
+#[derive(Clone, Copy, Debug)]
+enum Expect {
+    Ok,
+    /// Fails with an error of this exit code (see `PlaygroundError::exit_code`) containing
+    /// this text.
+    Fails(i32, &'static str),
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Tamper {
+    FlipByte(usize),
+    Truncate(usize),
+    /// Replaces the instances file.
+    Instances(&'static str),
+}
+
+#[derive(Clone, Debug)]
+enum Step {
+    Setup {
+        k: u32,
+    },
+    Keygen,
+    /// Writes `name.proof` and `name.instances`, keeping a copy to restore.
+    Prove {
+        name: &'static str,
+        witness: &'static str,
+        options: ProofOptions,
+        budget: Option<Duration>,
+        expect: Expect,
+    },
+    Tamper {
+        name: &'static str,
+        how: Tamper,
+    },
+    /// Puts back the proof and instances written by `Prove`.
+    Restore {
+        name: &'static str,
+    },
+    Verify {
+        name: &'static str,
+        options: ProofOptions,
+        expect: Expect,
+    },
+    VerifyBatch {
+        names: &'static [&'static str],
+        expect: Expect,
+    },
+}
+
+fn prove(name: &'static str, witness: &'static str) -> Step {
+    Step::Prove {
+        name,
+        witness,
+        options: ProofOptions::default(),
+        budget: None,
+        expect: Expect::Ok,
+    }
+}
+
+fn verify(name: &'static str, expect: Expect) -> Step {
+    Step::Verify {
+        name,
+        options: ProofOptions::default(),
+        expect,
+    }
+}
+
+fn tamper(name: &'static str, how: Tamper) -> Step {
+    Step::Tamper { name, how }
+}
+
+const EVM: ProofOptions = ProofOptions {
+    multiopen: MultiOpen::Gwc,
+    transcript: TranscriptHash::Keccak256,
+};
+
+const POSEIDON: ProofOptions = ProofOptions {
+    multiopen: MultiOpen::Shplonk,
+    transcript: TranscriptHash::Poseidon,
+};
+
+struct Scenario {
+    name: &'static str,
+    steps: Vec<Step>,
+}
+
+impl Scenario {
+    fn dir(&self) -> PathBuf {
+        let dir = Path::new(env!("CARGO_TARGET_TMPDIR"))
+            .join("scenarios")
+            .join(self.name);
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).expect("scenario directory");
+        dir
+    }
+
+    fn run<C: CliCircuit>(&self) {
+        let dir = self.dir();
+        let file = |name: &str, ext: &str| dir.join(format!("{}.{}", name, ext));
+        let files = KeyFiles {
+            params: dir.join("params.bin"),
+            vk: dir.join(format!("{}.vk", C::NAME)),
+        };
+        let mut log = None;
+        for (index, step) in self.steps.iter().enumerate() {
+            let (result, expect) = match step.clone() {
+                Step::Setup { k } => (cli::setup(k, &files.params), Expect::Ok),
+                Step::Keygen => (cli::keygen::<C>(&files, &mut log), Expect::Ok),
+                Step::Prove {
+                    name,
+                    witness,
+                    options,
+                    budget,
+                    expect,
+                } => {
+                    fs::write(file(name, "witness"), witness).unwrap();
+                    let result = cli::prove::<C>(
+                        &files,
+                        &file(name, "witness"),
+                        &file(name, "instances"),
+                        &file(name, "proof"),
+                        options,
+                        budget,
+                        &mut log,
+                    );
+                    if result.is_ok() {
+                        for ext in ["proof", "instances"] {
+                            fs::copy(file(name, ext), file(name, &format!("{}.honest", ext)))
+                                .unwrap();
+                        }
+                    }
+                    (result, expect)
+                }
+                Step::Tamper { name, how } => {
+                    let path = file(name, "proof");
+                    let mut proof = fs::read(&path).unwrap();
+                    match how {
+                        Tamper::FlipByte(at) => proof[at] ^= 1,
+                        Tamper::Truncate(by) => proof.truncate(proof.len() - by),
+                        Tamper::Instances(text) => {
+                            fs::write(file(name, "instances"), text).unwrap()
+                        }
+                    }
+                    fs::write(&path, proof).unwrap();
+                    (Ok(()), Expect::Ok)
+                }
+                Step::Restore { name } => {
+                    for ext in ["proof", "instances"] {
+                        fs::copy(file(name, &format!("{}.honest", ext)), file(name, ext)).unwrap();
+                    }
+                    (Ok(()), Expect::Ok)
+                }
+                Step::Verify {
+                    name,
+                    options,
+                    expect,
+                } => (
+                    cli::verify::<C>(
+                        &files,
+                        &file(name, "instances"),
+                        &file(name, "proof"),
+                        options,
+                        &mut log,
+                    ),
+                    expect,
+                ),
+                Step::VerifyBatch { names, expect } => {
+                    let proofs: Vec<PathBuf> =
+                        names.iter().map(|name| file(name, "proof")).collect();
+                    (
+                        cli::verify_batch::<C>(&files, &proofs, ProofOptions::default(), &mut log),
+                        expect,
+                    )
+                }
+            };
+            match (expect, result) {
+                (Expect::Ok, Ok(())) => {}
+                (Expect::Fails(code, text), Err(err))
+                    if err.exit_code() == code && err.to_string().contains(text) => {}
+                (expect, result) => panic!(
+                    "{}: step {} {:?}: expected {:?}, got {:?}",
+                    self.name, index, step, expect, result
+                ),
+            }
+        }
+    }
+}
+
+fn keyed(name: &'static str, steps: Vec<Step>) -> Scenario {
+    let mut all = vec![Step::Setup { k: 8 }, Step::Keygen];
+    all.extend(steps);
+    Scenario { name, steps: all }
+}
+
+#[test]
+fn mul_tamper_and_restore() {
+    keyed(
+        "mul_tamper_and_restore",
+        vec![
+            prove("a", "3 5"),
+            verify("a", Expect::Ok),
+            tamper("a", Tamper::FlipByte(40)),
+            verify("a", Expect::Fails(1, "verify_proof")),
+            Step::Restore { name: "a" },
+            tamper("a", Tamper::Instances("16\n")),
+            verify("a", Expect::Fails(1, "verify_proof")),
+            Step::Restore { name: "a" },
+            tamper("a", Tamper::Truncate(32)),
+            verify("a", Expect::Fails(1, "verify_proof")),
+            Step::Restore { name: "a" },
+            verify("a", Expect::Ok),
+        ],
+    )
+    .run::<MulCircuit<Fr>>();
+}
+
+#[test]
+fn mul_witness_files() {
+    keyed(
+        "mul_witness_files",
+        vec![
+            prove("json", r#"{"a": "4", "b": "0x6"}"#),
+            verify("json", Expect::Ok),
+            Step::Prove {
+                name: "short",
+                witness: "3",
+                options: ProofOptions::default(),
+                budget: None,
+                expect: Expect::Fails(6, "short.witness"),
+            },
+            Step::Prove {
+                name: "unknown",
+                witness: r#"{"a": "4", "c": "6"}"#,
+                options: ProofOptions::default(),
+                budget: None,
+                expect: Expect::Fails(6, "unknown.witness"),
+            },
+        ],
+    )
+    .run::<MulCircuit<Fr>>();
+}
+
+#[test]
+fn mul_transcripts_and_batches() {
+    keyed(
+        "mul_transcripts_and_batches",
+        vec![
+            Step::Prove {
+                name: "evm",
+                witness: "3 5",
+                options: EVM,
+                budget: None,
+                expect: Expect::Ok,
+            },
+            Step::Verify {
+                name: "evm",
+                options: EVM,
+                expect: Expect::Ok,
+            },
+            // the proof only verifies under the transcript it was made with
+            verify("evm", Expect::Fails(1, "verify_proof")),
+            Step::Prove {
+                name: "recursive",
+                witness: "3 5",
+                options: POSEIDON,
+                budget: None,
+                expect: Expect::Ok,
+            },
+            Step::Verify {
+                name: "recursive",
+                options: POSEIDON,
+                expect: Expect::Ok,
+            },
+            Step::Verify {
+                name: "recursive",
+                options: EVM,
+                expect: Expect::Fails(1, "verify_proof"),
+            },
+            prove("a", "3 5"),
+            prove("b", "7 11"),
+            Step::VerifyBatch {
+                names: &["a", "b"],
+                expect: Expect::Ok,
+            },
+            tamper("b", Tamper::FlipByte(40)),
+            Step::VerifyBatch {
+                names: &["a", "b"],
+                expect: Expect::Fails(1, "b.proof"),
+            },
+        ],
+    )
+    .run::<MulCircuit<Fr>>();
+}
+
+#[test]
+fn mul_proofs_for_other_keys() {
+    keyed(
+        "mul_proofs_for_other_keys",
+        vec![
+            prove("a", "3 5"),
+            // a proof left over from earlier keys is refused by its digest, not the transcript
+            Step::Setup { k: 9 },
+            Step::Keygen,
+            verify("a", Expect::Fails(1, "made for verifying key")),
+            prove("a", "3 5"),
+            verify("a", Expect::Ok),
+        ],
+    )
+    .run::<MulCircuit<Fr>>();
+}
+
+#[test]
+fn poseidon_keys_and_budgets() {
+    keyed(
+        "poseidon_keys_and_budgets",
+        vec![
+            prove("h", "1 2"),
+            verify("h", Expect::Ok),
+            Step::Prove {
+                name: "late",
+                witness: "1 2",
+                options: ProofOptions::default(),
+                budget: Some(Duration::ZERO),
+                expect: Expect::Fails(5, ""),
+            },
+            // new params invalidate the old verifying key and proof
+            Step::Setup { k: 9 },
+            verify("h", Expect::Fails(1, "")),
+            Step::Keygen,
+            prove("h", "1 2"),
+            verify("h", Expect::Ok),
+        ],
+    )
+    .run::<PoseidonCircuit>();
+}
@@ -0,0 +1,111 @@
+//! The multiplication circuit, unchanged, proven under KZG over BN256 and IPA over Pasta.
+
+use halo2_playground::{
+    audit::fit_k,
+    circuits::mul::MulCircuit,
+    commit_instances, commit_instances_prover,
+    entropy::SeededEntropy,
+    scheme::{self, prove_and_verify, Ipa, Kzg},
+    storage::cached_params,
+};
+use halo2_proofs::{
+    arithmetic::{Field, FieldExt},
+    halo2curves::{
+        bn256::{Bn256, Fr},
+        group::Curve,
+        pasta::{EqAffine, Fp},
+    },
+    plonk::{keygen_pk, keygen_vk},
+    poly::{
+        commitment::{Blind, Params, ParamsProver},
+        ipa::{
+            commitment::{IPACommitmentScheme, ParamsIPA},
+            multiopen::VerifierIPA,
+        },
+        kzg::commitment::ParamsKZG,
+    },
+};
+
+fn seed() -> SeededEntropy {
+    SeededEntropy::new(0x5962_be5d_763d_318d)
+}
+
+fn circuit<F: FieldExt>() -> (MulCircuit<F>, Vec<F>) {
+    let (a, b) = (F::from(3), F::from(5));
+    (MulCircuit::new(a, b), MulCircuit::instance(a * b))
+}
+
+fn kzg_params() -> ParamsKZG<Bn256> {
+    cached_params(fit_k(&circuit::<Fr>().0).expect("fit_k")).expect("cached_params")
+}
+
+/// Transparent, so generated on the spot.
+fn ipa_params() -> ParamsIPA<EqAffine> {
+    ParamsIPA::new(fit_k(&circuit::<Fp>().0).expect("fit_k"))
+}
+
+#[test]
+fn mul_proves_under_kzg() {
+    let params = kzg_params();
+    let (circuit, instance) = circuit::<Fr>();
+    let run = prove_and_verify::<Kzg, _, _>(&params, circuit, &[&instance], seed())
+        .expect("prove_and_verify");
+    assert_eq!(run.scheme, "kzg/bn256");
+    assert!(run.proof_bytes > 0);
+}
+
+#[test]
+fn mul_proves_under_ipa() {
+    let params = ipa_params();
+    let (circuit, instance) = circuit::<Fp>();
+    let run = prove_and_verify::<Ipa, _, _>(&params, circuit, &[&instance], seed())
+        .expect("prove_and_verify");
+    assert_eq!(run.scheme, "ipa/pasta");
+}
+
+#[test]
+fn both_schemes_reject_a_wrong_product() {
+    let params = kzg_params();
+    let (circuit, instance) = circuit::<Fr>();
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let proof = scheme::prove::<Kzg, _, _>(&params, &pk, circuit, &[&instance], seed())
+        .expect("create_proof");
+    assert!(scheme::verify::<Kzg>(&params, pk.get_vk(), &[&instance], &proof).is_ok());
+    let wrong = MulCircuit::instance(Fr::from(16));
+    assert!(scheme::verify::<Kzg>(&params, pk.get_vk(), &[&wrong], &proof).is_err());
+
+    let params = ipa_params();
+    let (circuit, instance) = circuit::<Fp>();
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let proof = scheme::prove::<Ipa, _, _>(&params, &pk, circuit, &[&instance], seed())
+        .expect("create_proof");
+    assert!(scheme::verify::<Ipa>(&params, pk.get_vk(), &[&instance], &proof).is_ok());
+    let wrong = MulCircuit::instance(Fp::from(16));
+    assert!(scheme::verify::<Ipa>(&params, pk.get_vk(), &[&wrong], &proof).is_err());
+}
+
+#[test]
+fn ipa_instance_commitments_match_halo2() {
+    let params = ipa_params();
+    let (circuit, instance) = circuit::<Fp>();
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let mut values = instance.clone();
+    values.resize(params.n() as usize, Fp::zero());
+    let expected = params
+        .commit_lagrange(&vk.get_domain().lagrange_from_vec(values), Blind::default())
+        .to_affine();
+    let commitments = commit_instances::<IPACommitmentScheme<EqAffine>, VerifierIPA<'_, EqAffine>>(
+        &params,
+        &vk,
+        &[&[&instance]],
+    )
+    .expect("commit_instances");
+    assert_eq!(commitments, vec![vec![expected]]);
+    assert_eq!(
+        commit_instances_prover::<IPACommitmentScheme<EqAffine>>(&params, &vk, &[&[&instance]])
+            .expect("commit_instances_prover"),
+        commitments
+    );
+}
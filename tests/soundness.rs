@@ -0,0 +1,261 @@
+//! The verifier must reject everything but the proof it was given: flipped bits, wrong public
+//! inputs, another circuit's verifying key and truncated proofs, under every multiopen and
+//! transcript the playground registers.
+
+use halo2_playground::{
+    audit::fit_k,
+    circuits::{mul::MulCircuit, poseidon::PoseidonHashCircuit},
+    entropy::{parse_seed, SeededEntropy},
+    poseidon::MySpec,
+    prover::{run_proof, MultiOpen, ProofOptions, TranscriptHash},
+    storage::cached_params,
+    transcript_registry::{by_id, by_tag, TRANSCRIPTS},
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{keygen_pk, keygen_vk, Circuit, ProvingKey, VerifyingKey},
+    poly::kzg::commitment::ParamsKZG,
+};
+
+type Poseidon = PoseidonHashCircuit<Fr, MySpec<3, 2>, 3, 2, 2>;
+
+const SEED: u64 = 0x5962_be5d_763d_318d;
+/// Bytes of a compressed point or a scalar in the transcript.
+const WORD_BYTES: usize = 32;
+
+fn all_options() -> Vec<ProofOptions> {
+    MultiOpen::ALL
+        .iter()
+        .flat_map(|&multiopen| {
+            TRANSCRIPTS.iter().map(move |entry| ProofOptions {
+                multiopen,
+                transcript: entry.hash,
+            })
+        })
+        .collect()
+}
+
+/// A circuit's keys and its honest proof under each of `all_options`.
+struct Fixture {
+    params: ParamsKZG<Bn256>,
+    pk: ProvingKey<G1Affine>,
+    instances: Vec<Vec<Fr>>,
+    proofs: Vec<(ProofOptions, Vec<u8>)>,
+}
+
+impl Fixture {
+    fn new<C: Circuit<Fr> + Clone>(circuit: C, instances: Vec<Vec<Fr>>) -> Self {
+        let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+        let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+        let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+        let columns: Vec<&[Fr]> = instances.iter().map(Vec::as_slice).collect();
+        let proofs = all_options()
+            .into_iter()
+            .map(|options| {
+                let proof = run_proof(
+                    &params,
+                    &pk,
+                    circuit.clone(),
+                    &columns,
+                    SeededEntropy::new(SEED),
+                    options,
+                )
+                .expect("create_proof");
+                (options, proof)
+            })
+            .collect();
+        Fixture {
+            params,
+            pk,
+            instances,
+            proofs,
+        }
+    }
+
+    fn accepts(&self, instances: &[Vec<Fr>], proof: &[u8], options: ProofOptions) -> bool {
+        accepts(&self.params, self.pk.get_vk(), instances, proof, options)
+    }
+}
+
+fn accepts(
+    params: &ParamsKZG<Bn256>,
+    vk: &VerifyingKey<G1Affine>,
+    instances: &[Vec<Fr>],
+    proof: &[u8],
+    options: ProofOptions,
+) -> bool {
+    let columns: Vec<&[Fr]> = instances.iter().map(Vec::as_slice).collect();
+    check_proof(params, vk, &columns, proof, options).is_verified()
+}
+
+fn mul() -> Fixture {
+    let (a, b) = (Fr::from(3), Fr::from(5));
+    Fixture::new(MulCircuit::new(a, b), vec![MulCircuit::instance(a * b)])
+}
+
+fn poseidon() -> Fixture {
+    let message = [Fr::from(1), Fr::from(2)];
+    Fixture::new(Poseidon::new(message), vec![vec![Poseidon::hash(message)]])
+}
+
+#[test]
+fn honest_proofs_verify() {
+    for fixture in [mul(), poseidon()] {
+        for (options, proof) in fixture.proofs.iter() {
+            assert!(
+                fixture.accepts(&fixture.instances, proof, *options),
+                "{:?}",
+                options
+            );
+        }
+    }
+}
+
+#[test]
+fn bit_flips_are_rejected() {
+    for fixture in [mul(), poseidon()] {
+        for (options, proof) in fixture.proofs.iter() {
+            // one bit in every word, moving through the byte and bit positions
+            for word in 0..proof.len() / WORD_BYTES {
+                let byte = word * WORD_BYTES + word % WORD_BYTES;
+                let mut tampered = proof.clone();
+                tampered[byte] ^= 1 << (word % 8);
+                assert!(
+                    !fixture.accepts(&fixture.instances, &tampered, *options),
+                    "{:?}: flipped bit {} of byte {} verifies",
+                    options,
+                    word % 8,
+                    byte
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn wrong_public_inputs_are_rejected() {
+    let fixture = mul();
+    let product = fixture.instances[0][1];
+    let wrong = [
+        vec![MulCircuit::instance(product + Fr::one())],
+        vec![MulCircuit::instance(Fr::zero())],
+        vec![vec![product, Fr::zero()]],
+        // a missing or an extra column
+        vec![],
+        vec![fixture.instances[0].clone(), fixture.instances[0].clone()],
+    ];
+    for (options, proof) in fixture.proofs.iter() {
+        for instances in wrong.iter() {
+            assert!(
+                !fixture.accepts(instances, proof, *options),
+                "{:?}: {:?}",
+                options,
+                instances
+            );
+        }
+    }
+
+    let fixture = poseidon();
+    let hash = fixture.instances[0][0];
+    for (options, proof) in fixture.proofs.iter() {
+        for instances in [vec![vec![hash + Fr::one()]], vec![vec![-hash]]] {
+            assert!(
+                !fixture.accepts(&instances, proof, *options),
+                "{:?}",
+                options
+            );
+        }
+    }
+}
+
+#[test]
+fn mismatched_verifying_keys_are_rejected() {
+    let (mul, poseidon) = (mul(), poseidon());
+    // the same circuit keyed at a larger k
+    let k = mul.pk.get_vk().domain.k() + 1;
+    let params = cached_params(k).expect("cached_params");
+    let vk = keygen_vk(&params, &MulCircuit::<Fr>::default()).expect("keygen_vk");
+    for (options, proof) in mul.proofs.iter() {
+        assert!(!accepts(&params, &vk, &mul.instances, proof, *options));
+        assert!(!poseidon.accepts(&poseidon.instances, proof, *options));
+        assert!(!poseidon.accepts(&mul.instances, proof, *options));
+    }
+    for (options, proof) in poseidon.proofs.iter() {
+        assert!(!mul.accepts(&mul.instances, proof, *options));
+    }
+}
+
+#[test]
+fn truncated_proofs_are_rejected() {
+    for fixture in [mul(), poseidon()] {
+        for (options, proof) in fixture.proofs.iter() {
+            let len = proof.len();
+            for truncated in [0, 1, WORD_BYTES, len / 2, len - WORD_BYTES, len - 1] {
+                assert!(
+                    !fixture.accepts(&fixture.instances, &proof[..truncated], *options),
+                    "{:?}: the first {} of {} bytes verify",
+                    options,
+                    truncated,
+                    len
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn proofs_only_verify_under_their_transcript() {
+    let fixture = mul();
+    for (options, proof) in fixture.proofs.iter() {
+        for entry in TRANSCRIPTS
+            .iter()
+            .filter(|entry| entry.hash != options.transcript)
+        {
+            let other = ProofOptions {
+                transcript: entry.hash,
+                ..*options
+            };
+            assert!(!fixture.accepts(&fixture.instances, proof, other));
+        }
+    }
+}
+
+#[test]
+fn transcript_ids_and_tags_are_unique() {
+    for (i, entry) in TRANSCRIPTS.iter().enumerate() {
+        assert_eq!(entry.hash.id(), entry.id);
+        assert_eq!(by_id(entry.id).map(|e| e.hash), Some(entry.hash));
+        assert_eq!(by_tag(entry.tag).map(|e| e.hash), Some(entry.hash));
+        assert!(TRANSCRIPTS[..i]
+            .iter()
+            .all(|earlier| earlier.hash != entry.hash && earlier.tag != entry.tag));
+    }
+    assert_eq!(TRANSCRIPTS[0].hash, TranscriptHash::default());
+}
+
+#[test]
+fn seeds_change_the_proof_but_not_its_validity() {
+    let fixture = mul();
+    let (a, b) = (Fr::from(3), Fr::from(5));
+    let columns: Vec<&[Fr]> = fixture.instances.iter().map(Vec::as_slice).collect();
+    let prove = |seed: &str| {
+        run_proof(
+            &fixture.params,
+            &fixture.pk,
+            MulCircuit::new(a, b),
+            &columns,
+            SeededEntropy::new(parse_seed(seed).expect("parse_seed")),
+            ProofOptions::default(),
+        )
+        .expect("create_proof")
+    };
+    let (first, second) = (prove("0x5eed"), prove("5eee"));
+    for proof in [&first, &second] {
+        assert!(fixture.accepts(&fixture.instances, proof, ProofOptions::default()));
+    }
+    assert_ne!(first, second);
+    // the blinding factors come from the seed alone
+    assert_eq!(prove("0x5eed"), first);
+}
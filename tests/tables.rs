@@ -0,0 +1,290 @@
+//! The shared lookup tables: chips asking for the same table get the same columns, and each
+//! table holds exactly its rows. The range check, comparison and decomposition chips all look
+//! up one byte table, assigned once.
+
+#![cfg(feature = "gadgets-lookup")]
+
+use halo2_playground::chips::{
+    decompose::{DecomposeChip, DecomposeConfig, Decomposition},
+    lt::{LtChip, LtConfig},
+    range_check::{RangeCheckChip, RangeCheckConfig},
+    tables::{Table, Tables},
+};
+use halo2_proofs::{
+    circuit::{Layouter, SimpleFloorPlanner, Value},
+    dev::MockProver,
+    halo2curves::bn256::Fr,
+    plonk::{Advice, Circuit, Column, ConstraintSystem, Error, Instance, Selector},
+    poly::Rotation,
+};
+
+/// Rows of the byte operation tables, plus the blinding rows.
+const K: u32 = 17;
+
+#[derive(Clone, Debug)]
+struct Config {
+    tables: Tables,
+    advice: [Column<Advice>; 3],
+    q_byte: Selector,
+    q_range: Selector,
+    q_ascii: Selector,
+    q_xor: Selector,
+    q_and: Selector,
+}
+
+#[derive(Clone, Copy, Debug)]
+enum Check {
+    /// Looked up by one chip in `Table::BYTE`...
+    Byte(u64),
+    /// ... and by another in `Table::Range(8)`.
+    Range8(u64),
+    Ascii(u64),
+    Xor(u64, u64, u64),
+    And(u64, u64, u64),
+}
+
+#[derive(Clone)]
+struct TablesCircuit(Vec<Check>);
+
+fn one_column(
+    meta: &mut ConstraintSystem<Fr>,
+    tables: &mut Tables,
+    table: Table,
+    advice: Column<Advice>,
+) -> Selector {
+    let q = meta.complex_selector();
+    let column = tables.column(meta, table);
+    meta.lookup("one column", |meta| {
+        let q = meta.query_selector(q);
+        vec![(q * meta.query_advice(advice, Rotation::cur()), column)]
+    });
+    q
+}
+
+fn binary(
+    meta: &mut ConstraintSystem<Fr>,
+    tables: &mut Tables,
+    table: Table,
+    advice: [Column<Advice>; 3],
+) -> Selector {
+    let q = meta.complex_selector();
+    let columns = tables.binary(meta, table);
+    meta.lookup("binary", |meta| {
+        let q = meta.query_selector(q);
+        advice
+            .iter()
+            .zip(columns)
+            .map(|(advice, column)| {
+                (
+                    q.clone() * meta.query_advice(*advice, Rotation::cur()),
+                    column,
+                )
+            })
+            .collect()
+    });
+    q
+}
+
+impl Circuit<Fr> for TablesCircuit {
+    type Config = Config;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let advice = [(); 3].map(|_| meta.advice_column());
+        let mut tables = Tables::new();
+        Config {
+            q_byte: one_column(meta, &mut tables, Table::BYTE, advice[0]),
+            q_range: one_column(meta, &mut tables, Table::Range(8), advice[0]),
+            q_ascii: one_column(meta, &mut tables, Table::Ascii, advice[0]),
+            q_xor: binary(meta, &mut tables, Table::Xor8, advice),
+            q_and: binary(meta, &mut tables, Table::And8, advice),
+            tables,
+            advice,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        config.tables.load(&mut layouter)?;
+        layouter.assign_region(
+            || "checks",
+            |mut region| {
+                for (row, check) in self.0.iter().enumerate() {
+                    let (q, values) = match *check {
+                        Check::Byte(value) => (config.q_byte, [value, 0, 0]),
+                        Check::Range8(value) => (config.q_range, [value, 0, 0]),
+                        Check::Ascii(value) => (config.q_ascii, [value, 0, 0]),
+                        Check::Xor(lhs, rhs, out) => (config.q_xor, [lhs, rhs, out]),
+                        Check::And(lhs, rhs, out) => (config.q_and, [lhs, rhs, out]),
+                    };
+                    q.enable(&mut region, row)?;
+                    for (column, value) in config.advice.iter().zip(values) {
+                        region.assign_advice(
+                            || "value",
+                            *column,
+                            row,
+                            || Value::known(Fr::from(value)),
+                        )?;
+                    }
+                }
+                Ok(())
+            },
+        )
+    }
+}
+
+fn holds(checks: &[Check]) -> bool {
+    MockProver::run(K, &TablesCircuit(checks.to_vec()), vec![])
+        .unwrap()
+        .verify()
+        .is_ok()
+}
+
+#[test]
+fn tables_are_shared() {
+    let mut meta = ConstraintSystem::<Fr>::default();
+    let config = TablesCircuit::configure(&mut meta);
+    assert_eq!(
+        config.tables.tables().collect::<Vec<_>>(),
+        [Table::BYTE, Table::Ascii, Table::Xor8, Table::And8]
+    );
+    assert_eq!(config.tables.rows(), 1 << 16);
+
+    // asking again hands back the same columns
+    let mut tables = config.tables.clone();
+    let byte = tables.column(&mut meta, Table::BYTE);
+    assert_eq!(tables.column(&mut meta, Table::Range(8)), byte);
+    assert_ne!(tables.column(&mut meta, Table::Range(4)), byte);
+    assert_eq!(tables.tables().count(), 5);
+}
+
+#[test]
+fn lookups_hold_on_their_rows() {
+    assert!(holds(&[
+        Check::Byte(0),
+        Check::Byte(255),
+        Check::Range8(200),
+        Check::Ascii(b'z' as u64),
+        Check::Ascii(127),
+        Check::Xor(0xa5, 0x0f, 0xaa),
+        Check::And(0xa5, 0x0f, 0x05),
+    ]));
+}
+
+#[test]
+fn lookups_reject_other_rows() {
+    for check in [
+        Check::Byte(256),
+        Check::Range8(256),
+        Check::Ascii(128),
+        Check::Xor(0xa5, 0x0f, 0x05),
+        Check::And(0xa5, 0x0f, 0xaa),
+        Check::Xor(0x100, 0, 0x100),
+    ] {
+        assert!(!holds(&[check]), "{:?}", check);
+    }
+}
+
+#[derive(Clone, Debug)]
+struct ChipsConfig {
+    tables: Tables,
+    range: RangeCheckConfig,
+    lt: LtConfig<2>,
+    decompose: DecomposeConfig,
+    advice: Column<Advice>,
+    instance: Column<Instance>,
+}
+
+/// Range checks `value` to 16 bits, exposes `value < bound` in instance row 0 and the low
+/// byte of `value` in row 1, each through a different chip.
+#[derive(Clone)]
+struct ChipsCircuit {
+    value: u64,
+    bound: u64,
+}
+
+impl Circuit<Fr> for ChipsCircuit {
+    type Config = ChipsConfig;
+    type FloorPlanner = SimpleFloorPlanner;
+
+    fn without_witnesses(&self) -> Self {
+        self.clone()
+    }
+
+    fn configure(meta: &mut ConstraintSystem<Fr>) -> Self::Config {
+        let advice = meta.advice_column();
+        let instance = meta.instance_column();
+        meta.enable_equality(advice);
+        meta.enable_equality(instance);
+        let mut tables = Tables::new();
+        ChipsConfig {
+            range: RangeCheckChip::<Fr, 8>::configure(meta, &mut tables),
+            lt: LtChip::<Fr, 2>::configure(meta, &mut tables),
+            decompose: DecomposeChip::configure(meta, &mut tables),
+            tables,
+            advice,
+            instance,
+        }
+    }
+
+    fn synthesize(
+        &self,
+        config: Self::Config,
+        mut layouter: impl Layouter<Fr>,
+    ) -> Result<(), Error> {
+        config.tables.load(&mut layouter)?;
+        let (value, bound) = layouter.assign_region(
+            || "operands",
+            |mut region| {
+                let mut assign = |row: usize, value: u64| {
+                    region.assign_advice(
+                        || "operand",
+                        config.advice,
+                        row,
+                        || Value::known(Fr::from(value)),
+                    )
+                };
+                Ok((assign(0, self.value)?, assign(1, self.bound)?))
+            },
+        )?;
+        let range = RangeCheckChip::<Fr, 8>::construct(config.range);
+        range.range_check(layouter.namespace(|| "value"), &value, 16)?;
+        range.range_check(layouter.namespace(|| "bound"), &bound, 16)?;
+        let lt = LtChip::<Fr, 2>::construct(config.lt);
+        let lt = lt.lt(layouter.namespace(|| "lt"), &value, &bound)?;
+        let decompose = DecomposeChip::construct(config.decompose);
+        let bytes =
+            decompose.decompose(layouter.namespace(|| "bytes"), &value, Decomposition::Bytes)?;
+        layouter.constrain_instance(lt.cell(), config.instance, 0)?;
+        layouter.constrain_instance(bytes[0].cell(), config.instance, 1)
+    }
+}
+
+#[test]
+fn chips_share_one_byte_table() {
+    let mut meta = ConstraintSystem::<Fr>::default();
+    let config = ChipsCircuit::configure(&mut meta);
+    assert_eq!(config.tables.tables().collect::<Vec<_>>(), [Table::BYTE]);
+    assert_eq!(config.tables.rows(), 256);
+
+    let run = |value: u64, bound: u64, instance: [u64; 2]| {
+        let instance = instance.iter().map(|value| Fr::from(*value)).collect();
+        MockProver::run(10, &ChipsCircuit { value, bound }, vec![instance])
+            .unwrap()
+            .verify()
+            .is_ok()
+    };
+    assert!(run(0x1234, 0x2000, [1, 0x34]));
+    assert!(run(0xffff, 0x2000, [0, 0xff]));
+    assert!(!run(0x1234, 0x2000, [0, 0x34]));
+    assert!(!run(0x1234, 0x2000, [1, 0x12]));
+    // beyond the range check, whatever the other chips make of it
+    assert!(!run(0x1_0000, 0x2000, [0, 0]));
+}
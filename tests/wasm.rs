@@ -0,0 +1,56 @@
+//! `verify_proof_wasm` in a JS host. Run with `make wasm-test`; on other targets this is empty.
+
+#![cfg(all(target_arch = "wasm32", feature = "wasm"))]
+
+use halo2_playground::{
+    audit::fit_k,
+    entropy::SeededEntropy,
+    onchain::{encode_columns, PoseidonCircuit},
+    prover::{run_proof, ProofOptions},
+    wasm::verify_proof_wasm,
+    GOD_PRIVATE_KEY,
+};
+use halo2_proofs::{
+    arithmetic::FieldExt,
+    halo2curves::bn256::{Bn256, Fr},
+    plonk::{keygen_pk, keygen_vk},
+    poly::{commitment::Params, kzg::commitment::ParamsKZG},
+};
+use wasm_bindgen_test::wasm_bindgen_test;
+
+#[wasm_bindgen_test]
+fn poseidon_proof_verifies_in_wasm() {
+    let message = [Fr::from(3), Fr::from(5)];
+    let circuit = PoseidonCircuit::new(message);
+    // there is no filesystem to cache params in
+    let params = ParamsKZG::<Bn256>::unsafe_setup_with_s(
+        fit_k(&circuit).expect("fit_k"),
+        Fr::from_u128(GOD_PRIVATE_KEY),
+    );
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    let instance = vec![PoseidonCircuit::hash(message)];
+    let proof = run_proof(
+        &params,
+        &pk,
+        circuit,
+        &[&instance],
+        SeededEntropy::new(0x5962_be5d_763d_318d),
+        ProofOptions::default(),
+    )
+    .expect("run_proof");
+    let mut vk_bytes = vec![];
+    pk.get_vk().write(&mut vk_bytes).expect("write vk");
+    let mut params_bytes = vec![];
+    params.write(&mut params_bytes).expect("write params");
+
+    let instances = encode_columns(&[instance.clone()]);
+    assert!(verify_proof_wasm(
+        &params_bytes,
+        &vk_bytes,
+        &instances,
+        &proof
+    ));
+    let other = encode_columns(&[vec![instance[0] + Fr::one()]]);
+    assert!(!verify_proof_wasm(&params_bytes, &vk_bytes, &other, &proof));
+}
@@ -0,0 +1,159 @@
+//! Proofs hide their witness: proofs of two witnesses of one statement look unrelated, and no
+//! two proofs share an advice commitment. Instance commitments stop at the blinding rows.
+
+use halo2_playground::{
+    audit::fit_k,
+    circuits::mul::MulCircuit,
+    commit_instances,
+    entropy::SeededEntropy,
+    error::PlaygroundError,
+    leakage::{advice_commitments, Independence, LeakageReport},
+    prover::{run_proof, ProofOptions},
+    storage::cached_params,
+    verifier::check_proof,
+};
+use halo2_proofs::{
+    arithmetic::Field,
+    halo2curves::bn256::{Bn256, Fr, G1Affine},
+    plonk::{keygen_pk, keygen_vk, Error, ProvingKey},
+    poly::{
+        commitment::Params,
+        kzg::{
+            commitment::{KZGCommitmentScheme, ParamsKZG},
+            multiopen::VerifierSHPLONK,
+        },
+    },
+};
+
+const SEED: u64 = 0x5962_be5d_763d_318d;
+
+struct Keys {
+    params: ParamsKZG<Bn256>,
+    pk: ProvingKey<G1Affine>,
+}
+
+fn keys() -> Keys {
+    let circuit = MulCircuit::<Fr>::default();
+    let params = cached_params(fit_k(&circuit).expect("fit_k")).expect("cached_params");
+    let vk = keygen_vk(&params, &circuit).expect("keygen_vk");
+    let pk = keygen_pk(&params, vk, &circuit).expect("keygen_pk");
+    Keys { params, pk }
+}
+
+impl Keys {
+    fn prove(
+        &self,
+        circuit: MulCircuit<Fr>,
+        instance: &[Fr],
+        seed: u64,
+    ) -> Result<Vec<u8>, PlaygroundError> {
+        run_proof(
+            &self.params,
+            &self.pk,
+            circuit,
+            &[instance],
+            SeededEntropy::new(seed),
+            ProofOptions::default(),
+        )
+    }
+
+    /// The most instance rows a column can have.
+    fn max_rows(&self) -> usize {
+        self.params.n() as usize - (self.pk.get_vk().cs.blinding_factors() + 1)
+    }
+}
+
+#[test]
+fn proofs_of_two_witnesses_are_independent() {
+    let keys = keys();
+    let instance = MulCircuit::instance(Fr::from(15));
+    let (first, second) = (
+        MulCircuit::new(Fr::from(3), Fr::from(5)),
+        MulCircuit::new(Fr::from(1), Fr::from(15)),
+    );
+    let report = LeakageReport::measure(
+        &keys.params,
+        &keys.pk,
+        [&first, &second],
+        &[instance.as_slice()],
+        SEED,
+    )
+    .expect("measure leakage");
+    assert!(report.independence.is_independent(), "{}", report);
+    assert_eq!(report.repeated_commitments, 0, "{}", report);
+    assert!(report.passes());
+}
+
+#[test]
+fn a_proof_is_not_independent_of_itself() {
+    let keys = keys();
+    let instance = MulCircuit::instance(Fr::from(15));
+    let proof = keys
+        .prove(MulCircuit::new(Fr::from(3), Fr::from(5)), &instance, SEED)
+        .expect("create_proof");
+    let pairs = vec![(proof.as_slice(), proof.as_slice()); 4];
+    let independence = Independence::measure(pairs).expect("enough samples");
+    assert!(!independence.is_independent());
+    assert_eq!(independence.equal, independence.samples);
+    // a single short proof has too few bytes to test
+    assert!(Independence::measure([(&proof[..64], &proof[..64])]).is_err());
+}
+
+#[test]
+fn advice_commitments_are_blinded_afresh_per_proof() {
+    let keys = keys();
+    let vk = keys.pk.get_vk();
+    let instance = MulCircuit::instance(Fr::from(15));
+    let circuit = MulCircuit::new(Fr::from(3), Fr::from(5));
+    let prove = |seed| {
+        keys.prove(circuit.clone(), &instance, seed)
+            .expect("create_proof")
+    };
+    let (first, second) = (prove(SEED), prove(SEED + 1));
+    let first = advice_commitments(vk, &first).unwrap();
+    let second = advice_commitments(vk, &second).unwrap();
+    assert_eq!(first.len(), vk.cs.num_advice_columns);
+    assert!(first.iter().zip(second.iter()).all(|(a, b)| a != b));
+    // the same blinds commit the same witness to the same points
+    let again = prove(SEED);
+    assert_eq!(advice_commitments(vk, &again).unwrap(), first);
+}
+
+#[test]
+fn instances_fill_every_row_above_the_blinding_rows() {
+    let keys = keys();
+    let vk = keys.pk.get_vk();
+    let max_rows = keys.max_rows();
+    let commit = |rows: usize| {
+        let mut column = MulCircuit::instance(Fr::from(15));
+        column.resize(rows, Fr::zero());
+        commit_instances::<KZGCommitmentScheme<Bn256>, VerifierSHPLONK<'_, Bn256>>(
+            &keys.params,
+            vk,
+            &[&[column.as_slice()]],
+        )
+    };
+    assert!(commit(max_rows).is_ok());
+    assert!(matches!(commit(max_rows + 1), Err(Error::InstanceTooLarge)));
+
+    // the prover draws the line in the same place
+    let circuit = MulCircuit::new(Fr::from(3), Fr::from(5));
+    let mut padded = MulCircuit::instance(Fr::from(15));
+    padded.resize(max_rows, Fr::zero());
+    let proof = keys
+        .prove(circuit.clone(), &padded, SEED)
+        .expect("create_proof");
+    assert!(check_proof(
+        &keys.params,
+        vk,
+        &[padded.as_slice()],
+        &proof,
+        ProofOptions::default()
+    )
+    .is_verified());
+    padded.push(Fr::zero());
+    assert!(matches!(
+        keys.prove(circuit, &padded, SEED),
+        Err(PlaygroundError::Prove(Error::InstanceTooLarge))
+    ));
+}